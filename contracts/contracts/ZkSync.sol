@@ -9,6 +9,7 @@ import "./SafeMath.sol";
 import "./SafeMathUInt128.sol";
 import "./SafeCast.sol";
 import "./Utils.sol";
+import "./IERC20Permit.sol";
 
 import "./Storage.sol";
 import "./Config.sol";
@@ -233,6 +234,40 @@ contract ZkSync is UpgradeableMaster, Storage, Config, Events, ReentrancyGuard {
         uint104 _amount,
         address _zkSyncAddress
     ) external nonReentrant {
+        registerERC20Deposit(_token, _amount, _zkSyncAddress);
+    }
+
+    /// @notice Deposit ERC20 token supporting EIP-2612 permit to Layer 2 - approve the transfer with the signed permit,
+    /// @notice transfer ERC20 tokens from user into contract, validate it, register deposit
+    /// @dev The permit may be already used by a front-running transaction, then the existing allowance is used
+    /// @param _token Token address
+    /// @param _amount Token amount
+    /// @param _zkSyncAddress Receiver Layer 2 address
+    /// @param _deadline Timestamp after which the permit is expired
+    /// @param _v Recovery id of the permit signature
+    /// @param _r R part of the permit signature
+    /// @param _s S part of the permit signature
+    function depositERC20WithPermit(
+        IERC20 _token,
+        uint104 _amount,
+        address _zkSyncAddress,
+        uint256 _deadline,
+        uint8 _v,
+        bytes32 _r,
+        bytes32 _s
+    ) external nonReentrant {
+        try IERC20Permit(address(_token)).permit(msg.sender, address(this), _amount, _deadline, _v, _r, _s) {} catch {
+            require(_token.allowance(msg.sender, address(this)) >= _amount, "pr"); // permit failed and allowance is not enough
+        }
+        registerERC20Deposit(_token, _amount, _zkSyncAddress);
+    }
+
+    /// @notice Transfers ERC20 tokens approved by the user into contract, validates it, registers deposit
+    function registerERC20Deposit(
+        IERC20 _token,
+        uint104 _amount,
+        address _zkSyncAddress
+    ) internal {
         require(_zkSyncAddress != SPECIAL_ACCOUNT_ADDRESS, "P");
         requireActive();
 
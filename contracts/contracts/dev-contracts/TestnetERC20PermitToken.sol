@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: UNLICENSED
+
+pragma solidity ^0.7.0;
+
+import "@openzeppelin/contracts/drafts/ERC20Permit.sol";
+
+contract TestnetERC20PermitToken is ERC20Permit {
+    constructor(
+        string memory name,
+        string memory symbol,
+        uint8 decimals
+    ) ERC20(name, symbol) ERC20Permit(name) {
+        _setupDecimals(decimals);
+    }
+
+    function mint(address _to, uint256 _amount) public returns (bool) {
+        _mint(_to, _amount);
+        return true;
+    }
+}
@@ -287,6 +287,7 @@ describe('ZK priority queue ops unit tests', function () {
 
     let zksyncContract;
     let tokenContract;
+    let permitTokenContract;
     before(async () => {
         [wallet] = await hardhat.ethers.getSigners();
         const contracts = readProductionContracts();
@@ -299,11 +300,43 @@ describe('ZK priority queue ops unit tests', function () {
         tokenContract = await tokenContractFactory.deploy('Matter Labs Trial Token', 'MLTT', 18);
         await tokenContract.mint(wallet.address, parseEther('1000000'));
 
+        const permitTokenContractFactory = await hardhat.ethers.getContractFactory('TestnetERC20PermitToken');
+        permitTokenContract = await permitTokenContractFactory.deploy('Matter Labs Permit Token', 'MLPT', 18);
+        await permitTokenContract.mint(wallet.address, parseEther('1000000'));
+
         const govContract = deployer.governanceContract(wallet);
         await govContract.addToken(tokenContract.address);
+        await govContract.addToken(permitTokenContract.address);
     });
 
-    async function performDeposit(to: Address, token: TokenAddress, depositAmount: BigNumber) {
+    async function signPermit(amount: BigNumber, deadline: number) {
+        const domain = {
+            name: await permitTokenContract.name(),
+            version: '1',
+            chainId: hardhat.network.config.chainId,
+            verifyingContract: permitTokenContract.address
+        };
+        const types = {
+            Permit: [
+                { name: 'owner', type: 'address' },
+                { name: 'spender', type: 'address' },
+                { name: 'value', type: 'uint256' },
+                { name: 'nonce', type: 'uint256' },
+                { name: 'deadline', type: 'uint256' }
+            ]
+        };
+        const message = {
+            owner: wallet.address,
+            spender: zksyncContract.address,
+            value: amount,
+            nonce: await permitTokenContract.nonces(wallet.address),
+            deadline
+        };
+        const signature = await wallet._signTypedData(domain, types, message);
+        return { deadline, ...ethers.utils.splitSignature(signature) };
+    }
+
+    async function performDeposit(to: Address, token: TokenAddress, depositAmount: BigNumber, permit?) {
         const openedRequests = await zksyncContract.getTotalOpenPriorityRequests();
         const depositOwner = wallet.address;
 
@@ -312,6 +345,16 @@ describe('ZK priority queue ops unit tests', function () {
             tx = await zksyncContract.depositETH(depositOwner, {
                 value: depositAmount
             });
+        } else if (permit) {
+            tx = await zksyncContract.depositERC20WithPermit(
+                token,
+                depositAmount,
+                depositOwner,
+                permit.deadline,
+                permit.v,
+                permit.r,
+                permit.s
+            );
         } else {
             tx = await zksyncContract.depositERC20(token, depositAmount, depositOwner);
         }
@@ -381,6 +424,50 @@ describe('ZK priority queue ops unit tests', function () {
         await performDeposit(ethers.Wallet.createRandom().address, tokenAddress, depositAmount);
     });
 
+    it('success ERC20 deposits with permit', async () => {
+        zksyncContract.connect(wallet);
+        const tokenAddress = permitTokenContract.address;
+        const depositAmount = parseEther('1.0');
+        const deadline = Math.floor(Date.now() / 1000) + 3600;
+
+        await performDeposit(wallet.address, tokenAddress, depositAmount, await signPermit(depositAmount, deadline));
+        expect(await permitTokenContract.allowance(wallet.address, zksyncContract.address)).eq(0);
+    });
+
+    it('success ERC20 deposit with front-run permit', async () => {
+        zksyncContract.connect(wallet);
+        const tokenAddress = permitTokenContract.address;
+        const depositAmount = parseEther('1.0');
+        const deadline = Math.floor(Date.now() / 1000) + 3600;
+
+        // The permit is submitted by someone else before the deposit, so it can't be used again.
+        const permit = await signPermit(depositAmount, deadline);
+        await permitTokenContract.permit(
+            wallet.address,
+            zksyncContract.address,
+            depositAmount,
+            permit.deadline,
+            permit.v,
+            permit.r,
+            permit.s
+        );
+        await performDeposit(wallet.address, tokenAddress, depositAmount, permit);
+    });
+
+    it('ERC20 deposit with invalid permit and no allowance', async () => {
+        zksyncContract.connect(wallet);
+        const tokenAddress = permitTokenContract.address;
+        const depositAmount = parseEther('1.0');
+        const deadline = Math.floor(Date.now() / 1000) + 3600;
+
+        // The permit is signed for the smaller amount.
+        const permit = await signPermit(depositAmount.div(2), deadline);
+        const { revertReason } = await getCallRevertReason(
+            async () => await performDeposit(wallet.address, tokenAddress, depositAmount, permit)
+        );
+        expect(revertReason, 'wrong revert reason').eq('pr');
+    });
+
     it('success FullExit request', async () => {
         zksyncContract.connect(wallet);
         const accountId = 1;
@@ -0,0 +1,117 @@
+//! Client of the server admin API, the requests are authorized with the JWT derived from the
+//! shared secret, the same way as the prover ones.
+
+// Built-in deps
+use std::time::{Duration, UNIX_EPOCH};
+// External deps
+use anyhow::format_err;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use reqwest::{Method, RequestBuilder};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+// Workspace deps
+use zksync_config::configs::api::AdminApiConfig;
+use zksync_types::pipeline::{PipelineStage, PipelineStagePause};
+use zksync_utils::PayloadAuthToken;
+
+/// Lifetime of the issued auth tokens, every request uses a new one.
+const AUTH_TOKEN_LIFETIME: Duration = Duration::from_secs(60);
+
+/// Log filter in the `RUST_LOG` format.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LogFilter {
+    pub filter: String,
+}
+
+/// Request to pause or resume the pipeline stage.
+#[derive(Debug, Serialize)]
+struct PipelineStageUpdate {
+    stage: PipelineStage,
+    paused: bool,
+    reason: String,
+}
+
+#[derive(Debug)]
+pub struct AdminApiClient {
+    client: reqwest::Client,
+    url: String,
+    secret_auth: String,
+}
+
+impl AdminApiClient {
+    pub fn new(config: AdminApiConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: config.url.trim_end_matches('/').to_string(),
+            secret_auth: config.secret_auth,
+        }
+    }
+
+    pub async fn fee_params(&self) -> anyhow::Result<Value> {
+        self.send(self.request(Method::GET, "fee_params")?).await
+    }
+
+    /// Updates the fee model parameters, the omitted ones stay unchanged.
+    pub async fn update_fee_params(&self, update: &Value) -> anyhow::Result<Value> {
+        self.send(self.request(Method::POST, "fee_params")?.json(update))
+            .await
+    }
+
+    pub async fn log_filter(&self) -> anyhow::Result<LogFilter> {
+        self.send(self.request(Method::GET, "log_filter")?).await
+    }
+
+    pub async fn update_log_filter(&self, filter: String) -> anyhow::Result<LogFilter> {
+        let request = self
+            .request(Method::POST, "log_filter")?
+            .json(&LogFilter { filter });
+        self.send(request).await
+    }
+
+    pub async fn paused_stages(&self) -> anyhow::Result<Vec<PipelineStagePause>> {
+        self.send(self.request(Method::GET, "paused_stages")?).await
+    }
+
+    /// Pauses the pipeline stage, or resumes it if the `reason` is not set.
+    pub async fn update_paused_stage(
+        &self,
+        stage: PipelineStage,
+        reason: Option<String>,
+    ) -> anyhow::Result<Vec<PipelineStagePause>> {
+        let update = PipelineStageUpdate {
+            stage,
+            paused: reason.is_some(),
+            reason: reason.unwrap_or_default(),
+        };
+        let request = self.request(Method::POST, "paused_stages")?.json(&update);
+        self.send(request).await
+    }
+
+    fn request(&self, method: Method, path: &str) -> anyhow::Result<RequestBuilder> {
+        let exp = UNIX_EPOCH.elapsed()? + AUTH_TOKEN_LIFETIME;
+        let token = encode(
+            &Header::default(),
+            &PayloadAuthToken::new(exp.as_secs() as usize),
+            &EncodingKey::from_secret(self.secret_auth.as_ref()),
+        )?;
+
+        Ok(self
+            .client
+            .request(method, format!("{}/{}", self.url, path))
+            .bearer_auth(token))
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> anyhow::Result<T> {
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(format_err!(
+                "Admin API responded with {}: {}",
+                status,
+                message
+            ));
+        }
+        Ok(response.json().await?)
+    }
+}
@@ -0,0 +1,500 @@
+//! Command line tool for the server operators.
+//!
+//! Most of the commands work with the database directly, while the runtime parameters of the
+//! running server are managed via its admin API.
+
+use anyhow::{bail, ensure, format_err};
+use structopt::StructOpt;
+use web3::contract::Options;
+use zksync_config::configs::api::AdminApiConfig;
+use zksync_config::{ContractsConfig, ETHClientConfig, ETHSenderConfig};
+use zksync_contracts::{erc20_metadata_contract, governance_contract};
+use zksync_crypto::{circuit::CircuitAccountTree, params::account_tree_depth};
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    pipeline::{PipelineStage, PipelineStagePause},
+    prover::ProverJobType,
+    Address, BlockNumber, Token, TokenId, TokenKind, U256,
+};
+
+use crate::admin_api::AdminApiClient;
+
+mod admin_api;
+
+/// Name the changes made by the tool are attributed to in the database.
+const UPDATED_BY: &str = "zksync_admin";
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Shows the progress of the blocks from the execution to the confirmation on Ethereum.
+    Status,
+    /// Shows the prover job queue along with the quarantined jobs.
+    ProverQueue,
+    /// Returns the idle or quarantined prover job to the queue with the reset attempts.
+    RequeueJob {
+        /// ID of the job in the prover job queue.
+        job_id: i32,
+        /// Requeue the job even if it's in progress, so it may be proven twice.
+        #[structopt(long)]
+        force: bool,
+    },
+    /// Lists the unconfirmed Ethereum operations, the stuck ones are marked.
+    EthOperations {
+        /// Show only the stuck operations.
+        #[structopt(long)]
+        stuck: bool,
+    },
+    /// Adds the ERC20 token, which is already registered in the Governance contract.
+    /// The token ID is taken from the Governance contract and the metadata from the token contract.
+    AddToken {
+        #[structopt(long)]
+        address: Address,
+        /// Required if the token contract doesn't return the symbol as a string,
+        /// must match the symbol of the token contract otherwise.
+        #[structopt(long)]
+        symbol: Option<String>,
+        /// Must match the decimals of the token contract, if set.
+        #[structopt(long)]
+        decimals: Option<u8>,
+    },
+    /// Stores the snapshot of the account tree, so the witness generator doesn't have
+    /// to restore the tree from scratch.
+    Snapshot {
+        /// Block to take the snapshot for, the last sealed block is used if not set.
+        #[structopt(long)]
+        block: Option<u32>,
+    },
+    /// Shows the fee model parameters of the server, or updates them via the admin API.
+    FeeParams {
+        /// JSON with the parameters to update, e.g. `{"gas_price_scale_percent": 120}`.
+        #[structopt(long)]
+        update: Option<serde_json::Value>,
+    },
+    /// Shows the log filter of the server, or updates it via the admin API.
+    LogFilter {
+        /// New filter in the `RUST_LOG` format, e.g. `zksync_api=debug,zksync_core=info`.
+        filter: Option<String>,
+    },
+    /// Shows the paused pipeline stages of the server.
+    PausedStages,
+    /// Pauses the pipeline stage via the admin API, without stopping the server.
+    Pause {
+        /// One of `tx_acceptance`, `block_sealing`, `commit_sending` or `proof_submission`.
+        stage: PipelineStage,
+        #[structopt(long)]
+        reason: String,
+    },
+    /// Resumes the paused pipeline stage via the admin API.
+    Resume { stage: PipelineStage },
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zkSync admin tool", author = "Matter Labs")]
+#[structopt(about = "Tool for inspecting and managing the zkSync server")]
+struct Opt {
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+async fn show_status(storage: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+    let pending_block = storage.chain().block_schema().load_pending_block().await?;
+    let last_sealed_block = storage
+        .chain()
+        .block_schema()
+        .get_last_saved_block()
+        .await?;
+    let last_committed_block = storage
+        .chain()
+        .block_schema()
+        .get_last_committed_block()
+        .await?;
+    let last_committed_confirmed_block = storage
+        .chain()
+        .block_schema()
+        .get_last_committed_confirmed_block()
+        .await?;
+    let first_unproven_block = storage.prover_schema().first_unproven_block().await?;
+    let last_proven_confirmed_block = storage
+        .chain()
+        .block_schema()
+        .get_last_proven_confirmed_block()
+        .await?;
+    let last_executed_block = storage
+        .chain()
+        .block_schema()
+        .get_last_verified_block()
+        .await?;
+    let last_executed_confirmed_block = storage
+        .chain()
+        .block_schema()
+        .get_last_verified_confirmed_block()
+        .await?;
+
+    match pending_block {
+        Some(block) => println!(
+            "Pending block: {} ({} operations, {} failed transactions, {} chunks left)",
+            block.number,
+            block.success_operations.len(),
+            block.failed_txs.len(),
+            block.chunks_left
+        ),
+        None => println!("Pending block: none"),
+    }
+    println!("Last sealed block: {}", last_sealed_block);
+    println!(
+        "Last committed block: {} (confirmed {})",
+        last_committed_block, last_committed_confirmed_block
+    );
+    println!(
+        "First unproven block: {} (last confirmed proof {})",
+        first_unproven_block, last_proven_confirmed_block
+    );
+    println!(
+        "Last executed block: {} (confirmed {})",
+        last_executed_block, last_executed_confirmed_block
+    );
+    Ok(())
+}
+
+async fn show_prover_queue(storage: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+    let stats = storage.prover_schema().prover_queue_stats().await?;
+    println!(
+        "Idle jobs: {}, in progress jobs: {}",
+        stats.idle_jobs, stats.in_progress_jobs
+    );
+    if let (Some(block), Some(created_at)) = (
+        stats.oldest_unproven_block,
+        stats.oldest_unproven_block_created_at,
+    ) {
+        println!(
+            "Oldest unproven block: {} (job created at {})",
+            block, created_at
+        );
+    }
+    println!(
+        "Last block in the queue: {} (aggregated proofs: {})",
+        storage
+            .prover_schema()
+            .get_last_block_prover_job_queue(ProverJobType::SingleProof)
+            .await?,
+        storage
+            .prover_schema()
+            .get_last_block_prover_job_queue(ProverJobType::AggregatedProof)
+            .await?
+    );
+
+    for queue in storage.prover_schema().idle_jobs_by_work_class().await? {
+        println!(
+            "  {}: {} idle jobs, highest priority {}",
+            queue.work_class, queue.idle_jobs, queue.job_priority
+        );
+    }
+
+    let quarantined_jobs = storage.prover_schema().quarantined_prover_jobs().await?;
+    println!("Quarantined jobs: {}", quarantined_jobs.len());
+    for job in quarantined_jobs {
+        println!(
+            "  job {} ({}) for blocks [{}, {}]: {} attempts, last prover {}, quarantined at {}: {}",
+            job.job_id,
+            job.job_type,
+            job.first_block,
+            job.last_block,
+            job.attempts,
+            job.last_prover,
+            job.created_at,
+            job.reason
+        );
+    }
+    Ok(())
+}
+
+fn eth_gateway(contracts: &ContractsConfig) -> EthereumGateway {
+    EthereumGateway::from_config(
+        &ETHClientConfig::from_env(),
+        &ETHSenderConfig::from_env(),
+        contracts.contract_addr,
+    )
+}
+
+async fn show_eth_operations(
+    storage: &mut StorageProcessor<'_>,
+    only_stuck: bool,
+) -> anyhow::Result<()> {
+    let eth_gateway = eth_gateway(&ContractsConfig::from_env());
+    let current_block = eth_gateway.block_number().await?.as_u64();
+    let operations = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?;
+
+    println!(
+        "Unconfirmed operations: {}, current Ethereum block: {}",
+        operations.len(),
+        current_block
+    );
+    for operation in operations {
+        let stuck = operation.is_stuck(current_block);
+        if only_stuck && !stuck {
+            continue;
+        }
+        let blocks = operation
+            .op
+            .as_ref()
+            .map(|(_, op)| {
+                let (from, to) = op.get_block_range();
+                format!("[{}, {}]", from, to)
+            })
+            .unwrap_or_default();
+        println!(
+            "  operation {} ({} {}): nonce {}, {} txs sent, last gas price {}, deadline block {}{}",
+            operation.id,
+            operation.op_type,
+            blocks,
+            operation.nonce,
+            operation.used_tx_hashes.len(),
+            operation.last_used_gas_price,
+            operation.last_deadline_block,
+            if stuck { ", STUCK" } else { "" }
+        );
+        if let Some(hash) = operation.used_tx_hashes.last() {
+            println!("    last tx hash: {:#x}", hash);
+        }
+    }
+    Ok(())
+}
+
+/// Loads the ID of the token from the Governance contract and its metadata from the token contract,
+/// the provided `symbol` and `decimals` are checked against them.
+async fn load_token(
+    address: Address,
+    symbol: Option<String>,
+    decimals: Option<u8>,
+) -> anyhow::Result<Token> {
+    let contracts = ContractsConfig::from_env();
+    let eth_gateway = eth_gateway(&contracts);
+
+    let token_id: U256 = eth_gateway
+        .call_contract_function(
+            "tokenIds",
+            address,
+            None,
+            Options::default(),
+            None,
+            contracts.governance_addr,
+            governance_contract(),
+        )
+        .await?;
+    ensure!(
+        !token_id.is_zero(),
+        "Token {:?} is not registered in the Governance contract",
+        address
+    );
+
+    let actual_decimals: U256 = eth_gateway
+        .call_contract_function(
+            "decimals",
+            (),
+            None,
+            Options::default(),
+            None,
+            address,
+            erc20_metadata_contract(),
+        )
+        .await?;
+    ensure!(
+        actual_decimals <= U256::from(u8::MAX),
+        "Invalid decimals {} of the token {:?}",
+        actual_decimals,
+        address
+    );
+    let actual_decimals = actual_decimals.as_u32() as u8;
+    if let Some(decimals) = decimals {
+        ensure!(
+            decimals == actual_decimals,
+            "Decimals of the token on L1 are {}, while {} are provided",
+            actual_decimals,
+            decimals
+        );
+    }
+
+    // Not every token implements `symbol` as a `string` (e.g. MKR returns `bytes32`).
+    let actual_symbol = eth_gateway
+        .call_contract_function::<String, _, _, _>(
+            "symbol",
+            (),
+            None,
+            Options::default(),
+            None,
+            address,
+            erc20_metadata_contract(),
+        )
+        .await
+        .ok();
+    let symbol = match (symbol, actual_symbol) {
+        (Some(symbol), Some(actual_symbol)) => {
+            ensure!(
+                symbol == actual_symbol,
+                "Symbol of the token on L1 is {}, while {} is provided",
+                actual_symbol,
+                symbol
+            );
+            symbol
+        }
+        (Some(symbol), None) | (None, Some(symbol)) => symbol,
+        (None, None) => bail!(
+            "Symbol of the token {:?} can't be loaded from L1, set it with --symbol",
+            address
+        ),
+    };
+
+    Ok(Token::new(
+        TokenId(token_id.as_u32()),
+        address,
+        &symbol,
+        actual_decimals,
+        TokenKind::ERC20,
+    ))
+}
+
+async fn add_token(storage: &mut StorageProcessor<'_>, token: Token) -> anyhow::Result<()> {
+    storage.tokens_schema().store_token(token.clone()).await?;
+    println!(
+        "Token {} ({:?}) is added with the ID {}",
+        token.symbol, token.address, token.id
+    );
+    Ok(())
+}
+
+async fn store_snapshot(
+    storage: &mut StorageProcessor<'_>,
+    block: Option<BlockNumber>,
+) -> anyhow::Result<()> {
+    let block = match block {
+        Some(block) => block,
+        None => {
+            storage
+                .chain()
+                .block_schema()
+                .get_last_saved_block()
+                .await?
+        }
+    };
+    ensure!(
+        *block > 0,
+        "There are no blocks to take the snapshot for yet"
+    );
+    let stored_block = storage
+        .chain()
+        .block_schema()
+        .get_block(block)
+        .await?
+        .ok_or_else(|| format_err!("Block {} is not sealed yet", block))?;
+
+    println!("Restoring the account tree for the block {}", block);
+    let (_, accounts) = storage
+        .chain()
+        .state_schema()
+        .load_committed_state(Some(block))
+        .await?;
+    let mut account_tree = CircuitAccountTree::new(account_tree_depth());
+    for (id, account) in accounts {
+        account_tree.insert(*id, account.into());
+    }
+    ensure!(
+        account_tree.root_hash() == stored_block.new_root_hash,
+        "Account tree root hash restored incorrectly"
+    );
+
+    let tree_cache = account_tree.get_internals().encode_bincode();
+    storage
+        .chain()
+        .tree_cache_schema_bincode()
+        .store_account_tree_cache(block, tree_cache)
+        .await?;
+    println!("Account tree snapshot for the block {} is stored", block);
+    Ok(())
+}
+
+fn show_paused_stages(pauses: &[PipelineStagePause]) {
+    if pauses.is_empty() {
+        println!("No pipeline stages are paused");
+    }
+    for pause in pauses {
+        println!(
+            "{} is paused since {}: {}",
+            pause.stage, pause.paused_at, pause.reason
+        );
+    }
+}
+
+async fn connect() -> anyhow::Result<StorageProcessor<'static>> {
+    StorageProcessor::establish_connection().await
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+
+    match opt.command {
+        Command::Status => show_status(&mut connect().await?).await?,
+        Command::ProverQueue => show_prover_queue(&mut connect().await?).await?,
+        Command::RequeueJob { job_id, force } => {
+            let requeued = connect()
+                .await?
+                .prover_schema()
+                .requeue_prover_job(job_id, UPDATED_BY, force)
+                .await?;
+            ensure!(
+                requeued,
+                "Job {} doesn't exist, is proven already or is in progress (use --force to requeue it)",
+                job_id
+            );
+            println!("Job {} is returned to the queue", job_id);
+        }
+        Command::EthOperations { stuck } => {
+            show_eth_operations(&mut connect().await?, stuck).await?
+        }
+        Command::AddToken {
+            address,
+            symbol,
+            decimals,
+        } => {
+            let token = load_token(address, symbol, decimals).await?;
+            add_token(&mut connect().await?, token).await?
+        }
+        Command::Snapshot { block } => {
+            store_snapshot(&mut connect().await?, block.map(BlockNumber)).await?
+        }
+        Command::FeeParams { update } => {
+            let admin_api = AdminApiClient::new(AdminApiConfig::from_env());
+            let params = match update {
+                Some(update) => admin_api.update_fee_params(&update).await?,
+                None => admin_api.fee_params().await?,
+            };
+            println!("{}", serde_json::to_string_pretty(&params)?);
+        }
+        Command::LogFilter { filter } => {
+            let admin_api = AdminApiClient::new(AdminApiConfig::from_env());
+            let log_filter = match filter {
+                Some(filter) => admin_api.update_log_filter(filter).await?,
+                None => admin_api.log_filter().await?,
+            };
+            println!("{}", log_filter.filter);
+        }
+        Command::PausedStages => {
+            let admin_api = AdminApiClient::new(AdminApiConfig::from_env());
+            show_paused_stages(&admin_api.paused_stages().await?);
+        }
+        Command::Pause { stage, reason } => {
+            let admin_api = AdminApiClient::new(AdminApiConfig::from_env());
+            show_paused_stages(&admin_api.update_paused_stage(stage, Some(reason)).await?);
+        }
+        Command::Resume { stage } => {
+            let admin_api = AdminApiClient::new(AdminApiConfig::from_env());
+            show_paused_stages(&admin_api.update_paused_stage(stage, None).await?);
+        }
+    }
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use ethabi::ParamType;
 
-use zksync_types::{AccountId, BlockNumber, ZkSyncOp, H256};
+use zksync_types::{operations::PubdataVersion, AccountId, BlockNumber, ZkSyncOp, H256};
 
 use crate::rollup_ops::RollupOpsBlock;
 
@@ -45,6 +45,7 @@ pub fn rollup_ops_blocks_from_bytes(input_data: Vec<u8>) -> Result<RollupOpsBloc
             fee_account,
             timestamp: None,
             previous_block_root_hash: H256::default(),
+            root_hash: H256::default(),
             contract_version: None,
         };
         Ok(block)
@@ -60,40 +61,10 @@ pub fn rollup_ops_blocks_from_bytes(input_data: Vec<u8>) -> Result<RollupOpsBloc
 /// Attempts to restore block operations from the public data.
 /// Should be used for contracts V1-V5.
 pub fn get_rollup_ops_from_data(data: &[u8]) -> Result<Vec<ZkSyncOp>, anyhow::Error> {
-    parse_pub_data(
+    Ok(ZkSyncOp::ops_from_public_data(
         data,
-        ZkSyncOp::from_legacy_public_data,
-        ZkSyncOp::legacy_public_data_length,
-    )
-}
-
-pub(super) fn parse_pub_data<Parse, ParseErr, GetSize, GetSizeErr>(
-    data: &[u8],
-    parse: Parse,
-    get_data_size: GetSize,
-) -> Result<Vec<ZkSyncOp>, anyhow::Error>
-where
-    Parse: Fn(&[u8]) -> Result<ZkSyncOp, ParseErr>,
-    ParseErr: std::error::Error + Send + Sync + 'static,
-    GetSize: Fn(u8) -> Result<usize, GetSizeErr>,
-    GetSizeErr: std::error::Error + Send + Sync + 'static,
-{
-    let mut current_pointer = 0;
-    let mut ops = Vec::new();
-    while current_pointer < data.len() {
-        let op_type: u8 = data[current_pointer];
-
-        let pub_data_size = get_data_size(op_type)?;
-
-        let pre = current_pointer;
-        let post = pre + pub_data_size;
-
-        let op = parse(&data[pre..post])?;
-
-        ops.push(op);
-        current_pointer += pub_data_size;
-    }
-    Ok(ops)
+        PubdataVersion::Legacy,
+    )?)
 }
 
 #[cfg(test)]
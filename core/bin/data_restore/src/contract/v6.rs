@@ -1,11 +1,9 @@
 // Built-in uses
 // External uses
 // Workspace uses
-use zksync_types::ZkSyncOp;
+use zksync_types::{operations::PubdataVersion, ZkSyncOp};
 // Local uses
-use super::{
-    default::parse_pub_data, v4::rollup_ops_blocks_from_bytes_inner, version::ZkSyncContractVersion,
-};
+use super::{v4::rollup_ops_blocks_from_bytes_inner, version::ZkSyncContractVersion};
 use crate::rollup_ops::RollupOpsBlock;
 
 pub fn rollup_ops_blocks_from_bytes(data: Vec<u8>) -> anyhow::Result<Vec<RollupOpsBlock>> {
@@ -13,9 +11,5 @@ pub fn rollup_ops_blocks_from_bytes(data: Vec<u8>) -> anyhow::Result<Vec<RollupO
 }
 
 pub fn get_rollup_ops_from_data(data: &[u8]) -> Result<Vec<ZkSyncOp>, anyhow::Error> {
-    parse_pub_data(
-        data,
-        ZkSyncOp::from_public_data,
-        ZkSyncOp::public_data_length,
-    )
+    Ok(ZkSyncOp::ops_from_public_data(data, PubdataVersion::V1)?)
 }
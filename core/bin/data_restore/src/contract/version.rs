@@ -2,9 +2,8 @@
 use std::convert::TryFrom;
 // External uses
 // Workspace uses
-use zksync_types::operations::ZkSyncOp;
+use zksync_types::operations::{PubdataVersion, ZkSyncOp};
 // Local uses
-use super::default;
 use crate::{contract, rollup_ops::RollupOpsBlock};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -76,10 +75,19 @@ impl ZkSyncContractVersion {
     /// * `data` - public data for block operations
     ///
     pub fn get_rollup_ops_from_data(&self, data: &[u8]) -> Result<Vec<ZkSyncOp>, anyhow::Error> {
+        Ok(ZkSyncOp::ops_from_public_data(
+            data,
+            self.pubdata_version(),
+        )?)
+    }
+
+    /// Returns the encoding of the operations public data committed to the contract
+    /// with the given version.
+    pub fn pubdata_version(&self) -> PubdataVersion {
         use ZkSyncContractVersion::*;
         match self {
-            V0 | V1 | V2 | V3 | V4 | V5 => default::get_rollup_ops_from_data(data),
-            V6 => contract::v6::get_rollup_ops_from_data(data),
+            V0 | V1 | V2 | V3 | V4 | V5 => PubdataVersion::Legacy,
+            V6 => PubdataVersion::V1,
         }
     }
 
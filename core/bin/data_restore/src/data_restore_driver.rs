@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 // External deps
+use futures::{stream, StreamExt, TryStreamExt};
 use web3::{
     contract::Contract,
     types::{H160, H256},
@@ -20,10 +21,14 @@ use zksync_types::{
 use crate::{
     contract::{get_genesis_account, ZkSyncDeployedContract},
     eth_tx_helpers::get_ethereum_transaction,
+    events::BlockEvent,
     events_state::EventsState,
+    progress::RestoreProgress,
+    request_limiter::RequestLimiter,
     rollup_ops::RollupOpsBlock,
     storage_interactor::StorageInteractor,
     tree_state::TreeState,
+    ETH_POLL_INTERVAL,
 };
 
 /// Storage state update:
@@ -81,6 +86,23 @@ pub struct DataRestoreDriver<T: Transport> {
     /// Serial id of the last priority operation processed by the driver. It's necessary to manually
     /// keep track of it since it's impossible to restore it from the contract.
     pub last_priority_op_serial_id: SerialId,
+    /// Limits of the parallel requests to the Ethereum node. By default, the events
+    /// and the commit transactions are fetched sequentially.
+    pub request_limiter: RequestLimiter,
+    /// Interval of polling the Ethereum node for the new blocks once the driver caught up with them.
+    pub eth_poll_interval: Duration,
+    /// Verified block to stop the restoring at, e.g. to reproduce the historical state.
+    /// The blocks after it are not applied to the tree.
+    pub target_block: Option<BlockNumber>,
+    /// Restoring progress reported by the status API and to the metrics.
+    pub progress: RestoreProgress,
+    /// Whether to apply the committed blocks without waiting for their verification. The blocks
+    /// are executed once verified, and removed if their commitments are reverted on the contract,
+    /// so the storage keeps up with the server and may serve the API as its read-only replica.
+    pub follow_committed: bool,
+    /// The last restored block which execution is saved in storage. Unless the driver follows
+    /// the committed blocks, it's the last block of the tree state.
+    pub last_executed_block: BlockNumber,
 }
 
 impl<T: Transport> DataRestoreDriver<T> {
@@ -134,6 +156,12 @@ impl<T: Transport> DataRestoreDriver<T> {
             finite_mode,
             final_hash,
             last_priority_op_serial_id: 0,
+            request_limiter: RequestLimiter::default(),
+            eth_poll_interval: ETH_POLL_INTERVAL,
+            target_block: None,
+            progress: RestoreProgress::default(),
+            follow_committed: false,
+            last_executed_block: BlockNumber(0),
         }
     }
 
@@ -246,6 +274,7 @@ impl<T: Transport> DataRestoreDriver<T> {
         vlog::info!("Saved genesis tree state\n");
 
         self.tree_state = tree_state;
+        self.last_executed_block = current_block;
     }
 
     async fn update_tree_cache(&mut self, interactor: &mut StorageInteractor<'_>) {
@@ -264,15 +293,56 @@ impl<T: Transport> DataRestoreDriver<T> {
             .await;
     }
 
-    /// Stops states from storage
+    /// Returns `true` if the target block is set and restored, reporting its root hash.
+    ///
+    /// Returns an error if the restored state is already past the target block, e.g. when resuming
+    /// with a lower target, since the restored blocks can't be reverted to it.
+    fn check_target_block(&self) -> anyhow::Result<bool> {
+        let target_block = match self.target_block {
+            Some(target_block) => target_block,
+            None => return Ok(false),
+        };
+        let restored_block = self.tree_state.block_number;
+        anyhow::ensure!(
+            restored_block <= target_block,
+            "Restored state is already past the target block {}: the last restored block is {}",
+            *target_block,
+            *restored_block
+        );
+        if restored_block < target_block {
+            return Ok(false);
+        }
+
+        let root_hash = self.tree_state.root_hash();
+        vlog::info!(
+            "Target block {} has been restored\nRoot hash: {:?}\n",
+            *target_block,
+            root_hash
+        );
+        // The final hash is expected to be exactly the hash of the target block.
+        if let Some(final_hash) = self.final_hash {
+            anyhow::ensure!(
+                final_hash == root_hash,
+                "Root hash of the target block doesn't match the final hash"
+            );
+        }
+        Ok(true)
+    }
+
+    /// Loads states from storage, so the restoring is resumed from the last saved checkpoint:
+    /// the events, operations and tree are saved after each processed batch of Ethereum blocks.
+    /// Returns `true` if there's nothing left to restore in the finite mode.
+    ///
+    /// Returns an error if the saved operations diverge from the state committed on the contract.
     pub async fn load_state_from_storage(
         &mut self,
         interactor: &mut StorageInteractor<'_>,
-    ) -> bool {
+    ) -> anyhow::Result<bool> {
         let mut transaction = interactor.start_transaction().await;
         vlog::info!("Loading state from storage");
         let state = transaction.get_storage_state().await;
         self.events_state = transaction.get_block_events_state_from_storage().await;
+        self.last_executed_block = transaction.get_last_executed_block().await;
 
         let mut is_cached = false;
         // Try to load tree cache from the database.
@@ -300,17 +370,22 @@ impl<T: Transport> DataRestoreDriver<T> {
             StorageUpdateState::Events => {
                 // Update operations
                 let new_ops_blocks = self.update_operations_state(&mut transaction).await;
+                // The cached tree is outdated once the new blocks are applied.
+                is_cached &= new_ops_blocks.is_empty();
 
                 // Update tree
                 self.update_tree_state(&mut transaction, new_ops_blocks)
-                    .await;
+                    .await?;
             }
             StorageUpdateState::Operations => {
                 // Update operations
                 let new_ops_blocks = transaction.get_ops_blocks_from_storage().await;
+                // The cached tree is outdated once the new blocks are applied.
+                is_cached &= new_ops_blocks.is_empty();
+
                 // Update tree
                 self.update_tree_state(&mut transaction, new_ops_blocks)
-                    .await;
+                    .await?;
             }
             StorageUpdateState::None => {}
         }
@@ -318,7 +393,9 @@ impl<T: Transport> DataRestoreDriver<T> {
         self.last_priority_op_serial_id = transaction.get_max_priority_op_serial_id().await;
         let total_verified_blocks = self.zksync_contract.get_total_verified_blocks().await;
 
-        let last_verified_block = self.tree_state.block_number;
+        let last_verified_block = self.last_executed_block;
+        self.progress
+            .update_blocks(last_verified_block, total_verified_blocks);
 
         transaction.commit().await;
 
@@ -328,38 +405,66 @@ impl<T: Transport> DataRestoreDriver<T> {
             self.tree_state.root_hash()
         );
 
-        let is_finished = self.finite_mode && (total_verified_blocks == *last_verified_block);
+        let is_finished = (self.finite_mode && (total_verified_blocks == *last_verified_block))
+            || self.check_target_block()?;
         // Save tree cache if necessary.
         if !is_cached {
             vlog::info!("Saving tree cache for future re-uses");
             self.update_tree_cache(interactor).await;
         }
-        is_finished
+        Ok(is_finished)
     }
 
-    /// Activates states updates
-    pub async fn run_state_update(&mut self, interactor: &mut StorageInteractor<'_>) {
+    /// Activates states updates. In the finite mode returns once all the verified blocks are
+    /// restored, otherwise keeps polling the contract for the new blocks every `eth_poll_interval`.
+    /// If the target block is set, returns once it's restored.
+    ///
+    /// Returns an error if the restored state can't be updated consistently with the contract.
+    pub async fn run_state_update(
+        &mut self,
+        interactor: &mut StorageInteractor<'_>,
+    ) -> anyhow::Result<()> {
         let mut last_watched_block: u64 = self.events_state.last_watched_eth_block_number;
         let mut final_hash_was_found = false;
+        // Whether there are no new Ethereum blocks to watch.
+        let mut is_following = false;
+        // The events loaded from storage may contain the blocks which are not applied yet,
+        // e.g. the ones after the target block of the previous run.
+        let mut has_loaded_events = !self.events_state.committed_events.is_empty();
         loop {
+            if self.check_target_block()? {
+                break;
+            }
             vlog::info!("Last watched ethereum block: {:?}", last_watched_block);
 
             // Update events
-            if self.update_events_state(interactor).await {
+            let has_new_events = self.update_events_state(interactor).await
+                || std::mem::take(&mut has_loaded_events);
+            if let Some(last_block) = self.events_state.last_committed_after_revert {
+                if last_block < self.tree_state.block_number {
+                    self.revert_blocks(interactor, last_block).await?;
+                }
+            }
+            if has_new_events {
                 // Update operations
                 let new_ops_blocks = self.update_operations_state(interactor).await;
+                // In the `follow_committed` mode the new events may only verify the restored blocks.
+                let has_verified_blocks =
+                    self.last_verified_restored_block() > self.last_executed_block;
 
-                if !new_ops_blocks.is_empty() {
+                if !new_ops_blocks.is_empty() || has_verified_blocks {
                     let mut transaction = interactor.start_transaction().await;
 
                     // Update tree
                     self.update_tree_state(&mut transaction, new_ops_blocks)
-                        .await;
+                        .await?;
 
                     let total_verified_blocks =
                         self.zksync_contract.get_total_verified_blocks().await;
 
-                    let last_verified_block = self.tree_state.block_number;
+                    let last_verified_block = self.last_executed_block;
+                    self.progress
+                        .update_blocks(last_verified_block, total_verified_blocks);
 
                     // We must update the Ethereum stats table to match the actual stored state
                     // to keep the `state_keeper` consistent with the `eth_sender`.
@@ -403,12 +508,82 @@ impl<T: Transport> DataRestoreDriver<T> {
             }
 
             if last_watched_block == self.events_state.last_watched_eth_block_number {
-                vlog::info!("sleep block");
-                std::thread::sleep(std::time::Duration::from_secs(5));
+                if !is_following {
+                    // In the non-finite mode the driver keeps following the contract,
+                    // so the storage may serve as a read-only replica of the zkSync state.
+                    // Unless following the committed blocks, it lags behind the server by the unverified ones.
+                    vlog::info!(
+                        "Caught up with the contract at the block {}, following the new blocks",
+                        *self.tree_state.block_number
+                    );
+                    is_following = true;
+                }
+                tokio::time::sleep(self.eth_poll_interval).await;
             } else {
                 last_watched_block = self.events_state.last_watched_eth_block_number;
+                is_following = false;
             }
         }
+        Ok(())
+    }
+
+    /// Returns the last restored block verified on the contract according to the events.
+    fn last_verified_restored_block(&self) -> BlockNumber {
+        self.events_state.max_verified_block_number().map_or(
+            self.last_executed_block,
+            |last_verified_block| {
+                last_verified_block
+                    .min(self.tree_state.block_number)
+                    .max(self.last_executed_block)
+            },
+        )
+    }
+
+    /// Removes the restored blocks which commitments are reverted on the contract and rebuilds
+    /// the tree state. Only the blocks applied in the `follow_committed` mode may be reverted,
+    /// since the verified blocks are final.
+    ///
+    /// # Arguments
+    ///
+    /// * `last_block` - The last committed block left after the revert
+    ///
+    async fn revert_blocks(
+        &mut self,
+        interactor: &mut StorageInteractor<'_>,
+        last_block: BlockNumber,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            last_block >= self.last_executed_block,
+            "Executed block {} can't be reverted",
+            *self.last_executed_block
+        );
+        vlog::warn!(
+            "Blocks {}-{} are reverted on the contract, removing them",
+            *last_block + 1,
+            *self.tree_state.block_number
+        );
+
+        let mut transaction = interactor.start_transaction().await;
+        transaction.revert_blocks(last_block).await?;
+        transaction.update_eth_state().await;
+
+        let tree_state = transaction.get_tree_state().await;
+        self.tree_state = TreeState::load(
+            tree_state.last_block_number,
+            tree_state.account_map,
+            tree_state.unprocessed_prior_ops,
+            tree_state.fee_acc_id,
+        );
+        self.last_priority_op_serial_id = transaction.get_max_priority_op_serial_id().await;
+        self.update_tree_cache(&mut transaction).await;
+
+        transaction.commit().await;
+
+        vlog::info!(
+            "Blocks are reverted\nRoot hash: {:?}\n",
+            self.tree_state.root_hash()
+        );
+        Ok(())
     }
 
     /// Updates events state, saves new blocks, tokens events and the last watched eth block number in storage
@@ -431,6 +606,7 @@ impl<T: Transport> DataRestoreDriver<T> {
                 self.eth_blocks_step,
                 self.end_eth_blocks_offset,
                 self.init_contract_version,
+                &self.request_limiter,
             )
             .await
             .expect("Updating events state: cant update events state");
@@ -447,6 +623,16 @@ impl<T: Transport> DataRestoreDriver<T> {
             .save_withdrawals(&withdrawal_events, &withdrawal_pending_events)
             .await;
 
+        // The progress is only reported, so it's not updated if the node is unavailable.
+        self.request_limiter.wait().await;
+        if let Ok(latest_eth_block) = EventsState::get_last_block_number(&self.web3).await {
+            self.progress.update_events(
+                last_watched_eth_block_number,
+                latest_eth_block,
+                self.events_state.committed_events.len() + self.events_state.verified_events.len(),
+            );
+        }
+
         !block_events.is_empty()
     }
 
@@ -456,14 +642,16 @@ impl<T: Transport> DataRestoreDriver<T> {
     ///
     /// * `new_ops_blocks` - the new Rollup operations blocks
     ///
+    /// Returns an error if the restored state of any block diverges from the committed one.
     async fn update_tree_state(
         &mut self,
         interactor: &mut StorageInteractor<'_>,
         new_ops_blocks: Vec<RollupOpsBlock>,
-    ) {
+    ) -> anyhow::Result<()> {
         let mut blocks = vec![];
         let mut updates = vec![];
         let mut count = 0;
+        let last_restored_block = self.tree_state.block_number;
 
         for op_block in new_ops_blocks {
             // Take the contract version into account when choosing block chunk sizes.
@@ -471,24 +659,35 @@ impl<T: Transport> DataRestoreDriver<T> {
                 .contract_version
                 .expect("contract version must be set")
                 .available_block_chunk_sizes();
-            let (block, acc_updates) = self
-                .tree_state
-                .update_tree_states_from_ops_block(
-                    &op_block,
-                    available_block_chunk_sizes,
-                    &mut self.last_priority_op_serial_id,
-                )
-                .expect("Updating tree state: cant update tree from operations");
+            let (block, acc_updates) = self.tree_state.update_tree_states_from_ops_block(
+                &op_block,
+                available_block_chunk_sizes,
+                &mut self.last_priority_op_serial_id,
+            )?;
             blocks.push(block);
             updates.push(acc_updates);
             count += 1;
         }
 
+        let last_verified_block = self.last_verified_restored_block();
         let mut transaction = interactor.start_transaction().await;
+        // The blocks restored by the previous updates may be verified since then,
+        // they're executed before the new ones.
+        let last_executed_block = last_verified_block.min(last_restored_block);
+        if last_executed_block > self.last_executed_block {
+            transaction
+                .execute_blocks(self.last_executed_block + 1, last_executed_block)
+                .await;
+            self.last_executed_block = last_executed_block;
+        }
         for i in 0..count {
+            let is_executed = blocks[i].block_number <= last_verified_block;
             transaction
-                .update_tree_state(blocks[i].clone(), updates[i].clone())
+                .update_tree_state(blocks[i].clone(), updates[i].clone(), is_executed)
                 .await;
+            if is_executed {
+                self.last_executed_block = blocks[i].block_number;
+            }
         }
 
         // Store priority operations Ethereum metadata in the database.
@@ -512,6 +711,7 @@ impl<T: Transport> DataRestoreDriver<T> {
         transaction.commit().await;
 
         vlog::debug!("Updated state");
+        Ok(())
     }
 
     /// Gets new operations blocks from events, updates rollup operations stored state.
@@ -529,44 +729,66 @@ impl<T: Transport> DataRestoreDriver<T> {
         new_blocks
     }
 
-    /// Returns operations blocks from verified op blocks events.
+    /// Returns operations blocks from verified op blocks events, or from all the committed ones
+    /// in the `follow_committed` mode.
+    /// The commit transactions are fetched concurrently, within the limits of `request_limiter`.
     pub async fn get_new_operation_blocks_from_events(&mut self) -> Vec<RollupOpsBlock> {
-        let mut blocks = Vec::new();
-
-        let mut last_event_tx_hash = None;
-        // The HashMap from block_num to the RollupOpsBlock data for the tx represented by last_event_tx_hash.
-        // It is used as a cache to reuse the fetched data.
-        let mut last_tx_blocks = HashMap::new();
-
+        let committed_events = if self.follow_committed {
+            self.events_state.committed_events.clone()
+        } else {
+            self.events_state.get_only_verified_committed_events()
+        };
         // TODO (ZKS-722): either due to Ethereum node lag or unknown
         // bug in the events state, we have to additionally filter out
         // already processed rollup blocks.
+        let mut events = Vec::new();
         let mut last_processed_block = self.tree_state.block_number;
-        for event in self
-            .events_state
-            .get_only_verified_committed_events()
-            .iter()
+        for event in committed_events
+            .into_iter()
             .filter(|bl| bl.block_num > self.tree_state.block_number)
+            .filter(|bl| {
+                self.target_block
+                    .map_or(true, |target| bl.block_num <= target)
+            })
         {
             // For some reasons, we have a bug where event state contains duplicates for blocks
             if last_processed_block >= event.block_num {
                 continue;
             }
-            // We use an aggregated block in contracts, which means that several BlockEvent can include the same tx_hash,
-            // but for correct restore we need to generate RollupBlocks from this tx only once.
-            // These blocks go one after the other, and checking only the last transaction hash is safe.
-
-            // If the previous tx hash does not exist or it is not equal to the current one, we should
-            // re-fetch the blocks for the new tx hash.
-            if !last_event_tx_hash
-                .map(|tx| tx == event.transaction_hash)
-                .unwrap_or_default()
-            {
-                let blocks = RollupOpsBlock::get_rollup_ops_blocks(&self.web3, event)
-                    .await
-                    .expect("Cant get new operation blocks from events");
-
-                last_tx_blocks = blocks
+            last_processed_block = event.block_num;
+            events.push(event);
+        }
+
+        // We use an aggregated block in contracts, which means that several BlockEvent can include the same tx_hash,
+        // but for correct restore we need to generate RollupBlocks from this tx only once.
+        // These blocks go one after the other, and checking only the previous transaction hash is safe.
+        let mut tx_events: Vec<&BlockEvent> = events.iter().collect();
+        tx_events.dedup_by_key(|event| event.transaction_hash);
+
+        let web3 = &self.web3;
+        let limiter = &self.request_limiter;
+        let tx_blocks: Vec<Vec<RollupOpsBlock>> = stream::iter(tx_events)
+            .map(|event| async move {
+                limiter.wait().await;
+                RollupOpsBlock::get_rollup_ops_blocks(web3, event).await
+            })
+            .buffered(limiter.max_concurrent_requests())
+            .try_collect()
+            .await
+            .expect("Cant get new operation blocks from events");
+
+        let mut blocks = Vec::with_capacity(events.len());
+        let mut tx_blocks = tx_blocks.into_iter();
+        let mut last_event_tx_hash = None;
+        // The HashMap from block_num to the RollupOpsBlock data for the tx represented by last_event_tx_hash.
+        let mut last_tx_blocks = HashMap::new();
+        for event in &events {
+            // If the previous tx hash does not exist or it is not equal to the current one,
+            // the blocks of the next fetched tx should be used.
+            if last_event_tx_hash != Some(event.transaction_hash) {
+                last_tx_blocks = tx_blocks
+                    .next()
+                    .expect("Blocks are fetched for each tx")
                     .into_iter()
                     .map(|block| (block.block_num, block))
                     .collect();
@@ -575,7 +797,6 @@ impl<T: Transport> DataRestoreDriver<T> {
 
             if let Some(rollup_block) = last_tx_blocks.remove(&event.block_num) {
                 blocks.push(rollup_block);
-                last_processed_block = event.block_num;
             } else {
                 panic!("Block not found")
             }
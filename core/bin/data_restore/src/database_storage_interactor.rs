@@ -8,8 +8,8 @@ use zksync_storage::{
 use zksync_types::withdrawals::{WithdrawalEvent, WithdrawalPendingEvent};
 use zksync_types::{
     aggregated_operations::{BlocksCommitOperation, BlocksExecuteOperation},
-    AccountId, BlockNumber, NewTokenEvent, PriorityOp, SerialId, Token, TokenId, TokenInfo,
-    TokenKind,
+    AccountId, AccountMap, BlockNumber, NewTokenEvent, PriorityOp, SerialId, Token, TokenId,
+    TokenInfo, TokenKind,
     {block::Block, AccountUpdate, AccountUpdates},
 };
 
@@ -69,6 +69,20 @@ impl<'a> DatabaseStorageInteractor<'a> {
             .expect("Сant make u256 block_number in get_last_watched_block_number_from_storage")
     }
 
+    /// Returns last watched ethereum block number from storage, or `None` if it was never saved
+    pub async fn find_last_watched_block_number(&mut self) -> Option<u64> {
+        let last_watched_block_number = self
+            .storage
+            .data_restore_schema()
+            .find_last_watched_block_number()
+            .await
+            .expect("Cant load last watched block number")?;
+
+        let block_number = u64::from_str(last_watched_block_number.block_number.as_str())
+            .expect("Сant make u256 block_number in find_last_watched_block_number");
+        Some(block_number)
+    }
+
     pub async fn save_rollup_ops(&mut self, blocks: &[RollupOpsBlock]) {
         let mut ops = Vec::with_capacity(blocks.len());
 
@@ -79,6 +93,7 @@ impl<'a> DatabaseStorageInteractor<'a> {
                 fee_account: block.fee_account,
                 timestamp: block.timestamp,
                 previous_block_root_hash: block.previous_block_root_hash,
+                root_hash: block.root_hash,
             });
         }
 
@@ -89,7 +104,12 @@ impl<'a> DatabaseStorageInteractor<'a> {
             .expect("Cant update rollup operations");
     }
 
-    pub async fn update_tree_state(&mut self, block: Block, accounts_updated: AccountUpdates) {
+    pub async fn update_tree_state(
+        &mut self,
+        block: Block,
+        accounts_updated: AccountUpdates,
+        is_executed: bool,
+    ) {
         let mut transaction = self
             .storage
             .start_transaction()
@@ -101,9 +121,9 @@ impl<'a> DatabaseStorageInteractor<'a> {
             blocks: vec![block.clone()],
         };
 
-        let execute_aggregated_operation = BlocksExecuteOperation {
+        let execute_aggregated_operation = is_executed.then(|| BlocksExecuteOperation {
             blocks: vec![block.clone()],
-        };
+        });
 
         transaction
             .chain()
@@ -131,6 +151,52 @@ impl<'a> DatabaseStorageInteractor<'a> {
             .expect("Unable to commit DB transaction");
     }
 
+    pub async fn execute_blocks(&mut self, from_block: BlockNumber, to_block: BlockNumber) {
+        let mut transaction = self
+            .storage
+            .start_transaction()
+            .await
+            .expect("Failed initializing a DB transaction");
+
+        for block_number in *from_block..=*to_block {
+            let block = transaction
+                .chain()
+                .block_schema()
+                .get_block(BlockNumber(block_number))
+                .await
+                .expect("Cant get the block from storage")
+                .expect("The executed block is not restored");
+            transaction
+                .data_restore_schema()
+                .save_blocks_execution(BlocksExecuteOperation {
+                    blocks: vec![block],
+                })
+                .await
+                .expect("Cant execute verify operation");
+        }
+
+        transaction
+            .commit()
+            .await
+            .expect("Unable to commit DB transaction");
+    }
+
+    pub async fn revert_blocks(&mut self, last_block: BlockNumber) -> anyhow::Result<()> {
+        self.storage
+            .data_restore_schema()
+            .revert_blocks(last_block)
+            .await
+    }
+
+    pub async fn get_last_executed_block(&mut self) -> BlockNumber {
+        self.storage
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await
+            .expect("Can't get the last executed block")
+    }
+
     pub async fn apply_priority_op_data(
         &mut self,
         priority_op_data: impl Iterator<Item = &PriorityOp>,
@@ -282,17 +348,12 @@ impl<'a> DatabaseStorageInteractor<'a> {
             verified_events,
             last_watched_eth_block_number,
             priority_op_data,
+            last_committed_after_revert: None,
         }
     }
 
     pub async fn get_tree_state(&mut self) -> StoredTreeState {
-        let (last_block, account_map) = self
-            .storage
-            .chain()
-            .state_schema()
-            .load_verified_state()
-            .await
-            .expect("There are no last verified state in storage");
+        let (last_block, account_map) = self.load_committed_state().await;
 
         let block = self
             .storage
@@ -360,14 +421,28 @@ impl<'a> DatabaseStorageInteractor<'a> {
             .expect("Failed to commit database transaction");
     }
 
-    pub async fn get_cached_tree_state(&mut self) -> Option<CachedTreeState> {
-        let (last_block, account_map) = self
+    /// Loads the state of the last restored block. In the `follow_committed` mode it may be
+    /// not verified yet, so its updates are not applied to the verified state.
+    async fn load_committed_state(&mut self) -> (BlockNumber, AccountMap) {
+        let last_block = self
+            .storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await
+            .expect("Can't get the last committed block");
+        let (_, account_map) = self
             .storage
             .chain()
             .state_schema()
-            .load_verified_state()
+            .load_committed_state(Some(last_block))
             .await
-            .expect("Failed to load verified state from the database");
+            .expect("Failed to load committed state from the database");
+        (last_block, account_map)
+    }
+
+    pub async fn get_cached_tree_state(&mut self) -> Option<CachedTreeState> {
+        let (last_block, account_map) = self.load_committed_state().await;
 
         let tree_cache = self
             .storage
@@ -4,6 +4,7 @@ use std::convert::TryFrom;
 use std::fmt::Debug;
 // External deps
 use anyhow::format_err;
+use futures::{stream, StreamExt, TryStreamExt};
 use web3::contract::Contract;
 use web3::types::{
     BlockNumber as Web3BlockNumber, FilterBuilder, Log, Transaction, H256, U256, U64,
@@ -17,6 +18,7 @@ use zksync_types::{Address, BlockNumber, NewTokenEvent, PriorityOp, SerialId};
 use crate::contract::{ZkSyncContractVersion, ZkSyncDeployedContract};
 use crate::eth_tx_helpers::get_block_number_from_ethereum_transaction;
 use crate::events::{BlockEvent, EventType};
+use crate::request_limiter::RequestLimiter;
 
 /// Rollup contract events states description
 #[derive(Debug, Clone)]
@@ -32,6 +34,9 @@ pub struct EventsState {
     /// fetching fields which are not present in public data
     /// such as Ethereum transaction hash.
     pub priority_op_data: HashMap<SerialId, PriorityOp>,
+    /// The last committed block left by the `BlocksRevert` events of the last update, if any.
+    /// The blocks after it are reverted on the contract.
+    pub last_committed_after_revert: Option<BlockNumber>,
 }
 
 pub struct NewEvents<'a, T: Transport + Debug> {
@@ -43,6 +48,16 @@ pub struct NewEvents<'a, T: Transport + Debug> {
     pub last_block: u64,
 }
 
+/// Events of a single range of Ethereum blocks.
+struct RangeEvents {
+    block_logs: Vec<Log>,
+    new_tokens: Vec<NewTokenEvent>,
+    priority_ops: Vec<PriorityOp>,
+    withdrawal_events: Vec<WithdrawalEvent>,
+    withdrawal_pending_events: Vec<WithdrawalPendingEvent>,
+    last_block: u64,
+}
+
 impl std::default::Default for EventsState {
     /// Create default Rollup contract events state
     fn default() -> Self {
@@ -51,6 +66,7 @@ impl std::default::Default for EventsState {
             verified_events: Vec::new(),
             last_watched_eth_block_number: 0,
             priority_op_data: HashMap::new(),
+            last_committed_after_revert: None,
         }
     }
 }
@@ -99,6 +115,7 @@ impl EventsState {
     /// * `eth_blocks_step` - Blocks step for watching
     /// * `end_eth_blocks_offset` - Delta between last eth block and last watched block
     /// * `init_contract_version` - The initial version of the deployed zkSync contract
+    /// * `limiter` - Limits of the parallel requests to the Ethereum node
     ///
     #[allow(clippy::too_many_arguments)]
     pub async fn update_events_state<T: Transport>(
@@ -110,6 +127,7 @@ impl EventsState {
         eth_blocks_step: u64,
         end_eth_blocks_offset: u64,
         init_contract_version: u32,
+        limiter: &RequestLimiter,
     ) -> Result<
         (
             Vec<BlockEvent>,
@@ -122,6 +140,7 @@ impl EventsState {
         anyhow::Error,
     > {
         self.remove_verified_events();
+        self.last_committed_after_revert = None;
 
         let NewEvents {
             block_logs,
@@ -137,6 +156,7 @@ impl EventsState {
             self.last_watched_eth_block_number,
             eth_blocks_step,
             end_eth_blocks_offset,
+            limiter,
         )
         .await?;
         // Parse the initial contract version.
@@ -185,7 +205,9 @@ impl EventsState {
         Ok(web3.eth().block_number().await.map(|n| n.as_u64())?)
     }
 
-    /// Returns blocks logs, added token logs and the new last watched block number
+    /// Returns blocks logs, added token logs and the new last watched block number.
+    /// Up to `limiter.max_concurrent_requests()` ranges of `eth_blocks_step` blocks are fetched
+    /// concurrently, so a single update may watch several ranges.
     ///
     /// # Arguments
     ///
@@ -195,6 +217,7 @@ impl EventsState {
     /// * `last_watched_block_number` - the current last watched eth block
     /// * `eth_blocks_step` - Ethereum blocks delta step
     /// * `end_eth_blocks_offset` - last block delta
+    /// * `limiter` - Limits of the parallel requests to the Ethereum node
     ///
     #[allow(clippy::needless_lifetimes)] // Cargo clippy gives a false positive warning on needless_lifetimes there, so can be allowed.
     async fn get_new_events_and_last_watched_block<'a, T: Transport>(
@@ -204,32 +227,95 @@ impl EventsState {
         last_watched_block_number: u64,
         eth_blocks_step: u64,
         end_eth_blocks_offset: u64,
+        limiter: &RequestLimiter,
     ) -> anyhow::Result<NewEvents<'a, T>> {
+        limiter.wait().await;
         let latest_eth_block_minus_delta =
             EventsState::get_last_block_number(web3).await? - end_eth_blocks_offset;
 
+        let mut new_events = NewEvents {
+            block_logs: vec![],
+            new_tokens: vec![],
+            priority_ops: vec![],
+            withdrawal_events: vec![],
+            withdrawal_pending_events: vec![],
+            last_block: last_watched_block_number,
+        };
         if latest_eth_block_minus_delta == last_watched_block_number {
-            return Ok(NewEvents {
-                block_logs: vec![],
-                new_tokens: vec![],
-                priority_ops: vec![],
-                withdrawal_events: vec![],
-                withdrawal_pending_events: vec![],
-                last_block: last_watched_block_number,
-            });
             // No new eth blocks
+            return Ok(new_events);
         }
 
-        let from_block_number_u64 = last_watched_block_number + 1;
+        let mut ranges = Vec::new();
+        let mut from_block_number_u64 = last_watched_block_number + 1;
+        loop {
+            // if (latest eth block < last watched + delta) then choose it
+            let to_block_number_u64 =
+                (from_block_number_u64 + eth_blocks_step).min(latest_eth_block_minus_delta);
+            ranges.push((from_block_number_u64, to_block_number_u64));
+
+            if to_block_number_u64 >= latest_eth_block_minus_delta
+                || ranges.len() >= limiter.max_concurrent_requests()
+            {
+                break;
+            }
+            from_block_number_u64 = to_block_number_u64 + 1;
+        }
 
-        let to_block_number_u64 =
-        // if (latest eth block < last watched + delta) then choose it
-        if from_block_number_u64 + eth_blocks_step > latest_eth_block_minus_delta {
-            latest_eth_block_minus_delta
-        } else {
-            from_block_number_u64 + eth_blocks_step
-        };
+        // The ranges are fetched concurrently, but their events are returned in order.
+        let ranges_events: Vec<RangeEvents> = stream::iter(ranges)
+            .map(|(from, to)| {
+                EventsState::get_range_events(
+                    web3,
+                    zksync_contract,
+                    governance_contract,
+                    from,
+                    to,
+                    limiter,
+                )
+            })
+            .buffered(limiter.max_concurrent_requests())
+            .try_collect()
+            .await?;
 
+        let mut logs = vec![];
+        for range_events in ranges_events {
+            logs.extend(range_events.block_logs);
+            new_events.new_tokens.extend(range_events.new_tokens);
+            new_events.priority_ops.extend(range_events.priority_ops);
+            new_events
+                .withdrawal_events
+                .extend(range_events.withdrawal_events);
+            new_events
+                .withdrawal_pending_events
+                .extend(range_events.withdrawal_pending_events);
+            new_events.last_block = range_events.last_block;
+        }
+        new_events.block_logs.push((zksync_contract, logs));
+
+        Ok(new_events)
+    }
+
+    /// Returns blocks logs, added token logs and other events that occurred on the specified blocks
+    ///
+    /// # Arguments
+    ///
+    /// * `web3` - Web3 provider url
+    /// * `zksync_contract` - Rollup contract
+    /// * `governance_contract` - Governance contract
+    /// * `from_block_number_u64` - Start ethereum block number
+    /// * `to_block_number_u64` - End ethereum block number (inclusive)
+    /// * `limiter` - Limits of the parallel requests to the Ethereum node
+    ///
+    async fn get_range_events<T: Transport>(
+        web3: &Web3<T>,
+        zksync_contract: &ZkSyncDeployedContract<T>,
+        governance_contract: &(ethabi::Contract, Contract<T>),
+        from_block_number_u64: u64,
+        to_block_number_u64: u64,
+        limiter: &RequestLimiter,
+    ) -> anyhow::Result<RangeEvents> {
+        limiter.wait().await;
         let new_tokens = EventsState::get_token_added_logs(
             web3,
             governance_contract,
@@ -237,7 +323,8 @@ impl EventsState {
             Web3BlockNumber::Number(to_block_number_u64.into()),
         )
         .await?;
-        let mut logs = vec![];
+
+        limiter.wait().await;
         let block_logs = EventsState::get_block_logs(
             web3,
             zksync_contract,
@@ -245,8 +332,8 @@ impl EventsState {
             Web3BlockNumber::Number(to_block_number_u64.into()),
         )
         .await?;
-        logs.push((zksync_contract, block_logs));
 
+        limiter.wait().await;
         let priority_op_data = EventsState::get_priority_operations_logs(
             web3,
             zksync_contract,
@@ -255,6 +342,7 @@ impl EventsState {
         )
         .await?;
 
+        limiter.wait().await;
         let (withdrawal_events, withdrawal_pending_events) = EventsState::get_withdrawal_logs(
             web3,
             zksync_contract,
@@ -263,8 +351,8 @@ impl EventsState {
         )
         .await?;
 
-        Ok(NewEvents {
-            block_logs: logs,
+        Ok(RangeEvents {
+            block_logs,
             new_tokens,
             priority_ops: priority_op_data,
             withdrawal_events,
@@ -305,6 +393,33 @@ impl EventsState {
         Ok(result)
     }
 
+    /// Returns Ethereum blocks that include `UpgradeComplete` events, one per each contract upgrade.
+    /// Since the upgrades may change the operations encoding, these blocks are used to choose
+    /// the contract version for parsing the commit transactions.
+    ///
+    /// # Arguments
+    ///
+    /// * `web3` - Web3 provider url
+    /// * `upgrade_gatekeeper_contract_address` - UpgradeGateKeeper contract address
+    ///
+    pub async fn get_contract_upgrade_eth_blocks<T: Transport>(
+        web3: &Web3<T>,
+        upgrade_gatekeeper_contract_address: Address,
+    ) -> anyhow::Result<Vec<u64>> {
+        let logs =
+            EventsState::get_gatekeeper_logs(web3, upgrade_gatekeeper_contract_address).await?;
+        let mut eth_blocks = logs
+            .into_iter()
+            .map(|log| {
+                log.block_number
+                    .map(|block| block.as_u64())
+                    .ok_or_else(|| format_err!("No Ethereum block number for upgrade log"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        eth_blocks.sort_unstable();
+        Ok(eth_blocks)
+    }
+
     async fn get_priority_operations_logs_inner<T: Transport>(
         web3: &Web3<T>,
         contract: &ZkSyncDeployedContract<T>,
@@ -327,7 +442,7 @@ impl EventsState {
         logs.into_iter()
             .map(|event| {
                 PriorityOp::try_from(event)
-                    .map_err(|e| format_err!("Failed to parse event log from ETH: {:?}", e))
+                    .map_err(|e| format_err!("Failed to parse event log from ETH: {}", e))
             })
             .collect()
     }
@@ -603,6 +718,10 @@ impl EventsState {
                     .retain(|bl| bl.block_num <= total_committed);
                 self.verified_events
                     .retain(|bl| bl.block_num <= total_executed);
+                self.last_committed_after_revert = Some(
+                    self.last_committed_after_revert
+                        .map_or(total_committed, |block| block.min(total_committed)),
+                );
 
                 continue;
             }
@@ -640,7 +759,8 @@ impl EventsState {
         true
     }
 
-    fn max_verified_block_number(&self) -> Option<BlockNumber> {
+    /// Returns the last verified block according to the verified events of the last update.
+    pub fn max_verified_block_number(&self) -> Option<BlockNumber> {
         self.verified_events
             .iter()
             .max_by_key(|e| e.block_num)
@@ -747,5 +867,9 @@ mod test {
         events_state.update_blocks_state(&contract, &[log], &upgrade_blocks, v4);
         assert_eq!(events_state.committed_events.len(), 16);
         assert_eq!(events_state.verified_events.len(), 11);
+        assert_eq!(
+            events_state.last_committed_after_revert,
+            Some(zksync_types::BlockNumber(15))
+        );
     }
 }
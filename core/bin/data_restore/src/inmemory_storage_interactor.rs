@@ -1,6 +1,6 @@
 use std::cell::RefCell;
 use std::cmp::max;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use web3::types::Address;
@@ -27,10 +27,12 @@ struct Inner {
     tokens: HashMap<TokenId, Token>,
     events_state: Vec<BlockEvent>,
     last_watched_block: u64,
-    #[allow(dead_code)]
     last_committed_block: BlockNumber,
     last_verified_block: BlockNumber,
     accounts: AccountMap,
+    /// Accounts and tokens before each of the committed blocks which are not executed yet,
+    /// so the blocks can be reverted.
+    unexecuted_blocks: BTreeMap<BlockNumber, (AccountMap, HashMap<TokenId, Token>)>,
 }
 
 impl Default for Inner {
@@ -44,6 +46,7 @@ impl Default for Inner {
             last_committed_block: BlockNumber(0),
             last_verified_block: BlockNumber(0),
             accounts: Default::default(),
+            unexecuted_blocks: Default::default(),
         }
     }
 }
@@ -169,6 +172,7 @@ impl InMemoryStorageInteractor {
                             decimals: 0,
                             kind: TokenKind::NFT,
                             is_nft: true,
+                            display_symbol: token.symbol.clone(),
                         },
                     );
                 }
@@ -193,7 +197,12 @@ impl InMemoryStorageInteractor {
         inner.storage_state = StorageUpdateState::Operations
     }
 
-    pub async fn update_tree_state(&mut self, block: Block, accounts_updated: AccountUpdates) {
+    pub async fn update_tree_state(
+        &mut self,
+        block: Block,
+        accounts_updated: AccountUpdates,
+        is_executed: bool,
+    ) {
         let mut inner = self.inner.borrow_mut();
 
         let commit_op = Operation {
@@ -211,7 +220,12 @@ impl InMemoryStorageInteractor {
         };
 
         inner.last_committed_block = commit_op.block.block_number;
-        inner.last_verified_block = verify_op.block.block_number;
+        if is_executed {
+            inner.last_verified_block = verify_op.block.block_number;
+        } else {
+            let snapshot = (inner.accounts.clone(), inner.tokens.clone());
+            inner.unexecuted_blocks.insert(block.block_number, snapshot);
+        }
         drop(inner);
 
         self.commit_state_update(*block.block_number, accounts_updated);
@@ -220,6 +234,38 @@ impl InMemoryStorageInteractor {
         // TODO save operations
     }
 
+    pub async fn execute_blocks(&mut self, _from_block: BlockNumber, to_block: BlockNumber) {
+        let mut inner = self.inner.borrow_mut();
+        inner.last_verified_block = to_block;
+        // The executed blocks are final, so their snapshots are not needed anymore.
+        inner.unexecuted_blocks = inner.unexecuted_blocks.split_off(&(to_block + 1));
+    }
+
+    pub async fn revert_blocks(&mut self, last_block: BlockNumber) -> anyhow::Result<()> {
+        let mut inner = self.inner.borrow_mut();
+        if last_block >= inner.last_committed_block {
+            return Ok(());
+        }
+        // The account updates are applied on commit, so the state before the first reverted
+        // block is restored.
+        let first_reverted_block = last_block + 1;
+        anyhow::ensure!(
+            inner.unexecuted_blocks.contains_key(&first_reverted_block),
+            "Block {} is executed and can't be reverted",
+            *first_reverted_block
+        );
+        let mut reverted_blocks = inner.unexecuted_blocks.split_off(&first_reverted_block);
+        let (accounts, tokens) = reverted_blocks.remove(&first_reverted_block).unwrap();
+        inner.accounts = accounts;
+        inner.tokens = tokens;
+        inner.last_committed_block = last_block;
+        Ok(())
+    }
+
+    pub async fn get_last_executed_block(&mut self) -> BlockNumber {
+        self.inner.borrow().last_verified_block
+    }
+
     pub async fn apply_priority_op_data(
         &mut self,
         _priority_op_data: impl Iterator<Item = &PriorityOp>,
@@ -264,6 +310,7 @@ impl InMemoryStorageInteractor {
                     decimals: 18,
                     kind: TokenKind::ERC20,
                     is_nft: false,
+                    display_symbol: format!("ERC20-{}", *id),
                 },
             );
         }
@@ -295,14 +342,21 @@ impl InMemoryStorageInteractor {
             verified_events,
             last_watched_eth_block_number: inner.last_watched_block,
             priority_op_data: Default::default(),
+            last_committed_after_revert: None,
         }
     }
 
+    pub async fn find_last_watched_block_number(&mut self) -> Option<u64> {
+        // Genesis Ethereum block is never zero, so zero means the restoring was never started.
+        let last_watched_block = self.inner.borrow().last_watched_block;
+        Some(last_watched_block).filter(|&block| block != 0)
+    }
+
     pub async fn get_tree_state(&mut self) -> StoredTreeState {
         let inner = self.inner.borrow();
         // TODO find a way how to get unprocessed_prior_ops and fee_acc_id
         StoredTreeState {
-            last_block_number: inner.last_verified_block,
+            last_block_number: inner.last_committed_block,
             account_map: inner.accounts.clone(),
             unprocessed_prior_ops: 0,
             fee_acc_id: AccountId(0),
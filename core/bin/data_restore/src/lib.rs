@@ -5,19 +5,28 @@ pub mod eth_tx_helpers;
 pub mod events;
 pub mod events_state;
 pub mod inmemory_storage_interactor;
+pub mod progress;
+pub mod request_limiter;
 pub mod rollup_ops;
+pub mod status_api;
 pub mod storage_interactor;
 pub mod tree_state;
 
 #[cfg(test)]
 mod tests;
 
+use std::time::Duration;
+
 use crate::storage_interactor::StorageInteractor;
 use zksync_types::{tokens::get_genesis_token_list, TokenId};
 
 // How many blocks we will process at once.
 pub const ETH_BLOCKS_STEP: u64 = 100;
 pub const END_ETH_BLOCKS_OFFSET: u64 = 40;
+// How many requests to the Ethereum node are processed concurrently by default.
+pub const MAX_CONCURRENT_REQUESTS: usize = 8;
+// How often the Ethereum node is polled for the new blocks once the restoring caught up.
+pub const ETH_POLL_INTERVAL: Duration = Duration::from_secs(5);
 
 pub async fn add_tokens_to_storage(interactor: &mut StorageInteractor<'_>, eth_network: &str) {
     let genesis_tokens = get_genesis_token_list(eth_network).expect("Initial token list not found");
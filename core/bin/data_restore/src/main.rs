@@ -1,18 +1,23 @@
+use std::time::Duration;
+
 use serde::Deserialize;
 use structopt::StructOpt;
 use web3::transports::Http;
 use zksync_config::configs::{ChainConfig, ContractsConfig as EnvContractsConfig, ETHClientConfig};
 use zksync_crypto::convert::FeConvert;
 use zksync_storage::ConnectionPool;
-use zksync_types::{Address, H256};
+use zksync_types::{Address, BlockNumber, H256};
 
 use web3::Web3;
 use zksync_data_restore::contract::ZkSyncDeployedContract;
 use zksync_data_restore::{
     add_tokens_to_storage, data_restore_driver::DataRestoreDriver,
-    database_storage_interactor::DatabaseStorageInteractor, storage_interactor::StorageInteractor,
-    END_ETH_BLOCKS_OFFSET, ETH_BLOCKS_STEP,
+    database_storage_interactor::DatabaseStorageInteractor, events_state::EventsState,
+    request_limiter::RequestLimiter, status_api::start_status_api,
+    storage_interactor::StorageInteractor, END_ETH_BLOCKS_OFFSET, ETH_BLOCKS_STEP,
+    MAX_CONCURRENT_REQUESTS,
 };
+use zksync_prometheus_exporter::run_prometheus_exporter;
 use zksync_types::network::Network;
 
 #[derive(StructOpt)]
@@ -22,22 +27,31 @@ use zksync_types::network::Network;
     rename_all = "snake_case"
 )]
 struct Opt {
-    /// Restores data with provided genesis (zero) block
+    /// Restores data with provided genesis (zero) block. Fails if the storage already contains
+    /// the restoring progress, which has to be removed to restore from genesis once again
     #[structopt(long)]
     genesis: bool,
 
-    /// Continues data restoring
+    /// Continues data restoring. The restoring is also continued without this flag
+    /// if the storage contains the progress of the previous run
     #[structopt(long = "continue", name = "continue")]
     continue_mode: bool,
 
-    /// Restore data until the last verified block and exit
+    /// Restore data until the last verified block and exit. Otherwise, the restoring keeps
+    /// following the new blocks, so the database is kept in sync with the contract
     #[structopt(long)]
     finite: bool,
 
     /// Expected tree root hash after restoring. This argument is ignored if mode is not `finite`
+    /// and the target block is not set
     #[structopt(long)]
     final_hash: Option<String>,
 
+    /// Restore data until the provided verified block, report its root hash and exit.
+    /// Fails if the saved restoring progress is already past the provided block
+    #[structopt(long)]
+    target_block: Option<u32>,
+
     /// Sets the web3 API to be used to interact with the Ethereum blockchain
     #[structopt(long = "web3", name = "web3")]
     web3_url: Option<String>,
@@ -45,12 +59,42 @@ struct Opt {
     /// Provides a path to the configuration file for data restore
     #[structopt(long = "config", name = "config")]
     config_path: Option<String>,
+
+    /// Sets the number of requests to the Ethereum node processed concurrently, 8 by default
+    #[structopt(long)]
+    max_concurrent_requests: Option<usize>,
+
+    /// Limits the rate of requests to the Ethereum node, e.g. to fit the quota of the Web3 provider
+    #[structopt(long)]
+    requests_per_second: Option<u32>,
+
+    /// Sets the interval in seconds of polling for the new Ethereum blocks once the restoring
+    /// caught up, 5 by default
+    #[structopt(long)]
+    poll_interval: Option<u64>,
+
+    /// Applies the committed blocks without waiting for their verification, so the database
+    /// keeps up with the server and may serve the API as its read-only replica. The blocks
+    /// reverted on the contract are removed. Can't be used with `finite` and `target_block`
+    #[structopt(long)]
+    follow_committed: bool,
+
+    /// Serves the restoring progress via HTTP on the provided port, at the `/status` endpoint
+    #[structopt(long)]
+    status_port: Option<u16>,
+
+    /// Exports the restoring progress metrics to Prometheus on the provided port
+    #[structopt(long)]
+    prometheus_port: Option<u16>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ContractsConfig {
     eth_network: Network,
     governance_addr: Address,
+    /// Used to detect the contract upgrades if `upgrade_eth_blocks` are not provided.
+    #[serde(default)]
+    upgrade_gatekeeper_addr: Option<Address>,
     genesis_tx_hash: H256,
     contract_addr: Address,
     init_contract_version: u32,
@@ -71,6 +115,7 @@ impl ContractsConfig {
         Self {
             eth_network: chain_opts.eth.network,
             governance_addr: contracts_opts.governance_addr,
+            upgrade_gatekeeper_addr: Some(contracts_opts.upgrade_gatekeeper_addr),
             genesis_tx_hash: contracts_opts.genesis_tx_hash,
             contract_addr: contracts_opts.contract_addr,
             init_contract_version: contracts_opts.init_contract_version,
@@ -102,7 +147,10 @@ async fn main() {
     vlog::info!("Using the following config: {:#?}", config);
 
     let finite_mode = opt.finite;
-    let final_hash = if finite_mode {
+    if opt.follow_committed && (finite_mode || opt.target_block.is_some()) {
+        panic!("`follow_committed` can't be used with `finite` or `target_block`");
+    }
+    let final_hash = if finite_mode || opt.target_block.is_some() {
         opt.final_hash
             .map(|value| FeConvert::from_hex(&value).expect("Can't parse the final hash"))
     } else {
@@ -110,11 +158,29 @@ async fn main() {
     };
     let storage = connection_pool.access_storage().await.unwrap();
     let web3 = Web3::new(transport);
+
+    // Contract upgrades may change the operations encoding, so the blocks committed after an upgrade
+    // are parsed with the corresponding contract version. If the upgrades are not configured,
+    // they are detected from the `UpgradeComplete` events.
+    let upgrade_eth_blocks = match config.upgrade_gatekeeper_addr {
+        Some(gatekeeper_addr) if config.upgrade_eth_blocks.is_empty() => {
+            let upgrade_eth_blocks =
+                EventsState::get_contract_upgrade_eth_blocks(&web3, gatekeeper_addr)
+                    .await
+                    .expect("Unable to load the contract upgrade events");
+            vlog::info!(
+                "Detected contract upgrades in the Ethereum blocks: {:?}",
+                upgrade_eth_blocks
+            );
+            upgrade_eth_blocks
+        }
+        _ => config.upgrade_eth_blocks,
+    };
     let contract = ZkSyncDeployedContract::version4(web3.eth(), config.contract_addr);
     let mut driver = DataRestoreDriver::new(
         web3,
         config.governance_addr,
-        config.upgrade_eth_blocks,
+        upgrade_eth_blocks,
         config.init_contract_version,
         ETH_BLOCKS_STEP,
         END_ETH_BLOCKS_OFFSET,
@@ -122,8 +188,41 @@ async fn main() {
         final_hash,
         contract,
     );
+    driver.request_limiter = RequestLimiter::new(
+        opt.max_concurrent_requests
+            .unwrap_or(MAX_CONCURRENT_REQUESTS),
+        opt.requests_per_second,
+    );
+    driver.target_block = opt.target_block.map(BlockNumber);
+    driver.follow_committed = opt.follow_committed;
+    if let Some(poll_interval) = opt.poll_interval {
+        driver.eth_poll_interval = Duration::from_secs(poll_interval);
+    }
+    if let Some(status_port) = opt.status_port {
+        start_status_api(driver.progress.clone(), status_port);
+    }
+    if let Some(prometheus_port) = opt.prometheus_port {
+        run_prometheus_exporter(prometheus_port);
+    }
 
     let mut interactor = StorageInteractor::Database(DatabaseStorageInteractor::new(storage));
+    // The progress is saved after each processed batch of Ethereum blocks, so the interrupted
+    // restoring is resumed from the last checkpoint rather than from genesis.
+    let saved_eth_block = interactor.find_last_watched_block_number().await;
+    if let Some(eth_block) = saved_eth_block {
+        if opt.genesis {
+            panic!(
+                "`genesis` can't be used when the storage contains the restoring progress (up to the Ethereum block {}): \
+                 remove it to restore from genesis, or run without `genesis` to resume",
+                eth_block
+            );
+        }
+        vlog::info!(
+            "Found the restoring progress, resuming from the Ethereum block {}",
+            eth_block
+        );
+    }
+
     // If genesis is argument is present - there will be fetching contracts creation transactions to get first eth block and genesis acc address
     if opt.genesis {
         // We have to load pre-defined tokens into the database before restoring state,
@@ -135,9 +234,20 @@ async fn main() {
             .await;
     }
 
-    if opt.continue_mode && driver.load_state_from_storage(&mut interactor).await {
-        std::process::exit(0);
+    let resume = opt.continue_mode || saved_eth_block.is_some();
+    if resume {
+        match driver.load_state_from_storage(&mut interactor).await {
+            Ok(true) => std::process::exit(0),
+            Ok(false) => {}
+            Err(err) => {
+                vlog::error!("Loading the restored state failed: {}", err);
+                std::process::exit(1);
+            }
+        }
     }
 
-    driver.run_state_update(&mut interactor).await;
+    if let Err(err) = driver.run_state_update(&mut interactor).await {
+        vlog::error!("Restoring failed: {}", err);
+        std::process::exit(1);
+    }
 }
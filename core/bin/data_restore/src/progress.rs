@@ -0,0 +1,144 @@
+// Built-in deps
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+// External deps
+use serde::Serialize;
+// Workspace deps
+use zksync_types::BlockNumber;
+
+/// Snapshot of the restoring progress, reported by the status API.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProgressStatus {
+    /// Last Ethereum block the events were fetched for.
+    pub last_watched_eth_block: u64,
+    /// Latest known Ethereum block.
+    pub latest_eth_block: u64,
+    /// How many Ethereum blocks are left to fetch the events for.
+    pub eth_block_lag: u64,
+    /// Number of the block events stored in the events state.
+    pub events_fetched: usize,
+    /// Last rollup block applied to the tree.
+    pub restored_block: BlockNumber,
+    /// Number of the blocks verified on the contract.
+    pub total_verified_blocks: u32,
+    /// Estimated time in seconds left to restore all the verified blocks,
+    /// `None` until the first blocks are restored.
+    pub eta_secs: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    status: ProgressStatus,
+    /// The moment and the block the rate of restoring is measured from.
+    start: Option<(Instant, BlockNumber)>,
+}
+
+/// Tracks the restoring progress for the status API, and reports it to the metrics.
+/// The clones of the tracker share the progress.
+#[derive(Debug, Clone, Default)]
+pub struct RestoreProgress {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl RestoreProgress {
+    /// Updates the events progress.
+    ///
+    /// # Arguments
+    ///
+    /// * `last_watched_eth_block` - Last Ethereum block the events were fetched for
+    /// * `latest_eth_block` - Latest known Ethereum block
+    /// * `events_fetched` - Number of the block events stored in the events state
+    ///
+    pub fn update_events(
+        &self,
+        last_watched_eth_block: u64,
+        latest_eth_block: u64,
+        events_fetched: usize,
+    ) {
+        let eth_block_lag = latest_eth_block.saturating_sub(last_watched_eth_block);
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.status.last_watched_eth_block = last_watched_eth_block;
+        inner.status.latest_eth_block = latest_eth_block;
+        inner.status.eth_block_lag = eth_block_lag;
+        inner.status.events_fetched = events_fetched;
+
+        metrics::gauge!(
+            "data_restore.last_watched_eth_block",
+            last_watched_eth_block as f64
+        );
+        metrics::gauge!("data_restore.eth_block_lag", eth_block_lag as f64);
+        metrics::gauge!("data_restore.events_fetched", events_fetched as f64);
+    }
+
+    /// Updates the blocks progress.
+    ///
+    /// # Arguments
+    ///
+    /// * `restored_block` - Last rollup block applied to the tree
+    /// * `total_verified_blocks` - Number of the blocks verified on the contract
+    ///
+    pub fn update_blocks(&self, restored_block: BlockNumber, total_verified_blocks: u32) {
+        let mut inner = self.inner.lock().unwrap();
+        let (start, start_block) = *inner
+            .start
+            .get_or_insert_with(|| (Instant::now(), restored_block));
+
+        let restored_since_start = restored_block.saturating_sub(*start_block);
+        let remaining = total_verified_blocks.saturating_sub(*restored_block);
+        inner.status.eta_secs = if restored_since_start > 0 {
+            let secs_per_block = start.elapsed().as_secs_f64() / f64::from(restored_since_start);
+            Some((secs_per_block * f64::from(remaining)) as u64)
+        } else if remaining == 0 {
+            Some(0)
+        } else {
+            None
+        };
+        inner.status.restored_block = restored_block;
+        inner.status.total_verified_blocks = total_verified_blocks;
+
+        metrics::gauge!("data_restore.restored_block", *restored_block as f64);
+        metrics::gauge!(
+            "data_restore.total_verified_blocks",
+            total_verified_blocks as f64
+        );
+        if let Some(eta_secs) = inner.status.eta_secs {
+            metrics::gauge!("data_restore.eta_secs", eta_secs as f64);
+        }
+    }
+
+    /// Returns the current progress.
+    pub fn status(&self) -> ProgressStatus {
+        self.inner.lock().unwrap().status.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RestoreProgress;
+    use zksync_types::BlockNumber;
+
+    #[test]
+    fn restore_progress() {
+        let progress = RestoreProgress::default();
+        progress.update_events(100, 140, 3);
+        let status = progress.status();
+        assert_eq!(status.eth_block_lag, 40);
+        assert_eq!(status.events_fetched, 3);
+
+        // The rate of restoring is not known yet.
+        progress.update_blocks(BlockNumber(1), 10);
+        assert_eq!(progress.status().eta_secs, None);
+
+        progress.update_blocks(BlockNumber(5), 10);
+        let status = progress.status();
+        assert_eq!(status.restored_block, BlockNumber(5));
+        assert!(status.eta_secs.is_some());
+
+        // All the verified blocks are restored.
+        progress.update_blocks(BlockNumber(10), 10);
+        assert_eq!(progress.status().eta_secs, Some(0));
+    }
+}
@@ -0,0 +1,98 @@
+// Built-in deps
+use std::{sync::Arc, time::Duration};
+// External deps
+use tokio::{
+    sync::Mutex,
+    time::{sleep_until, Instant},
+};
+
+/// Limits of the requests to the Ethereum node made while fetching the events and the
+/// commit transactions in parallel:
+/// - the number of the requests being processed concurrently
+/// - the rate of the requests, so the parallel fetching doesn't exceed the quota of the Web3 provider
+///
+/// The clones of the limiter share the rate limit.
+#[derive(Debug, Clone)]
+pub struct RequestLimiter {
+    max_concurrent_requests: usize,
+    /// Minimal interval between two subsequent requests, `None` if the rate is not limited.
+    interval: Option<Duration>,
+    /// Moment when the next request is allowed.
+    next_request: Arc<Mutex<Instant>>,
+}
+
+impl RequestLimiter {
+    /// Returns the new limiter.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_concurrent_requests` - The number of the requests processed concurrently, at least one
+    /// * `requests_per_second` - The maximal rate of the requests, not limited if it's `None`
+    ///
+    pub fn new(max_concurrent_requests: usize, requests_per_second: Option<u32>) -> Self {
+        let interval = requests_per_second
+            .filter(|&rate| rate > 0)
+            .map(|rate| Duration::from_secs(1) / rate);
+
+        Self {
+            max_concurrent_requests: max_concurrent_requests.max(1),
+            interval,
+            next_request: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Returns the number of the requests processed concurrently.
+    pub fn max_concurrent_requests(&self) -> usize {
+        self.max_concurrent_requests
+    }
+
+    /// Waits until the next request is allowed by the rate limit.
+    pub async fn wait(&self) {
+        let interval = match self.interval {
+            Some(interval) => interval,
+            None => return,
+        };
+
+        let request_at = {
+            let mut next_request = self.next_request.lock().await;
+            let request_at = (*next_request).max(Instant::now());
+            *next_request = request_at + interval;
+            request_at
+        };
+        sleep_until(request_at).await;
+    }
+}
+
+impl Default for RequestLimiter {
+    /// Returns the limiter fetching sequentially without the rate limit.
+    fn default() -> Self {
+        Self::new(1, None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RequestLimiter;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn request_limiter() {
+        let limiter = RequestLimiter::new(0, Some(20));
+        assert_eq!(limiter.max_concurrent_requests(), 1);
+
+        // The first request is not delayed, the subsequent ones are spaced by 50ms.
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.clone().wait().await;
+        }
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        // Without the rate limit, requests are never delayed.
+        let limiter = RequestLimiter::new(4, None);
+        let start = Instant::now();
+        for _ in 0..100 {
+            limiter.wait().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+}
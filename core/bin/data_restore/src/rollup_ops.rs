@@ -1,3 +1,4 @@
+use anyhow::Context;
 use web3::{Transport, Web3};
 
 use zksync_types::operations::ZkSyncOp;
@@ -20,6 +21,8 @@ pub struct RollupOpsBlock {
     pub timestamp: Option<u64>,
     /// Previous block root hash.
     pub previous_block_root_hash: H256,
+    /// Root hash of the block committed on the contract, zero if it's unknown.
+    pub root_hash: H256,
     /// zkSync contract version for the given block.
     /// Used to obtain block chunk sizes. Stored in the database
     /// in the corresponding block event.
@@ -41,9 +44,18 @@ impl RollupOpsBlock {
     ) -> anyhow::Result<Vec<Self>> {
         let transaction = get_ethereum_transaction(web3, &event_data.transaction_hash).await?;
         let input_data = get_input_data_from_ethereum_transaction(&transaction)?;
+        // The operations encoding depends on the contract version, so the wrong version
+        // usually means that a contract upgrade is missing.
         let blocks: Vec<RollupOpsBlock> = event_data
             .contract_version
-            .rollup_ops_blocks_from_bytes(input_data)?;
+            .rollup_ops_blocks_from_bytes(input_data)
+            .with_context(|| {
+                format!(
+                    "Failed to parse the commit transaction {:?} of the block {} \
+                     with the contract version {:?}, check the contract upgrade blocks",
+                    event_data.transaction_hash, *event_data.block_num, event_data.contract_version
+                )
+            })?;
         Ok(blocks)
     }
 }
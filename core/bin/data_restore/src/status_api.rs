@@ -0,0 +1,47 @@
+//! Data restore status API server.
+//!
+//! Reports the progress of the restoring, so the long-running restores can be monitored.
+//! The same progress is reported to the Prometheus metrics.
+
+use std::thread;
+
+use actix_web::{web, App, HttpResponse, HttpServer};
+use futures::{channel::mpsc, StreamExt};
+use tokio::task::JoinHandle;
+use zksync_utils::panic_notify::ThreadPanicNotify;
+
+use crate::progress::RestoreProgress;
+
+/// Returns the restoring progress.
+#[actix_web::get("/status")]
+async fn status(progress: web::Data<RestoreProgress>) -> actix_web::Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(progress.status()))
+}
+
+pub fn start_status_api(progress: RestoreProgress, port: u16) -> JoinHandle<()> {
+    let (panic_sender, mut panic_receiver) = mpsc::channel(1);
+
+    thread::Builder::new()
+        .name("data-restore-status-api".to_string())
+        .spawn(move || {
+            let _panic_sentinel = ThreadPanicNotify(panic_sender.clone());
+            let actix_runtime = actix_rt::System::new();
+
+            actix_runtime.block_on(async move {
+                HttpServer::new(move || {
+                    App::new()
+                        .app_data(web::Data::new(progress.clone()))
+                        .service(status)
+                })
+                .bind(("0.0.0.0", port))
+                .expect("failed to bind")
+                .workers(1)
+                .run()
+                .await
+            })
+        })
+        .expect("failed to start status API server");
+    tokio::spawn(async move {
+        panic_receiver.next().await.unwrap();
+    })
+}
@@ -84,9 +84,42 @@ impl StorageInteractor<'_> {
     ///
     /// * `block` - Rollup block
     /// * `accounts_updated` - accounts updates
+    /// * `is_executed` - whether the block is already verified on the contract,
+    ///   so its execution is saved as well
     ///
-    pub async fn update_tree_state(&mut self, block: Block, accounts_updated: AccountUpdates) {
-        storage_interact!(self.update_tree_state(block, accounts_updated))
+    pub async fn update_tree_state(
+        &mut self,
+        block: Block,
+        accounts_updated: AccountUpdates,
+        is_executed: bool,
+    ) {
+        storage_interact!(self.update_tree_state(block, accounts_updated, is_executed))
+    }
+
+    /// Saves the execution of the restored blocks verified on the contract after their commitment
+    ///
+    /// # Arguments
+    ///
+    /// * `from_block` - The first block to execute
+    /// * `to_block` - The last block to execute
+    ///
+    pub async fn execute_blocks(&mut self, from_block: BlockNumber, to_block: BlockNumber) {
+        storage_interact!(self.execute_blocks(from_block, to_block))
+    }
+
+    /// Removes the restored blocks which commitments are reverted on the contract
+    ///
+    /// # Arguments
+    ///
+    /// * `last_block` - The last block left after the revert
+    ///
+    pub async fn revert_blocks(&mut self, last_block: BlockNumber) -> anyhow::Result<()> {
+        storage_interact!(self.revert_blocks(last_block))
+    }
+
+    /// Returns the last restored block which execution is saved in storage
+    pub async fn get_last_executed_block(&mut self) -> BlockNumber {
+        storage_interact!(self.get_last_executed_block())
     }
 
     /// Saves the priority operations metadata in storage.
@@ -177,6 +210,12 @@ impl StorageInteractor<'_> {
         storage_interact!(self.get_block_events_state_from_storage())
     }
 
+    /// Returns last watched ethereum block number from storage, or `None` if the restoring
+    /// was never started and there's no saved progress to resume
+    pub async fn find_last_watched_block_number(&mut self) -> Option<u64> {
+        storage_interact!(self.find_last_watched_block_number())
+    }
+
     /// Returns the current Rollup block, tree accounts map, unprocessed priority ops and the last fee acc from storage
     pub async fn get_tree_state(&mut self) -> StoredTreeState {
         storage_interact!(self.get_tree_state())
@@ -286,6 +325,10 @@ pub fn stored_ops_block_into_ops_block(op_block: StoredRollupOpsBlock) -> Rollup
             .previous_block_root_hash
             .map(|h| H256::from_slice(&h))
             .unwrap_or_default(),
+        root_hash: op_block
+            .root_hash
+            .map(|h| H256::from_slice(&h))
+            .unwrap_or_default(),
         contract_version: Some(
             ZkSyncContractVersion::try_from(op_block.contract_version as u32)
                 .expect("invalid contract version in the database"),
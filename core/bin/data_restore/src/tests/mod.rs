@@ -1,7 +1,7 @@
 pub(crate) mod utils;
 
 use std::cmp::max;
-use std::{collections::HashMap, future::Future};
+use std::{collections::HashMap, future::Future, time::Duration};
 
 use chrono::Utc;
 use futures::future;
@@ -18,12 +18,14 @@ use db_test_macro::test as db_test;
 use zksync_contracts::{governance_contract, zksync_contract};
 use zksync_crypto::Fr;
 use zksync_storage::{
-    chain::account::AccountSchema, data_restore::DataRestoreSchema, StorageProcessor,
+    chain::{account::AccountSchema, block::BlockSchema},
+    data_restore::DataRestoreSchema,
+    StorageProcessor,
 };
 use zksync_types::{
-    block::Block, AccountId, Address, BlockNumber, Deposit, DepositOp, ExecutedOperations,
-    ExecutedPriorityOp, ExecutedTx, Log, Nonce, PriorityOp, TokenId, Withdraw, WithdrawOp,
-    ZkSyncOp, H256,
+    block::Block, AccountId, AccountUpdate, Address, BlockNumber, Deposit, DepositOp,
+    ExecutedOperations, ExecutedPriorityOp, ExecutedTx, Log, Nonce, PriorityOp, TokenId, Withdraw,
+    WithdrawOp, ZkSyncOp, H256,
 };
 
 use crate::contract::ZkSyncDeployedContract;
@@ -196,6 +198,7 @@ pub(crate) struct Web3Transport {
     transactions: HashMap<String, Transaction>,
     logs: HashMap<String, Vec<Log>>,
     last_block: u32,
+    eth_block_number: u64,
 }
 
 impl Web3Transport {
@@ -204,6 +207,7 @@ impl Web3Transport {
             transactions: HashMap::default(),
             logs: HashMap::default(),
             last_block: 0,
+            eth_block_number: 0x80,
         }
     }
     fn push_transactions(&mut self, transactions: Vec<Transaction>) {
@@ -266,7 +270,7 @@ impl Transport for Web3Transport {
                     unreachable!()
                 };
                 match req.method.as_str() {
-                    "eth_blockNumber" => Ok(json!("0x80")),
+                    "eth_blockNumber" => Ok(json!(format!("{:#x}", self.eth_block_number))),
                     "eth_getLogs" => {
                         let filter = params.pop().unwrap();
                         Ok(json!(self.get_logs(filter)))
@@ -420,7 +424,11 @@ async fn test_run_state_update(mut storage: StorageProcessor<'_>) {
         ZkSyncDeployedContract::version4(eth, [1u8; 20].into()),
     );
 
-    driver.run_state_update(&mut interactor).await;
+    // Nothing to resume yet.
+    assert!(interactor.find_last_watched_block_number().await.is_none());
+    driver.run_state_update(&mut interactor).await.unwrap();
+    // The progress is saved and can be resumed.
+    assert!(interactor.find_last_watched_block_number().await.is_some());
 
     let db = match &mut interactor {
         StorageInteractor::Database(db) => db,
@@ -461,11 +469,381 @@ async fn test_run_state_update(mut storage: StorageProcessor<'_>) {
     );
 
     // Load state from db and check it
-    assert!(driver.load_state_from_storage(&mut interactor).await);
+    assert!(driver
+        .load_state_from_storage(&mut interactor)
+        .await
+        .unwrap());
     assert_eq!(driver.events_state.committed_events.len(), events.len());
     assert_eq!(*driver.tree_state.block_number, 2)
 }
 
+/// Returns the transport with the blocks 1 and 2 committed, the first one deposits 50
+/// and the second one withdraws 10. Only the provided blocks are verified.
+fn committed_blocks_transport(contract_addr: H160, verified_blocks: &[u32]) -> Web3Transport {
+    let mut transport = Web3Transport::new();
+    let contract = zksync_contract();
+
+    let block_verified_topic = contract
+        .event("BlockVerification")
+        .expect("Main contract abi error")
+        .signature();
+    transport.insert_logs(
+        format!("{:?}", block_verified_topic),
+        verified_blocks
+            .iter()
+            .map(|&block| {
+                create_log(
+                    contract_addr,
+                    block_verified_topic,
+                    vec![u32_to_32bytes(block).into()],
+                    Bytes(vec![]),
+                    block,
+                    u32_to_32bytes(block).into(),
+                )
+            })
+            .collect(),
+    );
+
+    let block_committed_topic = contract
+        .event("BlockCommit")
+        .expect("Main contract abi error")
+        .signature();
+    transport.insert_logs(
+        format!("{:?}", block_committed_topic),
+        (1..=2)
+            .map(|block| {
+                create_log(
+                    contract_addr,
+                    block_committed_topic,
+                    vec![u32_to_32bytes(block).into()],
+                    Bytes(vec![]),
+                    block,
+                    u32_to_32bytes(block).into(),
+                )
+            })
+            .collect(),
+    );
+
+    transport.push_transactions(vec![
+        create_transaction(
+            1,
+            create_block(
+                BlockNumber(1),
+                vec![create_deposit(Default::default(), Default::default(), 50)],
+            ),
+        ),
+        create_transaction(
+            2,
+            create_block(
+                BlockNumber(2),
+                vec![create_withdraw_operations(
+                    AccountId(0),
+                    Default::default(),
+                    Default::default(),
+                    10,
+                )],
+            ),
+        ),
+    ]);
+    transport
+}
+
+fn follow_committed_driver(
+    transport: Web3Transport,
+    contract_addr: H160,
+) -> DataRestoreDriver<Web3Transport> {
+    let eth = Eth::new(transport.clone());
+    let mut driver = DataRestoreDriver::new(
+        Web3::new(transport),
+        contract_addr,
+        Vec::new(),
+        3,
+        ETH_BLOCKS_STEP,
+        END_ETH_BLOCKS_OFFSET,
+        false,
+        None,
+        ZkSyncDeployedContract::version4(eth, [1u8; 20].into()),
+    );
+    driver.follow_committed = true;
+    driver.eth_poll_interval = Duration::from_millis(10);
+    driver
+}
+
+fn target_block_driver(
+    transport: Web3Transport,
+    contract_addr: H160,
+    target_block: u32,
+) -> DataRestoreDriver<Web3Transport> {
+    let eth = Eth::new(transport.clone());
+    let mut driver = DataRestoreDriver::new(
+        Web3::new(transport),
+        contract_addr,
+        Vec::new(),
+        3,
+        ETH_BLOCKS_STEP,
+        END_ETH_BLOCKS_OFFSET,
+        false,
+        None,
+        ZkSyncDeployedContract::version4(eth, [1u8; 20].into()),
+    );
+    driver.target_block = Some(BlockNumber(target_block));
+    driver.eth_poll_interval = Duration::from_millis(10);
+    driver
+}
+
+/// Runs the driver until it catches up with the contract. In the non-finite mode
+/// it keeps following the contract, so it's stopped by the timeout.
+async fn follow_contract(
+    driver: &mut DataRestoreDriver<Web3Transport>,
+    interactor: &mut StorageInteractor<'_>,
+) {
+    tokio::time::timeout(Duration::from_secs(1), driver.run_state_update(interactor))
+        .await
+        .expect_err("Driver must keep following the contract");
+}
+
+/// Returns the committed and verified balances of the account created by the deposit.
+async fn committed_and_verified_balances(
+    interactor: &mut StorageInteractor<'_>,
+) -> (BigUint, BigUint) {
+    let db = match interactor {
+        StorageInteractor::Database(db) => db,
+        _ => unreachable!(),
+    };
+    let state = AccountSchema(db.storage())
+        .account_state_by_address(Address::default())
+        .await
+        .unwrap();
+    (
+        state.committed.unwrap().1.get_balance(TokenId(0)),
+        state.verified.unwrap().1.get_balance(TokenId(0)),
+    )
+}
+
+async fn last_committed_and_executed_blocks(
+    interactor: &mut StorageInteractor<'_>,
+) -> (BlockNumber, BlockNumber) {
+    let db = match interactor {
+        StorageInteractor::Database(db) => db,
+        _ => unreachable!(),
+    };
+    let last_committed_block = BlockSchema(db.storage())
+        .get_last_committed_block()
+        .await
+        .unwrap();
+    let last_executed_block = BlockSchema(db.storage())
+        .get_last_verified_confirmed_block()
+        .await
+        .unwrap();
+    (last_committed_block, last_executed_block)
+}
+
+#[db_test]
+async fn test_follow_committed_blocks(mut storage: StorageProcessor<'_>) {
+    let contract_addr = H160::from([1u8; 20]);
+    let mut interactor = StorageInteractor::Database(DatabaseStorageInteractor::new(storage));
+
+    // Only the first block is verified, but both are applied.
+    let transport = committed_blocks_transport(contract_addr, &[1]);
+    let mut driver = follow_committed_driver(transport, contract_addr);
+    follow_contract(&mut driver, &mut interactor).await;
+
+    assert_eq!(driver.tree_state.block_number, BlockNumber(2));
+    assert_eq!(driver.last_executed_block, BlockNumber(1));
+    assert_eq!(
+        committed_and_verified_balances(&mut interactor).await,
+        (BigUint::from(40u32), BigUint::from(50u32))
+    );
+    assert_eq!(
+        last_committed_and_executed_blocks(&mut interactor).await,
+        (BlockNumber(2), BlockNumber(1))
+    );
+
+    // The restored block is executed once verified, including after the restart.
+    let mut transport = committed_blocks_transport(contract_addr, &[1, 2]);
+    transport.eth_block_number = 0x100;
+    let mut driver = follow_committed_driver(transport, contract_addr);
+    assert!(!driver
+        .load_state_from_storage(&mut interactor)
+        .await
+        .unwrap());
+    assert_eq!(driver.last_executed_block, BlockNumber(1));
+    follow_contract(&mut driver, &mut interactor).await;
+
+    assert_eq!(driver.tree_state.block_number, BlockNumber(2));
+    assert_eq!(driver.last_executed_block, BlockNumber(2));
+    assert_eq!(
+        committed_and_verified_balances(&mut interactor).await,
+        (BigUint::from(40u32), BigUint::from(40u32))
+    );
+    assert_eq!(
+        last_committed_and_executed_blocks(&mut interactor).await,
+        (BlockNumber(2), BlockNumber(2))
+    );
+}
+
+#[db_test]
+async fn test_revert_committed_blocks(mut storage: StorageProcessor<'_>) {
+    let contract_addr = H160::from([1u8; 20]);
+    let mut interactor = StorageInteractor::Database(DatabaseStorageInteractor::new(storage));
+
+    let transport = committed_blocks_transport(contract_addr, &[1]);
+    let mut driver = follow_committed_driver(transport, contract_addr);
+    follow_contract(&mut driver, &mut interactor).await;
+    assert_eq!(driver.tree_state.block_number, BlockNumber(2));
+
+    // The second block is reverted on the contract.
+    let mut transport = committed_blocks_transport(contract_addr, &[1]);
+    let reverted_topic = zksync_contract()
+        .event("BlocksRevert")
+        .expect("Main contract abi error")
+        .signature();
+    let mut data = u32_to_32bytes(1).to_vec();
+    data.extend(u32_to_32bytes(1));
+    transport.insert_logs(
+        format!("{:?}", reverted_topic),
+        vec![create_log(
+            contract_addr,
+            reverted_topic,
+            vec![],
+            Bytes(data),
+            3,
+            u32_to_32bytes(3).into(),
+        )],
+    );
+    transport.eth_block_number = 0x100;
+    let mut driver = follow_committed_driver(transport, contract_addr);
+    assert!(!driver
+        .load_state_from_storage(&mut interactor)
+        .await
+        .unwrap());
+    follow_contract(&mut driver, &mut interactor).await;
+
+    assert_eq!(driver.tree_state.block_number, BlockNumber(1));
+    assert_eq!(driver.last_executed_block, BlockNumber(1));
+    assert_eq!(
+        committed_and_verified_balances(&mut interactor).await,
+        (BigUint::from(50u32), BigUint::from(50u32))
+    );
+    assert_eq!(
+        last_committed_and_executed_blocks(&mut interactor).await,
+        (BlockNumber(1), BlockNumber(1))
+    );
+}
+
+#[db_test]
+async fn test_resume_target_block(mut storage: StorageProcessor<'_>) {
+    let contract_addr = H160::from([1u8; 20]);
+    let mut interactor = StorageInteractor::Database(DatabaseStorageInteractor::new(storage));
+    let transport = committed_blocks_transport(contract_addr, &[1, 2]);
+
+    // The restoring stops at the target block, even though the next one is verified.
+    let mut driver = target_block_driver(transport.clone(), contract_addr, 1);
+    tokio::time::timeout(
+        Duration::from_secs(1),
+        driver.run_state_update(&mut interactor),
+    )
+    .await
+    .expect("Driver must stop at the target block")
+    .unwrap();
+    assert_eq!(driver.tree_state.block_number, BlockNumber(1));
+    assert_eq!(
+        committed_and_verified_balances(&mut interactor).await,
+        (BigUint::from(50u32), BigUint::from(50u32))
+    );
+
+    // Resuming with the same target finishes right away.
+    let mut driver = target_block_driver(transport.clone(), contract_addr, 1);
+    assert!(driver
+        .load_state_from_storage(&mut interactor)
+        .await
+        .unwrap());
+    assert_eq!(driver.tree_state.block_number, BlockNumber(1));
+
+    // Resuming with the next target restores it.
+    let mut driver = target_block_driver(transport.clone(), contract_addr, 2);
+    assert!(!driver
+        .load_state_from_storage(&mut interactor)
+        .await
+        .unwrap());
+    tokio::time::timeout(
+        Duration::from_secs(1),
+        driver.run_state_update(&mut interactor),
+    )
+    .await
+    .expect("Driver must stop at the target block")
+    .unwrap();
+    assert_eq!(driver.tree_state.block_number, BlockNumber(2));
+
+    // The restored state can't be reverted to the previous target.
+    let mut driver = target_block_driver(transport, contract_addr, 1);
+    let err = driver
+        .load_state_from_storage(&mut interactor)
+        .await
+        .expect_err("Resuming past the target block must fail");
+    assert!(err
+        .to_string()
+        .contains("past the target block 1: the last restored block is 2"));
+}
+
+/// Checks that the in-memory storage reverts the committed blocks, but not the executed ones.
+#[tokio::test]
+async fn test_inmemory_storage_revert_blocks() {
+    let mut interactor = InMemoryStorageInteractor::new();
+    interactor.insert_new_account(AccountId(0), &Address::default());
+    let balance_update = |old_balance: u32, new_balance: u32| {
+        vec![(
+            AccountId(0),
+            AccountUpdate::UpdateBalance {
+                old_nonce: Nonce(0),
+                new_nonce: Nonce(0),
+                balance_update: (TokenId(0), old_balance.into(), new_balance.into()),
+            },
+        )]
+    };
+    let balance = |interactor: &InMemoryStorageInteractor| {
+        interactor
+            .get_account(&AccountId(0))
+            .unwrap()
+            .get_balance(TokenId(0))
+    };
+
+    interactor
+        .update_tree_state(
+            create_block(BlockNumber(1), Vec::new()),
+            balance_update(0, 50),
+            true,
+        )
+        .await;
+    for (block, (old_balance, new_balance)) in [(2, (50, 40)), (3, (40, 30))].iter() {
+        interactor
+            .update_tree_state(
+                create_block(BlockNumber(*block), Vec::new()),
+                balance_update(*old_balance, *new_balance),
+                false,
+            )
+            .await;
+    }
+    assert_eq!(balance(&interactor), BigUint::from(30u32));
+
+    // Once the second block is executed, only the third one can be reverted.
+    interactor
+        .execute_blocks(BlockNumber(2), BlockNumber(2))
+        .await;
+    assert!(interactor.revert_blocks(BlockNumber(1)).await.is_err());
+    assert_eq!(balance(&interactor), BigUint::from(30u32));
+
+    interactor.revert_blocks(BlockNumber(2)).await.unwrap();
+    assert_eq!(balance(&interactor), BigUint::from(40u32));
+    assert_eq!(
+        interactor.get_tree_state().await.last_block_number,
+        BlockNumber(2)
+    );
+    // Nothing is left to revert.
+    interactor.revert_blocks(BlockNumber(2)).await.unwrap();
+    assert_eq!(balance(&interactor), BigUint::from(40u32));
+}
+
 // TODO: Find a way to restore this test (ZKS-694)
 #[tokio::test]
 #[ignore]
@@ -650,7 +1028,7 @@ async fn test_with_inmemory_storage() {
         ZkSyncDeployedContract::version4(eth, [1u8; 20].into()),
     );
 
-    driver.run_state_update(&mut interactor).await;
+    driver.run_state_update(&mut interactor).await.unwrap();
 
     let inmemory = match &mut interactor {
         StorageInteractor::InMemory(db) => db,
@@ -684,7 +1062,10 @@ async fn test_with_inmemory_storage() {
     );
 
     // Load state from db and check it
-    assert!(driver.load_state_from_storage(&mut interactor).await);
+    assert!(driver
+        .load_state_from_storage(&mut interactor)
+        .await
+        .unwrap());
     assert_eq!(driver.events_state.committed_events.len(), events.len());
     assert_eq!(*driver.tree_state.block_number, 4)
 }
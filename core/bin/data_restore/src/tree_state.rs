@@ -128,6 +128,7 @@ impl TreeState {
         last_priority_op_serial_id: &mut SerialId,
     ) -> Result<(Block, AccountUpdates), anyhow::Error> {
         vlog::debug!("Updating tree for block {}", *ops_block.block_num);
+        self.verify_root_hash(ops_block.previous_block_root_hash)?;
         let operations = ops_block.ops.clone();
 
         let mut accounts_updated = Vec::new();
@@ -477,6 +478,9 @@ impl TreeState {
         );
 
         *self.block_number += 1;
+        // The root hash of the block is checked as well, so the last restored block is verified
+        // without waiting for the next one, which commits it as the previous block.
+        self.verify_root_hash(ops_block.root_hash)?;
 
         Ok((block, accounts_updated))
     }
@@ -587,6 +591,33 @@ impl TreeState {
         self.state.get_account_by_address(address)
     }
 
+    /// Checks that the restored state matches the state of the last restored block committed
+    /// on the contract, so the restored blocks are verified independently of the operator.
+    /// Returns the error reporting the divergent block otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `committed_root_hash` - Root hash of the last restored block committed on the contract,
+    ///   zero if it's unknown, e.g. for the contracts older than version 4
+    ///
+    pub fn verify_root_hash(&self, committed_root_hash: H256) -> Result<(), anyhow::Error> {
+        if committed_root_hash.is_zero() {
+            return Ok(());
+        }
+
+        let restored_root_hash = Block::encode_fr_for_eth(self.state.root_hash());
+        if restored_root_hash != committed_root_hash {
+            return Err(format_err!(
+                "Restored state diverges from the committed one on the block {}: \
+                 restored root hash is {:?}, committed root hash is {:?}",
+                *self.block_number,
+                restored_root_hash,
+                committed_root_hash
+            ));
+        }
+        Ok(())
+    }
+
     /// Returns ZkSync Account description by its id
     pub fn get_account(&self, account_id: AccountId) -> Option<Account> {
         self.state.get_account(account_id)
@@ -599,11 +630,12 @@ mod test {
     use crate::rollup_ops::RollupOpsBlock;
     use crate::tree_state::TreeState;
     use num::BigUint;
+    use zksync_types::block::Block;
     use zksync_types::tx::ChangePubKey;
     use zksync_types::{
         AccountId, BlockNumber, ChangePubKeyOp, Deposit, DepositOp, ForcedExit, ForcedExitOp,
         FullExit, FullExitOp, Nonce, PubKeyHash, TokenId, Transfer, TransferOp, TransferToNewOp,
-        Withdraw, WithdrawOp, ZkSyncOp,
+        Withdraw, WithdrawOp, ZkSyncOp, H256,
     };
 
     #[test]
@@ -627,6 +659,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: Default::default(),
             contract_version: None,
         };
 
@@ -654,6 +687,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: Default::default(),
             contract_version: None,
         };
 
@@ -682,6 +716,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: Default::default(),
             contract_version: None,
         };
 
@@ -710,6 +745,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: Default::default(),
             contract_version: None,
         };
 
@@ -739,6 +775,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: Default::default(),
             contract_version: None,
         };
 
@@ -765,6 +802,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: Default::default(),
             contract_version: None,
         };
 
@@ -791,6 +829,7 @@ mod test {
             fee_account: AccountId(1),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: Default::default(),
             contract_version: None,
         };
         // This transaction have to be deleted, do not uncomment. Delete it after removing the corresponding code        // let tx6 = Close {
@@ -999,6 +1038,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            root_hash: Default::default(),
             contract_version: None,
         };
 
@@ -1018,4 +1058,52 @@ mod test {
         assert_eq!(first_acc.address, [8u8; 20].into());
         assert_eq!(first_acc.get_balance(TokenId(1)), BigUint::from(0u32));
     }
+
+    #[test]
+    fn test_update_tree_with_divergent_root_hash() {
+        let make_block = |block_num, previous_block_root_hash| {
+            let op = ZkSyncOp::Deposit(Box::new(DepositOp {
+                priority_op: Deposit {
+                    from: [1u8; 20].into(),
+                    token: TokenId(1),
+                    amount: BigUint::from(1000u32),
+                    to: [7u8; 20].into(),
+                },
+                account_id: AccountId(0),
+            }));
+            RollupOpsBlock {
+                block_num: BlockNumber(block_num),
+                ops: get_rollup_ops_from_data(&op.public_data()).expect("cant get ops from data"),
+                fee_account: AccountId(0),
+                timestamp: None,
+                previous_block_root_hash,
+                root_hash: Default::default(),
+                contract_version: None,
+            }
+        };
+
+        let mut tree = TreeState::new();
+        let available_block_chunk_sizes = vec![10, 32, 72, 156, 322, 654];
+
+        // The committed root hash matches the restored one.
+        let block1 = make_block(1, Block::encode_fr_for_eth(tree.root_hash()));
+        tree.update_tree_states_from_ops_block(&block1, &available_block_chunk_sizes, &mut 0)
+            .expect("Cant update state from block 1");
+
+        // The committed root hash of the block 1 differs from the restored one.
+        let block2 = make_block(2, H256::repeat_byte(1));
+        let err = tree
+            .update_tree_states_from_ops_block(&block2, &available_block_chunk_sizes, &mut 0)
+            .expect_err("Divergent state is not reported");
+        assert!(err.to_string().contains("on the block 1"));
+        assert_eq!(*tree.block_number, 1);
+
+        // The committed root hash of the last block differs from the restored one.
+        let mut block2 = make_block(2, Block::encode_fr_for_eth(tree.root_hash()));
+        block2.root_hash = H256::repeat_byte(1);
+        let err = tree
+            .update_tree_states_from_ops_block(&block2, &available_block_chunk_sizes, &mut 0)
+            .expect_err("Divergent state of the last block is not reported");
+        assert!(err.to_string().contains("on the block 2"));
+    }
 }
@@ -11,20 +11,23 @@
 //! Before generating parameters universal setup keys should be downloaded using `zksync plonk-setup` command.
 
 mod recursive_keys;
+mod registry;
 mod sample_proofs;
 mod verifier_contract_generator;
 mod zksync_key;
 
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 use crate::recursive_keys::{
     count_gates_recursive_verification_keys, make_recursive_verification_keys,
 };
+use crate::registry::{make_key_registry, remove_key_registry};
 use crate::sample_proofs::make_sample_proofs;
 use crate::verifier_contract_generator::create_verifier_contract;
 use crate::zksync_key::{
     calculate_and_print_max_zksync_main_circuit_size, make_plonk_blocks_verify_keys,
-    make_plonk_exodus_verify_key,
+    make_plonk_exodus_verify_key, profile_zksync_main_circuit,
 };
 use zksync_config::configs::ChainConfig;
 
@@ -36,6 +39,21 @@ enum Command {
     Contract,
     /// Counts available sizes (chunks and aggregated proof size) for available setups
     CircuitSize,
+    /// Generate registry of the content hashes of the keys, it is also updated by the other commands
+    Registry {
+        /// Directory to copy the keys into under their content hashes, to be published for the provers
+        #[structopt(long)]
+        export: Option<PathBuf>,
+    },
+    /// Profiles the synthesis of zkSync main circuit and prints the report in the JSON format
+    Profile {
+        /// Number of chunks in the block, the smallest supported block size is used if not set
+        #[structopt(long)]
+        chunks: Option<usize>,
+        /// Depth of the nested circuit namespaces to report separately
+        #[structopt(long, default_value = "3")]
+        depth: usize,
+    },
 }
 
 #[derive(StructOpt)]
@@ -53,17 +71,35 @@ fn main() {
 
     match opt.command {
         Command::Keys => {
+            remove_key_registry();
             make_plonk_exodus_verify_key();
             make_plonk_blocks_verify_keys(config.clone());
             make_recursive_verification_keys(config.clone());
             make_sample_proofs(config).expect("Failed to generate sample proofs");
+            make_key_registry(None);
         }
         Command::Contract => {
             create_verifier_contract(config);
+            make_key_registry(None);
+        }
+        Command::Registry { export } => {
+            make_key_registry(export);
         }
         Command::CircuitSize => {
             calculate_and_print_max_zksync_main_circuit_size();
             count_gates_recursive_verification_keys();
         }
+        Command::Profile { chunks, depth } => {
+            let chunks = chunks.unwrap_or_else(|| {
+                config
+                    .circuit
+                    .supported_block_chunks_sizes
+                    .iter()
+                    .copied()
+                    .min()
+                    .expect("No supported block sizes")
+            });
+            profile_zksync_main_circuit(chunks, depth);
+        }
     }
 }
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+use zksync_prover_utils::fs_utils::get_keys_root_dir;
+use zksync_prover_utils::key_registry::KeyRegistry;
+
+/// Creates the registry of the generated keys. If the export directory is provided, the keys are
+/// copied there under their content hashes, so the directory can be uploaded to the network storage.
+pub(crate) fn make_key_registry(export_dir: Option<PathBuf>) {
+    let keys_dir = get_keys_root_dir();
+    let registry = KeyRegistry::compute(&keys_dir).expect("Failed to create key registry");
+    registry
+        .save(&keys_dir)
+        .expect("Failed to save key registry");
+    vlog::info!(
+        "Key registry with {} keys saved into: {}",
+        registry.keys.len(),
+        KeyRegistry::registry_path(&keys_dir).display()
+    );
+
+    if let Some(export_dir) = export_dir {
+        registry
+            .export(&keys_dir, &export_dir)
+            .expect("Failed to export keys");
+        vlog::info!("Keys exported into: {}", export_dir.display());
+    }
+}
+
+/// Removes the registry of the previously generated keys, since the keys are going to be replaced.
+pub(crate) fn remove_key_registry() {
+    let path = KeyRegistry::registry_path(&get_keys_root_dir());
+    if path.exists() {
+        std::fs::remove_file(&path).expect("Failed to remove key registry");
+    }
+}
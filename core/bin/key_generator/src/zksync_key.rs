@@ -8,6 +8,7 @@ use zksync_circuit::exit_circuit::ZkSyncExitCircuit;
 use zksync_circuit::operation::{
     Operation, OperationArguments, OperationBranch, OperationBranchWitness, SignatureData,
 };
+use zksync_circuit::profiler::profile_circuit;
 use zksync_config::configs::ChainConfig;
 use zksync_crypto::bellman::plonk::{make_verification_key, setup, transpile_with_gates_count};
 use zksync_crypto::bellman::Circuit;
@@ -255,3 +256,15 @@ pub fn calculate_and_print_max_zksync_main_circuit_size() {
         }
     }
 }
+
+/// Profiles the synthesis of the zkSync circuit with the given number of chunks and prints
+/// the report in the JSON format.
+pub fn profile_zksync_main_circuit(chunks: usize, depth: usize) {
+    vlog::info!("Profiling zkSync circuit with {} chunks", chunks);
+    let profile =
+        profile_circuit(zksync_circuit(chunks), depth).expect("failed to synthesize circuit");
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&profile).expect("failed to serialize circuit profile")
+    );
+}
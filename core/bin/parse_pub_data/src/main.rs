@@ -1,22 +1,18 @@
-use zksync_types::ZkSyncOp;
+use zksync_types::{operations::PubdataVersion, ZkSyncOp};
 
 fn main() {
-    let hex_data = std::env::args()
-        .nth(1)
-        .expect("cli arg should be hex of pubdata");
+    let mut args = std::env::args().skip(1);
+    let hex_data = args.next().expect("cli arg should be hex of pubdata");
+    // Blocks committed prior to the v6 upgrade use the legacy encoding.
+    let version = match args.next().as_deref() {
+        None => PubdataVersion::CURRENT,
+        Some("--legacy") => PubdataVersion::Legacy,
+        Some(arg) => panic!("unexpected cli arg {}, only --legacy is supported", arg),
+    };
     let data = hex::decode(&hex_data).expect("failed to decode hex");
 
-    let mut unparsed_data = data.as_slice();
-    while !unparsed_data.is_empty() {
-        let op_type = unparsed_data[0];
-        let op_data_len = ZkSyncOp::public_data_length(op_type).expect("wrong op type");
-        assert!(
-            data.len() > op_data_len,
-            "not enough bytes in the pubdata for current op"
-        );
-        let (current_op, unparsed) = unparsed_data.split_at(op_data_len);
-        let op = ZkSyncOp::from_public_data(current_op).expect("failed to parse pubdata");
+    let ops = ZkSyncOp::ops_from_public_data(&data, version).expect("failed to parse pubdata");
+    for op in ops {
         println!("{:#?}", op);
-        unparsed_data = unparsed;
     }
 }
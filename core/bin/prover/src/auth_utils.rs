@@ -1,28 +1,14 @@
 use jsonwebtoken::{encode as encode_token, EncodingKey, Header};
 use serde::{Deserialize, Serialize};
 use std::time;
-
-#[derive(Debug, Serialize, Deserialize)]
-struct PayloadAuthToken {
-    /// Subject (whom auth token refers to).
-    sub: String,
-    /// Expiration time (as UTC timestamp).
-    exp: usize,
-}
-
-impl PayloadAuthToken {
-    pub fn new(exp: usize) -> Self {
-        Self {
-            sub: "Authorization".to_string(),
-            exp,
-        }
-    }
-}
+use zksync_utils::PayloadAuthToken;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AuthTokenGenerator {
     secret: String,
     period_availability: time::Duration,
+    /// Token issued to the prover machine, it's sent instead of the tokens derived from the secret.
+    prover_token: Option<String>,
 }
 
 impl AuthTokenGenerator {
@@ -30,11 +16,20 @@ impl AuthTokenGenerator {
         Self {
             secret,
             period_availability,
+            prover_token: None,
         }
     }
 
-    /// Encode JsonWebToken with shared secret
+    pub fn with_prover_token(mut self, prover_token: Option<String>) -> Self {
+        self.prover_token = prover_token;
+        self
+    }
+
+    /// Encode JsonWebToken with shared secret, or returns the token of the prover if it's set.
     pub fn encode(&self) -> jsonwebtoken::errors::Result<String> {
+        if let Some(prover_token) = &self.prover_token {
+            return Ok(prover_token.clone());
+        }
         // Time (Unix Timestamp) until which the token will be valid
         let exp = time::UNIX_EPOCH.elapsed().unwrap() + self.period_availability;
 
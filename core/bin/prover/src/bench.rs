@@ -0,0 +1,569 @@
+//! Benchmark of the block proving on the standardized workloads, used to compare the prover
+//! performance on the different hardware and before and after the code changes.
+//!
+//! Blocks are synthesized from the deterministic accounts and transactions without the database
+//! or Ethereum, so the reports are comparable between the runs as long as the workload and
+//! the block sizes are the same. Every stage of the proving (block synthesis, witness generation,
+//! setup preparation and proof generation) is measured separately.
+
+// Built-in deps
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+// External deps
+use chrono::{DateTime, Utc};
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use zksync_crypto::rand::{Rng, SeedableRng, XorShiftRng};
+// Workspace deps
+use zksync_circuit::serialization::ProverData;
+use zksync_circuit::witness::utils::build_block_witness;
+use zksync_crypto::circuit::{account::CircuitAccount, CircuitAccountTree};
+use zksync_crypto::params::account_tree_depth;
+use zksync_crypto::{priv_key_from_fs, PrivateKey};
+use zksync_prover_utils::{PlonkVerificationKey, SetupForStepByStepProver};
+use zksync_state::state::{CollectedFee, OpSuccess, ZkSyncState};
+use zksync_types::{
+    block::Block, tx::TimeRange, Account, AccountId, AccountMap, Address, BlockNumber, Deposit,
+    DepositOp, ExecutedOperations, ExecutedPriorityOp, ExecutedTx, Nonce, PriorityOp, PubKeyHash,
+    SignedZkSyncTx, TokenId, Transfer, TransferOp, TransferToNewOp, Withdraw, WithdrawOp,
+    ZkSyncPriorityOp, ZkSyncTx, H256, U256,
+};
+// Local deps
+use crate::resources::{available_cpus, peak_memory, reset_peak_memory};
+
+const FEE_ACCOUNT_ID: AccountId = AccountId(0);
+const BLOCK_TIMESTAMP: u64 = 0x12345678;
+const TOKEN: TokenId = TokenId(0);
+/// Amount of the accounts the transactions of the block are sent from.
+const ACCOUNTS: u32 = 32;
+const BENCH_SEED: [u32; 4] = [0x5eed, 0xbe, 0x4c, 0x4b];
+
+/// Set of the operations the benchmarked blocks are filled with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Workload {
+    /// Deposits to the existing accounts.
+    Deposits,
+    /// Transfers between the existing accounts.
+    Transfers,
+    /// Deposits, transfers to the existing and to the new accounts and withdrawals,
+    /// the way the blocks of the network usually look.
+    Mixed,
+}
+
+impl Workload {
+    fn operation(self, index: usize) -> OperationKind {
+        const MIXED: [OperationKind; 5] = [
+            OperationKind::Deposit,
+            OperationKind::Transfer,
+            OperationKind::Transfer,
+            OperationKind::TransferToNew,
+            OperationKind::Withdraw,
+        ];
+        match self {
+            Self::Deposits => OperationKind::Deposit,
+            Self::Transfers => OperationKind::Transfer,
+            Self::Mixed => MIXED[index % MIXED.len()],
+        }
+    }
+}
+
+impl FromStr for Workload {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "deposits" => Ok(Self::Deposits),
+            "transfers" => Ok(Self::Transfers),
+            "mixed" => Ok(Self::Mixed),
+            _ => anyhow::bail!(
+                "Unknown workload '{}', expected one of: deposits, transfers, mixed",
+                s
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Workload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Deposits => "deposits",
+            Self::Transfers => "transfers",
+            Self::Mixed => "mixed",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum OperationKind {
+    Deposit,
+    Transfer,
+    TransferToNew,
+    Withdraw,
+}
+
+impl OperationKind {
+    fn chunks(self) -> usize {
+        match self {
+            Self::Deposit => DepositOp::CHUNKS,
+            Self::Transfer => TransferOp::CHUNKS,
+            Self::TransferToNew => TransferToNewOp::CHUNKS,
+            Self::Withdraw => WithdrawOp::CHUNKS,
+        }
+    }
+}
+
+struct BenchAccount {
+    id: AccountId,
+    address: Address,
+    private_key: PrivateKey,
+    nonce: Nonce,
+}
+
+/// Block prepared for the benchmark along with the account tree the block is applied to.
+pub struct SynthesizedBlock {
+    pub block: Block,
+    pub account_tree: CircuitAccountTree,
+    /// Amount of the operations in the block, not counting the noops.
+    pub operations: usize,
+}
+
+struct BlockSynthesizer {
+    rng: XorShiftRng,
+    state: ZkSyncState,
+    accounts: Vec<BenchAccount>,
+    operations: Vec<ExecutedOperations>,
+    fees: Vec<CollectedFee>,
+    priority_ops: u64,
+}
+
+impl BlockSynthesizer {
+    fn new() -> Self {
+        let mut rng = XorShiftRng::from_seed(BENCH_SEED);
+        let mut account_map = AccountMap::default();
+        account_map.insert(
+            FEE_ACCOUNT_ID,
+            Account::default_with_address(&random_address(&mut rng)),
+        );
+
+        let accounts: Vec<_> = (1..=ACCOUNTS)
+            .map(|id| BenchAccount {
+                id: AccountId(id),
+                address: random_address(&mut rng),
+                private_key: priv_key_from_fs(rng.gen()),
+                nonce: Nonce(0),
+            })
+            .collect();
+        for account in &accounts {
+            let mut state_account = Account::default_with_address(&account.address);
+            state_account.pub_key_hash = PubKeyHash::from_privkey(&account.private_key);
+            state_account.add_balance(TOKEN, &BigUint::from(10u64.pow(18)));
+            account_map.insert(account.id, state_account);
+        }
+
+        Self {
+            rng,
+            state: ZkSyncState::from_acc_map(account_map),
+            accounts,
+            operations: Vec::new(),
+            fees: Vec::new(),
+            priority_ops: 0,
+        }
+    }
+
+    fn account_tree(&self) -> CircuitAccountTree {
+        let mut tree = CircuitAccountTree::new(account_tree_depth());
+        for (id, account) in self.state.get_accounts() {
+            tree.insert(id, CircuitAccount::from(account));
+        }
+        tree
+    }
+
+    fn add_operation(&mut self, kind: OperationKind) -> anyhow::Result<()> {
+        let index = self.operations.len();
+        let sender = index % self.accounts.len();
+        let receiver = self.accounts[(index + 1) % self.accounts.len()].address;
+        let amount = BigUint::from(1000u32);
+        let fee = BigUint::from(10u32);
+
+        let tx = match kind {
+            OperationKind::Deposit => return self.add_deposit(receiver, amount),
+            OperationKind::Transfer | OperationKind::TransferToNew => {
+                let to = match kind {
+                    OperationKind::Transfer => receiver,
+                    _ => random_address(&mut self.rng),
+                };
+                let account = &self.accounts[sender];
+                ZkSyncTx::Transfer(Box::new(Transfer::new_signed(
+                    account.id,
+                    account.address,
+                    to,
+                    TOKEN,
+                    amount,
+                    fee,
+                    account.nonce,
+                    TimeRange::new(0, u64::MAX),
+                    &account.private_key,
+                )?))
+            }
+            OperationKind::Withdraw => {
+                let account = &self.accounts[sender];
+                ZkSyncTx::Withdraw(Box::new(Withdraw::new_signed(
+                    account.id,
+                    account.address,
+                    account.address,
+                    TOKEN,
+                    amount,
+                    fee,
+                    account.nonce,
+                    TimeRange::new(0, u64::MAX),
+                    &account.private_key,
+                )?))
+            }
+        };
+
+        let OpSuccess {
+            fee, executed_op, ..
+        } = self.state.execute_tx(tx.clone(), BLOCK_TIMESTAMP)?;
+        *self.accounts[sender].nonce += 1;
+        self.fees.extend(fee);
+        self.operations
+            .push(ExecutedOperations::Tx(Box::new(ExecutedTx {
+                signed_tx: SignedZkSyncTx::from(tx),
+                success: true,
+                op: Some(executed_op),
+                fail_reason: None,
+                block_index: Some(index as u32),
+                created_at: Utc::now(),
+                batch_id: None,
+            })));
+        Ok(())
+    }
+
+    fn add_deposit(&mut self, to: Address, amount: BigUint) -> anyhow::Result<()> {
+        let priority_op = PriorityOp {
+            serial_id: self.priority_ops,
+            data: ZkSyncPriorityOp::Deposit(Deposit {
+                from: to,
+                token: TOKEN,
+                amount,
+                to,
+            }),
+            deadline_block: 0,
+            eth_hash: H256::zero(),
+            eth_block: 0,
+            eth_block_index: None,
+        };
+        let block_index = self.operations.len() as u32;
+        let OpSuccess { executed_op, .. } =
+            self.state.execute_priority_op(priority_op.data.clone());
+        self.priority_ops += 1;
+        self.operations
+            .push(ExecutedOperations::PriorityOp(Box::new(
+                ExecutedPriorityOp {
+                    priority_op,
+                    op: executed_op,
+                    block_index,
+                    created_at: Utc::now(),
+                },
+            )));
+        Ok(())
+    }
+}
+
+fn random_address(rng: &mut XorShiftRng) -> Address {
+    Address::from(rng.gen::<[u8; 20]>())
+}
+
+/// Synthesizes the block of the given size filled with the operations of the workload.
+pub fn synthesize_block(workload: Workload, block_size: usize) -> anyhow::Result<SynthesizedBlock> {
+    let mut synthesizer = BlockSynthesizer::new();
+    let account_tree = synthesizer.account_tree();
+    let previous_root_hash = synthesizer.state.root_hash();
+
+    let mut chunks = 0;
+    loop {
+        let kind = workload.operation(synthesizer.operations.len());
+        if chunks + kind.chunks() > block_size {
+            break;
+        }
+        synthesizer.add_operation(kind)?;
+        chunks += kind.chunks();
+    }
+    anyhow::ensure!(
+        !synthesizer.operations.is_empty(),
+        "Block of size {} can't fit any operation of the workload '{}'",
+        block_size,
+        workload
+    );
+
+    let fees = std::mem::take(&mut synthesizer.fees);
+    synthesizer.state.collect_fee(&fees, FEE_ACCOUNT_ID);
+    let operations = synthesizer.operations.len();
+    let block = Block::new_from_available_block_sizes(
+        BlockNumber(1),
+        synthesizer.state.root_hash(),
+        FEE_ACCOUNT_ID,
+        synthesizer.operations,
+        (0, synthesizer.priority_ops),
+        &[block_size],
+        U256::zero(),
+        U256::zero(),
+        Block::encode_fr_for_eth(previous_root_hash),
+        BLOCK_TIMESTAMP,
+    );
+
+    Ok(SynthesizedBlock {
+        block,
+        account_tree,
+        operations,
+    })
+}
+
+/// Durations and peak memory usage of the proving stage over all the iterations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageReport {
+    pub stage: String,
+    pub mean_ms: u64,
+    pub min_ms: u64,
+    pub max_ms: u64,
+    /// Peak resident set size of the process during the stage in megabytes,
+    /// `None` if it can't be measured on the machine.
+    pub peak_memory_mb: Option<u64>,
+}
+
+/// Report of the proving of the block of the single size.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockReport {
+    pub block_size: usize,
+    pub operations: usize,
+    pub stages: Vec<StageReport>,
+    /// Mean duration of the whole proving routine.
+    pub total_ms: u64,
+}
+
+/// Report of the benchmark, stored in the JSON format so the runs can be compared.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchReport {
+    pub created_at: DateTime<Utc>,
+    pub workload: Workload,
+    pub iterations: usize,
+    /// Amount of the CPUs available to the benchmark.
+    pub cpus: Option<usize>,
+    pub blocks: Vec<BlockReport>,
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cpus = self
+            .cpus
+            .map(|cpus| cpus.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        writeln!(
+            f,
+            "Workload: {}, iterations: {}, CPUs: {}",
+            self.workload, self.iterations, cpus
+        )?;
+        writeln!(
+            f,
+            "{:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>12}",
+            "block size", "operations", "stage", "mean, ms", "min, ms", "max, ms", "peak mem, MB"
+        )?;
+        for block in &self.blocks {
+            for stage in &block.stages {
+                let peak_memory = stage
+                    .peak_memory_mb
+                    .map(|memory| memory.to_string())
+                    .unwrap_or_else(|| "-".to_string());
+                writeln!(
+                    f,
+                    "{:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>12}",
+                    block.block_size,
+                    block.operations,
+                    stage.stage,
+                    stage.mean_ms,
+                    stage.min_ms,
+                    stage.max_ms,
+                    peak_memory
+                )?;
+            }
+            writeln!(
+                f,
+                "{:>10} {:>10} {:>10} {:>10}",
+                block.block_size, block.operations, "total", block.total_ms
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Options of the benchmark run.
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    pub workload: Workload,
+    pub block_sizes: Vec<usize>,
+    pub iterations: usize,
+    pub download_setup: bool,
+    /// Only synthesizes the blocks and generates the witnesses, so the benchmark can run
+    /// on the machines without the setup and the keys.
+    pub skip_proving: bool,
+}
+
+/// Measurements of the single stage collected over the iterations.
+#[derive(Debug, Default)]
+struct StageMeasurements {
+    stage: &'static str,
+    durations: Vec<Duration>,
+    peak_memory_mb: Option<u64>,
+}
+
+impl StageMeasurements {
+    fn measure<T>(&mut self, f: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<T> {
+        // The peak is not reset if the kernel doesn't support it, so only the overall peak
+        // of the process is reported then.
+        if let Err(e) = reset_peak_memory() {
+            vlog::debug!("Failed to reset the peak memory usage: {}", e);
+        }
+        let start = Instant::now();
+        let result = f()?;
+        self.durations.push(start.elapsed());
+        if let Ok(peak_memory) = peak_memory() {
+            self.peak_memory_mb = self.peak_memory_mb.max(Some(peak_memory));
+        }
+        Ok(result)
+    }
+
+    fn report(&self) -> StageReport {
+        let millis: Vec<u64> = self
+            .durations
+            .iter()
+            .map(|duration| duration.as_millis() as u64)
+            .collect();
+        StageReport {
+            stage: self.stage.to_string(),
+            mean_ms: millis.iter().sum::<u64>() / millis.len().max(1) as u64,
+            min_ms: millis.iter().copied().min().unwrap_or_default(),
+            max_ms: millis.iter().copied().max().unwrap_or_default(),
+            peak_memory_mb: self.peak_memory_mb,
+        }
+    }
+}
+
+/// Runs the whole proving routine for the block of the given size and reports every stage.
+pub fn bench_block_size(options: &BenchOptions, block_size: usize) -> anyhow::Result<BlockReport> {
+    let mut synthesis = StageMeasurements {
+        stage: "synthesis",
+        ..Default::default()
+    };
+    let mut witness = StageMeasurements {
+        stage: "witness",
+        ..Default::default()
+    };
+    let mut setup = StageMeasurements {
+        stage: "setup",
+        ..Default::default()
+    };
+    let mut proof = StageMeasurements {
+        stage: "proof",
+        ..Default::default()
+    };
+
+    let mut operations = 0;
+    for iteration in 0..options.iterations {
+        vlog::info!(
+            "Benchmarking the block of size {}, iteration {}",
+            block_size,
+            iteration + 1
+        );
+        let mut block = synthesis.measure(|| synthesize_block(options.workload, block_size))?;
+        operations = block.operations;
+
+        let circuit = witness.measure(|| {
+            let witness = build_block_witness(&mut block.account_tree, &block.block)?;
+            Ok(ProverData::from(witness).into_circuit())
+        })?;
+        if options.skip_proving {
+            continue;
+        }
+
+        let prepared_setup = setup.measure(|| {
+            SetupForStepByStepProver::prepare_setup_for_step_by_step_prover(
+                circuit.clone(),
+                options.download_setup,
+            )
+        })?;
+        proof.measure(|| {
+            let vk = PlonkVerificationKey::read_verification_key_for_main_circuit(block_size)?;
+            prepared_setup.gen_step_by_step_proof_using_prepared_setup(circuit, &vk)
+        })?;
+    }
+
+    let mut stages = vec![synthesis.report(), witness.report()];
+    if !options.skip_proving {
+        stages.push(setup.report());
+        stages.push(proof.report());
+    }
+    let total_ms = stages.iter().map(|stage| stage.mean_ms).sum();
+    Ok(BlockReport {
+        block_size,
+        operations,
+        stages,
+        total_ms,
+    })
+}
+
+/// Runs the benchmark for all the block sizes of the options.
+pub fn run_bench(options: &BenchOptions) -> anyhow::Result<BenchReport> {
+    anyhow::ensure!(options.iterations > 0, "At least one iteration is required");
+    let blocks = options
+        .block_sizes
+        .iter()
+        .map(|&block_size| bench_block_size(options, block_size))
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(BenchReport {
+        created_at: Utc::now(),
+        workload: options.workload,
+        iterations: options.iterations,
+        cpus: available_cpus().ok().map(|cpus| cpus.len()),
+        blocks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that the synthesized blocks are filled with the operations and are accepted
+    /// by the witness generator.
+    #[test]
+    fn synthesized_blocks() {
+        for workload in [Workload::Deposits, Workload::Transfers, Workload::Mixed] {
+            let mut synthesized = synthesize_block(workload, 10).unwrap();
+            assert_eq!(synthesized.block.block_chunks_size, 10);
+            assert!(synthesized.operations > 0);
+            build_block_witness(&mut synthesized.account_tree, &synthesized.block).unwrap();
+        }
+    }
+
+    #[test]
+    fn synthesis_is_deterministic() {
+        let first = synthesize_block(Workload::Mixed, 10).unwrap();
+        let second = synthesize_block(Workload::Mixed, 10).unwrap();
+        assert_eq!(first.block.new_root_hash, second.block.new_root_hash);
+        assert_eq!(first.block.block_commitment, second.block.block_commitment);
+    }
+
+    #[test]
+    fn parse_workload() {
+        for workload in [Workload::Deposits, Workload::Transfers, Workload::Mixed] {
+            assert_eq!(workload.to_string().parse::<Workload>().unwrap(), workload);
+        }
+        "swaps".parse::<Workload>().unwrap_err();
+    }
+}
@@ -0,0 +1,59 @@
+//! Benchmark of the prover on the standardized workloads.
+//!
+//! Synthesizes the blocks of every supported size, generates the witnesses and the proofs for them
+//! and prints the time and the peak memory usage of every stage. The report can be stored in the
+//! JSON format to compare the runs on the different machines or before and after the code changes.
+
+use std::path::PathBuf;
+use structopt::StructOpt;
+use zksync_config::ChainConfig;
+use zksync_prover::bench::{run_bench, BenchOptions, Workload};
+use zksync_utils::parse_env;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zkSync prover benchmark", author = "Matter Labs")]
+#[structopt(about = "Benchmarks the block proving on the standardized workloads")]
+struct Opt {
+    /// Operations the blocks are filled with: deposits, transfers or mixed.
+    #[structopt(long, default_value = "mixed")]
+    workload: Workload,
+    /// Block sizes to benchmark, all the supported block sizes by default.
+    #[structopt(long, use_delimiter = true)]
+    block_sizes: Vec<usize>,
+    /// Amount of the proving runs for every block size.
+    #[structopt(long, default_value = "1")]
+    iterations: usize,
+    /// Only generates the witnesses, so the setup and the keys are not required.
+    #[structopt(long)]
+    skip_proving: bool,
+    /// File to store the JSON report to.
+    #[structopt(long)]
+    output: Option<PathBuf>,
+}
+
+fn main() -> anyhow::Result<()> {
+    let _vlog_guard = vlog::init();
+    let opt = Opt::from_args();
+
+    let block_sizes = if opt.block_sizes.is_empty() {
+        ChainConfig::from_env().circuit.supported_block_chunks_sizes
+    } else {
+        opt.block_sizes
+    };
+    let options = BenchOptions {
+        workload: opt.workload,
+        block_sizes,
+        iterations: opt.iterations,
+        download_setup: parse_env("MISC_PROVER_DOWNLOAD_SETUP"),
+        skip_proving: opt.skip_proving,
+    };
+
+    let report = run_bench(&options)?;
+    print!("{}", report);
+    if let Some(output) = opt.output {
+        std::fs::write(&output, serde_json::to_string_pretty(&report)?)?;
+        println!("Report is stored to {}", output.display());
+    }
+
+    Ok(())
+}
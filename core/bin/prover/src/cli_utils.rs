@@ -6,15 +6,23 @@ use structopt::StructOpt;
 use zksync_config::configs::ProverConfig as EnvProverConfig;
 use zksync_utils::{get_env, parse_env};
 // Local deps
-use crate::{client, prover_work_cycle, ProverConfig, ProverImpl, ShutdownRequest};
+use crate::{prover_work_cycle, stream_client, ProverConfig, ProverImpl, ShutdownRequest};
 use zksync_config::configs::api::PrometheusConfig;
 use zksync_prometheus_exporter::run_prometheus_exporter;
 
-fn api_client_from_env() -> client::ApiClient {
-    let server_api_url = parse_env("API_PROVER_URL");
+fn api_client_from_env(compression_level: Option<i32>) -> stream_client::StreamClient {
+    let server_stream_url = get_env("API_PROVER_STREAM_URL");
     let request_timout = Duration::from_secs(parse_env::<u64>("PROVER_PROVER_REQUEST_TIMEOUT"));
-    let secret = get_env("API_PROVER_SECRET_AUTH");
-    client::ApiClient::new(&server_api_url, request_timout, &secret)
+    // The machines with their own tokens don't need to know the shared secret.
+    let prover_token = std::env::var("PROVER_AUTH_TOKEN").ok();
+    let secret = if prover_token.is_some() {
+        String::new()
+    } else {
+        get_env("API_PROVER_SECRET_AUTH")
+    };
+    stream_client::StreamClient::new(&server_stream_url, request_timout, &secret)
+        .with_prover_token(prover_token)
+        .with_compression_level(compression_level)
 }
 
 #[derive(StructOpt)]
@@ -39,7 +47,7 @@ where
     // used env
     let prover_options = EnvProverConfig::from_env();
     let prover_config = <PROVER as ProverImpl>::Config::from_env();
-    let api_client = api_client_from_env();
+    let api_client = api_client_from_env(prover_options.prover.compression_level);
     let prover = PROVER::create_from_config(prover_config);
 
     let _vlog_guard = vlog::init();
@@ -55,7 +63,7 @@ where
         let shutdown_request = shutdown_request.clone();
         ctrlc::set_handler(move || {
             vlog::info!(
-                "Termination signal received. It will be handled after the currently working round"
+                "Termination signal received. The prover is draining: the current job is finished, no new jobs are taken"
             );
 
             if shutdown_request.get() {
@@ -10,7 +10,8 @@ use vlog::*;
 // Workspace deps
 use crate::auth_utils::AuthTokenGenerator;
 use zksync_prover_utils::api::{
-    ProverInputRequest, ProverInputResponse, ProverOutputRequest, ProverStopped, WorkingOn,
+    JobFailed, ProverInputRequest, ProverInputResponse, ProverOutputRequest, ProverStopped,
+    WorkingOn,
 };
 
 #[derive(Debug, Clone)]
@@ -18,6 +19,7 @@ pub struct ApiClient {
     get_job_url: Url,
     working_on_url: Url,
     publish_url: Url,
+    job_failed_url: Url,
     stopped_url: Url,
     // Client keeps connection pool inside, so it is recommended to reuse it (see docstring for reqwest::Client).
     http_client: reqwest::Client,
@@ -40,12 +42,19 @@ impl ApiClient {
             get_job_url: base_url.join("/get_job").unwrap(),
             working_on_url: base_url.join("/working_on").unwrap(),
             publish_url: base_url.join("/publish").unwrap(),
+            job_failed_url: base_url.join("/job_failed").unwrap(),
             stopped_url: base_url.join("/stopped").unwrap(),
             http_client,
             auth_token_generator,
         }
     }
 
+    /// Authenticates the prover with the token issued to it instead of the shared secret.
+    pub fn with_prover_token(mut self, prover_token: Option<String>) -> Self {
+        self.auth_token_generator = self.auth_token_generator.with_prover_token(prover_token);
+        self
+    }
+
     /// Repeats the function execution on the exponential backoff principle.
     async fn with_retries<I, E, Fn, Fut>(&self, operation: Fn) -> anyhow::Result<I>
     where
@@ -172,6 +181,36 @@ impl crate::ApiClient for ApiClient {
         self.with_retries(operation).await
     }
 
+    async fn job_failed(
+        &self,
+        job_id: i32,
+        prover_name: &str,
+        error: String,
+    ) -> anyhow::Result<()> {
+        let operation = || async {
+            let response = self
+                .http_client
+                .post(self.job_failed_url.clone())
+                .bearer_auth(&self.get_encoded_token()?)
+                .json(&JobFailed {
+                    prover_name: prover_name.to_string(),
+                    job_id,
+                    error: error.clone(),
+                })
+                .send()
+                .await
+                .map_err(|e| Transient(format_err!("failed to send job_failed request: {}", e)))?;
+
+            if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+                return Err(Permanent(format_err!("authorization error")));
+            }
+
+            Ok(())
+        };
+
+        self.with_retries(operation).await
+    }
+
     async fn prover_stopped(&self, prover_name: String) -> anyhow::Result<()> {
         let operation = || async {
             let response = self
@@ -1,13 +1,27 @@
+//! Prover which doesn't prove anything, but returns the precomputed proofs instead.
+//!
+//! It is used in the development environments, and can simulate the behavior of the real
+//! provers: proving takes time according to the configured latency distribution, and proving
+//! fails or hangs with the configured probabilities.
+
+use std::time::Duration;
+
 use crate::{ProverConfig, ProverImpl};
 use anyhow::Error;
+use zksync_config::configs::prover::{DummyProver as DummyProverOptions, LatencyDistribution};
 use zksync_config::ZkSyncConfig;
 use zksync_crypto::proof::PrecomputedSampleProofs;
+use zksync_crypto::rand::{
+    distributions::{Exp, IndependentSample, Range},
+    thread_rng, Rng,
+};
 use zksync_prover_utils::api::{JobRequestData, JobResultData};
 use zksync_prover_utils::fs_utils::load_precomputed_proofs;
 
 #[derive(Debug)]
 pub struct DummyProverConfig {
     pub block_sizes: Vec<usize>,
+    pub simulation: DummyProverOptions,
 }
 
 impl ProverConfig for DummyProverConfig {
@@ -16,6 +30,7 @@ impl ProverConfig for DummyProverConfig {
 
         Self {
             block_sizes: env_config.chain.state_keeper.block_chunk_sizes,
+            simulation: env_config.prover.dummy_prover,
         }
     }
 }
@@ -23,19 +38,67 @@ impl ProverConfig for DummyProverConfig {
 #[derive(Debug)]
 pub struct DummyProver {
     precomputed_proofs: PrecomputedSampleProofs,
+    simulation: DummyProverOptions,
+}
+
+impl DummyProver {
+    /// Samples the time it takes to create a proof.
+    fn sample_latency(&self) -> Duration {
+        let options = &self.simulation;
+        let min_latency = options.min_latency();
+        let max_latency = options.max_latency().max(min_latency);
+        let mut rng = thread_rng();
+
+        let latency = match options.latency_distribution {
+            LatencyDistribution::Fixed => min_latency,
+            LatencyDistribution::Uniform if max_latency > min_latency => {
+                let range = Range::new(min_latency.as_secs_f64(), max_latency.as_secs_f64());
+                Duration::from_secs_f64(range.ind_sample(&mut rng))
+            }
+            LatencyDistribution::Uniform => min_latency,
+            LatencyDistribution::Exponential => {
+                let mean_excess = options.mean_latency().saturating_sub(min_latency);
+                if mean_excess.as_secs_f64() > 0.0 {
+                    let excess = Exp::new(1.0 / mean_excess.as_secs_f64()).ind_sample(&mut rng);
+                    min_latency + Duration::from_secs_f64(excess)
+                } else {
+                    min_latency
+                }
+            }
+        };
+        latency.min(max_latency)
+    }
+
+    /// Waits for the simulated proving time, and fails with the configured probabilities.
+    fn simulate_proving(&self) -> Result<(), Error> {
+        let options = &self.simulation;
+        if thread_rng().gen::<f64>() < options.timeout_probability {
+            vlog::warn!("Simulating proving hang for {:?}", options.timeout());
+            std::thread::sleep(options.timeout());
+            anyhow::bail!("Simulated proving timeout");
+        }
+
+        std::thread::sleep(self.sample_latency());
+        if thread_rng().gen::<f64>() < options.failure_probability {
+            anyhow::bail!("Simulated proving failure");
+        }
+        Ok(())
+    }
 }
 
 impl ProverImpl for DummyProver {
     type Config = DummyProverConfig;
 
-    fn create_from_config(_config: Self::Config) -> Self {
+    fn create_from_config(config: Self::Config) -> Self {
         Self {
             precomputed_proofs: load_precomputed_proofs()
                 .expect("Failed to load precomputed proofs"),
+            simulation: config.simulation,
         }
     }
 
     fn create_proof(&self, data: JobRequestData) -> Result<JobResultData, Error> {
+        self.simulate_proving()?;
         let empty_proof = match data {
             JobRequestData::AggregatedBlockProof(single_proofs) => {
                 let mut aggregated_proof = self.precomputed_proofs.aggregated_proof.clone();
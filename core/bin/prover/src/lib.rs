@@ -1,8 +1,11 @@
 pub mod auth_utils;
+pub mod bench;
 pub mod cli_utils;
 pub mod client;
 pub mod dummy_prover;
 pub mod plonk_step_by_step_prover;
+pub mod resources;
+pub mod stream_client;
 
 // Built-in deps
 use futures::{pin_mut, FutureExt};
@@ -24,6 +27,8 @@ use zksync_prover_utils::api::{
     JobRequestData, JobResultData, ProverInputRequest, ProverInputRequestAuxData,
     ProverInputResponse, ProverOutputRequest,
 };
+// Local deps
+use crate::resources::{pin_current_thread, ResourceLimits};
 
 const ABSENT_PROVER_ID: i32 = -1;
 
@@ -91,22 +96,33 @@ pub trait ApiClient: Debug {
     async fn get_job(&self, req: ProverInputRequest) -> anyhow::Result<ProverInputResponse>;
     async fn working_on(&self, job_id: i32, prover_name: &str) -> anyhow::Result<()>;
     async fn publish(&self, data: ProverOutputRequest) -> anyhow::Result<()>;
+    /// Reports that the prover failed to prove the job, so the server returns it to the queue.
+    async fn job_failed(&self, job_id: i32, prover_name: &str, error: String)
+        -> anyhow::Result<()>;
     async fn prover_stopped(&self, prover_name: String) -> anyhow::Result<()>;
 }
 
+/// Creates the proof in a separate thread. The prover is returned back even if the proving fails,
+/// the outer error means that the proving thread has died.
 async fn compute_proof_no_blocking<PROVER>(
     prover: PROVER,
     data: JobRequestData,
-) -> anyhow::Result<(PROVER, JobResultData)>
+    proving_cpus: Option<Vec<usize>>,
+) -> anyhow::Result<(PROVER, anyhow::Result<JobResultData>)>
 where
     PROVER: ProverImpl + Send + Sync + 'static,
 {
     let (result_sender, result_receiver) = oneshot::channel();
     std::thread::spawn(move || {
-        let prover_with_proof = prover.create_proof(data).map(|proof| (prover, proof));
-        result_sender.send(prover_with_proof).unwrap_or_default();
+        if let Some(cpus) = proving_cpus {
+            pin_current_thread(&cpus)
+                .map_err(|e| vlog::warn!("Failed to pin proving threads: {}", e))
+                .unwrap_or_default();
+        }
+        let proof = prover.create_proof(data);
+        result_sender.send((prover, proof)).unwrap_or_default();
     });
-    result_receiver.await?
+    Ok(result_receiver.await?)
 }
 
 /// Endlessly sends requests to the server, in case of not receiving a response
@@ -149,13 +165,28 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
     PROVER: ProverImpl + Send + Sync + 'static,
 {
     vlog::info!("Running worker cycle");
+    let resource_limits = ResourceLimits::from_config(&prover_options.prover);
+    let proving_cpus = resource_limits.proving_cpus().unwrap_or_else(|e| {
+        vlog::warn!(
+            "Failed to get the CPUs for proving, threads are not pinned: {}",
+            e
+        );
+        None
+    });
+    if let Some(cpus) = &proving_cpus {
+        vlog::info!("Proving threads are pinned to CPUs {:?}", cpus);
+    }
     let mut new_job_poll_timer = tokio::time::interval(prover_options.prover.cycle_wait());
     loop {
         new_job_poll_timer.tick().await;
 
         if shutdown.get() {
+            vlog::info!("Prover is drained, no new jobs are taken");
             break;
         }
+        if !resource_limits.has_enough_memory() {
+            continue;
+        }
 
         let aux_data = prover.get_request_aux_data();
         let prover_input_response = match client
@@ -198,7 +229,8 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
             prover_options.prover.heartbeat_interval(),
         )
         .fuse();
-        let compute_proof_future = compute_proof_no_blocking(prover, job_data).fuse();
+        let compute_proof_future =
+            compute_proof_no_blocking(prover, job_data, proving_cpus.clone()).fuse();
 
         pin_mut!(heartbeat_future_handle, compute_proof_future);
 
@@ -215,6 +247,26 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
             _ = heartbeat_future_handle => unreachable!(),
         };
         prover = ret_prover;
+        let proof = match proof {
+            Ok(proof) => proof,
+            Err(e) => {
+                vlog::error!(
+                    "Failed to compute proof for blocks: [{}, {}]: {}",
+                    first_block,
+                    last_block,
+                    e
+                );
+                metrics::increment_counter!("prover.proving_failures");
+                // If the report isn't delivered, the job is returned to the queue once the server
+                // stops receiving the heartbeats for it.
+                client
+                    .job_failed(job_id, prover_name, e.to_string())
+                    .await
+                    .map_err(|e| vlog::warn!("Failed to report the proving failure: {}", e))
+                    .unwrap_or_default();
+                continue;
+            }
+        };
 
         client
             .publish(ProverOutputRequest {
@@ -233,7 +285,14 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
             last_block
         );
         if prover_options.prover.die_after_proof {
-            return;
+            break;
         }
     }
+
+    // Let the server know the prover won't send the heartbeats anymore.
+    client
+        .prover_stopped(prover_name.to_string())
+        .await
+        .map_err(|e| vlog::warn!("Failed to report the prover stop: {}", e))
+        .unwrap_or_default();
 }
@@ -5,8 +5,9 @@ use zksync_config::ChainConfig;
 use zksync_crypto::proof::{AggregatedProof, PrecomputedSampleProofs, SingleProof};
 use zksync_crypto::Engine;
 use zksync_prover_utils::aggregated_proofs::{gen_aggregate_proof, prepare_proof_data};
-use zksync_prover_utils::api::{JobRequestData, JobResultData};
+use zksync_prover_utils::api::{JobRequestData, JobResultData, ProverInputRequestAuxData};
 use zksync_prover_utils::{PlonkVerificationKey, SetupForStepByStepProver};
+use zksync_types::prover::ProverWorkClass;
 use zksync_utils::parse_env;
 // Local deps
 use crate::{ProverConfig, ProverImpl};
@@ -173,6 +174,22 @@ impl ProverImpl for PlonkStepByStepProver {
         Ok(proof)
     }
 
+    fn get_request_aux_data(&self) -> ProverInputRequestAuxData {
+        // Setup for the block sizes of the state keeper is prepared, other jobs are not assigned.
+        let work_classes = self
+            .config
+            .block_sizes
+            .iter()
+            .map(|&block_size| ProverWorkClass::SingleProof { block_size })
+            .chain(std::iter::once(ProverWorkClass::AggregatedProof))
+            .collect();
+
+        ProverInputRequestAuxData {
+            work_classes,
+            ..Default::default()
+        }
+    }
+
     fn create_from_config(config: PlonkStepByStepProverConfig) -> Self {
         assert!(!config.block_sizes.is_empty());
         PlonkStepByStepProver {
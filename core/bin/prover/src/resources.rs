@@ -0,0 +1,213 @@
+//! Limits of the machine resources used by the prover, so it can share the machine with
+//! the other applications (e.g. the witness generator).
+//!
+//! The amount of the proving threads is limited by pinning the thread that creates the proof
+//! to the chosen CPUs: the threads spawned by the proving routines inherit the CPU affinity and
+//! the size of their thread pool is determined by the amount of the CPUs available to them.
+//! New jobs are not requested while the machine doesn't have enough available memory.
+
+// Built-in deps
+use std::fs;
+// Workspace deps
+use zksync_config::configs::prover::Prover as ProverOptions;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceLimits {
+    proving_threads: usize,
+    min_available_memory: u64,
+    cpu_affinity: Option<Vec<usize>>,
+}
+
+impl ResourceLimits {
+    pub fn from_config(config: &ProverOptions) -> Self {
+        Self {
+            proving_threads: config.proving_threads,
+            min_available_memory: config.min_available_memory,
+            cpu_affinity: config.cpu_affinity.clone(),
+        }
+    }
+
+    /// Returns the CPUs the proving threads should be pinned to, or `None` if there is no need
+    /// to pin the threads.
+    pub fn proving_cpus(&self) -> anyhow::Result<Option<Vec<usize>>> {
+        if self.cpu_affinity.is_none() && self.proving_threads == 0 {
+            return Ok(None);
+        }
+        let available_cpus = available_cpus()?;
+        Ok(Some(self.select_cpus(available_cpus)))
+    }
+
+    fn select_cpus(&self, available_cpus: Vec<usize>) -> Vec<usize> {
+        let mut cpus = self.cpu_affinity.clone().unwrap_or(available_cpus);
+        if self.proving_threads > 0 {
+            cpus.truncate(self.proving_threads);
+        }
+        cpus
+    }
+
+    /// Checks whether the machine has enough available memory to accept a new job.
+    pub fn has_enough_memory(&self) -> bool {
+        if self.min_available_memory == 0 {
+            return true;
+        }
+        match available_memory() {
+            Ok(available_memory) => {
+                metrics::gauge!("prover.available_memory", available_memory as f64);
+                if available_memory < self.min_available_memory {
+                    vlog::warn!(
+                        "Not enough memory to accept a job: {} MB available, {} MB required",
+                        available_memory,
+                        self.min_available_memory
+                    );
+                    return false;
+                }
+                true
+            }
+            Err(e) => {
+                // The check is not supported on the machine, it should not prevent proving.
+                vlog::warn!("Failed to get the available memory: {}", e);
+                true
+            }
+        }
+    }
+}
+
+/// Returns the memory available on the machine in megabytes.
+pub fn available_memory() -> anyhow::Result<u64> {
+    let meminfo = fs::read_to_string("/proc/meminfo")?;
+    parse_available_memory(&meminfo)
+}
+
+/// Parses the available memory in megabytes from the content of `/proc/meminfo`.
+pub fn parse_available_memory(meminfo: &str) -> anyhow::Result<u64> {
+    parse_megabytes(meminfo, "MemAvailable:")
+}
+
+/// Returns the peak resident set size of the current process in megabytes.
+///
+/// The peak is counted since the start of the process or since the last `reset_peak_memory` call.
+pub fn peak_memory() -> anyhow::Result<u64> {
+    let status = fs::read_to_string("/proc/self/status")?;
+    parse_peak_memory(&status)
+}
+
+/// Resets the peak resident set size of the current process to its current size, so the peak
+/// memory usage of the next stage of the work can be measured separately.
+pub fn reset_peak_memory() -> anyhow::Result<()> {
+    fs::write("/proc/self/clear_refs", "5")?;
+    Ok(())
+}
+
+/// Parses the peak resident set size in megabytes from the content of `/proc/self/status`.
+pub fn parse_peak_memory(status: &str) -> anyhow::Result<u64> {
+    parse_megabytes(status, "VmHWM:")
+}
+
+/// Parses the value of the `<key> <value> kB` line of the `/proc` files in megabytes.
+fn parse_megabytes(content: &str, key: &str) -> anyhow::Result<u64> {
+    let line = content
+        .lines()
+        .find(|line| line.starts_with(key))
+        .ok_or_else(|| anyhow::format_err!("{} is not reported", key.trim_end_matches(':')))?;
+    let kilobytes: u64 = line
+        .trim_start_matches(key)
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()?;
+    Ok(kilobytes / 1024)
+}
+
+/// Returns the CPUs the current thread is allowed to run on.
+#[cfg(target_os = "linux")]
+pub fn available_cpus() -> anyhow::Result<Vec<usize>> {
+    // Safety: the set is initialized by `sched_getaffinity` and only inspected afterwards.
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        let result = libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set);
+        anyhow::ensure!(
+            result == 0,
+            "Failed to get CPU affinity: {}",
+            std::io::Error::last_os_error()
+        );
+        Ok((0..libc::CPU_SETSIZE as usize)
+            .filter(|&cpu| libc::CPU_ISSET(cpu, &set))
+            .collect())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn available_cpus() -> anyhow::Result<Vec<usize>> {
+    anyhow::bail!("CPU affinity is only supported on Linux")
+}
+
+/// Pins the current thread to the given CPUs, the threads it spawns inherit the affinity.
+#[cfg(target_os = "linux")]
+pub fn pin_current_thread(cpus: &[usize]) -> anyhow::Result<()> {
+    // Safety: the set is initialized before being passed to `sched_setaffinity`.
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_ZERO(&mut set);
+        for &cpu in cpus {
+            libc::CPU_SET(cpu, &mut set);
+        }
+        let result = libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set);
+        anyhow::ensure!(
+            result == 0,
+            "Failed to set CPU affinity {:?}: {}",
+            cpus,
+            std::io::Error::last_os_error()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn pin_current_thread(_cpus: &[usize]) -> anyhow::Result<()> {
+    anyhow::bail!("CPU affinity is only supported on Linux")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select_proving_cpus() {
+        let limits = ResourceLimits {
+            proving_threads: 2,
+            min_available_memory: 0,
+            cpu_affinity: None,
+        };
+        assert_eq!(limits.select_cpus(vec![0, 1, 2, 3]), vec![0, 1]);
+
+        let limits = ResourceLimits {
+            proving_threads: 0,
+            min_available_memory: 0,
+            cpu_affinity: Some(vec![4, 5, 6]),
+        };
+        assert_eq!(limits.select_cpus(vec![0, 1, 2, 3]), vec![4, 5, 6]);
+
+        let limits = ResourceLimits {
+            proving_threads: 2,
+            ..limits
+        };
+        assert_eq!(limits.select_cpus(vec![0, 1, 2, 3]), vec![4, 5]);
+        assert!(limits.has_enough_memory());
+    }
+
+    #[test]
+    fn parse_meminfo() {
+        let meminfo = "MemTotal:       32810864 kB\nMemFree:         1738092 kB\nMemAvailable:   20971520 kB\n";
+        assert_eq!(parse_available_memory(meminfo).unwrap(), 20480);
+        parse_available_memory("MemTotal:       32810864 kB\n").unwrap_err();
+    }
+
+    #[test]
+    fn parse_process_status() {
+        let status =
+            "Name:   prover-bench\nVmPeak:  8388608 kB\nVmHWM:   4194304 kB\nVmRSS:   1048576 kB\n";
+        assert_eq!(parse_peak_memory(status).unwrap(), 4096);
+        parse_peak_memory("Name:   prover-bench\n").unwrap_err();
+        parse_peak_memory("VmHWM:   unknown kB\n").unwrap_err();
+    }
+}
@@ -0,0 +1,231 @@
+//! Prover side of the job stream, see `zksync_prover_utils::stream` for the protocol description.
+
+// Built-in deps
+use std::sync::Arc;
+use std::time::Duration;
+// External deps
+use anyhow::format_err;
+use tokio::sync::{mpsc, Mutex};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{metadata::MetadataValue, Request};
+// Workspace deps
+use crate::auth_utils::AuthTokenGenerator;
+use zksync_prover_utils::api::{ProverInputRequest, ProverInputResponse, ProverOutputRequest};
+use zksync_prover_utils::stream::{
+    decode_payload, encode_payload, prover_message, server_message, Heartbeat, JobFailed, Proof,
+    ProverMessage, ProverStreamClient, Register, ServerMessage, AUTHORIZATION_KEY,
+};
+use zksync_types::BlockNumber;
+
+/// Amount of the messages buffered for sending to the server.
+const OUTBOUND_BUFFER_SIZE: usize = 4;
+
+#[derive(Debug)]
+struct JobStream {
+    sender: mpsc::Sender<ProverMessage>,
+    jobs: mpsc::Receiver<ProverInputResponse>,
+}
+
+/// Client receiving the jobs over the stream opened to the prover server.
+/// The stream is opened on the first job request and is reopened once it's broken.
+#[derive(Debug, Clone)]
+pub struct StreamClient {
+    server_url: String,
+    /// Time to wait for a job before reporting that there is no job for the prover.
+    job_wait_timeout: Duration,
+    auth_token_generator: AuthTokenGenerator,
+    /// Level of the zstd compression of the sent proofs, they're not compressed if not set.
+    compression_level: Option<i32>,
+    stream: Arc<Mutex<Option<JobStream>>>,
+}
+
+impl StreamClient {
+    // The time for which the authorization token will be valid.
+    const AUTH_TOKEN_LIFETIME: Duration = Duration::from_secs(10);
+
+    pub fn new(server_url: &str, job_wait_timeout: Duration, secret: &str) -> Self {
+        Self {
+            server_url: server_url.to_string(),
+            job_wait_timeout,
+            auth_token_generator: AuthTokenGenerator::new(
+                secret.to_string(),
+                Self::AUTH_TOKEN_LIFETIME,
+            ),
+            compression_level: None,
+            stream: Default::default(),
+        }
+    }
+
+    /// Authenticates the prover with the token issued to it instead of the shared secret.
+    pub fn with_prover_token(mut self, prover_token: Option<String>) -> Self {
+        self.auth_token_generator = self.auth_token_generator.with_prover_token(prover_token);
+        self
+    }
+
+    /// Sets the level of the zstd compression of the proofs sent to the server.
+    pub fn with_compression_level(mut self, compression_level: Option<i32>) -> Self {
+        self.compression_level = compression_level;
+        self
+    }
+
+    async fn open_stream(&self, req: &ProverInputRequest) -> anyhow::Result<JobStream> {
+        let mut client = ProverStreamClient::connect(self.server_url.clone())
+            .await
+            .map_err(|e| format_err!("failed to connect to the job stream server: {}", e))?;
+
+        let (sender, receiver) = mpsc::channel(OUTBOUND_BUFFER_SIZE);
+        sender
+            .send(stream_message(prover_message::Message::Register(
+                Register {
+                    request: encode_payload(req, None),
+                },
+            )))
+            .await?;
+        let mut request = Request::new(ReceiverStream::new(receiver));
+        let token = self
+            .auth_token_generator
+            .encode()
+            .map_err(|e| format_err!("failed generate authorization token: {}", e))?;
+        request.metadata_mut().insert(
+            AUTHORIZATION_KEY,
+            MetadataValue::from_str(&format!("Bearer {}", token))?,
+        );
+        let mut inbound = client
+            .open_job_stream(request)
+            .await
+            .map_err(|e| format_err!("failed to open the job stream: {}", e))?
+            .into_inner();
+
+        let (jobs_sender, jobs) = mpsc::channel(1);
+        tokio::spawn(async move {
+            loop {
+                let message = match inbound.message().await {
+                    Ok(Some(ServerMessage {
+                        message: Some(message),
+                    })) => message,
+                    Ok(Some(_)) => continue,
+                    Ok(None) => {
+                        vlog::warn!("Job stream is closed by the server");
+                        break;
+                    }
+                    Err(status) => {
+                        vlog::warn!("Job stream is broken: {}", status);
+                        break;
+                    }
+                };
+
+                match message {
+                    server_message::Message::Job(job) => {
+                        let job = match decode_payload(&job.response) {
+                            Ok(job) => job,
+                            Err(status) => {
+                                vlog::warn!("Failed to decode the job: {}", status);
+                                break;
+                            }
+                        };
+                        if jobs_sender.send(job).await.is_err() {
+                            break;
+                        }
+                    }
+                    server_message::Message::ProofStored(proof_stored) => {
+                        if proof_stored.error.is_empty() {
+                            vlog::debug!("Proof for job {} is stored", proof_stored.job_id);
+                        } else {
+                            vlog::warn!(
+                                "Server failed to store the proof for job {}: {}",
+                                proof_stored.job_id,
+                                proof_stored.error
+                            );
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(JobStream { sender, jobs })
+    }
+
+    async fn send(&self, message: prover_message::Message) -> anyhow::Result<()> {
+        let sender = self
+            .stream
+            .lock()
+            .await
+            .as_ref()
+            .map(|stream| stream.sender.clone())
+            .ok_or_else(|| format_err!("job stream is not open"))?;
+
+        sender
+            .send(stream_message(message))
+            .await
+            .map_err(|_| format_err!("job stream is closed"))
+    }
+}
+
+fn stream_message(message: prover_message::Message) -> ProverMessage {
+    ProverMessage {
+        message: Some(message),
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ApiClient for StreamClient {
+    async fn get_job(&self, req: ProverInputRequest) -> anyhow::Result<ProverInputResponse> {
+        let mut stream = self.stream.lock().await;
+        if stream.is_none() {
+            *stream = Some(self.open_stream(&req).await?);
+        }
+
+        let jobs = &mut stream.as_mut().unwrap().jobs;
+        match tokio::time::timeout(self.job_wait_timeout, jobs.recv()).await {
+            Ok(Some(job)) => Ok(job),
+            Ok(None) => {
+                *stream = None;
+                Err(format_err!("job stream is closed"))
+            }
+            // No job is pushed by the server yet.
+            Err(_) => Ok(ProverInputResponse {
+                job_id: 0,
+                first_block: BlockNumber(0),
+                last_block: BlockNumber(0),
+                data: None,
+            }),
+        }
+    }
+
+    async fn working_on(&self, job_id: i32, prover_name: &str) -> anyhow::Result<()> {
+        vlog::trace!(
+            "sending heartbeat job_id: {}, prover_name: {}",
+            job_id,
+            prover_name
+        );
+        self.send(prover_message::Message::Heartbeat(Heartbeat { job_id }))
+            .await
+    }
+
+    async fn publish(&self, data: ProverOutputRequest) -> anyhow::Result<()> {
+        vlog::trace!("Trying publish proof: {:?}", data);
+        self.send(prover_message::Message::Proof(Proof {
+            output: encode_payload(&data, self.compression_level),
+        }))
+        .await
+    }
+
+    async fn job_failed(
+        &self,
+        job_id: i32,
+        _prover_name: &str,
+        error: String,
+    ) -> anyhow::Result<()> {
+        self.send(prover_message::Message::JobFailed(JobFailed {
+            job_id,
+            error,
+        }))
+        .await
+    }
+
+    async fn prover_stopped(&self, _prover_name: String) -> anyhow::Result<()> {
+        // The server returns the jobs of the prover to the queue once the stream is closed.
+        self.stream.lock().await.take();
+        Ok(())
+    }
+}
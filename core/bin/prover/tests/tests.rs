@@ -50,6 +50,7 @@ impl Default for MockProverConfigs {
         };
         let dummy_config = DummyProverConfig {
             block_sizes: vec![10, 32],
+            simulation: Default::default(),
         };
         let prover_options = ProverConfig {
             prover: zksync_config::configs::prover::Prover {
@@ -57,15 +58,26 @@ impl Default for MockProverConfigs {
                 cycle_wait: 500,
                 request_timeout: 1,
                 die_after_proof: false,
+                proving_threads: 0,
+                min_available_memory: 0,
+                cpu_affinity: None,
+                compression_level: None,
             },
             core: zksync_config::configs::prover::Core {
                 gone_timeout: 2,
                 idle_provers: 1,
+                urgent_job_timeout: 1800,
+                max_job_attempts: 3,
+                verify_proofs: false,
+                compression_level: None,
             },
             witness_generator: zksync_config::configs::prover::WitnessGenerator {
                 prepare_data_interval: 5000,
                 witness_generators: 2,
+                standalone: false,
+                max_witnesses_in_flight: 8,
             },
+            dummy_prover: Default::default(),
         };
 
         Self {
@@ -160,7 +172,7 @@ async fn test_shutdown_request() {
 
     let prover_work_cycle = zksync_prover::prover_work_cycle(
         prover,
-        client,
+        client.clone(),
         shutdown_request.clone(),
         prover_options.clone(),
         &prover_name,
@@ -181,6 +193,9 @@ async fn test_shutdown_request() {
         shutdown_requested,
         "prover did not complete work after receiving a shutdown request"
     );
+    // The drained prover doesn't take the jobs and reports that it's stopped.
+    assert_eq!(*client.last_job_id.lock().await, 0);
+    assert_eq!(*client.stopped.lock().await, vec!["Test".to_string()]);
 }
 
 #[tokio::test]
@@ -256,6 +271,50 @@ async fn test_publishing_proof() {
     };
 }
 
+/// Checks that the simulated proving failures of the dummy prover are reported to the server
+/// and don't stop the prover.
+#[tokio::test]
+async fn test_simulated_proving_failure() {
+    let MockProverConfigs {
+        plonk_config: _,
+        mut dummy_config,
+        prover_options,
+        shutdown_request,
+        prover_name,
+    } = MockProverConfigs::default();
+    dummy_config.simulation.failure_probability = 1.0;
+
+    let prover = DummyProver::create_from_config(dummy_config);
+    let client = MockApiClient::default();
+
+    let prover_work_cycle = zksync_prover::prover_work_cycle(
+        prover,
+        client.clone(),
+        shutdown_request.clone(),
+        prover_options.clone(),
+        &prover_name,
+    )
+    .fuse();
+    let timeout = tokio::time::sleep(Duration::from_secs(5)).fuse();
+
+    pin_mut!(prover_work_cycle, timeout);
+
+    futures::select! {
+        _ = prover_work_cycle => panic!("prover work ended too quickly"),
+        _ = timeout => {
+            shutdown_request.set();
+            // The prover keeps requesting the new jobs after the failures.
+            let last_job_id = *client.last_job_id.lock().await;
+            assert!(last_job_id > 1);
+            assert!(client.published_prof.lock().await.is_empty());
+            // Every failed job is reported, the last one may be still in progress.
+            let failed_jobs = client.failed_jobs.lock().await.clone();
+            assert!(failed_jobs.len() as i32 >= last_job_id - 1);
+            assert_eq!(failed_jobs, (0..failed_jobs.len() as i32).collect::<Vec<_>>());
+        },
+    };
+}
+
 #[derive(Debug, Clone, Default)]
 struct MockApiClient {
     /// All published proofs are saved by `job_id`.
@@ -264,6 +323,10 @@ struct MockApiClient {
     working_on: Arc<Mutex<HashMap<i32, String>>>,
     /// `gob_id` of the last work that has not yet been submitted.
     last_job_id: Arc<Mutex<i32>>,
+    /// IDs of the jobs reported as failed.
+    failed_jobs: Arc<Mutex<Vec<i32>>>,
+    /// Names of the provers reported as stopped.
+    stopped: Arc<Mutex<Vec<String>>>,
 }
 
 #[async_trait::async_trait]
@@ -296,7 +359,15 @@ impl zksync_prover::ApiClient for MockApiClient {
         Ok(())
     }
 
-    async fn prover_stopped(&self, _: String) -> anyhow::Result<()> {
+    async fn job_failed(&self, job_id: i32, _: &str, _: String) -> anyhow::Result<()> {
+        self.failed_jobs.lock().await.push(job_id);
+
+        Ok(())
+    }
+
+    async fn prover_stopped(&self, prover_name: String) -> anyhow::Result<()> {
+        self.stopped.lock().await.push(prover_name);
+
         Ok(())
     }
 }
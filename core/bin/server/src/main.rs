@@ -1,36 +1,55 @@
-use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
-use std::cell::RefCell;
+use futures::{channel::mpsc, future, FutureExt};
 use std::str::FromStr;
+use std::time::Duration;
 
 use structopt::StructOpt;
 
 use serde::{Deserialize, Serialize};
 
-use zksync_api::fee_ticker::{run_updaters, FeeTicker, TickerInfo};
-use zksync_core::{genesis_init, run_core, wait_for_tasks};
+use zksync_api::fee_ticker::{
+    run_fee_quote_recorder, run_updaters, FeeModelParams, FeeModelParamsHandle, FeeQuoteRecorder,
+    FeeTicker, PriceCachePolicy, TickerInfo,
+};
+use zksync_api::signature_checker::EthMessageBinding;
+use zksync_core::leader_election::{wait_for_leadership, watch_leadership, AdvisoryLock};
+use zksync_core::{eth_watch::backfill_eth_events, genesis_init, run_core, wait_for_tasks};
 use zksync_eth_client::EthereumGateway;
 use zksync_forced_exit_requests::run_forced_exit_requests_actors;
 use zksync_gateway_watcher::run_gateway_watcher_if_multiplexed;
 use zksync_witness_generator::run_prover_server;
 
+use tokio::signal::unix::{signal, SignalKind};
 use tokio::task::JoinHandle;
-use zksync_config::configs::api::{PrivateApiConfig, PrometheusConfig, TokenConfig};
+use zksync_config::configs::api::{
+    AdminApiConfig, PrivateApiConfig, PrometheusConfig, TokenConfig,
+};
 use zksync_config::{
     configs::api::{CommonApiConfig, JsonRpcConfig, ProverApiConfig, RestApiConfig, Web3Config},
     ChainConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
-    ForcedExitRequestsConfig, GatewayWatcherConfig, ProverConfig, TickerConfig, ZkSyncConfig,
+    ForcedExitRequestsConfig, GatewayWatcherConfig, ObjectStoreConfig, ProverConfig, TickerConfig,
+    ZkSyncConfig,
 };
 use zksync_core::rejected_tx_cleaner::run_rejected_tx_cleaner;
-use zksync_mempool::run_mempool_tx_handler;
+use zksync_mempool::{
+    fee_free_whitelist::FeeFreeWhitelist,
+    pipeline_pauses::{run_paused_stages_updater, PausedStages},
+    run_mempool_tx_handler,
+    token_blacklist::{run_token_blacklist_updater, TokenBlacklist},
+};
+use zksync_object_store::ArtifactStore;
 use zksync_prometheus_exporter::{run_operation_counter, run_prometheus_exporter};
 use zksync_storage::ConnectionPool;
 use zksync_types::ChainId;
+use zksync_utils::shutdown::{ShutdownController, ShutdownGuard, ShutdownSignal};
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 32_768;
+/// Time given to the actors to persist their state after the stop signal.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(60);
 
 #[derive(Debug, Clone, Copy)]
 pub enum ServerCommand {
     Genesis,
+    EthWatchBackfill(u64, u64),
     Launch,
 }
 
@@ -41,6 +60,7 @@ pub enum Component {
     Web3Api,
     RpcApi,
     RpcWebSocketApi,
+    AdminApi,
 
     // Core components
     Fetchers,
@@ -64,6 +84,7 @@ impl FromStr for Component {
             "web3-api" => Ok(Component::Web3Api),
             "rpc-api" => Ok(Component::RpcApi),
             "rpc-websocket-api" => Ok(Component::RpcWebSocketApi),
+            "admin-api" => Ok(Component::AdminApi),
             "eth-sender" => Ok(Component::EthSender),
             "witness-generator" => Ok(Component::WitnessGenerator),
             "forced-exit" => Ok(Component::ForcedExit),
@@ -77,6 +98,47 @@ impl FromStr for Component {
     }
 }
 
+impl Component {
+    /// Whether the component modifies the state, so it's run only by the leader replica
+    /// if the leader election is enabled.
+    fn is_leader_only(&self) -> bool {
+        matches!(
+            self,
+            Component::EthSender
+                | Component::Core
+                | Component::WitnessGenerator
+                | Component::ForcedExit
+                | Component::RejectedTaskCleaner
+        )
+    }
+
+    /// Whether the component runs a mempool, so it needs the paused pipeline stages and the token blacklist.
+    fn uses_mempool(&self) -> bool {
+        matches!(
+            self,
+            Component::RpcWebSocketApi
+                | Component::RpcApi
+                | Component::RestApi
+                | Component::Core
+                | Component::ForcedExit
+        )
+    }
+
+    /// Whether the component interacts with Ethereum, so it needs the Ethereum gateway.
+    fn uses_eth_gateway(&self) -> bool {
+        matches!(
+            self,
+            Component::RpcWebSocketApi
+                | Component::RpcApi
+                | Component::RestApi
+                | Component::AdminApi
+                | Component::Fetchers
+                | Component::EthSender
+                | Component::Core
+        )
+    }
+}
+
 #[derive(Debug)]
 struct ComponentsToRun(Vec<Component>);
 
@@ -117,6 +179,10 @@ struct Opt {
     /// Generate genesis block for the first contract deployment
     #[structopt(long)]
     genesis: bool,
+    /// Rescan the given range of Ethereum blocks for the priority operations and Governance
+    /// contract events missed by the Ethereum watcher and store them
+    #[structopt(long, number_of_values = 2, value_names = &["FROM_BLOCK", "TO_BLOCK"])]
+    eth_watch_backfill: Option<Vec<u64>>,
     /// comma-separated list of components to launch
     #[structopt(
         long,
@@ -131,6 +197,9 @@ async fn main() -> anyhow::Result<()> {
     let mut _vlog_guard = None;
     let server_mode = if opt.genesis {
         ServerCommand::Genesis
+    } else if let Some(block_range) = &opt.eth_watch_backfill {
+        _vlog_guard = Some(vlog::init());
+        ServerCommand::EthWatchBackfill(block_range[0], block_range[1])
     } else {
         _vlog_guard = Some(vlog::init());
         ServerCommand::Launch
@@ -143,20 +212,96 @@ async fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
+    if let ServerCommand::EthWatchBackfill(from_block, to_block) = server_mode {
+        vlog::info!(
+            "Backfilling the Ethereum events for the blocks [{}, {}]",
+            from_block,
+            to_block
+        );
+        let stats = backfill_eth_events(
+            create_eth_gateway(),
+            ConnectionPool::new(Some(1)),
+            &ZkSyncConfig::from_env(),
+            from_block..=to_block,
+        )
+        .await?;
+        vlog::info!("Backfill is finished: {:?}", stats);
+        return Ok(());
+    }
+
     // It's a `ServerCommand::Launch`, perform the usual routine.
     vlog::info!("Running the zkSync server");
 
+    validate_config();
     run_server(&opt.components).await;
 
     Ok(())
 }
 
+/// Loads all the config sections used by the server, so an invalid config fails the startup
+/// with the precise error before any component is started.
+///
+/// # Panics
+///
+/// Panics if any of the config sections is invalid.
+fn validate_config() {
+    ZkSyncConfig::from_env();
+    ObjectStoreConfig::from_env();
+}
+
 async fn run_server(components: &ComponentsToRun) {
-    let connection_pool = ConnectionPool::new(None);
+    let connection_pool =
+        ConnectionPool::new(None).with_compression_level(DBConfig::from_env().compression_level);
     let read_only_connection_pool = ConnectionPool::new_readonly_pool(None);
-    let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
 
     let mut tasks = vec![];
+    let shutdown = ShutdownController::new();
+    // Set if the fee ticker is running, so its parameters are reloaded with the config.
+    let mut reloadable_fee_params = None;
+
+    // The same gateway is shared by all the components, so the failover of one of
+    // the Ethereum nodes is detected once and affects all of them. It's only created
+    // if any of the components needs it, so the others don't require the Ethereum config.
+    let eth_gateway = components
+        .0
+        .iter()
+        .any(Component::uses_eth_gateway)
+        .then(create_eth_gateway);
+    let shared_eth_gateway = || {
+        eth_gateway
+            .clone()
+            .expect("Ethereum gateway is not created for the component using it")
+    };
+    // The pauses and the token blacklist are loaded once and shared by all the mempools,
+    // the state keeper and the APIs.
+    let (paused_stages, token_blacklist) = if components.0.iter().any(Component::uses_mempool) {
+        let paused_stages = PausedStages::load(&connection_pool)
+            .await
+            .expect("Failed to load the paused pipeline stages");
+        tasks.push(run_paused_stages_updater(
+            connection_pool.clone(),
+            paused_stages.clone(),
+        ));
+        let token_blacklist = TokenBlacklist::load(&connection_pool)
+            .await
+            .expect("Failed to load the token blacklist");
+        tasks.push(run_token_blacklist_updater(
+            connection_pool.clone(),
+            token_blacklist.clone(),
+        ));
+        (paused_stages, token_blacklist)
+    } else {
+        (PausedStages::default(), TokenBlacklist::default())
+    };
+    if let Some(eth_gateway) = &eth_gateway {
+        // Run eth multiplexer
+        let gateway_watcher_config = GatewayWatcherConfig::from_env();
+        if let Some(task) =
+            run_gateway_watcher_if_multiplexed(eth_gateway.clone(), &gateway_watcher_config)
+        {
+            tasks.push(task);
+        }
+    }
 
     if components.0.contains(&Component::Web3Api) {
         // Run web3 api
@@ -169,50 +314,81 @@ async fn run_server(components: &ComponentsToRun) {
 
     if components.0.contains(&Component::Fetchers) {
         // Run price fetchers
-        let mut price_tasks = run_price_updaters(connection_pool.clone());
+        let mut price_tasks = run_price_updaters(connection_pool.clone(), shared_eth_gateway());
         tasks.append(&mut price_tasks);
     }
 
     if components.0.iter().any(|c| {
         matches!(
             c,
-            Component::RpcWebSocketApi | Component::RpcApi | Component::RestApi
+            Component::RpcWebSocketApi
+                | Component::RpcApi
+                | Component::RestApi
+                | Component::AdminApi
         )
     }) {
-        // Create gateway
-        let eth_gateway = create_eth_gateway();
-
         let eth_watch_config = ETHWatchConfig::from_env();
-        let gateway_watcher_config = GatewayWatcherConfig::from_env();
-
-        // Run eth multiplexer
-        if let Some(task) =
-            run_gateway_watcher_if_multiplexed(eth_gateway.clone(), &gateway_watcher_config)
-        {
-            tasks.push(task);
-        }
+        let common_config = CommonApiConfig::from_env();
+        let eth_client_config = ETHClientConfig::from_env();
+        let fee_free_whitelist = FeeFreeWhitelist::new(
+            common_config.fee_free_accounts.clone(),
+            common_config.fee_free_tx_types.clone(),
+        );
 
         // Run signer
         let (sign_check_sender, sign_check_receiver) = mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
         tasks.push(zksync_api::signature_checker::start_sign_checker(
-            eth_gateway,
+            shared_eth_gateway(),
             sign_check_receiver,
+            EthMessageBinding {
+                chain_id: ChainId(eth_client_config.chain_id),
+                accept_unbound_messages: common_config.accept_eth_messages_without_chain_id,
+            },
+            common_config.eth_signature_cache_size,
         ));
 
         let contracts_config = ContractsConfig::from_env();
-        let common_config = CommonApiConfig::from_env();
         let token_config = TokenConfig::from_env();
         let chain_config = ChainConfig::from_env();
         let fee_ticker_config = TickerConfig::from_env();
-        let eth_client_config = ETHClientConfig::from_env();
-        let ticker_info = Box::new(TickerInfo::new(read_only_connection_pool.clone()));
+        let ticker_info = Box::new(TickerInfo::new(
+            read_only_connection_pool.clone(),
+            PriceCachePolicy::from_config(&fee_ticker_config),
+        ));
 
-        let ticker = FeeTicker::new_with_default_validator(
+        let fee_quote_sample_rate = fee_ticker_config.fee_quote_sample_rate;
+        let mut ticker = FeeTicker::new_with_default_validator(
             ticker_info,
             fee_ticker_config,
             chain_config.max_blocks_to_aggregate(),
             read_only_connection_pool.clone(),
         );
+        if fee_quote_sample_rate > 0.0 {
+            let (quote_recorder, quotes_receiver) = FeeQuoteRecorder::new(fee_quote_sample_rate);
+            tasks.push(run_fee_quote_recorder(
+                connection_pool.clone(),
+                quotes_receiver,
+            ));
+            ticker = ticker.with_quote_recorder(quote_recorder);
+        }
+
+        // The fee model parameters can be updated via the admin API of any server,
+        // so they are reloaded from the database periodically.
+        let fee_params = ticker.fee_params();
+        tasks.push(tokio::spawn(
+            fee_params
+                .clone()
+                .keep_updated(read_only_connection_pool.clone()),
+        ));
+        reloadable_fee_params = Some(fee_params.clone());
+
+        if components.0.contains(&Component::AdminApi) {
+            tasks.push(zksync_api::api_server::admin_server::start_admin_server(
+                connection_pool.clone(),
+                AdminApiConfig::from_env(),
+                fee_params,
+            ));
+        }
 
         if components.0.contains(&Component::RpcWebSocketApi) {
             let (mempool_tx_request_sender, mempool_tx_request_receiver) =
@@ -221,6 +397,10 @@ async fn run_server(components: &ComponentsToRun) {
                 connection_pool.clone(),
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes.clone(),
+                paused_stages.clone(),
+                token_blacklist.clone(),
+                fee_free_whitelist.clone(),
+                shutdown.signal(),
             ));
             tasks.push(zksync_api::api_server::rpc_subscriptions::start_ws_server(
                 read_only_connection_pool.clone(),
@@ -231,6 +411,7 @@ async fn run_server(components: &ComponentsToRun) {
                 &JsonRpcConfig::from_env(),
                 chain_config.state_keeper.miniblock_iteration_interval(),
                 mempool_tx_request_sender,
+                token_blacklist.clone(),
                 eth_watch_config.confirmations_for_eth_event,
                 ChainId(eth_client_config.chain_id),
             ));
@@ -243,6 +424,10 @@ async fn run_server(components: &ComponentsToRun) {
                 connection_pool.clone(),
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes.clone(),
+                paused_stages.clone(),
+                token_blacklist.clone(),
+                fee_free_whitelist.clone(),
+                shutdown.signal(),
             ));
             tasks.push(zksync_api::api_server::rpc_server::start_rpc_server(
                 read_only_connection_pool.clone(),
@@ -252,6 +437,7 @@ async fn run_server(components: &ComponentsToRun) {
                 &common_config,
                 &token_config,
                 mempool_tx_request_sender,
+                token_blacklist.clone(),
                 ChainId(eth_client_config.chain_id),
                 eth_watch_config.confirmations_for_eth_event,
             ));
@@ -264,6 +450,10 @@ async fn run_server(components: &ComponentsToRun) {
                 connection_pool.clone(),
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes,
+                paused_stages.clone(),
+                token_blacklist.clone(),
+                fee_free_whitelist.clone(),
+                shutdown.signal(),
             ));
             let private_config = PrivateApiConfig::from_env();
             tasks.push(zksync_api::api_server::rest::start_server_thread_detached(
@@ -275,24 +465,70 @@ async fn run_server(components: &ComponentsToRun) {
                 sign_check_sender,
                 ChainId(eth_client_config.chain_id),
                 mempool_tx_request_sender,
+                token_blacklist.clone(),
                 private_config.url,
             ));
         }
     }
 
+    if components.0.contains(&Component::Prometheus) {
+        // Run prometheus data exporter.
+        let config = PrometheusConfig::from_env();
+        let prometheus_task_handle = run_prometheus_exporter(config.port);
+        tasks.push(prometheus_task_handle);
+        // We can run them only with active prometheus
+        if components.0.contains(&Component::PrometheusPeriodicMetrics) {
+            let counter_task_handle = run_operation_counter(read_only_connection_pool.clone());
+            tasks.push(counter_task_handle);
+        }
+    }
+
+    // `SIGHUP` handler is set before waiting for the leadership, so the standby can reload
+    // its config as well.
+    tasks.push(run_config_reloader(
+        read_only_connection_pool.clone(),
+        reloadable_fee_params,
+    ));
+
+    // Resolves once the leadership is lost, never resolves if the leader election is disabled.
+    let mut leadership_lost = future::pending::<()>().boxed();
+    let db_config = DBConfig::from_env();
+    if let Some(lock_id) = db_config.leader_lock_id {
+        if components.0.iter().any(Component::is_leader_only) {
+            let check_interval = db_config.leader_check_interval();
+            // The standby keeps serving the APIs while waiting for the leadership.
+            let leadership = tokio::select! {
+                leadership = wait_for_leadership(AdvisoryLock::new(lock_id), check_interval) => leadership,
+                _ = wait_for_tasks(tasks.iter_mut()) => {
+                    panic!("One if the actors is not supposed to finish its execution")
+                },
+                _ = wait_for_stop_signal() => {
+                    vlog::warn!("Stop signal received while running as a standby, shutting down");
+                    return;
+                }
+            };
+            leadership_lost = watch_leadership(leadership, check_interval).boxed();
+        }
+    }
+
     if components.0.contains(&Component::EthSender) {
-        tasks.push(run_eth_sender(connection_pool.clone()))
+        tasks.push(run_eth_sender(
+            connection_pool.clone(),
+            shared_eth_gateway(),
+            shutdown.guard(),
+        ))
     }
 
     if components.0.contains(&Component::Core) {
-        let eth_gateway = create_eth_gateway();
-
         tasks.append(
             &mut run_core(
                 connection_pool.clone(),
                 read_only_connection_pool.clone(),
                 &ZkSyncConfig::from_env(),
-                eth_gateway.clone(),
+                shared_eth_gateway(),
+                paused_stages.clone(),
+                token_blacklist.clone(),
+                &shutdown,
             )
             .await
             .unwrap(),
@@ -303,47 +539,110 @@ async fn run_server(components: &ComponentsToRun) {
         tasks.push(run_witness_generator(connection_pool.clone()))
     }
 
-    if components.0.contains(&Component::Prometheus) {
-        // Run prometheus data exporter.
-        let config = PrometheusConfig::from_env();
-        let prometheus_task_handle = run_prometheus_exporter(config.port);
-        tasks.push(prometheus_task_handle);
-        // We can run them only with active prometheus
-        if components.0.contains(&Component::PrometheusPeriodicMetrics) {
-            let counter_task_handle = run_operation_counter(read_only_connection_pool.clone());
-            tasks.push(counter_task_handle);
-        }
-    }
-
     if components.0.contains(&Component::ForcedExit) {
-        tasks.append(&mut run_forced_exit(connection_pool.clone()));
+        tasks.append(&mut run_forced_exit(
+            connection_pool.clone(),
+            paused_stages,
+            token_blacklist,
+            shutdown.signal(),
+        ));
     }
 
     if components.0.contains(&Component::RejectedTaskCleaner) {
-        let config = DBConfig::from_env();
-        tasks.push(run_rejected_tx_cleaner(&config, connection_pool));
-    }
-
-    {
-        let stop_signal_sender = RefCell::new(stop_signal_sender.clone());
-        ctrlc::set_handler(move || {
-            let mut sender = stop_signal_sender.borrow_mut();
-            block_on(sender.send(true)).expect("Ctrl+C signal send");
-        })
-        .expect("Error setting Ctrl+C handler");
+        tasks.push(run_rejected_tx_cleaner(&db_config, connection_pool));
     }
 
     tokio::select! {
         _ = async { wait_for_tasks(tasks).await } => {
             panic!("One if the actors is not supposed to finish its execution")
         },
-        _ = async { stop_signal_receiver.next().await } => {
+        _ = wait_for_stop_signal() => {
             vlog::warn!("Stop signal received, shutting down");
         }
+        _ = leadership_lost => {
+            // The lock may already be acquired by a standby, so the graceful shutdown would let both replicas
+            // seal the blocks and send the Ethereum transactions until it's finished. Instead, the process
+            // exits right away, aborting all the actors, and is restarted as a standby.
+            vlog::error!("Leader lock is lost, the replica is not the leader anymore, exiting");
+            std::process::exit(1);
+        }
     };
+
+    // New transactions are rejected from now on, the server exits once the state keeper and
+    // the Ethereum sender persist their state.
+    if shutdown.shutdown(SHUTDOWN_TIMEOUT).await {
+        vlog::info!("All the actors are stopped");
+    } else {
+        vlog::warn!(
+            "Actors didn't stop within {} seconds, exiting anyway",
+            SHUTDOWN_TIMEOUT.as_secs()
+        );
+    }
+}
+
+/// Waits for `SIGINT` or `SIGTERM`. `SIGHUP` doesn't stop the server, it's used to reload the config.
+async fn wait_for_stop_signal() {
+    let mut terminate = signal(SignalKind::terminate()).expect("Error setting SIGTERM handler");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {},
+        _ = terminate.recv() => {},
+    }
 }
 
-pub fn run_forced_exit(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>> {
+/// Reloads the hot-reloadable part of the config on `SIGHUP`: the log filter, the fee model
+/// parameters and the API submission limits. The rest of the config requires a restart.
+fn run_config_reloader(
+    connection_pool: ConnectionPool,
+    fee_params: Option<FeeModelParamsHandle>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut hangups = signal(SignalKind::hangup()).expect("Error setting SIGHUP handler");
+        // The log filter may be changed via the admin API, so it's replaced only if `RUST_LOG` is changed.
+        let mut log_filter = zksync_config::loader::variables()
+            .ok()
+            .and_then(|variables| variables.get("RUST_LOG").map(str::to_owned));
+        while hangups.recv().await.is_some() {
+            vlog::info!("SIGHUP received, reloading the config");
+            match reload_config(&connection_pool, fee_params.as_ref(), &mut log_filter).await {
+                Ok(()) => vlog::info!("Config is reloaded"),
+                Err(err) => vlog::error!("Failed to reload the config: {}", err),
+            }
+        }
+    })
+}
+
+/// `log_filter` is the last applied value of `RUST_LOG`, the log filter is replaced only if it's changed.
+async fn reload_config(
+    connection_pool: &ConnectionPool,
+    fee_params: Option<&FeeModelParamsHandle>,
+    log_filter: &mut Option<String>,
+) -> anyhow::Result<()> {
+    // Updates the API submission limits.
+    let variables = zksync_config::loader::reload()?;
+
+    let new_log_filter = variables.get("RUST_LOG");
+    if let Some(new_log_filter) = new_log_filter {
+        if log_filter.as_deref() != Some(new_log_filter) {
+            vlog::set_log_filter(new_log_filter)
+                .map_err(|err| anyhow::anyhow!("Invalid log filter: {}", err))?;
+        }
+    }
+    *log_filter = new_log_filter.map(str::to_owned);
+    if let Some(fee_params) = fee_params {
+        let config: TickerConfig = variables.load("fee_ticker", "FEE_TICKER_")?;
+        fee_params
+            .update_defaults(connection_pool, FeeModelParams::from_config(&config))
+            .await?;
+    }
+    Ok(())
+}
+
+pub fn run_forced_exit(
+    connection_pool: ConnectionPool,
+    paused_stages: PausedStages,
+    token_blacklist: TokenBlacklist,
+    shutdown: ShutdownSignal,
+) -> Vec<JoinHandle<()>> {
     vlog::info!("Starting the ForcedExitRequests actors");
     let config = ForcedExitRequestsConfig::from_env();
     let common_config = CommonApiConfig::from_env();
@@ -357,6 +656,11 @@ pub fn run_forced_exit(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>> {
         connection_pool.clone(),
         mempool_tx_request_receiver,
         chain_config.state_keeper.block_chunk_sizes,
+        paused_stages,
+        token_blacklist,
+        // Forced exits are sent with the fee paid.
+        FeeFreeWhitelist::default(),
+        shutdown,
     );
     let forced_exit_task = run_forced_exit_requests_actors(
         connection_pool,
@@ -373,27 +677,30 @@ pub fn run_witness_generator(connection_pool: ConnectionPool) -> JoinHandle<()>
     vlog::info!("Starting the Prover server actors");
     let prover_api_config = ProverApiConfig::from_env();
     let prover_config = ProverConfig::from_env();
-    let database = zksync_witness_generator::database::Database::new(connection_pool);
+    let artifact_store = ArtifactStore::from_config(&ObjectStoreConfig::from_env())
+        .expect("Failed to create the prover artifacts store");
+    let database =
+        zksync_witness_generator::database::Database::new(connection_pool, artifact_store);
     run_prover_server(database, prover_api_config, prover_config)
 }
 
-pub fn run_eth_sender(connection_pool: ConnectionPool) -> JoinHandle<()> {
+pub fn run_eth_sender(
+    connection_pool: ConnectionPool,
+    eth_gateway: EthereumGateway,
+    shutdown: ShutdownGuard,
+) -> JoinHandle<()> {
     vlog::info!("Starting the Ethereum sender actors");
-    let eth_client_config = ETHClientConfig::from_env();
     let eth_sender_config = ETHSenderConfig::from_env();
-    let contracts = ContractsConfig::from_env();
-    let eth_gateway = EthereumGateway::from_config(
-        &eth_client_config,
-        &eth_sender_config,
-        contracts.contract_addr,
-    );
 
-    zksync_eth_sender::run_eth_sender(connection_pool, eth_gateway, eth_sender_config)
+    zksync_eth_sender::run_eth_sender(connection_pool, eth_gateway, eth_sender_config, shutdown)
 }
 
-pub fn run_price_updaters(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>> {
+pub fn run_price_updaters(
+    connection_pool: ConnectionPool,
+    eth_gateway: EthereumGateway,
+) -> Vec<JoinHandle<()>> {
     let ticker_config = TickerConfig::from_env();
-    run_updaters(connection_pool, &ticker_config)
+    run_updaters(connection_pool, &ticker_config, eth_gateway)
 }
 
 pub fn create_eth_gateway() -> EthereumGateway {
@@ -0,0 +1,271 @@
+//! Admin API server, used by the operators to tune the server at runtime.
+//!
+//! The API is protected with the JWT authentication, the same way as the prover API.
+//! Currently it allows to read and update the fee model parameters and the log filter,
+//! and to pause or resume the pipeline stages.
+
+// External deps
+use actix_web::{web, App, HttpResponse, HttpServer};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+// Workspace deps
+use zksync_config::configs::api::AdminApiConfig;
+use zksync_storage::ConnectionPool;
+use zksync_types::pipeline::PipelineStage;
+use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
+use zksync_utils::AuthTokenValidator;
+// Local deps
+use crate::fee_ticker::{fee_params::FeeModelParamsUpdate, FeeModelParamsHandle};
+
+#[derive(Clone)]
+struct AppState {
+    secret_auth: String,
+    connection_pool: ConnectionPool,
+    fee_params: FeeModelParamsHandle,
+}
+
+/// Log filter in the `RUST_LOG` format, e.g. `zksync_api=debug,zksync_core=info`.
+#[derive(Debug, Serialize, Deserialize)]
+struct LogFilter {
+    filter: String,
+}
+
+/// Request to pause or resume the pipeline stage.
+#[derive(Debug, Serialize, Deserialize)]
+struct PipelineStageUpdate {
+    stage: PipelineStage,
+    paused: bool,
+    /// Reason of the pause, required to pause the stage.
+    #[serde(default)]
+    reason: String,
+}
+
+async fn get_fee_params(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(data.fee_params.get().as_ref()))
+}
+
+async fn update_fee_params(
+    data: web::Data<AppState>,
+    r: web::Json<FeeModelParamsUpdate>,
+) -> actix_web::Result<HttpResponse> {
+    let update = r.into_inner();
+    vlog::info!("Fee model parameters update requested: {:?}", update);
+    update.validate().map_err(|err| {
+        vlog::warn!("Invalid fee model parameters update: {}", err);
+        actix_web::error::ErrorBadRequest(err)
+    })?;
+
+    let params = data
+        .fee_params
+        .update(&data.connection_pool, update)
+        .await
+        .map_err(|err| {
+            vlog::warn!("Failed to update the fee model parameters: {}", err);
+            actix_web::error::ErrorInternalServerError(err)
+        })?;
+    Ok(HttpResponse::Ok().json(params))
+}
+
+async fn get_log_filter() -> actix_web::Result<HttpResponse> {
+    let filter = vlog::log_filter()
+        .ok_or_else(|| actix_web::error::ErrorInternalServerError("Logger is not initialized"))?;
+    Ok(HttpResponse::Ok().json(LogFilter { filter }))
+}
+
+async fn update_log_filter(r: web::Json<LogFilter>) -> actix_web::Result<HttpResponse> {
+    let LogFilter { filter } = r.into_inner();
+    vlog::info!("Log filter update requested: {}", filter);
+
+    vlog::set_log_filter(&filter).map_err(|err| {
+        vlog::warn!("Failed to update the log filter: {}", err);
+        actix_web::error::ErrorBadRequest(err.to_string())
+    })?;
+    Ok(HttpResponse::Ok().json(LogFilter { filter }))
+}
+
+async fn get_paused_stages(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let pauses = storage
+        .misc_schema()
+        .load_paused_pipeline_stages()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(pauses))
+}
+
+async fn update_paused_stages(
+    data: web::Data<AppState>,
+    r: web::Json<PipelineStageUpdate>,
+) -> actix_web::Result<HttpResponse> {
+    let update = r.into_inner();
+    if update.paused && update.reason.is_empty() {
+        return Err(actix_web::error::ErrorBadRequest(
+            "Reason is required to pause the stage",
+        ));
+    }
+
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let result = if update.paused {
+        vlog::warn!(
+            "Pipeline stage {} is paused via the admin API: {}",
+            update.stage,
+            update.reason
+        );
+        storage
+            .misc_schema()
+            .pause_pipeline_stage(update.stage, &update.reason)
+            .await
+    } else {
+        vlog::info!(
+            "Pipeline stage {} is resumed via the admin API",
+            update.stage
+        );
+        storage
+            .misc_schema()
+            .resume_pipeline_stage(update.stage)
+            .await
+            .map(drop)
+    };
+    result.map_err(actix_web::error::ErrorInternalServerError)?;
+    drop(storage);
+
+    get_paused_stages(data).await
+}
+
+/// Starts the admin API server in the separate thread.
+///
+/// # Arguments
+///
+/// * `connection_pool` - Pool of the main database, the updated parameters are persisted there
+/// * `config` - Config of the admin API
+/// * `fee_params` - Fee model parameters used by the fee ticker
+///
+pub fn start_admin_server(
+    connection_pool: ConnectionPool,
+    config: AdminApiConfig,
+    fee_params: FeeModelParamsHandle,
+) -> JoinHandle<()> {
+    let (handler, panic_sender) = spawn_panic_handler();
+
+    std::thread::Builder::new()
+        .name("admin-api".to_string())
+        .spawn(move || {
+            let _panic_sentinel = ThreadPanicNotify(panic_sender.clone());
+
+            actix_rt::System::new().block_on(async move {
+                let secret_auth = config.secret_auth.clone();
+
+                HttpServer::new(move || {
+                    let app_state = AppState {
+                        secret_auth: secret_auth.clone(),
+                        connection_pool: connection_pool.clone(),
+                        fee_params: fee_params.clone(),
+                    };
+
+                    let auth = HttpAuthentication::bearer(move |req, credentials| async {
+                        let secret_auth = req
+                            .app_data::<web::Data<AppState>>()
+                            .expect("failed get AppState upon receipt of the authentication token")
+                            .secret_auth
+                            .clone();
+                        AuthTokenValidator::new(&secret_auth)
+                            .validator(req, credentials)
+                            .await
+                    });
+
+                    App::new()
+                        .wrap(auth)
+                        .app_data(web::Data::new(app_state))
+                        .route("/fee_params", web::get().to(get_fee_params))
+                        .route("/fee_params", web::post().to(update_fee_params))
+                        .route("/log_filter", web::get().to(get_log_filter))
+                        .route("/log_filter", web::post().to(update_log_filter))
+                        .route("/paused_stages", web::get().to(get_paused_stages))
+                        .route("/paused_stages", web::post().to(update_paused_stages))
+                })
+                .bind(&config.bind_addr())
+                .expect("failed to bind the admin API")
+                .run()
+                .await
+                .expect("failed to run the admin API");
+            });
+        })
+        .expect("failed to start the admin API thread");
+
+    handler
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test};
+    use zksync_config::TickerConfig;
+    use zksync_types::pipeline::PipelineStagePause;
+
+    use super::*;
+    use crate::fee_ticker::FeeModelParams;
+
+    /// The stage which isn't checked by the other API tests.
+    const STAGE: PipelineStage = PipelineStage::ProofSubmission;
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn paused_stages_update() {
+        let app_state = AppState {
+            secret_auth: String::new(),
+            connection_pool: ConnectionPool::new(Some(1)),
+            fee_params: FeeModelParamsHandle::new(FeeModelParams::from_config(
+                &TickerConfig::from_env(),
+            )),
+        };
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(app_state))
+                .route("/paused_stages", web::get().to(get_paused_stages))
+                .route("/paused_stages", web::post().to(update_paused_stages)),
+        )
+        .await;
+        let update_request = |paused: bool, reason: &str| {
+            test::TestRequest::post()
+                .uri("/paused_stages")
+                .set_json(&PipelineStageUpdate {
+                    stage: STAGE,
+                    paused,
+                    reason: reason.to_string(),
+                })
+                .to_request()
+        };
+
+        // The reason is required to pause the stage.
+        let response = test::call_service(&app, update_request(true, "")).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let pauses: Vec<PipelineStagePause> =
+            test::read_response_json(&app, update_request(true, "Incident")).await;
+        let pause = pauses
+            .iter()
+            .find(|pause| pause.stage == STAGE)
+            .expect("The stage is not paused");
+        assert_eq!(pause.reason, "Incident");
+
+        let request = test::TestRequest::get().uri("/paused_stages").to_request();
+        let loaded_pauses: Vec<PipelineStagePause> = test::read_response_json(&app, request).await;
+        assert_eq!(loaded_pauses, pauses);
+
+        // The reason isn't required to resume the stage.
+        let pauses: Vec<PipelineStagePause> =
+            test::read_response_json(&app, update_request(false, "")).await;
+        assert!(pauses.iter().all(|pause| pause.stage != STAGE));
+    }
+}
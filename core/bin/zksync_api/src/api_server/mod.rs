@@ -3,7 +3,9 @@
 //! `mod rest` - api is used for block explorer.
 //! `mod rpc_server` - JSON rpc via HTTP (for request reply functions)
 //! `mod rpc_subscriptions` - JSON rpc via WebSocket (for request reply functions and subscriptions)
+//! `mod admin_server` - api used by the operators to tune the server at runtime
 
+pub mod admin_server;
 mod event_notify;
 pub mod forced_exit_checker;
 mod helpers;
@@ -16,7 +16,7 @@ use crate::api_server::rest::network_status::SharedNetworkStatus;
 use crate::fee_ticker::FeeTicker;
 use tokio::task::JoinHandle;
 use zksync_config::ZkSyncConfig;
-use zksync_mempool::MempoolTransactionRequest;
+use zksync_mempool::{token_blacklist::TokenBlacklist, MempoolTransactionRequest};
 
 mod forced_exit_requests;
 mod helpers;
@@ -30,6 +30,7 @@ async fn start_server(
     sign_verifier: mpsc::Sender<VerifySignatureRequest>,
     bind_to: SocketAddr,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    token_blacklist: TokenBlacklist,
     chain_id: ChainId,
 ) {
     HttpServer::new(move || {
@@ -54,6 +55,7 @@ async fn start_server(
                 &api_v01.config.api.common,
                 &api_v01.config.api.token_config,
                 mempool_tx_sender.clone(),
+                token_blacklist.clone(),
                 chain_id,
             );
             v02::api_scope(tx_sender, &api_v01.config, api_v01.network_status.clone())
@@ -99,6 +101,7 @@ pub fn start_server_thread_detached(
     sign_verifier: mpsc::Sender<VerifySignatureRequest>,
     chain_id: ChainId,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    token_blacklist: TokenBlacklist,
     core_address: String,
 ) -> JoinHandle<()> {
     let (handler, panic_sender) = spawn_panic_handler();
@@ -140,6 +143,7 @@ pub fn start_server_thread_detached(
                     sign_verifier,
                     listen_addr,
                     mempool_tx_sender.clone(),
+                    token_blacklist,
                     chain_id,
                 )
                 .await;
@@ -117,6 +117,7 @@ mod tests {
         fee::{ApiTxFeeTypes, TxInBatchFeeRequest},
         ApiVersion,
     };
+    use zksync_mempool::token_blacklist::TokenBlacklist;
     use zksync_types::{
         tokens::{TokenLike, TokenMarketVolume},
         Address, ChainId, Token, TokenId, TokenKind,
@@ -172,6 +173,7 @@ mod tests {
                     &cfg.config.api.common,
                     &cfg.config.api.token_config,
                     mempool_tx_request_sender.clone(),
+                    TokenBlacklist::default(),
                     ChainId(cfg.config.eth_client.chain_id),
                 ))
             },
@@ -36,7 +36,7 @@ use zksync_types::{
     aggregated_operations::AggregatedActionType,
     helpers::{apply_updates, closest_packable_fee_amount, closest_packable_token_amount},
     operations::{ChangePubKeyOp, TransferToNewOp},
-    prover::ProverJobType,
+    prover::{ProverJobType, StoredArtifact},
     tx::ChangePubKeyType,
     AccountId, AccountMap, AccountUpdate, Address, BlockNumber, Deposit, DepositOp,
     ExecutedOperations, ExecutedPriorityOp, ExecutedTx, FullExit, FullExitOp, MintNFTOp, Nonce,
@@ -550,14 +550,14 @@ impl TestServerConfig {
                 let proof = get_sample_single_proof();
                 let aggregated_proof = get_sample_aggregated_proof();
                 ProverSchema(&mut storage)
-                    .store_proof(stored_job_id, block_number, &proof)
+                    .store_proof(stored_job_id, block_number, StoredArtifact::Inline(&proof))
                     .await?;
                 ProverSchema(&mut storage)
                     .store_aggregated_proof(
                         stored_aggregated_job_id,
                         block_number,
                         block_number,
-                        &aggregated_proof,
+                        StoredArtifact::Inline(&aggregated_proof),
                     )
                     .await?;
 
@@ -832,6 +832,9 @@ pub fn dummy_sign_verifier() -> mpsc::Sender<VerifySignatureRequest> {
 #[derive(Debug, Clone)]
 pub struct DummyFeeTickerInfo {
     prices: HashMap<TokenLike, BigDecimal>,
+    /// Whether the price sources are down, so neither the token prices nor the gas price
+    /// can be provided.
+    prices_unavailable: bool,
 }
 
 #[async_trait::async_trait]
@@ -855,6 +858,9 @@ impl FeeTickerInfo for DummyFeeTickerInfo {
     }
 
     async fn get_last_token_price(&self, token: TokenLike) -> Result<TokenPrice, PriceError> {
+        if self.prices_unavailable {
+            return Err(PriceError::api_error("price sources are unavailable"));
+        }
         if let Some(price) = self.prices.get(&token) {
             Ok(TokenPrice {
                 usd_price: big_decimal_to_ratio(price).unwrap(),
@@ -869,6 +875,9 @@ impl FeeTickerInfo for DummyFeeTickerInfo {
     }
 
     async fn get_gas_price_wei(&self) -> Result<BigUint, Error> {
+        if self.prices_unavailable {
+            anyhow::bail!("gas price source is unavailable");
+        }
         Ok(BigUint::from(1u64))
     }
 
@@ -909,7 +918,9 @@ pub fn get_test_ticker_config() -> TickerConfig {
                 t.risk_factor.map(|risk| (id, risk))
             })
             .collect(),
+        token_fee_markups: HashMap::new(),
         scale_fee_coefficient: Ratio::new(BigUint::from(150u32), BigUint::from(100u32)),
+        gas_price_scale_percent: 130,
         max_blocks_to_aggregate: 5,
         subsidy_cpk_price_usd: scaled_u64_to_ratio(SUBSIDY_CPK_PRICE_USD_SCALED),
     }
@@ -919,6 +930,31 @@ pub fn dummy_fee_ticker(
     in_memory_cache: Option<TokenInMemoryCache>,
 ) -> FeeTicker {
     let prices: HashMap<_, _> = prices.iter().cloned().collect();
+    dummy_fee_ticker_with_info(
+        DummyFeeTickerInfo {
+            prices,
+            prices_unavailable: false,
+        },
+        in_memory_cache,
+    )
+}
+
+/// Fee ticker which fails to estimate the fees, as if the price sources are down.
+/// The fee tokens are still validated by the `in_memory_cache`.
+pub fn unavailable_fee_ticker(in_memory_cache: Option<TokenInMemoryCache>) -> FeeTicker {
+    dummy_fee_ticker_with_info(
+        DummyFeeTickerInfo {
+            prices: HashMap::new(),
+            prices_unavailable: true,
+        },
+        in_memory_cache,
+    )
+}
+
+fn dummy_fee_ticker_with_info(
+    info: DummyFeeTickerInfo,
+    in_memory_cache: Option<TokenInMemoryCache>,
+) -> FeeTicker {
     let validator = FeeTokenValidator::new(
         in_memory_cache.unwrap_or_default(),
         chrono::Duration::seconds(100),
@@ -926,9 +962,5 @@ pub fn dummy_fee_ticker(
         Default::default(),
     );
 
-    FeeTicker::new(
-        Box::new(DummyFeeTickerInfo { prices }),
-        get_test_ticker_config(),
-        validator,
-    )
+    FeeTicker::new(Box::new(info), get_test_ticker_config(), validator)
 }
@@ -269,7 +269,7 @@ mod tests {
         transaction::{L2Receipt, TxHashSerializeWrapper},
         ApiVersion,
     };
-    use zksync_mempool::MempoolTransactionRequest;
+    use zksync_mempool::{token_blacklist::TokenBlacklist, MempoolTransactionRequest};
     use zksync_types::{
         tokens::{Token, TokenMarketVolume},
         tx::{
@@ -285,13 +285,16 @@ mod tests {
         let task = tokio::spawn(async move {
             while let Some(tx) = mempool_tx_request_receiver.next().await {
                 match tx {
-                    MempoolTransactionRequest::NewTx(_, resp) => {
+                    MempoolTransactionRequest::NewTx(_, _, resp) => {
                         resp.send(Ok(())).unwrap_or_default()
                     }
                     MempoolTransactionRequest::NewPriorityOps(_, _, resp) => {
                         resp.send(Ok(())).unwrap_or_default()
                     }
-                    MempoolTransactionRequest::NewTxsBatch(_, _, resp) => {
+                    MempoolTransactionRequest::NewTxsBatch(_, _, _, resp) => {
+                        resp.send(Ok(())).unwrap_or_default()
+                    }
+                    MempoolTransactionRequest::RevertPriorityOps(_, resp) => {
                         resp.send(Ok(())).unwrap_or_default()
                     }
                 }
@@ -353,6 +356,7 @@ mod tests {
                     &cfg.config.api.common,
                     &cfg.config.api.token_config,
                     sender.clone(),
+                    TokenBlacklist::default(),
                     ChainId(cfg.config.eth_client.chain_id),
                 ))
             },
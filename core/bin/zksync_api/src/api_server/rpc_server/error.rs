@@ -11,6 +11,9 @@ pub enum RpcErrorCodes {
     IncorrectTx = 103,
     FeeTooLow = 104,
     InappropriateFeeToken = 105,
+    TokenBlacklisted = 106,
+    ServerShuttingDown = 107,
+    TxAcceptancePaused = 108,
 
     MissingEthSignature = 200,
     EIP1271SignatureVerificationFail = 201,
@@ -41,6 +44,10 @@ impl From<TxAddError> for RpcErrorCodes {
             TxAddError::BatchTooBig => Self::Other,
             TxAddError::BatchWithdrawalsOverload => Self::Other,
             TxAddError::EthSignaturesLimitExceeded => Self::Other,
+            TxAddError::TransactionExpired => Self::IncorrectTx,
+            TxAddError::TokenBlacklisted(_) => Self::TokenBlacklisted,
+            TxAddError::ServerShuttingDown => Self::ServerShuttingDown,
+            TxAddError::TxAcceptancePaused => Self::TxAcceptancePaused,
         }
     }
 }
@@ -33,7 +33,7 @@ use self::types::*;
 use super::tx_sender::TxSender;
 use crate::fee_ticker::FeeTicker;
 use ip_insert_middleware::IpInsertMiddleWare;
-use zksync_mempool::MempoolTransactionRequest;
+use zksync_mempool::{token_blacklist::TokenBlacklist, MempoolTransactionRequest};
 
 #[derive(Clone)]
 pub struct RpcApp {
@@ -57,6 +57,7 @@ impl RpcApp {
         confirmations_for_eth_event: u64,
         chain_id: ChainId,
         mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+        token_blacklist: TokenBlacklist,
     ) -> Self {
         let api_requests_caches_size = config.caches_size;
 
@@ -67,6 +68,7 @@ impl RpcApp {
             config,
             token_config,
             mempool_tx_sender,
+            token_blacklist,
             chain_id,
         );
 
@@ -268,6 +270,7 @@ pub fn start_rpc_server(
     common_api_config: &CommonApiConfig,
     token_config: &TokenConfig,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    token_blacklist: TokenBlacklist,
     chain_id: ChainId,
     confirmations_for_eth_event: u64,
 ) -> JoinHandle<()> {
@@ -281,6 +284,7 @@ pub fn start_rpc_server(
         confirmations_for_eth_event,
         chain_id,
         mempool_tx_sender,
+        token_blacklist,
     );
 
     let (handler, panic_sender) = spawn_panic_handler();
@@ -263,7 +263,8 @@ impl RpcApp {
                 if *id == 0 {
                     ("ETH".to_string(), token)
                 } else {
-                    (token.symbol.clone(), token)
+                    // Symbols can be shared by several tokens, the display symbols are unique.
+                    (token.display_symbol.clone(), token)
                 }
             })
             .collect();
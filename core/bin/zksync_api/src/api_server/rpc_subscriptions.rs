@@ -12,7 +12,7 @@ use jsonrpc_ws_server::RequestContext;
 use tokio::task::JoinHandle;
 // Workspace uses
 use zksync_config::configs::api::{CommonApiConfig, JsonRpcConfig, TokenConfig};
-use zksync_mempool::MempoolTransactionRequest;
+use zksync_mempool::{token_blacklist::TokenBlacklist, MempoolTransactionRequest};
 use zksync_storage::ConnectionPool;
 use zksync_types::{tx::TxHash, ActionType, Address, ChainId};
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
@@ -187,6 +187,7 @@ pub fn start_ws_server(
     config: &JsonRpcConfig,
     miniblock_iteration_interval: Duration,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    token_blacklist: TokenBlacklist,
     confirmations_for_eth_event: u64,
     chain_id: ChainId,
 ) -> JoinHandle<()> {
@@ -211,6 +212,7 @@ pub fn start_ws_server(
         confirmations_for_eth_event,
         chain_id,
         mempool_tx_sender,
+        token_blacklist,
     );
 
     let (handler, panic_sender) = spawn_panic_handler();
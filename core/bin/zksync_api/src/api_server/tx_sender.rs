@@ -1,7 +1,6 @@
 //! Helper module to submit transactions into the zkSync Network.
 
 // Built-in uses
-use std::iter::FromIterator;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
@@ -21,10 +20,13 @@ use num::{bigint::ToBigInt, BigUint, Zero};
 use thiserror::Error;
 
 // Workspace uses
+use vlog::Instrument;
 use zksync_api_types::{
     v02::transaction::{SubmitBatchResponse, Toggle2FA, Toggle2FAResponse, TxHashSerializeWrapper},
     TxWithSignature,
 };
+use zksync_crypto::params::MIN_NFT_TOKEN_ID;
+use zksync_prometheus_exporter::labels::report_op_received;
 use zksync_storage::misc::records::Subsidy;
 use zksync_storage::{chain::account::records::EthAccountType, ConnectionPool};
 use zksync_token_db_cache::TokenDBCache;
@@ -33,7 +35,8 @@ use zksync_types::{
         EthBatchSignData, EthBatchSignatures, EthSignData, Order, SignedZkSyncTx, TxEthSignature,
         TxEthSignatureVariant, TxHash,
     },
-    AccountId, Address, ChainId, PubKeyHash, Token, TokenId, TokenLike, TxFeeTypes, ZkSyncTx, H160,
+    AccountId, Address, ChainId, FeeTokenPriceFloor, PubKeyHash, Token, TokenId, TokenLike,
+    TxFeeTypes, ZkSyncTx, H160,
 };
 use zksync_utils::{
     big_decimal_to_ratio, biguint_to_big_decimal, ratio_to_scaled_u64, scaled_big_decimal_to_ratio,
@@ -50,18 +53,30 @@ use crate::{
     tx_error::Toggle2FAError,
     utils::block_details_cache::BlockDetailsCache,
 };
-use zksync_config::configs::api::{CommonApiConfig, TokenConfig};
-use zksync_mempool::MempoolTransactionRequest;
+use zksync_config::{
+    configs::api::{CommonApiConfig, TokenConfig},
+    loader::Reloadable,
+};
+use zksync_mempool::{
+    fee_free_whitelist::FeeFreeWhitelist, token_blacklist::TokenBlacklist,
+    MempoolTransactionRequest,
+};
 use zksync_types::tx::error::TxAddError;
 
 use super::rpc_server::types::RequestMetadata;
 use crate::fee_ticker::{FeeTicker, PriceError};
 
 const VALIDNESS_INTERVAL_MINUTES: i64 = 40;
+/// Subsidy type of the fees waived for the fee-free accounts and transaction types.
+const FEE_FREE_SUBSIDY_TYPE: &str = "FeeFree";
+/// Subsidy type of the fees waived for the withdrawals granted by the free withdrawal quota.
+const FREE_WITHDRAWAL_SUBSIDY_TYPE: &str = "FreeWithdrawal";
 
 #[derive(Clone)]
 pub struct TxSender {
     pub mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    /// Tokens blacklisted by the operators, kept updated by the caller.
+    pub token_blacklist: TokenBlacklist,
     pub sign_verify_requests: mpsc::Sender<VerifySignatureRequest>,
     pub ticker: FeeTicker,
 
@@ -70,12 +85,15 @@ pub struct TxSender {
 
     pub forced_exit_checker: ForcedExitChecker,
     pub blocks: BlockDetailsCache,
-    /// List of account IDs that do not have to pay fees for operations.
-    pub fee_free_accounts: HashSet<AccountId>,
+    /// Account IDs and transaction types that do not have to pay fees.
+    pub fee_free_whitelist: FeeFreeWhitelist,
     pub enforce_pubkey_change_fee: bool,
-    // Limit the number of both transactions and Ethereum signatures per batch.
-    pub max_number_of_transactions_per_batch: usize,
-    pub max_number_of_authors_per_batch: usize,
+    /// Limits of the number of both transactions and Ethereum signatures per batch,
+    /// and the free withdrawal quota: accounts with the total balance below
+    /// `free_withdrawal_max_balance_usd` can make one withdrawal per `free_withdrawal_period`
+    /// without the fee, the quota is disabled if the balance is zero.
+    /// The limits are updated when the config is reloaded.
+    pub limits: Reloadable<CommonApiConfig>,
 
     pub current_subsidy_type: String,
     pub max_subsidy_usd: Ratio<BigUint>,
@@ -83,6 +101,14 @@ pub struct TxSender {
     pub chain_id: ChainId,
 }
 
+/// Fee of the fee-free transaction covered by the operator.
+#[derive(Debug, Clone)]
+pub struct WaivedFee {
+    pub normal_fee: BigUint,
+    pub provided_fee: BigUint,
+    pub token_id: TokenId,
+}
+
 #[derive(Debug, Error)]
 pub enum SubmitError {
     #[error("Account close tx is disabled.")]
@@ -143,6 +169,7 @@ macro_rules! internal_error {
 }
 
 impl TxSender {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         connection_pool: ConnectionPool,
         sign_verify_request_sender: mpsc::Sender<VerifySignatureRequest>,
@@ -150,14 +177,12 @@ impl TxSender {
         config: &CommonApiConfig,
         token_config: &TokenConfig,
         mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+        token_blacklist: TokenBlacklist,
         chain_id: ChainId,
     ) -> Self {
-        let max_number_of_transactions_per_batch =
-            config.max_number_of_transactions_per_batch as usize;
-        let max_number_of_authors_per_batch = config.max_number_of_authors_per_batch as usize;
-
         Self {
             mempool_tx_sender,
+            token_blacklist,
             pool: connection_pool,
             sign_verify_requests: sign_verify_request_sender,
             ticker,
@@ -168,9 +193,11 @@ impl TxSender {
             enforce_pubkey_change_fee: config.enforce_pubkey_change_fee,
             blocks: BlockDetailsCache::new(config.caches_size),
 
-            fee_free_accounts: HashSet::from_iter(config.fee_free_accounts.clone()),
-            max_number_of_transactions_per_batch,
-            max_number_of_authors_per_batch,
+            fee_free_whitelist: FeeFreeWhitelist::new(
+                config.fee_free_accounts.clone(),
+                config.fee_free_tx_types.clone(),
+            ),
+            limits: config.clone().into_reloadable(),
             current_subsidy_type: config.subsidy_name.clone(),
             max_subsidy_usd: config.max_subsidy_usd(),
             subsidized_ips: config.subsidized_ips.clone().into_iter().collect(),
@@ -450,6 +477,7 @@ impl TxSender {
         normal_fee: BigUint,
         subsidized_fee: BigUint,
         token_id: TokenId,
+        subsidy_type: String,
     ) -> Result<(), anyhow::Error> {
         let token_price_in_usd = self
             .ticker
@@ -470,7 +498,7 @@ impl TxSender {
             token_id,
             token_amount: biguint_to_big_decimal(subsidized_fee),
             full_cost_token: biguint_to_big_decimal(normal_fee),
-            subsidy_type: self.current_subsidy_type.clone(),
+            subsidy_type,
             tx_hash: hash,
         };
 
@@ -484,51 +512,256 @@ impl TxSender {
         Ok(())
     }
 
+    /// Stores the fee token price floor of the transaction before it's sent to the mempool,
+    /// so its fee is checked again when the transaction is proposed for the block.
+    async fn store_fee_token_price_floor(
+        &self,
+        hash: TxHash,
+        floor: &FeeTokenPriceFloor,
+    ) -> Result<(), SubmitError> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        storage
+            .chain()
+            .mempool_schema()
+            .store_fee_token_price_floor(hash, floor)
+            .await
+            .map_err(SubmitError::internal)
+    }
+
+    /// Checks whether the transaction is accepted without the fee, since either its initiator
+    /// or its type is whitelisted.
+    pub fn is_fee_free(&self, tx: &ZkSyncTx) -> bool {
+        self.fee_free_whitelist.is_fee_free(tx)
+    }
+
+    /// Returns the part of the normal fee of the fee-free transaction which is not provided,
+    /// so it's covered by the operator.
+    ///
+    /// The waiver doesn't depend on the ticker: if the normal fee can't be estimated, the
+    /// transaction is still accepted and only its waived fee is missing from the analytics.
+    async fn waived_fee(&self, tx: &ZkSyncTx) -> Option<WaivedFee> {
+        match self.estimate_waived_fee(tx).await {
+            Ok(waived_fee) => waived_fee,
+            Err(err) => {
+                metrics::increment_counter!("tx_sender.estimate_waived_fee_fail");
+                vlog::warn!(
+                    "Failed to estimate the waived fee of the tx {}: {}",
+                    tx.hash().to_string(),
+                    err
+                );
+                None
+            }
+        }
+    }
+
+    async fn estimate_waived_fee(&self, tx: &ZkSyncTx) -> Result<Option<WaivedFee>, SubmitError> {
+        let (tx_type, token, address, provided_fee) = match tx.get_fee_info() {
+            Some(fee_info) => fee_info,
+            None => return Ok(None),
+        };
+        // Fees in the tokens not allowed for paying fees can't be estimated.
+        if !self.ticker.token_allowed_for_fees(token.clone()).await? {
+            return Ok(None);
+        }
+
+        let normal_fee = self
+            .ticker
+            .get_fee_from_ticker_in_wei(tx_type, token.clone(), address)
+            .await?
+            .normal_fee
+            .total_fee;
+        if provided_fee >= normal_fee {
+            return Ok(None);
+        }
+        let token = self.token_info_from_id(token).await?;
+
+        Ok(Some(WaivedFee {
+            normal_fee,
+            provided_fee,
+            token_id: token.id,
+        }))
+    }
+
+    /// Checks whether the withdrawal with the insufficient fee can be granted by the free
+    /// withdrawal quota, i.e. the total balance of the account is below the threshold.
+    /// The quota is not granted if any of the fungible tokens of the account can't be valued.
+    /// Whether the quota of the current period is already used is checked by `use_free_withdrawal`.
+    async fn free_withdrawal_allowed(&self, tx: &ZkSyncTx) -> Result<bool, SubmitError> {
+        let max_balance_usd = self.limits.get().free_withdrawal_max_balance_usd();
+        if max_balance_usd.is_zero() {
+            return Ok(false);
+        }
+        let account_id = match tx {
+            ZkSyncTx::Withdraw(withdraw) => withdraw.account_id,
+            _ => return Ok(false),
+        };
+
+        let account = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?
+            .chain()
+            .account_schema()
+            .last_committed_state_for_account(account_id)
+            .await
+            .map_err(SubmitError::internal)?
+            .1;
+        let account = match account {
+            Some(account) => account,
+            None => return Ok(false),
+        };
+
+        let mut balance_usd = Ratio::from_integer(BigUint::zero());
+        for (token_id, balance) in account.get_nonzero_balances() {
+            // NFTs have no price.
+            if token_id.0 >= MIN_NFT_TOKEN_ID {
+                continue;
+            }
+            // The balance in the token without the price may exceed the threshold.
+            let price = match self
+                .ticker
+                .get_token_price(TokenLike::Id(token_id), TokenPriceRequestType::USDForOneWei)
+                .await
+            {
+                Ok(price) => price,
+                Err(_) => return Ok(false),
+            };
+            balance_usd += big_decimal_to_ratio(&price).map_err(SubmitError::internal)? * balance.0;
+            if balance_usd >= max_balance_usd {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Uses the free withdrawal quota of the account for the current period.
+    async fn use_free_withdrawal(&self, tx: &ZkSyncTx) -> Result<(), SubmitError> {
+        let account_id = tx
+            .account_id()
+            .map_err(|_| SubmitError::AccountCloseDisabled)?;
+        let period = Duration::from_std(self.limits.get().free_withdrawal_period())
+            .map_err(SubmitError::internal)?;
+        let period_start = Utc::now() - period;
+        let granted = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?
+            .misc_schema()
+            .use_free_withdrawal(account_id, tx.hash(), period_start)
+            .await
+            .map_err(SubmitError::internal)?;
+
+        if !granted {
+            return Err(SubmitError::TxAdd(TxAddError::TxFeeTooLow));
+        }
+        metrics::increment_counter!("tx_sender.free_withdrawals");
+        Ok(())
+    }
+
+    /// Returns the free withdrawal of the rejected transaction back to the quota.
+    /// Failures are only logged, since the transaction is already rejected.
+    async fn revert_free_withdrawal(&self, hash: TxHash) {
+        let result = match self.pool.access_storage().await {
+            Ok(mut storage) => storage.misc_schema().revert_free_withdrawal(hash).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            vlog::warn!(
+                "Failed to revert the free withdrawal of the tx {}: {}",
+                hash.to_string(),
+                err
+            );
+        }
+    }
+
+    /// Stores the waived fee of the accepted transaction for the fee analytics.
+    /// Failures are only logged, since the transaction is already accepted.
+    async fn store_waived_fee(&self, hash: TxHash, waived_fee: WaivedFee, subsidy_type: &str) {
+        if let Err(err) = self
+            .store_subsidy_data(
+                hash,
+                waived_fee.normal_fee,
+                waived_fee.provided_fee,
+                waived_fee.token_id,
+                subsidy_type.to_string(),
+            )
+            .await
+        {
+            metrics::increment_counter!("tx_sender.store_waived_fee_fail");
+            vlog::warn!(
+                "Failed to store the waived fee of the tx {}: {}",
+                hash.to_string(),
+                err
+            );
+        }
+    }
+
     pub async fn submit_tx(
         &self,
         mut tx: ZkSyncTx,
         signature: TxEthSignatureVariant,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<TxHash, SubmitError> {
-        let labels = vec![
-            ("stage", "api".to_string()),
-            ("name", tx.variance_name()),
-            ("token", tx.token_id().to_string()),
-        ];
+        // The chain id is a part of the signed data, so it must be set before the hash is taken.
+        if let ZkSyncTx::ChangePubKey(change_pub_key) = &mut tx {
+            change_pub_key.chain_id = Some(self.chain_id)
+        };
+
+        // The hash is the correlation id of the logs related to the transaction,
+        // the mempool and the state keeper log it the same way.
+        let tx_hash = tx.hash();
+        let span = vlog::info_span!("submit_tx", tx_hash = %tx_hash.to_string());
+        let result = self
+            .submit_tx_inner(tx, signature, extracted_request_metadata)
+            .instrument(span.clone())
+            .await;
+        span.in_scope(|| match &result {
+            Ok(_) => vlog::debug!("Transaction is accepted by the mempool"),
+            Err(err) => vlog::debug!("Transaction is rejected: {}", err),
+        });
+        result
+    }
+
+    async fn submit_tx_inner(
+        &self,
+        tx: ZkSyncTx,
+        signature: TxEthSignatureVariant,
+        extracted_request_metadata: Option<RequestMetadata>,
+    ) -> Result<TxHash, SubmitError> {
         // The initial state of processing tx
-        metrics::increment_counter!("process_tx_count", &labels);
+        report_op_received("api", tx.variance_name(), tx.token_id());
 
         if tx.is_close() {
             return Err(SubmitError::AccountCloseDisabled);
         }
+        self.check_token_blacklist(std::iter::once(&tx))?;
 
         if let ZkSyncTx::ForcedExit(forced_exit) = &tx {
             self.check_forced_exit(forced_exit).await?;
         }
-        if let ZkSyncTx::ChangePubKey(change_pub_key) = &mut tx {
-            change_pub_key.chain_id = Some(self.chain_id)
-        };
-
         // Resolve the token.
         let token = self.token_info_from_id(tx.token_id()).await?;
-        let msg_to_sign = tx
-            .get_ethereum_sign_message(token.clone())
-            .map(String::into_bytes);
-
-        let is_whitelisted_initiator = tx
-            .account_id()
-            .map(|account_id| self.fee_free_accounts.contains(&account_id))
-            .unwrap_or(false);
+        let msg_to_sign =
+            eth_sign_message(&tx, signature.tx_signature(), token.clone(), self.chain_id)?;
 
-        let tx_fee_info = if !is_whitelisted_initiator {
-            tx.get_fee_info()
+        let is_fee_free = self.is_fee_free(&tx);
+        let (tx_fee_info, waived_fee) = if is_fee_free {
+            (None, self.waived_fee(&tx).await)
         } else {
-            None
+            (tx.get_fee_info(), None)
         };
 
         let sign_verify_channel = self.sign_verify_requests.clone();
 
         let mut fee_data_for_subsidy: Option<ResponseFee> = None;
+        let mut free_withdrawal_fee: Option<WaivedFee> = None;
+        let mut fee_price_floor: Option<FeeTokenPriceFloor> = None;
 
         if let Some((tx_type, token, address, provided_fee)) = tx_fee_info {
             let should_enforce_fee = !matches!(tx_type, TxFeeTypes::ChangePubKey { .. })
@@ -562,11 +795,24 @@ impl TxSender {
 
             // Converting `BitUint` to `BigInt` is safe.
             let required_fee: BigDecimal = required_fee_data.total_fee.to_bigint().unwrap().into();
-            let provided_fee: BigDecimal = provided_fee.to_bigint().unwrap().into();
+            let provided_fee_decimal: BigDecimal = provided_fee.to_bigint().unwrap().into();
             // Scaling the fee required since the price may change between signing the transaction and sending it to the server.
-            let scaled_provided_fee = scale_user_fee_up(provided_fee);
+            let scaled_provided_fee = scale_user_fee_up(provided_fee_decimal);
             if required_fee >= scaled_provided_fee && should_enforce_fee {
-                return Err(SubmitError::TxAdd(TxAddError::TxFeeTooLow));
+                if !self.free_withdrawal_allowed(&tx).await? {
+                    return Err(SubmitError::TxAdd(TxAddError::TxFeeTooLow));
+                }
+                free_withdrawal_fee = Some(WaivedFee {
+                    normal_fee: required_fee_data.total_fee,
+                    provided_fee,
+                    token_id: tx.token_id(),
+                });
+            } else if should_enforce_fee {
+                let fee_token = self.token_info_from_id(token).await?;
+                fee_price_floor = self
+                    .ticker
+                    .fee_token_price_floor(&fee_token, &required_fee_data.total_fee, &provided_fee)
+                    .await?;
             }
         }
 
@@ -598,15 +844,31 @@ impl TxSender {
                 .await?;
         }
 
+        // The quota is used right before the withdrawal is sent to the mempool,
+        // and returned back if the mempool doesn't accept it.
+        if free_withdrawal_fee.is_some() {
+            self.use_free_withdrawal(&tx).await?;
+        }
+        if let Some(floor) = &fee_price_floor {
+            self.store_fee_token_price_floor(tx.hash(), floor).await?;
+        }
+
         let (sender, receiver) = oneshot::channel();
-        let item = MempoolTransactionRequest::NewTx(Box::new(verified_tx), sender);
+        let item = MempoolTransactionRequest::NewTx(Box::new(verified_tx), is_fee_free, sender);
         let mut mempool_sender = self.mempool_tx_sender.clone();
-        mempool_sender
-            .send(item)
-            .await
-            .map_err(SubmitError::internal)?;
-
-        receiver.await.map_err(SubmitError::internal)??;
+        let mempool_result = match mempool_sender.send(item).await {
+            Ok(()) => receiver
+                .await
+                .map_err(SubmitError::internal)
+                .and_then(|result| result.map_err(SubmitError::from)),
+            Err(err) => Err(SubmitError::internal(err)),
+        };
+        if let Err(err) = mempool_result {
+            if free_withdrawal_fee.is_some() {
+                self.revert_free_withdrawal(tx.hash()).await;
+            }
+            return Err(err);
+        }
 
         // fee_data_for_subsidy has Some value only if the batch of transactions is subsidised
         if let Some(fee_data_for_subsidy) = fee_data_for_subsidy {
@@ -621,6 +883,7 @@ impl TxSender {
                 fee_data_for_subsidy.normal_fee.total_fee,
                 fee_data_for_subsidy.subsidized_fee.total_fee,
                 token.id,
+                self.current_subsidy_type.clone(),
             )
             .await
             .map_err(|e| {
@@ -632,6 +895,15 @@ impl TxSender {
             })?;
         }
 
+        if let Some(waived_fee) = waived_fee {
+            self.store_waived_fee(tx.hash(), waived_fee, FEE_FREE_SUBSIDY_TYPE)
+                .await;
+        }
+        if let Some(free_withdrawal_fee) = free_withdrawal_fee {
+            self.store_waived_fee(tx.hash(), free_withdrawal_fee, FREE_WITHDRAWAL_SUBSIDY_TYPE)
+                .await;
+        }
+
         // if everything is OK, return the transactions hashes.
         Ok(tx.hash())
     }
@@ -648,30 +920,36 @@ impl TxSender {
         if txs.is_empty() {
             return Err(SubmitError::TxAdd(TxAddError::EmptyBatch));
         }
+        let limits = self.limits.get();
         // Even though this is going to be checked on the Mempool part,
         // we don't want to verify huge batches as long as this operation
         // is expensive.
-        if txs.len() > self.max_number_of_transactions_per_batch {
+        if txs.len() > limits.max_number_of_transactions_per_batch as usize {
             return Err(SubmitError::TxAdd(TxAddError::BatchTooBig));
         }
 
         for tx in &txs {
-            let labels = vec![
-                ("stage", "api".to_string()),
-                ("name", tx.tx.variance_name()),
-                ("token", tx.tx.token_id().to_string()),
-            ];
-            metrics::increment_counter!("process_tx_count", &labels);
+            report_op_received("api", tx.tx.variance_name(), tx.tx.token_id());
         }
 
         // Same check but in terms of signatures.
-        if eth_signatures.len() > self.max_number_of_authors_per_batch {
+        if eth_signatures.len() > limits.max_number_of_authors_per_batch as usize {
             return Err(SubmitError::TxAdd(TxAddError::EthSignaturesLimitExceeded));
         }
+        // The batch message is always human-readable, there is no typed data for it.
+        if eth_signatures
+            .iter()
+            .any(|signature| matches!(signature, TxEthSignature::EIP712Signature(_)))
+        {
+            return Err(SubmitError::IncorrectTx(
+                "EIP-712 signature is not supported for batch signatures".to_string(),
+            ));
+        }
 
         if txs.iter().any(|tx| tx.tx.is_close()) {
             return Err(SubmitError::AccountCloseDisabled);
         }
+        self.check_token_blacklist(txs.iter().map(|tx| &tx.tx))?;
 
         // Checking fees data
         let mut provided_total_usd_fee = BigDecimal::from(0);
@@ -682,13 +960,27 @@ impl TxSender {
         let mut token_fees = HashMap::<Address, BigUint>::new();
         let mut token_fees_ids = vec![];
 
+        let mut waived_fees = Vec::new();
+        let mut fee_free_txs = Vec::new();
+
         for tx in &txs {
             let tx_fee_info = tx.tx.get_fee_info();
+            // Fee-free transactions don't increase the required batch fee,
+            // but the fee they provide still counts.
+            let is_fee_free = self.is_fee_free(&tx.tx);
+            if is_fee_free {
+                fee_free_txs.push(tx.tx.hash());
+                if let Some(waived_fee) = self.waived_fee(&tx.tx).await {
+                    waived_fees.push((tx.tx.hash(), waived_fee));
+                }
+            }
 
             if let Some((tx_type, token, address, provided_fee)) = tx_fee_info {
                 // Save the transaction type before moving on to the next one, otherwise
                 // the total fee won't get affected by it.
-                transaction_types.push((tx_type, address));
+                if !is_fee_free {
+                    transaction_types.push((tx_type, address));
+                }
 
                 if provided_fee == BigUint::zero() {
                     continue;
@@ -728,6 +1020,8 @@ impl TxSender {
         }
 
         let mut fee_data_for_subsidy: Option<ResponseBatchFee> = None;
+        // The fees paid in several tokens are checked in ETH, so the floor is used only for the single fee token.
+        let mut fee_price_floor: Option<FeeTokenPriceFloor> = None;
 
         // Only one token in batch
         if token_fees.len() == 1 {
@@ -765,6 +1059,12 @@ impl TxSender {
                 );
                 return Err(SubmitError::TxAdd(TxAddError::TxBatchFeeTooLow));
             }
+
+            let fee_token = self.token_info_from_id(batch_token).await?;
+            fee_price_floor = self
+                .ticker
+                .fee_token_price_floor(&fee_token, &required_fee, &fee_paid)
+                .await?;
         } else {
             // Calculate required fee for ethereum token
             let required_eth_fee = self
@@ -829,12 +1129,17 @@ impl TxSender {
         let mut tx_senders = Vec::with_capacity(txs.len());
         let mut tx_sender_types = Vec::with_capacity(txs.len());
         let mut tokens = Vec::with_capacity(txs.len());
-        for tx in txs.iter().map(|tx| &tx.tx) {
+        for TxWithSignature { tx, signature } in txs.iter() {
             // Resolve the token and save it for constructing the batch message.
             let token = self.token_info_from_id(tx.token_id()).await?;
             tokens.push(token.clone());
 
-            messages_to_sign.push(tx.get_ethereum_sign_message(token).map(String::into_bytes));
+            messages_to_sign.push(eth_sign_message(
+                tx,
+                signature.tx_signature(),
+                token,
+                self.chain_id,
+            )?);
             tx_senders.push(
                 self.get_tx_sender(tx)
                     .await
@@ -882,19 +1187,33 @@ impl TxSender {
         verified_txs.extend(verified_batch.into_iter());
 
         let tx_hashes: Vec<TxHash> = verified_txs.iter().map(|tx| tx.tx.hash()).collect();
+        // The batch hash is the correlation id of the logs related to the batch.
+        let batch_hash = TxHash::batch_hash(&tx_hashes);
+
+        // The batch is executed as a whole, so the floor is stored for its first transaction.
+        if let Some(floor) = &fee_price_floor {
+            self.store_fee_token_price_floor(tx_hashes[0], floor)
+                .await?;
+        }
 
         let (sender, receiver) = oneshot::channel();
-        let item =
-            MempoolTransactionRequest::NewTxsBatch(verified_txs, verified_signatures, sender);
+        let item = MempoolTransactionRequest::NewTxsBatch(
+            verified_txs,
+            verified_signatures,
+            fee_free_txs,
+            sender,
+        );
         let mut mempool_sender = self.mempool_tx_sender.clone();
         mempool_sender
             .send(item)
             .await
             .map_err(SubmitError::mempool_communication)?;
 
-        receiver.await.map_err(SubmitError::internal)??;
-
-        let batch_hash = TxHash::batch_hash(&tx_hashes);
+        receiver.await.map_err(SubmitError::internal)?.map_err(|err| {
+            vlog::debug!(batch_hash = %batch_hash.to_string(), "Transactions batch is rejected: {}", err);
+            err
+        })?;
+        vlog::debug!(batch_hash = %batch_hash.to_string(), "Transactions batch is accepted by the mempool");
 
         // fee_data_for_subsidy has Some value only if the batch of transactions is subsidised
         if let Some(fee_data) = fee_data_for_subsidy {
@@ -919,6 +1238,7 @@ impl TxSender {
                 fee_data.normal_fee.total_fee,
                 fee_data.subsidized_fee.total_fee,
                 subsidy_token_id,
+                self.current_subsidy_type.clone(),
             )
             .await
             .map_err(|e| {
@@ -931,6 +1251,11 @@ impl TxSender {
             })?;
         }
 
+        for (hash, waived_fee) in waived_fees {
+            self.store_waived_fee(hash, waived_fee, FEE_FREE_SUBSIDY_TYPE)
+                .await;
+        }
+
         Ok(SubmitBatchResponse {
             transaction_hashes: tx_hashes.into_iter().map(TxHashSerializeWrapper).collect(),
             batch_hash,
@@ -957,6 +1282,20 @@ impl TxSender {
             .await
     }
 
+    /// Rejects the transactions using the blacklisted tokens before the expensive checks,
+    /// the mempool checks the blacklist again when the transactions are added.
+    fn check_token_blacklist<'a>(
+        &self,
+        txs: impl Iterator<Item = &'a ZkSyncTx>,
+    ) -> Result<(), SubmitError> {
+        for tx in txs {
+            if let Some(token) = self.token_blacklist.blacklisted_token(tx) {
+                return Err(SubmitError::TxAdd(TxAddError::TokenBlacklisted(token)));
+            }
+        }
+        Ok(())
+    }
+
     /// Returns a message that user has to sign to send the transaction.
     /// If the transaction doesn't need a message signature, returns `None`.
     /// If any error is encountered during the message generation, returns `jsonrpc_core::Error`.
@@ -1037,6 +1376,31 @@ async fn send_verify_request_and_recv(
         .map_err(SubmitError::TxAdd)
 }
 
+/// Returns the message the Ethereum signature of the transaction has to be checked against.
+/// EIP-712 signatures are checked against the typed data of the transaction, the rest
+/// are checked against the human-readable message.
+fn eth_sign_message(
+    tx: &ZkSyncTx,
+    signature: &Option<TxEthSignature>,
+    token: Token,
+    chain_id: ChainId,
+) -> Result<Option<Vec<u8>>, SubmitError> {
+    if let Some(TxEthSignature::EIP712Signature(_)) = signature {
+        // Falling back to the text message would let the signature be checked against
+        // a message it was never meant for, so the unsupported transactions are rejected.
+        return tx
+            .get_eip712_sign_message(chain_id)
+            .map(Some)
+            .ok_or_else(|| {
+                SubmitError::IncorrectTx(format!(
+                    "EIP-712 signature is not supported for {} transactions",
+                    tx.variance_name()
+                ))
+            });
+    }
+    Ok(tx.get_ethereum_sign_message(token).map(String::into_bytes))
+}
+
 /// Send a request for Ethereum signature verification and wait for the response.
 /// If `msg_to_sign` is not `None`, then the signature must be present.
 async fn verify_tx_info_message_signature(
@@ -1231,6 +1595,235 @@ fn scale_user_fee_up(provided_total_usd_fee: BigDecimal) -> BigDecimal {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        api_server::rest::v02::test_utils::{
+            dummy_fee_ticker, dummy_sign_verifier, unavailable_fee_ticker, TestServerConfig,
+        },
+        fee_ticker::validator::cache::TokenInMemoryCache,
+    };
+    use zksync_test_account::ZkSyncAccount;
+    use zksync_types::{tokens::TokenMarketVolume, Nonce, TokenKind};
+
+    /// Account whitelisted to be accepted without the fee by the test tx sender.
+    const FEE_FREE_ACCOUNT_ID: AccountId = AccountId(0xfee);
+
+    /// Fee ticker knowing the ETH price, or failing to estimate the fees if `prices_available`
+    /// is not set. In both cases ETH is allowed for paying fees.
+    fn test_ticker(prices_available: bool) -> FeeTicker {
+        let eth = Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20);
+        let mut tokens = HashMap::new();
+        tokens.insert(TokenLike::Id(TokenId(0)), eth);
+        let mut market = HashMap::new();
+        market.insert(
+            TokenId(0),
+            TokenMarketVolume {
+                market_volume: Ratio::from_integer(BigUint::from(400u32)),
+                last_updated: Utc::now(),
+            },
+        );
+        let cache = TokenInMemoryCache::new()
+            .with_tokens(tokens)
+            .with_market(market);
+
+        if prices_available {
+            let prices = [(TokenLike::Id(TokenId(0)), 10500u64.into())];
+            dummy_fee_ticker(&prices, Some(cache))
+        } else {
+            unavailable_fee_ticker(Some(cache))
+        }
+    }
+
+    /// Mempool accepting or rejecting all the transactions sent to it.
+    fn mempool_stub(accept: bool) -> mpsc::Sender<MempoolTransactionRequest> {
+        let (sender, mut receiver) = mpsc::channel(10);
+        actix_rt::spawn(async move {
+            while let Some(request) = receiver.next().await {
+                if let MempoolTransactionRequest::NewTx(_, _, response) = request {
+                    let result = if accept {
+                        Ok(())
+                    } else {
+                        Err(TxAddError::Other)
+                    };
+                    response.send(result).ok();
+                }
+            }
+        });
+        sender
+    }
+
+    fn test_tx_sender(
+        cfg: &TestServerConfig,
+        ticker: FeeTicker,
+        mempool: mpsc::Sender<MempoolTransactionRequest>,
+    ) -> TxSender {
+        let mut config = cfg.config.api.common.clone();
+        config.fee_free_accounts = vec![FEE_FREE_ACCOUNT_ID];
+        config.fee_free_tx_types = Vec::new();
+
+        TxSender::new(
+            cfg.pool.clone(),
+            dummy_sign_verifier(),
+            ticker,
+            &config,
+            &cfg.config.api.token_config,
+            mempool,
+            TokenBlacklist::default(),
+            ChainId(cfg.config.eth_client.chain_id),
+        )
+    }
+
+    fn transfer_without_fee(account_id: AccountId) -> (ZkSyncTx, TxEthSignatureVariant) {
+        let account = ZkSyncAccount::rand();
+        account.set_account_id(Some(account_id));
+        let (transfer, eth_signature) = account.sign_transfer(
+            TokenId(0),
+            "ETH",
+            100u64.into(),
+            BigUint::zero(),
+            &Address::random(),
+            Some(Nonce(0)),
+            false,
+            Default::default(),
+        );
+        (
+            ZkSyncTx::Transfer(Box::new(transfer)),
+            TxEthSignatureVariant::Single(eth_signature.map(TxEthSignature::EthereumSignature)),
+        )
+    }
+
+    fn withdraw_without_fee(account_id: AccountId) -> (ZkSyncTx, TxEthSignatureVariant) {
+        let account = ZkSyncAccount::rand();
+        account.set_account_id(Some(account_id));
+        let (withdraw, eth_signature) = account.sign_withdraw(
+            TokenId(0),
+            "ETH",
+            100u64.into(),
+            BigUint::zero(),
+            &Address::random(),
+            Some(Nonce(0)),
+            false,
+            Default::default(),
+        );
+        (
+            ZkSyncTx::Withdraw(Box::new(withdraw)),
+            TxEthSignatureVariant::Single(eth_signature.map(TxEthSignature::EthereumSignature)),
+        )
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn fee_free_accounts() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let tx_sender = test_tx_sender(&cfg, test_ticker(true), mempool_stub(true));
+
+        // The fee of the whitelisted account is waived, the normal fee is kept for the analytics.
+        let (tx, signature) = transfer_without_fee(FEE_FREE_ACCOUNT_ID);
+        assert!(tx_sender.is_fee_free(&tx));
+        let waived_fee = tx_sender.waived_fee(&tx).await.expect("fee is not waived");
+        assert_eq!(waived_fee.token_id, TokenId(0));
+        assert!(waived_fee.provided_fee.is_zero());
+        assert!(!waived_fee.normal_fee.is_zero());
+        tx_sender.submit_tx(tx, signature, None).await?;
+
+        // Other accounts have to pay the fee.
+        let (tx, signature) = transfer_without_fee(AccountId(0xbeef));
+        assert!(!tx_sender.is_fee_free(&tx));
+        let err = tx_sender.submit_tx(tx, signature, None).await.unwrap_err();
+        assert!(
+            matches!(err, SubmitError::TxAdd(TxAddError::TxFeeTooLow)),
+            "unexpected error: {}",
+            err
+        );
+
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn fee_free_accounts_without_ticker() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let tx_sender = test_tx_sender(&cfg, test_ticker(false), mempool_stub(true));
+
+        // The waiver doesn't depend on the ticker, only the waived fee is unknown.
+        let (tx, signature) = transfer_without_fee(FEE_FREE_ACCOUNT_ID);
+        assert!(tx_sender.waived_fee(&tx).await.is_none());
+        tx_sender.submit_tx(tx, signature, None).await?;
+
+        // The fee of other accounts can't be checked without the ticker.
+        let (tx, signature) = transfer_without_fee(AccountId(0xbeef));
+        tx_sender.submit_tx(tx, signature, None).await.unwrap_err();
+
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn free_withdrawal_quota() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        // Any account of the test database is eligible for the quota.
+        let mut limits = cfg.config.api.common.clone();
+        limits.free_withdrawal_max_balance_usd_scaled = u64::MAX;
+        let account_id = *cfg
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .state_schema()
+            .load_committed_state(None)
+            .await?
+            .1
+            .keys()
+            .min()
+            .unwrap();
+
+        // The balance can't be valued without the prices, so the quota is not granted.
+        let mut tx_sender = test_tx_sender(&cfg, test_ticker(false), mempool_stub(true));
+        tx_sender.limits = limits.clone().into_reloadable();
+        let (tx, _) = withdraw_without_fee(account_id);
+        assert!(!tx_sender.free_withdrawal_allowed(&tx).await?);
+
+        // The quota is returned back if the withdrawal is rejected by the mempool.
+        let mut tx_sender = test_tx_sender(&cfg, test_ticker(true), mempool_stub(false));
+        tx_sender.limits = limits.clone().into_reloadable();
+        let (tx, signature) = withdraw_without_fee(account_id);
+        assert!(tx_sender.free_withdrawal_allowed(&tx).await?);
+        let err = tx_sender.submit_tx(tx, signature, None).await.unwrap_err();
+        assert!(
+            matches!(err, SubmitError::TxAdd(TxAddError::Other)),
+            "unexpected error: {}",
+            err
+        );
+
+        let mut tx_sender = test_tx_sender(&cfg, test_ticker(true), mempool_stub(true));
+        tx_sender.limits = limits.into_reloadable();
+        let (tx, signature) = withdraw_without_fee(account_id);
+        let hash = tx_sender.submit_tx(tx, signature, None).await?;
+
+        // The quota is used until the end of the period.
+        let (tx, signature) = withdraw_without_fee(account_id);
+        let err = tx_sender.submit_tx(tx, signature, None).await.unwrap_err();
+        assert!(
+            matches!(err, SubmitError::TxAdd(TxAddError::TxFeeTooLow)),
+            "unexpected error: {}",
+            err
+        );
+
+        // Return the quota, so the test can be run on the same database again.
+        tx_sender.revert_free_withdrawal(hash).await;
+        Ok(())
+    }
 
     #[test]
     fn test_scaling_user_fee_by_two() {
@@ -0,0 +1,363 @@
+//! Parameters of the fee model that can be tuned at runtime.
+//!
+//! The initial parameters are taken from the config, and are replaced when the config is reloaded.
+//! They can be updated via the admin API, in which case the parameters are persisted in the storage
+//! and are periodically reloaded by every server, so all the replicas eventually use the same parameters.
+
+// Built-in deps
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+// External deps
+use num::{rational::Ratio, BigUint, Zero};
+use serde::{Deserialize, Serialize};
+// Workspace deps
+use zksync_config::TickerConfig;
+use zksync_storage::ConnectionPool;
+use zksync_types::{Address, OutputFeeType};
+use zksync_utils::{BigUintSerdeAsRadix10Str, UnsignedRatioSerializeAsDecimal};
+// Local deps
+use crate::fee_ticker::GasOperationsCost;
+
+/// Interval of reloading the fee model parameters from the storage.
+const FEE_PARAMS_RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Gas cost of the operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationGasCost {
+    pub fee_type: OutputFeeType,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub gas: BigUint,
+}
+
+/// Fee markup of the token.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenFeeMarkup {
+    pub token: Address,
+    /// Markup in percent the fees paid in the token are increased by, 0 removes the markup.
+    pub markup_percent: u32,
+}
+
+/// Fee model parameters tunable at runtime.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeModelParams {
+    /// Coefficient in percent the gas price is increased by, so the fee covers the gas price growth.
+    pub gas_price_scale_percent: u32,
+    /// Cost of the zero-knowledge proof of one chunk in USD.
+    #[serde(with = "UnsignedRatioSerializeAsDecimal")]
+    pub zkp_cost_chunk_usd: Ratio<BigUint>,
+    /// Gas cost of the operations.
+    pub gas_cost_tx: GasOperationsCost,
+    /// Markups in percent of the fees paid in the volatile tokens, so the fee covers
+    /// the price swings between the fee quote and the transaction execution.
+    pub token_fee_markups: HashMap<Address, u32>,
+}
+
+/// Update of the fee model parameters, the fields that are not set are kept unchanged.
+/// The gas costs and the markups are updated only for the listed operations and tokens.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeModelParamsUpdate {
+    #[serde(default)]
+    pub gas_price_scale_percent: Option<u32>,
+    #[serde(default, with = "optional_ratio")]
+    pub zkp_cost_chunk_usd: Option<Ratio<BigUint>>,
+    #[serde(default)]
+    pub gas_cost_tx: Vec<OperationGasCost>,
+    #[serde(default)]
+    pub token_fee_markups: Vec<TokenFeeMarkup>,
+}
+
+impl FeeModelParamsUpdate {
+    /// Checks that the updated parameters are valid, so the fees can't be nullified by mistake.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(gas_price_scale_percent) = self.gas_price_scale_percent {
+            anyhow::ensure!(
+                gas_price_scale_percent > 0,
+                "Gas price scale must be positive"
+            );
+        }
+        if let Some(zkp_cost_chunk_usd) = &self.zkp_cost_chunk_usd {
+            anyhow::ensure!(
+                !zkp_cost_chunk_usd.is_zero(),
+                "Cost of the proof of one chunk must be positive"
+            );
+        }
+        for OperationGasCost { fee_type, gas } in &self.gas_cost_tx {
+            anyhow::ensure!(
+                !gas.is_zero(),
+                "Gas cost of {:?} must be positive",
+                fee_type
+            );
+        }
+        Ok(())
+    }
+}
+
+mod optional_ratio {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper(#[serde(with = "UnsignedRatioSerializeAsDecimal")] Ratio<BigUint>);
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Ratio<BigUint>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.clone().map(Wrapper).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Ratio<BigUint>>, D::Error> {
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|wrapper| wrapper.0))
+    }
+}
+
+impl FeeModelParams {
+    /// Returns the parameters set in the config.
+    pub fn from_config(config: &TickerConfig) -> Self {
+        Self {
+            gas_price_scale_percent: config.gas_price_scale_percent,
+            zkp_cost_chunk_usd: Ratio::new(BigUint::from(1u32), BigUint::from(1000u32)),
+            gas_cost_tx: GasOperationsCost::from_constants(config.fast_processing_coeff),
+            token_fee_markups: config.token_fee_markups(),
+        }
+    }
+
+    /// Returns the parameters with the update applied.
+    pub fn apply(&self, update: FeeModelParamsUpdate) -> anyhow::Result<Self> {
+        update.validate()?;
+
+        let mut params = self.clone();
+        if let Some(gas_price_scale_percent) = update.gas_price_scale_percent {
+            params.gas_price_scale_percent = gas_price_scale_percent;
+        }
+        if let Some(zkp_cost_chunk_usd) = update.zkp_cost_chunk_usd {
+            params.zkp_cost_chunk_usd = zkp_cost_chunk_usd;
+        }
+        for OperationGasCost { fee_type, gas } in update.gas_cost_tx {
+            params.gas_cost_tx.standard_cost.insert(fee_type, gas);
+        }
+        for TokenFeeMarkup {
+            token,
+            markup_percent,
+        } in update.token_fee_markups
+        {
+            if markup_percent == 0 {
+                params.token_fee_markups.remove(&token);
+            } else {
+                params.token_fee_markups.insert(token, markup_percent);
+            }
+        }
+        Ok(params)
+    }
+
+    /// Returns the coefficient the fees paid in the token are multiplied by.
+    pub fn token_fee_markup(&self, token: Address) -> Ratio<BigUint> {
+        let markup_percent = self.token_fee_markups.get(&token).copied().unwrap_or(0);
+        Ratio::new(BigUint::from(100 + markup_percent), BigUint::from(100u32))
+    }
+
+    /// Returns the update setting all the parameters on top of the `defaults`, used to persist them.
+    /// The default markups removed from the parameters are persisted as the zero ones.
+    fn to_update(&self, defaults: &FeeModelParams) -> FeeModelParamsUpdate {
+        let removed_markups = defaults
+            .token_fee_markups
+            .keys()
+            .filter(|token| !self.token_fee_markups.contains_key(token))
+            .map(|&token| TokenFeeMarkup {
+                token,
+                markup_percent: 0,
+            });
+
+        FeeModelParamsUpdate {
+            gas_price_scale_percent: Some(self.gas_price_scale_percent),
+            zkp_cost_chunk_usd: Some(self.zkp_cost_chunk_usd.clone()),
+            gas_cost_tx: self
+                .gas_cost_tx
+                .standard_cost
+                .iter()
+                .map(|(&fee_type, gas)| OperationGasCost {
+                    fee_type,
+                    gas: gas.clone(),
+                })
+                .collect(),
+            token_fee_markups: self
+                .token_fee_markups
+                .iter()
+                .map(|(&token, &markup_percent)| TokenFeeMarkup {
+                    token,
+                    markup_percent,
+                })
+                .chain(removed_markups)
+                .collect(),
+        }
+    }
+}
+
+/// Fee model parameters shared by the fee tickers.
+/// The clones of the handle share the parameters.
+#[derive(Debug, Clone)]
+pub struct FeeModelParamsHandle {
+    /// Parameters from the config, the stored updates are applied on top of them.
+    defaults: Arc<RwLock<Arc<FeeModelParams>>>,
+    current: Arc<RwLock<Arc<FeeModelParams>>>,
+}
+
+impl FeeModelParamsHandle {
+    pub fn new(defaults: FeeModelParams) -> Self {
+        let defaults = Arc::new(defaults);
+        Self {
+            current: Arc::new(RwLock::new(defaults.clone())),
+            defaults: Arc::new(RwLock::new(defaults)),
+        }
+    }
+
+    fn defaults(&self) -> Arc<FeeModelParams> {
+        self.defaults.read().unwrap().clone()
+    }
+
+    /// Returns the current parameters.
+    pub fn get(&self) -> Arc<FeeModelParams> {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Applies the update, persists the resulting parameters and returns them.
+    pub async fn update(
+        &self,
+        connection_pool: &ConnectionPool,
+        update: FeeModelParamsUpdate,
+    ) -> anyhow::Result<FeeModelParams> {
+        let params = self.get().apply(update)?;
+
+        let mut storage = connection_pool.access_storage().await?;
+        storage
+            .misc_schema()
+            .store_fee_model_params(serde_json::to_value(params.to_update(&self.defaults()))?)
+            .await?;
+
+        *self.current.write().unwrap() = Arc::new(params.clone());
+        vlog::info!("Fee model parameters are updated: {:?}", params);
+        Ok(params)
+    }
+
+    /// Loads the parameters persisted in the storage, if any.
+    pub async fn reload(&self, connection_pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = connection_pool.access_storage().await?;
+        let stored = storage.misc_schema().load_fee_model_params().await?;
+
+        let params = match stored {
+            Some(stored) => {
+                let update: FeeModelParamsUpdate = serde_json::from_value(stored)?;
+                self.defaults().apply(update)?
+            }
+            None => self.defaults().as_ref().clone(),
+        };
+        *self.current.write().unwrap() = Arc::new(params);
+        Ok(())
+    }
+
+    /// Replaces the parameters from the config and applies the stored updates on top of them.
+    pub async fn update_defaults(
+        &self,
+        connection_pool: &ConnectionPool,
+        defaults: FeeModelParams,
+    ) -> anyhow::Result<()> {
+        *self.defaults.write().unwrap() = Arc::new(defaults);
+        self.reload(connection_pool).await?;
+        vlog::info!("Fee model parameters are reloaded: {:?}", self.get());
+        Ok(())
+    }
+
+    /// Periodically reloads the parameters persisted in the storage.
+    pub async fn keep_updated(self, connection_pool: ConnectionPool) {
+        loop {
+            if let Err(err) = self.reload(&connection_pool).await {
+                vlog::warn!("Failed to reload the fee model parameters: {}", err);
+            }
+            tokio::time::sleep(FEE_PARAMS_RELOAD_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_model_params_update() {
+        let volatile_token = Address::repeat_byte(1);
+        let params = FeeModelParams {
+            gas_price_scale_percent: 130,
+            zkp_cost_chunk_usd: Ratio::new(1u32.into(), 1000u32.into()),
+            gas_cost_tx: GasOperationsCost::from_constants(10.0),
+            token_fee_markups: vec![(volatile_token, 20)].into_iter().collect(),
+        };
+        let transfer_cost = params.gas_cost_tx.standard_cost[&OutputFeeType::Transfer].clone();
+
+        let update = FeeModelParamsUpdate {
+            gas_price_scale_percent: Some(150),
+            zkp_cost_chunk_usd: None,
+            gas_cost_tx: vec![OperationGasCost {
+                fee_type: OutputFeeType::Withdraw,
+                gas: 100_000u32.into(),
+            }],
+            token_fee_markups: vec![TokenFeeMarkup {
+                token: volatile_token,
+                markup_percent: 0,
+            }],
+        };
+        let updated = params.apply(update).unwrap();
+        assert_eq!(updated.gas_price_scale_percent, 150);
+        assert_eq!(updated.zkp_cost_chunk_usd, params.zkp_cost_chunk_usd);
+        assert_eq!(
+            updated.gas_cost_tx.standard_cost[&OutputFeeType::Withdraw],
+            100_000u32.into()
+        );
+        assert_eq!(
+            updated.gas_cost_tx.standard_cost[&OutputFeeType::Transfer],
+            transfer_cost
+        );
+        assert_eq!(
+            params.token_fee_markup(volatile_token),
+            Ratio::new(120u32.into(), 100u32.into())
+        );
+        assert_eq!(
+            updated.token_fee_markup(volatile_token),
+            Ratio::from_integer(1u32.into())
+        );
+
+        // The persisted form restores the same parameters.
+        let stored = serde_json::to_value(updated.to_update(&params)).unwrap();
+        let restored = params
+            .apply(serde_json::from_value(stored).unwrap())
+            .unwrap();
+        assert_eq!(restored, updated);
+
+        // Invalid parameters are rejected.
+        let update = FeeModelParamsUpdate {
+            gas_price_scale_percent: Some(0),
+            ..Default::default()
+        };
+        assert!(params.apply(update).is_err());
+        let update = FeeModelParamsUpdate {
+            zkp_cost_chunk_usd: Some(Ratio::from_integer(0u32.into())),
+            ..Default::default()
+        };
+        assert!(update.validate().is_err());
+        assert!(params.apply(update).is_err());
+        let update = FeeModelParamsUpdate {
+            gas_cost_tx: vec![OperationGasCost {
+                fee_type: OutputFeeType::Transfer,
+                gas: 0u32.into(),
+            }],
+            ..Default::default()
+        };
+        assert!(update.validate().is_err());
+    }
+}
@@ -12,11 +12,7 @@ use std::time::Duration;
 
 // External deps
 use bigdecimal::BigDecimal;
-use num::{
-    rational::Ratio,
-    traits::{Inv, Pow},
-    BigUint, CheckedDiv, Zero,
-};
+use num::{rational::Ratio, traits::Pow, BigUint, CheckedDiv, Zero};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::task::JoinHandle;
@@ -25,28 +21,45 @@ use tokio::time::Instant;
 // Workspace deps
 
 use zksync_config::configs::ticker::TokenPriceSource;
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::misc::records::FeeQuote;
 use zksync_storage::ConnectionPool;
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::{
     gas_counter::GasCounter, tokens::ChangePubKeyFeeTypeArg, tx::ChangePubKeyType, Address,
-    BatchFee, ChangePubKeyOp, Fee, MintNFTOp, OutputFeeType, SwapOp, Token, TokenId, TokenLike,
-    TransferOp, TransferToNewOp, TxFeeTypes, WithdrawNFTOp, WithdrawOp,
+    BatchFee, ChangePubKeyOp, Fee, FeeTokenPriceFloor, MintNFTOp, OutputFeeType, SwapOp, Token,
+    TokenId, TokenLike, TransferOp, TransferToNewOp, TxFeeTypes, WithdrawNFTOp, WithdrawOp,
 };
-use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
+use zksync_utils::{big_decimal_to_ratio, biguint_to_big_decimal, ratio_to_big_decimal};
 
 // Local deps
 use crate::fee_ticker::constants::AMORTIZED_COST_PER_CHUNK;
+pub use crate::fee_ticker::fee_params::{FeeModelParams, FeeModelParamsHandle};
+pub use crate::fee_ticker::price_cache::PriceCachePolicy;
+use crate::fee_ticker::quote_recorder::FEE_QUOTE_PRICE_PRECISION;
+pub use crate::fee_ticker::quote_recorder::{run_fee_quote_recorder, FeeQuoteRecorder};
 pub use crate::fee_ticker::ticker_info::{FeeTickerInfo, TickerInfo};
 use crate::fee_ticker::validator::FeeTokenValidator;
 use crate::fee_ticker::{
     ticker_api::{
-        coingecko::CoinGeckoAPI, coinmarkercap::CoinMarketCapAPI, FeeTickerAPI, TickerApi,
-        CONNECTION_TIMEOUT,
+        chainlink::ChainlinkAPI,
+        coingecko::CoinGeckoAPI,
+        coinmarkercap::CoinMarketCapAPI,
+        fallback::{BoxedPriceSource, FallbackPriceSource},
+        uniswap_twap::UniswapTwapAPI,
+        FeeTickerAPI, TickerApi, CONNECTION_TIMEOUT,
+    },
+    validator::{
+        policy::{FeeTokenPolicy, FeeTokenPolicyUpdater},
+        watcher::UniswapTokenWatcher,
+        MarketUpdater,
     },
-    validator::{watcher::UniswapTokenWatcher, MarketUpdater},
 };
 
 mod constants;
+pub mod fee_params;
+pub mod price_cache;
+mod quote_recorder;
 mod ticker_api;
 pub(crate) mod ticker_info;
 pub mod validator;
@@ -55,7 +68,7 @@ pub mod validator;
 pub(crate) mod tests;
 
 /// Contains cost of zkSync operations in Wei.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct GasOperationsCost {
     standard_cost: HashMap<OutputFeeType, BigUint>,
 }
@@ -147,7 +160,10 @@ pub struct TickerConfig {
     pub zkp_cost_chunk_usd: Ratio<BigUint>,
     pub gas_cost_tx: GasOperationsCost,
     pub tokens_risk_factors: HashMap<TokenId, Ratio<BigUint>>,
+    /// Fee markups of the tokens in percent, the initial values of the fee model parameters.
+    pub token_fee_markups: HashMap<Address, u32>,
     pub scale_fee_coefficient: Ratio<BigUint>,
+    pub gas_price_scale_percent: u32,
     pub max_blocks_to_aggregate: u32,
     pub subsidy_cpk_price_usd: Ratio<BigUint>,
 }
@@ -200,7 +216,9 @@ impl PriceError {
 pub struct FeeTicker {
     info: Box<dyn FeeTickerInfo>,
     config: TickerConfig,
+    fee_params: FeeModelParamsHandle,
     validator: FeeTokenValidator,
+    quote_recorder: Option<FeeQuoteRecorder>,
 }
 
 const CPK_CREATE2_FEE_TYPE: OutputFeeType = OutputFeeType::ChangePubKey(
@@ -209,45 +227,124 @@ const CPK_CREATE2_FEE_TYPE: OutputFeeType = OutputFeeType::ChangePubKey(
 // Make no more than (Number of tokens) queries per 5 minutes to database is a good result
 // for updating names for tokens.
 const TOKEN_INVALIDATE_CACHE: Duration = Duration::from_secs(5 * 60);
+// The price source can be unavailable on startup, its initialization is retried
+// while the cached prices are used for fee quoting.
+const PRICE_SOURCE_INIT_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Creates the token price source of the given type, retrying until the source is available.
+async fn create_price_source(
+    source: TokenPriceSource,
+    config: &zksync_config::TickerConfig,
+    client: reqwest::Client,
+    eth_gateway: EthereumGateway,
+) -> BoxedPriceSource {
+    loop {
+        match try_create_price_source(source, config, client.clone(), eth_gateway.clone()).await {
+            Ok(price_source) => return price_source,
+            Err(err) => {
+                vlog::warn!(
+                    "Failed to init the {:?} price source, retrying: {}",
+                    source,
+                    err
+                );
+                tokio::time::sleep(PRICE_SOURCE_INIT_RETRY_INTERVAL).await;
+            }
+        }
+    }
+}
+
+async fn try_create_price_source(
+    source: TokenPriceSource,
+    config: &zksync_config::TickerConfig,
+    client: reqwest::Client,
+    eth_gateway: EthereumGateway,
+) -> anyhow::Result<BoxedPriceSource> {
+    let base_url = config.base_url(source);
+    let price_source: BoxedPriceSource = match source {
+        TokenPriceSource::CoinMarketCap => Box::new(CoinMarketCapAPI::new(
+            client,
+            base_url
+                .unwrap()
+                .parse()
+                .expect("Correct CoinMarketCap url"),
+        )),
+        TokenPriceSource::CoinGecko => Box::new(
+            CoinGeckoAPI::new(
+                client,
+                base_url.unwrap().parse().expect("Correct CoinGecko url"),
+            )
+            .await?,
+        ),
+        TokenPriceSource::UniswapTwap => Box::new(
+            UniswapTwapAPI::new(
+                eth_gateway,
+                config
+                    .uniswap_v3_factory_addr
+                    .expect("Uniswap V3 factory address is not set"),
+                config
+                    .uniswap_twap_quote_token_addr
+                    .expect("Uniswap TWAP quote token address is not set"),
+                config
+                    .uniswap_twap_weth_addr
+                    .expect("Uniswap TWAP WETH address is not set"),
+                config.uniswap_twap_pool_fee,
+                config.uniswap_twap_period_secs,
+            )
+            .await?,
+        ),
+        TokenPriceSource::Chainlink => Box::new(ChainlinkAPI::new(
+            eth_gateway,
+            config
+                .chainlink_feed_registry_addr
+                .expect("Chainlink feed registry address is not set"),
+        )),
+    };
+    Ok(price_source)
+}
 
 #[must_use]
 pub fn run_updaters(
     db_pool: ConnectionPool,
     config: &zksync_config::TickerConfig,
+    eth_gateway: EthereumGateway,
 ) -> Vec<JoinHandle<()>> {
     let cache = (db_pool.clone(), TokenDBCache::new(TOKEN_INVALIDATE_CACHE));
 
     let watcher = UniswapTokenWatcher::new(config.uniswap_url.clone());
 
     let updater = MarketUpdater::new(cache, watcher);
-    let mut tasks = vec![tokio::spawn(
-        updater.keep_updated(config.token_market_update_time),
-    )];
+    let policy_updater = FeeTokenPolicyUpdater::new(
+        db_pool.clone(),
+        eth_gateway.clone(),
+        FeeTokenPolicy::from_config(config),
+        HashSet::from_iter(config.unconditionally_valid_tokens.clone()),
+    );
+    let mut tasks = vec![
+        tokio::spawn(updater.keep_updated(config.token_market_update_time)),
+        tokio::spawn(policy_updater.keep_updated(config.token_market_update_time)),
+    ];
     let client = reqwest::ClientBuilder::new()
         .timeout(CONNECTION_TIMEOUT)
         .connect_timeout(CONNECTION_TIMEOUT)
         .build()
         .expect("Failed to build reqwest::Client");
-    let (price_source, base_url) = config.price_source();
-    let price_updater = match price_source {
-        TokenPriceSource::CoinMarketCap => {
-            let token_price_api =
-                CoinMarketCapAPI::new(client, base_url.parse().expect("Correct CoinMarketCap url"));
-
-            let ticker_api = TickerApi::new(db_pool, token_price_api);
-            tokio::spawn(ticker_api.keep_price_updated())
-        }
-
-        TokenPriceSource::CoinGecko => tokio::spawn(async move {
-            let token_price_api =
-                CoinGeckoAPI::new(client, base_url.parse().expect("Correct CoinGecko url"))
-                    .await
-                    .expect("failed to init CoinGecko client");
-            let ticker_api = TickerApi::new(db_pool, token_price_api);
+    let config = config.clone();
+    let price_updater = tokio::spawn(async move {
+        let (primary, fallback) = config.price_sources();
+        let primary =
+            create_price_source(primary, &config, client.clone(), eth_gateway.clone()).await;
+        let fallback = match fallback {
+            Some(fallback) => {
+                Some(create_price_source(fallback, &config, client, eth_gateway).await)
+            }
+            None => None,
+        };
+        let token_price_api =
+            FallbackPriceSource::new(primary, fallback, config.max_price_change_percent);
+        let ticker_api = TickerApi::new(db_pool, token_price_api);
 
-            ticker_api.keep_price_updated().await;
-        }),
-    };
+        ticker_api.keep_price_updated().await;
+    });
     tasks.push(price_updater);
     tasks
 }
@@ -258,13 +355,32 @@ impl FeeTicker {
         config: TickerConfig,
         validator: FeeTokenValidator,
     ) -> Self {
+        let fee_params = FeeModelParamsHandle::new(FeeModelParams {
+            gas_price_scale_percent: config.gas_price_scale_percent,
+            zkp_cost_chunk_usd: config.zkp_cost_chunk_usd.clone(),
+            gas_cost_tx: config.gas_cost_tx.clone(),
+            token_fee_markups: config.token_fee_markups.clone(),
+        });
         Self {
             info,
             config,
+            fee_params,
             validator,
+            quote_recorder: None,
         }
     }
 
+    /// Enables the recording of the sampled fee quotes.
+    pub fn with_quote_recorder(mut self, quote_recorder: FeeQuoteRecorder) -> Self {
+        self.quote_recorder = Some(quote_recorder);
+        self
+    }
+
+    /// Returns the handle of the fee model parameters, which can be used to update them at runtime.
+    pub fn fee_params(&self) -> FeeModelParamsHandle {
+        self.fee_params.clone()
+    }
+
     pub fn new_with_default_validator(
         info: Box<dyn FeeTickerInfo>,
         config: zksync_config::TickerConfig,
@@ -272,14 +388,17 @@ impl FeeTicker {
         connection_pool: ConnectionPool,
     ) -> Self {
         let cache = (connection_pool, TokenDBCache::new(TOKEN_INVALIDATE_CACHE));
+        let fee_params = FeeModelParams::from_config(&config);
         let ticker_config = TickerConfig {
-            zkp_cost_chunk_usd: Ratio::from_integer(BigUint::from(10u32).pow(3u32)).inv(),
-            gas_cost_tx: GasOperationsCost::from_constants(config.fast_processing_coeff),
+            zkp_cost_chunk_usd: fee_params.zkp_cost_chunk_usd,
+            gas_cost_tx: fee_params.gas_cost_tx,
             tokens_risk_factors: HashMap::new(),
+            token_fee_markups: fee_params.token_fee_markups,
             scale_fee_coefficient: Ratio::new(
                 BigUint::from(config.scale_fee_percent),
                 BigUint::from(100u32),
             ),
+            gas_price_scale_percent: fee_params.gas_price_scale_percent,
             max_blocks_to_aggregate,
             subsidy_cpk_price_usd: config.subsidy_cpk_price_usd(),
         };
@@ -294,11 +413,32 @@ impl FeeTicker {
 }
 
 impl FeeTicker {
-    /// Increases the gas price by a constant coefficient.
+    /// Increases the gas price by the coefficient from the fee model parameters.
     /// Due to the high volatility of gas prices, we are include the risk
     /// in the fee in order not to go into negative territory.
-    fn risk_gas_price_estimate(gas_price: BigUint) -> BigUint {
-        gas_price * BigUint::from(130u32) / BigUint::from(100u32)
+    fn risk_gas_price_estimate(gas_price: BigUint, fee_params: &FeeModelParams) -> BigUint {
+        gas_price * BigUint::from(fee_params.gas_price_scale_percent) / BigUint::from(100u32)
+    }
+
+    /// Records the fee quote if it's sampled. The quote is made from the USD price of the token.
+    /// Recording must not affect the fee quoting, so the errors are only reported.
+    async fn record_fee_quote(
+        &self,
+        token: &Token,
+        make_quote: impl FnOnce(&Ratio<BigUint>) -> FeeQuote,
+    ) {
+        let quote_recorder = match &self.quote_recorder {
+            Some(quote_recorder) if quote_recorder.sample() => quote_recorder,
+            _ => return,
+        };
+        match self
+            .info
+            .get_last_token_price(TokenLike::Id(token.id))
+            .await
+        {
+            Ok(price) => quote_recorder.record(make_quote(&price.usd_price)),
+            Err(err) => vlog::warn!("Failed to record the fee quote: {}", err),
+        }
     }
 
     pub async fn get_token_price(
@@ -336,11 +476,12 @@ impl FeeTicker {
         recipient: Address,
     ) -> Result<ResponseFee, anyhow::Error> {
         let start = Instant::now();
-        let zkp_cost_chunk = self.config.zkp_cost_chunk_usd.clone();
+        let fee_params = self.fee_params.get();
+        let zkp_cost_chunk = fee_params.zkp_cost_chunk_usd.clone();
         let token = self.info.get_token(token).await?;
 
         let gas_price_wei = self.info.get_gas_price_wei().await?;
-        let scale_gas_price = Self::risk_gas_price_estimate(gas_price_wei.clone());
+        let scale_gas_price = Self::risk_gas_price_estimate(gas_price_wei.clone(), &fee_params);
         let wei_price_usd = self.wei_price_usd().await?;
         let token_usd_risk = self.token_usd_risk(&token).await?;
 
@@ -368,6 +509,23 @@ impl FeeTicker {
             gas_tx_amount,
             gas_price_wei.clone(),
         );
+        self.record_fee_quote(&token, |token_price_usd| FeeQuote {
+            fee_type: format!("{:?}", fee_type),
+            token_id: token.id,
+            gas_price_wei: biguint_to_big_decimal(gas_price_wei.clone()),
+            scaled_gas_price_wei: biguint_to_big_decimal(scale_gas_price.clone()),
+            eth_price_usd: ratio_to_big_decimal(
+                &(&wei_price_usd * BigUint::from(10u32).pow(18u32)),
+                FEE_QUOTE_PRICE_PRECISION,
+            ),
+            token_price_usd: ratio_to_big_decimal(token_price_usd, FEE_QUOTE_PRICE_PRECISION),
+            gas_tx_amount: biguint_to_big_decimal(normal_fee.gas_tx_amount.clone()),
+            zkp_fee: biguint_to_big_decimal(normal_fee.zkp_fee.clone()),
+            gas_fee: biguint_to_big_decimal(normal_fee.gas_fee.clone()),
+            total_fee: biguint_to_big_decimal(normal_fee.total_fee.clone()),
+            created_at: chrono::Utc::now(),
+        })
+        .await;
 
         if fee_type == CPK_CREATE2_FEE_TYPE {
             let token_price = self
@@ -418,12 +576,13 @@ impl FeeTicker {
         txs: Vec<(TxFeeTypes, Address)>,
     ) -> anyhow::Result<ResponseBatchFee> {
         let start = Instant::now();
-        let zkp_cost_chunk = self.config.zkp_cost_chunk_usd.clone();
+        let fee_params = self.fee_params.get();
+        let zkp_cost_chunk = fee_params.zkp_cost_chunk_usd.clone();
 
         let token = self.info.get_token(token).await?;
 
         let gas_price_wei = self.info.get_gas_price_wei().await?;
-        let scale_gas_price = Self::risk_gas_price_estimate(gas_price_wei.clone());
+        let scale_gas_price = Self::risk_gas_price_estimate(gas_price_wei.clone(), &fee_params);
         let wei_price_usd = self.wei_price_usd().await?;
         let token_usd_risk = self.token_usd_risk(&token).await?;
 
@@ -499,9 +658,26 @@ impl FeeTicker {
         let normal_fee = {
             let total_zkp_fee = (&zkp_cost_chunk * total_op_chunks) * &token_usd_risk;
             let total_gas_fee =
-                (&wei_price_usd * total_normal_gas_tx_amount * &scale_gas_price) * &token_usd_risk;
+                (&wei_price_usd * &total_normal_gas_tx_amount * &scale_gas_price) * &token_usd_risk;
             BatchFee::new(total_zkp_fee, total_gas_fee)
         };
+        self.record_fee_quote(&token, |token_price_usd| FeeQuote {
+            fee_type: "Batch".to_string(),
+            token_id: token.id,
+            gas_price_wei: biguint_to_big_decimal(gas_price_wei.clone()),
+            scaled_gas_price_wei: biguint_to_big_decimal(scale_gas_price.clone()),
+            eth_price_usd: ratio_to_big_decimal(
+                &(&wei_price_usd * BigUint::from(10u32).pow(18u32)),
+                FEE_QUOTE_PRICE_PRECISION,
+            ),
+            token_price_usd: ratio_to_big_decimal(token_price_usd, FEE_QUOTE_PRICE_PRECISION),
+            gas_tx_amount: ratio_to_big_decimal(&total_normal_gas_tx_amount, 0),
+            zkp_fee: biguint_to_big_decimal(normal_fee.zkp_fee.clone()),
+            gas_fee: biguint_to_big_decimal(normal_fee.gas_fee.clone()),
+            total_fee: biguint_to_big_decimal(normal_fee.total_fee.clone()),
+            created_at: chrono::Utc::now(),
+        })
+        .await;
 
         let subsidized_fee = {
             let total_zkp_fee = (zkp_cost_chunk * total_subsidized_op_chunks) * &token_usd_risk;
@@ -544,7 +720,8 @@ impl FeeTicker {
             .tokens_risk_factors
             .get(&token.id)
             .cloned()
-            .unwrap_or_else(|| Ratio::from_integer(1u32.into()));
+            .unwrap_or_else(|| Ratio::from_integer(1u32.into()))
+            * self.fee_params.get().token_fee_markup(token.address);
 
         let token_price_usd = self
             .info
@@ -559,6 +736,33 @@ impl FeeTicker {
             .ok_or_else(|| anyhow::format_err!("Token is not acceptable for fee"))
     }
 
+    /// Returns the lowest price of the token at which the `provided_fee` still covers the `required_fee`
+    /// without the token fee markup. `None` if the token has no markup, so the fee needn't be checked
+    /// again at the execution.
+    pub async fn fee_token_price_floor(
+        &self,
+        token: &Token,
+        required_fee: &BigUint,
+        provided_fee: &BigUint,
+    ) -> Result<Option<FeeTokenPriceFloor>, PriceError> {
+        let markup = self.fee_params.get().token_fee_markup(token.address);
+        if markup == Ratio::from_integer(1u32.into()) || provided_fee.is_zero() {
+            return Ok(None);
+        }
+
+        let token_price_usd = self
+            .info
+            .get_last_token_price(TokenLike::Id(token.id))
+            .await?
+            .usd_price;
+        let min_usd_price =
+            token_price_usd * Ratio::new(required_fee.clone(), provided_fee.clone()) / markup;
+        Ok(Some(FeeTokenPriceFloor {
+            token_id: token.id,
+            min_usd_price,
+        }))
+    }
+
     /// Returns `true` if account does not yet exist in the zkSync network.
     pub async fn is_account_new(&self, address: Address) -> anyhow::Result<bool> {
         self.info.is_account_new(address).await
@@ -595,7 +799,8 @@ impl FeeTicker {
         ) {
             self.calculate_fast_withdrawal_gas_cost(op_chunks).await?
         } else {
-            self.config
+            self.fee_params
+                .get()
                 .gas_cost_tx
                 .standard_cost
                 .get(&fee_type)
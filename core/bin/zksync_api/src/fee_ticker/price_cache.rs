@@ -0,0 +1,187 @@
+//! In-memory cache of the token prices used for fee quoting.
+//!
+//! The prices are cached per token for `ttl`, or for the token's own TTL from `ttl_overrides`,
+//! and are re-read from the database afterwards. If the price can't be re-read, the expired one
+//! is used. Prices older than `stale_after` are reported to the metrics as stale, and the prices
+//! older than `max_age` are refused, so the fees aren't quoted using the prices that are too outdated.
+
+// Built-in deps
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+// External deps
+use chrono::Utc;
+use tokio::sync::RwLock;
+// Workspace deps
+use zksync_types::{Address, Token, TokenId, TokenPrice};
+// Local deps
+use crate::fee_ticker::PriceError;
+
+/// Caching and staleness policy of the token prices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceCachePolicy {
+    /// Time the price is cached in memory.
+    pub ttl: Duration,
+    /// Cache TTLs of the tokens overriding the `ttl`.
+    pub ttl_overrides: HashMap<Address, Duration>,
+    /// Age of the price after which it's considered stale.
+    pub stale_after: Duration,
+    /// Age of the price after which it's refused.
+    pub max_age: Duration,
+}
+
+impl PriceCachePolicy {
+    pub fn from_config(config: &zksync_config::TickerConfig) -> Self {
+        Self {
+            ttl: config.price_cache_ttl(),
+            ttl_overrides: config.price_cache_ttl_overrides(),
+            stale_after: config.stale_price_age(),
+            max_age: config.max_price_age(),
+        }
+    }
+
+    /// Returns the time the price of the token is cached in memory.
+    pub fn ttl(&self, token_address: Address) -> Duration {
+        self.ttl_overrides
+            .get(&token_address)
+            .copied()
+            .unwrap_or(self.ttl)
+    }
+}
+
+impl Default for PriceCachePolicy {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(60),
+            ttl_overrides: HashMap::new(),
+            stale_after: Duration::from_secs(30 * 60),
+            max_age: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PriceCache {
+    policy: PriceCachePolicy,
+    prices: Arc<RwLock<HashMap<TokenId, (TokenPrice, Instant)>>>,
+}
+
+impl PriceCache {
+    pub fn new(policy: PriceCachePolicy) -> Self {
+        Self {
+            policy,
+            prices: Arc::default(),
+        }
+    }
+
+    /// Returns the cached price of the token if it's not expired yet.
+    pub async fn get(&self, token: &Token) -> Option<TokenPrice> {
+        match self.prices.read().await.get(&token.id) {
+            Some((price, cached_at)) if cached_at.elapsed() < self.policy.ttl(token.address) => {
+                Some(price.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the cached price of the token even if it's expired, used if
+    /// the price can't be re-read from the database.
+    pub async fn get_expired(&self, token_id: TokenId) -> Option<TokenPrice> {
+        self.prices
+            .read()
+            .await
+            .get(&token_id)
+            .map(|(price, _)| price.clone())
+    }
+
+    pub async fn insert(&self, token_id: TokenId, price: TokenPrice) {
+        self.prices
+            .write()
+            .await
+            .insert(token_id, (price, Instant::now()));
+    }
+
+    /// Checks the age of the price according to the policy and reports it to the metrics.
+    /// Returns an error if the price is too outdated to be used for fee quoting.
+    pub fn check_age(&self, token: &Token, price: TokenPrice) -> Result<TokenPrice, PriceError> {
+        let age = (Utc::now() - price.last_updated)
+            .to_std()
+            .unwrap_or_default();
+        metrics::gauge!("ticker_info.price_age_secs", age.as_secs() as f64, "token" => token.symbol.clone());
+
+        if age > self.policy.max_age {
+            metrics::increment_counter!("ticker_info.outdated_price", "token" => token.symbol.clone());
+            return Err(PriceError::api_error(format!(
+                "Price of the token {} is outdated, last updated at {}",
+                token.symbol, price.last_updated
+            )));
+        }
+        if age > self.policy.stale_after {
+            vlog::warn!(
+                "Using the stale price of the token {}, last updated at {}",
+                token.symbol,
+                price.last_updated
+            );
+            metrics::increment_counter!("ticker_info.stale_price", "token" => token.symbol.clone());
+        }
+        Ok(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::rational::Ratio;
+    use zksync_types::TokenKind;
+
+    fn price(age_secs: i64) -> TokenPrice {
+        TokenPrice {
+            usd_price: Ratio::from_integer(1u32.into()),
+            last_updated: Utc::now() - chrono::Duration::seconds(age_secs),
+        }
+    }
+
+    #[tokio::test]
+    async fn price_cache() {
+        let token = Token::new(TokenId(1), Default::default(), "DAI", 18, TokenKind::ERC20);
+        let cache = PriceCache::new(PriceCachePolicy {
+            ttl: Duration::from_secs(0),
+            ttl_overrides: HashMap::new(),
+            stale_after: Duration::from_secs(60),
+            max_age: Duration::from_secs(600),
+        });
+
+        assert!(cache.get_expired(token.id).await.is_none());
+        cache.insert(token.id, price(0)).await;
+        // The price is expired immediately with zero TTL, but is still available if needed.
+        assert!(cache.get(&token).await.is_none());
+        assert!(cache.get_expired(token.id).await.is_some());
+
+        // Fresh and stale prices are used, the outdated ones are refused.
+        assert!(cache.check_age(&token, price(10)).is_ok());
+        assert!(cache.check_age(&token, price(120)).is_ok());
+        assert!(cache.check_age(&token, price(1200)).is_err());
+
+        let cache = PriceCache::new(PriceCachePolicy::default());
+        cache.insert(token.id, price(0)).await;
+        assert!(cache.get(&token).await.is_some());
+
+        // The token TTL overrides the default one, the other tokens use the default TTL.
+        let other_token = Token::new(
+            TokenId(2),
+            Address::repeat_byte(2),
+            "USDC",
+            6,
+            TokenKind::ERC20,
+        );
+        let mut policy = PriceCachePolicy::default();
+        policy
+            .ttl_overrides
+            .insert(token.address, Duration::from_secs(0));
+        let cache = PriceCache::new(policy);
+        cache.insert(token.id, price(0)).await;
+        cache.insert(other_token.id, price(0)).await;
+        assert!(cache.get(&token).await.is_none());
+        assert!(cache.get(&other_token).await.is_some());
+    }
+}
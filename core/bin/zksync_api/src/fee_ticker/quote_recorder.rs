@@ -0,0 +1,123 @@
+//! Fee quote recorder persists the sampled fee quotes issued by the fee ticker along with
+//! the inputs they were calculated from, so the accuracy of the fee model against the actual
+//! L1 costs can be analyzed offline.
+//!
+//! Quotes are stored by the background task, so recording never slows down the fee quoting.
+
+// Built-in deps
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+// External deps
+use futures::FutureExt;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+// Workspace deps
+use zksync_storage::{misc::records::FeeQuote, ConnectionPool};
+
+/// Number of digits after the decimal point the prices are stored with.
+pub const FEE_QUOTE_PRICE_PRECISION: usize = 18;
+/// Number of the quotes waiting to be stored, the new quotes are dropped if it's exceeded.
+const FEE_QUOTES_CHANNEL_CAPACITY: usize = 1024;
+/// Maximal number of the quotes stored in one database transaction.
+const MAX_FEE_QUOTES_BATCH: usize = 100;
+
+/// Returns `true` if the quote with the given sequence number is sampled.
+/// Sampling is deterministic, exactly `sample_rate` of the quotes are sampled.
+fn is_sampled(quote_number: u64, sample_rate: f64) -> bool {
+    (quote_number as f64 * sample_rate).floor() < ((quote_number + 1) as f64 * sample_rate).floor()
+}
+
+#[derive(Debug, Clone)]
+pub struct FeeQuoteRecorder {
+    sample_rate: f64,
+    quotes_counter: Arc<AtomicU64>,
+    sender: mpsc::Sender<FeeQuote>,
+}
+
+impl FeeQuoteRecorder {
+    /// Creates the recorder and the receiver of the recorded quotes,
+    /// which should be passed to `run_fee_quote_recorder`.
+    pub fn new(sample_rate: f64) -> (Self, mpsc::Receiver<FeeQuote>) {
+        assert!(
+            (0.0..=1.0).contains(&sample_rate),
+            "Fee quote sample rate must be in the [0, 1] range"
+        );
+        let (sender, receiver) = mpsc::channel(FEE_QUOTES_CHANNEL_CAPACITY);
+        let recorder = Self {
+            sample_rate,
+            quotes_counter: Arc::new(AtomicU64::new(0)),
+            sender,
+        };
+        (recorder, receiver)
+    }
+
+    /// Counts the issued quote, returns `true` if it should be recorded.
+    pub fn sample(&self) -> bool {
+        let quote_number = self.quotes_counter.fetch_add(1, Ordering::Relaxed);
+        is_sampled(quote_number, self.sample_rate)
+    }
+
+    /// Sends the quote to be stored, the quote is dropped if the storing falls behind.
+    pub fn record(&self, quote: FeeQuote) {
+        if self.sender.try_send(quote).is_err() {
+            metrics::increment_counter!("ticker.fee_quote_recorder.dropped");
+        }
+    }
+}
+
+async fn store_fee_quotes(pool: &ConnectionPool, quotes: Vec<FeeQuote>) -> anyhow::Result<()> {
+    pool.access_storage()
+        .await?
+        .misc_schema()
+        .store_fee_quotes(quotes)
+        .await
+}
+
+/// Stores the quotes recorded by the `FeeQuoteRecorder`.
+#[must_use]
+pub fn run_fee_quote_recorder(
+    connection_pool: ConnectionPool,
+    mut receiver: mpsc::Receiver<FeeQuote>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        while let Some(quote) = receiver.recv().await {
+            let mut quotes = vec![quote];
+            while quotes.len() < MAX_FEE_QUOTES_BATCH {
+                // Take the already recorded quotes without waiting for the new ones.
+                match receiver.recv().now_or_never() {
+                    Some(Some(quote)) => quotes.push(quote),
+                    _ => break,
+                }
+            }
+
+            let quotes_count = quotes.len();
+            if let Err(err) = store_fee_quotes(&connection_pool, quotes).await {
+                vlog::warn!("Failed to store {} fee quotes: {}", quotes_count, err);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_quotes_sampling() {
+        let sampled = |sample_rate: f64| {
+            (0..1000)
+                .filter(|quote_number| is_sampled(*quote_number, sample_rate))
+                .count()
+        };
+        assert_eq!(sampled(0.0), 0);
+        assert_eq!(sampled(0.01), 10);
+        assert_eq!(sampled(0.25), 250);
+        assert_eq!(sampled(1.0), 1000);
+
+        // The sampled quotes are spread evenly.
+        let sampled_numbers: Vec<_> = (0..10).filter(|n| is_sampled(*n, 0.5)).collect();
+        assert_eq!(sampled_numbers, vec![1, 3, 5, 7, 9]);
+    }
+}
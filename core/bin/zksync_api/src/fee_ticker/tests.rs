@@ -3,7 +3,7 @@ use std::any::Any;
 use async_trait::async_trait;
 use bigdecimal::BigDecimal;
 use chrono::Utc;
-use futures::executor::block_on;
+use futures::{executor::block_on, FutureExt};
 use std::str::FromStr;
 use zksync_types::{Address, Token, TokenId, TokenKind, TokenPrice};
 use zksync_utils::{
@@ -11,7 +11,7 @@ use zksync_utils::{
 };
 
 use crate::fee_ticker::{
-    ticker_api::TokenPriceAPI,
+    ticker_api::PriceSource,
     validator::{cache::TokenInMemoryCache, FeeTokenValidator},
 };
 
@@ -111,7 +111,9 @@ pub fn get_test_ticker_config() -> TickerConfig {
                 t.risk_factor.map(|risk| (id, risk))
             })
             .collect(),
+        token_fee_markups: HashMap::new(),
         scale_fee_coefficient: Ratio::new(BigUint::from(150u32), BigUint::from(100u32)),
+        gas_price_scale_percent: 130,
         max_blocks_to_aggregate: 5,
         subsidy_cpk_price_usd: scaled_u64_to_ratio(SUBSIDY_CPK_PRICE_USD_SCALED),
     }
@@ -207,7 +209,7 @@ fn format_with_dot(num: &Ratio<BigUint>, precision: usize) -> String {
 struct ErrorTickerApi;
 
 #[async_trait::async_trait]
-impl TokenPriceAPI for ErrorTickerApi {
+impl PriceSource for ErrorTickerApi {
     async fn get_price(&self, _token: &Token) -> Result<TokenPrice, PriceError> {
         Err(PriceError::token_not_found("Wrong token"))
     }
@@ -711,3 +713,117 @@ fn test_zero_price_token_fee() {
     ))
     .unwrap_err();
 }
+
+#[test]
+fn test_token_fee_markup() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+    let token = TestToken::hex();
+    let mut config = get_test_ticker_config();
+    #[allow(clippy::box_default)]
+    let mut ticker_without_markup = FeeTicker::new(
+        Box::new(MockTickerInfo::default()),
+        config.clone(),
+        validator.clone(),
+    );
+    config.token_fee_markups.insert(token.address, 20);
+    #[allow(clippy::box_default)]
+    let mut ticker = FeeTicker::new(Box::new(MockTickerInfo::default()), config, validator);
+
+    let mut get_fees = |token: &TestToken| {
+        let fee = get_normal_and_subsidy_fee(
+            &mut ticker_without_markup,
+            TxFeeTypes::Transfer,
+            token.id.into(),
+            Address::default(),
+            None,
+            None,
+        )
+        .0;
+        let fee_with_markup = get_normal_and_subsidy_fee(
+            &mut ticker,
+            TxFeeTypes::Transfer,
+            token.id.into(),
+            Address::default(),
+            None,
+            None,
+        )
+        .0;
+        (fee, fee_with_markup)
+    };
+
+    // The fee is increased by 20%, up to the rounding of the total fee.
+    let (fee, fee_with_markup) = get_fees(&token);
+    let expected_fee = fee * Ratio::new(BigUint::from(6u32), BigUint::from(5u32));
+    let one = Ratio::from_integer(BigUint::from(1u32));
+    assert!(fee_with_markup <= &expected_fee + &one);
+    assert!(&fee_with_markup + &one >= expected_fee);
+
+    // The fees paid in other tokens aren't affected.
+    let (fee, fee_with_markup) = get_fees(&TestToken::cheap());
+    assert_eq!(fee, fee_with_markup);
+}
+
+#[test]
+fn test_fee_quote_recording() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+    let config = get_test_ticker_config();
+    let (quote_recorder, mut quotes_receiver) = FeeQuoteRecorder::new(0.5);
+    #[allow(clippy::box_default)]
+    let ticker = FeeTicker::new(Box::new(MockTickerInfo::default()), config, validator)
+        .with_quote_recorder(quote_recorder);
+    let token = TestToken::hex();
+
+    let fees: Vec<_> = (0..4)
+        .map(|_| {
+            block_on(ticker.get_fee_from_ticker_in_wei(
+                TxFeeTypes::Withdraw,
+                token.id.into(),
+                Address::default(),
+            ))
+            .unwrap()
+            .normal_fee
+        })
+        .collect();
+    block_on(ticker.get_batch_from_ticker_in_wei(
+        token.id.into(),
+        vec![(TxFeeTypes::Transfer, Address::default())],
+    ))
+    .unwrap();
+    block_on(ticker.get_batch_from_ticker_in_wei(
+        token.id.into(),
+        vec![(TxFeeTypes::Transfer, Address::default())],
+    ))
+    .unwrap();
+
+    // Every second quote is recorded along with its inputs.
+    let quotes: Vec<_> =
+        std::iter::from_fn(|| quotes_receiver.recv().now_or_never().flatten()).collect();
+    assert_eq!(quotes.len(), 3);
+    for (quote, fee) in quotes.iter().zip([&fees[1], &fees[3]]) {
+        assert_eq!(quote.fee_type, "Withdraw");
+        assert_eq!(quote.token_id, token.id);
+        assert_eq!(
+            quote.total_fee,
+            BigDecimal::from_str(&fee.total_fee.to_string()).unwrap()
+        );
+        assert_eq!(
+            quote.gas_price_wei,
+            BigDecimal::from_str(&fee.gas_price_wei.to_string()).unwrap()
+        );
+        assert_eq!(
+            quote.token_price_usd,
+            ratio_to_big_decimal(&token.price_usd, FEE_QUOTE_PRICE_PRECISION)
+        );
+    }
+    assert_eq!(quotes[2].fee_type, "Batch");
+}
@@ -0,0 +1,199 @@
+// Built-in deps
+use std::str::FromStr;
+use std::time::Instant;
+// External deps
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
+use jsonrpc_core::ErrorCode;
+use num::{rational::Ratio, traits::Pow, BigUint};
+use thiserror::Error;
+use web3::contract::{tokens::Detokenize, Options};
+use web3::types::U256;
+// Workspace deps
+use super::PriceSource;
+use crate::fee_ticker::PriceError;
+use zksync_eth_client::EthereumGateway;
+use zksync_types::{Address, Token, TokenPrice};
+
+/// Part of the Chainlink `FeedRegistry` ABI used to get the prices.
+/// The signed `answer` is decoded as unsigned, the negative answers are rejected.
+const FEED_REGISTRY_ABI: &str = r#"[
+    {
+        "inputs": [
+            { "name": "base", "type": "address" },
+            { "name": "quote", "type": "address" }
+        ],
+        "name": "latestRoundData",
+        "outputs": [
+            { "name": "roundId", "type": "uint80" },
+            { "name": "answer", "type": "uint256" },
+            { "name": "startedAt", "type": "uint256" },
+            { "name": "updatedAt", "type": "uint256" },
+            { "name": "answeredInRound", "type": "uint80" }
+        ],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [
+            { "name": "base", "type": "address" },
+            { "name": "quote", "type": "address" }
+        ],
+        "name": "decimals",
+        "outputs": [{ "name": "", "type": "uint8" }],
+        "stateMutability": "view",
+        "type": "function"
+    }
+]"#;
+
+/// Denomination of ETH in the Chainlink feed registry.
+const ETH_DENOMINATION: &str = "eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+/// Denomination of USD in the Chainlink feed registry.
+const USD_DENOMINATION: &str = "0000000000000000000000000000000000000348";
+/// JSON RPC error code of the reverted `eth_call`.
+const EXECUTION_REVERTED_CODE: i64 = 3;
+
+#[derive(Debug, Error)]
+enum ChainlinkError {
+    /// The registry has no feed for the token, its view functions revert only in this case.
+    #[error("Token '{0:?}' is not listed on Chainlink")]
+    FeedNotFound(Address),
+    #[error("Chainlink request failed: {0}")]
+    RequestFailed(anyhow::Error),
+}
+
+impl ChainlinkError {
+    fn from_call_error(base: Address, err: anyhow::Error) -> Self {
+        let is_reverted = matches!(
+            err.downcast_ref::<web3::contract::Error>(),
+            Some(web3::contract::Error::Api(web3::Error::Rpc(rpc_err)))
+                if rpc_err.code == ErrorCode::ServerError(EXECUTION_REVERTED_CODE)
+        );
+        if is_reverted {
+            Self::FeedNotFound(base)
+        } else {
+            Self::RequestFailed(err)
+        }
+    }
+}
+
+impl From<ChainlinkError> for PriceError {
+    fn from(err: ChainlinkError) -> Self {
+        match err {
+            ChainlinkError::FeedNotFound(_) => PriceError::token_not_found(err),
+            ChainlinkError::RequestFailed(_) => PriceError::api_error(err),
+        }
+    }
+}
+
+/// Price source querying the Chainlink price feeds via the feed registry contract.
+#[derive(Debug, Clone)]
+pub struct ChainlinkAPI {
+    eth_gateway: EthereumGateway,
+    feed_registry: Address,
+    feed_registry_abi: ethabi::Contract,
+}
+
+impl ChainlinkAPI {
+    pub fn new(eth_gateway: EthereumGateway, feed_registry: Address) -> Self {
+        let feed_registry_abi = ethabi::Contract::load(FEED_REGISTRY_ABI.as_bytes())
+            .expect("Chainlink feed registry abi");
+
+        Self {
+            eth_gateway,
+            feed_registry,
+            feed_registry_abi,
+        }
+    }
+
+    async fn call_feed_registry<R>(&self, func: &str, base: Address) -> Result<R, PriceError>
+    where
+        R: Detokenize + Unpin,
+    {
+        let quote = Address::from_str(USD_DENOMINATION).unwrap();
+        self.eth_gateway
+            .call_contract_function(
+                func,
+                (base, quote),
+                None,
+                Options::default(),
+                None,
+                self.feed_registry,
+                self.feed_registry_abi.clone(),
+            )
+            .await
+            .map_err(|err| PriceError::from(ChainlinkError::from_call_error(base, err)))
+    }
+}
+
+#[async_trait]
+impl PriceSource for ChainlinkAPI {
+    async fn get_price(&self, token: &Token) -> Result<TokenPrice, PriceError> {
+        let start = Instant::now();
+        let base = if token.id.0 == 0 {
+            Address::from_str(ETH_DENOMINATION).unwrap()
+        } else {
+            token.address
+        };
+
+        let (_round_id, answer, _started_at, updated_at, _answered_in_round): (
+            U256,
+            U256,
+            U256,
+            U256,
+            U256,
+        ) = self.call_feed_registry("latestRoundData", base).await?;
+        let decimals: U256 = self.call_feed_registry("decimals", base).await?;
+
+        if answer.is_zero() || answer.bit(255) {
+            return Err(PriceError::api_error(format!(
+                "Chainlink returned non-positive price for the token {}",
+                token.symbol
+            )));
+        }
+
+        let usd_price = Ratio::new(
+            BigUint::from_str(&answer.to_string()).unwrap(),
+            BigUint::from(10u32).pow(decimals.as_u32()),
+        );
+        let last_updated = Utc.timestamp(updated_at.as_u64() as i64, 0);
+        metrics::histogram!("ticker.chainlink.request", start.elapsed());
+        Ok(TokenPrice {
+            usd_price,
+            last_updated,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rpc_error(code: ErrorCode) -> anyhow::Error {
+        web3::contract::Error::Api(web3::Error::Rpc(jsonrpc_core::Error {
+            code,
+            message: "execution reverted".to_string(),
+            data: None,
+        }))
+        .into()
+    }
+
+    #[test]
+    fn missing_feed_is_token_not_found() {
+        let base = Address::repeat_byte(1);
+        let err = ChainlinkError::from_call_error(
+            base,
+            rpc_error(ErrorCode::ServerError(EXECUTION_REVERTED_CODE)),
+        );
+        assert!(matches!(err, ChainlinkError::FeedNotFound(address) if address == base));
+        assert!(matches!(
+            PriceError::from(err),
+            PriceError::TokenNotFound(_)
+        ));
+
+        let err = ChainlinkError::from_call_error(base, rpc_error(ErrorCode::InternalError));
+        assert!(matches!(PriceError::from(err), PriceError::ApiError(_)));
+        let err = ChainlinkError::from_call_error(base, anyhow::anyhow!("Feed not found"));
+        assert!(matches!(PriceError::from(err), PriceError::ApiError(_)));
+    }
+}
@@ -1,4 +1,4 @@
-use super::{TokenPriceAPI, REQUEST_TIMEOUT};
+use super::{PriceSource, REQUEST_TIMEOUT};
 use crate::fee_ticker::ticker_api::PriceError;
 use async_trait::async_trait;
 use chrono::{DateTime, NaiveDateTime, Utc};
@@ -55,7 +55,7 @@ impl CoinGeckoAPI {
 }
 
 #[async_trait]
-impl TokenPriceAPI for CoinGeckoAPI {
+impl PriceSource for CoinGeckoAPI {
     async fn get_price(&self, token: &Token) -> Result<TokenPrice, PriceError> {
         let start = Instant::now();
         let token_id = self.token_ids.get(&token.address).ok_or_else(|| {
@@ -7,7 +7,7 @@ use num::{rational::Ratio, BigUint};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 // Workspace deps
-use super::{TokenPriceAPI, REQUEST_TIMEOUT};
+use super::{PriceSource, REQUEST_TIMEOUT};
 use crate::fee_ticker::PriceError;
 use zksync_types::{Token, TokenLike, TokenPrice};
 use zksync_utils::UnsignedRatioSerializeAsDecimal;
@@ -25,7 +25,7 @@ impl CoinMarketCapAPI {
 }
 
 #[async_trait]
-impl TokenPriceAPI for CoinMarketCapAPI {
+impl PriceSource for CoinMarketCapAPI {
     async fn get_price(&self, token: &Token) -> Result<TokenPrice, PriceError> {
         let token_symbol = token.symbol.as_str();
         let request_url = self
@@ -0,0 +1,303 @@
+// Built-in deps
+use std::collections::HashMap;
+use std::sync::Mutex;
+// External deps
+use async_trait::async_trait;
+use num::{rational::Ratio, BigUint, Zero};
+// Workspace deps
+use super::PriceSource;
+use crate::fee_ticker::PriceError;
+use zksync_types::{Token, TokenId, TokenPrice};
+
+pub type BoxedPriceSource = Box<dyn PriceSource + Send + Sync>;
+
+/// Number of the consecutive consistent readings out of the sanity bounds
+/// after which the price move is considered real and the new price is accepted.
+pub const PRICE_MOVE_CONFIRMATIONS: u32 = 3;
+
+/// Last accepted price of the token and the price move being confirmed, if any.
+#[derive(Debug, Clone)]
+struct PriceAnchor {
+    price: Ratio<BigUint>,
+    /// The price out of the bounds and the number of the consistent readings of it.
+    pending_move: Option<(Ratio<BigUint>, u32)>,
+}
+
+/// Price source combining the primary source with the optional fallback one.
+///
+/// The price returned by the source is accepted if it's within the sanity bounds: it's not zero,
+/// and it's changed by no more than `max_price_change_percent` since the last accepted price of the token.
+/// The fallback source is queried if the primary one fails or returns the price out of the bounds.
+/// If the prices of both sources are out of the bounds, but they agree with each other, the market
+/// is considered to be actually moving and the primary price is accepted.
+/// The same holds if the price stays out of the bounds for `PRICE_MOVE_CONFIRMATIONS` consecutive
+/// readings consistent with each other, so the bounds are re-anchored after a real market move.
+pub struct FallbackPriceSource {
+    primary: BoxedPriceSource,
+    fallback: Option<BoxedPriceSource>,
+    max_price_change_percent: u32,
+    last_prices: Mutex<HashMap<TokenId, PriceAnchor>>,
+}
+
+impl FallbackPriceSource {
+    /// Creates the price source.
+    ///
+    /// # Arguments
+    ///
+    /// * `primary` - The source queried first
+    /// * `fallback` - The source queried if the primary one fails, if any
+    /// * `max_price_change_percent` - The sanity bound of the price change, 0 disables the check
+    ///
+    pub fn new(
+        primary: BoxedPriceSource,
+        fallback: Option<BoxedPriceSource>,
+        max_price_change_percent: u32,
+    ) -> Self {
+        Self {
+            primary,
+            fallback,
+            max_price_change_percent,
+            last_prices: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Checks whether the prices differ by no more than `max_price_change_percent` of the `reference` one.
+    fn is_close(&self, price: &Ratio<BigUint>, reference: &Ratio<BigUint>) -> bool {
+        if self.max_price_change_percent == 0 {
+            return true;
+        }
+        let diff = if price > reference {
+            price - reference
+        } else {
+            reference - price
+        };
+        diff * BigUint::from(100u32)
+            <= reference.clone() * BigUint::from(self.max_price_change_percent)
+    }
+
+    fn is_within_bounds(&self, token_id: TokenId, price: &TokenPrice) -> bool {
+        if price.usd_price.is_zero() {
+            return false;
+        }
+        match self.last_prices.lock().unwrap().get(&token_id) {
+            Some(anchor) => self.is_close(&price.usd_price, &anchor.price),
+            None => true,
+        }
+    }
+
+    /// Records the reading out of the sanity bounds and checks whether it confirms the price move,
+    /// i.e. the last `PRICE_MOVE_CONFIRMATIONS` readings are consistent with each other.
+    fn confirms_price_move(&self, token_id: TokenId, price: &TokenPrice) -> bool {
+        if price.usd_price.is_zero() {
+            return false;
+        }
+        let mut last_prices = self.last_prices.lock().unwrap();
+        let anchor = match last_prices.get_mut(&token_id) {
+            Some(anchor) => anchor,
+            None => return true,
+        };
+        let readings = match &anchor.pending_move {
+            Some((pending_price, readings)) if self.is_close(&price.usd_price, pending_price) => {
+                readings + 1
+            }
+            _ => 1,
+        };
+        anchor.pending_move = Some((price.usd_price.clone(), readings));
+        readings >= PRICE_MOVE_CONFIRMATIONS
+    }
+
+    fn accept(&self, token_id: TokenId, price: TokenPrice) -> TokenPrice {
+        self.last_prices.lock().unwrap().insert(
+            token_id,
+            PriceAnchor {
+                price: price.usd_price.clone(),
+                pending_move: None,
+            },
+        );
+        price
+    }
+}
+
+#[async_trait]
+impl PriceSource for FallbackPriceSource {
+    async fn get_price(&self, token: &Token) -> Result<TokenPrice, PriceError> {
+        let primary = match self.primary.get_price(token).await {
+            Ok(price) if self.is_within_bounds(token.id, &price) => {
+                return Ok(self.accept(token.id, price))
+            }
+            result => result,
+        };
+
+        let fallback = match &self.fallback {
+            Some(fallback) => fallback,
+            None => {
+                let price = primary?;
+                if self.confirms_price_move(token.id, &price) {
+                    return Ok(self.accept(token.id, price));
+                }
+                return Err(PriceError::api_error(format!(
+                    "Price {} of the token {} is out of the sanity bounds",
+                    price.usd_price, token.symbol
+                )));
+            }
+        };
+        metrics::increment_counter!("ticker.price_source.fallback");
+
+        match (primary, fallback.get_price(token).await) {
+            (_, Ok(price)) if self.is_within_bounds(token.id, &price) => {
+                Ok(self.accept(token.id, price))
+            }
+            (Ok(primary), Ok(fallback))
+                if !primary.usd_price.is_zero()
+                    && self.is_close(&primary.usd_price, &fallback.usd_price) =>
+            {
+                Ok(self.accept(token.id, primary))
+            }
+            (primary, Ok(fallback)) => {
+                let price = match primary {
+                    Ok(primary) if !primary.usd_price.is_zero() => primary,
+                    _ => fallback,
+                };
+                if self.confirms_price_move(token.id, &price) {
+                    Ok(self.accept(token.id, price))
+                } else {
+                    Err(PriceError::api_error(format!(
+                        "Prices of the token {} from all the sources are out of the sanity bounds",
+                        token.symbol
+                    )))
+                }
+            }
+            // The token is unknown to the fallback source, report the primary error.
+            (Err(err), Err(PriceError::TokenNotFound(_))) => Err(err),
+            (_, Err(err)) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::sync::Arc;
+    use zksync_types::TokenKind;
+
+    /// Price source returning the price set by the test, `None` means the API error.
+    #[derive(Clone, Default)]
+    struct MockPriceSource(Arc<Mutex<Option<u32>>>);
+
+    impl MockPriceSource {
+        fn set(&self, price: Option<u32>) {
+            *self.0.lock().unwrap() = price;
+        }
+    }
+
+    #[async_trait]
+    impl PriceSource for MockPriceSource {
+        async fn get_price(&self, _token: &Token) -> Result<TokenPrice, PriceError> {
+            let price = self
+                .0
+                .lock()
+                .unwrap()
+                .ok_or_else(|| PriceError::api_error("Unavailable"))?;
+            Ok(TokenPrice {
+                usd_price: Ratio::from_integer(price.into()),
+                last_updated: Utc::now(),
+            })
+        }
+    }
+
+    async fn get_price(source: &FallbackPriceSource, token: &Token) -> Option<u32> {
+        let price = source.get_price(token).await.ok()?;
+        Some(price.usd_price.to_integer().to_string().parse().unwrap())
+    }
+
+    #[tokio::test]
+    async fn fallback_price_source() {
+        let token = Token::new(TokenId(1), Default::default(), "DAI", 18, TokenKind::ERC20);
+        let primary = MockPriceSource::default();
+        let fallback = MockPriceSource::default();
+        let source = FallbackPriceSource::new(
+            Box::new(primary.clone()),
+            Some(Box::new(fallback.clone())),
+            50,
+        );
+
+        // Nothing is available.
+        assert_eq!(get_price(&source, &token).await, None);
+
+        // The primary source is preferred.
+        primary.set(Some(100));
+        fallback.set(Some(110));
+        assert_eq!(get_price(&source, &token).await, Some(100));
+
+        // The primary source fails.
+        primary.set(None);
+        assert_eq!(get_price(&source, &token).await, Some(110));
+
+        // The primary price is out of the bounds.
+        primary.set(Some(1000));
+        fallback.set(Some(120));
+        assert_eq!(get_price(&source, &token).await, Some(120));
+
+        // Both sources agree on the big price change.
+        fallback.set(Some(1100));
+        assert_eq!(get_price(&source, &token).await, Some(1000));
+
+        // Zero price is never accepted.
+        primary.set(Some(0));
+        fallback.set(Some(0));
+        assert_eq!(get_price(&source, &token).await, None);
+
+        // The price move reported by the only available source is accepted once confirmed.
+        primary.set(None);
+        fallback.set(Some(2000));
+        for _ in 1..PRICE_MOVE_CONFIRMATIONS {
+            assert_eq!(get_price(&source, &token).await, None);
+        }
+        assert_eq!(get_price(&source, &token).await, Some(2000));
+    }
+
+    #[tokio::test]
+    async fn price_source_without_fallback() {
+        let token = Token::new(TokenId(1), Default::default(), "DAI", 18, TokenKind::ERC20);
+        let primary = MockPriceSource::default();
+        let source = FallbackPriceSource::new(Box::new(primary.clone()), None, 50);
+
+        primary.set(Some(100));
+        assert_eq!(get_price(&source, &token).await, Some(100));
+        primary.set(Some(149));
+        assert_eq!(get_price(&source, &token).await, Some(149));
+        primary.set(Some(1000));
+        assert_eq!(get_price(&source, &token).await, None);
+
+        // The price move is accepted once it's confirmed by the consistent readings.
+        primary.set(Some(1010));
+        assert_eq!(get_price(&source, &token).await, None);
+        primary.set(Some(1005));
+        assert_eq!(get_price(&source, &token).await, Some(1005));
+        // The bounds are re-anchored to the new price.
+        primary.set(Some(1100));
+        assert_eq!(get_price(&source, &token).await, Some(1100));
+
+        // The inconsistent readings don't confirm the move.
+        for price in [5000, 20000, 5000, 20000] {
+            primary.set(Some(price));
+            assert_eq!(get_price(&source, &token).await, None);
+        }
+        // The accepted price resets the pending move.
+        primary.set(Some(5000));
+        assert_eq!(get_price(&source, &token).await, None);
+        assert_eq!(get_price(&source, &token).await, None);
+        primary.set(Some(1200));
+        assert_eq!(get_price(&source, &token).await, Some(1200));
+        primary.set(Some(5000));
+        assert_eq!(get_price(&source, &token).await, None);
+
+        // The check can be disabled.
+        let source = FallbackPriceSource::new(Box::new(primary.clone()), None, 0);
+        primary.set(Some(100));
+        assert_eq!(get_price(&source, &token).await, Some(100));
+        primary.set(Some(1000));
+        assert_eq!(get_price(&source, &token).await, Some(1000));
+    }
+}
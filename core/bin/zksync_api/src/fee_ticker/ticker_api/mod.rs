@@ -10,8 +10,11 @@ use std::time::{Duration, Instant};
 use zksync_storage::ConnectionPool;
 use zksync_types::{Token, TokenId, TokenPrice};
 
+pub mod chainlink;
 pub mod coingecko;
 pub mod coinmarkercap;
+pub mod fallback;
+pub mod uniswap_twap;
 
 const UPDATE_PRICE_INTERVAL_SECS: u64 = 10 * 60;
 /// The limit of time we are willing to wait for response.
@@ -19,8 +22,12 @@ pub const REQUEST_TIMEOUT: Duration = Duration::from_millis(700);
 /// Configuration parameter of the reqwest Client
 pub const CONNECTION_TIMEOUT: Duration = Duration::from_millis(700);
 
+/// Source of the token prices in USD, e.g. the market data API or the on-chain oracle.
+/// The sources can be combined via the `FallbackPriceSource`.
 #[async_trait]
-pub trait TokenPriceAPI {
+pub trait PriceSource {
+    /// Returns the current USD price of the token, `PriceError::TokenNotFound`
+    /// if the token isn't listed in the source.
     async fn get_price(&self, token: &Token) -> Result<TokenPrice, PriceError>;
 }
 
@@ -31,13 +38,13 @@ pub trait FeeTickerAPI {
 }
 
 #[derive(Debug, Clone)]
-pub(super) struct TickerApi<T: TokenPriceAPI> {
+pub(super) struct TickerApi<T: PriceSource> {
     db_pool: ConnectionPool,
 
     token_price_api: T,
 }
 
-impl<T: TokenPriceAPI> TickerApi<T> {
+impl<T: PriceSource> TickerApi<T> {
     pub fn new(db_pool: ConnectionPool, token_price_api: T) -> Self {
         Self {
             db_pool,
@@ -82,7 +89,7 @@ impl<T: TokenPriceAPI> TickerApi<T> {
         let api_price = match self.token_price_api.get_price(token).await {
             Ok(api_price) => api_price,
 
-            // Database contain this token, but is not listed in CoinGecko(CoinMarketCap)
+            // Database contain this token, but is not listed in the price source
             Err(PriceError::TokenNotFound(_)) => TokenPrice {
                 usd_price: Ratio::from_integer(0u32.into()),
                 last_updated: Utc::now(),
@@ -99,7 +106,7 @@ impl<T: TokenPriceAPI> TickerApi<T> {
 }
 
 #[async_trait]
-impl<T: TokenPriceAPI + Send + Sync> FeeTickerAPI for TickerApi<T> {
+impl<T: PriceSource + Send + Sync> FeeTickerAPI for TickerApi<T> {
     async fn keep_price_updated(self) {
         loop {
             if let Ok(tokens) = self.get_all_tokens().await {
@@ -0,0 +1,246 @@
+// Built-in deps
+use std::str::FromStr;
+use std::time::Instant;
+// External deps
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use num::rational::Ratio;
+use web3::contract::{tokens::Detokenize, tokens::Tokenize, Options};
+use web3::types::U256;
+// Workspace deps
+use super::PriceSource;
+use crate::fee_ticker::PriceError;
+use zksync_contracts::erc20_metadata_contract;
+use zksync_eth_client::EthereumGateway;
+use zksync_types::{Address, Token, TokenPrice};
+use zksync_utils::big_decimal_to_ratio;
+
+/// Part of the Uniswap V3 `UniswapV3Factory` and `UniswapV3Pool` ABIs used to get the prices.
+/// The signed tick cumulatives are decoded as unsigned and converted manually.
+const UNISWAP_V3_ABI: &str = r#"[
+    {
+        "inputs": [
+            { "name": "tokenA", "type": "address" },
+            { "name": "tokenB", "type": "address" },
+            { "name": "fee", "type": "uint24" }
+        ],
+        "name": "getPool",
+        "outputs": [{ "name": "pool", "type": "address" }],
+        "stateMutability": "view",
+        "type": "function"
+    },
+    {
+        "inputs": [{ "name": "secondsAgos", "type": "uint32[]" }],
+        "name": "observe",
+        "outputs": [
+            { "name": "tickCumulatives", "type": "uint256[]" },
+            { "name": "secondsPerLiquidityCumulativeX128s", "type": "uint256[]" }
+        ],
+        "stateMutability": "view",
+        "type": "function"
+    }
+]"#;
+
+/// Price source calculating the time-weighted average price of the token from the
+/// Uniswap V3 pool of the token and the USD stablecoin.
+#[derive(Debug, Clone)]
+pub struct UniswapTwapAPI {
+    eth_gateway: EthereumGateway,
+    abi: ethabi::Contract,
+    factory: Address,
+    quote_token: Address,
+    quote_token_decimals: u8,
+    weth: Address,
+    pool_fee: u32,
+    period_secs: u32,
+}
+
+impl UniswapTwapAPI {
+    /// Creates the price source, the decimals of the quote token are requested from the contract.
+    ///
+    /// # Arguments
+    ///
+    /// * `factory` - Address of the Uniswap V3 factory
+    /// * `quote_token` - Address of the USD stablecoin the prices are quoted in
+    /// * `weth` - Address of the WETH token, used instead of ETH
+    /// * `pool_fee` - Fee tier of the pools, in hundredths of a bip
+    /// * `period_secs` - Period the average price is calculated for
+    ///
+    pub async fn new(
+        eth_gateway: EthereumGateway,
+        factory: Address,
+        quote_token: Address,
+        weth: Address,
+        pool_fee: u32,
+        period_secs: u32,
+    ) -> anyhow::Result<Self> {
+        let quote_token_decimals: U256 = eth_gateway
+            .call_contract_function(
+                "decimals",
+                (),
+                None,
+                Options::default(),
+                None,
+                quote_token,
+                erc20_metadata_contract(),
+            )
+            .await?;
+        let abi = ethabi::Contract::load(UNISWAP_V3_ABI.as_bytes()).expect("Uniswap V3 abi");
+
+        Ok(Self {
+            eth_gateway,
+            abi,
+            factory,
+            quote_token,
+            quote_token_decimals: quote_token_decimals.as_u32() as u8,
+            weth,
+            pool_fee,
+            period_secs,
+        })
+    }
+
+    async fn call<R, P>(&self, func: &str, params: P, address: Address) -> Result<R, PriceError>
+    where
+        R: Detokenize + Unpin,
+        P: Tokenize + Clone,
+    {
+        self.eth_gateway
+            .call_contract_function(
+                func,
+                params,
+                None,
+                Options::default(),
+                None,
+                address,
+                self.abi.clone(),
+            )
+            .await
+            .map_err(|err| PriceError::api_error(format!("Uniswap request failed: {}", err)))
+    }
+}
+
+/// Converts the tick cumulative decoded as unsigned back to the signed value.
+/// Tick cumulatives are `int56`, so the lowest 64 bits of the sign-extended value are enough.
+fn tick_cumulative(value: U256) -> i64 {
+    value.low_u64() as i64
+}
+
+/// Returns the average tick over the period, rounded to the negative infinity
+/// as it's done by the Uniswap `OracleLibrary`.
+fn average_tick(tick_cumulative_start: i64, tick_cumulative_end: i64, period_secs: u32) -> i32 {
+    let delta = tick_cumulative_end - tick_cumulative_start;
+    let period = i64::from(period_secs);
+    let mut tick = delta / period;
+    if delta < 0 && delta % period != 0 {
+        tick -= 1;
+    }
+    tick as i32
+}
+
+/// Returns the price of the token in the quote token units using the pool tick.
+///
+/// # Arguments
+///
+/// * `tick` - The pool tick, the price of `token0` in `token1` base units is `1.0001^tick`
+/// * `is_token0` - Whether the token is the `token0` of the pool, i.e. its address is lower
+/// * `decimals` - Decimals of the token
+/// * `quote_decimals` - Decimals of the quote token
+///
+fn tick_to_price(tick: i32, is_token0: bool, decimals: u8, quote_decimals: u8) -> f64 {
+    let tick = if is_token0 { tick } else { -tick };
+    1.0001f64.powi(tick) * 10f64.powi(i32::from(decimals) - i32::from(quote_decimals))
+}
+
+#[async_trait]
+impl PriceSource for UniswapTwapAPI {
+    async fn get_price(&self, token: &Token) -> Result<TokenPrice, PriceError> {
+        let start = Instant::now();
+        let token_address = if token.id.0 == 0 {
+            self.weth
+        } else {
+            token.address
+        };
+        if token_address == self.quote_token {
+            return Ok(TokenPrice {
+                usd_price: Ratio::from_integer(1u32.into()),
+                last_updated: Utc::now(),
+            });
+        }
+
+        let pool: Address = self
+            .call(
+                "getPool",
+                (token_address, self.quote_token, U256::from(self.pool_fee)),
+                self.factory,
+            )
+            .await?;
+        if pool.is_zero() {
+            return Err(PriceError::token_not_found(format!(
+                "Token '{}, {:?}' has no Uniswap V3 pool",
+                token.symbol, token.address
+            )));
+        }
+
+        let (tick_cumulatives, _): (Vec<U256>, Vec<U256>) = self
+            .call(
+                "observe",
+                vec![U256::from(self.period_secs), U256::zero()],
+                pool,
+            )
+            .await?;
+        if tick_cumulatives.len() != 2 {
+            return Err(PriceError::api_error(
+                "Uniswap returned unexpected observations",
+            ));
+        }
+        let tick = average_tick(
+            tick_cumulative(tick_cumulatives[0]),
+            tick_cumulative(tick_cumulatives[1]),
+            self.period_secs,
+        );
+
+        let is_token0 = token_address < self.quote_token;
+        let price = tick_to_price(tick, is_token0, token.decimals, self.quote_token_decimals);
+        let usd_price = BigDecimal::from_str(&price.to_string())
+            .map_err(PriceError::api_error)
+            .and_then(|price| big_decimal_to_ratio(&price).map_err(PriceError::api_error))?;
+
+        metrics::histogram!("ticker.uniswap_twap.request", start.elapsed());
+        Ok(TokenPrice {
+            usd_price,
+            last_updated: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn twap_tick_calculation() {
+        // Tick cumulatives are sign-extended to 256 bits.
+        assert_eq!(tick_cumulative(U256::from(42)), 42);
+        assert_eq!(tick_cumulative(U256::max_value()), -1);
+
+        assert_eq!(average_tick(0, 1800 * 10, 1800), 10);
+        assert_eq!(average_tick(0, -1800 * 10, 1800), -10);
+        // Rounded to the negative infinity.
+        assert_eq!(average_tick(0, 1801, 1800), 1);
+        assert_eq!(average_tick(0, -1801, 1800), -2);
+    }
+
+    #[test]
+    fn twap_tick_to_price() {
+        // Zero tick means the equal base units price.
+        assert!((tick_to_price(0, true, 6, 6) - 1.0).abs() < 1e-9);
+        // ETH (18 decimals) is `token1` in the USDC (6 decimals) pool, the tick around
+        // 200000 corresponds to the price around 2000 USD.
+        let price = tick_to_price(200_000, false, 18, 6);
+        assert!(price > 2000.0 && price < 2100.0);
+        // The same pool with the token order swapped.
+        let price = tick_to_price(-200_000, true, 18, 6);
+        assert!(price > 2000.0 && price < 2100.0);
+    }
+}
@@ -18,6 +18,7 @@ use zksync_token_db_cache::TokenDBCache;
 use zksync_types::aggregated_operations::AggregatedActionType;
 use zksync_types::{Address, Token, TokenId, TokenLike, TokenPrice};
 // Local deps
+use crate::fee_ticker::price_cache::{PriceCache, PriceCachePolicy};
 use crate::fee_ticker::PriceError;
 
 pub trait FeeTickerClone {
@@ -69,13 +70,15 @@ pub trait FeeTickerInfo: FeeTickerClone + Send + Sync + 'static {
 pub struct TickerInfo {
     db: ConnectionPool,
     token_db_cache: TokenDBCache,
+    price_cache: PriceCache,
 }
 
 impl TickerInfo {
-    pub fn new(db: ConnectionPool) -> Self {
+    pub fn new(db: ConnectionPool, price_cache_policy: PriceCachePolicy) -> Self {
         Self {
             db,
             token_db_cache: Default::default(),
+            price_cache: PriceCache::new(price_cache_policy),
         }
     }
 }
@@ -201,13 +204,26 @@ impl FeeTickerInfo for TickerInfo {
             });
         }
 
+        if let Some(price) = self.price_cache.get(&token).await {
+            metrics::histogram!("ticker_info.get_last_token_price", start.elapsed(), "type" => "cached");
+            return self.price_cache.check_age(&token, price);
+        }
+
         let historical_price = self
             .get_ticker_price(token.id)
             .await
             .map_err(|e| vlog::warn!("Failed to get historical ticker price: {}", e));
 
         if let Ok(Some(historical_price)) = historical_price {
-            return Ok(historical_price);
+            self.price_cache
+                .insert(token.id, historical_price.clone())
+                .await;
+            return self.price_cache.check_age(&token, historical_price);
+        }
+
+        // The database is unavailable, fall back to the expired cached price, if any.
+        if let Some(price) = self.price_cache.get_expired(token.id).await {
+            return self.price_cache.check_age(&token, price);
         }
 
         metrics::histogram!("ticker_info.get_last_token_price", start.elapsed(), "type" => "error");
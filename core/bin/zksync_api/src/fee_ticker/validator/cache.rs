@@ -5,7 +5,10 @@ use tokio::sync::Mutex;
 use zksync_token_db_cache::TokenDBCache;
 
 use zksync_storage::ConnectionPool;
-use zksync_types::{tokens::TokenMarketVolume, Token, TokenId, TokenLike};
+use zksync_types::{
+    tokens::{FeeTokenEligibility, TokenMarketVolume},
+    Token, TokenId, TokenLike,
+};
 
 #[derive(Debug, Clone)]
 pub(crate) enum TokenCacheWrapper {
@@ -25,6 +28,7 @@ pub(crate) struct TokenInDBCache {
 pub struct TokenInMemoryCache {
     tokens: Arc<Mutex<HashMap<TokenLike, Token>>>,
     market: Arc<Mutex<HashMap<TokenId, TokenMarketVolume>>>,
+    eligibility: Arc<Mutex<HashMap<TokenId, FeeTokenEligibility>>>,
 }
 
 impl TokenInDBCache {
@@ -52,6 +56,13 @@ impl TokenInMemoryCache {
             ..self
         }
     }
+
+    pub fn with_eligibility(self, eligibility: HashMap<TokenId, FeeTokenEligibility>) -> Self {
+        Self {
+            eligibility: Arc::new(Mutex::new(eligibility)),
+            ..self
+        }
+    }
 }
 
 #[cfg(test)]
@@ -130,6 +141,25 @@ impl TokenCacheWrapper {
             }
         }
     }
+
+    /// Returns the decision of the fee token policy for the token, if it was made.
+    pub async fn get_fee_token_eligibility(
+        &self,
+        token_id: TokenId,
+    ) -> anyhow::Result<Option<FeeTokenEligibility>> {
+        match self {
+            Self::DB(cache) => Ok(cache
+                .pool
+                .access_storage()
+                .await?
+                .tokens_schema()
+                .get_fee_token_eligibility(token_id)
+                .await?),
+            #[cfg(test)]
+            Self::Memory(cache) => Ok(cache.eligibility.lock().await.get(&token_id).cloned()),
+        }
+    }
+
     pub async fn get_all_tokens(&self) -> anyhow::Result<Vec<Token>> {
         match self {
             Self::DB(cache) => {
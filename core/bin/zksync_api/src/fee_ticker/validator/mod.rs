@@ -2,6 +2,7 @@
 //! an entity which decides whether certain ERC20 token is suitable for paying fees.
 
 pub mod cache;
+pub mod policy;
 pub mod watcher;
 
 // Built-in uses
@@ -137,6 +138,16 @@ impl FeeTokenValidator {
 
     async fn check_token(&self, token: Token) -> anyhow::Result<bool> {
         let start = Instant::now();
+        // The decision of the fee token policy takes precedence, if it was made.
+        if let Some(eligibility) = self
+            .tokens_cache
+            .get_fee_token_eligibility(token.id)
+            .await?
+        {
+            metrics::histogram!("ticker.validator.check_token", start.elapsed());
+            return Ok(eligibility.eligible);
+        }
+
         let volume = match self.get_token_market_volume(&token).await? {
             Some(volume) => volume,
             None => return Ok(false),
@@ -168,7 +179,7 @@ mod tests {
     use std::str::FromStr;
     use std::sync::Arc;
     use tokio::sync::Mutex;
-    use zksync_types::{TokenId, TokenKind};
+    use zksync_types::{tokens::FeeTokenEligibility, TokenId, TokenKind};
 
     #[derive(Clone)]
     struct InMemoryTokenWatcher {
@@ -284,4 +295,65 @@ mod tests {
         assert!(!phnx_allowed);
         assert!(eth_allowed);
     }
+
+    #[tokio::test]
+    async fn fee_token_policy_precedence() {
+        let dai_token_address =
+            Address::from_str("6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let dai_token = Token::new(TokenId(1), dai_token_address, "DAI", 18, TokenKind::ERC20);
+
+        let mut market = HashMap::new();
+        market.insert(
+            dai_token.id,
+            TokenMarketVolume {
+                market_volume: Ratio::new(BigUint::from(200u32), BigUint::from(1u32)),
+                last_updated: Utc::now(),
+            },
+        );
+        let mut tokens = HashMap::new();
+        tokens.insert(TokenLike::Address(dai_token_address), dai_token.clone());
+        let mut eligibility = HashMap::new();
+        eligibility.insert(
+            dai_token.id,
+            FeeTokenEligibility {
+                token_id: dai_token.id,
+                eligible: false,
+                reason: "Market cap is unknown".to_string(),
+                market_volume: Ratio::new(BigUint::from(200u32), BigUint::from(1u32)),
+                market_cap: None,
+                decided_at: Utc::now(),
+            },
+        );
+
+        let cache = TokenInMemoryCache::new()
+            .with_tokens(tokens.clone())
+            .with_market(market.clone());
+        let validator = FeeTokenValidator::new(
+            cache,
+            chrono::Duration::seconds(100),
+            BigDecimal::from(100),
+            HashSet::new(),
+        );
+        // Enough market volume without the policy decision.
+        assert!(validator
+            .token_allowed(TokenLike::Address(dai_token_address))
+            .await
+            .unwrap());
+
+        let cache = TokenInMemoryCache::new()
+            .with_tokens(tokens)
+            .with_market(market)
+            .with_eligibility(eligibility);
+        let validator = FeeTokenValidator::new(
+            cache,
+            chrono::Duration::seconds(100),
+            BigDecimal::from(100),
+            HashSet::new(),
+        );
+        // The token is disabled by the policy.
+        assert!(!validator
+            .token_allowed(TokenLike::Address(dai_token_address))
+            .await
+            .unwrap());
+    }
 }
@@ -0,0 +1,300 @@
+//! Fee token policy periodically evaluates the tokens against the liquidity and market cap
+//! thresholds and automatically enables or disables them as fee tokens.
+//!
+//! Decisions are stored in the database along with their history and take precedence
+//! over the market volume check of the `FeeTokenValidator`.
+
+// Built-in uses
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+// External uses
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use num::{rational::Ratio, traits::Pow, BigUint, Zero};
+use web3::contract::Options;
+use web3::types::U256;
+
+// Workspace uses
+use zksync_contracts::erc20_contract;
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::ConnectionPool;
+use zksync_types::{
+    tokens::{FeeTokenEligibility, TokenMarketVolume, TokenPrice},
+    Address, Token, TokenId, TokenKind,
+};
+use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
+
+/// Thresholds the tokens have to meet to be used to pay fees.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeTokenPolicy {
+    min_market_volume: Ratio<BigUint>,
+    /// Minimal market cap in USD, the check is disabled if not set.
+    min_market_cap: Option<Ratio<BigUint>>,
+}
+
+impl FeeTokenPolicy {
+    pub fn new(min_market_volume: Ratio<BigUint>, min_market_cap: Option<Ratio<BigUint>>) -> Self {
+        Self {
+            min_market_volume,
+            min_market_cap,
+        }
+    }
+
+    pub fn from_config(config: &zksync_config::TickerConfig) -> Self {
+        let to_ratio = |value: f64| {
+            let value = BigDecimal::try_from(value).expect("Valid f64 for decimal");
+            big_decimal_to_ratio(&value).expect("Fee token policy thresholds must be positive")
+        };
+        let min_market_cap = Some(to_ratio(config.min_market_cap_usd))
+            .filter(|min_market_cap| !min_market_cap.is_zero());
+
+        Self::new(to_ratio(config.liquidity_volume), min_market_cap)
+    }
+
+    /// Returns `true` if the market cap of the tokens has to be known to evaluate them.
+    pub fn requires_market_cap(&self) -> bool {
+        self.min_market_cap.is_some()
+    }
+
+    /// Decides whether the token can be used to pay fees.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_id` - ID of the evaluated token
+    /// * `market_volume` - Market volume of the token, if it's known
+    /// * `market_cap` - Market cap of the token in USD, if it's known
+    ///
+    pub fn evaluate(
+        &self,
+        token_id: TokenId,
+        market_volume: Option<&TokenMarketVolume>,
+        market_cap: Option<Ratio<BigUint>>,
+    ) -> FeeTokenEligibility {
+        let market_volume = market_volume
+            .map(|volume| volume.market_volume.clone())
+            .unwrap_or_else(Ratio::zero);
+
+        let rejection = if market_volume < self.min_market_volume {
+            Some(format!(
+                "Market volume {} is below {}",
+                ratio_to_big_decimal(&market_volume, 2),
+                ratio_to_big_decimal(&self.min_market_volume, 2)
+            ))
+        } else {
+            match (&self.min_market_cap, &market_cap) {
+                (Some(_), None) => Some("Market cap is unknown".to_string()),
+                (Some(min_market_cap), Some(market_cap)) if market_cap < min_market_cap => {
+                    Some(format!(
+                        "Market cap {} USD is below {} USD",
+                        ratio_to_big_decimal(market_cap, 2),
+                        ratio_to_big_decimal(min_market_cap, 2)
+                    ))
+                }
+                _ => None,
+            }
+        };
+
+        FeeTokenEligibility {
+            token_id,
+            eligible: rejection.is_none(),
+            reason: rejection.unwrap_or_else(|| "All the thresholds are met".to_string()),
+            market_volume,
+            market_cap,
+            decided_at: Utc::now(),
+        }
+    }
+}
+
+/// Periodically applies the fee token policy to all the tokens.
+/// Only the changed decisions are stored, so the history contains the moments
+/// when the tokens were enabled or disabled.
+#[derive(Debug, Clone)]
+pub(crate) struct FeeTokenPolicyUpdater {
+    connection_pool: ConnectionPool,
+    eth_gateway: EthereumGateway,
+    policy: FeeTokenPolicy,
+    /// Tokens that are always acceptable for paying fees, they aren't evaluated.
+    unconditionally_valid: HashSet<Address>,
+}
+
+impl FeeTokenPolicyUpdater {
+    pub(crate) fn new(
+        connection_pool: ConnectionPool,
+        eth_gateway: EthereumGateway,
+        policy: FeeTokenPolicy,
+        unconditionally_valid: HashSet<Address>,
+    ) -> Self {
+        Self {
+            connection_pool,
+            eth_gateway,
+            policy,
+            unconditionally_valid,
+        }
+    }
+
+    /// Returns the market cap of the token in USD, calculated from its price and total supply.
+    async fn get_market_cap(
+        &self,
+        token: &Token,
+        price: &TokenPrice,
+    ) -> anyhow::Result<Ratio<BigUint>> {
+        let total_supply: U256 = self
+            .eth_gateway
+            .call_contract_function(
+                "totalSupply",
+                (),
+                None,
+                Options::default(),
+                None,
+                token.address,
+                erc20_contract(),
+            )
+            .await?;
+        let total_supply = BigUint::from_str(&total_supply.to_string())?;
+
+        Ok(price.usd_price.clone()
+            * Ratio::new(total_supply, BigUint::from(10u32).pow(token.decimals)))
+    }
+
+    async fn update_token(&self, token: &Token) -> anyhow::Result<()> {
+        let mut storage = self.connection_pool.access_storage().await?;
+        let market_volume = storage
+            .tokens_schema()
+            .get_token_market_volume(token.id)
+            .await?;
+        let price = if self.policy.requires_market_cap() {
+            storage
+                .tokens_schema()
+                .get_historical_ticker_price(token.id)
+                .await?
+        } else {
+            None
+        };
+        let current = storage
+            .tokens_schema()
+            .get_fee_token_eligibility(token.id)
+            .await?;
+        // Don't hold the connection while requesting the Ethereum node.
+        drop(storage);
+
+        let market_cap = match price {
+            Some(price) => Some(self.get_market_cap(token, &price).await?),
+            None => None,
+        };
+        let decision = self
+            .policy
+            .evaluate(token.id, market_volume.as_ref(), market_cap);
+
+        if current.map(|current| current.eligible) == Some(decision.eligible) {
+            return Ok(());
+        }
+        if decision.eligible {
+            vlog::info!("Token {} is enabled for paying fees", token.symbol);
+            metrics::increment_counter!("ticker.fee_token_policy.enabled", "token" => token.symbol.clone());
+        } else {
+            vlog::info!(
+                "Token {} is disabled for paying fees: {}",
+                token.symbol,
+                decision.reason
+            );
+            metrics::increment_counter!("ticker.fee_token_policy.disabled", "token" => token.symbol.clone());
+        }
+
+        self.connection_pool
+            .access_storage()
+            .await?
+            .tokens_schema()
+            .store_fee_token_eligibility(decision)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_all_tokens(&self) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let tokens = self
+            .connection_pool
+            .access_storage()
+            .await?
+            .tokens_schema()
+            .load_tokens()
+            .await?;
+
+        for token in tokens.values() {
+            if token.kind != TokenKind::ERC20 || self.unconditionally_valid.contains(&token.address)
+            {
+                continue;
+            }
+            // The failed token keeps its previous decision until the next update.
+            if let Err(err) = self.update_token(token).await {
+                vlog::warn!(
+                    "Failed to apply the fee token policy to the token {}: {}",
+                    token.symbol,
+                    err
+                );
+            }
+        }
+        metrics::histogram!("ticker.fee_token_policy.update_all_tokens", start.elapsed());
+        Ok(())
+    }
+
+    pub async fn keep_updated(self, duration_secs: u64) {
+        loop {
+            if let Err(err) = self.update_all_tokens().await {
+                vlog::warn!("Error when applying the fee token policy: {}", err);
+            }
+            tokio::time::sleep(Duration::from_secs(duration_secs)).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market_volume(volume: u32) -> TokenMarketVolume {
+        TokenMarketVolume {
+            market_volume: Ratio::from_integer(volume.into()),
+            last_updated: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn fee_token_policy() {
+        let token_id = TokenId(1);
+        let policy = FeeTokenPolicy::new(
+            Ratio::from_integer(100u32.into()),
+            Some(Ratio::from_integer(1000u32.into())),
+        );
+        assert!(policy.requires_market_cap());
+
+        let eligible = |volume: Option<u32>, market_cap: Option<u32>| {
+            policy
+                .evaluate(
+                    token_id,
+                    volume.map(market_volume).as_ref(),
+                    market_cap.map(|market_cap| Ratio::from_integer(market_cap.into())),
+                )
+                .eligible
+        };
+        assert!(eligible(Some(100), Some(1000)));
+        // Not enough liquidity.
+        assert!(!eligible(Some(99), Some(1000)));
+        assert!(!eligible(None, Some(1000)));
+        // Not enough market cap.
+        assert!(!eligible(Some(100), Some(999)));
+        assert!(!eligible(Some(100), None));
+
+        // The market cap check is disabled.
+        let policy = FeeTokenPolicy::new(Ratio::from_integer(100u32.into()), None);
+        assert!(!policy.requires_market_cap());
+        let decision = policy.evaluate(token_id, Some(&market_volume(100)), None);
+        assert!(decision.eligible);
+        assert_eq!(decision.token_id, token_id);
+        assert_eq!(decision.market_volume, Ratio::from_integer(100u32.into()));
+    }
+}
@@ -18,11 +18,12 @@ use tokio::task::JoinHandle;
 // Workspace uses
 use zksync_eth_client::EthereumGateway;
 use zksync_types::{
-    tx::{error::TxAddError, EthBatchSignData, EthSignData, TxEthSignature},
-    Address, Order, SignedZkSyncTx, Token, ZkSyncTx,
+    chain_bound_ethereum_message,
+    tx::{error::TxAddError, EthBatchSignData, EthSignData, PackedEthSignature, TxEthSignature},
+    Address, ChainId, Order, SignedZkSyncTx, Token, ZkSyncTx, H256,
 };
 // Local uses
-use crate::eth_checker::EthereumChecker;
+use crate::{eth_checker::EthereumChecker, utils::shared_lru_cache::SharedLruCache};
 use zksync_types::tx::TransactionError;
 
 /// `TxVariant` is used to form a verify request. It is possible to wrap
@@ -35,6 +36,62 @@ pub enum TxVariant {
     Toggle2FA,
 }
 
+/// Binding of the transactions Ethereum signatures to the network.
+///
+/// The messages signed by the Ethereum keys are expected to end with the chain id, see
+/// `chain_bound_ethereum_message`. The messages without the chain id are accepted only
+/// during the migration window, while `accept_unbound_messages` is set.
+#[derive(Debug, Clone, Copy)]
+pub struct EthMessageBinding {
+    pub chain_id: ChainId,
+    pub accept_unbound_messages: bool,
+}
+
+/// Cache of the addresses recovered from the ECDSA signatures.
+///
+/// Signature recovery is the most expensive part of the transaction checks. Within a batch
+/// every signature may be checked against several messages and senders, and rejected batches
+/// are often resubmitted, so the recovered signers are cached by the signature and the message.
+#[derive(Debug, Clone)]
+pub struct RecoveredSignersCache(SharedLruCache<H256, Option<Address>>);
+
+impl RecoveredSignersCache {
+    pub fn new(capacity: usize) -> Self {
+        Self(SharedLruCache::new(capacity))
+    }
+
+    /// Recovers the signer of the message (or of the EIP-712 typed data message if `typed_data`
+    /// is set). Returns `None` if the signature is incorrect.
+    pub fn recover_signer(
+        &self,
+        signature: &PackedEthSignature,
+        typed_data: bool,
+        message: &[u8],
+    ) -> Option<Address> {
+        let key = Self::cache_key(signature, typed_data, message);
+        if let Some(signer) = self.0.get(&key) {
+            return signer;
+        }
+
+        let signer = if typed_data {
+            signature.signature_recover_signer_from_typed_data_message(message)
+        } else {
+            signature.signature_recover_signer_from_raw_message(message)
+        }
+        .ok();
+        self.0.insert(key, signer);
+        signer
+    }
+
+    fn cache_key(signature: &PackedEthSignature, typed_data: bool, message: &[u8]) -> H256 {
+        let mut bytes = Vec::with_capacity(1 + 65 + message.len());
+        bytes.push(typed_data as u8);
+        bytes.extend_from_slice(&signature.serialize_packed());
+        bytes.extend_from_slice(message);
+        H256(tiny_keccak::keccak256(&bytes))
+    }
+}
+
 /// Wrapper on a `TxVariant` which guarantees that (a batch of)
 /// transaction(s) was checked and signatures associated with
 /// this transactions are correct.
@@ -50,8 +107,10 @@ impl VerifiedTx {
     pub async fn verify(
         request_data: RequestData,
         eth_checker: &EthereumChecker,
+        signers_cache: &RecoveredSignersCache,
+        binding: EthMessageBinding,
     ) -> Result<Self, TxAddError> {
-        verify_eth_signature(&request_data, eth_checker).await?;
+        verify_eth_signature(&request_data, eth_checker, signers_cache, binding).await?;
         let mut tx_variant = request_data.get_tx_variant();
         verify_tx_correctness(&mut tx_variant)?;
 
@@ -89,6 +148,8 @@ impl VerifiedTx {
 async fn verify_eth_signature(
     request_data: &RequestData,
     eth_checker: &EthereumChecker,
+    signers_cache: &RecoveredSignersCache,
+    binding: EthMessageBinding,
 ) -> Result<(), TxAddError> {
     match request_data {
         RequestData::Tx(request) => {
@@ -97,6 +158,8 @@ async fn verify_eth_signature(
                 request.sender,
                 request.token.clone(),
                 eth_checker,
+                signers_cache,
+                binding,
             )
             .await?;
         }
@@ -108,23 +171,43 @@ async fn verify_eth_signature(
             if accounts.len() != request.txs.len() {
                 return Err(TxAddError::Other);
             }
+            // The checks below only look the signers up in the cache.
+            recover_batch_signers(request, signers_cache, binding).await;
             if let Some(batch_sign_data) = &request.batch_sign_data {
-                verify_eth_signature_txs_batch(txs, accounts, batch_sign_data, eth_checker).await?;
+                verify_eth_signature_txs_batch(
+                    txs,
+                    accounts,
+                    batch_sign_data,
+                    eth_checker,
+                    signers_cache,
+                    binding,
+                )
+                .await?;
             }
             // In case there're signatures provided for some of transactions
             // we still verify them.
             for ((tx, &account), token) in
                 txs.iter().zip(accounts.iter()).zip(tokens.iter().cloned())
             {
-                verify_eth_signature_single_tx(tx, account, token, eth_checker).await?;
+                verify_eth_signature_single_tx(
+                    tx,
+                    account,
+                    token,
+                    eth_checker,
+                    signers_cache,
+                    binding,
+                )
+                .await?;
             }
         }
         RequestData::Order(request) => {
-            let signature_correct = verify_ethereum_signature(
+            let signature_correct = verify_bound_ethereum_signature(
                 &request.sign_data.signature,
                 &request.sign_data.message,
                 request.sender,
                 eth_checker,
+                signers_cache,
+                binding,
             )
             .await;
             if !signature_correct {
@@ -137,6 +220,7 @@ async fn verify_eth_signature(
                 &request.sign_data.message,
                 request.sender,
                 eth_checker,
+                signers_cache,
             )
             .await;
             if !signature_correct {
@@ -155,11 +239,11 @@ async fn verify_ethereum_signature(
     message: &[u8],
     sender_address: Address,
     eth_checker: &EthereumChecker,
+    signers_cache: &RecoveredSignersCache,
 ) -> bool {
-    let signer_account = match eth_signature {
-        TxEthSignature::EthereumSignature(packed_signature) => {
-            packed_signature.signature_recover_signer_from_raw_message(message)
-        }
+    let (signature, typed_data) = match eth_signature {
+        TxEthSignature::EthereumSignature(signature) => (signature, false),
+        TxEthSignature::EIP712Signature(signature) => (signature, true),
         TxEthSignature::EIP1271Signature(signature) => {
             return eth_checker
                 .is_eip1271_signature_correct(sender_address, message, signature.clone())
@@ -167,10 +251,112 @@ async fn verify_ethereum_signature(
                 .expect("Unable to check EIP1271 signature")
         }
     };
-    match signer_account {
-        Ok(address) => address == sender_address,
-        Err(_) => false,
+    signers_cache.recover_signer(signature, typed_data, message) == Some(sender_address)
+}
+
+/// Returns the ECDSA signature and whether it signs the EIP-712 typed data.
+/// EIP-1271 signatures are checked by the account contract and have no signer to recover.
+fn ecdsa_signature(eth_signature: &TxEthSignature) -> Option<(&PackedEthSignature, bool)> {
+    match eth_signature {
+        TxEthSignature::EthereumSignature(signature) => Some((signature, false)),
+        TxEthSignature::EIP712Signature(signature) => Some((signature, true)),
+        TxEthSignature::EIP1271Signature(_) => None,
+    }
+}
+
+/// Recovers the signers of all the ECDSA signatures of the batch in parallel and stores them
+/// in the cache. Only the bound and (if accepted) unbound messages are recovered in advance,
+/// the old-formatted messages are rare and checked on demand.
+async fn recover_batch_signers(
+    request: &BatchRequest,
+    signers_cache: &RecoveredSignersCache,
+    binding: EthMessageBinding,
+) {
+    let start = Instant::now();
+    let tx_signatures = request
+        .txs
+        .iter()
+        .filter_map(|tx| tx.eth_sign_data.as_ref())
+        .map(|sign_data| (&sign_data.signature, &sign_data.message));
+    let batch_signatures = request.batch_sign_data.iter().flat_map(|sign_data| {
+        sign_data
+            .signatures
+            .iter()
+            .map(move |signature| (signature, &sign_data.message))
+    });
+
+    let mut to_recover = Vec::new();
+    for (eth_signature, message) in tx_signatures.chain(batch_signatures) {
+        let (signature, typed_data) = match ecdsa_signature(eth_signature) {
+            Some(signature) => signature,
+            None => continue,
+        };
+        // The typed data is already bound to the network by the chain id of its domain.
+        if typed_data || binding.accept_unbound_messages {
+            to_recover.push((signature.clone(), typed_data, message.clone()));
+        }
+        if !typed_data {
+            let bound_message = chain_bound_ethereum_message(message, binding.chain_id);
+            to_recover.push((signature.clone(), typed_data, bound_message));
+        }
     }
+
+    let tasks = to_recover
+        .into_iter()
+        .map(|(signature, typed_data, message)| {
+            let signers_cache = signers_cache.clone();
+            tokio::task::spawn_blocking(move || {
+                signers_cache.recover_signer(&signature, typed_data, &message);
+            })
+        });
+    // A failed task only leaves the signer out of the cache, it will be recovered during the check.
+    futures::future::join_all(tasks).await;
+
+    metrics::histogram!("signature_checker.recover_batch_signers", start.elapsed());
+}
+
+/// Checks the Ethereum signature of the transaction message bound to the network.
+/// The message without the chain id is accepted only if `binding` allows it.
+async fn verify_bound_ethereum_signature(
+    eth_signature: &TxEthSignature,
+    message: &[u8],
+    sender_address: Address,
+    eth_checker: &EthereumChecker,
+    signers_cache: &RecoveredSignersCache,
+    binding: EthMessageBinding,
+) -> bool {
+    // The typed data is already bound to the network by the chain id of its domain.
+    if let TxEthSignature::EIP712Signature(_) = eth_signature {
+        return verify_ethereum_signature(
+            eth_signature,
+            message,
+            sender_address,
+            eth_checker,
+            signers_cache,
+        )
+        .await;
+    }
+    let bound_message = chain_bound_ethereum_message(message, binding.chain_id);
+    if verify_ethereum_signature(
+        eth_signature,
+        &bound_message,
+        sender_address,
+        eth_checker,
+        signers_cache,
+    )
+    .await
+    {
+        return true;
+    }
+    binding.accept_unbound_messages
+        && verify_ethereum_signature(
+            eth_signature,
+            message,
+            sender_address,
+            eth_checker,
+            signers_cache,
+        )
+        .await
 }
 
 async fn verify_eth_signature_single_tx(
@@ -178,6 +364,8 @@ async fn verify_eth_signature_single_tx(
     sender_address: Address,
     token: Token,
     eth_checker: &EthereumChecker,
+    signers_cache: &RecoveredSignersCache,
+    binding: EthMessageBinding,
 ) -> Result<(), TxAddError> {
     let start = Instant::now();
     // Check if the tx is a `ChangePubKey` operation without an Ethereum signature.
@@ -202,10 +390,17 @@ async fn verify_eth_signature_single_tx(
     // Check the signature.
     if let Some(sign_data) = &tx.eth_sign_data {
         let signature = &sign_data.signature;
-        let mut signature_correct =
-            verify_ethereum_signature(signature, &sign_data.message, sender_address, eth_checker)
-                .await;
-        if !signature_correct {
+        let mut signature_correct = verify_bound_ethereum_signature(
+            signature,
+            &sign_data.message,
+            sender_address,
+            eth_checker,
+            signers_cache,
+            binding,
+        )
+        .await;
+        // Old-formatted messages don't contain the chain id.
+        if !signature_correct && binding.accept_unbound_messages {
             let old_message = tx.get_old_ethereum_sign_message(token);
             if let Some(message) = old_message {
                 signature_correct = verify_ethereum_signature(
@@ -213,6 +408,7 @@ async fn verify_eth_signature_single_tx(
                     message.as_bytes(),
                     sender_address,
                     eth_checker,
+                    signers_cache,
                 )
                 .await;
             }
@@ -234,12 +430,17 @@ async fn verify_eth_signature_txs_batch(
     senders: &[Address],
     batch_sign_data: &EthBatchSignData,
     eth_checker: &EthereumChecker,
+    signers_cache: &RecoveredSignersCache,
+    binding: EthMessageBinding,
 ) -> Result<(), TxAddError> {
     let start = Instant::now();
     // Cache for verified senders.
     let mut signers = HashSet::with_capacity(senders.len());
     // For every sender check whether there exists at least one signature that matches it.
-    let old_message = match txs.iter().all(|tx| tx.is_backwards_compatible()) {
+    // Old-formatted messages don't contain the chain id.
+    let old_message = match binding.accept_unbound_messages
+        && txs.iter().all(|tx| tx.is_backwards_compatible())
+    {
         true => Some(EthBatchSignData::get_old_ethereum_batch_message(
             txs.iter().map(|tx| &tx.tx),
         )),
@@ -257,11 +458,13 @@ async fn verify_eth_signature_txs_batch(
         // This block will set the `sender_correct` variable to `true` at the first match.
         let mut sender_correct = false;
         for signature in &batch_sign_data.signatures {
-            let mut signature_correct = verify_ethereum_signature(
+            let mut signature_correct = verify_bound_ethereum_signature(
                 signature,
                 &batch_sign_data.message,
                 *sender,
                 eth_checker,
+                signers_cache,
+                binding,
             )
             .await;
             if !signature_correct {
@@ -271,6 +474,7 @@ async fn verify_eth_signature_txs_batch(
                         old_message.as_slice(),
                         *sender,
                         eth_checker,
+                        signers_cache,
                     )
                     .await;
                 }
@@ -380,23 +584,166 @@ impl RequestData {
 pub fn start_sign_checker(
     client: EthereumGateway,
     input: mpsc::Receiver<VerifySignatureRequest>,
+    binding: EthMessageBinding,
+    signers_cache_capacity: usize,
 ) -> JoinHandle<()> {
     let eth_checker = EthereumChecker::new(client);
+    // The cache is shared by all the API servers submitting the transactions to the checker.
+    let signers_cache = RecoveredSignersCache::new(signers_cache_capacity);
+    tokio::spawn(checker_routine(input, eth_checker, signers_cache, binding))
+}
+
+/// Basically it receives the requests through the channel and verifies signatures,
+/// notifying the request sender about the check result.
+async fn checker_routine(
+    mut input: mpsc::Receiver<VerifySignatureRequest>,
+    eth_checker: EthereumChecker,
+    signers_cache: RecoveredSignersCache,
+    binding: EthMessageBinding,
+) {
+    while let Some(VerifySignatureRequest { data, response }) = input.next().await {
+        let eth_checker = eth_checker.clone();
+        let signers_cache = signers_cache.clone();
+        tokio::spawn(async move {
+            let resp = VerifiedTx::verify(data, &eth_checker, &signers_cache, binding).await;
+
+            response.send(resp).unwrap_or_default();
+        });
+    }
+}
 
-    /// Basically it receives the requests through the channel and verifies signatures,
-    /// notifying the request sender about the check result.
-    async fn checker_routine(
-        mut input: mpsc::Receiver<VerifySignatureRequest>,
-        eth_checker: EthereumChecker,
-    ) {
-        while let Some(VerifySignatureRequest { data, response }) = input.next().await {
-            let eth_checker = eth_checker.clone();
-            tokio::spawn(async move {
-                let resp = VerifiedTx::verify(data, &eth_checker).await;
-
-                response.send(resp).unwrap_or_default();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_eth_client::clients::mock::MockEthereum;
+
+    #[test]
+    fn recovered_signers_cache() {
+        let private_key = H256::random();
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let message = b"zkSync batch";
+        let signature = PackedEthSignature::sign(&private_key, message).unwrap();
+
+        let cache = RecoveredSignersCache::new(2);
+        assert_eq!(
+            cache.recover_signer(&signature, false, message),
+            Some(address)
+        );
+        // Cached value is returned for the same signature and message.
+        assert_eq!(
+            cache.recover_signer(&signature, false, message),
+            Some(address)
+        );
+        // The typed data is hashed differently, so the signer doesn't match.
+        assert_ne!(
+            cache.recover_signer(&signature, true, message),
+            Some(address)
+        );
+        assert_ne!(
+            cache.recover_signer(&signature, false, b"other message"),
+            Some(address)
+        );
+    }
+
+    /// Checks that the signers recovered for one request are reused by the next ones.
+    #[tokio::test]
+    async fn signers_cache_reused_across_requests() {
+        let private_key = H256::random();
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let message = b"Enable 2FA".to_vec();
+        let signature = PackedEthSignature::sign(&private_key, &message).unwrap();
+
+        let eth_checker = EthereumChecker::new(EthereumGateway::Mock(MockEthereum::default()));
+        let signers_cache = RecoveredSignersCache::new(10);
+        let binding = EthMessageBinding {
+            chain_id: ChainId(4),
+            accept_unbound_messages: false,
+        };
+        let (mut requests, input) = mpsc::channel(1);
+        let checker = tokio::spawn(checker_routine(
+            input,
+            eth_checker,
+            signers_cache.clone(),
+            binding,
+        ));
+
+        let mut toggle_2fa = |sender: Address| {
+            let (response, response_rx) = oneshot::channel();
+            let data = RequestData::Toggle2FA(Toggle2FARequest {
+                sign_data: EthSignData {
+                    signature: TxEthSignature::EthereumSignature(signature.clone()),
+                    message: message.clone(),
+                },
+                sender,
             });
+            requests
+                .try_send(VerifySignatureRequest { data, response })
+                .unwrap();
+            response_rx
+        };
+
+        assert!(toggle_2fa(address).await.unwrap().is_ok());
+        let key = RecoveredSignersCache::cache_key(&signature, false, &message);
+        assert_eq!(signers_cache.0.get(&key), Some(Some(address)));
+
+        // The next request is checked against the cached signer instead of recovering it again.
+        let other_address = Address::repeat_byte(1);
+        signers_cache.0.insert(key, Some(other_address));
+        assert!(toggle_2fa(other_address).await.unwrap().is_ok());
+        assert!(matches!(
+            toggle_2fa(address).await.unwrap(),
+            Err(TxAddError::IncorrectEthSignature)
+        ));
+
+        // The routine stops once the requests channel is closed.
+        drop(requests);
+        checker.await.unwrap();
+    }
+
+    /// Checks that the signatures of the messages bound to another network are rejected,
+    /// and the unbound ones are accepted only during the migration window.
+    #[tokio::test]
+    async fn chain_bound_signatures() {
+        let private_key = H256::random();
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let message = b"Transfer 1.0 ETH\nNonce: 1";
+        let sign = |message: &[u8]| {
+            TxEthSignature::EthereumSignature(
+                PackedEthSignature::sign(&private_key, message).unwrap(),
+            )
+        };
+        let bound_signature = sign(&chain_bound_ethereum_message(message, ChainId(4)));
+        let unbound_signature = sign(message);
+
+        let eth_checker = EthereumChecker::new(EthereumGateway::Mock(MockEthereum::default()));
+        let signers_cache = RecoveredSignersCache::new(10);
+        // Signature, chain id of the server, whether the unbound messages are accepted, result.
+        let cases = [
+            (&bound_signature, 4, false, true),
+            (&bound_signature, 4, true, true),
+            (&bound_signature, 1, true, false),
+            (&unbound_signature, 4, true, true),
+            (&unbound_signature, 4, false, false),
+        ];
+        for &(signature, chain_id, accept_unbound_messages, expected) in cases.iter() {
+            let binding = EthMessageBinding {
+                chain_id: ChainId(chain_id),
+                accept_unbound_messages,
+            };
+            let verified = verify_bound_ethereum_signature(
+                signature,
+                message,
+                address,
+                &eth_checker,
+                &signers_cache,
+                binding,
+            )
+            .await;
+            assert_eq!(
+                verified, expected,
+                "chain id: {}, unbound messages accepted: {}",
+                chain_id, accept_unbound_messages
+            );
         }
     }
-    tokio::spawn(checker_routine(input, eth_checker))
 }
@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use std::{cmp::max, time::Duration};
 use zksync_config::ChainConfig;
 use zksync_crypto::proof::AggregatedProof;
+use zksync_object_store::ArtifactStore;
 use zksync_storage::{
     chain::{block::BlockSchema, operations::OperationsSchema},
     prover::ProverSchema,
@@ -334,6 +335,7 @@ async fn create_aggregated_prover_task_storage(
 
 async fn create_aggregated_publish_proof_operation_storage(
     storage: &mut StorageProcessor<'_>,
+    artifact_store: &ArtifactStore,
 ) -> anyhow::Result<bool> {
     let mut transaction = storage.start_transaction().await?;
     let last_aggregate_create_proof_block = OperationsSchema(&mut transaction)
@@ -378,10 +380,14 @@ async fn create_aggregated_publish_proof_operation_storage(
             .last()
             .map(|b| b.block_number)
             .unwrap();
-        transaction
+        match transaction
             .prover_schema()
             .load_aggregated_proof(first_block, last_block)
             .await?
+        {
+            Some(proof) => Some(artifact_store.load(proof).await?),
+            None => None,
+        }
     };
 
     let result = if let Some(proof) = aggregated_proof {
@@ -457,10 +463,11 @@ async fn create_aggregated_execute_operation_storage(
 pub async fn create_aggregated_operations_storage(
     storage: &mut StorageProcessor<'_>,
     config: &ChainConfig,
+    artifact_store: &ArtifactStore,
 ) -> anyhow::Result<()> {
     while create_aggregated_commits_storage(storage, config).await? {}
     while create_aggregated_prover_task_storage(storage, config).await? {}
-    while create_aggregated_publish_proof_operation_storage(storage).await? {}
+    while create_aggregated_publish_proof_operation_storage(storage, artifact_store).await? {}
     while create_aggregated_execute_operation_storage(storage, config).await? {}
 
     Ok(())
@@ -1,13 +1,17 @@
 // Built-in uses
 use std::time::{Duration, Instant};
 // External uses
-use futures::{channel::mpsc::Receiver, StreamExt};
+use futures::{
+    channel::{mpsc::Receiver, oneshot},
+    StreamExt,
+};
 use serde::{Deserialize, Serialize};
 use tokio::{task::JoinHandle, time};
 
 // Workspace uses
 use zksync_config::ChainConfig;
 use zksync_crypto::Fr;
+use zksync_object_store::ArtifactStore;
 use zksync_storage::ConnectionPool;
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::{
@@ -27,6 +31,8 @@ pub enum CommitRequest {
     RemoveRevertedBlock(BlockNumber),
     SealIncompleteBlock((BlockCommitRequest, AppliedUpdatesRequest)),
     FinishBlock(BlockFinishRequest),
+    /// Replies once all the requests received before are processed.
+    Flush(oneshot::Sender<()>),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -80,6 +86,9 @@ async fn handle_new_commit_task(mut rx_for_ops: Receiver<CommitRequest>, pool: C
             CommitRequest::RemoveRevertedBlock(block_number) => {
                 remove_reverted_block(block_number, &pool).await;
             }
+            CommitRequest::Flush(sender) => {
+                sender.send(()).unwrap_or_default();
+            }
         }
     }
 }
@@ -281,7 +290,11 @@ async fn finish_block(request: BlockFinishRequest, pool: &ConnectionPool) {
     metrics::histogram!("committer.finish_block", start.elapsed());
 }
 
-async fn poll_for_new_proofs_task(pool: ConnectionPool, config: ChainConfig) {
+async fn poll_for_new_proofs_task(
+    pool: ConnectionPool,
+    config: ChainConfig,
+    artifact_store: ArtifactStore,
+) {
     let mut timer = time::interval(PROOF_POLL_INTERVAL);
     loop {
         timer.tick().await;
@@ -291,10 +304,14 @@ async fn poll_for_new_proofs_task(pool: ConnectionPool, config: ChainConfig) {
             .await
             .expect("db connection failed for committer");
 
-        aggregated_committer::create_aggregated_operations_storage(&mut storage, &config)
-            .await
-            .map_err(|e| vlog::error!("Failed to create aggregated operation: {}", e))
-            .unwrap_or_default();
+        aggregated_committer::create_aggregated_operations_storage(
+            &mut storage,
+            &config,
+            &artifact_store,
+        )
+        .await
+        .map_err(|e| vlog::error!("Failed to create aggregated operation: {}", e))
+        .unwrap_or_default();
     }
 }
 
@@ -303,7 +320,8 @@ pub fn run_committer(
     rx_for_ops: Receiver<CommitRequest>,
     pool: ConnectionPool,
     config: ChainConfig,
+    artifact_store: ArtifactStore,
 ) -> JoinHandle<()> {
     tokio::spawn(handle_new_commit_task(rx_for_ops, pool.clone()));
-    tokio::spawn(poll_for_new_proofs_task(pool, config))
+    tokio::spawn(poll_for_new_proofs_task(pool, config, artifact_store))
 }
@@ -0,0 +1,147 @@
+//! Backfill of the Ethereum events that were missed by the Ethereum watcher, e.g. because of
+//! a long server downtime.
+//!
+//! The given range of Ethereum blocks is rescanned for the priority operations and the Governance
+//! contract events, and everything that is not stored in the database yet is inserted. Running the
+//! backfill for the same range several times is safe.
+
+// Built-in deps
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
+// External uses
+use web3::types::BlockNumber;
+// Workspace deps
+use zksync_config::ZkSyncConfig;
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::PriorityOp;
+// Local deps
+use super::{client::EthClient, EthHttpClient, EventConfirmations};
+use crate::{register_factory_handler::save_register_factory, token_handler::save_new_tokens};
+
+/// Amount of the Ethereum blocks requested at once.
+const BACKFILL_CHUNK_SIZE: u64 = 1000;
+
+/// Amounts of the events processed by the backfill.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BackfillStats {
+    /// Priority operations that were missing in the database.
+    pub priority_ops: usize,
+    /// Tokens that were missing in the database.
+    pub new_tokens: usize,
+    /// Registered NFT factories. These are stored idempotently, so all of them are counted.
+    pub nft_factories: usize,
+}
+
+/// Rescans the `block_range` for the events missed by the Ethereum watcher and stores them.
+/// All the blocks in the range must have enough confirmations for the events to be processed.
+pub async fn backfill_eth_events(
+    eth_gateway: EthereumGateway,
+    connection_pool: ConnectionPool,
+    config: &ZkSyncConfig,
+    block_range: RangeInclusive<u64>,
+) -> anyhow::Result<BackfillStats> {
+    let client = EthHttpClient::new(
+        eth_gateway.clone(),
+        config.contracts.contract_addr,
+        config.contracts.governance_addr,
+    );
+    let token_list: HashMap<_, _> = config
+        .token_handler
+        .token_list()
+        .into_iter()
+        .map(|token| (token.address, token))
+        .collect();
+
+    let confirmations = EventConfirmations::from_config(&config.eth_watch);
+    let required_confirmations = confirmations
+        .max_for_priority_ops()
+        .max(confirmations.governance_event);
+    let last_confirmed_block = client
+        .block_number()
+        .await?
+        .saturating_sub(required_confirmations);
+    anyhow::ensure!(
+        *block_range.end() <= last_confirmed_block,
+        "Backfill range {:?} includes blocks without enough confirmations, the last confirmed block is {}",
+        block_range,
+        last_confirmed_block
+    );
+
+    let mut stats = BackfillStats::default();
+    let mut storage = connection_pool.access_storage().await?;
+    let mut chunk_start = *block_range.start();
+    while chunk_start <= *block_range.end() {
+        let chunk_end = (chunk_start + BACKFILL_CHUNK_SIZE - 1).min(*block_range.end());
+        let (from, to) = (
+            BlockNumber::Number(chunk_start.into()),
+            BlockNumber::Number(chunk_end.into()),
+        );
+
+        let priority_ops = client.get_priority_op_events(from, to).await?;
+        stats.priority_ops += backfill_priority_ops(&mut storage, priority_ops).await?;
+
+        let new_tokens = client.get_new_tokens_events(from, to).await?;
+        stats.new_tokens += save_new_tokens(&mut storage, &eth_gateway, &token_list, new_tokens)
+            .await?
+            .len();
+
+        let nft_factories = client.get_new_register_nft_factory_events(from, to).await?;
+        stats.nft_factories += nft_factories.len();
+        save_register_factory(&mut storage, nft_factories).await?;
+
+        vlog::info!(
+            "Backfilled Ethereum blocks [{}, {}]: {:?}",
+            chunk_start,
+            chunk_end,
+            stats
+        );
+        chunk_start = chunk_end + 1;
+    }
+
+    Ok(stats)
+}
+
+/// Stores the priority operations that were neither executed nor added to the mempool.
+/// Returns the amount of the stored operations.
+async fn backfill_priority_ops(
+    storage: &mut StorageProcessor<'_>,
+    mut priority_ops: Vec<PriorityOp>,
+) -> anyhow::Result<usize> {
+    if let Some(last_executed_op) = storage
+        .chain()
+        .operations_schema()
+        .get_max_priority_op_serial_id()
+        .await?
+    {
+        priority_ops.retain(|op| op.serial_id > last_executed_op);
+    }
+
+    let serial_ids: Vec<_> = priority_ops.iter().map(|op| op.serial_id).collect();
+    let known_ids: HashSet<_> = storage
+        .chain()
+        .mempool_schema()
+        .get_known_priority_op_ids(&serial_ids)
+        .await?
+        .into_iter()
+        .collect();
+    priority_ops.retain(|op| !known_ids.contains(&op.serial_id));
+
+    if priority_ops.is_empty() {
+        return Ok(0);
+    }
+    for op in &priority_ops {
+        vlog::warn!(
+            "Restoring missed priority operation #{} from the Ethereum block {}",
+            op.serial_id,
+            op.eth_block
+        );
+    }
+    storage
+        .chain()
+        .mempool_schema()
+        .insert_priority_ops(&priority_ops, true)
+        .await?;
+
+    Ok(priority_ops.len())
+}
@@ -2,24 +2,26 @@ use std::{convert::TryFrom, time::Instant};
 
 use anyhow::format_err;
 use ethabi::Hash;
-use std::fmt::Debug;
+use std::fmt::Display;
 use web3::{
     contract::Options,
     transports::http,
-    types::{BlockNumber, FilterBuilder, Log},
+    types::{BlockId, BlockNumber, FilterBuilder, Log},
     Web3,
 };
 
 use zksync_contracts::{governance_contract, zksync_contract};
 use zksync_eth_client::ethereum_gateway::EthereumGateway;
 use zksync_types::{
-    Address, NewTokenEvent, Nonce, PriorityOp, RegisterNFTFactoryEvent, H160, U256,
+    Address, NewTokenEvent, Nonce, PriorityOp, RegisterNFTFactoryEvent, ValidatorStatusUpdateEvent,
+    H160, H256, U256,
 };
 
 struct ContractTopics {
     new_priority_request: Hash,
     new_token: Hash,
     factory_registered: Hash,
+    validator_status_update: Hash,
 }
 
 impl ContractTopics {
@@ -37,6 +39,10 @@ impl ContractTopics {
                 .event("NFTFactoryRegisteredCreator")
                 .expect("main contract abi error")
                 .signature(),
+            validator_status_update: governance_contract
+                .event("ValidatorStatusUpdate")
+                .expect("main contract abi error")
+                .signature(),
         }
     }
 }
@@ -58,7 +64,18 @@ pub trait EthClient {
         from: BlockNumber,
         to: BlockNumber,
     ) -> anyhow::Result<Vec<NewTokenEvent>>;
+    async fn get_validator_status_update_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> anyhow::Result<Vec<ValidatorStatusUpdateEvent>>;
     async fn block_number(&self) -> anyhow::Result<u64>;
+    /// Returns the hash of the block with the given number in the canonical chain,
+    /// or `None` if there is no such block yet.
+    async fn block_hash(&self, block_number: u64) -> anyhow::Result<Option<H256>>;
+    /// Returns the total amount of the priority requests received by the contract
+    /// as of the given block.
+    async fn total_priority_requests(&self, block: BlockNumber) -> anyhow::Result<u64>;
     async fn get_auth_fact(&self, address: Address, nonce: Nonce) -> anyhow::Result<Vec<u8>>;
     async fn get_auth_fact_reset_time(&self, address: Address, nonce: Nonce)
         -> anyhow::Result<u64>;
@@ -94,7 +111,7 @@ impl EthHttpClient {
     ) -> anyhow::Result<Vec<T>>
     where
         T: TryFrom<Log>,
-        T::Error: Debug,
+        T::Error: Display,
     {
         let filter = FilterBuilder::default()
             .address(vec![
@@ -120,7 +137,7 @@ impl EthHttpClient {
         logs.into_iter()
             .map(|event| {
                 T::try_from(event)
-                    .map_err(|e| format_err!("Failed to parse event log from ETH: {:?}", e))
+                    .map_err(|e| format_err!("Failed to parse event log from ETH: {}", e))
             })
             .collect()
     }
@@ -220,10 +237,66 @@ impl EthClient for EthHttpClient {
         result
     }
 
+    async fn get_validator_status_update_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> anyhow::Result<Vec<ValidatorStatusUpdateEvent>> {
+        let start = Instant::now();
+
+        let result = self
+            .get_events(from, to, vec![self.topics.validator_status_update])
+            .await;
+        metrics::histogram!(
+            "eth_watcher.get_validator_status_update_events",
+            start.elapsed()
+        );
+        result
+    }
+
     async fn block_number(&self) -> anyhow::Result<u64> {
         Ok(self.client.block_number().await?.as_u64())
     }
 
+    async fn block_hash(&self, block_number: u64) -> anyhow::Result<Option<H256>> {
+        let block = self
+            .client
+            .block(BlockId::Number(BlockNumber::Number(block_number.into())))
+            .await?;
+
+        Ok(block.and_then(|block| block.hash))
+    }
+
+    async fn total_priority_requests(&self, block: BlockNumber) -> anyhow::Result<u64> {
+        let block = Some(BlockId::Number(block));
+        let first_priority_request_id: U256 = self
+            .client
+            .call_main_contract_function(
+                "firstPriorityRequestId",
+                (),
+                None,
+                Options::default(),
+                block,
+            )
+            .await
+            .map_err(|e| format_err!("Failed to query contract firstPriorityRequestId: {}", e))?;
+        let total_open_priority_requests: U256 = self
+            .client
+            .call_main_contract_function(
+                "totalOpenPriorityRequests",
+                (),
+                None,
+                Options::default(),
+                block,
+            )
+            .await
+            .map_err(|e| {
+                format_err!("Failed to query contract totalOpenPriorityRequests: {}", e)
+            })?;
+
+        Ok((first_priority_request_id + total_open_priority_requests).as_u64())
+    }
+
     async fn get_auth_fact(&self, address: Address, nonce: Nonce) -> anyhow::Result<Vec<u8>> {
         self.client
             .call_main_contract_function(
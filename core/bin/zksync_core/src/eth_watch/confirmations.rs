@@ -0,0 +1,148 @@
+// Built-in deps
+// External uses
+// Workspace deps
+use zksync_config::ETHWatchConfig;
+use zksync_types::{PriorityOp, ZkSyncPriorityOp};
+// Local deps
+
+/// Amounts of confirmations required to accept the different kinds of Ethereum events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventConfirmations {
+    /// Confirmations for the priority operations (except for full exits) to be processed.
+    pub priority_op: u64,
+    /// Confirmations for the priority operations to be reported as pending.
+    pub pending_priority_op: u64,
+    /// Confirmations for the full exits to be processed.
+    pub full_exit: u64,
+    /// Confirmations for the Governance contract events to be processed.
+    pub governance_event: u64,
+}
+
+impl EventConfirmations {
+    pub fn from_config(config: &ETHWatchConfig) -> Self {
+        Self {
+            priority_op: config.confirmations_for_eth_event,
+            pending_priority_op: config.confirmations_for_pending_deposit,
+            full_exit: config.confirmations_for_full_exit,
+            governance_event: config.confirmations_for_governance_event,
+        }
+    }
+
+    /// Creates the confirmations config with the same amount of confirmations required
+    /// for all the events to be processed.
+    pub fn uniform(confirmations: u64) -> Self {
+        Self {
+            priority_op: confirmations,
+            pending_priority_op: 0,
+            full_exit: confirmations,
+            governance_event: confirmations,
+        }
+    }
+
+    /// Returns the greatest amount of confirmations required for a priority operation.
+    pub fn max_for_priority_ops(&self) -> u64 {
+        self.priority_op.max(self.full_exit)
+    }
+
+    fn for_priority_op(&self, op: &PriorityOp) -> u64 {
+        match op.data {
+            ZkSyncPriorityOp::FullExit(_) => self.full_exit,
+            ZkSyncPriorityOp::Deposit(_) => self.priority_op,
+        }
+    }
+
+    /// Splits the priority operations into the confirmed and unconfirmed ones, both sorted by
+    /// the serial id.
+    ///
+    /// Priority operations are processed in the order of their serial ids, so once some operation
+    /// doesn't have enough confirmations, all the subsequent ones are considered unconfirmed too.
+    /// Unconfirmed operations that don't have enough confirmations to be reported as pending are
+    /// skipped.
+    pub fn split_priority_ops(
+        &self,
+        mut ops: Vec<PriorityOp>,
+        current_ethereum_block: u64,
+    ) -> (Vec<PriorityOp>, Vec<PriorityOp>) {
+        ops.sort_by_key(|op| op.serial_id);
+        let confirmations = |op: &PriorityOp| current_ethereum_block.saturating_sub(op.eth_block);
+
+        let first_unconfirmed = ops
+            .iter()
+            .position(|op| confirmations(op) < self.for_priority_op(op))
+            .unwrap_or(ops.len());
+        let mut unconfirmed = ops.split_off(first_unconfirmed);
+        unconfirmed.retain(|op| confirmations(op) >= self.pending_priority_op);
+
+        (ops, unconfirmed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::{AccountId, Deposit, FullExit, TokenId};
+
+    fn priority_op(serial_id: u64, eth_block: u64, is_full_exit: bool) -> PriorityOp {
+        let data = if is_full_exit {
+            ZkSyncPriorityOp::FullExit(FullExit {
+                account_id: AccountId(1),
+                eth_address: Default::default(),
+                token: TokenId(0),
+                is_legacy: false,
+            })
+        } else {
+            ZkSyncPriorityOp::Deposit(Deposit {
+                from: Default::default(),
+                token: TokenId(0),
+                amount: Default::default(),
+                to: Default::default(),
+            })
+        };
+
+        PriorityOp {
+            serial_id,
+            data,
+            deadline_block: 0,
+            eth_hash: Default::default(),
+            eth_block,
+            eth_block_index: Some(serial_id),
+        }
+    }
+
+    fn serial_ids(ops: &[PriorityOp]) -> Vec<u64> {
+        ops.iter().map(|op| op.serial_id).collect()
+    }
+
+    /// Checks that the priority operations are split according to the required confirmations.
+    #[test]
+    fn split_priority_ops() {
+        let confirmations = EventConfirmations {
+            priority_op: 5,
+            pending_priority_op: 2,
+            full_exit: 10,
+            governance_event: 0,
+        };
+        let ops = vec![
+            priority_op(1, 10, false),
+            priority_op(0, 5, true),
+            priority_op(2, 12, true),
+            priority_op(3, 13, false),
+            priority_op(4, 19, false),
+        ];
+
+        // Full exit in block 12 doesn't have enough confirmations, so the subsequent deposit
+        // is unconfirmed as well. The last deposit is too recent to be reported as pending.
+        let (confirmed, unconfirmed) = confirmations.split_priority_ops(ops.clone(), 20);
+        assert_eq!(serial_ids(&confirmed), vec![0, 1]);
+        assert_eq!(serial_ids(&unconfirmed), vec![2, 3]);
+
+        let (confirmed, unconfirmed) = confirmations.split_priority_ops(ops.clone(), 22);
+        assert_eq!(serial_ids(&confirmed), vec![0, 1, 2, 3]);
+        assert_eq!(serial_ids(&unconfirmed), vec![4]);
+
+        // Nothing is confirmed until the first full exit gets enough confirmations.
+        let (confirmed, unconfirmed) = confirmations.split_priority_ops(ops, 14);
+        assert_eq!(serial_ids(&confirmed), Vec::<u64>::new());
+        assert_eq!(serial_ids(&unconfirmed), vec![0, 1, 2]);
+    }
+}
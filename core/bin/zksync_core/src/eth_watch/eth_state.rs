@@ -2,7 +2,9 @@
 use std::collections::HashMap;
 // External uses
 // Workspace deps
-use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId};
+use zksync_types::{
+    NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId, ValidatorStatusUpdateEvent,
+};
 // Local deps
 use super::received_ops::ReceivedPriorityOp;
 
@@ -39,6 +41,8 @@ pub struct ETHState {
     new_tokens: Vec<NewTokenEvent>,
     /// List of events denoting registered factories for NFT withdrawing
     register_nft_factory_events: Vec<RegisterNFTFactoryEvent>,
+    /// List of events denoting activated or deactivated validators.
+    validator_status_updates: Vec<ValidatorStatusUpdateEvent>,
 }
 
 impl ETHState {
@@ -49,6 +53,7 @@ impl ETHState {
         priority_queue: HashMap<SerialId, ReceivedPriorityOp>,
         new_tokens: Vec<NewTokenEvent>,
         register_nft_factory_events: Vec<RegisterNFTFactoryEvent>,
+        validator_status_updates: Vec<ValidatorStatusUpdateEvent>,
     ) -> Self {
         assert!(
             last_ethereum_block_backup <= last_ethereum_block,
@@ -67,6 +72,7 @@ impl ETHState {
             priority_queue,
             new_tokens,
             register_nft_factory_events,
+            validator_status_updates,
         }
     }
 
@@ -90,6 +96,10 @@ impl ETHState {
         &self.new_tokens
     }
 
+    pub fn validator_status_updates(&self) -> &[ValidatorStatusUpdateEvent] {
+        &self.validator_status_updates
+    }
+
     pub fn next_priority_op_id(&self) -> SerialId {
         self.next_priority_op_id
     }
@@ -98,6 +108,41 @@ impl ETHState {
         self.last_ethereum_block = self.last_ethereum_block_backup;
     }
 
+    /// Removes all the events received after the `last_valid_block` and moves the
+    /// last processed block back to it, so the reverted range is processed once again.
+    ///
+    /// Should be used once the chain reorganization is detected. Returns the reverted token
+    /// events, since the tokens may have been already stored by the token handler.
+    pub fn revert_to_block(&mut self, last_valid_block: u64) -> Vec<NewTokenEvent> {
+        let reverted_ops: Vec<_> = self
+            .priority_queue
+            .iter()
+            .filter(|(_, op)| op.as_ref().eth_block > last_valid_block)
+            .map(|(serial_id, _)| *serial_id)
+            .collect();
+        for serial_id in &reverted_ops {
+            self.priority_queue.remove(serial_id);
+        }
+        if let Some(serial_id) = reverted_ops.into_iter().min() {
+            self.next_priority_op_id = self.next_priority_op_id.min(serial_id);
+        }
+
+        self.unconfirmed_queue
+            .retain(|op| op.eth_block <= last_valid_block);
+        let (new_tokens, reverted_tokens) = std::mem::take(&mut self.new_tokens)
+            .into_iter()
+            .partition(|token| token.eth_block_number <= last_valid_block);
+        self.new_tokens = new_tokens;
+        self.register_nft_factory_events
+            .retain(|event| event.eth_block <= last_valid_block);
+        self.validator_status_updates
+            .retain(|event| event.eth_block <= last_valid_block);
+
+        self.last_ethereum_block = self.last_ethereum_block.min(last_valid_block);
+        self.last_ethereum_block_backup = self.last_ethereum_block_backup.min(last_valid_block);
+        reverted_tokens
+    }
+
     #[cfg(test)]
     pub(crate) fn last_ethereum_block_backup(&self) -> u64 {
         self.last_ethereum_block_backup
@@ -1,12 +1,35 @@
 //! Ethereum watcher polls the Ethereum node for new events
-//! such as PriorityQueue events or NewToken events.
+//! such as PriorityQueue events or Governance contract events (e.g. NewToken or ValidatorStatusUpdate).
 //! New events are accepted to the zkSync network once they have the sufficient amount of confirmations.
 //!
-//! Poll interval is configured using the `ETH_POLL_INTERVAL` constant.
+//! While the watcher lags behind the Ethereum node, the node is polled with the `ETH_NODE_POLL_INTERVAL`
+//! interval. Near the head of the chain the interval is adjusted to the observed block time and is
+//! bounded by `ETH_NODE_MAX_POLL_INTERVAL`, see the `polling` module for details.
 //! Number of confirmations is configured using the `CONFIRMATIONS_FOR_ETH_EVENT` environment variable.
+//! Full exits and Governance contract events may require different amounts of confirmations, see
+//! `EventConfirmations` for details.
+//!
+//! Hashes of the processed blocks are persisted in the database, and on every poll the watcher checks
+//! that the last processed block is still a part of the canonical chain. Once a reorg is detected,
+//! all the events received after the latest common ancestor (including the priority operations stored
+//! in the mempool) are reverted, and the blocks of the new chain are processed once again.
+//!
+//! The position in the priority queue (the last processed block and the serial id of the next
+//! expected priority operation) is persisted as well, so after the restart the watcher resumes from
+//! it and neither skips nor inserts the processed operations once again. Whenever new priority
+//! operations are accepted, their amount is checked against the priority queue of the contract.
+//!
+//! Events missed during a long downtime can be restored with `backfill_eth_events`.
+//!
+//! If the WebSocket URL of the Ethereum node is configured, the node is polled only when it notifies
+//! about new blocks or contract logs, see the `subscription` module for details.
 
 // Built-in deps
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 use std::time::{Duration, Instant};
 
 // External uses
@@ -25,14 +48,35 @@ use zksync_config::{ContractsConfig, ETHWatchConfig};
 use zksync_crypto::params::PRIORITY_EXPIRATION;
 use zksync_eth_client::ethereum_gateway::EthereumGateway;
 use zksync_mempool::MempoolTransactionRequest;
-use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId};
+use zksync_prometheus_exporter::labels::report_op_received;
+use zksync_storage::ConnectionPool;
+use zksync_types::{
+    NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId, ValidatorStatusUpdateEvent,
+    ZkSyncPriorityOp, H256,
+};
 
 // Local deps
-use self::{client::EthClient, eth_state::ETHState, received_ops::sift_outdated_ops};
+pub use self::{
+    backfill::{backfill_eth_events, BackfillStats},
+    confirmations::EventConfirmations,
+    polling::PollingSchedule,
+};
+use self::{
+    client::EthClient,
+    eth_state::ETHState,
+    received_ops::sift_outdated_ops,
+    storage::{DbStorage, EthWatchStorage, PriorityOpCursor},
+    subscription::SubscriptionPollTrigger,
+};
 
+mod backfill;
 mod client;
+mod confirmations;
 mod eth_state;
+mod polling;
 mod received_ops;
+mod storage;
+mod subscription;
 
 #[cfg(test)]
 mod tests;
@@ -41,6 +85,10 @@ mod tests;
 /// before repeating the request.
 const RATE_LIMIT_DELAY: Duration = Duration::from_secs(30);
 
+/// Amount of the latest Ethereum blocks which hashes are tracked to detect chain reorganizations.
+/// Deeper reorganizations can't be reverted precisely.
+const REORG_DETECTION_WINDOW: u64 = 256;
+
 /// Ethereum Watcher operating mode.
 ///
 /// Normally Ethereum watcher will always poll the Ethereum node upon request,
@@ -67,73 +115,105 @@ pub enum EthWatchRequest {
         last_eth_block: Option<u64>,
         resp: oneshot::Sender<Vec<RegisterNFTFactoryEvent>>,
     },
+    GetValidatorStatusUpdates {
+        last_eth_block: Option<u64>,
+        resp: oneshot::Sender<Vec<ValidatorStatusUpdateEvent>>,
+    },
 }
 
 #[derive(Debug, Error)]
 #[error("A priority op log is missing: last processed id is {0}, next is {1}")]
 struct MissingPriorityOpError(SerialId, SerialId);
 
+#[derive(Debug, Error)]
+#[error(
+    "Priority queue is inconsistent with the contract: next priority op id is {next_priority_op_id}, \
+    the contract has {confirmed_requests} confirmed and {total_requests} total priority requests"
+)]
+struct PriorityOpsInconsistencyError {
+    next_priority_op_id: SerialId,
+    confirmed_requests: u64,
+    total_requests: u64,
+}
+
 fn is_missing_priority_op_error(error: &anyhow::Error) -> bool {
-    error.is::<MissingPriorityOpError>()
+    error.is::<MissingPriorityOpError>() || error.is::<PriorityOpsInconsistencyError>()
 }
 
-pub struct EthWatch<W: EthClient> {
+pub struct EthWatch<W: EthClient, S: EthWatchStorage> {
     client: W,
+    storage: S,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     eth_state: ETHState,
     /// All ethereum events are accepted after sufficient confirmations to eliminate risk of block reorg.
-    number_of_confirmations_for_event: u64,
+    confirmations: EventConfirmations,
     mode: WatcherMode,
+    /// Numbers and hashes of the processed Ethereum blocks in the ascending order.
+    processed_blocks: VecDeque<(u64, H256)>,
+    /// Position in the priority queue, `None` until it's stored for the first time.
+    priority_op_cursor: Option<PriorityOpCursor>,
+    polling: PollingSchedule,
+    /// Number of the Ethereum blocks the watcher lagged behind the node before the last poll.
+    lag: u64,
+    /// Delay before the next poll in milliseconds, shared with the poll timer.
+    next_poll_delay: Arc<AtomicU64>,
 }
 
-impl<W: EthClient> EthWatch<W> {
+impl<W: EthClient, S: EthWatchStorage> EthWatch<W, S> {
     pub fn new(
         client: W,
+        storage: S,
         mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
-        number_of_confirmations_for_event: u64,
+        confirmations: EventConfirmations,
+        polling: PollingSchedule,
     ) -> Self {
+        let next_poll_delay = Arc::new(AtomicU64::new(polling.interval(0).as_millis() as u64));
         Self {
             client,
+            storage,
             mempool_tx_sender,
             eth_state: ETHState::default(),
             mode: WatcherMode::Working,
-            number_of_confirmations_for_event,
+            confirmations,
+            processed_blocks: VecDeque::new(),
+            priority_op_cursor: None,
+            polling,
+            lag: 0,
+            next_poll_delay,
         }
     }
 
+    /// Returns the delay before the next poll in milliseconds, updated after every poll.
+    pub fn next_poll_delay(&self) -> Arc<AtomicU64> {
+        self.next_poll_delay.clone()
+    }
+
+    /// Reports the lag behind the Ethereum node and schedules the next poll.
+    ///
+    /// The lag is measured before the poll processes the new blocks: otherwise the watcher always looks
+    /// caught up after it, and the next poll isn't scheduled with the catch-up interval.
+    fn update_poll_schedule(&mut self) {
+        metrics::gauge!("eth_watcher.lag", self.lag as f64);
+
+        let delay = self.polling.next_delay(self.lag);
+        self.next_poll_delay
+            .store(delay.as_millis() as u64, Ordering::Relaxed);
+    }
+
     /// Atomically replaces the stored Ethereum state.
     fn set_new_state(&mut self, new_state: ETHState) {
         self.eth_state = new_state;
     }
 
-    async fn get_unconfirmed_ops(
-        &mut self,
-        current_ethereum_block: u64,
-    ) -> anyhow::Result<Vec<PriorityOp>> {
-        // We want to scan the interval of blocks from the latest one up to the oldest one which may
-        // have unconfirmed priority ops.
-        // `+ 1` is added because if we subtract number of confirmations, we'll obtain the last block
-        // which has operations that must be processed. So, for the unconfirmed operations, we must
-        // start from the block next to it.
-        let block_from_number =
-            current_ethereum_block.saturating_sub(self.number_of_confirmations_for_event) + 1;
-        let block_from = BlockNumber::Number(block_from_number.into());
-        let block_to = BlockNumber::Latest;
-
-        self.client
-            .get_priority_op_events(block_from, block_to)
-            .await
-    }
-
     async fn process_new_blocks(&mut self, last_ethereum_block: u64) -> anyhow::Result<()> {
         debug_assert!(self.eth_state.last_ethereum_block() < last_ethereum_block);
         debug_assert!(self.eth_state.last_ethereum_block() < last_ethereum_block);
 
         // We have to process every block between the current and previous known values.
         // This is crucial since `eth_watch` may enter the backoff mode in which it will skip many blocks.
-        // Note that we don't have to add `number_of_confirmations_for_event` here, because the check function takes
+        // Note that we don't have to add the number of confirmations here, because the check function takes
         // care of it on its own. Here we calculate "how many blocks should we watch", and the offsets with respect
-        // to the `number_of_confirmations_for_event` are calculated by `update_eth_state`.
+        // to the number of confirmations are calculated by `update_eth_state`.
         let mut next_priority_op_id = self.eth_state.next_priority_op_id();
         let previous_ethereum_block = self.eth_state.last_ethereum_block();
         let block_difference = last_ethereum_block.saturating_sub(previous_ethereum_block);
@@ -191,6 +271,14 @@ impl<W: EthClient> EthWatch<W> {
         register_nft_factory_events.sort_by_key(|factory_event| factory_event.creator_address);
         register_nft_factory_events.dedup_by_key(|factory_event| factory_event.creator_address);
 
+        let mut validator_status_updates = self.eth_state.validator_status_updates().to_vec();
+        for event in updated_state.validator_status_updates() {
+            validator_status_updates.push(event.clone());
+        }
+        // Remove duplicates, keeping the order of updates within a block.
+        validator_status_updates.sort_by_key(|event| (event.eth_block, event.log_index));
+        validator_status_updates.dedup_by_key(|event| (event.eth_block, event.log_index));
+
         let new_state = ETHState::new(
             last_ethereum_block,
             previous_ethereum_block,
@@ -198,17 +286,70 @@ impl<W: EthClient> EthWatch<W> {
             priority_queue,
             new_tokens,
             register_nft_factory_events,
+            validator_status_updates,
         );
         self.set_new_state(new_state);
         Ok(())
     }
 
+    /// Priority operations can't be rejected, so the deposits of the blacklisted tokens
+    /// are processed as usual and marked as withdraw-only: the deposited funds can only
+    /// be withdrawn back to L1.
+    async fn mark_withdraw_only_deposits(&self, ops: &[PriorityOp]) -> anyhow::Result<()> {
+        let blacklist = self.storage.load_token_blacklist().await?;
+        let deposits: Vec<_> = ops
+            .iter()
+            .filter(|op| {
+                matches!(
+                    &op.data,
+                    ZkSyncPriorityOp::Deposit(deposit) if blacklist.contains(&deposit.token)
+                )
+            })
+            .cloned()
+            .collect();
+        if deposits.is_empty() {
+            return Ok(());
+        }
+
+        for op in &deposits {
+            vlog::warn!(
+                "Priority operation {} deposits the blacklisted token {}, marking it as withdraw-only",
+                op.serial_id,
+                op.data.token_id()
+            );
+            metrics::increment_counter!(
+                "eth_watcher.withdraw_only_deposits",
+                "token" => op.data.token_id().to_string()
+            );
+        }
+        self.storage.save_withdraw_only_deposits(&deposits).await
+    }
+
     async fn restore_state_from_eth(&mut self, last_ethereum_block: u64) -> anyhow::Result<()> {
+        // Reorg could happen while the server was down, so the events from the reverted
+        // blocks have to be removed before restoring the state.
+        self.processed_blocks = self.storage.load_processed_blocks().await?.into();
+        self.priority_op_cursor = self.storage.load_priority_op_cursor().await?;
+        self.handle_reorg().await?;
+
+        // Blocks received during the downtime are scanned as well, so no operation is skipped.
+        let unprocessed_blocks_amount = match self.priority_op_cursor {
+            Some(cursor) => last_ethereum_block
+                .saturating_sub(cursor.last_processed_block)
+                .max(PRIORITY_EXPIRATION),
+            None => PRIORITY_EXPIRATION,
+        };
+        let block_hash = self.client.block_hash(last_ethereum_block).await?;
         let new_state = self
-            .update_eth_state(last_ethereum_block, PRIORITY_EXPIRATION)
+            .update_eth_state(last_ethereum_block, unprocessed_blocks_amount)
             .await?;
 
         self.set_new_state(new_state);
+        self.advance_priority_op_cursor(last_ethereum_block).await?;
+        if let Some(block_hash) = block_hash {
+            self.save_processed_block(last_ethereum_block, block_hash)
+                .await?;
+        }
 
         vlog::debug!("ETH state: {:#?}", self.eth_state);
         Ok(())
@@ -219,24 +360,37 @@ impl<W: EthClient> EthWatch<W> {
         current_ethereum_block: u64,
         unprocessed_blocks_amount: u64,
     ) -> anyhow::Result<ETHState> {
-        let new_block_with_accepted_events =
-            current_ethereum_block.saturating_sub(self.number_of_confirmations_for_event);
-        let previous_block_with_accepted_events =
-            new_block_with_accepted_events.saturating_sub(unprocessed_blocks_amount);
-
-        let unconfirmed_queue = self.get_unconfirmed_ops(current_ethereum_block).await?;
-        let priority_queue = self
+        // We want to scan the interval of blocks from the latest one up to the oldest one which may
+        // have priority ops that weren't accepted yet. Whether the operation is accepted depends on its type.
+        let previous_block_with_accepted_priority_ops = current_ethereum_block
+            .saturating_sub(self.confirmations.max_for_priority_ops())
+            .saturating_sub(unprocessed_blocks_amount);
+        let priority_ops = self
             .client
             .get_priority_op_events(
-                BlockNumber::Number(previous_block_with_accepted_events.into()),
-                BlockNumber::Number(new_block_with_accepted_events.into()),
+                BlockNumber::Number(previous_block_with_accepted_priority_ops.into()),
+                BlockNumber::Latest,
             )
             .await?;
+        let (priority_queue, unconfirmed_queue) = self
+            .confirmations
+            .split_priority_ops(priority_ops, current_ethereum_block);
+
+        let new_block_with_accepted_events =
+            current_ethereum_block.saturating_sub(self.confirmations.governance_event);
+        let previous_block_with_accepted_events =
+            new_block_with_accepted_events.saturating_sub(unprocessed_blocks_amount);
+
         let priority_queue_map: HashMap<u64, _> = priority_queue
             .iter()
             .cloned()
             .map(|priority_op| (priority_op.serial_id, priority_op.into()))
             .collect();
+        // Operations before the cursor are already stored in the mempool as confirmed ones.
+        let mut new_priority_ops = priority_queue;
+        if let Some(cursor) = self.priority_op_cursor {
+            new_priority_ops.retain(|op| op.serial_id >= cursor.next_priority_op_id);
+        }
 
         let new_tokens = self
             .client
@@ -254,12 +408,20 @@ impl<W: EthClient> EthWatch<W> {
             )
             .await?;
 
+        let validator_status_updates = self
+            .client
+            .get_validator_status_update_events(
+                BlockNumber::Number(previous_block_with_accepted_events.into()),
+                BlockNumber::Number(new_block_with_accepted_events.into()),
+            )
+            .await?;
+
         let mut new_priority_op_ids: Vec<_> = priority_queue_map.keys().cloned().collect();
         new_priority_op_ids.sort_unstable();
         vlog::debug!(
             "Updating eth state: block_range=[{},{}], new_priority_ops={:?}",
-            previous_block_with_accepted_events,
-            new_block_with_accepted_events,
+            previous_block_with_accepted_priority_ops,
+            current_ethereum_block,
             new_priority_op_ids
         );
 
@@ -275,11 +437,15 @@ impl<W: EthClient> EthWatch<W> {
 
         // TODO maybe retry? It can be the only problem is database
         receiver.await.expect("Mempool actor was dropped")?;
+        self.mark_withdraw_only_deposits(&new_priority_ops).await?;
+        for op in &new_priority_ops {
+            report_op_received("eth_watch", op.data.variance_name(), op.data.token_id());
+        }
         // Add confirmed priority ops to queue
         let (sender, receiver) = oneshot::channel();
         self.mempool_tx_sender
             .send(MempoolTransactionRequest::NewPriorityOps(
-                priority_queue,
+                new_priority_ops,
                 true,
                 sender,
             ))
@@ -295,6 +461,7 @@ impl<W: EthClient> EthWatch<W> {
             priority_queue_map,
             new_tokens,
             new_register_nft_factory_events,
+            validator_status_updates,
         );
         Ok(state)
     }
@@ -321,12 +488,226 @@ impl<W: EthClient> EthWatch<W> {
         new_tokens
     }
 
+    fn get_validator_status_updates(
+        &self,
+        last_block_number: Option<u64>,
+    ) -> Vec<ValidatorStatusUpdateEvent> {
+        let mut events = self.eth_state.validator_status_updates().to_vec();
+
+        if let Some(last_block_number) = last_block_number {
+            events.retain(|event| event.eth_block > last_block_number);
+        }
+
+        events
+    }
+
+    /// Checks whether the last processed block is still a part of the canonical chain.
+    /// If it's not, reverts the state to the latest common ancestor of the processed and
+    /// the canonical chains, so the events of the new chain will be processed once again.
+    async fn handle_reorg(&mut self) -> anyhow::Result<()> {
+        let (last_block, last_block_hash) = match self.processed_blocks.back() {
+            Some(block) => *block,
+            None => return Ok(()),
+        };
+        if self.client.block_hash(last_block).await? == Some(last_block_hash) {
+            return Ok(());
+        }
+
+        let mut common_ancestor = None;
+        for (block, block_hash) in self.processed_blocks.iter().rev().skip(1) {
+            if self.client.block_hash(*block).await? == Some(*block_hash) {
+                common_ancestor = Some(*block);
+                break;
+            }
+        }
+        let common_ancestor = match common_ancestor {
+            Some(block) => block,
+            None => {
+                // None of the tracked blocks belongs to the canonical chain anymore,
+                // so the best we can do is to revert every tracked block.
+                let oldest_block = self.processed_blocks[0].0.saturating_sub(1);
+                vlog::error!(
+                    "Ethereum chain reorganization is deeper than {} tracked blocks, reverting to the block {}",
+                    self.processed_blocks.len(),
+                    oldest_block
+                );
+                oldest_block
+            }
+        };
+
+        vlog::warn!(
+            "Ethereum chain reorganization detected: processed block {} ({:#x}) was reverted, \
+            the latest common ancestor is the block {}",
+            last_block,
+            last_block_hash,
+            common_ancestor
+        );
+        metrics::increment_counter!("eth_watcher.reorg");
+        self.revert_to_block(common_ancestor).await
+    }
+
+    /// Reverts all the events received after the `last_valid_block`.
+    async fn revert_to_block(&mut self, last_valid_block: u64) -> anyhow::Result<()> {
+        // Mempool is updated first: in case of failure the processed blocks are left
+        // intact, and the reorg will be detected once again on the next poll.
+        let (sender, receiver) = oneshot::channel();
+        self.mempool_tx_sender
+            .send(MempoolTransactionRequest::RevertPriorityOps(
+                last_valid_block,
+                sender,
+            ))
+            .await?;
+        receiver.await.expect("Mempool actor was dropped")?;
+
+        self.storage
+            .revert_processed_blocks(last_valid_block)
+            .await?;
+        self.processed_blocks
+            .retain(|(block, _)| *block <= last_valid_block);
+        let next_priority_op_id = self.eth_state.next_priority_op_id();
+        let reverted_tokens = self.eth_state.revert_to_block(last_valid_block);
+        self.remove_reverted_tokens(&reverted_tokens).await?;
+
+        if let Some(mut cursor) = self.priority_op_cursor {
+            cursor.last_processed_block = cursor.last_processed_block.min(last_valid_block);
+            if self.eth_state.next_priority_op_id() < next_priority_op_id {
+                // Reverted operations will be received once again.
+                cursor.next_priority_op_id = cursor
+                    .next_priority_op_id
+                    .min(self.eth_state.next_priority_op_id());
+            }
+            self.storage.save_priority_op_cursor(cursor).await?;
+            self.priority_op_cursor = Some(cursor);
+        }
+        Ok(())
+    }
+
+    /// Removes the tokens added by the reverted blocks from the database. The events are
+    /// received once again if the tokens are added by the new chain, possibly with other ids.
+    async fn remove_reverted_tokens(
+        &mut self,
+        reverted_tokens: &[NewTokenEvent],
+    ) -> anyhow::Result<()> {
+        if reverted_tokens.is_empty() {
+            return Ok(());
+        }
+        let token_ids: Vec<_> = reverted_tokens.iter().map(|token| token.id).collect();
+        let removed_ids = self.storage.remove_reverted_tokens(&token_ids).await?;
+        for token_id in token_ids {
+            if !removed_ids.contains(&token_id) {
+                // The deposits of the token were already executed, so the reorg is deeper
+                // than the confirmations required for the priority operations.
+                vlog::error!(
+                    "Token {} added by the reverted Ethereum block has balances and is kept",
+                    token_id
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the priority operation cursor to the newly processed blocks. Once new priority
+    /// operations are accepted, checks that their amount matches the priority queue of the contract.
+    async fn advance_priority_op_cursor(
+        &mut self,
+        current_ethereum_block: u64,
+    ) -> anyhow::Result<()> {
+        let last_processed_block =
+            current_ethereum_block.saturating_sub(self.confirmations.max_for_priority_ops());
+        let next_priority_op_id = match self.priority_op_cursor {
+            Some(cursor) => cursor.next_priority_op_id,
+            // Without the stored cursor, operations received before the scanned range
+            // are considered processed.
+            None => {
+                self.client
+                    .total_priority_requests(BlockNumber::Number(last_processed_block.into()))
+                    .await?
+            }
+        }
+        .max(self.eth_state.next_priority_op_id());
+
+        let cursor = PriorityOpCursor {
+            last_processed_block,
+            next_priority_op_id,
+        };
+        if self.priority_op_cursor == Some(cursor) {
+            return Ok(());
+        }
+        let is_new_priority_ops = self.priority_op_cursor.map_or(true, |cursor| {
+            cursor.next_priority_op_id != next_priority_op_id
+        });
+        if is_new_priority_ops {
+            self.check_priority_ops_consistency(last_processed_block, next_priority_op_id)
+                .await?;
+        }
+
+        self.storage.save_priority_op_cursor(cursor).await?;
+        self.priority_op_cursor = Some(cursor);
+        Ok(())
+    }
+
+    /// Checks that every priority request received by the contract up to the `last_processed_block`
+    /// is processed, and that there are no more processed operations than the contract has.
+    async fn check_priority_ops_consistency(
+        &self,
+        last_processed_block: u64,
+        next_priority_op_id: SerialId,
+    ) -> anyhow::Result<()> {
+        let confirmed_requests = self
+            .client
+            .total_priority_requests(BlockNumber::Number(last_processed_block.into()))
+            .await?;
+        let total_requests = self
+            .client
+            .total_priority_requests(BlockNumber::Latest)
+            .await?;
+
+        if !(confirmed_requests..=total_requests).contains(&next_priority_op_id) {
+            metrics::increment_counter!("eth_watcher.priority_ops_inconsistency");
+            return Err(PriorityOpsInconsistencyError {
+                next_priority_op_id,
+                confirmed_requests,
+                total_requests,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    async fn save_processed_block(&mut self, block: u64, block_hash: H256) -> anyhow::Result<()> {
+        let oldest_tracked_block = block.saturating_sub(REORG_DETECTION_WINDOW);
+        self.storage
+            .save_processed_block(block, block_hash, oldest_tracked_block)
+            .await?;
+
+        self.processed_blocks
+            .retain(|(processed_block, _)| (oldest_tracked_block..block).contains(processed_block));
+        self.processed_blocks.push_back((block, block_hash));
+        Ok(())
+    }
+
     async fn poll_eth_node(&mut self) -> anyhow::Result<()> {
         let start = Instant::now();
         let last_block_number = self.client.block_number().await?;
+        self.polling
+            .observe_block(last_block_number, Instant::now());
+        self.lag = last_block_number.saturating_sub(self.eth_state.last_ethereum_block());
 
+        self.handle_reorg().await?;
         if last_block_number > self.eth_state.last_ethereum_block() {
+            // Hash is obtained before processing the events, so if the block gets reverted
+            // in the meantime, it will be detected on the next poll.
+            let block_hash = self.client.block_hash(last_block_number).await?;
             self.process_new_blocks(last_block_number).await?;
+            if let Err(error) = self.advance_priority_op_cursor(last_block_number).await {
+                // The same blocks range will be processed once again on the next poll.
+                self.eth_state.reset_last_ethereum_block();
+                return Err(error);
+            }
+            if let Some(block_hash) = block_hash {
+                self.save_processed_block(last_block_number, block_hash)
+                    .await?;
+            }
         }
 
         metrics::histogram!("eth_watcher.poll_eth_node", start.elapsed());
@@ -422,6 +803,7 @@ impl<W: EthClient> EthWatch<W> {
                             vlog::error!("Failed to process new blocks {}", error);
                         }
                     }
+                    self.update_poll_schedule();
                 }
                 EthWatchRequest::GetNewTokens {
                     last_eth_block,
@@ -436,6 +818,13 @@ impl<W: EthClient> EthWatch<W> {
                     resp.send(self.get_register_factory_event(last_eth_block))
                         .ok();
                 }
+                EthWatchRequest::GetValidatorStatusUpdates {
+                    last_eth_block,
+                    resp,
+                } => {
+                    resp.send(self.get_validator_status_updates(last_eth_block))
+                        .ok();
+                }
             }
         }
     }
@@ -448,6 +837,7 @@ pub async fn start_eth_watch(
     contract_config: &ContractsConfig,
     eth_watcher_config: &ETHWatchConfig,
     mempool_req_sender: mpsc::Sender<MempoolTransactionRequest>,
+    db_pool: ConnectionPool,
 ) -> JoinHandle<()> {
     let eth_client = EthHttpClient::new(
         eth_gateway,
@@ -457,20 +847,38 @@ pub async fn start_eth_watch(
 
     let mut eth_watch = EthWatch::new(
         eth_client,
+        DbStorage::new(db_pool),
         mempool_req_sender,
-        eth_watcher_config.confirmations_for_eth_event,
+        EventConfirmations::from_config(eth_watcher_config),
+        PollingSchedule::new(
+            eth_watcher_config.poll_interval(),
+            eth_watcher_config.max_poll_interval(),
+        ),
     );
+    let next_poll_delay = eth_watch.next_poll_delay();
 
     eth_watch.restore_from_eth_using_latest_block_number().await;
 
     tokio::spawn(eth_watch.run(eth_req_receiver));
 
-    let poll_interval = eth_watcher_config.poll_interval();
-    tokio::spawn(async move {
-        let mut timer = time::interval(poll_interval);
+    if let Some(ws_url) = eth_watcher_config.eth_node_ws_url.clone() {
+        let poll_trigger = SubscriptionPollTrigger::new(
+            ws_url,
+            vec![
+                contract_config.contract_addr,
+                contract_config.governance_addr,
+            ],
+            eth_watcher_config.poll_interval(),
+            eth_watcher_config.ws_fallback_interval(),
+            eth_req_sender,
+        );
+        return tokio::spawn(poll_trigger.run());
+    }
 
+    tokio::spawn(async move {
         loop {
-            timer.tick().await;
+            let delay = next_poll_delay.load(Ordering::Relaxed);
+            time::sleep(Duration::from_millis(delay)).await;
             eth_req_sender
                 .clone()
                 .send(EthWatchRequest::PollETHNode)
@@ -0,0 +1,155 @@
+//! Adaptive polling schedule of the Ethereum watcher.
+//!
+//! While the watcher lags behind the Ethereum node, the node is polled with the minimal interval.
+//! Once the watcher has caught up, there is no need to poll the node more often than the new blocks
+//! appear, so the interval is derived from the observed block time and is bounded by the maximal
+//! interval. A random jitter is added so that several servers don't poll the node simultaneously.
+
+// Built-in deps
+use std::time::{Duration, Instant};
+// External uses
+use rand::Rng;
+// Workspace deps
+// Local deps
+
+/// Weight of the latest observed block time in its moving average.
+const BLOCK_TIME_SMOOTHING: f64 = 0.2;
+/// Maximal deviation of the poll delay from the computed interval, as a fraction of the interval.
+const JITTER_FRACTION: f64 = 0.1;
+
+/// Interval between the polls of the Ethereum node, adjusted to the observed block cadence.
+#[derive(Debug, Clone)]
+pub struct PollingSchedule {
+    min_interval: Duration,
+    max_interval: Duration,
+    /// Moving average of the time between the Ethereum blocks.
+    block_time: Option<Duration>,
+    /// The latest Ethereum block seen by the watcher and the moment it was first seen.
+    last_observed_block: Option<(u64, Instant)>,
+}
+
+impl PollingSchedule {
+    pub fn new(min_interval: Duration, max_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            max_interval: max_interval.max(min_interval),
+            block_time: None,
+            last_observed_block: None,
+        }
+    }
+
+    /// Updates the estimated block time once the Ethereum node reports a new block.
+    pub fn observe_block(&mut self, block: u64, now: Instant) {
+        match self.last_observed_block {
+            Some((last_block, last_seen)) if block > last_block => {
+                let elapsed = now.saturating_duration_since(last_seen);
+                let sample = elapsed.div_f64((block - last_block) as f64);
+                let block_time = match self.block_time {
+                    Some(block_time) => {
+                        block_time.mul_f64(1.0 - BLOCK_TIME_SMOOTHING)
+                            + sample.mul_f64(BLOCK_TIME_SMOOTHING)
+                    }
+                    None => sample,
+                };
+                self.block_time = Some(block_time);
+                self.last_observed_block = Some((block, now));
+            }
+            Some(_) => {}
+            None => self.last_observed_block = Some((block, now)),
+        }
+    }
+
+    /// Returns the latest Ethereum block reported by the node.
+    pub fn last_observed_block(&self) -> Option<u64> {
+        self.last_observed_block.map(|(block, _)| block)
+    }
+
+    /// Returns the poll interval for the given amount of Ethereum blocks the watcher lags behind.
+    pub fn interval(&self, lag: u64) -> Duration {
+        if lag > 0 {
+            return self.min_interval;
+        }
+        // The new block is expected to appear in a single block time, so the node is polled
+        // twice per block to notice it in time.
+        let interval = self
+            .block_time
+            .map(|block_time| block_time / 2)
+            .unwrap_or(self.min_interval);
+        interval.max(self.min_interval).min(self.max_interval)
+    }
+
+    /// Returns the delay before the next poll, with the random jitter applied.
+    pub fn next_delay(&self, lag: u64) -> Duration {
+        let jitter = rand::thread_rng().gen_range(-JITTER_FRACTION..=JITTER_FRACTION);
+        self.interval(lag).mul_f64(1.0 + jitter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN_INTERVAL: Duration = Duration::from_millis(100);
+    const MAX_INTERVAL: Duration = Duration::from_secs(5);
+
+    fn assert_block_time(schedule: &PollingSchedule, expected: Duration) {
+        let block_time = schedule.block_time.expect("Block time is not estimated");
+        let difference = block_time.max(expected) - block_time.min(expected);
+        assert!(
+            difference < Duration::from_millis(1),
+            "Block time {:?} differs from the expected {:?}",
+            block_time,
+            expected
+        );
+    }
+
+    /// Checks that the block time is estimated from the observed blocks.
+    #[test]
+    fn block_time_estimation() {
+        let mut schedule = PollingSchedule::new(MIN_INTERVAL, MAX_INTERVAL);
+        let start = Instant::now();
+        assert_eq!(schedule.interval(0), MIN_INTERVAL);
+
+        schedule.observe_block(10, start);
+        // The same block seen later doesn't affect the estimation.
+        schedule.observe_block(10, start + Duration::from_secs(3));
+        schedule.observe_block(11, start + Duration::from_secs(4));
+        assert_block_time(&schedule, Duration::from_secs(4));
+        assert_eq!(schedule.interval(0), Duration::from_secs(2));
+
+        // Several blocks at once are averaged.
+        schedule.observe_block(13, start + Duration::from_secs(12));
+        assert_block_time(&schedule, Duration::from_secs(4));
+
+        schedule.observe_block(14, start + Duration::from_secs(21));
+        assert_block_time(&schedule, Duration::from_secs(5));
+    }
+
+    /// Checks that the interval is bounded and is minimal while the watcher is catching up.
+    #[test]
+    fn interval_bounds() {
+        let start = Instant::now();
+        let mut schedule = PollingSchedule::new(MIN_INTERVAL, MAX_INTERVAL);
+        schedule.observe_block(1, start);
+        schedule.observe_block(2, start + Duration::from_secs(60));
+        assert_eq!(schedule.interval(0), MAX_INTERVAL);
+        assert_eq!(schedule.interval(1), MIN_INTERVAL);
+        // The delay switches to the catch-up interval while the watcher lags behind.
+        for _ in 0..100 {
+            let delay = schedule.next_delay(1);
+            assert!(delay >= MIN_INTERVAL.mul_f64(1.0 - JITTER_FRACTION));
+            assert!(delay <= MIN_INTERVAL.mul_f64(1.0 + JITTER_FRACTION));
+        }
+
+        let mut schedule = PollingSchedule::new(MIN_INTERVAL, MAX_INTERVAL);
+        schedule.observe_block(1, start);
+        schedule.observe_block(1000, start + Duration::from_secs(1));
+        assert_eq!(schedule.interval(0), MIN_INTERVAL);
+
+        for _ in 0..100 {
+            let delay = schedule.next_delay(0);
+            assert!(delay >= MIN_INTERVAL.mul_f64(1.0 - JITTER_FRACTION));
+            assert!(delay <= MIN_INTERVAL.mul_f64(1.0 + JITTER_FRACTION));
+        }
+    }
+}
@@ -0,0 +1,151 @@
+// Built-in deps
+use std::collections::HashSet;
+// External uses
+// Workspace deps
+use zksync_storage::ConnectionPool;
+use zksync_types::{PriorityOp, SerialId, TokenId, H256};
+// Local deps
+
+/// Position of `EthWatch` in the priority queue of the zkSync contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriorityOpCursor {
+    /// The last Ethereum block which priority operations are fully processed.
+    pub last_processed_block: u64,
+    /// Serial id of the next expected priority operation. All the operations with lower serial ids
+    /// are processed, since `EthWatch` never accepts priority operations with gaps.
+    pub next_priority_op_id: SerialId,
+}
+
+/// Abstract storage access trait, optimized for the needs of `EthWatch`.
+#[async_trait::async_trait]
+pub trait EthWatchStorage {
+    /// Loads the processed Ethereum blocks in the ascending order.
+    async fn load_processed_blocks(&self) -> anyhow::Result<Vec<(u64, H256)>>;
+
+    /// Stores the hash of the processed Ethereum block. Blocks older than `oldest_tracked_block`
+    /// are not required for the reorg detection anymore and can be removed.
+    async fn save_processed_block(
+        &self,
+        block_number: u64,
+        block_hash: H256,
+        oldest_tracked_block: u64,
+    ) -> anyhow::Result<()>;
+
+    /// Removes the processed blocks that are newer than `last_valid_block`.
+    async fn revert_processed_blocks(&self, last_valid_block: u64) -> anyhow::Result<()>;
+
+    /// Loads the position in the priority queue, if it was ever stored.
+    async fn load_priority_op_cursor(&self) -> anyhow::Result<Option<PriorityOpCursor>>;
+
+    /// Stores the position in the priority queue, replacing the previous one.
+    async fn save_priority_op_cursor(&self, cursor: PriorityOpCursor) -> anyhow::Result<()>;
+
+    /// Loads the ids of the blacklisted tokens.
+    async fn load_token_blacklist(&self) -> anyhow::Result<HashSet<TokenId>>;
+
+    /// Marks the deposits among the given priority operations as withdraw-only.
+    async fn save_withdraw_only_deposits(&self, deposits: &[PriorityOp]) -> anyhow::Result<()>;
+
+    /// Removes the tokens added by the reverted blocks, returns the ids of the removed ones.
+    async fn remove_reverted_tokens(&self, token_ids: &[TokenId]) -> anyhow::Result<Vec<TokenId>>;
+}
+
+/// The actual database wrapper.
+#[derive(Debug, Clone)]
+pub struct DbStorage {
+    db_pool: ConnectionPool,
+}
+
+impl DbStorage {
+    pub fn new(db_pool: ConnectionPool) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl EthWatchStorage for DbStorage {
+    async fn load_processed_blocks(&self) -> anyhow::Result<Vec<(u64, H256)>> {
+        let mut storage = self.db_pool.access_storage().await?;
+        let blocks = storage.eth_watch_schema().load_processed_blocks().await?;
+
+        Ok(blocks)
+    }
+
+    async fn save_processed_block(
+        &self,
+        block_number: u64,
+        block_hash: H256,
+        oldest_tracked_block: u64,
+    ) -> anyhow::Result<()> {
+        let mut storage = self.db_pool.access_storage().await?;
+        storage
+            .eth_watch_schema()
+            .save_processed_block(block_number, block_hash, oldest_tracked_block)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn revert_processed_blocks(&self, last_valid_block: u64) -> anyhow::Result<()> {
+        let mut storage = self.db_pool.access_storage().await?;
+        storage
+            .eth_watch_schema()
+            .remove_processed_blocks_after(last_valid_block)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn load_priority_op_cursor(&self) -> anyhow::Result<Option<PriorityOpCursor>> {
+        let mut storage = self.db_pool.access_storage().await?;
+        let cursor = storage
+            .eth_watch_schema()
+            .load_priority_op_cursor()
+            .await?
+            .map(
+                |(last_processed_block, next_priority_op_id)| PriorityOpCursor {
+                    last_processed_block,
+                    next_priority_op_id,
+                },
+            );
+
+        Ok(cursor)
+    }
+
+    async fn save_priority_op_cursor(&self, cursor: PriorityOpCursor) -> anyhow::Result<()> {
+        let mut storage = self.db_pool.access_storage().await?;
+        storage
+            .eth_watch_schema()
+            .save_priority_op_cursor(cursor.last_processed_block, cursor.next_priority_op_id)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn load_token_blacklist(&self) -> anyhow::Result<HashSet<TokenId>> {
+        let mut storage = self.db_pool.access_storage().await?;
+        let blacklist = storage.tokens_schema().load_token_blacklist().await?;
+
+        Ok(blacklist)
+    }
+
+    async fn save_withdraw_only_deposits(&self, deposits: &[PriorityOp]) -> anyhow::Result<()> {
+        let mut storage = self.db_pool.access_storage().await?;
+        storage
+            .tokens_schema()
+            .store_withdraw_only_deposits(deposits)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_reverted_tokens(&self, token_ids: &[TokenId]) -> anyhow::Result<Vec<TokenId>> {
+        let mut storage = self.db_pool.access_storage().await?;
+        let removed_ids = storage
+            .tokens_schema()
+            .remove_reverted_tokens(token_ids)
+            .await?;
+
+        Ok(removed_ids)
+    }
+}
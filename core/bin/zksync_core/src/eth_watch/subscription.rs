@@ -0,0 +1,106 @@
+//! WebSocket subscription mode of the Ethereum watcher.
+//!
+//! Instead of polling the Ethereum node with the fixed interval, the watcher subscribes to the
+//! new blocks and to the logs of the zkSync contracts, and polls the node only once notified.
+//! Once the connection is lost, the watcher falls back to the regular polling until the
+//! subscription is restored.
+
+// Built-in deps
+use std::time::{Duration, Instant};
+// External uses
+use futures::{channel::mpsc, stream, SinkExt, StreamExt};
+use tokio::time;
+use web3::{
+    transports::WebSocket,
+    types::{FilterBuilder, H160},
+    Web3,
+};
+// Local deps
+use super::EthWatchRequest;
+
+/// Sends the poll requests to the Ethereum watcher whenever the node notifies about new events.
+pub(super) struct SubscriptionPollTrigger {
+    ws_url: String,
+    contract_addresses: Vec<H160>,
+    /// Interval of the regular polling used when the subscription is not available.
+    poll_interval: Duration,
+    /// See `ETHWatchConfig::eth_node_ws_fallback_interval`.
+    fallback_interval: Duration,
+    eth_req_sender: mpsc::Sender<EthWatchRequest>,
+}
+
+impl SubscriptionPollTrigger {
+    pub fn new(
+        ws_url: String,
+        contract_addresses: Vec<H160>,
+        poll_interval: Duration,
+        fallback_interval: Duration,
+        eth_req_sender: mpsc::Sender<EthWatchRequest>,
+    ) -> Self {
+        Self {
+            ws_url,
+            contract_addresses,
+            poll_interval,
+            fallback_interval,
+            eth_req_sender,
+        }
+    }
+
+    pub async fn run(mut self) {
+        loop {
+            match self.run_subscription().await {
+                Ok(()) => vlog::warn!(
+                    "Ethereum node closed the WebSocket subscription, falling back to polling"
+                ),
+                Err(err) => vlog::warn!(
+                    "Ethereum node WebSocket subscription failed: {}, falling back to polling",
+                    err
+                ),
+            }
+            metrics::increment_counter!("eth_watcher.ws_fallback");
+
+            self.poll_regularly(self.fallback_interval).await;
+        }
+    }
+
+    /// Requests a poll on every notification until the subscription is closed.
+    async fn run_subscription(&mut self) -> anyhow::Result<()> {
+        let web3 = Web3::new(WebSocket::new(&self.ws_url).await?);
+        let new_heads = web3.eth_subscribe().subscribe_new_heads().await?;
+        let filter = FilterBuilder::default()
+            .address(self.contract_addresses.clone())
+            .build();
+        let logs = web3.eth_subscribe().subscribe_logs(filter).await?;
+        vlog::info!("Subscribed to the Ethereum node notifications over WebSocket");
+
+        let mut notifications = stream::select(
+            new_heads.map(|notification| notification.map(drop)).boxed(),
+            logs.map(|notification| notification.map(drop)).boxed(),
+        );
+        loop {
+            // Notifications may be lost, so the node is polled anyway after a while.
+            match time::timeout(self.fallback_interval, notifications.next()).await {
+                Ok(Some(notification)) => notification?,
+                Ok(None) => return Ok(()),
+                Err(_elapsed) => {}
+            }
+            self.request_poll().await;
+        }
+    }
+
+    async fn poll_regularly(&mut self, duration: Duration) {
+        let deadline = Instant::now() + duration;
+        let mut timer = time::interval(self.poll_interval);
+        while Instant::now() < deadline {
+            timer.tick().await;
+            self.request_poll().await;
+        }
+    }
+
+    async fn request_poll(&mut self) {
+        self.eth_req_sender
+            .send(EthWatchRequest::PollETHNode)
+            .await
+            .expect("ETH watch receiver dropped");
+    }
+}
@@ -1,13 +1,14 @@
 #![allow(clippy::diverging_sub_expression)]
 use std::cmp::max;
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{atomic::Ordering, Arc};
+use std::time::{Duration, Instant};
 
 use web3::types::{Address, BlockNumber};
 
 use zksync_types::{
     AccountId, Deposit, FullExit, NewTokenEvent, Nonce, PriorityOp, RegisterNFTFactoryEvent,
-    SerialId, TokenId, ZkSyncPriorityOp, H256,
+    SerialId, TokenId, ValidatorStatusUpdateEvent, ZkSyncPriorityOp, H256,
 };
 
 use futures::channel::mpsc;
@@ -16,18 +17,41 @@ use tokio::sync::RwLock;
 use zksync_mempool::MempoolTransactionRequest;
 
 use super::is_missing_priority_op_error;
-use crate::eth_watch::{client::EthClient, EthWatch};
+use crate::eth_watch::{
+    client::EthClient,
+    storage::{EthWatchStorage, PriorityOpCursor},
+    EthWatch, EventConfirmations, PollingSchedule,
+};
 
 struct FakeEthClientData {
     priority_ops: HashMap<u64, Vec<PriorityOp>>,
+    new_tokens: Vec<NewTokenEvent>,
     last_block_number: u64,
+    /// Blocks starting from this one were replaced by the chain reorganization.
+    reorg_block_number: u64,
+    /// Number of the reorganizations that happened, used to generate different block hashes.
+    reorgs_count: u64,
+    /// Priority requests received by the contract, which logs are not returned by the node.
+    missing_priority_requests: u64,
 }
 
 impl FakeEthClientData {
     fn new() -> Self {
         Self {
             priority_ops: Default::default(),
+            new_tokens: Vec::new(),
             last_block_number: 0,
+            reorg_block_number: u64::MAX,
+            reorgs_count: 0,
+            missing_priority_requests: 0,
+        }
+    }
+
+    fn block_hash(&self, block_number: u64) -> H256 {
+        if block_number >= self.reorg_block_number {
+            H256::from_low_u64_be((self.reorgs_count << 32) + block_number)
+        } else {
+            H256::from_low_u64_be(block_number)
         }
     }
 
@@ -67,10 +91,99 @@ impl FakeEthClient {
         }
     }
 
+    async fn add_new_tokens(&mut self, tokens: &[NewTokenEvent]) {
+        self.inner
+            .write()
+            .await
+            .new_tokens
+            .extend_from_slice(tokens);
+    }
+
     async fn set_last_block_number(&mut self, block_number: u64) {
         let mut inner = self.inner.write().await;
         inner.last_block_number = block_number;
     }
+
+    /// Replaces the blocks starting from `first_reverted_block` with the new
+    /// ones, which don't have any operations.
+    async fn reorg(&mut self, first_reverted_block: u64) {
+        let mut inner = self.inner.write().await;
+        inner
+            .priority_ops
+            .retain(|block_number, _| *block_number < first_reverted_block);
+        inner
+            .new_tokens
+            .retain(|token| token.eth_block_number < first_reverted_block);
+        inner.reorg_block_number = first_reverted_block;
+        inner.reorgs_count += 1;
+    }
+}
+
+#[derive(Default)]
+struct FakeStorage {
+    processed_blocks: RwLock<BTreeMap<u64, H256>>,
+    priority_op_cursor: RwLock<Option<PriorityOpCursor>>,
+    token_blacklist: RwLock<HashSet<TokenId>>,
+    withdraw_only_deposits: RwLock<Vec<PriorityOp>>,
+    removed_tokens: RwLock<Vec<TokenId>>,
+}
+
+#[async_trait::async_trait]
+impl EthWatchStorage for FakeStorage {
+    async fn load_processed_blocks(&self) -> anyhow::Result<Vec<(u64, H256)>> {
+        let processed_blocks = self.processed_blocks.read().await;
+        Ok(processed_blocks
+            .iter()
+            .map(|(block_number, block_hash)| (*block_number, *block_hash))
+            .collect())
+    }
+
+    async fn save_processed_block(
+        &self,
+        block_number: u64,
+        block_hash: H256,
+        oldest_tracked_block: u64,
+    ) -> anyhow::Result<()> {
+        let mut processed_blocks = self.processed_blocks.write().await;
+        processed_blocks.insert(block_number, block_hash);
+        processed_blocks.retain(|block_number, _| *block_number >= oldest_tracked_block);
+        Ok(())
+    }
+
+    async fn revert_processed_blocks(&self, last_valid_block: u64) -> anyhow::Result<()> {
+        let mut processed_blocks = self.processed_blocks.write().await;
+        processed_blocks.retain(|block_number, _| *block_number <= last_valid_block);
+        Ok(())
+    }
+
+    async fn load_priority_op_cursor(&self) -> anyhow::Result<Option<PriorityOpCursor>> {
+        Ok(*self.priority_op_cursor.read().await)
+    }
+
+    async fn save_priority_op_cursor(&self, cursor: PriorityOpCursor) -> anyhow::Result<()> {
+        *self.priority_op_cursor.write().await = Some(cursor);
+        Ok(())
+    }
+
+    async fn load_token_blacklist(&self) -> anyhow::Result<HashSet<TokenId>> {
+        Ok(self.token_blacklist.read().await.clone())
+    }
+
+    async fn save_withdraw_only_deposits(&self, deposits: &[PriorityOp]) -> anyhow::Result<()> {
+        self.withdraw_only_deposits
+            .write()
+            .await
+            .extend_from_slice(deposits);
+        Ok(())
+    }
+
+    async fn remove_reverted_tokens(&self, token_ids: &[TokenId]) -> anyhow::Result<Vec<TokenId>> {
+        self.removed_tokens
+            .write()
+            .await
+            .extend_from_slice(token_ids);
+        Ok(token_ids.to_vec())
+    }
 }
 
 #[async_trait::async_trait]
@@ -100,11 +213,28 @@ impl EthClient for FakeEthClient {
     }
 
     async fn get_new_tokens_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> anyhow::Result<Vec<NewTokenEvent>> {
+        let from = self.block_to_number(&from).await;
+        let to = self.block_to_number(&to).await;
+        Ok(self
+            .inner
+            .read()
+            .await
+            .new_tokens
+            .iter()
+            .filter(|token| (from..=to).contains(&token.eth_block_number))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_validator_status_update_events(
         &self,
         _from: BlockNumber,
         _to: BlockNumber,
-    ) -> anyhow::Result<Vec<NewTokenEvent>> {
-        // Ignore NewTokens event.
+    ) -> anyhow::Result<Vec<ValidatorStatusUpdateEvent>> {
         Ok(Vec::new())
     }
 
@@ -112,6 +242,27 @@ impl EthClient for FakeEthClient {
         Ok(self.inner.read().await.last_block_number)
     }
 
+    async fn block_hash(&self, block_number: u64) -> Result<Option<H256>, anyhow::Error> {
+        let inner = self.inner.read().await;
+        if block_number > inner.last_block_number {
+            return Ok(None);
+        }
+        Ok(Some(inner.block_hash(block_number)))
+    }
+
+    async fn total_priority_requests(&self, block: BlockNumber) -> anyhow::Result<u64> {
+        let block_number = self.block_to_number(&block).await;
+        let inner = self.inner.read().await;
+        let received_requests = inner
+            .priority_ops
+            .iter()
+            .filter(|(eth_block, _)| **eth_block <= block_number)
+            .flat_map(|(_, ops)| ops.iter().map(|op| op.serial_id + 1))
+            .max()
+            .unwrap_or(0);
+        Ok(received_requests + inner.missing_priority_requests)
+    }
+
     async fn get_auth_fact(
         &self,
         _address: Address,
@@ -132,8 +283,14 @@ impl EthClient for FakeEthClient {
 fn create_watcher<T: EthClient>(
     client: T,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
-) -> EthWatch<T> {
-    EthWatch::new(client, mempool_tx_sender, 1)
+) -> EthWatch<T, FakeStorage> {
+    EthWatch::new(
+        client,
+        FakeStorage::default(),
+        mempool_tx_sender,
+        EventConfirmations::uniform(1),
+        PollingSchedule::new(Duration::from_millis(100), Duration::from_secs(5)),
+    )
 }
 
 async fn fake_mempool(
@@ -142,7 +299,7 @@ async fn fake_mempool(
 ) {
     while let Some(a) = receiver.next().await {
         match a {
-            MempoolTransactionRequest::NewTx(_, _) => {
+            MempoolTransactionRequest::NewTx(_, _, _) => {
                 unreachable!()
             }
             MempoolTransactionRequest::NewPriorityOps(ops, conf, channel) => {
@@ -152,7 +309,12 @@ async fn fake_mempool(
                 }
                 channel.send(Ok(())).unwrap_or_default()
             }
-            MempoolTransactionRequest::NewTxsBatch(_, _, _) => unreachable!(),
+            MempoolTransactionRequest::NewTxsBatch(_, _, _, _) => unreachable!(),
+            MempoolTransactionRequest::RevertPriorityOps(last_valid_eth_block, channel) => {
+                let mut lock = data.write().await;
+                lock.retain(|_, (op, _)| op.eth_block <= last_valid_eth_block);
+                channel.send(Ok(())).unwrap_or_default()
+            }
         }
     }
 }
@@ -240,7 +402,7 @@ async fn test_operation_queues() {
 }
 
 /// This test simulates the situation when eth watch module did not poll Ethereum node for some time
-/// (e.g. because of rate limit) and skipped more blocks than `number_of_confirmations_for_event`.
+/// (e.g. because of rate limit) and skipped more blocks than the number of confirmations.
 #[tokio::test]
 async fn test_operation_queues_time_lag() {
     let mut client = FakeEthClient::new();
@@ -547,3 +709,299 @@ async fn test_serial_id_gaps() {
     assert_eq!(watcher.eth_state.last_ethereum_block_backup(), 0);
     assert_eq!(watcher.eth_state.last_ethereum_block(), 3);
 }
+
+/// Checks that the events from the blocks reverted by the chain reorganization
+/// are removed both from the watcher state and the mempool.
+#[tokio::test]
+async fn test_reorg() {
+    let mut client = FakeEthClient::new();
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+
+    let priority_ops = vec![
+        PriorityOp {
+            serial_id: 0,
+            data: ZkSyncPriorityOp::Deposit(Deposit {
+                from: Default::default(),
+                token: TokenId(0),
+                amount: Default::default(),
+                to: [2u8; 20].into(),
+            }),
+            deadline_block: 0,
+            eth_hash: [2; 32].into(),
+            eth_block: 2,
+            eth_block_index: Some(1),
+        },
+        PriorityOp {
+            serial_id: 1,
+            data: ZkSyncPriorityOp::Deposit(Deposit {
+                from: Default::default(),
+                token: TokenId(0),
+                amount: Default::default(),
+                to: [3u8; 20].into(),
+            }),
+            deadline_block: 0,
+            eth_hash: [3; 32].into(),
+            eth_block: 4, // <- This operation will be reverted.
+            eth_block_index: Some(1),
+        },
+    ];
+    client.add_operations(&priority_ops).await;
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    client.set_last_block_number(3).await;
+    watcher.poll_eth_node().await.unwrap();
+    client.set_last_block_number(4).await;
+    watcher.poll_eth_node().await.unwrap();
+
+    assert_eq!(watcher.eth_state.last_ethereum_block(), 4);
+    assert_eq!(watcher.eth_state.unconfirmed_queue().len(), 1);
+    assert!(data.read().await.contains_key(&1));
+
+    // The 4th block is replaced, and the new chain is longer than the old one.
+    client.reorg(4).await;
+    client.set_last_block_number(5).await;
+    watcher.poll_eth_node().await.unwrap();
+
+    assert_eq!(watcher.eth_state.last_ethereum_block(), 5);
+    assert!(watcher.eth_state.unconfirmed_queue().is_empty());
+    assert!(watcher.eth_state.priority_queue().contains_key(&0));
+    assert_eq!(watcher.eth_state.next_priority_op_id(), 1);
+
+    // Phantom operation is removed from the mempool, while the valid one is retained.
+    let mempool_ops = data.read().await;
+    assert!(!mempool_ops.contains_key(&1));
+    let (_, confirmed) = mempool_ops.get(&0).unwrap();
+    assert!(confirmed);
+
+    // Both the watcher and the storage track the common ancestor and the new block.
+    let expected_blocks = vec![
+        (3, H256::from_low_u64_be(3)),
+        (5, client.block_hash(5).await.unwrap().unwrap()),
+    ];
+    assert_eq!(
+        watcher.processed_blocks.iter().copied().collect::<Vec<_>>(),
+        expected_blocks
+    );
+    assert_eq!(
+        watcher.storage.load_processed_blocks().await.unwrap(),
+        expected_blocks
+    );
+}
+
+/// Checks that the tokens added by the reverted blocks are removed from the storage,
+/// and the ones added by the new chain are received once again.
+#[tokio::test]
+async fn test_reorg_new_tokens() {
+    let mut client = FakeEthClient::new();
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data));
+
+    let token = |id: u32, eth_block_number: u64| NewTokenEvent {
+        eth_block_number,
+        address: Address::from_low_u64_be(id.into()),
+        id: TokenId(id),
+    };
+    let received_tokens = |watcher: &EthWatch<FakeEthClient, FakeStorage>| {
+        watcher
+            .get_new_tokens(None)
+            .into_iter()
+            .map(|token| (token.id, token.eth_block_number))
+            .collect::<Vec<_>>()
+    };
+    client.add_new_tokens(&[token(1, 2), token(2, 4)]).await;
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    client.set_last_block_number(3).await;
+    watcher.poll_eth_node().await.unwrap();
+    client.set_last_block_number(5).await;
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(
+        received_tokens(&watcher),
+        vec![(TokenId(1), 2), (TokenId(2), 4)]
+    );
+
+    // The 4th block is replaced, and the second token is added once again by the later block.
+    client.reorg(4).await;
+    client.add_new_tokens(&[token(2, 5)]).await;
+    client.set_last_block_number(6).await;
+    watcher.poll_eth_node().await.unwrap();
+
+    assert_eq!(
+        *watcher.storage.removed_tokens.read().await,
+        vec![TokenId(2)]
+    );
+    assert_eq!(
+        received_tokens(&watcher),
+        vec![(TokenId(1), 2), (TokenId(2), 5)]
+    );
+}
+
+fn deposit_op(serial_id: SerialId, eth_block: u64) -> PriorityOp {
+    PriorityOp {
+        serial_id,
+        data: ZkSyncPriorityOp::Deposit(Deposit {
+            from: Default::default(),
+            token: TokenId(0),
+            amount: Default::default(),
+            to: [2u8; 20].into(),
+        }),
+        deadline_block: 0,
+        eth_hash: H256::from_low_u64_be(serial_id),
+        eth_block,
+        eth_block_index: Some(serial_id),
+    }
+}
+
+/// Checks that the confirmed deposits of the blacklisted tokens are marked as withdraw-only,
+/// but still added to the mempool.
+#[tokio::test]
+async fn test_withdraw_only_deposits() {
+    let mut client = FakeEthClient::new();
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+
+    let mut blacklisted_deposit = deposit_op(1, 2);
+    if let ZkSyncPriorityOp::Deposit(deposit) = &mut blacklisted_deposit.data {
+        deposit.token = TokenId(1);
+    }
+    // The unconfirmed deposit isn't marked until it's confirmed.
+    let mut unconfirmed_deposit = deposit_op(2, 5);
+    if let ZkSyncPriorityOp::Deposit(deposit) = &mut unconfirmed_deposit.data {
+        deposit.token = TokenId(1);
+    }
+    client
+        .add_operations(&[deposit_op(0, 1), blacklisted_deposit, unconfirmed_deposit])
+        .await;
+    client.set_last_block_number(5).await;
+
+    let mut watcher = create_watcher(client, sender);
+    watcher
+        .storage
+        .token_blacklist
+        .write()
+        .await
+        .insert(TokenId(1));
+    watcher.poll_eth_node().await.unwrap();
+
+    let withdraw_only_deposits: Vec<_> = watcher
+        .storage
+        .withdraw_only_deposits
+        .read()
+        .await
+        .iter()
+        .map(|op| op.serial_id)
+        .collect();
+    assert_eq!(withdraw_only_deposits, vec![1]);
+    let mempool_ops = data.read().await;
+    let (_, confirmed) = mempool_ops.get(&1).unwrap();
+    assert!(confirmed);
+}
+
+/// Checks that after the restart the watcher resumes from the stored priority operation cursor,
+/// even if the downtime was longer than the default restored range.
+#[tokio::test]
+async fn test_restore_from_priority_op_cursor() {
+    let mut client = FakeEthClient::new();
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+    client
+        .add_operations(&[deposit_op(0, 1), deposit_op(1, 4_000)])
+        .await;
+    client.set_last_block_number(40_000).await;
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    // The operation 0 was processed before the restart.
+    watcher
+        .storage
+        .save_priority_op_cursor(PriorityOpCursor {
+            last_processed_block: 2,
+            next_priority_op_id: 1,
+        })
+        .await
+        .unwrap();
+    watcher.restore_state_from_eth(40_000).await.unwrap();
+
+    assert!(watcher.eth_state.priority_queue().contains_key(&1));
+    let mempool_ops = data.read().await;
+    assert!(!mempool_ops.contains_key(&0));
+    let (_, confirmed) = mempool_ops.get(&1).unwrap();
+    assert!(confirmed);
+    assert_eq!(
+        watcher.storage.load_priority_op_cursor().await.unwrap(),
+        Some(PriorityOpCursor {
+            last_processed_block: 39_999,
+            next_priority_op_id: 2,
+        })
+    );
+}
+
+/// Checks that the priority operations are not accepted if the contract has received
+/// more of them than the node returned.
+#[tokio::test]
+async fn test_priority_ops_inconsistency() {
+    let mut client = FakeEthClient::new();
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+    client.add_operations(&[deposit_op(0, 1)]).await;
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    client.set_last_block_number(2).await;
+    watcher.poll_eth_node().await.unwrap();
+    let cursor = watcher.storage.load_priority_op_cursor().await.unwrap();
+    assert_eq!(
+        cursor,
+        Some(PriorityOpCursor {
+            last_processed_block: 1,
+            next_priority_op_id: 1,
+        })
+    );
+
+    // The log of the operation 2 is missing.
+    client.add_operations(&[deposit_op(1, 3)]).await;
+    client.inner.write().await.missing_priority_requests = 1;
+    client.set_last_block_number(5).await;
+    let err = watcher.poll_eth_node().await.unwrap_err();
+    assert!(is_missing_priority_op_error(&err));
+
+    // The blocks range is processed once again on the next poll, the cursor is not moved.
+    assert_eq!(watcher.eth_state.last_ethereum_block(), 2);
+    assert_eq!(
+        watcher.storage.load_priority_op_cursor().await.unwrap(),
+        cursor
+    );
+}
+
+/// Checks that the poll after which the watcher caught up with the node is followed by the poll
+/// with the catch-up interval, and the interval is derived from the block time afterwards.
+#[tokio::test]
+async fn test_poll_schedule() {
+    let mut client = FakeEthClient::new();
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data));
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    let next_poll_delay = watcher.next_poll_delay();
+    // The blocks are produced once a minute, so the idle interval is the maximal one.
+    watcher
+        .polling
+        .observe_block(1, Instant::now() - Duration::from_secs(60));
+    client.set_last_block_number(2).await;
+
+    watcher.poll_eth_node().await.unwrap();
+    watcher.update_poll_schedule();
+    assert_eq!(watcher.eth_state.last_ethereum_block(), 2);
+    let delay = Duration::from_millis(next_poll_delay.load(Ordering::Relaxed));
+    assert!(delay <= Duration::from_millis(110), "{:?}", delay);
+
+    watcher.poll_eth_node().await.unwrap();
+    watcher.update_poll_schedule();
+    let delay = Duration::from_millis(next_poll_delay.load(Ordering::Relaxed));
+    assert!(delay >= Duration::from_millis(4500), "{:?}", delay);
+}
@@ -0,0 +1,213 @@
+//! Leader election between the server replicas running simultaneously.
+//!
+//! Replicas compete for the Postgres advisory lock, and only the one holding it (the leader)
+//! runs the components modifying the state, e.g. the state keeper and `eth_sender`. The others
+//! (standbys) only serve the APIs and keep trying to acquire the lock.
+//!
+//! The lock is bound to the database session, so it's released once the leader process dies or
+//! loses its connection, and one of the standbys takes over. The leader checks the lock
+//! periodically and exits immediately once it's lost, so it doesn't keep acting as the leader.
+
+// Built-in uses
+use std::time::Duration;
+// External uses
+use anyhow::format_err;
+use tokio::time;
+// Workspace uses
+use zksync_storage::StorageProcessor;
+
+/// Lock which can be held by a single replica at a time.
+#[async_trait::async_trait]
+pub trait LeaderLock: Send {
+    /// Tries to acquire the lock without waiting for it.
+    async fn try_acquire(&mut self) -> anyhow::Result<bool>;
+
+    /// Checks whether the lock is still held by this replica.
+    async fn is_held(&mut self) -> anyhow::Result<bool>;
+}
+
+/// Postgres advisory lock held by the dedicated connection.
+///
+/// The dedicated connection is used for the lock rather than the one from the pool, since
+/// the lock is held as long as the connection is open.
+pub struct AdvisoryLock {
+    storage: Option<StorageProcessor<'static>>,
+    lock_id: i64,
+}
+
+impl AdvisoryLock {
+    pub fn new(lock_id: i64) -> Self {
+        Self {
+            storage: None,
+            lock_id,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LeaderLock for AdvisoryLock {
+    async fn try_acquire(&mut self) -> anyhow::Result<bool> {
+        if self.storage.is_none() {
+            self.storage = Some(StorageProcessor::establish_connection().await?);
+        }
+        let storage = self
+            .storage
+            .as_mut()
+            .expect("connection is established above");
+
+        match storage.misc_schema().try_advisory_lock(self.lock_id).await {
+            Ok(locked) => Ok(locked),
+            Err(err) => {
+                // The connection is likely broken, reconnect on the next attempt.
+                self.storage = None;
+                Err(err)
+            }
+        }
+    }
+
+    async fn is_held(&mut self) -> anyhow::Result<bool> {
+        let storage = self
+            .storage
+            .as_mut()
+            .ok_or_else(|| format_err!("There is no connection holding the lock"))?;
+        storage
+            .misc_schema()
+            .holds_advisory_lock(self.lock_id)
+            .await
+    }
+}
+
+/// Held leader lock, the leadership is lost once it's dropped.
+pub struct Leadership<L> {
+    lock: L,
+}
+
+/// Waits until the replica acquires the leader lock.
+pub async fn wait_for_leadership<L: LeaderLock>(
+    mut lock: L,
+    retry_interval: Duration,
+) -> Leadership<L> {
+    let mut standby_reported = false;
+    loop {
+        match lock.try_acquire().await {
+            Ok(true) => {
+                vlog::info!("Leader lock is acquired, running as the leader");
+                return Leadership { lock };
+            }
+            Ok(false) => {
+                if !standby_reported {
+                    vlog::info!("Leader lock is held by another replica, running as a standby");
+                    standby_reported = true;
+                }
+            }
+            Err(err) => vlog::warn!("Failed to acquire the leader lock: {}", err),
+        }
+        metrics::gauge!("leader_election.is_leader", 0.0);
+
+        time::sleep(retry_interval).await;
+    }
+}
+
+/// Checks that the leader lock is still held, resolves once the leadership is lost (or can't be confirmed).
+///
+/// The replica should stop its leader-only components immediately then, without waiting for them
+/// to finish gracefully: the lock may already be acquired by a standby, so both replicas modify
+/// the state until the leader-only components of this one are stopped.
+pub async fn watch_leadership<L: LeaderLock>(
+    mut leadership: Leadership<L>,
+    check_interval: Duration,
+) {
+    let mut timer = time::interval(check_interval);
+    loop {
+        timer.tick().await;
+        metrics::gauge!("leader_election.is_leader", 1.0);
+
+        match leadership.lock.is_held().await {
+            Ok(true) => {}
+            Ok(false) => {
+                vlog::error!("Leader lock is released, the replica is not the leader anymore");
+                break;
+            }
+            Err(err) => {
+                vlog::error!(
+                    "Unable to check the leader lock, the leadership is considered lost: {}",
+                    err
+                );
+                break;
+            }
+        }
+    }
+    metrics::gauge!("leader_election.is_leader", 0.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Lock shared by the replicas, stores the id of the replica holding it.
+    #[derive(Debug, Clone, Default)]
+    struct MockLock {
+        holder: Arc<Mutex<Option<u32>>>,
+        replica: u32,
+    }
+
+    impl MockLock {
+        fn replica(&self, replica: u32) -> Self {
+            Self {
+                holder: self.holder.clone(),
+                replica,
+            }
+        }
+
+        fn set_holder(&self, holder: Option<u32>) {
+            *self.holder.lock().unwrap() = holder;
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl LeaderLock for MockLock {
+        async fn try_acquire(&mut self) -> anyhow::Result<bool> {
+            let mut holder = self.holder.lock().unwrap();
+            if holder.is_none() {
+                *holder = Some(self.replica);
+            }
+            Ok(*holder == Some(self.replica))
+        }
+
+        async fn is_held(&mut self) -> anyhow::Result<bool> {
+            Ok(*self.holder.lock().unwrap() == Some(self.replica))
+        }
+    }
+
+    const INTERVAL: Duration = Duration::from_millis(10);
+    const TIMEOUT: Duration = Duration::from_millis(200);
+
+    #[tokio::test]
+    async fn standby_waits_for_leadership() {
+        let lock = MockLock::default();
+        lock.set_holder(Some(0));
+
+        let mut standby = tokio::spawn(wait_for_leadership(lock.replica(1), INTERVAL));
+        assert!(time::timeout(TIMEOUT, &mut standby).await.is_err());
+
+        // The leader is gone, so the standby takes over.
+        lock.set_holder(None);
+        let leadership = time::timeout(TIMEOUT, standby).await.unwrap().unwrap();
+        assert_eq!(leadership.lock.replica, 1);
+        assert_eq!(*lock.holder.lock().unwrap(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn watcher_detects_lost_leadership() {
+        let lock = MockLock::default();
+        let leadership = wait_for_leadership(lock.replica(0), INTERVAL).await;
+
+        let mut watcher = tokio::spawn(watch_leadership(leadership, INTERVAL));
+        assert!(time::timeout(TIMEOUT, &mut watcher).await.is_err());
+
+        // The lock is released and acquired by another replica.
+        lock.set_holder(Some(1));
+        time::timeout(TIMEOUT, watcher).await.unwrap().unwrap();
+    }
+}
@@ -5,24 +5,34 @@ use crate::{
     eth_watch::start_eth_watch,
     state_keeper::{start_root_hash_calculator, start_state_keeper, ZkSyncStateKeeper},
     token_handler::run_token_handler,
+    token_metadata_validator::run_token_metadata_validator,
+    validator_status_handler::run_validator_status_handler,
 };
-use futures::{channel::mpsc, future};
-use tokio::task::JoinHandle;
-use zksync_config::{ChainConfig, ZkSyncConfig};
+use futures::{channel::mpsc, future, Future};
+use tokio::task::{JoinError, JoinHandle};
+use zksync_config::{ChainConfig, ObjectStoreConfig, ZkSyncConfig};
 use zksync_eth_client::EthereumGateway;
-use zksync_mempool::{run_mempool_block_handler, run_mempool_tx_handler};
+use zksync_mempool::{
+    fee_free_whitelist::FeeFreeWhitelist, pipeline_pauses::PausedStages, run_mempool_block_handler,
+    run_mempool_tx_handler, token_blacklist::TokenBlacklist,
+};
+use zksync_object_store::ArtifactStore;
 use zksync_storage::ConnectionPool;
 use zksync_types::{tokens::get_genesis_token_list, Token, TokenId, TokenKind};
+use zksync_utils::shutdown::ShutdownController;
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 32_768;
 
 pub mod committer;
 pub mod eth_watch;
+pub mod leader_election;
 pub mod register_factory_handler;
 pub mod rejected_tx_cleaner;
 pub mod state_keeper;
 pub mod token_handler;
+pub mod token_metadata_validator;
 pub mod tx_event_emitter;
+pub mod validator_status_handler;
 
 mod genesis;
 mod private_api;
@@ -31,7 +41,10 @@ mod private_api;
 /// Since the main tokio tasks are used as actors which should live as long
 /// as application runs, any possible outcome (either `Ok` or `Err`) is considered
 /// as a reason to stop the server completely.
-pub async fn wait_for_tasks(task_futures: Vec<JoinHandle<()>>) {
+pub async fn wait_for_tasks<T>(task_futures: impl IntoIterator<Item = T>)
+where
+    T: Future<Output = Result<(), JoinError>> + Unpin,
+{
     match future::select_all(task_futures).await {
         (Ok(_), _, _) => {
             panic!("One of the actors finished its run, while it wasn't expected to do it");
@@ -87,11 +100,21 @@ pub async fn genesis_init(config: &ChainConfig) {
 /// - block proposer, module to create block proposals for state keeper.
 /// - committer, module to store pending and completed blocks into the database.
 /// - private Core API server.
+///
+/// On `shutdown` the mempool stops accepting new transactions and the state keeper
+/// persists the pending block before stopping.
+///
+/// The `paused_stages` are expected to be kept updated by the caller. The mempool rejects
+/// the transactions while their acceptance is paused, and the state keeper doesn't execute
+/// the miniblocks while the block sealing is paused. The same goes for the `token_blacklist`.
 pub async fn run_core(
     connection_pool: ConnectionPool,
     read_only_connection_pool: ConnectionPool,
     config: &ZkSyncConfig,
     eth_gateway: EthereumGateway,
+    paused_stages: PausedStages,
+    token_blacklist: TokenBlacklist,
+    shutdown: &ShutdownController,
 ) -> anyhow::Result<Vec<JoinHandle<()>>> {
     let (proposed_blocks_sender, proposed_blocks_receiver) =
         mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
@@ -108,6 +131,11 @@ pub async fn run_core(
         connection_pool.clone(),
         mempool_tx_request_receiver,
         config.chain.state_keeper.block_chunk_sizes.clone(),
+        paused_stages.clone(),
+        token_blacklist,
+        // Only the priority operations are sent by the Ethereum watcher.
+        FeeFreeWhitelist::default(),
+        shutdown.signal(),
     );
 
     // Run health check api for core
@@ -126,6 +154,7 @@ pub async fn run_core(
         &config.contracts,
         &config.eth_watch,
         mempool_tx_request_sender.clone(),
+        connection_pool.clone(),
     )
     .await;
 
@@ -157,14 +186,19 @@ pub async fn run_core(
     let state_keeper_task = start_state_keeper(
         state_keeper,
         config.chain.state_keeper.miniblock_iteration_interval(),
+        paused_stages,
+        shutdown.guard(),
     );
     let root_hash_calculator_task = start_root_hash_calculator(root_hash_calculator);
 
     // Start committer.
+    let artifact_store = ArtifactStore::from_config(&ObjectStoreConfig::from_env())
+        .expect("Failed to create the prover artifacts store");
     let committer_task = run_committer(
         proposed_blocks_receiver,
         connection_pool.clone(),
         config.chain.clone(),
+        artifact_store,
     );
 
     // Start mempool.
@@ -182,6 +216,13 @@ pub async fn run_core(
         eth_watch_req_sender.clone(),
     );
 
+    // Start token metadata validator.
+    let token_metadata_validator_task = run_token_metadata_validator(
+        connection_pool.clone(),
+        eth_gateway.clone(),
+        &config.token_handler,
+    );
+
     // Start token handler.
     let register_factory_task = run_register_factory_handler(
         connection_pool.clone(),
@@ -189,6 +230,14 @@ pub async fn run_core(
         config.token_handler.clone(),
     );
 
+    // Start validator status handler.
+    let validator_status_task = run_validator_status_handler(
+        connection_pool.clone(),
+        eth_watch_req_sender.clone(),
+        config.eth_watch.clone(),
+        config.eth_sender.sender.operator_commit_eth_addr,
+    );
+
     let tx_event_emitter_task = tx_event_emitter::run_tx_event_emitter_task(
         connection_pool.clone(),
         processed_tx_events_receiver,
@@ -200,7 +249,9 @@ pub async fn run_core(
         root_hash_calculator_task,
         committer_task,
         token_handler_task,
+        token_metadata_validator_task,
         register_factory_task,
+        validator_status_task,
         tx_event_emitter_task,
         mempool_block_handler_task,
         mempool_tx_handler_task,
@@ -53,47 +53,6 @@ impl NFTFactoryHandler {
         receiver.await.expect("Err response from eth watch")
     }
 
-    async fn save_register_factory(
-        &self,
-        storage: &mut StorageProcessor<'_>,
-        register_nft_factory_events: Vec<RegisterNFTFactoryEvent>,
-    ) -> anyhow::Result<()> {
-        let mut transaction = storage.start_transaction().await?;
-
-        let factories = {
-            let mut factories = vec![];
-            let mut account_schema = transaction.chain().account_schema();
-            for factory in register_nft_factory_events {
-                // If account does not exists skip factory
-                if let Some(account_id) = account_schema
-                    .account_id_by_address(factory.creator_address)
-                    .await?
-                {
-                    factories.push((account_id, factory))
-                } else {
-                    vlog::warn!(
-                        "Cant register factory, creator {:?} does not exist",
-                        &factory.creator_address
-                    )
-                }
-            }
-            factories
-        };
-
-        let mut token_schema = transaction.tokens_schema();
-        for (account_id, nft_factory) in factories {
-            token_schema
-                .store_nft_factory(
-                    account_id,
-                    nft_factory.creator_address,
-                    nft_factory.factory_address,
-                )
-                .await?
-        }
-        transaction.commit().await?;
-        Ok(())
-    }
-
     async fn run(&mut self) {
         let mut timer = tokio::time::interval(self.poll_interval);
         loop {
@@ -113,13 +72,54 @@ impl NFTFactoryHandler {
                 .await
                 .expect("db connection failed for token handler");
 
-            self.save_register_factory(&mut storage, register_nft_factory_events)
+            save_register_factory(&mut storage, register_nft_factory_events)
                 .await
                 .expect("failed to add register tokens to the database");
         }
     }
 }
 
+/// Stores the factories registered for the existing accounts.
+pub(crate) async fn save_register_factory(
+    storage: &mut StorageProcessor<'_>,
+    register_nft_factory_events: Vec<RegisterNFTFactoryEvent>,
+) -> anyhow::Result<()> {
+    let mut transaction = storage.start_transaction().await?;
+
+    let factories = {
+        let mut factories = vec![];
+        let mut account_schema = transaction.chain().account_schema();
+        for factory in register_nft_factory_events {
+            // If account does not exists skip factory
+            if let Some(account_id) = account_schema
+                .account_id_by_address(factory.creator_address)
+                .await?
+            {
+                factories.push((account_id, factory))
+            } else {
+                vlog::warn!(
+                    "Cant register factory, creator {:?} does not exist",
+                    &factory.creator_address
+                )
+            }
+        }
+        factories
+    };
+
+    let mut token_schema = transaction.tokens_schema();
+    for (account_id, nft_factory) in factories {
+        token_schema
+            .store_nft_factory(
+                account_id,
+                nft_factory.creator_address,
+                nft_factory.factory_address,
+            )
+            .await?
+    }
+    transaction.commit().await?;
+    Ok(())
+}
+
 #[must_use]
 pub fn run_register_factory_handler(
     db_pool: ConnectionPool,
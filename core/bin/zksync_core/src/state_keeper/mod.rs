@@ -10,7 +10,10 @@ use futures::{
 use tokio::task::JoinHandle;
 use tokio::time;
 // Workspace uses
-use zksync_mempool::{GetBlockRequest, MempoolBlocksRequest, ProposedBlock};
+use zksync_mempool::{
+    pipeline_pauses::PausedStages, GetBlockRequest, MempoolBlocksRequest, ProposedBlock,
+};
+use zksync_prometheus_exporter::labels::{report_block_processed, report_op_processed};
 use zksync_state::state::{OpSuccess, ZkSyncState};
 use zksync_types::tx::TxHash;
 use zksync_types::{
@@ -20,9 +23,11 @@ use zksync_types::{
     },
     gas_counter::GasCounter,
     mempool::SignedTxVariant,
+    pipeline::PipelineStage,
     tx::ZkSyncTx,
     Address, PriorityOp, SignedZkSyncTx,
 };
+use zksync_utils::shutdown::ShutdownGuard;
 // Local uses
 use self::{
     pending_block::PendingBlock,
@@ -276,16 +281,36 @@ impl ZkSyncStateKeeper {
     }
 
     // Generate and execute new miniblock every miniblock_interval
-    async fn run(mut self, miniblock_interval: Duration) {
+    //
+    // On shutdown the pending block is not sealed: sealing it early would only produce a smaller block,
+    // while the restarted state keeper continues the same pending block (see `initialize`).
+    async fn run(
+        mut self,
+        miniblock_interval: Duration,
+        paused_stages: PausedStages,
+        mut shutdown: ShutdownGuard,
+    ) {
         let mut timer = time::interval(miniblock_interval);
         loop {
             let start = Instant::now();
-            timer.tick().await;
+            // The shutdown is checked only between the miniblocks, so the executed operations
+            // are always persisted as a part of the pending block.
+            tokio::select! {
+                _ = timer.tick() => {},
+                _ = shutdown.triggered() => break,
+            }
             // Report timings between two miniblocks.
             // If reported value stays at 0, most likely we have `miniblock_interval` variable too small and
             // spend more time in the loop iteration than this interval.
             metrics::histogram!("state_keeper.miniblock_interval", start.elapsed());
 
+            // Any miniblock may seal the pending block, so none of them is executed while
+            // the sealing is paused, even if it contains only the priority operations.
+            // The transactions and the priority operations are kept in the mempool meanwhile.
+            if paused_stages.is_paused(PipelineStage::BlockSealing) {
+                continue;
+            }
+
             let start = Instant::now();
             // `.throttle()` method will postpone the next miniblock iteration if currently we have too
             // many blocks for which root hash is not yet calculated.
@@ -298,6 +323,25 @@ impl ZkSyncStateKeeper {
 
             self.execute_proposed_block(proposed_block).await;
         }
+
+        // Flushing is enough to persist the pending block: every non-empty miniblock has already sent it
+        // to the committer, either sealed or as the pending block. Only the iterations of the empty miniblocks
+        // are not stored, so after the restart the block is sealed at most that many iterations later.
+        self.flush_commitments().await;
+        vlog::info!(
+            "State keeper is stopped, the executed operations of the block #{} are persisted",
+            *self.pending_block.number
+        );
+    }
+
+    /// Waits until the committer persists all the blocks sent to it.
+    async fn flush_commitments(&mut self) {
+        let (sender, receiver) = oneshot::channel();
+        self.tx_for_commitments
+            .send(CommitRequest::Flush(sender))
+            .await
+            .expect("committer receiver dropped");
+        receiver.await.expect("committer dropped the flush request");
     }
 
     async fn propose_new_block(&mut self, block_timestamp: u64) -> ProposedBlock {
@@ -421,8 +465,24 @@ impl ZkSyncStateKeeper {
             }
         }
 
+        let underpriced_txs = proposed_block.underpriced_txs;
         let mut tx_queue = proposed_block.txs.into_iter().collect::<VecDeque<_>>();
         while let Some(variant) = tx_queue.pop_front() {
+            if variant
+                .hashes()
+                .iter()
+                .any(|tx_hash| underpriced_txs.contains(tx_hash))
+            {
+                let batch_id = match &variant {
+                    SignedTxVariant::Tx(_) => None,
+                    SignedTxVariant::Batch(batch) => Some(batch.batch_id),
+                };
+                executed_ops.append(
+                    &mut self.reject_underpriced_txs(&variant.get_transactions(), batch_id),
+                );
+                continue;
+            }
+
             match &variant {
                 SignedTxVariant::Tx(tx) => {
                     match self.apply_tx(tx) {
@@ -547,6 +607,37 @@ impl ZkSyncStateKeeper {
         ApplyOutcome::Included(exec_result)
     }
 
+    /// Rejects the transactions whose fee doesn't cover their cost at the current fee token price.
+    fn reject_underpriced_txs(
+        &mut self,
+        txs: &[SignedZkSyncTx],
+        batch_id: Option<i64>,
+    ) -> Vec<ExecutedOperations> {
+        let fail_reason = "Fee is too low at the current price of the fee token".to_string();
+        let labels = vec![
+            ("stage", "state".to_string()),
+            ("error", fail_reason.clone()),
+        ];
+
+        txs.iter()
+            .map(|tx| {
+                vlog::warn!("Failed to execute transaction: {:?}, {}", tx, fail_reason);
+                metrics::increment_counter!("rejected_txs", &labels);
+                let failed_tx = ExecutedTx {
+                    signed_tx: tx.clone(),
+                    success: false,
+                    op: None,
+                    fail_reason: Some(fail_reason.clone()),
+                    block_index: None,
+                    created_at: chrono::Utc::now(),
+                    batch_id,
+                };
+                self.pending_block.failed_txs.push(failed_tx.clone());
+                ExecutedOperations::Tx(Box::new(failed_tx))
+            })
+            .collect()
+    }
+
     fn apply_batch(
         &mut self,
         txs: &[SignedZkSyncTx],
@@ -554,6 +645,12 @@ impl ZkSyncStateKeeper {
     ) -> ApplyOutcome<Vec<ExecutedOperations>> {
         metrics::gauge!("tx_batch_size", txs.len() as f64);
         let start = Instant::now();
+        let tx_hashes: Vec<_> = txs.iter().map(|tx| tx.hash()).collect();
+        let _span = vlog::info_span!(
+            "apply_batch",
+            batch_hash = %TxHash::batch_hash(&tx_hashes).to_string()
+        )
+        .entered();
 
         let chunks_needed = self.state.chunks_for_batch(txs);
 
@@ -666,6 +763,7 @@ impl ZkSyncStateKeeper {
 
     fn apply_tx(&mut self, tx: &SignedZkSyncTx) -> ApplyOutcome<ExecutedOperations> {
         let start = Instant::now();
+        let _span = vlog::info_span!("apply_tx", tx_hash = %tx.hash().to_string()).entered();
         let chunks_needed = self.state.chunks_for_tx(tx);
 
         // If we can't add the tx to the block due to the size limit, we return this tx,
@@ -730,6 +828,10 @@ impl ZkSyncStateKeeper {
                     fee,
                     exec_result.clone(),
                 );
+                vlog::debug!(
+                    "Transaction is executed in the block {}",
+                    self.pending_block.number
+                );
 
                 exec_result
             }
@@ -819,18 +921,14 @@ impl ZkSyncStateKeeper {
         };
 
         for tx in &block.block_transactions {
-            let labels = vec![
-                ("stage", "seal_block".to_string()),
-                ("name", tx.variance_name()),
-                ("token", tx.token_id().to_string()),
-            ];
-            metrics::histogram!("process_tx", tx.elapsed(), &labels);
+            report_op_processed(
+                "seal_block",
+                tx.variance_name(),
+                tx.token_id(),
+                tx.elapsed(),
+            );
         }
-        metrics::histogram!(
-            "process_block",
-            block.elapsed(),
-            "stage" => "seal"
-        );
+        report_block_processed("seal", *block.block_number, block.elapsed());
 
         let block_commit_request = BlockCommitRequest {
             block,
@@ -904,7 +1002,13 @@ impl ZkSyncStateKeeper {
     }
 }
 
+/// Starts the state keeper, which stops once the `shutdown` is triggered and the pending block is persisted.
 #[must_use]
-pub fn start_state_keeper(sk: ZkSyncStateKeeper, miniblock_interval: Duration) -> JoinHandle<()> {
-    tokio::spawn(sk.run(miniblock_interval))
+pub fn start_state_keeper(
+    sk: ZkSyncStateKeeper,
+    miniblock_interval: Duration,
+    paused_stages: PausedStages,
+    shutdown: ShutdownGuard,
+) -> JoinHandle<()> {
+    tokio::spawn(sk.run(miniblock_interval, paused_stages, shutdown))
 }
@@ -117,6 +117,7 @@ async fn small() {
             SignedTxVariant::Tx(bad_withdraw),
         ],
         priority_ops: vec![deposit],
+        underpriced_txs: Default::default(),
     };
     let pending_block_iteration = tester.state_keeper.pending_block.pending_block_iteration;
     tester
@@ -159,6 +160,7 @@ async fn few_chunks() {
             SignedTxVariant::Tx(bad_withdraw),
         ],
         priority_ops: vec![deposit],
+        underpriced_txs: Default::default(),
     };
     tester
         .state_keeper
@@ -196,6 +198,7 @@ async fn few_iterations() {
             SignedTxVariant::Tx(bad_withdraw),
         ],
         priority_ops: vec![deposit],
+        underpriced_txs: Default::default(),
     };
     tester
         .state_keeper
@@ -225,6 +228,7 @@ async fn fast_withdrawal() {
     let proposed_block = ProposedBlock {
         priority_ops: Vec::new(),
         txs: vec![withdraw.into()],
+        underpriced_txs: Default::default(),
     };
 
     tester
@@ -250,6 +254,7 @@ async fn pending_block_updates() {
     let proposed_block = ProposedBlock {
         txs: vec![],
         priority_ops: vec![],
+        underpriced_txs: Default::default(),
     };
 
     tester
@@ -278,6 +283,7 @@ async fn pending_block_updates() {
     let proposed_block = ProposedBlock {
         txs: vec![SignedTxVariant::Tx(bad_withdraw)],
         priority_ops: vec![],
+        underpriced_txs: Default::default(),
     };
 
     tester
@@ -306,6 +312,7 @@ async fn pending_block_updates() {
     let proposed_block = ProposedBlock {
         txs: vec![SignedTxVariant::Tx(good_withdraw)],
         priority_ops: vec![],
+        underpriced_txs: Default::default(),
     };
 
     let pending_block_iteration = tester.state_keeper.pending_block.pending_block_iteration;
@@ -335,6 +342,7 @@ async fn pending_block_updates() {
     let proposed_block = ProposedBlock {
         txs: vec![SignedTxVariant::Tx(bad_withdraw)],
         priority_ops: vec![],
+        underpriced_txs: Default::default(),
     };
 
     let pending_block_iteration = tester.state_keeper.pending_block.pending_block_iteration;
@@ -356,6 +364,7 @@ async fn pending_block_updates() {
     let proposed_block = ProposedBlock {
         txs: vec![],
         priority_ops: vec![],
+        underpriced_txs: Default::default(),
     };
 
     let pending_block_iteration = tester.state_keeper.pending_block.pending_block_iteration;
@@ -400,6 +409,7 @@ async fn pending_block_diff() {
             SignedTxVariant::Tx(bad_withdraw_1.clone()),
         ],
         priority_ops: vec![],
+        underpriced_txs: Default::default(),
     };
 
     let good_withdraw_2 = create_account_and_withdrawal(
@@ -424,6 +434,7 @@ async fn pending_block_diff() {
             SignedTxVariant::Tx(bad_withdraw_2.clone()),
         ],
         priority_ops: vec![],
+        underpriced_txs: Default::default(),
     };
 
     tester
@@ -533,6 +544,7 @@ async fn transfers_with_different_timestamps() {
             SignedTxVariant::Tx(correct_transfer.clone()),
         ],
         priority_ops: vec![],
+        underpriced_txs: Default::default(),
     };
 
     tester
@@ -565,3 +577,57 @@ async fn transfers_with_different_timestamps() {
         })
         .await;
 }
+
+/// Checks that the underpriced transactions are rejected without the execution.
+#[tokio::test]
+async fn underpriced_txs() {
+    let mut tester = StateKeeperTester::new(20, 3, 3);
+    let withdraw = create_account_and_withdrawal(
+        &mut tester,
+        TokenId(0),
+        AccountId(1),
+        200u32,
+        145u32,
+        Default::default(),
+    );
+    let underpriced_withdraw = create_account_and_withdrawal(
+        &mut tester,
+        TokenId(0),
+        AccountId(2),
+        200u32,
+        145u32,
+        Default::default(),
+    );
+    let proposed_block = ProposedBlock {
+        txs: vec![
+            SignedTxVariant::Tx(withdraw.clone()),
+            SignedTxVariant::Tx(underpriced_withdraw.clone()),
+        ],
+        priority_ops: vec![],
+        underpriced_txs: vec![underpriced_withdraw.hash()].into_iter().collect(),
+    };
+    tester
+        .state_keeper
+        .execute_proposed_block(proposed_block)
+        .await;
+    tester
+        .assert_pending_with(|block| {
+            assert_eq!(block.success_operations.len(), 1);
+            assert_eq!(
+                block.success_operations[0]
+                    .get_executed_tx()
+                    .unwrap()
+                    .signed_tx
+                    .hash(),
+                withdraw.hash()
+            );
+
+            assert_eq!(block.failed_txs.len(), 1);
+            assert_eq!(
+                block.failed_txs[0].signed_tx.hash(),
+                underpriced_withdraw.hash()
+            );
+            assert!(block.failed_txs[0].fail_reason.is_some());
+        })
+        .await;
+}
@@ -7,6 +7,7 @@ mod apply_tx;
 mod execute_proposed_block;
 mod gas_limit;
 mod pending_block;
+mod pipeline_pauses;
 mod utils;
 
 /// Checks that StateKeeper will panic with incorrect initialization data
@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use futures::stream::StreamExt;
+use tokio::time;
+use zksync_mempool::{pipeline_pauses::PausedStages, MempoolBlocksRequest, ProposedBlock};
+use zksync_types::{pipeline::PipelineStage, TokenId};
+use zksync_utils::shutdown::ShutdownController;
+
+use super::utils::*;
+use crate::state_keeper::{start_state_keeper, CommitRequest};
+
+const MINIBLOCK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Checks that no miniblocks are proposed while the block sealing is paused,
+/// so the priority operations are not executed either.
+#[tokio::test]
+async fn block_sealing_pause_stops_miniblocks() {
+    let tester = StateKeeperTester::new(8, 3, 3);
+    let StateKeeperTester {
+        state_keeper,
+        mut response_rx,
+        mut request_rx,
+        ..
+    } = tester;
+
+    let sealing_paused: HashSet<_> = vec![PipelineStage::BlockSealing].into_iter().collect();
+    let paused_stages = PausedStages::default();
+    paused_stages.update(sealing_paused.clone());
+    let shutdown = ShutdownController::new();
+    let state_keeper_task = start_state_keeper(
+        state_keeper,
+        MINIBLOCK_INTERVAL,
+        paused_stages.clone(),
+        shutdown.guard(),
+    );
+
+    time::sleep(MINIBLOCK_INTERVAL * 10).await;
+    assert!(
+        request_rx.try_next().is_err(),
+        "A miniblock was proposed while the block sealing is paused"
+    );
+
+    paused_stages.update(HashSet::new());
+    let request = time::timeout(Duration::from_secs(5), request_rx.next())
+        .await
+        .expect("No miniblock was proposed after the block sealing is resumed");
+    let request = match request {
+        Some(MempoolBlocksRequest::GetBlock(request)) => request,
+        None => panic!("Mempool requests channel is closed"),
+    };
+    // Pausing again before responding, so the next miniblocks are not proposed.
+    paused_stages.update(sealing_paused);
+    request
+        .response_sender
+        .send(ProposedBlock {
+            txs: Vec::new(),
+            priority_ops: vec![create_deposit(TokenId(0), 145u32)],
+            underpriced_txs: Default::default(),
+        })
+        .unwrap();
+    assert!(
+        matches!(
+            response_rx.next().await,
+            Some(CommitRequest::PendingBlock(_))
+        ),
+        "Expected the deposit to be executed as a part of the pending block"
+    );
+
+    let shutdown_task = tokio::spawn(shutdown.shutdown(Duration::from_secs(5)));
+    match response_rx.next().await {
+        Some(CommitRequest::Flush(sender)) => sender.send(()).unwrap(),
+        _ => panic!("Expected the state keeper to flush the commitments on shutdown"),
+    }
+    assert!(shutdown_task.await.unwrap());
+    state_keeper_task.await.unwrap();
+    // The channel is closed once the state keeper is stopped, with no miniblocks proposed meanwhile.
+    assert!(matches!(request_rx.try_next(), Ok(None)));
+}
@@ -8,7 +8,7 @@ use zksync_crypto::{
     rand::{Rng, SeedableRng, XorShiftRng},
     PrivateKey,
 };
-use zksync_mempool::ProposedBlock;
+use zksync_mempool::{MempoolBlocksRequest, ProposedBlock};
 use zksync_types::block::{IncompleteBlock, PendingBlock};
 use zksync_types::tx::TimeRange;
 use zksync_types::{
@@ -18,6 +18,7 @@ use zksync_types::{
 pub struct StateKeeperTester {
     pub state_keeper: ZkSyncStateKeeper,
     pub response_rx: mpsc::Receiver<CommitRequest>,
+    pub request_rx: mpsc::Receiver<MempoolBlocksRequest>,
     pub fee_collector: AccountId,
 }
 
@@ -25,7 +26,7 @@ impl StateKeeperTester {
     pub fn new(available_chunk_size: usize, max_iterations: usize, fast_iterations: usize) -> Self {
         const CHANNEL_SIZE: usize = 32768;
         let (events_sender, _events_receiver) = mpsc::channel(CHANNEL_SIZE);
-        let (request_tx, _request_rx) = mpsc::channel(CHANNEL_SIZE);
+        let (request_tx, request_rx) = mpsc::channel(CHANNEL_SIZE);
         let (response_tx, response_rx) = mpsc::channel(CHANNEL_SIZE);
 
         let fee_collector = Account::default_with_address(&H160::random());
@@ -49,6 +50,7 @@ impl StateKeeperTester {
         Self {
             state_keeper,
             response_rx,
+            request_rx,
             fee_collector: AccountId(0),
         }
     }
@@ -312,6 +314,7 @@ pub async fn apply_single_transfer(tester: &mut StateKeeperTester) {
     let proposed_block = ProposedBlock {
         txs: vec![SignedTxVariant::Tx(transfer)],
         priority_ops: Vec::new(),
+        underpriced_txs: Default::default(),
     };
     tester
         .state_keeper
@@ -331,6 +334,7 @@ pub async fn apply_batch_with_two_transfers(tester: &mut StateKeeperTester) {
             eth_signatures: Vec::new(),
         })],
         priority_ops: Vec::new(),
+        underpriced_txs: Default::default(),
     };
     tester
         .state_keeper
@@ -4,6 +4,8 @@
 //! To set the name and the decimals parameter for the token, a match is searched for with the
 //! token list (which is taken from the environment). If the token address is not found in the
 //! trusted token list, then the default values are used (name = "ERC20-{id}", decimals = 18).
+//! Several tokens can share a symbol, in this case they are distinguished by the display symbol
+//! generated by the storage.
 
 // Built-in deps
 use std::collections::HashMap;
@@ -33,7 +35,6 @@ struct TokenHandler {
     eth_watcher_req: mpsc::Sender<EthWatchRequest>,
     eth_client: EthereumGateway,
     token_list: HashMap<Address, TokenInfo>,
-    last_eth_block: Option<u64>,
     notifier: Option<Notifier>,
 }
 
@@ -60,17 +61,19 @@ impl TokenHandler {
             token_list,
             poll_interval,
             notifier,
-            last_eth_block: None, // TODO: Maybe load last viewed Ethereum block number for TokenHandler from DB (ZKS-518).
             eth_watcher_req,
         }
     }
 
     async fn load_new_token_events(&self) -> Vec<NewTokenEvent> {
         let (sender, receiver) = oneshot::channel();
+        // All the events are requested: the tokens re-added after the chain reorganization
+        // may have lower block numbers than the reverted ones.
+        // The tokens which are already stored are skipped by their ids.
         self.eth_watcher_req
             .clone()
             .send(EthWatchRequest::GetNewTokens {
-                last_eth_block: self.last_eth_block,
+                last_eth_block: None,
                 resp: sender,
             })
             .await
@@ -79,124 +82,6 @@ impl TokenHandler {
         receiver.await.expect("Err response from eth watch")
     }
 
-    async fn is_contract_erc20(&self, address: Address) -> bool {
-        self.eth_client
-            .call_contract_function::<U256, _, _, _>(
-                "balanceOf",
-                address,
-                None,
-                Options::default(),
-                None,
-                address,
-                erc20_contract(),
-            )
-            .await
-            .is_ok()
-    }
-
-    async fn save_new_tokens(
-        &self,
-        storage: &mut StorageProcessor<'_>,
-        tokens: Vec<NewTokenEvent>,
-    ) -> anyhow::Result<Vec<Token>> {
-        let mut transaction = storage.start_transaction().await?;
-        let mut token_schema = transaction.tokens_schema();
-
-        let last_token_id = TokenId(token_schema.get_max_token_id().await?);
-        let mut new_tokens = Vec::new();
-
-        for token_event in tokens {
-            if token_event.id.0 <= last_token_id.0 {
-                continue;
-            }
-
-            // Find a token in the list of trusted tokens
-            // or use default values (name = "ERC20-{id}", decimals = 18).
-            let default_symbol = format!("ERC20-{}", token_event.id);
-            let default_decimals = 18;
-
-            let is_erc20 = self.is_contract_erc20(token_event.address).await;
-            let token_kind = if is_erc20 {
-                TokenKind::ERC20
-            } else {
-                TokenKind::None
-            };
-
-            let token_from_list = {
-                let token_info = self.token_list.get(&token_event.address).cloned();
-
-                if let Some(token_info) = token_info {
-                    Some(Token::new(
-                        token_event.id,
-                        token_info.address,
-                        &token_info.symbol,
-                        token_info.decimals,
-                        token_kind,
-                    ))
-                } else {
-                    None
-                }
-            };
-
-            let token = match token_from_list {
-                Some(token_from_list) => {
-                    let try_insert_token = token_schema.store_token(token_from_list.clone()).await;
-
-                    match try_insert_token {
-                        Ok(..) => token_from_list,
-                        Err(StoreTokenError::TokenAlreadyExistsError(..)) => {
-                            // If a token with such parameters already exists in the database
-                            // then try insert token with other symbol.
-                            let token = Token::new(
-                                token_from_list.id,
-                                token_from_list.address,
-                                &default_symbol,
-                                token_from_list.decimals,
-                                token_kind,
-                            );
-                            let try_insert_token = token_schema.store_token(token.clone()).await;
-                            match try_insert_token {
-                                Ok(..) => (),
-                                Err(StoreTokenError::Other(anyhow_err)) => return Err(anyhow_err),
-                                Err(StoreTokenError::TokenAlreadyExistsError(err)) => {
-                                    vlog::warn!("failed to store token in database: {}", err)
-                                }
-                            }
-
-                            token
-                        }
-                        Err(StoreTokenError::Other(anyhow_err)) => return Err(anyhow_err),
-                    }
-                }
-                None => {
-                    // Token with default parameters.
-                    let token = Token::new(
-                        token_event.id,
-                        token_event.address,
-                        &default_symbol,
-                        default_decimals,
-                        token_kind,
-                    );
-                    let try_insert_token = token_schema.store_token(token.clone()).await;
-                    match try_insert_token {
-                        Ok(..) => (),
-                        Err(StoreTokenError::Other(anyhow_err)) => return Err(anyhow_err),
-                        Err(StoreTokenError::TokenAlreadyExistsError(err)) => {
-                            vlog::warn!("failed to store token in database: {}", err)
-                        }
-                    }
-
-                    token
-                }
-            };
-
-            new_tokens.push(token);
-        }
-
-        transaction.commit().await?;
-        Ok(new_tokens)
-    }
-
     async fn run(&mut self) {
         let mut timer = tokio::time::interval(self.poll_interval);
         loop {
@@ -204,23 +89,20 @@ impl TokenHandler {
 
             let new_tokens_events = self.load_new_token_events().await;
 
-            // Ether is a standard token, so we can assume that at least the last token ID is zero.
-            self.last_eth_block = new_tokens_events
-                .iter()
-                .map(|token| token.eth_block_number)
-                .max()
-                .or(self.last_eth_block);
-
             let mut storage = self
                 .connection_pool
                 .access_storage()
                 .await
                 .expect("db connection failed for token handler");
 
-            let new_tokens = self
-                .save_new_tokens(&mut storage, new_tokens_events)
-                .await
-                .expect("failed to add tokens to the database");
+            let new_tokens = save_new_tokens(
+                &mut storage,
+                &self.eth_client,
+                &self.token_list,
+                new_tokens_events,
+            )
+            .await
+            .expect("failed to add tokens to the database");
 
             // Send a notification that the token has been successfully added to the database.
             if let Some(notifier) = &self.notifier {
@@ -237,6 +119,94 @@ impl TokenHandler {
     }
 }
 
+async fn is_contract_erc20(eth_client: &EthereumGateway, address: Address) -> bool {
+    eth_client
+        .call_contract_function::<U256, _, _, _>(
+            "balanceOf",
+            address,
+            None,
+            Options::default(),
+            None,
+            address,
+            erc20_contract(),
+        )
+        .await
+        .is_ok()
+}
+
+/// Stores the tokens added to the contract, skipping the ones that are already stored.
+/// Returns the stored tokens.
+pub(crate) async fn save_new_tokens(
+    storage: &mut StorageProcessor<'_>,
+    eth_client: &EthereumGateway,
+    token_list: &HashMap<Address, TokenInfo>,
+    tokens: Vec<NewTokenEvent>,
+) -> anyhow::Result<Vec<Token>> {
+    let mut transaction = storage.start_transaction().await?;
+    let mut token_schema = transaction.tokens_schema();
+
+    let last_token_id = TokenId(token_schema.get_max_token_id().await?);
+    let mut new_tokens = Vec::new();
+
+    for token_event in tokens {
+        if token_event.id.0 <= last_token_id.0 {
+            continue;
+        }
+
+        // Find a token in the list of trusted tokens
+        // or use default values (name = "ERC20-{id}", decimals = 18).
+        let default_symbol = format!("ERC20-{}", token_event.id);
+        let default_decimals = 18;
+
+        let is_erc20 = is_contract_erc20(eth_client, token_event.address).await;
+        let token_kind = if is_erc20 {
+            TokenKind::ERC20
+        } else {
+            TokenKind::None
+        };
+
+        let token_from_list = {
+            let token_info = token_list.get(&token_event.address).cloned();
+
+            if let Some(token_info) = token_info {
+                Some(Token::new(
+                    token_event.id,
+                    token_info.address,
+                    &token_info.symbol,
+                    token_info.decimals,
+                    token_kind,
+                ))
+            } else {
+                None
+            }
+        };
+
+        // Tokens can share a symbol, the storage makes the display symbol unique.
+        let token = token_from_list.unwrap_or_else(|| {
+            // Token with default parameters.
+            Token::new(
+                token_event.id,
+                token_event.address,
+                &default_symbol,
+                default_decimals,
+                token_kind,
+            )
+        });
+        match token_schema.store_token(token.clone()).await {
+            Ok(..) => (),
+            Err(StoreTokenError::Other(anyhow_err)) => return Err(anyhow_err),
+            Err(StoreTokenError::TokenAlreadyExistsError(err)) => {
+                vlog::warn!("failed to store token in database: {}", err)
+            }
+        }
+
+        new_tokens.push(token);
+    }
+
+    transaction.commit().await?;
+    Ok(new_tokens)
+}
+
 #[must_use]
 pub fn run_token_handler(
     db_pool: ConnectionPool,
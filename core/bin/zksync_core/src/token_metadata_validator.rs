@@ -0,0 +1,315 @@
+//! Token metadata validator periodically re-queries the `decimals` and `symbol` of the listed
+//! ERC20 tokens on L1 and compares them with the stored ones.
+//!
+//! A wrong decimals value silently corrupts every displayed amount of the token, so the mismatches
+//! are reported and, if enabled in the config, the stored decimals are fixed. Symbols are never
+//! changed automatically, since they are used as unique token identifiers by the API.
+
+// Built-in deps
+use std::collections::HashMap;
+use std::time::Duration;
+// External uses
+use tokio::task::JoinHandle;
+use web3::contract::Options;
+// Workspace uses
+use zksync_config::TokenHandlerConfig;
+use zksync_contracts::erc20_metadata_contract;
+use zksync_eth_client::EthereumGateway;
+use zksync_notifier::Notifier;
+use zksync_storage::ConnectionPool;
+use zksync_types::{tokens::Token, TokenId, TokenKind, U256};
+
+/// Difference between the stored token metadata and the one returned by the token contract.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenMetadataMismatch {
+    Decimals { stored: u8, actual: u8 },
+    Symbol { stored: String, actual: String },
+}
+
+impl std::fmt::Display for TokenMetadataMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Decimals { stored, actual } => write!(
+                f,
+                "stored decimals = {}, decimals on L1 = {}",
+                stored, actual
+            ),
+            Self::Symbol { stored, actual } => {
+                write!(f, "stored symbol = {}, symbol on L1 = {}", stored, actual)
+            }
+        }
+    }
+}
+
+/// Metadata of the token returned by its contract.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenMetadata {
+    pub decimals: u8,
+    /// Not every token implements `symbol` as a `string` (e.g. MKR returns `bytes32`),
+    /// so it's `None` if the symbol couldn't be obtained.
+    pub symbol: Option<String>,
+}
+
+/// Compares the stored token metadata with the one from L1.
+///
+/// The symbol is not checked for the tokens that are missing in the trusted token list,
+/// since the default "ERC20-{id}" symbol is assigned to them on purpose.
+pub fn check_metadata(token: &Token, actual: &TokenMetadata) -> Vec<TokenMetadataMismatch> {
+    let mut mismatches = Vec::new();
+    if token.decimals != actual.decimals {
+        mismatches.push(TokenMetadataMismatch::Decimals {
+            stored: token.decimals,
+            actual: actual.decimals,
+        });
+    }
+
+    let default_symbol = format!("ERC20-{}", token.id);
+    if let Some(symbol) = &actual.symbol {
+        if token.symbol != default_symbol && &token.symbol != symbol {
+            mismatches.push(TokenMetadataMismatch::Symbol {
+                stored: token.symbol.clone(),
+                actual: symbol.clone(),
+            });
+        }
+    }
+    mismatches
+}
+
+struct TokenMetadataValidator {
+    connection_pool: ConnectionPool,
+    eth_client: EthereumGateway,
+    check_interval: Duration,
+    fix_decimals: bool,
+    notifier: Option<Notifier>,
+    /// Mismatches that were already reported, so they aren't reported on every check.
+    reported: HashMap<TokenId, Vec<TokenMetadataMismatch>>,
+}
+
+impl TokenMetadataValidator {
+    fn new(
+        connection_pool: ConnectionPool,
+        eth_client: EthereumGateway,
+        config: TokenHandlerConfig,
+    ) -> Self {
+        let webhook_url = reqwest::Url::parse(&config.webhook_url).ok();
+        let notifier = webhook_url.map(Notifier::with_mattermost);
+
+        Self {
+            connection_pool,
+            eth_client,
+            check_interval: config.metadata_check_interval(),
+            fix_decimals: config.fix_token_decimals,
+            notifier,
+            reported: HashMap::new(),
+        }
+    }
+
+    async fn load_metadata(&self, token: &Token) -> anyhow::Result<TokenMetadata> {
+        let decimals: U256 = self
+            .eth_client
+            .call_contract_function(
+                "decimals",
+                (),
+                None,
+                Options::default(),
+                None,
+                token.address,
+                erc20_metadata_contract(),
+            )
+            .await?;
+        anyhow::ensure!(
+            decimals <= U256::from(u8::MAX),
+            "Invalid decimals {} of the token {}",
+            decimals,
+            token.symbol
+        );
+
+        let symbol = self
+            .eth_client
+            .call_contract_function::<String, _, _, _>(
+                "symbol",
+                (),
+                None,
+                Options::default(),
+                None,
+                token.address,
+                erc20_metadata_contract(),
+            )
+            .await
+            .ok();
+
+        Ok(TokenMetadata {
+            decimals: decimals.as_u32() as u8,
+            symbol,
+        })
+    }
+
+    async fn report_mismatch(&self, token: &Token, mismatch: &TokenMetadataMismatch) {
+        vlog::error!(
+            "Metadata of the token {} ({:?}) doesn't match L1: {}",
+            token.symbol,
+            token.address,
+            mismatch
+        );
+        let metric = match mismatch {
+            TokenMetadataMismatch::Decimals { .. } => "token_metadata_validator.decimals_mismatch",
+            TokenMetadataMismatch::Symbol { .. } => "token_metadata_validator.symbol_mismatch",
+        };
+        metrics::increment_counter!(metric, "token" => token.symbol.clone());
+
+        if let Some(notifier) = &self.notifier {
+            notifier
+                .send_token_metadata_mismatch_notify(token, &mismatch.to_string())
+                .await
+                .unwrap_or_else(|e| {
+                    vlog::error!(
+                        "Failed to send a token metadata mismatch notification: {}",
+                        e
+                    );
+                });
+        }
+    }
+
+    async fn check_token(&mut self, token: &Token) -> anyhow::Result<()> {
+        let metadata = self.load_metadata(token).await?;
+        let mismatches = check_metadata(token, &metadata);
+
+        let reported = self.reported.get(&token.id).cloned().unwrap_or_default();
+        for mismatch in mismatches.iter().filter(|m| !reported.contains(m)) {
+            self.report_mismatch(token, mismatch).await;
+        }
+
+        let decimals_mismatch = mismatches
+            .iter()
+            .any(|mismatch| matches!(mismatch, TokenMetadataMismatch::Decimals { .. }));
+        if decimals_mismatch && self.fix_decimals {
+            let mut fixed_token = token.clone();
+            fixed_token.decimals = metadata.decimals;
+            self.connection_pool
+                .access_storage()
+                .await?
+                .tokens_schema()
+                .store_or_update_token(fixed_token)
+                .await?;
+            vlog::info!(
+                "Decimals of the token {} are fixed: {} -> {}",
+                token.symbol,
+                token.decimals,
+                metadata.decimals
+            );
+            metrics::increment_counter!("token_metadata_validator.decimals_fixed", "token" => token.symbol.clone());
+        }
+
+        if mismatches.is_empty() {
+            self.reported.remove(&token.id);
+        } else {
+            self.reported.insert(token.id, mismatches);
+        }
+        Ok(())
+    }
+
+    async fn check_all_tokens(&mut self) -> anyhow::Result<()> {
+        let tokens = self
+            .connection_pool
+            .access_storage()
+            .await?
+            .tokens_schema()
+            .load_tokens()
+            .await?;
+
+        for token in tokens.values() {
+            // ETH has no contract, and only ERC20 tokens are guaranteed to have `decimals`.
+            if token.id == TokenId(0) || token.kind != TokenKind::ERC20 {
+                continue;
+            }
+            if let Err(err) = self.check_token(token).await {
+                vlog::warn!(
+                    "Failed to check the metadata of the token {}: {}",
+                    token.symbol,
+                    err
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn run(&mut self) {
+        let mut timer = tokio::time::interval(self.check_interval);
+        loop {
+            timer.tick().await;
+
+            if let Err(err) = self.check_all_tokens().await {
+                vlog::warn!("Error when checking the token metadata: {}", err);
+            }
+        }
+    }
+}
+
+#[must_use]
+pub fn run_token_metadata_validator(
+    db_pool: ConnectionPool,
+    eth_client: EthereumGateway,
+    config: &TokenHandlerConfig,
+) -> JoinHandle<()> {
+    let config = config.clone();
+    tokio::spawn(async move {
+        let mut validator = TokenMetadataValidator::new(db_pool, eth_client, config);
+
+        validator.run().await
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::Address;
+
+    #[test]
+    fn token_metadata_mismatches() {
+        let token = Token::new(
+            TokenId(1),
+            Address::repeat_byte(1),
+            "USDC",
+            6,
+            TokenKind::ERC20,
+        );
+        let metadata = |decimals: u8, symbol: Option<&str>| TokenMetadata {
+            decimals,
+            symbol: symbol.map(str::to_string),
+        };
+
+        assert!(check_metadata(&token, &metadata(6, Some("USDC"))).is_empty());
+        // Symbol can't be obtained, so it's not checked.
+        assert!(check_metadata(&token, &metadata(6, None)).is_empty());
+        assert_eq!(
+            check_metadata(&token, &metadata(18, Some("USDT"))),
+            vec![
+                TokenMetadataMismatch::Decimals {
+                    stored: 6,
+                    actual: 18
+                },
+                TokenMetadataMismatch::Symbol {
+                    stored: "USDC".to_string(),
+                    actual: "USDT".to_string()
+                }
+            ]
+        );
+
+        // The default symbol of the tokens missing in the token list isn't checked.
+        let token = Token::new(
+            TokenId(2),
+            Address::repeat_byte(2),
+            "ERC20-2",
+            18,
+            TokenKind::ERC20,
+        );
+        assert!(check_metadata(&token, &metadata(18, Some("DAI"))).is_empty());
+        assert_eq!(
+            check_metadata(&token, &metadata(8, Some("DAI"))),
+            vec![TokenMetadataMismatch::Decimals {
+                stored: 18,
+                actual: 8
+            }]
+        );
+    }
+}
@@ -0,0 +1,129 @@
+// Built-in deps
+use std::time::Duration;
+
+// External uses
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt,
+};
+use tokio::task::JoinHandle;
+// Workspace uses
+use zksync_config::ETHWatchConfig;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{Address, ValidatorStatusUpdateEvent};
+// Local uses
+use crate::eth_watch::EthWatchRequest;
+
+/// Handle events about activating and deactivating validators in the Governance contract.
+#[derive(Debug)]
+struct ValidatorStatusHandler {
+    connection_pool: ConnectionPool,
+    poll_interval: Duration,
+    eth_watch_req: mpsc::Sender<EthWatchRequest>,
+    last_eth_block: Option<u64>,
+    /// Address used by the server to send the operations to Ethereum.
+    operator_address: Address,
+}
+
+impl ValidatorStatusHandler {
+    fn new(
+        connection_pool: ConnectionPool,
+        eth_watch_req: mpsc::Sender<EthWatchRequest>,
+        config: &ETHWatchConfig,
+        operator_address: Address,
+    ) -> Self {
+        let poll_interval = config.validator_status_poll_interval();
+
+        Self {
+            connection_pool,
+            eth_watch_req,
+            poll_interval,
+            last_eth_block: None,
+            operator_address,
+        }
+    }
+
+    async fn load_validator_status_updates(&self) -> Vec<ValidatorStatusUpdateEvent> {
+        let (sender, receiver) = oneshot::channel();
+        self.eth_watch_req
+            .clone()
+            .send(EthWatchRequest::GetValidatorStatusUpdates {
+                last_eth_block: self.last_eth_block,
+                resp: sender,
+            })
+            .await
+            .expect("ETH watch req receiver dropped");
+
+        receiver.await.expect("Err response from eth watch")
+    }
+
+    async fn save_validator_status_updates(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        validator_status_updates: Vec<ValidatorStatusUpdateEvent>,
+    ) -> anyhow::Result<()> {
+        let mut transaction = storage.start_transaction().await?;
+        for event in validator_status_updates {
+            vlog::info!(
+                "Validator {:?} status is updated in the Ethereum block {}: active = {}",
+                event.validator_address,
+                event.eth_block,
+                event.is_active
+            );
+            if event.validator_address == self.operator_address && !event.is_active {
+                vlog::error!(
+                    "Operator address {:?} is not an active validator anymore, \
+                    operations sent to Ethereum will be rejected",
+                    self.operator_address
+                );
+            }
+
+            transaction
+                .governance_schema()
+                .store_validator_status(event.validator_address, event.is_active, event.eth_block)
+                .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    async fn run(&mut self) {
+        let mut timer = tokio::time::interval(self.poll_interval);
+        loop {
+            timer.tick().await;
+
+            let validator_status_updates = self.load_validator_status_updates().await;
+
+            self.last_eth_block = validator_status_updates
+                .iter()
+                .map(|event| event.eth_block)
+                .max()
+                .or(self.last_eth_block);
+
+            let mut storage = self
+                .connection_pool
+                .access_storage()
+                .await
+                .expect("db connection failed for validator status handler");
+
+            self.save_validator_status_updates(&mut storage, validator_status_updates)
+                .await
+                .expect("failed to add validator statuses to the database");
+        }
+    }
+}
+
+#[must_use]
+pub fn run_validator_status_handler(
+    db_pool: ConnectionPool,
+    eth_watch_req: mpsc::Sender<EthWatchRequest>,
+    config: ETHWatchConfig,
+    operator_address: Address,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut handler =
+            ValidatorStatusHandler::new(db_pool, eth_watch_req, &config, operator_address);
+
+        handler.run().await
+    })
+}
@@ -5,10 +5,12 @@ use std::str::FromStr;
 use num::BigUint;
 use zksync_basic_types::{H256, U256};
 // Workspace uses
+use zksync_prometheus_exporter::labels::{report_block_processed, report_op_processed};
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_types::ethereum::{ETHOperation, EthOpId, InsertedOperationResponse};
+use zksync_types::pipeline::PipelineStage;
 // Local uses
-use super::transactions::ETHStats;
+use super::{dependencies::operation_dependencies, transactions::ETHStats};
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
 use zksync_types::block::Block;
 
@@ -86,6 +88,9 @@ pub(super) trait DatabaseInterface {
     /// Loads the stored Ethereum operations stats.
     async fn load_stats(&self, connection: &mut StorageProcessor<'_>) -> anyhow::Result<ETHStats>;
 
+    /// Loads the nonce that will be assigned to the next Ethereum operation.
+    async fn load_next_nonce(&self, connection: &mut StorageProcessor<'_>) -> anyhow::Result<U256>;
+
     /// Loads the stored gas price limit.
     async fn load_gas_price_limit(
         &self,
@@ -100,11 +105,19 @@ pub(super) trait DatabaseInterface {
         average_gas_price: U256,
     ) -> anyhow::Result<()>;
 
-    async fn is_previous_operation_confirmed(
+    /// Checks whether all the operations that the provided operation depends on are confirmed.
+    /// See the `dependencies` module for details.
+    async fn are_dependencies_confirmed(
         &self,
         connection: &mut StorageProcessor<'_>,
         op: &ETHOperation,
     ) -> anyhow::Result<bool>;
+
+    /// Loads the pipeline stages paused by the operators.
+    async fn load_paused_pipeline_stages(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<PipelineStage>>;
 }
 
 /// The actual database wrapper.
@@ -229,26 +242,30 @@ impl DatabaseInterface for Database {
             .await?)
     }
 
-    async fn is_previous_operation_confirmed(
+    async fn are_dependencies_confirmed(
         &self,
         connection: &mut StorageProcessor<'_>,
         op: &ETHOperation,
     ) -> anyhow::Result<bool> {
-        // If the ID of the current operation is 1, then this is the first transaction
-        // and it is not needed for checking confirmation.
-        if op.id == 1 {
-            return Ok(true);
+        let block_range = match &op.op {
+            Some((_, aggregated_op)) => aggregated_op.get_block_range(),
+            None => return Ok(true),
+        };
+
+        for dependency in operation_dependencies(op.op_type, block_range) {
+            // Operations of the same type are confirmed in order, so it's enough
+            // to check the last confirmed block.
+            let last_confirmed_block = connection
+                .chain()
+                .operations_schema()
+                .get_last_block_by_aggregated_action(dependency.action_type, Some(true))
+                .await?;
+            if last_confirmed_block < dependency.block {
+                return Ok(false);
+            }
         }
 
-        // Since the operations are sent to the Ethereum one by one,
-        // we simply consider the operation with ID less by one.
-        let previous_op = op.id - 1;
-        let confirmed = connection
-            .ethereum_schema()
-            .is_aggregated_op_confirmed(previous_op)
-            .await?;
-
-        Ok(confirmed)
+        Ok(true)
     }
 
     async fn confirm_operation(
@@ -323,6 +340,11 @@ impl DatabaseInterface for Database {
         Ok(stats.into())
     }
 
+    async fn load_next_nonce(&self, connection: &mut StorageProcessor<'_>) -> anyhow::Result<U256> {
+        let nonce = connection.ethereum_schema().load_next_nonce().await?;
+        Ok(U256::from(nonce as u64))
+    }
+
     async fn load_gas_price_limit(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -343,22 +365,26 @@ impl DatabaseInterface for Database {
             .await?;
         Ok(())
     }
+
+    async fn load_paused_pipeline_stages(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<PipelineStage>> {
+        let pauses = connection
+            .misc_schema()
+            .load_paused_pipeline_stages()
+            .await?;
+        Ok(pauses.into_iter().map(|pause| pause.stage).collect())
+    }
 }
 
 impl Database {
     async fn set_metrics(&self, blocks: &[Block], stage: String) {
         for block in blocks {
             for tx in &block.block_transactions {
-                let labels = vec![
-                    ("stage", stage.clone()),
-                    ("name", tx.variance_name()),
-                    ("token", tx.token_id().to_string()),
-                ];
-
-                metrics::histogram!("process_tx", tx.elapsed(), &labels);
+                report_op_processed(&stage, tx.variance_name(), tx.token_id(), tx.elapsed());
             }
-            let labels = vec![("stage", stage.clone())];
-            metrics::histogram!("process_block", block.elapsed(), &labels)
+            report_block_processed(&stage, *block.block_number, block.elapsed());
         }
     }
 }
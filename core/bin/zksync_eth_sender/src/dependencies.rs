@@ -0,0 +1,111 @@
+//! Dependencies between the Ethereum operations.
+//!
+//! Operations of different types that affect different blocks are independent from each
+//! other, e.g. the commit of block N+1 doesn't have to wait for the proof of block N to be
+//! confirmed. Thus instead of confirming operations strictly in the order they were sent,
+//! every operation is confirmed as soon as the operations it depends on are confirmed:
+//!
+//! - every operation depends on the operation of the same type for the preceding block;
+//! - proof operation depends on the commit operation for its last block;
+//! - execute operation depends on the proof operation for its last block.
+
+// Workspace uses
+use zksync_types::{aggregated_operations::AggregatedActionType, BlockNumber};
+
+/// Operation that must be confirmed before the dependent one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Dependency {
+    /// Type of the operation.
+    pub action_type: AggregatedActionType,
+    /// Block that must be processed by the confirmed operations of `action_type`.
+    pub block: BlockNumber,
+}
+
+impl Dependency {
+    fn new(action_type: AggregatedActionType, block: BlockNumber) -> Self {
+        Self { action_type, block }
+    }
+}
+
+/// Returns the list of operations that must be confirmed before the operation
+/// of `action_type` affecting the `block_range` can be confirmed.
+pub(super) fn operation_dependencies(
+    action_type: AggregatedActionType,
+    block_range: (BlockNumber, BlockNumber),
+) -> Vec<Dependency> {
+    let (first_block, last_block) = block_range;
+    let mut dependencies = Vec::with_capacity(2);
+
+    // The first block doesn't have a predecessor.
+    if *first_block > 1 {
+        dependencies.push(Dependency::new(action_type, first_block - 1));
+    }
+
+    match action_type {
+        AggregatedActionType::PublishProofBlocksOnchain => {
+            dependencies.push(Dependency::new(
+                AggregatedActionType::CommitBlocks,
+                last_block,
+            ));
+        }
+        AggregatedActionType::ExecuteBlocks => {
+            dependencies.push(Dependency::new(
+                AggregatedActionType::PublishProofBlocksOnchain,
+                last_block,
+            ));
+        }
+        AggregatedActionType::CommitBlocks | AggregatedActionType::CreateProofBlocks => {}
+    }
+
+    dependencies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that every operation type gets the expected set of dependencies.
+    #[test]
+    fn dependencies() {
+        assert_eq!(
+            operation_dependencies(
+                AggregatedActionType::CommitBlocks,
+                (BlockNumber(1), BlockNumber(2))
+            ),
+            vec![]
+        );
+        assert_eq!(
+            operation_dependencies(
+                AggregatedActionType::CommitBlocks,
+                (BlockNumber(3), BlockNumber(4))
+            ),
+            vec![Dependency::new(
+                AggregatedActionType::CommitBlocks,
+                BlockNumber(2)
+            )]
+        );
+        assert_eq!(
+            operation_dependencies(
+                AggregatedActionType::PublishProofBlocksOnchain,
+                (BlockNumber(1), BlockNumber(2))
+            ),
+            vec![Dependency::new(
+                AggregatedActionType::CommitBlocks,
+                BlockNumber(2)
+            )]
+        );
+        assert_eq!(
+            operation_dependencies(
+                AggregatedActionType::ExecuteBlocks,
+                (BlockNumber(3), BlockNumber(4))
+            ),
+            vec![
+                Dependency::new(AggregatedActionType::ExecuteBlocks, BlockNumber(2)),
+                Dependency::new(
+                    AggregatedActionType::PublishProofBlocksOnchain,
+                    BlockNumber(4)
+                ),
+            ]
+        );
+    }
+}
@@ -93,6 +93,39 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
         Ok(price)
     }
 
+    /// Calculates the gas price for the transaction that must be mined before the priority
+    /// operations expire (otherwise the network will enter the exodus mode).
+    ///
+    /// Unlike `get_gas_price`, the price of the stuck transaction is always scaled up (even
+    /// if the average price is known), and it's capped by the emergency limit rather than by
+    /// the dynamically calculated one. Urgent prices are not reported to the statistics module,
+    /// since they don't reflect the usual network state.
+    pub async fn get_urgent_gas_price(
+        &mut self,
+        ethereum: &EthereumGateway,
+        old_tx_gas_price: Option<U256>,
+    ) -> anyhow::Result<U256> {
+        let network_price = ethereum.get_gas_price().await?;
+        let base_price = match self.statistics.get_average_price() {
+            Some(average_price) => std::cmp::max(average_price, network_price),
+            None => network_price,
+        };
+        let scaled_price = match old_tx_gas_price {
+            Some(old_price) => self.scale_up(old_price, base_price),
+            None => base_price,
+        };
+        let price = self.limit_emergency(scaled_price);
+
+        if price > self.get_current_max_price() {
+            vlog::warn!(
+                "Gas price above the regular limit will be used for the urgent tx: <{}>",
+                price
+            );
+        }
+
+        Ok(price)
+    }
+
     /// Performs an actualization routine for `GasAdjuster`:
     /// This method is intended to be invoked periodically, and it updates the
     /// current max gas price limit according to the configurable update interval.
@@ -118,35 +151,41 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
             self.last_price_renewal = Instant::now();
 
             // Update the value in the database as well.
-            let mut connection = match db.acquire_connection().await {
-                Ok(connection) => connection,
-                Err(err) => {
-                    vlog::warn!("Cannot update the gas limit value in the database: {}", err);
-                    return;
-                }
-            };
+            self.store_gas_price_params(db).await;
+        }
+    }
 
-            let average_gas_price = match self.statistics.get_average_price() {
-                Some(price) => price,
-                None => {
-                    // Not enough data to update anything yet.
-                    return;
-                }
-            };
-
-            let result = db
-                .update_gas_price_params(
-                    &mut connection,
-                    self.statistics.get_limit(),
-                    average_gas_price,
-                )
-                .await;
-
-            if let Err(err) = result {
-                // Inability of update the value in the DB is not critical as it's not
-                // an essential logic part, so just report the error to the log.
+    /// Stores the current max gas price limit and the average gas price in the database,
+    /// so they are restored after the restart.
+    pub async fn store_gas_price_params(&self, db: &DB) {
+        let mut connection = match db.acquire_connection().await {
+            Ok(connection) => connection,
+            Err(err) => {
                 vlog::warn!("Cannot update the gas limit value in the database: {}", err);
+                return;
+            }
+        };
+
+        let average_gas_price = match self.statistics.get_average_price() {
+            Some(price) => price,
+            None => {
+                // Not enough data to update anything yet.
+                return;
             }
+        };
+
+        let result = db
+            .update_gas_price_params(
+                &mut connection,
+                self.statistics.get_limit(),
+                average_gas_price,
+            )
+            .await;
+
+        if let Err(err) = result {
+            // Inability of update the value in the DB is not critical as it's not
+            // an essential logic part, so just report the error to the log.
+            vlog::warn!("Cannot update the gas limit value in the database: {}", err);
         }
     }
 
@@ -161,6 +200,14 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
         std::cmp::min(price, limit)
     }
 
+    fn limit_emergency(&self, price: U256) -> U256 {
+        // Emergency limit is not expected to be lower than the regular one,
+        // but it's not a reason to make the urgent transactions cheaper.
+        let limit = std::cmp::max(parameters::emergency_limit(), self.get_current_max_price());
+
+        std::cmp::min(price, limit)
+    }
+
     /// Returns current max gas price that can be used to send transactions.
     pub fn get_current_max_price(&self) -> U256 {
         self.statistics.get_limit()
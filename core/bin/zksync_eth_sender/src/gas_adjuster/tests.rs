@@ -3,7 +3,10 @@
 use zksync_basic_types::U256;
 // Local uses
 use crate::{
-    gas_adjuster::{parameters::limit_scale_factor, GasStatistics},
+    gas_adjuster::{
+        parameters::{emergency_limit, limit_scale_factor},
+        GasStatistics,
+    },
     tests::mock::{default_eth_sender, MockDatabase},
     DatabaseInterface, GasAdjuster,
 };
@@ -91,6 +94,54 @@ async fn lower_gas_limit() {
     }
 }
 
+/// Checks that the price for urgent transactions is allowed to exceed the regular limit,
+/// but is still clamped according to the emergency limit.
+#[tokio::test]
+async fn urgent_gas_price() {
+    // Initial price limit to set.
+    const PRICE_LIMIT: i64 = 1000;
+
+    let (mut ethereum, db) = eth_and_db_clients().await;
+
+    db.update_gas_price_limit(PRICE_LIMIT).await.unwrap();
+    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+
+    // Set the gas price in Ethereum, which is greater than the current limit.
+    let network_price = U256::from(PRICE_LIMIT) * 2;
+    ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_gas_price(network_price)
+        .await
+        .unwrap();
+
+    // Regular transaction is clamped, while the urgent one is not.
+    let regular_gas = gas_adjuster.get_gas_price(&ethereum, None).await.unwrap();
+    assert_eq!(regular_gas, PRICE_LIMIT.into());
+    let urgent_gas = gas_adjuster
+        .get_urgent_gas_price(&ethereum, None)
+        .await
+        .unwrap();
+    assert_eq!(urgent_gas, network_price);
+
+    // Stuck urgent transaction is scaled up by 15%.
+    let urgent_gas = gas_adjuster
+        .get_urgent_gas_price(&ethereum, Some(network_price))
+        .await
+        .unwrap();
+    assert_eq!(
+        urgent_gas,
+        network_price * U256::from(115) / U256::from(100)
+    );
+
+    // Urgent transaction can't exceed the emergency limit.
+    let urgent_gas = gas_adjuster
+        .get_urgent_gas_price(&ethereum, Some(emergency_limit()))
+        .await
+        .unwrap();
+    assert_eq!(urgent_gas, emergency_limit());
+}
+
 // Checks that after re-creation the price limit is restored from the database.
 #[tokio::test]
 async fn gas_price_limit_restore() {
@@ -19,20 +19,25 @@ use zksync_config::ETHSenderConfig;
 use zksync_eth_client::{EthereumGateway, SignedCallResult};
 use zksync_storage::ConnectionPool;
 use zksync_types::ethereum::ETHOperation;
+use zksync_utils::shutdown::ShutdownGuard;
 // Local uses
 use self::{
     database::{Database, DatabaseInterface},
     gas_adjuster::GasAdjuster,
+    nonce_reconciliation::{is_nonce_error, NonceDivergence, NonceSnapshot},
     transactions::*,
     tx_queue::{TxData, TxQueue, TxQueueBuilder},
 };
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     gas_counter::GasCounter,
+    pipeline::PipelineStage,
 };
 
 mod database;
+mod dependencies;
 mod gas_adjuster;
+mod nonce_reconciliation;
 mod transactions;
 mod tx_queue;
 
@@ -43,6 +48,8 @@ mod tests;
 const RATE_LIMIT_BACKOFF_PERIOD: Duration = Duration::from_secs(30);
 /// Rate limit error will contain this response code
 const RATE_LIMIT_HTTP_CODE: &str = "429";
+/// Urgent transactions are considered stuck this many times faster than the regular ones.
+const URGENT_WAIT_TIME_DIVIDER: u64 = 4;
 
 /// `TxCheckMode` enum determines the policy on the obtaining the tx status.
 /// The latest sent transaction can be pending (we're still waiting for it),
@@ -103,12 +110,33 @@ enum TxCheckMode {
 /// 2. Withdraw operations (only if both commit/verify for the same block operations were sent).
 /// 3. Commit operations.
 ///
+/// Sent operations are confirmed according to the dependencies between them rather than strictly
+/// in the order they were sent: an operation only waits for the previous operation of the same
+/// type and for the operation of the preceding stage for the same blocks (e.g. the commit of
+/// block N+1 doesn't wait for the proof of block N to be confirmed).
+///
 /// # Failure policy
 ///
 /// By default, `ETHSender` expects no transactions to fail, and thus upon a failure it will
 /// report the incident to the log and then panic to prevent continue working in a probably
 /// erroneous conditions. Failure handling policy is determined by a corresponding callback,
 /// which can be changed if needed.
+///
+/// # Priority operations expiration
+///
+/// Every priority operation must be executed on L1 until its deadline block, otherwise the
+/// network enters the exodus mode. Once the deadline of a priority operation processed by
+/// the proof or execute operation is closer than the configured escalation window, such an
+/// operation is considered urgent: its transactions are replaced more frequently, and the gas
+/// price for them is allowed to exceed the regular limit up to the configured emergency ceiling.
+///
+/// # Nonce reconciliation
+///
+/// Nonces for the Ethereum transactions are assigned by `ETHSender` itself. On startup and
+/// every time the node rejects a transaction because of the nonce, the stored nonce is
+/// compared with the operator account state on chain. If they diverged in a way that can't
+/// be resolved automatically, the incident is reported to the log along with the actions
+/// required from the operator, and the node is terminated.
 struct ETHSender<DB: DatabaseInterface> {
     /// Ongoing operations queue.
     ongoing_ops: VecDeque<ETHOperation>,
@@ -184,13 +212,27 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     }
 
     /// Main routine of `ETHSender`.
-    pub async fn run(mut self) {
+    ///
+    /// Once the `shutdown` is triggered, the current iteration is finished and the gas price
+    /// statistics are stored. The state of the sent transactions is stored on every iteration.
+    pub async fn run(mut self, mut shutdown: ShutdownGuard) {
         // `eth_sender` must perform some of the activities only once per block change.
         // Having `0` as an initial value is to ensure that on the first iteration we will run all the activities.
         let mut last_used_block = 0;
+
+        // Make sure that the stored nonce matches the operator account before sending anything.
+        if self.options.sender.is_enabled {
+            if let Err(e) = self.reconcile_nonce().await {
+                vlog::warn!("Unable to reconcile the operator nonce on startup: {}", e);
+            }
+        }
+
         loop {
             // We perform a loading routine every X seconds.
-            tokio::time::sleep(self.options.sender.tx_poll_period()).await;
+            tokio::select! {
+                _ = tokio::time::sleep(self.options.sender.tx_poll_period()) => {},
+                _ = shutdown.triggered() => break,
+            }
             // If we received an error when loading a new operation, we can't do anything about it and should panic.
             if let Err(error) = self.load_new_operations().await {
                 vlog::error!("Unable to restore operations from the database: {}", error);
@@ -198,6 +240,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             }
 
             if self.options.sender.is_enabled {
+                self.update_paused_operations().await;
                 // ...and proceed them.
                 last_used_block = self.proceed_next_operations(last_used_block).await;
                 // Update the gas adjuster to maintain the up-to-date max gas price limit.
@@ -206,6 +249,39 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                     .await;
             }
         }
+
+        self.gas_adjuster.store_gas_price_params(&self.db).await;
+        vlog::info!(
+            "Ethereum sender is stopped, {} operations are in flight",
+            self.ongoing_ops.len()
+        );
+    }
+
+    /// Loads the pipeline stages paused by the operators, so the paused operations aren't sent.
+    /// The operations that are already sent are still tracked until they're confirmed.
+    async fn update_paused_operations(&mut self) {
+        match self.load_paused_operations().await {
+            Ok(paused_operations) => self.tx_queue.set_paused_operations(paused_operations),
+            // Keeping the previous pauses is safer than resuming the paused operations.
+            Err(err) => vlog::warn!("Unable to load the paused pipeline stages: {}", err),
+        }
+    }
+
+    async fn load_paused_operations(&self) -> anyhow::Result<Vec<AggregatedActionType>> {
+        let mut connection = self.db.acquire_connection().await?;
+        let paused_stages = self.db.load_paused_pipeline_stages(&mut connection).await?;
+
+        let paused_operations = paused_stages
+            .into_iter()
+            .filter_map(|stage| match stage {
+                PipelineStage::CommitSending => Some(AggregatedActionType::CommitBlocks),
+                PipelineStage::ProofSubmission => {
+                    Some(AggregatedActionType::PublishProofBlocksOnchain)
+                }
+                _ => None,
+            })
+            .collect();
+        Ok(paused_operations)
     }
 
     /// Gets the incoming operations from the database and adds them to the
@@ -338,11 +414,20 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
 
     /// Stores the new operation in the database and sends the corresponding transaction.
     async fn initialize_operation(&mut self, tx: TxData, current_block: u64) -> anyhow::Result<()> {
-        let deadline_block = self.get_deadline_block(current_block);
-        let gas_price = self
-            .gas_adjuster
-            .get_gas_price(&self.ethereum, None)
-            .await?;
+        let urgent = self.is_urgent(&tx.operation.1, current_block);
+        let (deadline_block, gas_price) = if urgent {
+            let gas_price = self
+                .gas_adjuster
+                .get_urgent_gas_price(&self.ethereum, None)
+                .await?;
+            (self.get_urgent_deadline_block(current_block), gas_price)
+        } else {
+            let gas_price = self
+                .gas_adjuster
+                .get_gas_price(&self.ethereum, None)
+                .await?;
+            (self.get_deadline_block(current_block), gas_price)
+        };
 
         let mut connection = self.db.acquire_connection().await?;
         let mut transaction = connection.start_transaction().await?;
@@ -397,16 +482,22 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             "Sending new tx: [ETH Operation <id: {}, type: {:?}>. ETH tx: {}. ZKSync operation: {}]",
             new_op.id, new_op.op_type, self.eth_tx_description(&signed_tx), self.zksync_operation_description(&new_op),
         );
-        if let Err(e) = self.ethereum.send_raw_tx(signed_tx.raw_tx).await {
+        let send_result = self.ethereum.send_raw_tx(signed_tx.raw_tx).await;
+
+        transaction.commit().await?;
+
+        if let Err(e) = send_result {
             // Sending tx error is not critical: this will result in transaction being considered stuck,
             // and resent. We can't do anything about this failure either, since it's most probably is not
             // related to the node logic, so we just log this error and pretend to have this operation
             // processed.
+            // The only exception is the nonce mismatch, since resending won't help in that case.
             vlog::warn!("Error while sending the operation: {}", e);
+            if is_nonce_error(&e) {
+                self.reconcile_nonce().await?;
+            }
         }
 
-        transaction.commit().await?;
-
         Ok(())
     }
 
@@ -455,6 +546,27 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             "OperationETHState should have at least one transaction"
         );
 
+        let urgent = op
+            .op
+            .as_ref()
+            .map(|(_, aggregated_op)| self.is_urgent(aggregated_op, current_block))
+            .unwrap_or(false);
+        if urgent {
+            // Operation could be sent before it became urgent, so we have to shorten its deadline
+            // to get the transaction replaced with the escalated gas price in time.
+            let urgent_deadline_block = self.get_urgent_deadline_block(current_block);
+            if op.last_deadline_block > urgent_deadline_block {
+                vlog::warn!(
+                    "ETH Operation <id: {}> processes priority operations close to expiration, \
+                    escalating it. ZKSync operation: {}",
+                    op.id,
+                    self.zksync_operation_description(op),
+                );
+                metrics::increment_counter!("eth_sender.urgent_operations");
+                op.last_deadline_block = urgent_deadline_block;
+            }
+        }
+
         // Check statuses of existing transactions.
         // Go through every transaction in a loop. We will exit this method early
         // if there will be discovered a pending or successfully committed transaction.
@@ -478,8 +590,8 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                     let mut transaction = connection.start_transaction().await?;
 
                     // While transactions are sent in order, has to be processed in order due to nonce,
-                    // and checked for commitment also in the same order, we still must check that previous
-                    // operation was confirmed.
+                    // and checked for commitment also in the same order, we still must check that the
+                    // operations this one depends on were confirmed.
                     //
                     // Consider the following scenario:
                     // 1. Two Verify operations are sent to the Ethereum and included into one block.
@@ -492,10 +604,11 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                     // 8. State is updated according to operation Verify#1, and likely some data is overwritten.
                     //
                     // For commit operations consequences aren't that drastic, but still it's not correct to confirm
-                    // operations out of order.
+                    // operations out of order. Operations that don't depend on each other (e.g. commit of block N+1
+                    // and verify of block N) are confirmed independently.
                     if !self
                         .db
-                        .is_previous_operation_confirmed(&mut transaction, op)
+                        .are_dependencies_confirmed(&mut transaction, op)
                         .await?
                     {
                         vlog::info!("ETH Operation <id: {}> is confirmed ahead of time, considering it pending for now", op.id);
@@ -532,10 +645,16 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
 
         // Reaching this point will mean that the latest transaction got stuck.
         // We should create another tx based on it, and send it.
-        let deadline_block = self.get_deadline_block(current_block);
+        let deadline_block = if urgent {
+            self.get_urgent_deadline_block(current_block)
+        } else {
+            self.get_deadline_block(current_block)
+        };
         // Raw tx contents are the same for every transaction, so we just
         // create a new one from the old one with updated parameters.
-        let new_tx = self.create_supplement_tx(deadline_block, op).await?;
+        let new_tx = self
+            .create_supplement_tx(deadline_block, op, urgent)
+            .await?;
         // New transaction should be persisted in the DB *before* sending it.
 
         let mut connection = self.db.acquire_connection().await?;
@@ -557,7 +676,22 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             op.id,
             self.eth_tx_description(&new_tx),
         );
-        self.ethereum.send_raw_tx(new_tx.raw_tx).await?;
+        if let Err(e) = self.ethereum.send_raw_tx(new_tx.raw_tx).await {
+            if !is_nonce_error(&e) {
+                return Err(e);
+            }
+            // Node rejected the supplement tx because of the nonce. If the stored nonce is
+            // consistent with the chain, then one of the previously sent txs for this operation
+            // was mined, and it will be confirmed on one of the next rounds.
+            vlog::warn!(
+                "Supplement tx for ETH Operation <id: {}> was rejected: {}",
+                op.id,
+                e
+            );
+            drop(transaction);
+            self.reconcile_nonce().await?;
+            return Ok(OperationCommitment::Pending);
+        }
         transaction.commit().await?;
 
         metrics::histogram!("eth_sender.perform_commitment_step", start.elapsed());
@@ -579,11 +713,93 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         panic!("Cannot operate after unexpected TX failure");
     }
 
+    /// Loads the stored nonce along with the operator account nonce and checks that they're
+    /// consistent with each other, terminating the node otherwise.
+    async fn reconcile_nonce(&self) -> anyhow::Result<()> {
+        let snapshot = self.load_nonce_snapshot().await?;
+
+        match snapshot.reconcile() {
+            Ok(()) => {
+                vlog::info!("Operator nonce is consistent with the chain: {}", snapshot);
+                Ok(())
+            }
+            Err(divergence) => self.nonce_divergence_handler(snapshot, divergence),
+        }
+    }
+
+    /// Collects the nonce values from both the database and the Ethereum node.
+    async fn load_nonce_snapshot(&self) -> anyhow::Result<NonceSnapshot> {
+        let mut connection = self.db.acquire_connection().await?;
+        let stored_next_nonce = self.db.load_next_nonce(&mut connection).await?;
+        // Ongoing operations may be partially drained at the moment of the check,
+        // so the database is used as a source of truth.
+        let lowest_unconfirmed_nonce = self
+            .db
+            .load_unconfirmed_operations(&mut connection)
+            .await?
+            .iter()
+            .map(|op| op.nonce)
+            .min();
+        drop(connection);
+
+        let chain_nonce = self.ethereum.current_nonce().await?;
+        let chain_pending_nonce = self.ethereum.pending_nonce().await?;
+
+        Ok(NonceSnapshot {
+            stored_next_nonce,
+            lowest_unconfirmed_nonce,
+            chain_nonce,
+            chain_pending_nonce,
+        })
+    }
+
+    /// Handles a nonce divergence by reporting the actions required from the operator
+    /// and terminating the node.
+    fn nonce_divergence_handler(&self, snapshot: NonceSnapshot, divergence: NonceDivergence) -> ! {
+        vlog::error!(
+            "Stored nonce diverged from the operator account state {}: {}",
+            snapshot,
+            divergence
+        );
+        metrics::increment_counter!("eth_sender.nonce_divergence");
+        panic!(
+            "Cannot operate with diverged operator nonce: {}",
+            divergence
+        );
+    }
+
     /// Helper method encapsulating the logic of determining the next deadline block.
     fn get_deadline_block(&self, current_block: u64) -> u64 {
         current_block + self.options.sender.expected_wait_time_block
     }
 
+    /// Same as `get_deadline_block`, but for the urgent operations.
+    fn get_urgent_deadline_block(&self, current_block: u64) -> u64 {
+        let wait_time = self.options.sender.expected_wait_time_block / URGENT_WAIT_TIME_DIVIDER;
+        current_block + std::cmp::max(wait_time, 1)
+    }
+
+    /// Checks whether the operation must be mined as soon as possible, since it's
+    /// required to process the priority operations which are close to expiration.
+    fn is_urgent(&self, op: &AggregatedOperation, current_block: u64) -> bool {
+        let blocks = match op {
+            AggregatedOperation::PublishProofBlocksOnchain(op) => &op.blocks,
+            AggregatedOperation::ExecuteBlocks(op) => &op.blocks,
+            AggregatedOperation::CommitBlocks(_) | AggregatedOperation::CreateProofBlocks(_) => {
+                return false;
+            }
+        };
+
+        blocks
+            .iter()
+            .filter_map(|block| block.priority_ops_deadline_block())
+            .min()
+            .map(|deadline_block| {
+                current_block + self.options.sender.expiration_escalation_window >= deadline_block
+            })
+            .unwrap_or(false)
+    }
+
     /// Looks up for a transaction state on the Ethereum chain
     /// and reduces it to the simpler `TxCheckOutcome` report.
     async fn check_transaction_state(
@@ -702,8 +918,9 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         &mut self,
         deadline_block: u64,
         stuck_tx: &mut ETHOperation,
+        urgent: bool,
     ) -> anyhow::Result<SignedCallResult> {
-        let tx_options = self.tx_options_from_stuck_tx(stuck_tx).await?;
+        let tx_options = self.tx_options_from_stuck_tx(stuck_tx, urgent).await?;
 
         let raw_tx = stuck_tx.encoded_tx_data.clone();
         let signed_tx = self.ethereum.sign_prepared_tx(raw_tx, tx_options).await?;
@@ -720,13 +937,19 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     async fn tx_options_from_stuck_tx(
         &mut self,
         stuck_tx: &ETHOperation,
+        urgent: bool,
     ) -> anyhow::Result<Options> {
         let old_tx_gas_price = stuck_tx.last_used_gas_price;
 
-        let new_gas_price = self
-            .gas_adjuster
-            .get_gas_price(&self.ethereum, Some(old_tx_gas_price))
-            .await?;
+        let new_gas_price = if urgent {
+            self.gas_adjuster
+                .get_urgent_gas_price(&self.ethereum, Some(old_tx_gas_price))
+                .await?
+        } else {
+            self.gas_adjuster
+                .get_gas_price(&self.ethereum, Some(old_tx_gas_price))
+                .await?
+        };
         let nonce = stuck_tx.nonce;
         let gas_limit = Self::gas_limit_for_op(stuck_tx);
 
@@ -802,12 +1025,13 @@ pub fn run_eth_sender(
     pool: ConnectionPool,
     eth_gateway: EthereumGateway,
     options: ETHSenderConfig,
+    shutdown: ShutdownGuard,
 ) -> JoinHandle<()> {
     let db = Database::new(pool);
 
     tokio::spawn(async move {
         let eth_sender = ETHSender::new(options, db, eth_gateway).await;
 
-        eth_sender.run().await
+        eth_sender.run(shutdown).await
     })
 }
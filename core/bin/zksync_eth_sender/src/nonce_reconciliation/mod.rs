@@ -0,0 +1,119 @@
+//! Reconciliation of the operator nonce stored in the database with the
+//! state of the operator account on the Ethereum chain.
+//!
+//! `ETHSender` assigns nonces to the Ethereum transactions on its own, so the stored
+//! nonce may diverge from the actual account state, e.g. if the operator private key
+//! was used by another process or the database was restored from an outdated backup.
+//! In that case every new transaction would be rejected by the node, so instead of
+//! resending the same failing transaction over and over, we detect such a situation
+//! and report what exactly should be fixed.
+
+// Built-in deps
+use std::fmt;
+// External deps
+use zksync_basic_types::U256;
+
+#[cfg(test)]
+mod tests;
+
+/// Substrings of the node responses that indicate that the transaction
+/// nonce doesn't match the operator account state.
+const NONCE_ERROR_MESSAGES: &[&str] = &["nonce too low", "nonce too high"];
+
+/// Set of nonce values collected from both the database and the Ethereum node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct NonceSnapshot {
+    /// Nonce that will be assigned to the next Ethereum operation.
+    pub stored_next_nonce: U256,
+    /// The lowest nonce among the sent but not yet confirmed operations.
+    pub lowest_unconfirmed_nonce: Option<U256>,
+    /// Operator account nonce based on the latest mined block.
+    pub chain_nonce: U256,
+    /// Operator account nonce including the transactions in the mempool.
+    pub chain_pending_nonce: U256,
+}
+
+/// Unrecoverable mismatch between the stored nonce and the operator account state.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub(super) enum NonceDivergence {
+    #[error(
+        "Operator account nonce on chain ({chain_nonce}) is ahead of the next stored nonce \
+        ({stored_next_nonce}). Operator account was used to send transactions outside of \
+        `eth_sender`: make sure that no other process uses the operator private key and set \
+        `eth_parameters.nonce` to {chain_nonce}"
+    )]
+    ChainAhead {
+        chain_nonce: U256,
+        stored_next_nonce: U256,
+    },
+    #[error(
+        "Operator account nonce on chain ({chain_nonce}) is behind the expected one \
+        ({expected_nonce}). Transactions that are considered confirmed are missing on chain \
+        (most likely the database was restored from an outdated backup or the chain was \
+        reorganized): operations with nonces starting from {chain_nonce} have to be re-sent"
+    )]
+    ChainBehind {
+        chain_nonce: U256,
+        expected_nonce: U256,
+    },
+}
+
+impl NonceSnapshot {
+    /// Checks whether `ETHSender` can continue operating with the stored nonce.
+    ///
+    /// Mined nonce is expected to be within the range of nonces assigned to the unconfirmed
+    /// operations: every nonce below that range belongs to an already confirmed operation,
+    /// and every nonce above the stored one was never assigned by us.
+    pub fn reconcile(&self) -> Result<(), NonceDivergence> {
+        if self.chain_nonce > self.stored_next_nonce {
+            return Err(NonceDivergence::ChainAhead {
+                chain_nonce: self.chain_nonce,
+                stored_next_nonce: self.stored_next_nonce,
+            });
+        }
+
+        let expected_nonce = self
+            .lowest_unconfirmed_nonce
+            .unwrap_or(self.stored_next_nonce);
+        if self.chain_nonce < expected_nonce {
+            return Err(NonceDivergence::ChainBehind {
+                chain_nonce: self.chain_nonce,
+                expected_nonce,
+            });
+        }
+
+        if self.chain_pending_nonce > self.stored_next_nonce {
+            // Pending nonce is not a trusted source when working with a cluster of nodes,
+            // so we don't consider it a divergence, but it's still worth reporting.
+            vlog::warn!(
+                "Pending operator nonce ({}) is ahead of the next stored nonce ({}), \
+                there might be transactions in the mempool not sent by `eth_sender`",
+                self.chain_pending_nonce,
+                self.stored_next_nonce
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for NonceSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<stored next: {}; lowest unconfirmed: {:?}; chain: {}; chain pending: {}>",
+            self.stored_next_nonce,
+            self.lowest_unconfirmed_nonce,
+            self.chain_nonce,
+            self.chain_pending_nonce,
+        )
+    }
+}
+
+/// Checks whether the node rejected the transaction because of the nonce mismatch.
+pub(super) fn is_nonce_error(error: &anyhow::Error) -> bool {
+    let message = error.to_string().to_lowercase();
+    NONCE_ERROR_MESSAGES
+        .iter()
+        .any(|pattern| message.contains(pattern))
+}
@@ -0,0 +1,87 @@
+// External uses
+use anyhow::format_err;
+// Workspace uses
+use zksync_basic_types::U256;
+// Local uses
+use super::{is_nonce_error, NonceDivergence, NonceSnapshot};
+
+fn snapshot(
+    stored_next_nonce: u64,
+    lowest_unconfirmed_nonce: Option<u64>,
+    chain_nonce: u64,
+) -> NonceSnapshot {
+    NonceSnapshot {
+        stored_next_nonce: stored_next_nonce.into(),
+        lowest_unconfirmed_nonce: lowest_unconfirmed_nonce.map(U256::from),
+        chain_nonce: chain_nonce.into(),
+        chain_pending_nonce: chain_nonce.into(),
+    }
+}
+
+/// Checks that the consistent states are accepted.
+#[test]
+fn consistent_nonces() {
+    // Fresh state, nothing was sent yet.
+    assert_eq!(snapshot(0, None, 0).reconcile(), Ok(()));
+    // All the sent operations are confirmed.
+    assert_eq!(snapshot(5, None, 5).reconcile(), Ok(()));
+    // None of the unconfirmed operations is mined yet.
+    assert_eq!(snapshot(5, Some(2), 2).reconcile(), Ok(()));
+    // Some of the unconfirmed operations are mined, but don't have enough confirmations.
+    assert_eq!(snapshot(5, Some(2), 4).reconcile(), Ok(()));
+    assert_eq!(snapshot(5, Some(2), 5).reconcile(), Ok(()));
+
+    // Pending nonce is not trusted, so it can't cause a divergence.
+    let mut with_foreign_pending_tx = snapshot(5, None, 5);
+    with_foreign_pending_tx.chain_pending_nonce = 6.into();
+    assert_eq!(with_foreign_pending_tx.reconcile(), Ok(()));
+}
+
+/// Checks that the chain nonce being ahead of the stored one is reported.
+#[test]
+fn chain_nonce_ahead() {
+    assert_eq!(
+        snapshot(5, None, 6).reconcile(),
+        Err(NonceDivergence::ChainAhead {
+            chain_nonce: 6.into(),
+            stored_next_nonce: 5.into(),
+        })
+    );
+    assert_eq!(
+        snapshot(5, Some(3), 7).reconcile(),
+        Err(NonceDivergence::ChainAhead {
+            chain_nonce: 7.into(),
+            stored_next_nonce: 5.into(),
+        })
+    );
+}
+
+/// Checks that missing confirmed transactions are reported.
+#[test]
+fn chain_nonce_behind() {
+    assert_eq!(
+        snapshot(5, None, 4).reconcile(),
+        Err(NonceDivergence::ChainBehind {
+            chain_nonce: 4.into(),
+            expected_nonce: 5.into(),
+        })
+    );
+    assert_eq!(
+        snapshot(5, Some(3), 1).reconcile(),
+        Err(NonceDivergence::ChainBehind {
+            chain_nonce: 1.into(),
+            expected_nonce: 3.into(),
+        })
+    );
+}
+
+/// Checks that nonce-related node responses are recognized.
+#[test]
+fn nonce_errors() {
+    assert!(is_nonce_error(&format_err!(
+        "RPC error: Error {{ code: ServerError(-32000), message: \"nonce too low\" }}"
+    )));
+    assert!(is_nonce_error(&format_err!("Nonce too high")));
+    assert!(!is_nonce_error(&format_err!("insufficient funds for gas")));
+    assert!(!is_nonce_error(&format_err!("429 Too Many Requests")));
+}
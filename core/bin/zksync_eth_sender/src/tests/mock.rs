@@ -6,16 +6,18 @@ use std::convert::TryFrom;
 // External uses
 use tokio::sync::RwLock;
 use web3::contract::Options;
-use zksync_basic_types::{BlockNumber, H256, U256};
+use zksync_basic_types::{H256, U256};
 // Workspace uses
 use zksync_config::configs::eth_sender::{ETHSenderConfig, GasLimit, Sender};
 use zksync_eth_client::EthereumGateway;
 use zksync_storage::{ethereum::records::ETHParams, StorageProcessor};
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
 use zksync_types::ethereum::{ETHOperation, EthOpId, InsertedOperationResponse};
+use zksync_types::pipeline::PipelineStage;
 // Local uses
 use super::ETHSender;
 use crate::database::DatabaseInterface;
+use crate::dependencies::operation_dependencies;
 use crate::transactions::ETHStats;
 use zksync_eth_client::clients::mock::MockEthereum;
 
@@ -26,6 +28,7 @@ pub(crate) struct MockDatabase {
     aggregated_operations: RwLock<Vec<(i64, AggregatedOperation)>>,
     unprocessed_operations: RwLock<Vec<(i64, AggregatedOperation)>>,
     eth_parameters: RwLock<ETHParams>,
+    paused_stages: RwLock<Vec<PipelineStage>>,
 }
 
 impl MockDatabase {
@@ -41,9 +44,15 @@ impl MockDatabase {
             aggregated_operations: RwLock::new(aggregated_operations),
             unprocessed_operations: RwLock::new(unprocessed_operations),
             eth_parameters: RwLock::new(eth_parameters),
+            paused_stages: RwLock::new(Vec::new()),
         }
     }
 
+    /// Simulates the operator pausing or resuming the pipeline stages.
+    pub async fn set_paused_stages(&self, stages: Vec<PipelineStage>) {
+        *self.paused_stages.write().await = stages;
+    }
+
     pub async fn update_gas_price_limit(&self, value: i64) -> anyhow::Result<()> {
         let mut eth_parameters = self.eth_parameters.write().await;
         eth_parameters.gas_price_limit = value;
@@ -323,38 +332,48 @@ impl DatabaseInterface for MockDatabase {
         Ok(eth_stats)
     }
 
-    async fn is_previous_operation_confirmed(
+    async fn load_next_nonce(
         &self,
         _connection: &mut StorageProcessor<'_>,
-        op: &ETHOperation,
-    ) -> anyhow::Result<bool> {
-        let confirmed = {
-            let op = op.op.as_ref().unwrap();
-            // We're checking previous block, so for the edge case of first block we can say that previous operation was confirmed.
-            let (first_block, _) = op.1.get_block_range();
-            if first_block == BlockNumber(1) {
-                return Ok(true);
-            }
+    ) -> anyhow::Result<U256> {
+        // Nonces are assigned sequentially in `save_new_eth_tx`.
+        let eth_operations = self.eth_operations.read().await;
 
-            let eth_operations = self.eth_operations.read().await.clone();
+        Ok(eth_operations.len().into())
+    }
 
-            // Consider an operation that affects sequential blocks.
-            let maybe_operation = eth_operations.iter().find(|eth_operation| {
-                let op_block_range = eth_operation.op.as_ref().unwrap().1.get_block_range();
+    async fn are_dependencies_confirmed(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        op: &ETHOperation,
+    ) -> anyhow::Result<bool> {
+        let block_range = op.op.as_ref().unwrap().1.get_block_range();
+        let eth_operations = self.eth_operations.read().await;
 
-                op_block_range.1 == first_block - 1
+        let confirmed = operation_dependencies(op.op_type, block_range)
+            .into_iter()
+            .all(|dependency| {
+                // Consider a confirmed operation of the required type that affects the dependency block.
+                eth_operations.iter().any(|eth_operation| {
+                    let (first_block, last_block) =
+                        eth_operation.op.as_ref().unwrap().1.get_block_range();
+
+                    eth_operation.confirmed
+                        && eth_operation.op_type == dependency.action_type
+                        && first_block <= dependency.block
+                        && dependency.block <= last_block
+                })
             });
 
-            let operation = match maybe_operation {
-                Some(op) => op,
-                None => return Ok(false),
-            };
-
-            operation.confirmed
-        };
-
         Ok(confirmed)
     }
+
+    async fn load_paused_pipeline_stages(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<PipelineStage>> {
+        Ok(self.paused_stages.read().await.clone())
+    }
 }
 
 /// Creates a default `ETHParams` for use by mock `ETHSender` .
@@ -440,6 +459,7 @@ async fn build_eth_sender(
             wait_confirmations: super::WAIT_CONFIRMATIONS,
             tx_poll_period: 0,
             is_enabled: true,
+            expiration_escalation_window: super::EXPIRATION_ESCALATION_WINDOW,
             operator_commit_eth_addr: Default::default(),
             operator_private_key: Default::default(),
         },
@@ -448,6 +468,7 @@ async fn build_eth_sender(
             sample_interval: 15,
             update_interval: 15,
             scale_factor: 1.0f64,
+            emergency_limit: 10000,
         },
     };
 
@@ -6,9 +6,11 @@ use self::mock::{
 use super::{transactions::TxCheckOutcome, ETHSender, TxCheckMode};
 use web3::types::U64;
 use zksync_eth_client::ethereum_gateway::ExecutedTxStatus;
+use zksync_types::pipeline::PipelineStage;
 
 const EXPECTED_WAIT_TIME_BLOCKS: u64 = 30;
 const WAIT_CONFIRMATIONS: u64 = 3;
+const EXPIRATION_ESCALATION_WINDOW: u64 = 100;
 
 pub mod mock;
 mod test_data;
@@ -34,6 +36,28 @@ async fn deadline_block() {
     );
 }
 
+/// Checks that operations processing the priority operations close to expiration
+/// are considered urgent and are replaced more frequently.
+#[tokio::test]
+async fn urgent_operations() {
+    let eth_sender = default_eth_sender().await;
+
+    let commit_op = test_data::commit_blocks_operation(0).1;
+    let proof_op = test_data::publish_proof_blocks_onchain_operations(0).1;
+    let execute_op = test_data::execute_blocks_operations(0).1;
+    let escalation_block = test_data::PRIORITY_OP_DEADLINE_BLOCK - EXPIRATION_ESCALATION_WINDOW;
+
+    assert!(!eth_sender.is_urgent(&proof_op, escalation_block - 1));
+    assert!(!eth_sender.is_urgent(&execute_op, escalation_block - 1));
+    assert!(eth_sender.is_urgent(&proof_op, escalation_block));
+    assert!(eth_sender.is_urgent(&execute_op, escalation_block));
+    // Commit operation doesn't process the priority operations.
+    assert!(!eth_sender.is_urgent(&commit_op, escalation_block));
+
+    assert!(eth_sender.get_urgent_deadline_block(10) > 10);
+    assert!(eth_sender.get_urgent_deadline_block(10) < eth_sender.get_deadline_block(10));
+}
+
 /// Checks that received transaction response is reduced to the
 /// `TxCheckOutcome` correctly.
 ///
@@ -383,6 +407,7 @@ async fn stuck_transaction() {
                     .as_u64(),
             ),
             &mut stuck_tx,
+            false,
         )
         .await
         .unwrap();
@@ -794,6 +819,7 @@ async fn confirmations_independence() {
                     .as_u64(),
             ),
             &mut stuck_tx,
+            false,
         )
         .await
         .unwrap();
@@ -972,3 +998,214 @@ async fn concurrent_operations_order() {
         }
     }
 }
+
+/// Checks that operations that don't depend on each other are confirmed independently:
+/// commit of the next block is confirmed while the verify of the previous one is still pending.
+#[tokio::test]
+async fn independent_operations_confirmation() {
+    const MAX_TXS_IN_FLIGHT: u64 = 3;
+    let mut eth_sender = concurrent_eth_sender(MAX_TXS_IN_FLIGHT).await;
+
+    let first_commit_operation = test_data::commit_blocks_operation(0);
+    let verify_operation = test_data::publish_proof_blocks_onchain_operations(0);
+    let second_commit_operation = test_data::commit_blocks_operation(1);
+    // By priority, the operations will be sent in the following order.
+    let operations = [
+        first_commit_operation,
+        verify_operation,
+        second_commit_operation,
+    ];
+
+    for operation in &operations {
+        eth_sender
+            .db
+            .send_aggregated_operation(operation.clone())
+            .await
+            .unwrap();
+    }
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+
+    let deadline_block = eth_sender.get_deadline_block(
+        eth_sender
+            .ethereum
+            .get_mock()
+            .unwrap()
+            .block_number()
+            .await
+            .unwrap()
+            .as_u64(),
+    );
+    let mut expected_txs = Vec::new();
+    for (eth_op_id, operation) in operations.iter().enumerate() {
+        let tx = create_signed_tx(
+            eth_op_id as i64,
+            &eth_sender,
+            operation.clone(),
+            deadline_block,
+            eth_op_id as i64,
+        )
+        .await;
+        eth_sender.db.assert_stored(&tx).await;
+        expected_txs.push(tx);
+    }
+
+    // Both commit transactions are executed, while the verify one is still pending.
+    for tx in &[&expected_txs[0], &expected_txs[2]] {
+        eth_sender
+            .ethereum
+            .get_mut_mock()
+            .unwrap()
+            .add_successfull_execution(tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
+            .await;
+    }
+    eth_sender.proceed_next_operations(0).await;
+
+    // The second commit doesn't depend on the verify operation, so it should be confirmed.
+    for tx in &[&expected_txs[0], &expected_txs[2]] {
+        let mut tx = (*tx).clone();
+        tx.confirmed = true;
+        tx.final_hash = Some(tx.used_tx_hashes[0]);
+        eth_sender.db.assert_confirmed(&tx).await;
+    }
+
+    // Now confirm the verify operation as well.
+    let mut verify_tx = expected_txs[1].clone();
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .add_successfull_execution(verify_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
+        .await;
+    eth_sender.proceed_next_operations(0).await;
+
+    verify_tx.confirmed = true;
+    verify_tx.final_hash = Some(verify_tx.used_tx_hashes[0]);
+    eth_sender.db.assert_confirmed(&verify_tx).await;
+}
+
+/// Checks that the operations of the paused pipeline stages are not sent, and are sent
+/// as usual once the stages are resumed.
+#[tokio::test]
+async fn paused_operations() {
+    let mut eth_sender = default_eth_sender().await;
+
+    let operations = [
+        (
+            PipelineStage::CommitSending,
+            test_data::commit_blocks_operation(0),
+        ),
+        (
+            PipelineStage::ProofSubmission,
+            test_data::publish_proof_blocks_onchain_operations(0),
+        ),
+    ];
+
+    for (eth_op_id, (stage, operation)) in operations.iter().enumerate() {
+        // The block sealing pause is not related to `ETHSender` and shouldn't affect it.
+        eth_sender
+            .db
+            .set_paused_stages(vec![*stage, PipelineStage::BlockSealing])
+            .await;
+        eth_sender
+            .db
+            .send_aggregated_operation(operation.clone())
+            .await
+            .unwrap();
+        eth_sender.load_new_operations().await.unwrap();
+        eth_sender.update_paused_operations().await;
+        eth_sender.proceed_next_operations(0).await;
+        assert!(
+            eth_sender.ongoing_ops.is_empty(),
+            "Operation was sent while the {} stage is paused",
+            stage
+        );
+
+        eth_sender
+            .db
+            .set_paused_stages(vec![PipelineStage::BlockSealing])
+            .await;
+        eth_sender.update_paused_operations().await;
+        eth_sender.proceed_next_operations(0).await;
+
+        let deadline_block = eth_sender.get_deadline_block(
+            eth_sender
+                .ethereum
+                .get_mock()
+                .unwrap()
+                .block_number()
+                .await
+                .unwrap()
+                .as_u64(),
+        );
+        let mut expected_tx = create_signed_tx(
+            eth_op_id as i64,
+            &eth_sender,
+            operation.clone(),
+            deadline_block,
+            eth_op_id as i64,
+        )
+        .await;
+        eth_sender.db.assert_stored(&expected_tx).await;
+
+        // Confirm the operation, so the next one can be sent.
+        eth_sender
+            .ethereum
+            .get_mut_mock()
+            .unwrap()
+            .add_successfull_execution(expected_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
+            .await;
+        eth_sender.proceed_next_operations(0).await;
+
+        expected_tx.confirmed = true;
+        expected_tx.final_hash = Some(expected_tx.used_tx_hashes[0]);
+        eth_sender.db.assert_confirmed(&expected_tx).await;
+    }
+}
+
+/// Checks that the nonce reconciliation accepts the consistent operator account state,
+/// both for a fresh `ETHSender` and for one with operations in flight.
+#[tokio::test]
+async fn nonce_reconciliation() {
+    let mut eth_sender = default_eth_sender().await;
+    eth_sender.reconcile_nonce().await.unwrap();
+
+    let aggregated_operation = test_data::commit_blocks_operation(0);
+    eth_sender
+        .db
+        .send_aggregated_operation(aggregated_operation)
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+
+    // Operation is sent, but not mined yet.
+    eth_sender.reconcile_nonce().await.unwrap();
+
+    // Operation is mined, but not confirmed yet.
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_nonce(1.into())
+        .await
+        .unwrap();
+    eth_sender.reconcile_nonce().await.unwrap();
+}
+
+/// Checks that `ETHSender` stops working if the operator account was used
+/// to send transactions by someone else.
+#[tokio::test]
+#[should_panic]
+async fn nonce_divergence() {
+    let mut eth_sender = default_eth_sender().await;
+
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_nonce(1.into())
+        .await
+        .unwrap();
+    eth_sender.reconcile_nonce().await.unwrap();
+}
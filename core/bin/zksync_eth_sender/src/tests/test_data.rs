@@ -16,6 +16,10 @@ use zksync_types::{
     FullExitOp, PriorityOp, TokenId, ZkSyncOp, ZkSyncPriorityOp,
 };
 
+/// Deadline block of the priority operations in test blocks, far enough
+/// for the operations to not be considered urgent.
+pub const PRIORITY_OP_DEADLINE_BLOCK: u64 = 1_000_000;
+
 /// Creates a dummy operation as a test input for `ETHSender` tests.
 fn gen_aggregated_operation(
     id: i64,
@@ -34,7 +38,7 @@ fn gen_aggregated_operation(
             priority_op: PriorityOp {
                 serial_id: 0,
                 data: ZkSyncPriorityOp::FullExit(priority_op.clone()),
-                deadline_block: 0,
+                deadline_block: PRIORITY_OP_DEADLINE_BLOCK,
                 eth_hash: H256::zero(),
                 eth_block: 0,
                 eth_block_index: None,
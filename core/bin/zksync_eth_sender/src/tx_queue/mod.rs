@@ -119,6 +119,8 @@ impl TxQueueBuilder {
             execute_operations: OperationQueue::new(BlockNumber(
                 self.execute_operations_count as u32,
             )),
+
+            paused_operations: Vec::new(),
         }
     }
 }
@@ -136,6 +138,8 @@ impl TxQueueBuilder {
 ///     for corresponding blocks is committed, the `verify` operation is yielded.
 ///   - Otherwise, if `commit` queue is not empty, a `commit` operation is yielded.
 /// 3. If all the queues are empty, no operation is returned.
+///
+/// Operations of the paused types are not yielded and stay in the queue until they're resumed.
 #[derive(Debug)]
 pub struct TxQueue {
     max_pending_txs: usize,
@@ -144,9 +148,20 @@ pub struct TxQueue {
     commit_operations: OperationQueue,
     verify_operations: OperationQueue,
     execute_operations: OperationQueue,
+
+    paused_operations: Vec<AggregatedActionType>,
 }
 
 impl TxQueue {
+    /// Replaces the types of the operations that shouldn't be sent.
+    pub fn set_paused_operations(&mut self, paused_operations: Vec<AggregatedActionType>) {
+        self.paused_operations = paused_operations;
+    }
+
+    fn is_paused(&self, action_type: AggregatedActionType) -> bool {
+        self.paused_operations.contains(&action_type)
+    }
+
     /// Adds the `commit` operation to the queue.
     pub fn add_commit_operation(&mut self, commit_operation: TxData) -> anyhow::Result<()> {
         self.commit_operations.push_back(commit_operation)?;
@@ -256,12 +271,17 @@ impl TxQueue {
         // 2. After execute operations we should process verify operation.
         if let Some(next_verify_block) = self.verify_operations.get_next_last_block_number() {
             let current_commit_block = self.commit_operations.get_last_block_number();
-            if *next_verify_block <= *current_commit_block {
+            if *next_verify_block <= *current_commit_block
+                && !self.is_paused(AggregatedActionType::PublishProofBlocksOnchain)
+            {
                 return Some(self.verify_operations.pop_front().unwrap());
             }
         }
 
         // 3. Finally, check the commit queue.
+        if self.is_paused(AggregatedActionType::CommitBlocks) {
+            return None;
+        }
         self.commit_operations.pop_front()
     }
 
@@ -408,6 +428,43 @@ mod tests {
         assert_eq!(queue.sent_pending_txs, pending_count);
     }
 
+    /// Checks that the operations of the paused types are held in the queue.
+    #[test]
+    fn paused_operations() {
+        const MAX_IN_FLY: usize = 3;
+        const COMMIT_MARK: u8 = 0;
+        const VERIFY_MARK: u8 = 1;
+
+        let mut queue = TxQueueBuilder::new(MAX_IN_FLY).build();
+        queue
+            .add_commit_operation(get_tx_data(
+                AggregatedActionType::CommitBlocks,
+                BlockNumber(1),
+                vec![COMMIT_MARK, 0],
+            ))
+            .unwrap();
+        queue
+            .add_verify_operation(get_tx_data(
+                AggregatedActionType::PublishProofBlocksOnchain,
+                BlockNumber(1),
+                vec![VERIFY_MARK, 0],
+            ))
+            .unwrap();
+
+        queue.set_paused_operations(vec![AggregatedActionType::CommitBlocks]);
+        assert_eq!(queue.pop_front(), None);
+
+        // Once the commits are resumed, only the commit is sent while the proofs are paused.
+        queue.set_paused_operations(vec![AggregatedActionType::PublishProofBlocksOnchain]);
+        let op_1 = queue.pop_front().unwrap();
+        assert_eq!(op_1.raw, vec![COMMIT_MARK, 0]);
+        assert_eq!(queue.pop_front(), None);
+
+        queue.set_paused_operations(Vec::new());
+        let op_2 = queue.pop_front().unwrap();
+        assert_eq!(op_2.raw, vec![VERIFY_MARK, 0]);
+    }
+
     #[test]
     #[should_panic(expected = "No transactions are expected to be returned")]
     fn return_popped_empty() {
@@ -0,0 +1,308 @@
+//! Generation of the exit proofs against the last verified state.
+//!
+//! Reconstructing the account tree is the most expensive part of the exit proof generation
+//! besides the proof itself, so the tree of the last verified block is kept in memory and is
+//! rebuilt only once a new block is verified. The generated proofs are cached until then as well.
+
+// Built-in uses
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
+// External uses
+use thiserror::Error;
+use tokio::sync::Mutex;
+// Workspace uses
+use zksync_crypto::circuit::CircuitAccountTree;
+use zksync_crypto::params::MIN_NFT_TOKEN_ID;
+use zksync_prover_utils::exit_proof::{
+    build_exit_account_tree, create_exit_proof_with_tree, ExitProofData, StoredBlockInfo,
+};
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{AccountId, AccountMap, Address, BlockNumber, TokenId, TokenLike};
+
+#[derive(Debug, Error)]
+pub enum ExitProofError {
+    #[error("{0}")]
+    NotFound(String),
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+/// State of the last verified block the exit proofs are generated against.
+struct VerifiedState {
+    block_info: StoredBlockInfo,
+    accounts: AccountMap,
+    tree: CircuitAccountTree,
+}
+
+/// Cache of the exit proofs generated for the same verified block.
+/// When the cache is full, the oldest proofs are evicted.
+#[derive(Debug)]
+struct ProofCache {
+    block: BlockNumber,
+    capacity: usize,
+    proofs: HashMap<(AccountId, TokenId), ExitProofData>,
+    order: VecDeque<(AccountId, TokenId)>,
+}
+
+impl ProofCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            block: BlockNumber(0),
+            capacity,
+            proofs: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn get(&self, block: BlockNumber, key: (AccountId, TokenId)) -> Option<ExitProofData> {
+        if block != self.block {
+            return None;
+        }
+        self.proofs.get(&key).cloned()
+    }
+
+    fn insert(&mut self, block: BlockNumber, key: (AccountId, TokenId), proof: ExitProofData) {
+        if block != self.block {
+            // Proofs of the previous block can't be used anymore.
+            self.block = block;
+            self.proofs.clear();
+            self.order.clear();
+        }
+        if self.capacity == 0 || self.proofs.contains_key(&key) {
+            return;
+        }
+        while self.proofs.len() >= self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.proofs.remove(&evicted);
+            }
+        }
+        self.proofs.insert(key, proof);
+        self.order.push_back(key);
+    }
+}
+
+/// Generates the exit proofs against the last verified state stored in the database.
+pub struct ExitProofGenerator {
+    pool: ConnectionPool,
+    /// Locked while the proof is generated, so only one proof is generated at a time.
+    state: Mutex<Option<VerifiedState>>,
+    cache: Mutex<ProofCache>,
+}
+
+impl ExitProofGenerator {
+    pub fn new(pool: ConnectionPool, max_cached_proofs: usize) -> Self {
+        Self {
+            pool,
+            state: Mutex::new(None),
+            cache: Mutex::new(ProofCache::new(max_cached_proofs)),
+        }
+    }
+
+    /// Returns the exit proof of the account balance of the given token.
+    pub async fn exit_proof(
+        &self,
+        address: Address,
+        token: TokenLike,
+    ) -> Result<ExitProofData, ExitProofError> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        let token = storage
+            .tokens_schema()
+            .get_token(token.clone())
+            .await?
+            .ok_or_else(|| ExitProofError::NotFound(format!("Token {:?} not found", token)))?;
+        let account_id = storage
+            .chain()
+            .account_schema()
+            .account_id_by_address(address)
+            .await?
+            .ok_or_else(|| {
+                ExitProofError::NotFound(format!("Account 0x{:x} not found", address))
+            })?;
+        let last_verified_block = storage
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+
+        let key = (account_id, token.id);
+        if let Some(proof) = self.cache.lock().await.get(last_verified_block, key) {
+            metrics::increment_counter!("exit_proof_server.cache_hits");
+            return Ok(proof);
+        }
+
+        // The NFT info is stored in the proof, fungible tokens use the placeholder
+        // with the account with id 0 being the creator.
+        let (nft_creator_id, nft_creator_address, nft_serial_id, nft_content_hash) =
+            if token.id.0 < MIN_NFT_TOKEN_ID {
+                let creator_address = storage
+                    .chain()
+                    .account_schema()
+                    .account_address_by_id(AccountId(0))
+                    .await?
+                    .ok_or_else(|| anyhow::format_err!("Account with id 0 does not exist"))?;
+                (AccountId(0), creator_address, 0, Default::default())
+            } else {
+                let nft = storage
+                    .tokens_schema()
+                    .get_nft(token.id)
+                    .await?
+                    .ok_or_else(|| anyhow::format_err!("NFT {} should exist", token.id))?;
+                (
+                    nft.creator_id,
+                    nft.creator_address,
+                    nft.serial_id,
+                    nft.content_hash,
+                )
+            };
+
+        let mut state_guard = self.state.lock().await;
+        let mut state = match state_guard.take() {
+            Some(state) if state.block_info.block_number == last_verified_block => state,
+            _ => Self::load_verified_state(&mut storage).await?,
+        };
+        drop(storage);
+        // A new block may be verified since the cache was checked, the proof is generated for the loaded one.
+        let verified_block = state.block_info.block_number;
+
+        let amount = state
+            .accounts
+            .get(&account_id)
+            .map(|account| account.get_balance(token.id))
+            .unwrap_or_default();
+        let start = Instant::now();
+        let token_id = token.id;
+        // Proof generation is a CPU-bound task, the tree is moved to the blocking thread and back.
+        let (state, proof) = tokio::task::spawn_blocking(move || {
+            let proof = create_exit_proof_with_tree(
+                &mut state.tree,
+                account_id,
+                token_id,
+                nft_creator_id,
+                nft_serial_id,
+                nft_content_hash,
+            );
+            (state, proof)
+        })
+        .await
+        .map_err(|e| anyhow::format_err!("Exit proof generation panicked: {}", e))?;
+        metrics::histogram!("exit_proof_server.proof_generation", start.elapsed());
+        let stored_block_info = state.block_info.clone();
+        *state_guard = Some(state);
+        drop(state_guard);
+
+        let proof_data = ExitProofData {
+            stored_block_info,
+            owner: address,
+            account_id,
+            token_id,
+            amount: amount.into(),
+            nft_creator_id,
+            nft_creator_address,
+            nft_serial_id,
+            nft_content_hash,
+            proof: proof?,
+            token_address: token.address,
+        };
+
+        self.cache
+            .lock()
+            .await
+            .insert(verified_block, key, proof_data.clone());
+        Ok(proof_data)
+    }
+
+    /// Loads the state of the last verified block. The block and the accounts are read from
+    /// the same snapshot of the database, so they stay consistent if a new block is verified meanwhile.
+    async fn load_verified_state(
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<VerifiedState> {
+        let start = Instant::now();
+        let mut transaction = storage.start_repeatable_read_transaction().await?;
+        let (block_number, accounts) = transaction
+            .chain()
+            .state_schema()
+            .load_verified_state()
+            .await?;
+        vlog::info!("Restoring the verified state of the block {}", block_number);
+        let block = transaction
+            .chain()
+            .block_schema()
+            .get_block(block_number)
+            .await?
+            .ok_or_else(|| anyhow::format_err!("Block {} is not stored", block_number))?;
+        transaction.commit().await?;
+
+        let (accounts, tree) = tokio::task::spawn_blocking(move || {
+            let tree = build_exit_account_tree(&accounts);
+            (accounts, tree)
+        })
+        .await?;
+        metrics::histogram!("exit_proof_server.restore_state", start.elapsed());
+        vlog::info!(
+            "Restored the verified state: {} s",
+            start.elapsed().as_secs()
+        );
+
+        Ok(VerifiedState {
+            block_info: StoredBlockInfo::from_block(&block),
+            accounts,
+            tree,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proof_data(account_id: AccountId) -> ExitProofData {
+        ExitProofData {
+            stored_block_info: StoredBlockInfo {
+                block_number: BlockNumber(1),
+                priority_operations: 0,
+                pending_onchain_operations_hash: Default::default(),
+                timestamp: 0,
+                state_hash: Default::default(),
+                commitment: Default::default(),
+            },
+            owner: Default::default(),
+            account_id,
+            token_id: TokenId(0),
+            amount: Default::default(),
+            nft_creator_id: AccountId(0),
+            nft_creator_address: Default::default(),
+            nft_serial_id: 0,
+            nft_content_hash: Default::default(),
+            proof: Default::default(),
+            token_address: Default::default(),
+        }
+    }
+
+    /// Checks that the cached proofs are evicted when the cache is full or a new block is verified.
+    #[test]
+    fn proof_cache() {
+        let mut cache = ProofCache::new(2);
+        let key = |id| (AccountId(id), TokenId(0));
+        for id in 1..=3 {
+            cache.insert(BlockNumber(1), key(id), proof_data(AccountId(id)));
+        }
+        assert!(cache.get(BlockNumber(1), key(1)).is_none());
+        assert_eq!(
+            cache
+                .get(BlockNumber(1), key(3))
+                .map(|proof| proof.account_id),
+            Some(AccountId(3))
+        );
+        assert!(cache.get(BlockNumber(2), key(3)).is_none());
+
+        cache.insert(BlockNumber(2), key(4), proof_data(AccountId(4)));
+        assert!(cache.get(BlockNumber(2), key(4)).is_some());
+        assert!(cache.get(BlockNumber(1), key(2)).is_none());
+        assert_eq!(cache.proofs.len(), 1);
+    }
+}
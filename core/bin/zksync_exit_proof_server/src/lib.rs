@@ -0,0 +1,101 @@
+//! Service generating the exit proofs, which are required to withdraw the funds from the
+//! exodus mode, against the last verified state.
+//!
+//! Proofs are served by the HTTP API protected with the JWT authentication, the same way
+//! as the prover API.
+
+// Built-in deps
+use std::sync::Arc;
+// External deps
+use actix_web::{web, App, HttpResponse, HttpServer};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use serde::{Deserialize, Serialize};
+// Workspace deps
+use zksync_config::configs::api::ExitProofApiConfig;
+use zksync_storage::ConnectionPool;
+use zksync_types::{Address, TokenLike};
+use zksync_utils::AuthTokenValidator;
+// Local deps
+use self::generator::{ExitProofError, ExitProofGenerator};
+
+pub mod generator;
+
+#[derive(Clone)]
+struct AppState {
+    secret_auth: String,
+    generator: Arc<ExitProofGenerator>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExitProofRequest {
+    pub address: Address,
+    pub token: TokenLike,
+}
+
+async fn status() -> actix_web::Result<String> {
+    Ok("alive".into())
+}
+
+async fn exit_proof(
+    data: web::Data<AppState>,
+    r: web::Json<ExitProofRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let ExitProofRequest { address, token } = r.into_inner();
+    vlog::info!(
+        "Exit proof requested for 0x{:x}, token {:?}",
+        address,
+        token
+    );
+
+    let proof = data
+        .generator
+        .exit_proof(address, token)
+        .await
+        .map_err(|e| match e {
+            ExitProofError::NotFound(_) => actix_web::error::ErrorNotFound(e),
+            ExitProofError::Internal(_) => {
+                vlog::warn!("Failed to generate the exit proof: {}", e);
+                actix_web::error::ErrorInternalServerError(e)
+            }
+        })?;
+    Ok(HttpResponse::Ok().json(proof))
+}
+
+/// Runs the HTTP server of the exit proofs until it's stopped.
+pub async fn run_exit_proof_server(
+    config: ExitProofApiConfig,
+    connection_pool: ConnectionPool,
+) -> std::io::Result<()> {
+    let generator = Arc::new(ExitProofGenerator::new(
+        connection_pool,
+        config.max_cached_proofs,
+    ));
+    let secret_auth = config.secret_auth.clone();
+
+    HttpServer::new(move || {
+        let app_state = AppState {
+            secret_auth: secret_auth.clone(),
+            generator: generator.clone(),
+        };
+
+        let auth = HttpAuthentication::bearer(move |req, credentials| async {
+            let secret_auth = req
+                .app_data::<web::Data<AppState>>()
+                .expect("failed get AppState upon receipt of the authentication token")
+                .secret_auth
+                .clone();
+            AuthTokenValidator::new(&secret_auth)
+                .validator(req, credentials)
+                .await
+        });
+
+        App::new()
+            .wrap(auth)
+            .app_data(web::Data::new(app_state))
+            .route("/status", web::get().to(status))
+            .route("/exit_proof", web::post().to(exit_proof))
+    })
+    .bind(&config.bind_addr())?
+    .run()
+    .await
+}
@@ -0,0 +1,21 @@
+//! Exit proof server.
+//!
+//! Generates the exit proofs of the account balances against the last verified state,
+//! so the users can withdraw their funds once the network enters the exodus mode.
+
+use zksync_config::configs::api::ExitProofApiConfig;
+use zksync_exit_proof_server::run_exit_proof_server;
+use zksync_storage::ConnectionPool;
+
+fn main() -> anyhow::Result<()> {
+    let _vlog_guard = vlog::init();
+    vlog::info!("Running the exit proof server");
+
+    let config = ExitProofApiConfig::from_env();
+    actix_rt::System::new().block_on(async move {
+        let connection_pool = ConnectionPool::new(Some(2));
+        run_exit_proof_server(config, connection_pool).await
+    })?;
+
+    Ok(())
+}
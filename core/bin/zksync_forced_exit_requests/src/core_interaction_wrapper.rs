@@ -146,7 +146,7 @@ impl CoreInteractionWrapper for MempoolCoreInteractionWrapper {
         let hashes: Vec<TxHash> = txs.iter().map(|tx| tx.hash()).collect();
 
         let (sender, receiver) = oneshot::channel();
-        let item = MempoolTransactionRequest::NewTxsBatch(txs, vec![], sender);
+        let item = MempoolTransactionRequest::NewTxsBatch(txs, vec![], vec![], sender);
         self.mempool_tx_sender.send(item).await?;
         receiver.await??;
         schema
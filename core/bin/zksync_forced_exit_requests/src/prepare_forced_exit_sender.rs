@@ -203,7 +203,7 @@ pub async fn register_signing_key(
     let tx_hash = tx.hash();
 
     let (sender, receiver) = oneshot::channel();
-    let item = MempoolTransactionRequest::NewTx(Box::new(tx.into()), sender);
+    let item = MempoolTransactionRequest::NewTx(Box::new(tx.into()), false, sender);
 
     mempool_tx_sender
         .send(item)
@@ -0,0 +1,98 @@
+//! Tool for managing the authentication tokens of the prover machines.
+//!
+//! The prover server accepts the tokens once `API_PROVER_PER_PROVER_AUTH` is enabled.
+//! Tokens are checked on every request, so the revoked prover is isolated at once:
+//! its jobs are returned to the queue and it can't take new ones. The audit trail shows
+//! which prover was assigned which job and when it published the proof.
+
+use structopt::StructOpt;
+use zksync_prover_utils::prover_tokens::{generate_prover_token, prover_token_hash};
+use zksync_storage::StorageProcessor;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zkSync prover tokens tool", author = "Matter Labs")]
+#[structopt(about = "Tool for managing the authentication tokens of the provers")]
+enum Opt {
+    /// Issues a new token for the prover, the previous token of the prover stops working.
+    Issue { prover_name: String },
+    /// Revokes the token of the prover and returns its jobs to the queue.
+    Revoke { prover_name: String },
+    /// Lists the provers the tokens were issued to.
+    List,
+    /// Shows the latest job assignments.
+    Audit {
+        /// Show only the jobs of the given prover.
+        #[structopt(long)]
+        prover_name: Option<String>,
+        #[structopt(long, default_value = "20")]
+        limit: u32,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+    let mut storage = StorageProcessor::establish_connection().await?;
+
+    match opt {
+        Opt::Issue { prover_name } => {
+            let token = generate_prover_token();
+            storage
+                .prover_schema()
+                .store_prover_token(&prover_name, &prover_token_hash(&token))
+                .await?;
+            println!("Token of the prover '{}': {}", prover_name, token);
+            println!("Set it to the `PROVER_AUTH_TOKEN` variable of the prover machine");
+        }
+        Opt::Revoke { prover_name } => {
+            if storage
+                .prover_schema()
+                .revoke_prover_token(&prover_name)
+                .await?
+            {
+                println!("Token of the prover '{}' is revoked", prover_name);
+            } else {
+                println!("Prover '{}' has no active token", prover_name);
+            }
+        }
+        Opt::List => {
+            for token in storage.prover_schema().prover_tokens().await? {
+                let status = match token.revoked_at {
+                    Some(revoked_at) => format!("revoked at {}", revoked_at),
+                    None => "active".to_string(),
+                };
+                println!(
+                    "{}: issued at {}, {}",
+                    token.prover_name, token.created_at, status
+                );
+            }
+        }
+        Opt::Audit { prover_name, limit } => {
+            let records = storage
+                .prover_schema()
+                .prover_job_audit(prover_name.as_deref(), limit)
+                .await?;
+            for record in records {
+                let published = match (record.published_at, record.published_by) {
+                    (Some(published_at), Some(published_by)) => format!(
+                        "published by '{}' in {} s",
+                        published_by,
+                        (published_at - record.assigned_at).num_seconds()
+                    ),
+                    _ => "not published".to_string(),
+                };
+                println!(
+                    "job {} (blocks [{}, {}]): assigned to '{}' at {}, {}",
+                    record.job_id,
+                    record.first_block,
+                    record.last_block,
+                    record.prover_name,
+                    record.assigned_at,
+                    published
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -5,13 +5,20 @@
 
 // Built-in
 use std::clone::Clone;
+use std::time::Duration;
 // Workspace uses
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
-use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_object_store::ArtifactStore;
+use zksync_storage::{
+    prover::records::StorageQuarantinedProverJob, ConnectionPool, StorageProcessor,
+};
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     block::Block,
-    prover::{ProverJob, ProverJobType},
+    prover::{
+        ProofRejectionCause, ProverJob, ProverJobType, ProverQueueStats, ProverWorkClass,
+        ProverWorkClassQueue, StoredArtifact,
+    },
     AccountMap, AccountUpdates, BlockNumber,
 };
 // Local uses
@@ -19,17 +26,62 @@ use crate::DatabaseInterface;
 
 const NUMBER_OF_STORED_ACCOUNT_TREE_CACHE: u32 = 400;
 
+fn report_quarantined_job(job: &StorageQuarantinedProverJob) {
+    vlog::error!(
+        "Prover job {} ({}) for blocks [{}, {}] is quarantined after {} attempts, \
+        last prover: '{}', reason: {}",
+        job.job_id,
+        job.job_type,
+        job.first_block,
+        job.last_block,
+        job.attempts,
+        job.last_prover,
+        job.reason
+    );
+}
+
 /// The actual database wrapper.
 /// This structure uses `StorageProcessor` to interact with an existing database.
 #[derive(Debug, Clone)]
 pub struct Database {
     /// Connection to the database.
     db_pool: ConnectionPool,
+    /// Storage of the witnesses and proofs, which may be kept outside of the database.
+    artifact_store: ArtifactStore,
 }
 
 impl Database {
-    pub fn new(db_pool: ConnectionPool) -> Self {
-        Self { db_pool }
+    pub fn new(db_pool: ConnectionPool, artifact_store: ArtifactStore) -> Self {
+        Self {
+            db_pool,
+            artifact_store,
+        }
+    }
+
+    /// Removes the object uploaded for the witness or proof which failed to be stored.
+    /// Object keys are reused for the same blocks, so the object is kept if it's referenced
+    /// by the record stored before.
+    async fn remove_orphaned_artifact<T>(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        artifact: &StoredArtifact<T>,
+    ) {
+        let key = match artifact {
+            StoredArtifact::Object(key) => key,
+            StoredArtifact::Inline(_) => return,
+        };
+        let result = match connection.prover_schema().is_artifact_referenced(key).await {
+            Ok(true) => Ok(()),
+            Ok(false) => self.artifact_store.remove(key).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            vlog::warn!(
+                "Failed to remove the orphaned prover artifact {}: {}",
+                key,
+                err
+            );
+        }
     }
 }
 
@@ -54,12 +106,24 @@ impl DatabaseInterface for Database {
         Ok(result)
     }
 
+    async fn load_first_unproven_block(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<BlockNumber> {
+        let result = connection.prover_schema().first_unproven_block().await?;
+
+        Ok(result)
+    }
+
     async fn load_witness(
         &self,
         connection: &mut StorageProcessor<'_>,
         block_number: BlockNumber,
     ) -> anyhow::Result<Option<serde_json::Value>> {
-        let witness = connection.prover_schema().get_witness(block_number).await?;
+        let witness = match connection.prover_schema().get_witness(block_number).await? {
+            Some(witness) => Some(self.artifact_store.load(witness).await?),
+            None => None,
+        };
 
         Ok(witness)
     }
@@ -101,7 +165,10 @@ impl DatabaseInterface for Database {
         connection: &mut StorageProcessor<'_>,
         block_number: BlockNumber,
     ) -> anyhow::Result<Option<SingleProof>> {
-        let proof = connection.prover_schema().load_proof(block_number).await?;
+        let proof = match connection.prover_schema().load_proof(block_number).await? {
+            Some(proof) => Some(self.artifact_store.load(proof).await?),
+            None => None,
+        };
 
         Ok(proof)
     }
@@ -109,8 +176,52 @@ impl DatabaseInterface for Database {
     async fn mark_stale_jobs_as_idle(
         &self,
         connection: &mut StorageProcessor<'_>,
+        heartbeat_timeout: Duration,
+        max_attempts: u32,
     ) -> anyhow::Result<()> {
-        connection.prover_schema().mark_stale_jobs_as_idle().await?;
+        let quarantined_jobs = connection
+            .prover_schema()
+            .mark_stale_jobs_as_idle(heartbeat_timeout, max_attempts)
+            .await?;
+        for job in quarantined_jobs {
+            report_quarantined_job(&job);
+        }
+
+        Ok(())
+    }
+
+    async fn reject_proof(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+        prover_name: Option<&str>,
+        cause: ProofRejectionCause,
+        reason: &str,
+        max_attempts: u32,
+    ) -> anyhow::Result<()> {
+        let quarantined_job = connection
+            .prover_schema()
+            .reject_proof(job_id, prover_name, cause, reason, max_attempts)
+            .await?;
+        if let Some(job) = quarantined_job {
+            report_quarantined_job(&job);
+        }
+
+        Ok(())
+    }
+
+    async fn move_jobs_to_urgent_lane(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        urgent_job_timeout: Duration,
+    ) -> anyhow::Result<()> {
+        let moved_jobs = connection
+            .prover_schema()
+            .move_jobs_to_urgent_lane(urgent_job_timeout)
+            .await?;
+        if moved_jobs > 0 {
+            vlog::info!("{} prover jobs are moved to the urgent lane", moved_jobs);
+        }
 
         Ok(())
     }
@@ -151,13 +262,23 @@ impl DatabaseInterface for Database {
         Ok(tree_cache)
     }
 
-    async fn load_idle_prover_job_from_job_queue(
+    async fn load_idle_jobs_by_work_class(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<ProverWorkClassQueue>> {
+        let queues = connection.prover_schema().idle_jobs_by_work_class().await?;
+
+        Ok(queues)
+    }
+
+    async fn load_idle_prover_job_of_work_class(
         &self,
         connection: &mut StorageProcessor<'_>,
+        work_class: ProverWorkClass,
     ) -> anyhow::Result<Option<ProverJob>> {
         let proof = connection
             .prover_schema()
-            .get_idle_prover_job_from_job_queue()
+            .get_idle_prover_job_of_work_class(work_class)
             .await?;
 
         Ok(proof)
@@ -184,10 +305,18 @@ impl DatabaseInterface for Database {
         block_number: BlockNumber,
         proof: &SingleProof,
     ) -> anyhow::Result<()> {
-        connection
-            .prover_schema()
-            .store_proof(job_id, block_number, proof)
+        let proof = self
+            .artifact_store
+            .save(self.artifact_store.proof_key(block_number), proof)
             .await?;
+        if let Err(err) = connection
+            .prover_schema()
+            .store_proof(job_id, block_number, proof.clone())
+            .await
+        {
+            self.remove_orphaned_artifact(connection, &proof).await;
+            return Err(err.into());
+        }
 
         Ok(())
     }
@@ -200,10 +329,22 @@ impl DatabaseInterface for Database {
         last_block: BlockNumber,
         proof: &AggregatedProof,
     ) -> anyhow::Result<()> {
-        connection
-            .prover_schema()
-            .store_aggregated_proof(job_id, first_block, last_block, proof)
+        let proof = self
+            .artifact_store
+            .save(
+                self.artifact_store
+                    .aggregated_proof_key(first_block, last_block),
+                proof,
+            )
             .await?;
+        if let Err(err) = connection
+            .prover_schema()
+            .store_aggregated_proof(job_id, first_block, last_block, proof.clone())
+            .await
+        {
+            self.remove_orphaned_artifact(connection, &proof).await;
+            return Err(err.into());
+        }
 
         Ok(())
     }
@@ -212,11 +353,15 @@ impl DatabaseInterface for Database {
         &self,
         connection: &mut StorageProcessor<'_>,
         prover_name: &str,
+        max_attempts: u32,
     ) -> anyhow::Result<()> {
-        connection
+        let quarantined_jobs = connection
             .prover_schema()
-            .record_prover_stop(prover_name)
+            .record_prover_stop(prover_name, max_attempts)
             .await?;
+        for job in quarantined_jobs {
+            report_quarantined_job(&job);
+        }
 
         Ok(())
     }
@@ -279,10 +424,23 @@ impl DatabaseInterface for Database {
         block: BlockNumber,
         witness: serde_json::Value,
     ) -> anyhow::Result<()> {
-        connection
+        let witness = self
+            .artifact_store
+            .save(self.artifact_store.witness_key(block), witness)
+            .await?;
+        // Only the object key is kept, the inline witness may be large.
+        let object = match &witness {
+            StoredArtifact::Object(key) => StoredArtifact::Object(key.clone()),
+            StoredArtifact::Inline(_) => StoredArtifact::Inline(()),
+        };
+        if let Err(err) = connection
             .prover_schema()
             .store_witness(block, witness)
-            .await?;
+            .await
+        {
+            self.remove_orphaned_artifact(connection, &object).await;
+            return Err(err.into());
+        }
 
         Ok(())
     }
@@ -295,4 +453,67 @@ impl DatabaseInterface for Database {
 
         Ok(count)
     }
+
+    async fn load_prover_queue_stats(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<ProverQueueStats> {
+        let stats = connection.prover_schema().prover_queue_stats().await?;
+
+        Ok(stats)
+    }
+
+    async fn load_prover_name_by_token(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        token_hash: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let prover_name = connection
+            .prover_schema()
+            .prover_name_by_token_hash(token_hash)
+            .await?;
+
+        Ok(prover_name)
+    }
+
+    async fn record_prover_job_assignment(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+        prover_name: &str,
+    ) -> anyhow::Result<()> {
+        connection
+            .prover_schema()
+            .record_prover_job_assignment(job_id, prover_name)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn record_prover_job_published(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+        published_by: Option<&str>,
+    ) -> anyhow::Result<()> {
+        connection
+            .prover_schema()
+            .record_prover_job_published(job_id, published_by)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn load_job_assignee(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+    ) -> anyhow::Result<Option<String>> {
+        let prover_name = connection
+            .prover_schema()
+            .prover_job_assignee(job_id)
+            .await?;
+
+        Ok(prover_name)
+    }
 }
@@ -6,13 +6,17 @@
 // Built-in
 use std::clone::Clone;
 use std::marker::{Send, Sync};
+use std::time::Duration;
 // Workspace uses
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
 use zksync_storage::StorageProcessor;
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
 use zksync_types::{
     block::Block,
-    prover::{ProverJob, ProverJobType},
+    prover::{
+        ProofRejectionCause, ProverJob, ProverJobType, ProverQueueStats, ProverWorkClass,
+        ProverWorkClassQueue,
+    },
     AccountMap, AccountUpdates, BlockNumber,
 };
 
@@ -29,6 +33,12 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         job_type: ProverJobType,
     ) -> anyhow::Result<BlockNumber>;
 
+    /// Returns the oldest block which single proof is not stored yet.
+    async fn load_first_unproven_block(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<BlockNumber>;
+
     /// Returns stored witness for a block.
     async fn load_witness(
         &self,
@@ -59,9 +69,33 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         block_number: BlockNumber,
     ) -> anyhow::Result<Option<SingleProof>>;
 
+    /// Returns the jobs of the provers that didn't send heartbeats for `heartbeat_timeout` to the queue,
+    /// or quarantines them if they were assigned `max_attempts` times already.
     async fn mark_stale_jobs_as_idle(
         &self,
         connection: &mut StorageProcessor<'_>,
+        heartbeat_timeout: Duration,
+        max_attempts: u32,
+    ) -> anyhow::Result<()>;
+
+    /// Records the proof of the job that failed the verification (or the failure to prove the job)
+    /// and returns the job to the queue, or quarantines it if it was attempted `max_attempts`
+    /// times already.
+    async fn reject_proof(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+        prover_name: Option<&str>,
+        cause: ProofRejectionCause,
+        reason: &str,
+        max_attempts: u32,
+    ) -> anyhow::Result<()>;
+
+    /// Moves the idle jobs waiting for longer than `urgent_job_timeout` to the urgent lane.
+    async fn move_jobs_to_urgent_lane(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        urgent_job_timeout: Duration,
     ) -> anyhow::Result<()>;
 
     async fn load_last_verified_block(
@@ -80,9 +114,17 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         connection: &mut StorageProcessor<'_>,
     ) -> anyhow::Result<Option<(BlockNumber, Vec<u8>)>>;
 
-    async fn load_idle_prover_job_from_job_queue(
+    /// Returns the idle jobs grouped by the work class.
+    async fn load_idle_jobs_by_work_class(
         &self,
         connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<ProverWorkClassQueue>>;
+
+    /// Takes the idle job of the given work class with the highest priority.
+    async fn load_idle_prover_job_of_work_class(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        work_class: ProverWorkClass,
     ) -> anyhow::Result<Option<ProverJob>>;
 
     async fn record_prover_is_working(
@@ -109,10 +151,13 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         proof: &AggregatedProof,
     ) -> anyhow::Result<()>;
 
+    /// Returns the jobs of the stopped prover to the queue, or quarantines them if they
+    /// were attempted `max_attempts` times already.
     async fn record_prover_stop(
         &self,
         connection: &mut StorageProcessor<'_>,
         prover_name: &str,
+        max_attempts: u32,
     ) -> anyhow::Result<()>;
 
     async fn load_committed_state(
@@ -146,4 +191,38 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         &self,
         connection: &mut StorageProcessor<'_>,
     ) -> anyhow::Result<u32>;
+
+    async fn load_prover_queue_stats(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<ProverQueueStats>;
+
+    /// Returns the name of the prover the token with the given hash was issued to,
+    /// if the token is not revoked.
+    async fn load_prover_name_by_token(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        token_hash: &str,
+    ) -> anyhow::Result<Option<String>>;
+
+    async fn record_prover_job_assignment(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+        prover_name: &str,
+    ) -> anyhow::Result<()>;
+
+    async fn record_prover_job_published(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+        published_by: Option<&str>,
+    ) -> anyhow::Result<()>;
+
+    /// Returns the name of the prover the job was assigned to the last time.
+    async fn load_job_assignee(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+    ) -> anyhow::Result<Option<String>>;
 }
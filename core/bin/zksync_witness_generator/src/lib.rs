@@ -5,15 +5,14 @@ use std::thread;
 use std::time::Duration;
 // External
 use actix_web::dev::ServiceRequest;
-use actix_web::{web, App, HttpResponse, HttpServer};
+use actix_web::{web, App, HttpMessage, HttpResponse, HttpServer};
 use actix_web_httpauth::extractors::{
     bearer::{BearerAuth, Config},
     AuthenticationError,
 };
 use actix_web_httpauth::middleware::HttpAuthentication;
+use futures::channel::mpsc;
 
-use jsonwebtoken::errors::Error as JwtError;
-use jsonwebtoken::{decode, DecodingKey, Validation};
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 // Workspace deps
@@ -21,21 +20,31 @@ use zksync_config::ProverConfig;
 // Local deps
 use self::database_interface::DatabaseInterface;
 use self::scaler::ScalerOracle;
+use self::stream::{run_stream_server, ProverStreamService};
+use self::work_scheduler::WorkScheduler;
 use tokio::task::JoinHandle;
 use zksync_circuit::serialization::ProverData;
-use zksync_config::configs::api::ProverApiConfig;
+use zksync_config::configs::{
+    api::ProverApiConfig,
+    prover::{Core as CoreProverConfig, WitnessGenerator as WitnessGeneratorConfig},
+};
 use zksync_prover_utils::api::{
-    JobRequestData, JobResultData, ProverInputRequest, ProverInputResponse, ProverOutputRequest,
-    WorkingOn,
+    JobFailed, JobRequestData, JobResultData, ProverInputRequest, ProverInputResponse,
+    ProverOutputRequest, WorkingOn,
+};
+use zksync_prover_utils::proof_verification::{
+    block_proof_input, verify_aggregated_proof, verify_block_proof,
 };
+use zksync_prover_utils::prover_tokens::prover_token_hash;
 use zksync_types::aggregated_operations::{
     AggregatedActionType, AggregatedOperation, BlocksCreateProofOperation,
 };
 use zksync_types::prover::{
-    ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY, SINGLE_PROOF_JOB_PRIORITY,
+    ProofRejectionCause, ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY, SINGLE_PROOF_JOB_PRIORITY,
 };
 use zksync_types::BlockNumber;
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
+use zksync_utils::AuthTokenValidator;
 
 #[cfg(test)]
 mod tests;
@@ -43,25 +52,38 @@ mod tests;
 pub mod database;
 mod database_interface;
 mod scaler;
+mod stream;
 mod witness_generator;
+mod work_scheduler;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct PayloadAuthToken {
-    /// Subject (whom auth token refers to).
-    sub: String,
-    /// Expiration time (as UTC timestamp).
-    exp: usize,
-}
+/// Prefix of the endpoints used by the infrastructure rather than the provers,
+/// they are authenticated with the shared secret even if the per-prover tokens are used.
+const INTERNAL_API_PREFIX: &str = "/api/internal/";
 
 #[derive(Debug, Clone)]
 struct AppState<DB: DatabaseInterface> {
     secret_auth: String,
     database: DB,
     scaler_oracle: Arc<RwLock<ScalerOracle<DB>>>,
+    /// Shared between the server workers, so the work classes are served in turns.
+    work_scheduler: Arc<RwLock<WorkScheduler>>,
+    /// Whether the received proofs are verified before they are stored.
+    verify_proofs: bool,
+    max_job_attempts: u32,
+    /// Whether the provers authenticate with their own tokens.
+    per_prover_auth: bool,
 }
 
 impl<DB: DatabaseInterface> AppState<DB> {
-    pub fn new(secret_auth: String, database: DB, idle_provers: u32) -> Self {
+    pub fn new(
+        secret_auth: String,
+        database: DB,
+        idle_provers: u32,
+        work_scheduler: Arc<RwLock<WorkScheduler>>,
+        verify_proofs: bool,
+        max_job_attempts: u32,
+        per_prover_auth: bool,
+    ) -> Self {
         let scaler_oracle = Arc::new(RwLock::new(ScalerOracle::new(
             database.clone(),
             idle_provers,
@@ -71,9 +93,22 @@ impl<DB: DatabaseInterface> AppState<DB> {
             secret_auth,
             database,
             scaler_oracle,
+            work_scheduler,
+            verify_proofs,
+            max_job_attempts,
+            per_prover_auth,
         }
     }
 
+    /// Returns the name of the prover the token was issued to,
+    /// or `None` if the token is unknown or revoked.
+    async fn authenticate_prover(&self, token: &str) -> anyhow::Result<Option<String>> {
+        let mut storage = self.database.acquire_connection().await?;
+        self.database
+            .load_prover_name_by_token(&mut storage, &prover_token_hash(token))
+            .await
+    }
+
     async fn access_storage(&self) -> actix_web::Result<zksync_storage::StorageProcessor<'_>> {
         self.database.acquire_connection().await.map_err(|e| {
             vlog::warn!("Failed to access storage: {}", e);
@@ -82,71 +117,283 @@ impl<DB: DatabaseInterface> AppState<DB> {
     }
 }
 
-/// The structure that stores the secret key for checking JsonWebToken matching.
-struct AuthTokenValidator<'a> {
-    decoding_key: DecodingKey<'a>,
+/// Prover authenticated with its own token.
+#[derive(Debug, Clone)]
+struct AuthenticatedProver(String);
+
+/// Checks the token issued to the prover and attaches the name of the prover to the request.
+async fn prover_token_validator<DB: DatabaseInterface>(
+    data: &AppState<DB>,
+    req: ServiceRequest,
+    credentials: BearerAuth,
+) -> actix_web::Result<ServiceRequest> {
+    let config = req.app_data::<Config>().cloned().unwrap_or_default();
+
+    let prover_name = data
+        .authenticate_prover(credentials.token())
+        .await
+        .map_err(|e| {
+            vlog::warn!("Failed to check the prover token: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?
+        .ok_or_else(|| AuthenticationError::from(config))?;
+    req.extensions_mut()
+        .insert(AuthenticatedProver(prover_name));
+
+    Ok(req)
 }
 
-impl<'a> AuthTokenValidator<'a> {
-    fn new(secret: &'a str) -> Self {
-        Self {
-            decoding_key: DecodingKey::from_secret(secret.as_ref()),
+/// Checks that the prover authenticated with its own token acts on its own behalf.
+fn check_prover_name(
+    prover: Option<&AuthenticatedProver>,
+    prover_name: &str,
+) -> actix_web::Result<()> {
+    match prover {
+        Some(prover) if prover.0 != prover_name => {
+            vlog::warn!(
+                "Prover '{}' tried to act on behalf of '{}'",
+                prover.0,
+                prover_name
+            );
+            Err(actix_web::error::ErrorForbidden(
+                "prover name doesn't match the token",
+            ))
         }
+        _ => Ok(()),
     }
+}
 
-    /// Checks whether the secret key and the authorization token match.
-    fn validate_auth_token(&self, token: &str) -> Result<(), JwtError> {
-        decode::<PayloadAuthToken>(token, &self.decoding_key, &Validation::default())?;
+async fn status() -> actix_web::Result<String> {
+    Ok("alive".into())
+}
 
-        Ok(())
+/// Assigns the job of the work class supported by the prover, if there is one.
+async fn assign_job<DB: DatabaseInterface>(
+    data: &AppState<DB>,
+    request: &ProverInputRequest,
+) -> anyhow::Result<Option<ProverInputResponse>> {
+    let mut storage = data.database.acquire_connection().await?;
+    let queues = data
+        .database
+        .load_idle_jobs_by_work_class(&mut storage)
+        .await
+        .map_err(|e| anyhow::format_err!("could not load idle prover jobs: {}", e))?;
+    for queue in &queues {
+        metrics::gauge!("prover_server.idle_jobs", queue.idle_jobs as f64, "work_class" => queue.work_class.to_string());
     }
 
-    async fn validator(
-        &self,
-        req: ServiceRequest,
-        credentials: BearerAuth,
-    ) -> actix_web::Result<ServiceRequest> {
-        let config = req.app_data::<Config>().cloned().unwrap_or_default();
+    let work_class = data
+        .work_scheduler
+        .write()
+        .await
+        .choose_work_class(&request.aux_data.work_classes, &queues);
+    let prover_job = match work_class {
+        Some(work_class) => data
+            .database
+            .load_idle_prover_job_of_work_class(&mut storage, work_class)
+            .await
+            .map_err(|e| {
+                anyhow::format_err!("could not get next unverified commit operation: {}", e)
+            })?,
+        None => None,
+    };
+    if let Some(prover_job) = &prover_job {
+        data.database
+            .record_prover_job_assignment(&mut storage, prover_job.job_id, &request.prover_name)
+            .await?;
+    }
 
-        self.validate_auth_token(credentials.token())
-            .map_err(|_| AuthenticationError::from(config))?;
+    Ok(prover_job.map(|prover_job| ProverInputResponse {
+        job_id: prover_job.job_id,
+        first_block: prover_job.first_block,
+        last_block: prover_job.last_block,
+        data: Some(
+            serde_json::from_value(prover_job.job_data)
+                .expect("Failed to parse prover job from db"),
+        ),
+    }))
+}
 
-        Ok(req)
+/// Checks that the received proof is valid and proves the blocks of the job.
+/// Returns the reason of the rejection if the proof is invalid.
+async fn check_received_proof<DB: DatabaseInterface>(
+    data: &AppState<DB>,
+    storage: &mut zksync_storage::StorageProcessor<'_>,
+    r: &ProverOutputRequest,
+) -> anyhow::Result<Option<String>> {
+    let mut blocks = Vec::new();
+    for block_number in *r.first_block..=*r.last_block {
+        let block = data
+            .database
+            .load_block(storage, BlockNumber(block_number))
+            .await?
+            .ok_or_else(|| anyhow::format_err!("block {} is not found", block_number))?;
+        blocks.push(block);
     }
+    let expected_inputs: Vec<_> = blocks.iter().map(block_proof_input).collect();
+
+    // Verification takes milliseconds, so it's done in place.
+    let reason = match &r.data {
+        JobResultData::BlockProof(single_proof) => {
+            if single_proof.0.input_values != expected_inputs {
+                Some("public input doesn't match the block commitment".to_string())
+            } else if !verify_block_proof(single_proof, blocks[0].block_chunks_size)? {
+                Some("proof verification failed".to_string())
+            } else {
+                None
+            }
+        }
+        JobResultData::AggregatedBlockProof(aggregated_proof) => {
+            if aggregated_proof.individual_vk_inputs != expected_inputs {
+                Some("public inputs don't match the block commitments".to_string())
+            } else if !verify_aggregated_proof(aggregated_proof)? {
+                Some("proof verification failed".to_string())
+            } else {
+                None
+            }
+        }
+    };
+    Ok(reason)
 }
 
-async fn status() -> actix_web::Result<String> {
-    Ok("alive".into())
+/// Checks that the job was assigned to the prover the last time, so the provers authenticated
+/// with their own tokens can't publish the proofs or report the failures of the others' jobs.
+async fn is_job_assignee<DB: DatabaseInterface>(
+    data: &AppState<DB>,
+    storage: &mut zksync_storage::StorageProcessor<'_>,
+    job_id: i32,
+    prover_name: &str,
+) -> anyhow::Result<bool> {
+    let assignee = data.database.load_job_assignee(storage, job_id).await?;
+    if assignee.as_deref() != Some(prover_name) {
+        vlog::warn!(
+            "Prover '{}' tried to act on job {} assigned to {:?}",
+            prover_name,
+            job_id,
+            assignee
+        );
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// Stores the proof received from the prover. If the verification of the proofs is enabled,
+/// invalid proofs are rejected and their jobs are returned to the queue.
+async fn store_received_proof<DB: DatabaseInterface>(
+    data: &AppState<DB>,
+    r: &ProverOutputRequest,
+    prover_name: Option<&str>,
+) -> anyhow::Result<()> {
+    let mut storage = data.database.acquire_connection().await?;
+    if let Some(prover_name) = prover_name.filter(|_| data.per_prover_auth) {
+        if !is_job_assignee(data, &mut storage, r.job_id, prover_name).await? {
+            anyhow::bail!("job {} is not assigned to '{}'", r.job_id, prover_name);
+        }
+    }
+    if data.verify_proofs {
+        if let Some(reason) = check_received_proof(data, &mut storage, r).await? {
+            vlog::error!(
+                "Rejected the proof for job: {}, blocks: [{},{}]: {}",
+                r.job_id,
+                r.first_block,
+                r.last_block,
+                reason
+            );
+            metrics::increment_counter!("prover_server.rejected_proofs");
+            data.database
+                .reject_proof(
+                    &mut storage,
+                    r.job_id,
+                    prover_name,
+                    ProofRejectionCause::InvalidProof,
+                    &reason,
+                    data.max_job_attempts,
+                )
+                .await?;
+            anyhow::bail!("invalid proof: {}", reason);
+        }
+    }
+
+    match &r.data {
+        JobResultData::BlockProof(single_proof) => {
+            vlog::info!(
+                "Received a proof for job: {}, single block: {}",
+                r.job_id,
+                r.first_block
+            );
+            data.database
+                .store_proof(&mut storage, r.job_id, r.first_block, single_proof)
+                .await?;
+        }
+        JobResultData::AggregatedBlockProof(aggregated_proof) => {
+            vlog::info!(
+                "Received a proof for job: {}, aggregated blocks: [{},{}]",
+                r.job_id,
+                r.first_block,
+                r.last_block
+            );
+            data.database
+                .store_aggregated_proof(
+                    &mut storage,
+                    r.job_id,
+                    r.first_block,
+                    r.last_block,
+                    aggregated_proof,
+                )
+                .await?;
+        }
+    }
+
+    data.database
+        .record_prover_job_published(&mut storage, r.job_id, prover_name)
+        .await
+}
+
+/// Returns the job the prover failed to prove to the queue, or quarantines it
+/// once it has failed too many times.
+async fn record_job_failure<DB: DatabaseInterface>(
+    data: &AppState<DB>,
+    job_id: i32,
+    prover_name: &str,
+    error: &str,
+) -> anyhow::Result<()> {
+    vlog::warn!(
+        "Prover '{}' failed to prove job {}: {}",
+        prover_name,
+        job_id,
+        error
+    );
+    metrics::increment_counter!("prover_server.failed_jobs");
+    let mut storage = data.database.acquire_connection().await?;
+    data.database
+        .reject_proof(
+            &mut storage,
+            job_id,
+            Some(prover_name),
+            ProofRejectionCause::ProvingFailed,
+            error,
+            data.max_job_attempts,
+        )
+        .await
 }
 
 async fn get_job<DB: DatabaseInterface>(
     data: web::Data<AppState<DB>>,
+    prover: Option<web::ReqData<AuthenticatedProver>>,
     r: web::Json<ProverInputRequest>,
 ) -> actix_web::Result<HttpResponse> {
     vlog::trace!("request block to prove from worker: {}", r.prover_name);
     if r.prover_name.is_empty() {
         return Err(actix_web::error::ErrorBadRequest("empty name"));
     }
-    let mut storage = data.access_storage().await?;
-    let ret = data
-        .database
-        .load_idle_prover_job_from_job_queue(&mut storage)
-        .await
-        .map_err(|e| {
-            vlog::warn!("could not get next unverified commit operation: {}", e);
-            actix_web::error::ErrorInternalServerError("storage layer error")
-        })?;
-    if let Some(prover_job) = ret {
+    check_prover_name(prover.as_deref(), &r.prover_name)?;
+    let ret = assign_job(&data, &r).await.map_err(|e| {
+        vlog::warn!("{}", e);
+        actix_web::error::ErrorInternalServerError("storage layer error")
+    })?;
+    if let Some(response) = ret {
         vlog::info!("satisfied request to prove from worker");
-        Ok(HttpResponse::Ok().json(ProverInputResponse {
-            job_id: prover_job.job_id,
-            first_block: prover_job.first_block,
-            last_block: prover_job.last_block,
-            data: Some(
-                serde_json::from_value(prover_job.job_data)
-                    .expect("Failed to parse prover job from db"),
-            ),
-        }))
+        Ok(HttpResponse::Ok().json(response))
     } else {
         Ok(HttpResponse::Ok().json(ProverInputResponse {
             job_id: 0,
@@ -159,11 +406,13 @@ async fn get_job<DB: DatabaseInterface>(
 
 async fn working_on<DB: DatabaseInterface>(
     data: web::Data<AppState<DB>>,
+    prover: Option<web::ReqData<AuthenticatedProver>>,
     r: web::Json<WorkingOn>,
 ) -> actix_web::Result<HttpResponse> {
     // These heartbeats aren't really important, as they're sent
     // continuously while prover is performing computations.
     vlog::trace!("Received heartbeat for prover_run with id: {}", r.job_id);
+    check_prover_name(prover.as_deref(), &r.prover_name)?;
     let mut storage = data
         .access_storage()
         .await
@@ -181,43 +430,20 @@ async fn working_on<DB: DatabaseInterface>(
 
 async fn publish<DB: DatabaseInterface>(
     data: web::Data<AppState<DB>>,
+    prover: Option<web::ReqData<AuthenticatedProver>>,
     r: web::Json<ProverOutputRequest>,
 ) -> actix_web::Result<HttpResponse> {
-    let mut storage = data
-        .access_storage()
-        .await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
-    let storage_result = match &r.data {
-        JobResultData::BlockProof(single_proof) => {
-            vlog::info!(
-                "Received a proof for job: {}, single block: {}",
-                r.job_id,
-                r.first_block
-            );
-            data.database
-                .store_proof(&mut storage, r.job_id, r.first_block, single_proof)
-                .await
+    let prover_name = prover.as_deref().map(|prover| prover.0.as_str());
+    if let Err(e) = store_received_proof(&data, &r, prover_name).await {
+        vlog::error!("failed to store received proof: {}", e);
+        if e.to_string().starts_with("invalid proof") {
+            return Err(actix_web::error::ErrorBadRequest("invalid proof"));
         }
-        JobResultData::AggregatedBlockProof(aggregated_proof) => {
-            vlog::info!(
-                "Received a proof for job: {}, aggregated blocks: [{},{}]",
-                r.job_id,
-                r.first_block,
-                r.last_block
-            );
-            data.database
-                .store_aggregated_proof(
-                    &mut storage,
-                    r.job_id,
-                    r.first_block,
-                    r.last_block,
-                    aggregated_proof,
-                )
-                .await
+        if e.to_string().contains("is not assigned to") {
+            return Err(actix_web::error::ErrorForbidden(
+                "job is not assigned to the prover",
+            ));
         }
-    };
-    if let Err(e) = storage_result {
-        vlog::error!("failed to store received proof: {}", e);
         let message = if e.to_string().contains("duplicate key") {
             "duplicate key"
         } else {
@@ -229,10 +455,42 @@ async fn publish<DB: DatabaseInterface>(
     Ok(HttpResponse::Ok().finish())
 }
 
+async fn job_failed<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+    prover: Option<web::ReqData<AuthenticatedProver>>,
+    r: web::Json<JobFailed>,
+) -> actix_web::Result<HttpResponse> {
+    check_prover_name(prover.as_deref(), &r.prover_name)?;
+    if prover.is_some() {
+        let mut storage = data.access_storage().await?;
+        let is_assignee = is_job_assignee(&data, &mut storage, r.job_id, &r.prover_name)
+            .await
+            .map_err(|e| {
+                vlog::warn!("failed to load the job assignee: {}", e);
+                actix_web::error::ErrorInternalServerError("storage layer error")
+            })?;
+        if !is_assignee {
+            return Err(actix_web::error::ErrorForbidden(
+                "job is not assigned to the prover",
+            ));
+        }
+    }
+    record_job_failure(&data, r.job_id, &r.prover_name, &r.error)
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to record the job failure: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
 async fn stopped<DB: DatabaseInterface>(
     data: web::Data<AppState<DB>>,
+    prover: Option<web::ReqData<AuthenticatedProver>>,
     prover_name: web::Json<String>,
 ) -> actix_web::Result<HttpResponse> {
+    check_prover_name(prover.as_deref(), &prover_name)?;
     let mut storage = data
         .access_storage()
         .await
@@ -244,7 +502,7 @@ async fn stopped<DB: DatabaseInterface>(
     );
 
     data.database
-        .record_prover_stop(&mut storage, &prover_name)
+        .record_prover_stop(&mut storage, &prover_name, data.max_job_attempts)
         .await
         .map_err(|e| {
             vlog::warn!("failed to record prover stop: {}", e);
@@ -285,9 +543,25 @@ async fn required_replicas<DB: DatabaseInterface>(
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Reports the load of the prover job queue and the estimated amount of the required provers.
+async fn prover_capacity<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+) -> actix_web::Result<HttpResponse> {
+    let capacity = data
+        .scaler_oracle
+        .write()
+        .await
+        .capacity()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(capacity))
+}
+
 async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
     database: DB,
     prepare_data_interval: Duration,
+    core_opts: CoreProverConfig,
 ) {
     // We use `prepare_data_interval` as timeout in this function to align creating prover jobs
     // with witness generator routine.
@@ -296,7 +570,7 @@ async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
     loop {
         interval.tick().await;
 
-        update_prover_job_queue(database.clone())
+        update_prover_job_queue(database.clone(), &core_opts)
             .await
             .unwrap_or_else(|e| {
                 vlog::warn!("Failed to update prover job queue: {}", e);
@@ -304,17 +578,22 @@ async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
     }
 }
 
-async fn update_prover_job_queue<DB: DatabaseInterface>(database: DB) -> anyhow::Result<()> {
+async fn update_prover_job_queue<DB: DatabaseInterface>(
+    database: DB,
+    core_opts: &CoreProverConfig,
+) -> anyhow::Result<()> {
     let mut connection = database.acquire_connection().await?;
     {
-        let next_single_block_to_add = database
+        let mut next_single_block_to_add = database
             .load_last_block_prover_job_queue(&mut connection, ProverJobType::SingleProof)
             .await?
             + 1;
-        let witness_for_next_single_block = database
+        // Jobs are added for all the consecutive blocks with the witnesses, so the witnesses
+        // generated ahead are picked up by the provers at once.
+        while let Some(witness) = database
             .load_witness(&mut connection, next_single_block_to_add)
-            .await?;
-        if let Some(witness) = witness_for_next_single_block {
+            .await?
+        {
             let prover_data: ProverData =
                 serde_json::from_value(witness).expect("incorrect single block witness");
             let block_size = prover_data.operations.len();
@@ -331,6 +610,7 @@ async fn update_prover_job_queue<DB: DatabaseInterface>(database: DB) -> anyhow:
                     ProverJobType::SingleProof,
                 )
                 .await?;
+            next_single_block_to_add = next_single_block_to_add + 1;
         }
     }
 
@@ -382,11 +662,78 @@ async fn update_prover_job_queue<DB: DatabaseInterface>(database: DB) -> anyhow:
                 .await?;
         }
     }
-    database.mark_stale_jobs_as_idle(&mut connection).await?;
+    database
+        .mark_stale_jobs_as_idle(
+            &mut connection,
+            core_opts.gone_timeout(),
+            core_opts.max_job_attempts,
+        )
+        .await?;
+    database
+        .move_jobs_to_urgent_lane(&mut connection, core_opts.urgent_job_timeout())
+        .await?;
 
     Ok(())
 }
 
+/// Starts the witness generator threads, the witnesses are prepared for the blocks
+/// following the last verified one.
+async fn start_witness_generators<DB: DatabaseInterface>(
+    database: DB,
+    witness_generator_opts: &WitnessGeneratorConfig,
+    panic_sender: mpsc::Sender<bool>,
+) {
+    let last_verified_block = {
+        let mut storage = database
+            .acquire_connection()
+            .await
+            .expect("Failed to access storage");
+
+        *database
+            .load_last_verified_block(&mut storage)
+            .await
+            .expect("Failed to get last verified block number") as usize
+    };
+
+    // Start pool maintainer threads.
+    let cache = Arc::new(RwLock::new(BTreeMap::default()));
+
+    for offset in 0..witness_generator_opts.witness_generators {
+        let start_block = (last_verified_block + offset + 1) as u32;
+        let block_step = witness_generator_opts.witness_generators as u32;
+        vlog::info!(
+            "Starting witness generator ({},{})",
+            start_block,
+            block_step
+        );
+        let start_wait = witness_generator_opts.prepare_data_interval() * offset as u32;
+        let pool_maintainer = witness_generator::WitnessGenerator::new(
+            database.clone(),
+            witness_generator_opts.prepare_data_interval(),
+            start_wait,
+            BlockNumber(start_block),
+            BlockNumber(block_step),
+            cache.clone(),
+            witness_generator_opts.max_witnesses_in_flight,
+        );
+        pool_maintainer.start(panic_sender.clone());
+    }
+}
+
+/// Runs the witness generators without the prover server, so the witnesses are prepared by
+/// a separate service which can be scaled independently of the server. The prover server
+/// picks the stored witnesses up and creates the prover jobs for them.
+///
+/// The returned handle is resolved once one of the witness generators panics.
+pub async fn run_witness_generator_service<DB: DatabaseInterface>(
+    database: DB,
+    witness_generator_opts: WitnessGeneratorConfig,
+) -> JoinHandle<()> {
+    let (handler, panic_sender) = spawn_panic_handler();
+    start_witness_generators(database, &witness_generator_opts, panic_sender).await;
+    handler
+}
+
 pub fn run_prover_server<DB: DatabaseInterface>(
     database: DB,
     prover_api_opts: ProverApiConfig,
@@ -406,59 +753,71 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                 tokio::spawn(update_prover_job_queue_loop(
                     database.clone(),
                     witness_generator_opts.prepare_data_interval(),
+                    core_opts.clone(),
                 ));
 
-                let last_verified_block = {
-                    let mut storage = database
-                        .acquire_connection()
-                        .await
-                        .expect("Failed to access storage");
-
-                    *database
-                        .load_last_verified_block(&mut storage)
-                        .await
-                        .expect("Failed to get last verified block number")
-                        as usize
-                };
-
-                // Start pool maintainer threads.
-                let cache = Arc::new(RwLock::new(BTreeMap::default()));
-
-                for offset in 0..witness_generator_opts.witness_generators {
-                    let start_block = (last_verified_block + offset + 1) as u32;
-                    let block_step = witness_generator_opts.witness_generators as u32;
-                    vlog::info!(
-                        "Starting witness generator ({},{})",
-                        start_block,
-                        block_step
-                    );
-                    let start_wait = witness_generator_opts.prepare_data_interval() * offset as u32;
-                    let pool_maintainer = witness_generator::WitnessGenerator::new(
+                if witness_generator_opts.standalone {
+                    vlog::info!("Witnesses are generated by the standalone witness generator");
+                } else {
+                    start_witness_generators(
                         database.clone(),
-                        witness_generator_opts.prepare_data_interval(),
-                        start_wait,
-                        BlockNumber(start_block),
-                        BlockNumber(block_step),
-                        cache.clone(),
-                    );
-                    pool_maintainer.start(panic_sender.clone());
+                        &witness_generator_opts,
+                        panic_sender.clone(),
+                    )
+                    .await;
                 }
-                // Start HTTP server.
                 let secret_auth = prover_api_opts.secret_auth.clone();
                 let idle_provers = core_opts.idle_provers;
+                let verify_proofs = core_opts.verify_proofs;
+                let max_job_attempts = core_opts.max_job_attempts;
+                let per_prover_auth = prover_api_opts.per_prover_auth;
+                let work_scheduler = Arc::new(RwLock::new(WorkScheduler::new()));
+
+                // Start the job stream server, jobs are added to the queue
+                // with the same interval as the witnesses are prepared.
+                let stream_service = ProverStreamService::new(
+                    AppState::new(
+                        secret_auth.clone(),
+                        database.clone(),
+                        idle_provers,
+                        work_scheduler.clone(),
+                        verify_proofs,
+                        max_job_attempts,
+                        per_prover_auth,
+                    ),
+                    witness_generator_opts.prepare_data_interval(),
+                    core_opts.gone_timeout(),
+                )
+                .with_compression_level(core_opts.compression_level);
+                tokio::spawn(run_stream_server(
+                    stream_service,
+                    prover_api_opts.stream_bind_addr(),
+                ));
+
+                // Start HTTP server.
                 HttpServer::new(move || {
-                    let app_state =
-                        AppState::new(secret_auth.clone(), database.clone(), idle_provers);
+                    let app_state = AppState::new(
+                        secret_auth.clone(),
+                        database.clone(),
+                        idle_provers,
+                        work_scheduler.clone(),
+                        verify_proofs,
+                        max_job_attempts,
+                        per_prover_auth,
+                    );
 
                     let auth = HttpAuthentication::bearer(move |req, credentials| async {
-                        let secret_auth = req
+                        let data = req
                             .app_data::<web::Data<AppState<DB>>>()
                             .expect("failed get AppState upon receipt of the authentication token")
-                            .secret_auth
                             .clone();
-                        AuthTokenValidator::new(&secret_auth)
-                            .validator(req, credentials)
-                            .await
+                        if data.per_prover_auth && !req.path().starts_with(INTERNAL_API_PREFIX) {
+                            prover_token_validator(&data, req, credentials).await
+                        } else {
+                            AuthTokenValidator::new(&data.secret_auth)
+                                .validator(req, credentials)
+                                .await
+                        }
                     });
 
                     // By calling `register_data` instead of `data` we're avoiding double
@@ -470,11 +829,16 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                         .route("/get_job", web::get().to(get_job::<DB>))
                         .route("/working_on", web::post().to(working_on::<DB>))
                         .route("/publish", web::post().to(publish::<DB>))
+                        .route("/job_failed", web::post().to(job_failed::<DB>))
                         .route("/stopped", web::post().to(stopped::<DB>))
                         .route(
                             "/api/internal/prover/replicas",
                             web::post().to(required_replicas::<DB>),
                         )
+                        .route(
+                            "/api/internal/prover/capacity",
+                            web::get().to(prover_capacity::<DB>),
+                        )
                 })
                 .bind(&prover_api_opts.bind_addr())
                 .expect("failed to bind")
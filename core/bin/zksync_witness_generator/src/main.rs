@@ -0,0 +1,41 @@
+//! Standalone witness generator service.
+//!
+//! Prepares the witnesses for the committed blocks and stores them (in the database or in the
+//! object storage, depending on the artifacts store configuration). The prover server should be
+//! configured with `PROVER_WITNESS_GENERATOR_STANDALONE=true`, so it doesn't generate the
+//! witnesses itself and only creates the prover jobs for the stored ones.
+
+use zksync_config::configs::api::PrometheusConfig;
+use zksync_config::{DBConfig, ObjectStoreConfig, ProverConfig};
+use zksync_object_store::ArtifactStore;
+use zksync_prometheus_exporter::run_prometheus_exporter;
+use zksync_storage::ConnectionPool;
+use zksync_witness_generator::{database::Database, run_witness_generator_service};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let _vlog_guard = vlog::init();
+    vlog::info!("Running the standalone witness generator");
+
+    let prover_config = ProverConfig::from_env();
+    let artifact_store = ArtifactStore::from_config(&ObjectStoreConfig::from_env())
+        .expect("Failed to create the prover artifacts store");
+    let connection_pool =
+        ConnectionPool::new(None).with_compression_level(DBConfig::from_env().compression_level);
+    let database = Database::new(connection_pool, artifact_store);
+
+    run_prometheus_exporter(PrometheusConfig::from_env().port);
+    let panic_handler =
+        run_witness_generator_service(database, prover_config.witness_generator).await;
+
+    tokio::select! {
+        _ = panic_handler => {
+            anyhow::bail!("One of the witness generators panicked");
+        }
+        _ = tokio::signal::ctrl_c() => {
+            vlog::info!("Stop signal received, shutting down");
+        }
+    }
+
+    Ok(())
+}
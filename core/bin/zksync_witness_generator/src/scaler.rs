@@ -1,7 +1,47 @@
 //! Module with utilities for prover scaler service.
 
+// External deps
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 // Workspace deps
 use crate::database_interface::DatabaseInterface;
+use zksync_types::{prover::ProverQueueStats, BlockNumber};
+
+/// Capacity report of the prover fleet, designed to be consumed by the autoscaler.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProverCapacity {
+    /// Amount of the jobs waiting for a prover.
+    pub idle_jobs: u32,
+    /// Amount of the jobs being proven at the moment.
+    pub in_progress_jobs: u32,
+    /// The first block of the oldest job which is not proven yet.
+    pub oldest_unproven_block: Option<BlockNumber>,
+    /// Age of the oldest job which is not proven yet in seconds.
+    pub oldest_unproven_block_age: Option<u64>,
+    /// Estimated amount of the provers required for server to operate optimally.
+    pub required_provers: u32,
+}
+
+impl ProverCapacity {
+    pub fn from_stats(stats: ProverQueueStats, idle_provers: u32, now: DateTime<Utc>) -> Self {
+        // Every pending job requires a prover, and there should be at least `idle_provers`
+        // running for faster up-scaling.
+        let required_provers =
+            std::cmp::max(stats.idle_jobs + stats.in_progress_jobs, idle_provers);
+        let oldest_unproven_block_age = stats
+            .oldest_unproven_block_created_at
+            .map(|created_at| (now - created_at).num_seconds().max(0) as u64);
+
+        Self {
+            idle_jobs: stats.idle_jobs,
+            in_progress_jobs: stats.in_progress_jobs,
+            oldest_unproven_block: stats.oldest_unproven_block,
+            oldest_unproven_block_age,
+            required_provers,
+        }
+    }
+}
+
 /// Scaler oracle provides information for prover scaler
 /// service about required amount of provers for server
 /// to operate optimally.
@@ -31,4 +71,26 @@ impl<DB: DatabaseInterface> ScalerOracle<DB> {
 
         Ok(provers_required)
     }
+
+    /// Reports the load of the prover job queue along with the amount of the required provers.
+    pub async fn capacity(&mut self) -> anyhow::Result<ProverCapacity> {
+        let mut storage = self.db.acquire_connection().await?;
+        let stats = self.db.load_prover_queue_stats(&mut storage).await?;
+        let capacity = ProverCapacity::from_stats(stats, self.idle_provers, Utc::now());
+
+        metrics::gauge!("prover_server.idle_jobs_total", capacity.idle_jobs as f64);
+        metrics::gauge!(
+            "prover_server.in_progress_jobs",
+            capacity.in_progress_jobs as f64
+        );
+        metrics::gauge!(
+            "prover_server.oldest_unproven_block_age",
+            capacity.oldest_unproven_block_age.unwrap_or_default() as f64
+        );
+        metrics::gauge!(
+            "prover_server.required_provers",
+            capacity.required_provers as f64
+        );
+        Ok(capacity)
+    }
 }
@@ -0,0 +1,406 @@
+//! Server side of the job stream, see `zksync_prover_utils::stream` for the protocol description.
+
+// Built-in
+use std::net::SocketAddr;
+use std::time::Duration;
+// External
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{transport::Server, Request, Response, Status, Streaming};
+// Workspace deps
+use zksync_prover_utils::api::{ProverInputRequest, ProverOutputRequest};
+use zksync_prover_utils::stream::{
+    decode_payload, encode_payload, prover_message, server_message, Job, ProofStored,
+    ProverMessage, ProverStream, ProverStreamServer, ServerMessage, AUTHORIZATION_KEY,
+};
+use zksync_utils::AuthTokenValidator;
+// Local deps
+use crate::database_interface::DatabaseInterface;
+use crate::{assign_job, record_job_failure, store_received_proof, AppState};
+
+/// Amount of the messages buffered for sending to a single prover.
+const OUTBOUND_BUFFER_SIZE: usize = 4;
+
+type OutboundSender = mpsc::Sender<Result<ServerMessage, Status>>;
+
+/// Prover authenticated with its own token. The token is checked again before the prover
+/// takes a job or publishes a proof, so the revoked prover is isolated without waiting
+/// for the stream to be reopened.
+struct StreamProver {
+    name: String,
+    token: String,
+}
+
+pub(crate) struct ProverStreamService<DB: DatabaseInterface> {
+    state: AppState<DB>,
+    /// Interval of checking for the new jobs while the prover is idle.
+    job_poll_interval: Duration,
+    /// Time after which the busy prover which sends no heartbeats is disconnected.
+    prover_timeout: Duration,
+    /// Level of the zstd compression of the pushed jobs, they're not compressed if not set.
+    compression_level: Option<i32>,
+}
+
+impl<DB: DatabaseInterface> ProverStreamService<DB> {
+    pub(crate) fn new(
+        state: AppState<DB>,
+        job_poll_interval: Duration,
+        prover_timeout: Duration,
+    ) -> Self {
+        Self {
+            state,
+            job_poll_interval,
+            prover_timeout,
+            compression_level: None,
+        }
+    }
+
+    /// Sets the level of the zstd compression of the jobs pushed to the provers.
+    pub(crate) fn with_compression_level(mut self, compression_level: Option<i32>) -> Self {
+        self.compression_level = compression_level;
+        self
+    }
+}
+
+#[tonic::async_trait]
+impl<DB: DatabaseInterface> ProverStream for ProverStreamService<DB> {
+    type OpenJobStreamStream = ReceiverStream<Result<ServerMessage, Status>>;
+
+    async fn open_job_stream(
+        &self,
+        request: Request<Streaming<ProverMessage>>,
+    ) -> Result<Response<Self::OpenJobStreamStream>, Status> {
+        let token = request
+            .metadata()
+            .get(AUTHORIZATION_KEY)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| Status::unauthenticated("authorization token is missing"))?;
+        let prover = if self.state.per_prover_auth {
+            let name = self
+                .state
+                .authenticate_prover(token)
+                .await
+                .map_err(|e| {
+                    vlog::warn!("Failed to check the prover token: {}", e);
+                    Status::unavailable("storage layer error")
+                })?
+                .ok_or_else(|| Status::unauthenticated("authorization error"))?;
+            Some(StreamProver {
+                name,
+                token: token.to_string(),
+            })
+        } else {
+            AuthTokenValidator::new(&self.state.secret_auth)
+                .validate_auth_token(token)
+                .map_err(|_| Status::unauthenticated("authorization error"))?;
+            None
+        };
+
+        let (outbound, receiver) = mpsc::channel(OUTBOUND_BUFFER_SIZE);
+        tokio::spawn(serve_prover(
+            self.state.clone(),
+            request.into_inner(),
+            outbound,
+            self.job_poll_interval,
+            self.prover_timeout,
+            self.compression_level,
+            prover,
+        ));
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+/// Pushes the jobs to the connected prover and handles the messages received from it,
+/// until the stream is closed.
+async fn serve_prover<DB: DatabaseInterface>(
+    state: AppState<DB>,
+    mut inbound: Streaming<ProverMessage>,
+    outbound: OutboundSender,
+    job_poll_interval: Duration,
+    prover_timeout: Duration,
+    compression_level: Option<i32>,
+    prover: Option<StreamProver>,
+) {
+    let mut request: ProverInputRequest = match inbound.message().await {
+        Ok(Some(ProverMessage {
+            message: Some(prover_message::Message::Register(register)),
+        })) => match decode_payload(&register.request) {
+            Ok(request) => request,
+            Err(status) => {
+                outbound.send(Err(status)).await.unwrap_or_default();
+                return;
+            }
+        },
+        _ => {
+            let status = Status::invalid_argument("the stream must start with a registration");
+            outbound.send(Err(status)).await.unwrap_or_default();
+            return;
+        }
+    };
+    if request.prover_name.is_empty() {
+        let status = Status::invalid_argument("empty name");
+        outbound.send(Err(status)).await.unwrap_or_default();
+        return;
+    }
+    if let Err(status) = check_prover(&state, prover.as_ref(), &request).await {
+        outbound.send(Err(status)).await.unwrap_or_default();
+        return;
+    }
+    vlog::info!("Prover '{}' opened the job stream", request.prover_name);
+    metrics::increment_gauge!("prover_server.stream_provers", 1.0);
+
+    let mut assigned_job = None;
+    loop {
+        if assigned_job.is_none() {
+            if let Err(status) = check_prover(&state, prover.as_ref(), &request).await {
+                outbound.send(Err(status)).await.unwrap_or_default();
+                break;
+            }
+            match push_job(&state, &request, &outbound, compression_level).await {
+                Ok(job_id) => assigned_job = job_id,
+                Err(e) => {
+                    vlog::warn!("Failed to push the job to '{}': {}", request.prover_name, e);
+                }
+            }
+            if outbound.is_closed() {
+                break;
+            }
+        }
+
+        // Jobs are pushed only to the idle provers. The busy prover sends the heartbeats,
+        // so it's considered gone if nothing is received from it for the timeout.
+        let message = if let Some(job_id) = assigned_job {
+            match tokio::time::timeout(prover_timeout, inbound.message()).await {
+                Ok(message) => message,
+                Err(_) => {
+                    vlog::warn!(
+                        "Prover '{}' sent nothing for {:?} while working on job {}",
+                        request.prover_name,
+                        prover_timeout,
+                        job_id
+                    );
+                    let status = Status::deadline_exceeded("no heartbeats were received");
+                    outbound.send(Err(status)).await.unwrap_or_default();
+                    break;
+                }
+            }
+        } else {
+            match tokio::time::timeout(job_poll_interval, inbound.message()).await {
+                Ok(message) => message,
+                Err(_) => continue,
+            }
+        };
+        let message = match message {
+            Ok(Some(ProverMessage {
+                message: Some(message),
+            })) => message,
+            Ok(Some(_)) => continue,
+            Ok(None) => break,
+            Err(status) => {
+                vlog::warn!(
+                    "Job stream of '{}' is broken: {}",
+                    request.prover_name,
+                    status
+                );
+                break;
+            }
+        };
+
+        match message {
+            prover_message::Message::Register(register) => {
+                match decode_payload(&register.request) {
+                    Ok(new_request) => request = new_request,
+                    Err(status) => {
+                        outbound.send(Err(status)).await.unwrap_or_default();
+                        break;
+                    }
+                }
+                if let Err(status) = check_prover(&state, prover.as_ref(), &request).await {
+                    outbound.send(Err(status)).await.unwrap_or_default();
+                    break;
+                }
+            }
+            prover_message::Message::Heartbeat(heartbeat) => {
+                vlog::trace!("Received heartbeat for job with id: {}", heartbeat.job_id);
+                if let Err(e) = record_prover_is_working(&state, heartbeat.job_id, &request).await {
+                    vlog::warn!("failed to record prover work in progress request: {}", e);
+                }
+            }
+            prover_message::Message::Proof(proof) => {
+                let output: ProverOutputRequest = match decode_payload(&proof.output) {
+                    Ok(output) => output,
+                    Err(status) => {
+                        outbound.send(Err(status)).await.unwrap_or_default();
+                        break;
+                    }
+                };
+                if let Err(status) = check_prover(&state, prover.as_ref(), &request).await {
+                    outbound.send(Err(status)).await.unwrap_or_default();
+                    break;
+                }
+                let error =
+                    match store_received_proof(&state, &output, Some(&request.prover_name)).await {
+                        Ok(()) => String::new(),
+                        Err(e) => {
+                            vlog::error!("failed to store received proof: {}", e);
+                            e.to_string()
+                        }
+                    };
+                if assigned_job == Some(output.job_id) {
+                    assigned_job = None;
+                }
+
+                let message = ServerMessage {
+                    message: Some(server_message::Message::ProofStored(ProofStored {
+                        job_id: output.job_id,
+                        error,
+                    })),
+                };
+                if outbound.send(Ok(message)).await.is_err() {
+                    break;
+                }
+            }
+            prover_message::Message::JobFailed(job_failed) => {
+                if let Err(status) = check_prover(&state, prover.as_ref(), &request).await {
+                    outbound.send(Err(status)).await.unwrap_or_default();
+                    break;
+                }
+                if assigned_job != Some(job_failed.job_id) {
+                    vlog::warn!(
+                        "Prover '{}' reported the failure of job {} which isn't assigned to it",
+                        request.prover_name,
+                        job_failed.job_id
+                    );
+                    continue;
+                }
+                // The prover becomes idle even if the failure isn't recorded:
+                // the job is returned to the queue once it's stale.
+                assigned_job = None;
+                if let Err(e) = record_job_failure(
+                    &state,
+                    job_failed.job_id,
+                    &request.prover_name,
+                    &job_failed.error,
+                )
+                .await
+                {
+                    vlog::warn!("failed to record the job failure: {}", e);
+                }
+            }
+        }
+    }
+
+    // The jobs of the disconnected prover are returned to the queue right away,
+    // without waiting for them to become stale.
+    vlog::info!("Prover '{}' closed the job stream", request.prover_name);
+    metrics::decrement_gauge!("prover_server.stream_provers", 1.0);
+    let result = match state.database.acquire_connection().await {
+        Ok(mut storage) => {
+            state
+                .database
+                .record_prover_stop(&mut storage, &request.prover_name, state.max_job_attempts)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        vlog::warn!("failed to record prover stop: {}", e);
+    }
+}
+
+/// Assigns the job to the prover and sends it over the stream.
+/// Returns the ID of the assigned job, if there was one.
+async fn push_job<DB: DatabaseInterface>(
+    state: &AppState<DB>,
+    request: &ProverInputRequest,
+    outbound: &OutboundSender,
+    compression_level: Option<i32>,
+) -> anyhow::Result<Option<i32>> {
+    let job = match assign_job(state, request).await? {
+        Some(job) => job,
+        None => return Ok(None),
+    };
+    let job_id = job.job_id;
+    // The job is attributed to the prover right away, so it's returned to the queue
+    // once the prover disconnects.
+    record_prover_is_working(state, job_id, request).await?;
+
+    vlog::info!(
+        "Pushing job {} to '{}', blocks: [{}, {}]",
+        job_id,
+        request.prover_name,
+        job.first_block,
+        job.last_block
+    );
+    let message = ServerMessage {
+        message: Some(server_message::Message::Job(Job {
+            response: encode_payload(&job, compression_level),
+        })),
+    };
+    outbound
+        .send(Ok(message))
+        .await
+        .map_err(|_| anyhow::format_err!("job stream is closed"))?;
+
+    Ok(Some(job_id))
+}
+
+/// Checks that the prover authenticated with its own token acts on its own behalf
+/// and its token is not revoked.
+async fn check_prover<DB: DatabaseInterface>(
+    state: &AppState<DB>,
+    prover: Option<&StreamProver>,
+    request: &ProverInputRequest,
+) -> Result<(), Status> {
+    let prover = match prover {
+        Some(prover) => prover,
+        None => return Ok(()),
+    };
+    if prover.name != request.prover_name {
+        vlog::warn!(
+            "Prover '{}' tried to act on behalf of '{}'",
+            prover.name,
+            request.prover_name
+        );
+        return Err(Status::permission_denied(
+            "prover name doesn't match the token",
+        ));
+    }
+    match state.authenticate_prover(&prover.token).await {
+        Ok(Some(name)) if name == prover.name => Ok(()),
+        Ok(_) => {
+            vlog::info!("Token of the prover '{}' is revoked", prover.name);
+            Err(Status::unauthenticated("prover token is revoked"))
+        }
+        Err(e) => {
+            vlog::warn!("Failed to check the prover token: {}", e);
+            Err(Status::unavailable("storage layer error"))
+        }
+    }
+}
+
+async fn record_prover_is_working<DB: DatabaseInterface>(
+    state: &AppState<DB>,
+    job_id: i32,
+    request: &ProverInputRequest,
+) -> anyhow::Result<()> {
+    let mut storage = state.database.acquire_connection().await?;
+    state
+        .database
+        .record_prover_is_working(&mut storage, job_id, &request.prover_name)
+        .await
+}
+
+pub(crate) async fn run_stream_server<DB: DatabaseInterface>(
+    service: ProverStreamService<DB>,
+    bind_addr: SocketAddr,
+) {
+    vlog::info!("Starting the prover job stream server at {}", bind_addr);
+    Server::builder()
+        .add_service(ProverStreamServer::new(service))
+        .serve(bind_addr)
+        .await
+        .expect("Prover job stream server failed");
+}
@@ -0,0 +1,86 @@
+// Built-in uses
+use std::sync::Arc;
+// External uses
+use tokio::sync::RwLock;
+// Workspace uses
+use zksync_prover_utils::api::{
+    JobRequestData, JobResultData, ProverInputRequest, ProverOutputRequest,
+};
+use zksync_types::prover::{ProverJobStatus, ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY};
+use zksync_types::BlockNumber;
+// Local uses
+use super::mock::MockDatabase;
+use crate::work_scheduler::WorkScheduler;
+use crate::{
+    assign_job, check_prover_name, store_received_proof, AppState, AuthenticatedProver,
+    DatabaseInterface,
+};
+
+/// Checks that the prover authenticated with its own token can't act on behalf of the other provers.
+#[test]
+fn prover_name_matches_token() {
+    let prover = AuthenticatedProver("prover_1".to_string());
+    check_prover_name(Some(&prover), "prover_1").unwrap();
+    check_prover_name(Some(&prover), "prover_2").unwrap_err();
+    // Provers authenticated with the shared secret can use any name.
+    check_prover_name(None, "prover_2").unwrap();
+}
+
+/// Checks that the prover authenticated with its own token can't publish the proofs
+/// of the jobs assigned to the other provers.
+#[tokio::test]
+async fn proofs_accepted_only_from_assignee() {
+    let database = MockDatabase::new();
+    let state = AppState::new(
+        "42".to_string(),
+        database.clone(),
+        1,
+        Arc::new(RwLock::new(WorkScheduler::new())),
+        false,
+        3,
+        true,
+    );
+
+    let mut storage = database.acquire_connection().await.unwrap();
+    database
+        .add_prover_job_to_job_queue(
+            &mut storage,
+            BlockNumber(1),
+            BlockNumber(2),
+            serde_json::to_value(JobRequestData::AggregatedBlockProof(Vec::new())).unwrap(),
+            AGGREGATED_PROOF_JOB_PRIORITY,
+            ProverJobType::AggregatedProof,
+        )
+        .await
+        .unwrap();
+    drop(storage);
+
+    let request = ProverInputRequest {
+        prover_name: "prover_1".to_string(),
+        aux_data: Default::default(),
+    };
+    let job = assign_job(&state, &request).await.unwrap().unwrap();
+    let output = ProverOutputRequest {
+        job_id: job.job_id,
+        first_block: job.first_block,
+        last_block: job.last_block,
+        data: JobResultData::AggregatedBlockProof(Default::default()),
+    };
+
+    let error = store_received_proof(&state, &output, Some("prover_2"))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("is not assigned to"));
+    assert_eq!(
+        database.job_status(job.job_id).await,
+        Some((ProverJobStatus::InProgress.to_number(), 0))
+    );
+
+    store_received_proof(&state, &output, Some("prover_1"))
+        .await
+        .unwrap();
+    assert_eq!(
+        database.job_status(job.job_id).await,
+        Some((ProverJobStatus::Done.to_number(), 0))
+    );
+}
@@ -1,6 +1,7 @@
 #![allow(clippy::diverging_sub_expression)]
 // Built-in
 use std::clone::Clone;
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -17,7 +18,10 @@ use zksync_storage::StorageProcessor;
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     block::Block,
-    prover::{ProverJob, ProverJobStatus, ProverJobType},
+    prover::{
+        ProofRejectionCause, ProverJob, ProverJobStatus, ProverJobType, ProverQueueStats,
+        ProverWorkClass, ProverWorkClassQueue, URGENT_JOB_PRIORITY,
+    },
     AccountId, AccountMap, AccountTree, AccountUpdates, Address, BlockNumber,
 };
 // Local uses
@@ -32,6 +36,10 @@ pub struct MockDatabase {
     blocks: Arc<RwLock<Vec<Block>>>,
     account_tree_cache: Arc<RwLock<AccountTreeCache>>,
     accounts_state: Arc<RwLock<(u32, AccountMap)>>,
+    /// Names of the provers by the hashes of their tokens.
+    prover_tokens: Arc<RwLock<HashMap<String, String>>>,
+    /// Job assignments: the job ID, the prover name and the prover that published the proof.
+    prover_job_audit: Arc<RwLock<Vec<(i32, String, Option<String>)>>>,
 }
 
 impl MockDatabase {
@@ -49,6 +57,20 @@ impl MockDatabase {
                 tree_cache_binary,
             })),
             accounts_state: Arc::new(RwLock::new((0, accounts))),
+            prover_tokens: Default::default(),
+            prover_job_audit: Default::default(),
+        }
+    }
+
+    /// Work class of the job, block size of the single proof is taken from the job data.
+    fn job_work_class(job: &StorageProverJobQueue) -> ProverWorkClass {
+        if job.job_type == ProverJobType::AggregatedProof.to_string() {
+            ProverWorkClass::AggregatedProof
+        } else {
+            let block_size = job.job_data["BlockProof"][1]
+                .as_u64()
+                .expect("Incorrect single proof job data") as usize;
+            ProverWorkClass::SingleProof { block_size }
         }
     }
 
@@ -75,6 +97,24 @@ impl MockDatabase {
     pub async fn add_block(&self, block: Block) {
         self.blocks.write().await.push(block);
     }
+
+    /// Returns the status number and the amount of the attempts of the job.
+    pub async fn job_status(&self, job_id: i32) -> Option<(i32, i32)> {
+        self.prover_job_queue
+            .read()
+            .await
+            .1
+            .iter()
+            .find(|job| job.id == job_id)
+            .map(|job| (job.job_status, job.attempts))
+    }
+
+    pub async fn add_prover_token(&self, token_hash: &str, prover_name: &str) {
+        self.prover_tokens
+            .write()
+            .await
+            .insert(token_hash.to_string(), prover_name.to_string());
+    }
 }
 
 #[async_trait::async_trait]
@@ -109,6 +149,7 @@ impl DatabaseInterface for MockDatabase {
             id,
             job_priority,
             job_data,
+            attempts: 0,
         };
 
         prover_job_queue.1.push(new_job);
@@ -135,6 +176,34 @@ impl DatabaseInterface for MockDatabase {
         Ok(BlockNumber(block_number as u32))
     }
 
+    async fn load_first_unproven_block(
+        &self,
+        _: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<BlockNumber> {
+        let prover_job_queue = self.prover_job_queue.read().await;
+        let single_proof_jobs = prover_job_queue
+            .1
+            .iter()
+            .filter(|job| job.job_type == ProverJobType::SingleProof.to_string());
+        let first_block = single_proof_jobs
+            .clone()
+            .filter(|job| job.job_status != ProverJobStatus::Done.to_number())
+            .map(|job| job.first_block)
+            .min();
+
+        let block_number = match first_block {
+            Some(first_block) => BlockNumber(first_block as u32),
+            None => {
+                let last_block = single_proof_jobs
+                    .map(|job| job.last_block)
+                    .max()
+                    .unwrap_or_default();
+                BlockNumber(last_block as u32 + 1)
+            }
+        };
+        Ok(block_number)
+    }
+
     async fn pending_jobs_count(&self, _: &mut StorageProcessor<'_>) -> anyhow::Result<u32> {
         let count = self
             .prover_job_queue
@@ -148,6 +217,92 @@ impl DatabaseInterface for MockDatabase {
         Ok(count as u32)
     }
 
+    async fn load_prover_queue_stats(
+        &self,
+        _: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<ProverQueueStats> {
+        let prover_job_queue = self.prover_job_queue.read().await;
+        let count = |status: ProverJobStatus| {
+            prover_job_queue
+                .1
+                .iter()
+                .filter(|job| job.job_status == status.to_number())
+                .count() as u32
+        };
+        let oldest_job = prover_job_queue
+            .1
+            .iter()
+            .filter(|job| {
+                job.job_status == ProverJobStatus::Idle.to_number()
+                    || job.job_status == ProverJobStatus::InProgress.to_number()
+            })
+            .min_by_key(|job| (job.first_block, job.created_at));
+
+        Ok(ProverQueueStats {
+            idle_jobs: count(ProverJobStatus::Idle),
+            in_progress_jobs: count(ProverJobStatus::InProgress),
+            oldest_unproven_block: oldest_job.map(|job| BlockNumber(job.first_block as u32)),
+            oldest_unproven_block_created_at: oldest_job.map(|job| job.created_at),
+        })
+    }
+
+    async fn load_prover_name_by_token(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        token_hash: &str,
+    ) -> anyhow::Result<Option<String>> {
+        Ok(self.prover_tokens.read().await.get(token_hash).cloned())
+    }
+
+    async fn record_prover_job_assignment(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        job_id: i32,
+        prover_name: &str,
+    ) -> anyhow::Result<()> {
+        self.prover_job_audit
+            .write()
+            .await
+            .push((job_id, prover_name.to_string(), None));
+
+        Ok(())
+    }
+
+    async fn record_prover_job_published(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        job_id: i32,
+        published_by: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let mut prover_job_audit = self.prover_job_audit.write().await;
+        if let Some(record) = prover_job_audit
+            .iter_mut()
+            .rev()
+            .find(|(id, _, _)| *id == job_id)
+        {
+            record.2 = Some(published_by.unwrap_or(&record.1).to_string());
+        }
+
+        Ok(())
+    }
+
+    async fn load_job_assignee(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        job_id: i32,
+    ) -> anyhow::Result<Option<String>> {
+        let assignee = self
+            .prover_job_audit
+            .read()
+            .await
+            .iter()
+            .rev()
+            .find(|(id, _, _)| *id == job_id)
+            .map(|(_, prover_name, _)| prover_name.clone());
+
+        Ok(assignee)
+    }
+
     async fn load_aggregated_op_that_affects_block(
         &self,
         _: &mut StorageProcessor<'_>,
@@ -166,17 +321,34 @@ impl DatabaseInterface for MockDatabase {
         let single_proof = proofs
             .iter()
             .find(|proof| proof.block_number == *block_number as i64)
-            .map(|stored| serde_json::from_value(stored.proof.clone()).unwrap());
+            .and_then(|stored| stored.proof.clone())
+            .map(|proof| serde_json::from_value(proof).unwrap());
 
         Ok(single_proof)
     }
 
-    async fn mark_stale_jobs_as_idle(&self, _: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+    async fn mark_stale_jobs_as_idle(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        _heartbeat_timeout: Duration,
+        max_attempts: u32,
+    ) -> anyhow::Result<()> {
         let now = Utc::now();
         let prover_job_queue = &mut self.prover_job_queue.write().await.1;
 
-        for job in prover_job_queue.iter_mut() {
+        for job in prover_job_queue
+            .iter_mut()
+            .filter(|job| job.job_status != ProverJobStatus::Quarantined.to_number())
+        {
             if now - job.updated_at > chrono::Duration::seconds(10) {
+                if job.job_status == ProverJobStatus::InProgress.to_number() {
+                    job.attempts += 1;
+                    if job.attempts >= max_attempts as i32 {
+                        job.job_status = ProverJobStatus::Quarantined.to_number();
+                        job.updated_at = now;
+                        continue;
+                    }
+                }
                 job.job_status = ProverJobStatus::Idle.to_number();
                 job.updated_at = now;
                 job.updated_by = "server_clean_idle".to_string();
@@ -186,6 +358,52 @@ impl DatabaseInterface for MockDatabase {
         Ok(())
     }
 
+    async fn reject_proof(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        job_id: i32,
+        _prover_name: Option<&str>,
+        _cause: ProofRejectionCause,
+        _reason: &str,
+        max_attempts: u32,
+    ) -> anyhow::Result<()> {
+        let prover_job_queue = &mut self.prover_job_queue.write().await.1;
+        if let Some(job) = prover_job_queue.iter_mut().find(|job| {
+            job.id == job_id && job.job_status == ProverJobStatus::InProgress.to_number()
+        }) {
+            job.attempts += 1;
+            job.job_status = if job.attempts >= max_attempts as i32 {
+                ProverJobStatus::Quarantined.to_number()
+            } else {
+                ProverJobStatus::Idle.to_number()
+            };
+            job.updated_at = Utc::now();
+        }
+
+        Ok(())
+    }
+
+    async fn move_jobs_to_urgent_lane(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        urgent_job_timeout: Duration,
+    ) -> anyhow::Result<()> {
+        let urgent_job_timeout = chrono::Duration::from_std(urgent_job_timeout)?;
+        let now = Utc::now();
+        let prover_job_queue = &mut self.prover_job_queue.write().await.1;
+
+        for job in prover_job_queue.iter_mut() {
+            if job.job_status == ProverJobStatus::Idle.to_number()
+                && job.job_priority > URGENT_JOB_PRIORITY
+                && now - job.created_at >= urgent_job_timeout
+            {
+                job.job_priority = URGENT_JOB_PRIORITY;
+            }
+        }
+
+        Ok(())
+    }
+
     async fn load_last_verified_block(
         &self,
         _: &mut StorageProcessor<'_>,
@@ -221,15 +439,47 @@ impl DatabaseInterface for MockDatabase {
         Ok(Some(result))
     }
 
-    async fn load_idle_prover_job_from_job_queue(
+    async fn load_idle_jobs_by_work_class(
+        &self,
+        _: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<ProverWorkClassQueue>> {
+        let prover_job_queue = &self.prover_job_queue.read().await.1;
+        let mut queues: Vec<ProverWorkClassQueue> = Vec::new();
+        for job in prover_job_queue
+            .iter()
+            .filter(|job| job.job_status == ProverJobStatus::Idle.to_number())
+        {
+            let work_class = Self::job_work_class(job);
+            match queues
+                .iter_mut()
+                .find(|queue| queue.work_class == work_class)
+            {
+                Some(queue) => {
+                    queue.job_priority = queue.job_priority.min(job.job_priority);
+                    queue.idle_jobs += 1;
+                }
+                None => queues.push(ProverWorkClassQueue {
+                    work_class,
+                    job_priority: job.job_priority,
+                    idle_jobs: 1,
+                }),
+            }
+        }
+
+        Ok(queues)
+    }
+
+    async fn load_idle_prover_job_of_work_class(
         &self,
         _: &mut StorageProcessor<'_>,
+        work_class: ProverWorkClass,
     ) -> anyhow::Result<Option<ProverJob>> {
         let prover_job_queue = &mut self.prover_job_queue.write().await.1;
         let idle_prover_job = prover_job_queue
             .iter_mut()
             .filter(|job| job.job_status == ProverJobStatus::Idle.to_number())
-            .max_by_key(|job| (job.job_priority, job.id));
+            .filter(|job| Self::job_work_class(job) == work_class)
+            .min_by_key(|job| (job.job_priority, job.first_block, job.id));
 
         let prover_job = if let Some(job) = idle_prover_job {
             job.job_status = ProverJobStatus::InProgress.to_number();
@@ -284,7 +534,9 @@ impl DatabaseInterface for MockDatabase {
         let proof = StoredProof {
             block_number: i64::from(*block_number),
             created_at: Utc::now(),
-            proof: serde_json::to_value(proof).unwrap(),
+            proof: Some(serde_json::to_value(proof).unwrap()),
+            object_key: None,
+            proof_compressed: None,
         };
         self.proofs.write().await.push(proof);
 
@@ -294,18 +546,28 @@ impl DatabaseInterface for MockDatabase {
     async fn store_aggregated_proof(
         &self,
         _: &mut StorageProcessor<'_>,
-        _job_id: i32,
+        job_id: i32,
         _first_block: BlockNumber,
         _last_block: BlockNumber,
         _proof: &AggregatedProof,
     ) -> anyhow::Result<()> {
-        unreachable!();
+        let prover_job_queue = &mut self.prover_job_queue.write().await.1;
+        let prover_job = prover_job_queue.iter_mut().find(|job| job.id == job_id);
+
+        if let Some(job) = prover_job {
+            job.updated_at = Utc::now();
+            job.job_status = ProverJobStatus::Done.to_number();
+            job.updated_by = "server_finish_job".to_string();
+        }
+
+        Ok(())
     }
 
     async fn record_prover_stop(
         &self,
         _: &mut StorageProcessor<'_>,
         prover_name: &str,
+        max_attempts: u32,
     ) -> anyhow::Result<()> {
         let prover_job_queue = &mut self.prover_job_queue.write().await.1;
 
@@ -313,7 +575,12 @@ impl DatabaseInterface for MockDatabase {
             if job.updated_by == prover_name
                 && job.job_status == ProverJobStatus::InProgress.to_number()
             {
-                job.job_status = ProverJobStatus::Idle.to_number();
+                job.attempts += 1;
+                job.job_status = if job.attempts >= max_attempts as i32 {
+                    ProverJobStatus::Quarantined.to_number()
+                } else {
+                    ProverJobStatus::Idle.to_number()
+                };
                 job.updated_at = Utc::now();
             }
         }
@@ -367,7 +634,8 @@ impl DatabaseInterface for MockDatabase {
         let witness = block_witness
             .iter()
             .find(|witness| witness.block == *block_number as i64)
-            .map(|w| serde_json::from_str(&w.witness).expect("Failed to deserialize witness"));
+            .and_then(|w| w.witness.as_deref())
+            .map(|w| serde_json::from_str(w).expect("Failed to deserialize witness"));
 
         Ok(witness)
     }
@@ -387,7 +655,9 @@ impl DatabaseInterface for MockDatabase {
         if is_block_not_saved_yet {
             block_witness.push(StorageBlockWitness {
                 block: *block as i64,
-                witness: witness_str,
+                witness: Some(witness_str),
+                object_key: None,
+                witness_compressed: None,
             });
         }
 
@@ -1,2 +1,5 @@
+mod auth;
 mod mock;
 mod prover_server;
+mod scaler;
+mod work_scheduler;
@@ -1,6 +1,8 @@
 // Built-in deps
+use std::sync::Arc;
 use std::time::Duration;
 // External deps
+use tokio::sync::RwLock;
 
 use num::BigUint;
 // Workspace deps
@@ -12,17 +14,27 @@ use zksync_config::{
     ProverConfig,
 };
 use zksync_crypto::franklin_crypto::bellman::pairing::ff::{PrimeField, PrimeFieldRepr};
-use zksync_prover::{client, ApiClient};
-use zksync_prover_utils::api::ProverInputRequest;
-use zksync_types::{block::Block, AccountId, BlockNumber, TokenId, H256};
+use zksync_prover::{client, stream_client::StreamClient, ApiClient};
+use zksync_prover_utils::api::{JobRequestData, ProverInputRequest};
+use zksync_prover_utils::prover_tokens::{generate_prover_token, prover_token_hash};
+use zksync_types::{
+    block::Block,
+    prover::{ProverJobStatus, ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY},
+    AccountId, BlockNumber, TokenId, H256,
+};
 // Local deps
 use super::mock::MockDatabase;
-use crate::{run_prover_server, DatabaseInterface};
+use crate::stream::{run_stream_server, ProverStreamService};
+use crate::work_scheduler::WorkScheduler;
+use crate::{run_prover_server, AppState, DatabaseInterface};
 
 const CORRECT_PROVER_SECRET_AUTH: &str = "42";
 const INCORRECT_PROVER_SECRET_AUTH: &str = "123";
 const SERVER_BIND_PORT: u16 = 8088;
+const SERVER_STREAM_PORT: u16 = 8089;
 const SERVER_BIND_TO: &str = "127.0.0.1:8088";
+const FAILURES_TEST_STREAM_PORT: u16 = 8090;
+const PROVER_AUTH_TEST_STREAM_PORT: u16 = 8091;
 
 struct MockProverOptions(ProverApiConfig, ProverConfig);
 
@@ -32,6 +44,9 @@ impl Default for MockProverOptions {
             port: SERVER_BIND_PORT,
             url: SERVER_BIND_TO.to_string(),
             secret_auth: CORRECT_PROVER_SECRET_AUTH.to_string(),
+            stream_port: SERVER_STREAM_PORT,
+            stream_url: format!("http://127.0.0.1:{}", SERVER_STREAM_PORT),
+            per_prover_auth: false,
         };
         let prover = ProverConfig {
             prover: Prover {
@@ -39,15 +54,27 @@ impl Default for MockProverOptions {
                 cycle_wait: 500,
                 request_timeout: 10,
                 die_after_proof: false,
+                proving_threads: 0,
+                min_available_memory: 0,
+                cpu_affinity: None,
+                compression_level: None,
             },
             core: Core {
                 gone_timeout: 60000,
                 idle_provers: 1,
+                urgent_job_timeout: 1800,
+                max_job_attempts: 3,
+                verify_proofs: false,
+                // Jobs pushed over the stream are compressed, so the client has to decompress them.
+                compression_level: Some(3),
             },
             witness_generator: WitnessGenerator {
                 prepare_data_interval: 500,
                 witness_generators: 1,
+                standalone: false,
+                max_witnesses_in_flight: 8,
             },
+            dummy_prover: Default::default(),
         };
 
         MockProverOptions(api, prover)
@@ -68,6 +95,7 @@ async fn test_api_client() {
     spawn_server(database.clone()).await;
     test_api_client_with_incorrect_secret_auth("tests1").await;
     test_api_client_simple_simulation("test2", database).await;
+    test_stream_client_simple_simulation("test3").await;
 }
 
 async fn test_api_client_with_incorrect_secret_auth(prover_name: &str) {
@@ -161,6 +189,181 @@ async fn test_api_client_simple_simulation(prover_name: &str, database: MockData
     assert!(witness.is_some());
 }
 
+async fn test_stream_client_simple_simulation(prover_name: &str) {
+    let client = StreamClient::new(
+        &format!("http://127.0.0.1:{}", SERVER_STREAM_PORT),
+        Duration::from_secs(1),
+        CORRECT_PROVER_SECRET_AUTH,
+    );
+    let request = ProverInputRequest {
+        prover_name: prover_name.to_string(),
+        aux_data: Default::default(),
+    };
+
+    // The job taken by the previous prover is pushed to the stream once it's stale.
+    MockDatabase::wait_for_stale_job_stale_idle().await;
+    let job = client.get_job(request.clone()).await.unwrap();
+    assert!(job.data.is_some());
+
+    // Once the stream is closed, the job is returned to the queue right away.
+    client
+        .prover_stopped(prover_name.to_string())
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let api_client = client::ApiClient::new(
+        &format!("http://{}", SERVER_BIND_TO).parse().unwrap(),
+        Duration::from_secs(1),
+        CORRECT_PROVER_SECRET_AUTH,
+    );
+    let job = api_client.get_job(request).await.unwrap();
+    assert!(job.data.is_some());
+}
+
+/// Checks that the job which the prover failed to prove is returned to the queue right away,
+/// and the job of the prover sending no heartbeats is returned once the prover is disconnected.
+#[tokio::test]
+async fn test_stream_job_failures() {
+    let database = MockDatabase::new();
+    let state = AppState::new(
+        CORRECT_PROVER_SECRET_AUTH.to_string(),
+        database.clone(),
+        1,
+        Arc::new(RwLock::new(WorkScheduler::new())),
+        false,
+        3,
+        false,
+    );
+    let prover_timeout = Duration::from_secs(2);
+    let service = ProverStreamService::new(state, Duration::from_millis(100), prover_timeout);
+    tokio::spawn(run_stream_server(
+        service,
+        ([127, 0, 0, 1], FAILURES_TEST_STREAM_PORT).into(),
+    ));
+
+    let mut storage = database.acquire_connection().await.unwrap();
+    database
+        .add_prover_job_to_job_queue(
+            &mut storage,
+            BlockNumber(1),
+            BlockNumber(2),
+            serde_json::to_value(JobRequestData::AggregatedBlockProof(Vec::new())).unwrap(),
+            AGGREGATED_PROOF_JOB_PRIORITY,
+            ProverJobType::AggregatedProof,
+        )
+        .await
+        .unwrap();
+    drop(storage);
+    // Let the server start.
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let prover_name = "failing_prover";
+    let client = StreamClient::new(
+        &format!("http://127.0.0.1:{}", FAILURES_TEST_STREAM_PORT),
+        Duration::from_secs(1),
+        CORRECT_PROVER_SECRET_AUTH,
+    );
+    let request = ProverInputRequest {
+        prover_name: prover_name.to_string(),
+        aux_data: Default::default(),
+    };
+    let job = client.get_job(request.clone()).await.unwrap();
+    assert!(job.data.is_some());
+    assert_eq!(
+        database.job_status(job.job_id).await,
+        Some((ProverJobStatus::InProgress.to_number(), 0))
+    );
+
+    client
+        .job_failed(job.job_id, prover_name, "out of memory".to_string())
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // The failed job is pushed once again, since the prover is idle now.
+    let job = client.get_job(request).await.unwrap();
+    assert!(job.data.is_some());
+    assert_eq!(
+        database.job_status(job.job_id).await,
+        Some((ProverJobStatus::InProgress.to_number(), 1))
+    );
+
+    // The prover sends no heartbeats, so it's disconnected and the job is returned to the queue.
+    tokio::time::sleep(prover_timeout * 2).await;
+    assert_eq!(
+        database.job_status(job.job_id).await,
+        Some((ProverJobStatus::Idle.to_number(), 1))
+    );
+}
+
+/// Checks that the provers authenticating with their own tokens are served only if the token
+/// is known and is used on behalf of the prover it was issued to.
+#[tokio::test]
+async fn test_stream_per_prover_auth() {
+    let database = MockDatabase::new();
+    let state = AppState::new(
+        CORRECT_PROVER_SECRET_AUTH.to_string(),
+        database.clone(),
+        1,
+        Arc::new(RwLock::new(WorkScheduler::new())),
+        false,
+        3,
+        true,
+    );
+    let service =
+        ProverStreamService::new(state, Duration::from_millis(100), Duration::from_secs(60));
+    tokio::spawn(run_stream_server(
+        service,
+        ([127, 0, 0, 1], PROVER_AUTH_TEST_STREAM_PORT).into(),
+    ));
+
+    let prover_token = generate_prover_token();
+    database
+        .add_prover_token(&prover_token_hash(&prover_token), "prover_1")
+        .await;
+    let mut storage = database.acquire_connection().await.unwrap();
+    database
+        .add_prover_job_to_job_queue(
+            &mut storage,
+            BlockNumber(1),
+            BlockNumber(2),
+            serde_json::to_value(JobRequestData::AggregatedBlockProof(Vec::new())).unwrap(),
+            AGGREGATED_PROOF_JOB_PRIORITY,
+            ProverJobType::AggregatedProof,
+        )
+        .await
+        .unwrap();
+    drop(storage);
+    // Let the server start.
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let stream_client = |prover_token: String| {
+        StreamClient::new(
+            &format!("http://127.0.0.1:{}", PROVER_AUTH_TEST_STREAM_PORT),
+            Duration::from_secs(1),
+            CORRECT_PROVER_SECRET_AUTH,
+        )
+        .with_prover_token(Some(prover_token))
+    };
+    let request = |prover_name: &str| ProverInputRequest {
+        prover_name: prover_name.to_string(),
+        aux_data: Default::default(),
+    };
+
+    // The unknown token is rejected.
+    let client = stream_client(generate_prover_token());
+    client.get_job(request("prover_1")).await.unwrap_err();
+
+    // The token can't be used on behalf of the other prover.
+    let client = stream_client(prover_token.clone());
+    client.get_job(request("prover_2")).await.unwrap_err();
+
+    let client = stream_client(prover_token);
+    let job = client.get_job(request("prover_1")).await.unwrap();
+    assert!(job.data.is_some());
+}
+
 pub async fn get_test_block() -> Block {
     let (circuit_tree, accounts) = MockDatabase::get_default_tree_and_accounts();
     let validator_account_id = AccountId(0);
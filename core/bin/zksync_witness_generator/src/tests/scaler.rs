@@ -0,0 +1,28 @@
+// External uses
+use chrono::{Duration, Utc};
+// Workspace uses
+use zksync_types::{prover::ProverQueueStats, BlockNumber};
+// Local uses
+use crate::scaler::ProverCapacity;
+
+/// Checks that the required provers are estimated from the pending jobs and the age
+/// of the oldest unproven block is reported.
+#[test]
+fn prover_capacity() {
+    let now = Utc::now();
+    let capacity = ProverCapacity::from_stats(Default::default(), 2, now);
+    assert_eq!(capacity.required_provers, 2);
+    assert_eq!(capacity.oldest_unproven_block, None);
+    assert_eq!(capacity.oldest_unproven_block_age, None);
+
+    let stats = ProverQueueStats {
+        idle_jobs: 3,
+        in_progress_jobs: 2,
+        oldest_unproven_block: Some(BlockNumber(10)),
+        oldest_unproven_block_created_at: Some(now - Duration::seconds(90)),
+    };
+    let capacity = ProverCapacity::from_stats(stats, 2, now);
+    assert_eq!(capacity.required_provers, 5);
+    assert_eq!(capacity.oldest_unproven_block, Some(BlockNumber(10)));
+    assert_eq!(capacity.oldest_unproven_block_age, Some(90));
+}
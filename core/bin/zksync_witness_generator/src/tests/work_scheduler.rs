@@ -0,0 +1,66 @@
+// Workspace uses
+use zksync_types::prover::{ProverWorkClass, ProverWorkClassQueue};
+// Local uses
+use crate::work_scheduler::WorkScheduler;
+
+fn queue(work_class: ProverWorkClass, job_priority: i32, idle_jobs: u32) -> ProverWorkClassQueue {
+    ProverWorkClassQueue {
+        work_class,
+        job_priority,
+        idle_jobs,
+    }
+}
+
+/// Checks that the provers get the jobs of the supported classes only.
+#[test]
+fn supported_work_classes() {
+    let small_blocks = ProverWorkClass::SingleProof { block_size: 10 };
+    let big_blocks = ProverWorkClass::SingleProof { block_size: 654 };
+    let queues = vec![queue(small_blocks, 1, 5), queue(big_blocks, 1, 5)];
+
+    let mut scheduler = WorkScheduler::new();
+    for _ in 0..3 {
+        assert_eq!(
+            scheduler.choose_work_class(&[big_blocks], &queues),
+            Some(big_blocks)
+        );
+    }
+    assert_eq!(
+        scheduler.choose_work_class(&[ProverWorkClass::AggregatedProof], &queues),
+        None
+    );
+    // Empty queues are skipped.
+    let queues = vec![queue(small_blocks, 1, 5), queue(big_blocks, 1, 0)];
+    assert_eq!(scheduler.choose_work_class(&[big_blocks], &queues), None);
+    assert_eq!(
+        scheduler.choose_work_class(&[], &queues),
+        Some(small_blocks)
+    );
+}
+
+/// Checks that the queues with the same priority are served in turns,
+/// and the more important jobs are assigned first.
+#[test]
+fn fair_scheduling() {
+    let small_blocks = ProverWorkClass::SingleProof { block_size: 10 };
+    let medium_blocks = ProverWorkClass::SingleProof { block_size: 32 };
+    let queues = vec![queue(small_blocks, 1, 100), queue(medium_blocks, 1, 1)];
+
+    let mut scheduler = WorkScheduler::new();
+    let assigned: Vec<_> = (0..4)
+        .map(|_| scheduler.choose_work_class(&[], &queues).unwrap())
+        .collect();
+    assert_eq!(
+        assigned,
+        vec![small_blocks, medium_blocks, small_blocks, medium_blocks]
+    );
+
+    let queues = vec![
+        queue(small_blocks, 1, 100),
+        queue(ProverWorkClass::AggregatedProof, 0, 1),
+    ];
+    assert_eq!(
+        scheduler.choose_work_class(&[], &queues),
+        Some(ProverWorkClass::AggregatedProof)
+    );
+}
@@ -24,6 +24,11 @@ use zksync_utils::panic_notify::ThreadPanicNotify;
 ///
 /// This will generate and store in db witnesses for blocks with indexes
 /// start_block, start_block + block_step, start_block + 2*block_step, ...
+///
+/// Witnesses are generated ahead of the proving: the witness of the block is generated as soon as
+/// the block is committed, unless `max_witnesses_in_flight` witnesses are already waiting for their
+/// proofs. The limit is counted from the oldest block without the proof stored in the prover schema,
+/// so it's shared by all the witness generators.
 pub struct WitnessGenerator<DB: DatabaseInterface> {
     /// Connection to the database.
     database: DB,
@@ -34,6 +39,7 @@ pub struct WitnessGenerator<DB: DatabaseInterface> {
     block_step: BlockNumber,
     start_wait: time::Duration,
     cached_account_tree: Arc<RwLock<BTreeMap<BlockNumber, CircuitAccountTree>>>,
+    max_witnesses_in_flight: usize,
 }
 
 #[derive(Debug)]
@@ -52,6 +58,7 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
         start_block: BlockNumber,
         block_step: BlockNumber,
         cached_account_tree: Arc<RwLock<BTreeMap<BlockNumber, CircuitAccountTree>>>,
+        max_witnesses_in_flight: usize,
     ) -> Self {
         Self {
             database,
@@ -60,6 +67,7 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
             block_step,
             start_wait,
             cached_account_tree,
+            max_witnesses_in_flight,
         }
     }
 
@@ -111,6 +119,38 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
         Ok(block_info)
     }
 
+    /// Checks whether the witness of the block can be generated without exceeding
+    /// the limit of the witnesses waiting for their proofs.
+    async fn witness_fits_in_flight_limit(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<bool, anyhow::Error> {
+        if self.max_witnesses_in_flight == 0 {
+            return Ok(true);
+        }
+        let mut storage = self.database.acquire_connection().await?;
+        let first_unproven_block = self
+            .database
+            .load_first_unproven_block(&mut storage)
+            .await?;
+        Ok(Self::fits_in_flight_limit(
+            block_number,
+            first_unproven_block,
+            self.max_witnesses_in_flight,
+        ))
+    }
+
+    /// Witnesses are only generated for the blocks in the window of `max_witnesses_in_flight` blocks
+    /// starting from the oldest unproven one. Unlike counting the stored witnesses, the window
+    /// can't be exceeded by the generators checking the limit at the same time.
+    fn fits_in_flight_limit(
+        block_number: BlockNumber,
+        first_unproven_block: BlockNumber,
+        max_witnesses_in_flight: usize,
+    ) -> bool {
+        (*block_number as usize) < *first_unproven_block as usize + max_witnesses_in_flight
+    }
+
     async fn load_account_tree_cache(
         &mut self,
         block: BlockNumber,
@@ -295,6 +335,7 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
             block.block_number.0 as f64,
             "stage" => "witness_generator"
         );
+        metrics::increment_counter!("witness_generator.witnesses_generated");
         Ok(())
     }
 
@@ -326,6 +367,10 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
         metrics::register_counter!("witness_generator.cache_access", "type" => "off_by_1");
         metrics::register_counter!("witness_generator.cache_access", "type" => "miss");
 
+        // Progress of the generator is reported under its first block, so the generators
+        // working in parallel are distinguished.
+        let generator = self.start_block.to_string();
+
         let mut current_block = self.start_block;
         // The first step of each job is downloading the account tree cache. And it takes a lot of time.
         // But we have a lot of witness-generators on one machine and they all start at the same time.
@@ -342,18 +387,49 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
             };
 
             let next_block = Self::next_witness_block(current_block, self.block_step, &should_work);
+            metrics::gauge!("witness_generator.current_block", current_block.0 as f64, "generator" => generator.clone());
+            let throttled = match &should_work {
+                BlockInfo::NoWitness(block) => {
+                    match self.witness_fits_in_flight_limit(block.block_number).await {
+                        Ok(fits) => !fits,
+                        Err(err) => {
+                            vlog::warn!(
+                                "Failed to check the witnesses in flight for block {}: {}",
+                                current_block,
+                                err
+                            );
+                            true
+                        }
+                    }
+                }
+                _ => false,
+            };
+            metrics::gauge!("witness_generator.throttled", throttled as u8 as f64, "generator" => generator.clone());
+            // The generator is idle while it waits for the next block to be committed
+            // or for the previous blocks to be proven.
+            let idle = throttled || matches!(should_work, BlockInfo::NotReadyBlock);
+            metrics::gauge!("witness_generator.idle", idle as u8 as f64, "generator" => generator.clone());
+            if throttled {
+                sleep(self.rounds_interval).await;
+                continue;
+            }
             if let BlockInfo::NoWitness(block) = should_work {
                 let block_number = block.block_number;
                 if let Err(err) = self.prepare_witness_and_save_it(block).await {
                     vlog::warn!("Witness generator ({},{}) failed to prepare witness for block: {}, err: {}",
                         self.start_block, self.block_step, block_number, err);
+                    metrics::increment_counter!("witness_generator.failures");
                     continue; // Retry the same block on the next iteration.
                 }
             }
 
             // Update current block.
             current_block = next_block;
-            sleep(self.rounds_interval).await;
+            // The next block is likely ready already if this one is, so there is no pause
+            // between the consecutive witnesses.
+            if idle {
+                sleep(self.rounds_interval).await;
+            }
         }
     }
 }
@@ -404,4 +480,20 @@ mod tests {
             BlockNumber(7)
         );
     }
+
+    #[test]
+    fn test_fits_in_flight_limit() {
+        let fits = |block, first_unproven_block| {
+            WitnessGenerator::<Database>::fits_in_flight_limit(
+                BlockNumber(block),
+                BlockNumber(first_unproven_block),
+                2,
+            )
+        };
+        assert!(fits(5, 5));
+        assert!(fits(6, 5));
+        assert!(!fits(7, 5));
+        // Witnesses of the blocks which are proven already are not limited.
+        assert!(fits(3, 5));
+    }
 }
@@ -0,0 +1,52 @@
+//! Assignment of the prover jobs to the heterogeneous provers.
+//!
+//! Jobs are split into queues by their work class (job type and block size), and every prover
+//! advertises the classes it's able to handle. Out of the queues the prover can serve, the one with
+//! the most important jobs is chosen. Queues with equally important jobs are served in turns, so a
+//! long queue of one class doesn't starve the others.
+
+// Built-in
+use std::collections::HashMap;
+// Workspace uses
+use zksync_types::prover::{ProverWorkClass, ProverWorkClassQueue};
+
+#[derive(Debug, Default)]
+pub struct WorkScheduler {
+    /// Number of the assignment at which the work class was served for the last time.
+    last_served: HashMap<ProverWorkClass, u64>,
+    assignments: u64,
+}
+
+impl WorkScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Chooses the work class to assign the job from. Empty `supported_classes` means that
+    /// the prover is able to handle jobs of any class.
+    pub fn choose_work_class(
+        &mut self,
+        supported_classes: &[ProverWorkClass],
+        queues: &[ProverWorkClassQueue],
+    ) -> Option<ProverWorkClass> {
+        let work_class = queues
+            .iter()
+            .filter(|queue| queue.idle_jobs > 0)
+            .filter(|queue| {
+                supported_classes.is_empty() || supported_classes.contains(&queue.work_class)
+            })
+            // Classes that were never served go first, since `None` is less than any `Some`.
+            .min_by_key(|queue| {
+                (
+                    queue.job_priority,
+                    self.last_served.get(&queue.work_class).copied(),
+                    queue.work_class,
+                )
+            })?
+            .work_class;
+
+        self.assignments += 1;
+        self.last_served.insert(work_class, self.assignments);
+        Some(work_class)
+    }
+}
@@ -8,6 +8,8 @@ pub struct ApiToken {
     pub id: TokenId,
     pub address: Address,
     pub symbol: String,
+    /// Unique identifier of the token, differs from the symbol if it's shared with other tokens.
+    pub display_symbol: String,
     pub decimals: u8,
     pub enabled_for_fees: bool,
 }
@@ -28,6 +30,7 @@ impl ApiToken {
             id: token.id,
             address: token.address,
             symbol: token.symbol,
+            display_symbol: token.display_symbol,
             decimals: token.decimals,
             enabled_for_fees: eligibility,
         }
@@ -12,6 +12,7 @@ pub mod operation;
 #[cfg(test)]
 #[cfg(feature = "playground")]
 mod playground;
+pub mod profiler;
 pub mod serialization;
 pub mod signature;
 pub mod utils;
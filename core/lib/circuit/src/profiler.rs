@@ -0,0 +1,306 @@
+//! Profiler of the circuit synthesis.
+//!
+//! `ProfilingConstraintSystem` synthesizes the circuit without creating a proof and collects the
+//! amount of constraints, allocated variables and the time spent for every namespace (gadget) of
+//! the circuit. Chunks of the block are profiled together, so the numbers in the report show the
+//! cost of the gadget summed up over all the chunks. Besides that, the cost of every operation
+//! type is reported separately, so it's easy to see which operations are the most expensive ones.
+//!
+//! The report is serializable, so it can be saved and compared between the circuit versions.
+
+// Built-in deps
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+// External deps
+use serde::{Deserialize, Serialize};
+use zksync_crypto::franklin_crypto::bellman::{
+    pairing::{ff::PrimeField, Engine},
+    Circuit, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable,
+};
+
+/// Prefix of the namespace of every block chunk, see `ZkSyncCircuit::synthesize`.
+const CHUNK_NAMESPACE_PREFIX: &str = "chunk number ";
+/// Name of the chunks namespace in the report, the chunks are profiled together.
+const CHUNK_NAMESPACE: &str = "chunk";
+/// Namespace of the chunk which applies the operation, see `ZkSyncCircuit::execute_op`.
+const EXECUTE_OP_NAMESPACE: &str = "execute_op";
+/// Namespaces of the operations inside of the `execute_op` namespace.
+const OPERATION_NAMESPACES: &[&str] = &[
+    "deposit",
+    "transfer",
+    "transfer_to_new",
+    "withdraw",
+    "close_account",
+    "full_exit",
+    "change_pubkey_offchain",
+    "noop",
+    "forced_exit",
+    "mint_nft",
+    "withdraw_nft",
+    "swap",
+];
+
+/// Cost of the part of the circuit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileEntry {
+    /// Amount of the constraints.
+    pub constraints: usize,
+    /// Amount of the allocated variables, including the public inputs.
+    pub variables: usize,
+    /// Time spent for the synthesis, in microseconds.
+    pub synthesis_time_us: u64,
+}
+
+impl ProfileEntry {
+    fn add_time(&mut self, time: Duration) {
+        self.synthesis_time_us += time.as_micros() as u64;
+    }
+}
+
+/// Profile of the circuit synthesis.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CircuitProfile {
+    /// Amount of the block chunks in the circuit.
+    pub chunks: usize,
+    /// Cost of the whole circuit.
+    pub total: ProfileEntry,
+    /// Amount of the public inputs.
+    pub public_inputs: usize,
+    /// Size of the witness (values of all the allocated variables) in bytes.
+    pub witness_size: usize,
+    /// Cost of every operation type, summed up over all the chunks.
+    pub operations: BTreeMap<String, ProfileEntry>,
+    /// Cost of every gadget. The key is the path of the gadget namespaces joined with `/`,
+    /// the cost of the nested namespaces beyond the profiling depth is included into the gadget.
+    pub gadgets: BTreeMap<String, ProfileEntry>,
+}
+
+/// Synthesizes the circuit and returns its profile. Nested namespaces are profiled up to `depth`
+/// levels, the deeper ones are accounted into their parents.
+pub fn profile_circuit<E: Engine, C: Circuit<E>>(
+    circuit: C,
+    depth: usize,
+) -> Result<CircuitProfile, SynthesisError> {
+    let mut cs = ProfilingConstraintSystem::new(depth);
+    circuit.synthesize(&mut cs)?;
+    Ok(cs.finish())
+}
+
+/// Constraint system which only collects the statistics of the circuit.
+#[derive(Debug)]
+pub struct ProfilingConstraintSystem<E: Engine> {
+    depth: usize,
+    /// Current namespace path, with the chunk number replaced by `CHUNK_NAMESPACE`.
+    namespace: Vec<String>,
+    /// The moment the time of the current namespace started to be accounted.
+    checkpoint: Instant,
+    started_at: Instant,
+    inputs: usize,
+    aux: usize,
+    profile: CircuitProfile,
+    _marker: std::marker::PhantomData<E>,
+}
+
+impl<E: Engine> ProfilingConstraintSystem<E> {
+    pub fn new(depth: usize) -> Self {
+        let now = Instant::now();
+        Self {
+            depth,
+            namespace: Vec::new(),
+            checkpoint: now,
+            started_at: now,
+            // The constant one is always the first input.
+            inputs: 1,
+            aux: 0,
+            profile: CircuitProfile::default(),
+            _marker: Default::default(),
+        }
+    }
+
+    /// Finishes the profiling and returns the collected profile.
+    pub fn finish(mut self) -> CircuitProfile {
+        self.account_time();
+        let mut profile = self.profile;
+        profile.total.variables = self.inputs + self.aux;
+        profile.total.synthesis_time_us = self.started_at.elapsed().as_micros() as u64;
+        profile.public_inputs = self.inputs;
+        profile.witness_size =
+            profile.total.variables * std::mem::size_of::<<E::Fr as PrimeField>::Repr>();
+        profile
+    }
+
+    fn gadget_key(&self) -> String {
+        let depth = self.namespace.len().min(self.depth);
+        self.namespace[..depth].join("/")
+    }
+
+    fn operation_key(&self) -> Option<&str> {
+        match self.namespace.as_slice() {
+            [chunk, execute_op, operation, ..]
+                if chunk == CHUNK_NAMESPACE
+                    && execute_op == EXECUTE_OP_NAMESPACE
+                    && OPERATION_NAMESPACES.contains(&operation.as_str()) =>
+            {
+                Some(operation.as_str())
+            }
+            _ => None,
+        }
+    }
+
+    /// Applies the update to the cost of the current gadget and operation.
+    fn update(&mut self, f: impl Fn(&mut ProfileEntry)) {
+        let gadget = self.gadget_key();
+        f(self.profile.gadgets.entry(gadget).or_default());
+        if let Some(operation) = self.operation_key().map(str::to_owned) {
+            f(self.profile.operations.entry(operation).or_default());
+        }
+    }
+
+    /// Accounts the time passed since the last checkpoint into the current namespace.
+    fn account_time(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.saturating_duration_since(self.checkpoint);
+        self.checkpoint = now;
+        self.update(|entry| entry.add_time(elapsed));
+    }
+}
+
+impl<E: Engine> ConstraintSystem<E> for ProfilingConstraintSystem<E> {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(&mut self, _annotation: A, f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        // The values are calculated to account their cost, but circuits without the witness
+        // (e.g. the ones used for the setup) are profiled as well.
+        match f() {
+            Ok(_) | Err(SynthesisError::AssignmentMissing) => {}
+            Err(err) => return Err(err),
+        }
+        let variable = Variable::new_unchecked(Index::Aux(self.aux));
+        self.aux += 1;
+        self.update(|entry| entry.variables += 1);
+        Ok(variable)
+    }
+
+    fn alloc_input<F, A, AR>(&mut self, _annotation: A, f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        match f() {
+            Ok(_) | Err(SynthesisError::AssignmentMissing) => {}
+            Err(err) => return Err(err),
+        }
+        let variable = Variable::new_unchecked(Index::Input(self.inputs));
+        self.inputs += 1;
+        self.update(|entry| entry.variables += 1);
+        Ok(variable)
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, _annotation: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+        LB: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+        LC: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+    {
+        a(LinearCombination::zero());
+        b(LinearCombination::zero());
+        c(LinearCombination::zero());
+        self.profile.total.constraints += 1;
+        self.update(|entry| entry.constraints += 1);
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.account_time();
+        let name = name_fn().into();
+        if self.namespace.is_empty() && name.starts_with(CHUNK_NAMESPACE_PREFIX) {
+            self.profile.chunks += 1;
+            self.namespace.push(CHUNK_NAMESPACE.to_owned());
+        } else {
+            self.namespace.push(name);
+        }
+    }
+
+    fn pop_namespace(&mut self) {
+        self.account_time();
+        self.namespace.pop();
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_crypto::franklin_crypto::bellman::pairing::{bn256::Bn256, ff::Field};
+
+    struct TestCircuit {
+        chunks: usize,
+    }
+
+    impl<E: Engine> Circuit<E> for TestCircuit {
+        fn synthesize<CS: ConstraintSystem<E>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+            let input = cs.alloc_input(|| "input", || Ok(E::Fr::one()))?;
+            for i in 0..self.chunks {
+                let cs = &mut cs.namespace(|| format!("chunk number {}", i));
+                let cs = &mut cs.namespace(|| EXECUTE_OP_NAMESPACE);
+                {
+                    let cs = &mut cs.namespace(|| "transfer");
+                    let cs = &mut cs.namespace(|| "check balance");
+                    let var = cs.alloc(|| "balance", || Err(SynthesisError::AssignmentMissing))?;
+                    cs.enforce(
+                        || "balance",
+                        |lc| lc + var,
+                        |lc| lc + CS::one(),
+                        |lc| lc + input,
+                    );
+                }
+                let cs = &mut cs.namespace(|| "is_swap");
+                cs.enforce(|| "is_swap", |lc| lc, |lc| lc, |lc| lc);
+            }
+            cs.enforce(|| "input", |lc| lc + input, |lc| lc, |lc| lc);
+            Ok(())
+        }
+    }
+
+    /// Checks that the cost of the gadgets is accounted for the chunks and operations.
+    #[test]
+    fn profile_circuit_cost() {
+        let profile = profile_circuit::<Bn256, _>(TestCircuit { chunks: 3 }, 3).unwrap();
+
+        assert_eq!(profile.chunks, 3);
+        assert_eq!(profile.total.constraints, 7);
+        assert_eq!(profile.total.variables, 5);
+        assert_eq!(profile.public_inputs, 2);
+        assert_eq!(profile.witness_size, 5 * 32);
+
+        let transfer = &profile.operations["transfer"];
+        assert_eq!((transfer.constraints, transfer.variables), (3, 3));
+        assert_eq!(profile.operations.len(), 1);
+
+        // Namespaces deeper than the profiling depth are accounted into their parents.
+        let gadget = &profile.gadgets["chunk/execute_op/transfer"];
+        assert_eq!((gadget.constraints, gadget.variables), (3, 3));
+        let gadget = &profile.gadgets["chunk/execute_op/is_swap"];
+        assert_eq!((gadget.constraints, gadget.variables), (3, 0));
+        let gadget = &profile.gadgets[""];
+        assert_eq!((gadget.constraints, gadget.variables), (1, 1));
+
+        let serialized = serde_json::to_string(&profile).unwrap();
+        let deserialized: CircuitProfile = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, profile);
+    }
+}
@@ -736,6 +736,30 @@ pub fn get_used_subtree_root_hash(account_tree: &CircuitAccountTree) -> Fr {
     current_hash
 }
 
+/// Circuit operations, pubdata and offset commitment data of a single transaction.
+type TxWitnessData = (Vec<Operation<Engine>>, Vec<bool>, Vec<bool>);
+
+/// Calculation of the transaction witness data that doesn't depend on the account tree.
+type TxWitnessTask = Box<dyn FnOnce() -> anyhow::Result<TxWitnessData> + Send>;
+
+/// Creates the task calculating the witness data of the transaction, once the transaction
+/// is applied to the account tree. `input` provides the data required to calculate the
+/// circuit operations, e.g. the signature data.
+fn tx_witness_task<W, F>(witness: W, input: F) -> TxWitnessTask
+where
+    W: Witness + Send + 'static,
+    F: FnOnce() -> anyhow::Result<W::CalculateOpsInput> + Send + 'static,
+{
+    Box::new(move || {
+        let operations = witness.calculate_operations(input()?);
+        Ok((
+            operations,
+            witness.get_pubdata(),
+            witness.get_offset_commitment_data(),
+        ))
+    })
+}
+
 pub fn build_block_witness<'a>(
     account_tree: &'a mut CircuitAccountTree,
     block: &Block,
@@ -752,180 +776,153 @@ pub fn build_block_witness<'a>(
         block.timestamp,
     );
 
-    let ops = block
+    let ops: Vec<_> = block
         .block_transactions
         .iter()
-        .filter_map(|tx| tx.get_executed_op().cloned());
+        .filter_map(|tx| tx.get_executed_op().cloned())
+        .collect();
+
+    // Account tree updates depend on each other, so they are applied one by one. The rest of
+    // the witness (signature data, circuit operations and pubdata) is calculated for every
+    // transaction independently, so the calculation is spawned to the thread pool as soon as
+    // the tree update for the transaction is applied and runs in parallel with the next updates.
+    let mut tx_witnesses: Vec<Option<anyhow::Result<TxWitnessData>>> =
+        ops.iter().map(|_| None).collect();
+    let mut fees = vec![];
+    let account_tree = &mut *witness_accum.account_tree;
+    rayon::scope(|scope| {
+        for (op, tx_witness) in ops.into_iter().zip(tx_witnesses.iter_mut()) {
+            let task = match op {
+                ZkSyncOp::Deposit(deposit) => {
+                    let deposit_witness = DepositWitness::apply_tx(account_tree, &deposit);
+
+                    tx_witness_task(deposit_witness, || Ok(()))
+                }
+                ZkSyncOp::Transfer(transfer) => {
+                    let transfer_witness = TransferWitness::apply_tx(account_tree, &transfer);
+
+                    fees.push(CollectedFee {
+                        token: transfer.tx.token,
+                        amount: transfer.tx.fee.clone(),
+                    });
+                    tx_witness_task(transfer_witness, move || {
+                        SigDataInput::from_transfer_op(&transfer)
+                    })
+                }
+                ZkSyncOp::TransferToNew(transfer_to_new) => {
+                    let transfer_to_new_witness =
+                        TransferToNewWitness::apply_tx(account_tree, &transfer_to_new);
+
+                    fees.push(CollectedFee {
+                        token: transfer_to_new.tx.token,
+                        amount: transfer_to_new.tx.fee.clone(),
+                    });
+                    tx_witness_task(transfer_to_new_witness, move || {
+                        SigDataInput::from_transfer_to_new_op(&transfer_to_new)
+                    })
+                }
+                ZkSyncOp::Withdraw(withdraw) => {
+                    let withdraw_witness = WithdrawWitness::apply_tx(account_tree, &withdraw);
+
+                    fees.push(CollectedFee {
+                        token: withdraw.tx.token,
+                        amount: withdraw.tx.fee.clone(),
+                    });
+                    tx_witness_task(withdraw_witness, move || {
+                        SigDataInput::from_withdraw_op(&withdraw)
+                    })
+                }
+                ZkSyncOp::Close(close) => {
+                    let close_account_witness = CloseAccountWitness::apply_tx(account_tree, &close);
+
+                    tx_witness_task(close_account_witness, move || {
+                        SigDataInput::from_close_op(&close)
+                    })
+                }
+                ZkSyncOp::FullExit(full_exit_op) => {
+                    let success = full_exit_op.withdraw_amount.is_some();
+
+                    let full_exit_witness =
+                        FullExitWitness::apply_tx(account_tree, &(*full_exit_op, success));
+
+                    tx_witness_task(full_exit_witness, || Ok(()))
+                }
+                ZkSyncOp::ChangePubKeyOffchain(change_pkhash_op) => {
+                    let change_pkhash_witness =
+                        ChangePubkeyOffChainWitness::apply_tx(account_tree, &change_pkhash_op);
+
+                    fees.push(CollectedFee {
+                        token: change_pkhash_op.tx.fee_token,
+                        amount: change_pkhash_op.tx.fee.clone(),
+                    });
+                    tx_witness_task(change_pkhash_witness, move || {
+                        SigDataInput::from_change_pubkey_op(&change_pkhash_op)
+                    })
+                }
+                ZkSyncOp::ForcedExit(forced_exit) => {
+                    let forced_exit_witness =
+                        ForcedExitWitness::apply_tx(account_tree, &forced_exit);
+
+                    fees.push(CollectedFee {
+                        token: forced_exit.tx.token,
+                        amount: forced_exit.tx.fee.clone(),
+                    });
+                    tx_witness_task(forced_exit_witness, move || {
+                        SigDataInput::from_forced_exit_op(&forced_exit)
+                    })
+                }
+                ZkSyncOp::Swap(swap) => {
+                    let swap_witness = SwapWitness::apply_tx(account_tree, &swap);
+
+                    fees.push(CollectedFee {
+                        token: swap.tx.fee_token,
+                        amount: swap.tx.fee.clone(),
+                    });
+                    tx_witness_task(swap_witness, move || {
+                        Ok((
+                            SigDataInput::from_order(&swap.tx.orders.0)?,
+                            SigDataInput::from_order(&swap.tx.orders.1)?,
+                            SigDataInput::from_swap_op(&swap)?,
+                        ))
+                    })
+                }
+                ZkSyncOp::Noop(_) => continue, // Noops are handled below
+                ZkSyncOp::MintNFTOp(mint_nft) => {
+                    let mint_nft_witness = MintNFTWitness::apply_tx(account_tree, &mint_nft);
+
+                    fees.push(CollectedFee {
+                        token: mint_nft.tx.fee_token,
+                        amount: mint_nft.tx.fee.clone(),
+                    });
+                    tx_witness_task(mint_nft_witness, move || {
+                        SigDataInput::from_mint_nft_op(&mint_nft)
+                    })
+                }
+                ZkSyncOp::WithdrawNFT(withdraw_nft) => {
+                    let withdraw_nft_witness =
+                        WithdrawNFTWitness::apply_tx(account_tree, &withdraw_nft);
+
+                    fees.push(CollectedFee {
+                        token: withdraw_nft.tx.fee_token,
+                        amount: withdraw_nft.tx.fee.clone(),
+                    });
+                    tx_witness_task(withdraw_nft_witness, move || {
+                        SigDataInput::from_withdraw_nft_op(&withdraw_nft)
+                    })
+                }
+            };
+            scope.spawn(move |_| *tx_witness = Some(task()));
+        }
+    });
 
     let mut operations = vec![];
     let mut pub_data = vec![];
     let mut offset_commitment = vec![];
-    let mut fees = vec![];
-    for op in ops {
-        match op {
-            ZkSyncOp::Deposit(deposit) => {
-                let deposit_witness =
-                    DepositWitness::apply_tx(witness_accum.account_tree, &deposit);
-
-                let deposit_operations = deposit_witness.calculate_operations(());
-                operations.extend(deposit_operations);
-                pub_data.extend(deposit_witness.get_pubdata());
-                offset_commitment.extend(deposit_witness.get_offset_commitment_data())
-            }
-            ZkSyncOp::Transfer(transfer) => {
-                let transfer_witness =
-                    TransferWitness::apply_tx(witness_accum.account_tree, &transfer);
-
-                let input = SigDataInput::from_transfer_op(&transfer)?;
-                let transfer_operations = transfer_witness.calculate_operations(input);
-
-                operations.extend(transfer_operations);
-                fees.push(CollectedFee {
-                    token: transfer.tx.token,
-                    amount: transfer.tx.fee,
-                });
-                pub_data.extend(transfer_witness.get_pubdata());
-                offset_commitment.extend(transfer_witness.get_offset_commitment_data())
-            }
-            ZkSyncOp::TransferToNew(transfer_to_new) => {
-                let transfer_to_new_witness =
-                    TransferToNewWitness::apply_tx(witness_accum.account_tree, &transfer_to_new);
-
-                let input = SigDataInput::from_transfer_to_new_op(&transfer_to_new)?;
-                let transfer_to_new_operations =
-                    transfer_to_new_witness.calculate_operations(input);
-
-                operations.extend(transfer_to_new_operations);
-                fees.push(CollectedFee {
-                    token: transfer_to_new.tx.token,
-                    amount: transfer_to_new.tx.fee,
-                });
-                pub_data.extend(transfer_to_new_witness.get_pubdata());
-                offset_commitment.extend(transfer_to_new_witness.get_offset_commitment_data())
-            }
-            ZkSyncOp::Withdraw(withdraw) => {
-                let withdraw_witness =
-                    WithdrawWitness::apply_tx(witness_accum.account_tree, &withdraw);
-
-                let input = SigDataInput::from_withdraw_op(&withdraw)?;
-                let withdraw_operations = withdraw_witness.calculate_operations(input);
-
-                operations.extend(withdraw_operations);
-                fees.push(CollectedFee {
-                    token: withdraw.tx.token,
-                    amount: withdraw.tx.fee,
-                });
-                pub_data.extend(withdraw_witness.get_pubdata());
-                offset_commitment.extend(withdraw_witness.get_offset_commitment_data())
-            }
-            ZkSyncOp::Close(close) => {
-                let close_account_witness =
-                    CloseAccountWitness::apply_tx(witness_accum.account_tree, &close);
-
-                let input = SigDataInput::from_close_op(&close)?;
-                let close_account_operations = close_account_witness.calculate_operations(input);
-
-                operations.extend(close_account_operations);
-                pub_data.extend(close_account_witness.get_pubdata());
-                offset_commitment.extend(close_account_witness.get_offset_commitment_data())
-            }
-            ZkSyncOp::FullExit(full_exit_op) => {
-                let success = full_exit_op.withdraw_amount.is_some();
-
-                let full_exit_witness = FullExitWitness::apply_tx(
-                    witness_accum.account_tree,
-                    &(*full_exit_op, success),
-                );
-
-                let full_exit_operations = full_exit_witness.calculate_operations(());
-
-                operations.extend(full_exit_operations);
-                pub_data.extend(full_exit_witness.get_pubdata());
-                offset_commitment.extend(full_exit_witness.get_offset_commitment_data())
-            }
-            ZkSyncOp::ChangePubKeyOffchain(change_pkhash_op) => {
-                let change_pkhash_witness = ChangePubkeyOffChainWitness::apply_tx(
-                    witness_accum.account_tree,
-                    &change_pkhash_op,
-                );
-
-                let input = SigDataInput::from_change_pubkey_op(&change_pkhash_op)?;
-                let change_pkhash_operations = change_pkhash_witness.calculate_operations(input);
-
-                operations.extend(change_pkhash_operations);
-                fees.push(CollectedFee {
-                    token: change_pkhash_op.tx.fee_token,
-                    amount: change_pkhash_op.tx.fee,
-                });
-                pub_data.extend(change_pkhash_witness.get_pubdata());
-                offset_commitment.extend(change_pkhash_witness.get_offset_commitment_data())
-            }
-            ZkSyncOp::ForcedExit(forced_exit) => {
-                let forced_exit_witness =
-                    ForcedExitWitness::apply_tx(witness_accum.account_tree, &forced_exit);
-
-                let input = SigDataInput::from_forced_exit_op(&forced_exit)?;
-                let forced_exit_operations = forced_exit_witness.calculate_operations(input);
-
-                operations.extend(forced_exit_operations);
-                fees.push(CollectedFee {
-                    token: forced_exit.tx.token,
-                    amount: forced_exit.tx.fee,
-                });
-                pub_data.extend(forced_exit_witness.get_pubdata());
-                offset_commitment.extend(forced_exit_witness.get_offset_commitment_data())
-            }
-            ZkSyncOp::Swap(swap) => {
-                let swap_witness = SwapWitness::apply_tx(witness_accum.account_tree, &swap);
-
-                let input = (
-                    SigDataInput::from_order(&swap.tx.orders.0)?,
-                    SigDataInput::from_order(&swap.tx.orders.1)?,
-                    SigDataInput::from_swap_op(&swap)?,
-                );
-
-                let swap_operations = swap_witness.calculate_operations(input);
-
-                operations.extend(swap_operations);
-                fees.push(CollectedFee {
-                    token: swap.tx.fee_token,
-                    amount: swap.tx.fee,
-                });
-                pub_data.extend(swap_witness.get_pubdata());
-                offset_commitment.extend(swap_witness.get_offset_commitment_data())
-            }
-            ZkSyncOp::Noop(_) => {} // Noops are handled below
-            ZkSyncOp::MintNFTOp(mint_nft) => {
-                let mint_nft_witness =
-                    MintNFTWitness::apply_tx(witness_accum.account_tree, &mint_nft);
-
-                let input = SigDataInput::from_mint_nft_op(&mint_nft)?;
-                let mint_nft_operations = mint_nft_witness.calculate_operations(input);
-
-                operations.extend(mint_nft_operations);
-                fees.push(CollectedFee {
-                    token: mint_nft.tx.fee_token,
-                    amount: mint_nft.tx.fee,
-                });
-                pub_data.extend(mint_nft_witness.get_pubdata());
-                offset_commitment.extend(mint_nft_witness.get_offset_commitment_data())
-            }
-            ZkSyncOp::WithdrawNFT(withdraw_nft) => {
-                let withdraw_nft_witness =
-                    WithdrawNFTWitness::apply_tx(witness_accum.account_tree, &withdraw_nft);
-
-                let input = SigDataInput::from_withdraw_nft_op(&withdraw_nft)?;
-                let withdraw_nft_operations = withdraw_nft_witness.calculate_operations(input);
-
-                operations.extend(withdraw_nft_operations);
-                fees.push(CollectedFee {
-                    token: withdraw_nft.tx.fee_token,
-                    amount: withdraw_nft.tx.fee,
-                });
-                pub_data.extend(withdraw_nft_witness.get_pubdata());
-                offset_commitment.extend(withdraw_nft_witness.get_offset_commitment_data())
-            }
-        }
+    for tx_witness in tx_witnesses.into_iter().flatten() {
+        let (tx_operations, tx_pub_data, tx_offset_commitment) = tx_witness?;
+        operations.extend(tx_operations);
+        pub_data.extend(tx_pub_data);
+        offset_commitment.extend(tx_offset_commitment);
     }
 
     witness_accum.add_operation_with_pubdata(operations, pub_data, offset_commitment);
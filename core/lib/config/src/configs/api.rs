@@ -6,9 +6,9 @@ use std::net::SocketAddr;
 use std::time::Duration;
 use zksync_utils::scaled_u64_to_ratio;
 // Workspace uses
-use zksync_types::AccountId;
+use zksync_types::{event::transaction::TransactionType, AccountId};
 // Local uses
-use crate::envy_load;
+use crate::{envy_load, loader::Reloadable};
 
 /// API configuration.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -27,6 +27,8 @@ pub struct ApiConfig {
     pub private: PrivateApiConfig,
     /// Configuration options for the prover server.
     pub prover: ProverApiConfig,
+    /// Configuration options for the exit proof server.
+    pub exit_proof: ExitProofApiConfig,
     /// Configuration options for the Prometheus exporter.
     pub prometheus: PrometheusConfig,
     pub token_config: TokenConfig,
@@ -42,6 +44,7 @@ impl ApiConfig {
             web3: envy_load!("web3", "API_WEB3_"),
             private: envy_load!("private", "API_PRIVATE_"),
             prover: envy_load!("prover", "API_PROVER_"),
+            exit_proof: envy_load!("exit_proof", "API_EXIT_PROOF_"),
             prometheus: envy_load!("prometheus", "API_PROMETHEUS_"),
             token_config: envy_load!("token", "API_TOKEN_"),
         }
@@ -53,9 +56,22 @@ impl CommonApiConfig {
         scaled_u64_to_ratio(self.max_subsidy_usd_scaled)
     }
 
+    pub fn free_withdrawal_max_balance_usd(&self) -> Ratio<BigUint> {
+        scaled_u64_to_ratio(self.free_withdrawal_max_balance_usd_scaled)
+    }
+
+    pub fn free_withdrawal_period(&self) -> Duration {
+        Duration::from_secs(self.free_withdrawal_period_secs)
+    }
+
     pub fn from_env() -> Self {
         envy_load!("common", "API_COMMON_")
     }
+
+    /// Returns the config updated from the config variables on `loader::reload`.
+    pub fn into_reloadable(self) -> Reloadable<Self> {
+        Reloadable::new("common", "API_COMMON_", self)
+    }
 }
 
 impl AdminApiConfig {
@@ -87,6 +103,12 @@ impl PrivateApiConfig {
         envy_load!("private", "API_PRIVATE_")
     }
 }
+impl ExitProofApiConfig {
+    pub fn from_env() -> Self {
+        envy_load!("exit_proof", "API_EXIT_PROOF_")
+    }
+}
+
 impl ProverApiConfig {
     pub fn from_env() -> Self {
         envy_load!("prover", "API_PROVER_")
@@ -109,6 +131,9 @@ pub struct CommonApiConfig {
     pub forced_exit_minimum_account_age_secs: u64,
     /// List of account IDs that do not have to pay fees for operations.
     pub fee_free_accounts: Vec<AccountId>,
+    /// Types of the transactions (e.g. `Transfer`) that do not have to pay fees, the unknown types are rejected.
+    /// Fees waived for both fee-free accounts and transaction types are stored as subsidies.
+    pub fee_free_tx_types: Vec<TransactionType>,
     pub enforce_pubkey_change_fee: bool,
 
     pub max_number_of_transactions_per_batch: u64,
@@ -122,6 +147,20 @@ pub struct CommonApiConfig {
 
     /// The name of current subsidy. It is needed to conveniently fetch historical data regarding subsidies for different partners
     pub subsidy_name: String,
+
+    /// Accounts with the total balance below this value (scaled by SUBSIDY_USD_AMOUNTS_SCALE)
+    /// can make one withdrawal per period without the fee. The policy is disabled if it's 0.
+    /// Only the withdrawals submitted outside of the batches are granted.
+    pub free_withdrawal_max_balance_usd_scaled: u64,
+    /// Period of the fee-free withdrawal quota in seconds.
+    pub free_withdrawal_period_secs: u64,
+
+    /// Whether the Ethereum signatures of the messages without the chain id are accepted.
+    /// Should be disabled once the migration to the chain-bound messages is over.
+    pub accept_eth_messages_without_chain_id: bool,
+    /// Number of the signers recovered from the Ethereum signatures kept in memory,
+    /// so the resubmitted transactions are not verified again.
+    pub eth_signature_cache_size: usize,
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -164,12 +203,42 @@ pub struct ProverApiConfig {
     pub url: String,
     /// Secret used to generate access token (JWT).
     pub secret_auth: String,
+    /// Port to which the job stream server is listening.
+    pub stream_port: u16,
+    /// URL to access the job stream server.
+    pub stream_url: String,
+    /// Whether the provers have to authenticate with their own tokens instead of the
+    /// tokens derived from the shared secret. The shared secret is still accepted
+    /// by the internal endpoints (e.g. the scaler ones).
+    pub per_prover_auth: bool,
 }
 
 impl ProverApiConfig {
     pub fn bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
     }
+
+    pub fn stream_bind_addr(&self) -> SocketAddr {
+        SocketAddr::new("0.0.0.0".parse().unwrap(), self.stream_port)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ExitProofApiConfig {
+    /// Port to which the API server is listening.
+    pub port: u16,
+    /// URL to access API server.
+    pub url: String,
+    /// Secret used to generate access token (JWT).
+    pub secret_auth: String,
+    /// Maximum amount of the generated exit proofs kept in the cache.
+    pub max_cached_proofs: usize,
+}
+
+impl ExitProofApiConfig {
+    pub fn bind_addr(&self) -> SocketAddr {
+        SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -260,9 +329,14 @@ mod tests {
                 max_number_of_transactions_per_batch: 200,
                 max_number_of_authors_per_batch: 10,
                 fee_free_accounts: vec![AccountId(4078), AccountId(387)],
+                fee_free_tx_types: vec![TransactionType::ChangePubKey],
                 subsidized_ips: vec!["127.0.0.1".to_owned()],
                 max_subsidy_usd_scaled: 20000,
                 subsidy_name: String::from("PartnerName"),
+                free_withdrawal_max_balance_usd_scaled: 5000000,
+                free_withdrawal_period_secs: 2592000,
+                accept_eth_messages_without_chain_id: true,
+                eth_signature_cache_size: 20000,
             },
             admin: AdminApiConfig {
                 port: 8080,
@@ -293,6 +367,15 @@ mod tests {
                 port: 8088,
                 url: "http://127.0.0.1:8088".into(),
                 secret_auth: "sample".into(),
+                stream_port: 8089,
+                stream_url: "http://127.0.0.1:8089".into(),
+                per_prover_auth: false,
+            },
+            exit_proof: ExitProofApiConfig {
+                port: 8091,
+                url: "http://127.0.0.1:8091".into(),
+                secret_auth: "sample".into(),
+                max_cached_proofs: 1000,
             },
             prometheus: PrometheusConfig { port: 3312 },
             token_config: TokenConfig {
@@ -307,12 +390,17 @@ mod tests {
 API_COMMON_CACHES_SIZE="10000"
 API_COMMON_FORCED_EXIT_MINIMUM_ACCOUNT_AGE_SECS="0"
 API_COMMON_FEE_FREE_ACCOUNTS=4078,387
+API_COMMON_FEE_FREE_TX_TYPES=ChangePubKey
 API_COMMON_ENFORCE_PUBKEY_CHANGE_FEE=true
 API_COMMON_SUBSIDIZED_IPS="127.0.0.1"
 API_COMMON_MAX_SUBSIDY_USD_SCALED=20000
 API_COMMON_SUBSIDY_NAME=PartnerName
+API_COMMON_FREE_WITHDRAWAL_MAX_BALANCE_USD_SCALED=5000000
+API_COMMON_FREE_WITHDRAWAL_PERIOD_SECS=2592000
 API_COMMON_MAX_NUMBER_OF_TRANSACTIONS_PER_BATCH=200
 API_COMMON_MAX_NUMBER_OF_AUTHORS_PER_BATCH=10
+API_COMMON_ACCEPT_ETH_MESSAGES_WITHOUT_CHAIN_ID=true
+API_COMMON_ETH_SIGNATURE_CACHE_SIZE="20000"
 API_TOKEN_INVALIDATE_TOKEN_CACHE_PERIOD_SEC="10"
 API_ADMIN_PORT="8080"
 API_ADMIN_URL="http://127.0.0.1:8080"
@@ -332,6 +420,13 @@ API_PRIVATE_URL="http://127.0.0.1:8090"
 API_PROVER_PORT="8088"
 API_PROVER_URL="http://127.0.0.1:8088"
 API_PROVER_SECRET_AUTH="sample"
+API_PROVER_STREAM_PORT="8089"
+API_PROVER_STREAM_URL="http://127.0.0.1:8089"
+API_PROVER_PER_PROVER_AUTH="false"
+API_EXIT_PROOF_PORT="8091"
+API_EXIT_PROOF_URL="http://127.0.0.1:8091"
+API_EXIT_PROOF_SECRET_AUTH="sample"
+API_EXIT_PROOF_MAX_CACHED_PROOFS="1000"
 API_PROMETHEUS_PORT="3312"
         "#;
         set_env(config);
@@ -354,6 +449,14 @@ API_PROMETHEUS_PORT="3312"
             config.prover.bind_addr(),
             SocketAddr::new(bind_broadcast_addr, config.prover.port)
         );
+        assert_eq!(
+            config.prover.stream_bind_addr(),
+            SocketAddr::new(bind_broadcast_addr, config.prover.stream_port)
+        );
+        assert_eq!(
+            config.exit_proof.bind_addr(),
+            SocketAddr::new(bind_broadcast_addr, config.exit_proof.port)
+        );
         assert_eq!(
             config.rest.bind_addr(),
             SocketAddr::new(bind_broadcast_addr, config.rest.port)
@@ -18,6 +18,14 @@ pub struct DBConfig {
     pub rejected_transactions_max_age: u64,
     /// Sleep time (in hours) of the actor responsible for deleting failed transactions from the database.
     pub rejected_transactions_cleaner_interval: u64,
+    /// Level of the zstd compression of the proofs, witnesses and aggregated operations stored
+    /// in the database. They are stored uncompressed if not set.
+    pub compression_level: Option<i32>,
+    /// Key of the Postgres advisory lock used for the leader election between the server replicas.
+    /// Leader election is disabled if not set.
+    pub leader_lock_id: Option<i64>,
+    /// Interval (in seconds) of the leader lock checks by the leader and the acquiring attempts by the standby.
+    pub leader_check_interval: u64,
 }
 
 impl DBConfig {
@@ -34,6 +42,10 @@ impl DBConfig {
     pub fn rejected_transactions_cleaner_interval(&self) -> time::Duration {
         time::Duration::from_secs(self.rejected_transactions_cleaner_interval)
     }
+
+    pub fn leader_check_interval(&self) -> time::Duration {
+        time::Duration::from_secs(self.leader_check_interval)
+    }
 }
 
 #[cfg(test)]
@@ -47,6 +59,9 @@ mod tests {
             url: "postgres://postgres@localhost/plasma".into(),
             rejected_transactions_max_age: 336,
             rejected_transactions_cleaner_interval: 24,
+            compression_level: Some(3),
+            leader_lock_id: Some(42),
+            leader_check_interval: 5,
         }
     }
 
@@ -57,6 +72,9 @@ DATABASE_POOL_SIZE="10"
 DATABASE_URL="postgres://postgres@localhost/plasma"
 DATABASE_REJECTED_TRANSACTIONS_MAX_AGE="336"
 DATABASE_REJECTED_TRANSACTIONS_CLEANER_INTERVAL="24"
+DATABASE_COMPRESSION_LEVEL="3"
+DATABASE_LEADER_LOCK_ID="42"
+DATABASE_LEADER_CHECK_INTERVAL="5"
         "#;
         set_env(config);
 
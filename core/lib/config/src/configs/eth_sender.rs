@@ -44,6 +44,9 @@ pub struct Sender {
     pub max_txs_in_flight: u64,
     /// Whether sender should interact with L1 or not.
     pub is_enabled: bool,
+    /// Amount of blocks before the expiration of the processed priority operations, starting
+    /// from which the proof and execute transactions are sent with the escalated gas price.
+    pub expiration_escalation_window: u64,
 }
 
 impl Sender {
@@ -63,6 +66,8 @@ pub struct GasLimit {
     pub sample_interval: u64,
     /// Scale factor for gas price limit (used by GasAdjuster).
     pub scale_factor: f64,
+    /// Gas price limit for the transactions that must be mined before the priority operations expire.
+    pub emergency_limit: u64,
 }
 
 impl GasLimit {
@@ -90,6 +95,7 @@ mod tests {
                 tx_poll_period: 3,
                 max_txs_in_flight: 3,
                 is_enabled: true,
+                expiration_escalation_window: 1000,
                 operator_private_key: hash(
                     "27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be",
                 ),
@@ -100,6 +106,7 @@ mod tests {
                 update_interval: 150,
                 sample_interval: 15,
                 scale_factor: 1.0f64,
+                emergency_limit: 2000000000000,
             },
         }
     }
@@ -112,12 +119,14 @@ ETH_SENDER_SENDER_EXPECTED_WAIT_TIME_BLOCK="30"
 ETH_SENDER_SENDER_TX_POLL_PERIOD="3"
 ETH_SENDER_SENDER_MAX_TXS_IN_FLIGHT="3"
 ETH_SENDER_SENDER_IS_ENABLED="true"
+ETH_SENDER_SENDER_EXPIRATION_ESCALATION_WINDOW="1000"
 ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY="0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be"
 ETH_SENDER_SENDER_OPERATOR_COMMIT_ETH_ADDR="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
 ETH_SENDER_GAS_PRICE_LIMIT_DEFAULT="400000000000"
 ETH_SENDER_GAS_PRICE_LIMIT_UPDATE_INTERVAL="150"
 ETH_SENDER_GAS_PRICE_LIMIT_SAMPLE_INTERVAL="15"
 ETH_SENDER_GAS_PRICE_LIMIT_SCALE_FACTOR="1"
+ETH_SENDER_GAS_PRICE_LIMIT_EMERGENCY_LIMIT="2000000000000"
         "#;
         set_env(config);
 
@@ -10,10 +10,34 @@ use crate::envy_load;
 pub struct ETHWatchConfig {
     /// Amount of confirmations for the priority operation to be processed.
     /// In production this should be a non-zero value because of block reverts.
+    /// Full exits use the `confirmations_for_full_exit` value instead.
     pub confirmations_for_eth_event: u64,
-    /// How often we want to poll the Ethereum node.
+    /// Amount of confirmations for the priority operation to be reported as pending.
+    /// Pending deposits are only shown to users and are not processed.
+    pub confirmations_for_pending_deposit: u64,
+    /// Amount of confirmations for the full exit priority operation to be processed.
+    pub confirmations_for_full_exit: u64,
+    /// Amount of confirmations for the Governance contract events (e.g. new tokens) to be processed.
+    pub confirmations_for_governance_event: u64,
+    /// How often we want to poll the Ethereum node while catching up with the chain.
     /// Value in milliseconds.
     pub eth_node_poll_interval: u64,
+    /// Near the head of the chain the polling interval is adjusted to the observed block time,
+    /// but never exceeds this value.
+    /// Value in milliseconds.
+    pub eth_node_max_poll_interval: u64,
+    /// WebSocket URL of the Ethereum node. If set, instead of polling the node with the
+    /// `eth_node_poll_interval`, the watcher subscribes to the new blocks and contract logs
+    /// and polls the node only when notified.
+    pub eth_node_ws_url: Option<String>,
+    /// In the WebSocket mode, the node is polled if there were no notifications for this time.
+    /// Once the connection is lost, the watcher falls back to the regular polling for this time
+    /// before trying to subscribe again.
+    /// Value in milliseconds.
+    pub eth_node_ws_fallback_interval: u64,
+    /// How often the validator status updates collected by the watcher are stored.
+    /// Value in seconds.
+    pub validator_status_poll_interval: u64,
 }
 
 impl ETHWatchConfig {
@@ -25,6 +49,21 @@ impl ETHWatchConfig {
     pub fn poll_interval(&self) -> Duration {
         Duration::from_millis(self.eth_node_poll_interval)
     }
+
+    /// Converts `self.eth_node_max_poll_interval` into `Duration`.
+    pub fn max_poll_interval(&self) -> Duration {
+        Duration::from_millis(self.eth_node_max_poll_interval)
+    }
+
+    /// Converts `self.eth_node_ws_fallback_interval` into `Duration`.
+    pub fn ws_fallback_interval(&self) -> Duration {
+        Duration::from_millis(self.eth_node_ws_fallback_interval)
+    }
+
+    /// Converts `self.validator_status_poll_interval` into `Duration`.
+    pub fn validator_status_poll_interval(&self) -> Duration {
+        Duration::from_secs(self.validator_status_poll_interval)
+    }
 }
 
 #[cfg(test)]
@@ -35,7 +74,14 @@ mod tests {
     fn expected_config() -> ETHWatchConfig {
         ETHWatchConfig {
             confirmations_for_eth_event: 0,
+            confirmations_for_pending_deposit: 0,
+            confirmations_for_full_exit: 5,
+            confirmations_for_governance_event: 10,
             eth_node_poll_interval: 300,
+            eth_node_max_poll_interval: 5000,
+            eth_node_ws_url: Some("ws://127.0.0.1:8546".into()),
+            eth_node_ws_fallback_interval: 10000,
+            validator_status_poll_interval: 3,
         }
     }
 
@@ -43,7 +89,14 @@ mod tests {
     fn from_env() {
         let config = r#"
 ETH_WATCH_CONFIRMATIONS_FOR_ETH_EVENT="0"
+ETH_WATCH_CONFIRMATIONS_FOR_PENDING_DEPOSIT="0"
+ETH_WATCH_CONFIRMATIONS_FOR_FULL_EXIT="5"
+ETH_WATCH_CONFIRMATIONS_FOR_GOVERNANCE_EVENT="10"
 ETH_WATCH_ETH_NODE_POLL_INTERVAL="300"
+ETH_WATCH_ETH_NODE_MAX_POLL_INTERVAL="5000"
+ETH_WATCH_ETH_NODE_WS_URL="ws://127.0.0.1:8546"
+ETH_WATCH_ETH_NODE_WS_FALLBACK_INTERVAL="10000"
+ETH_WATCH_VALIDATOR_STATUS_POLL_INTERVAL="3"
         "#;
         set_env(config);
 
@@ -60,5 +113,17 @@ ETH_WATCH_ETH_NODE_POLL_INTERVAL="300"
             config.poll_interval(),
             Duration::from_millis(config.eth_node_poll_interval)
         );
+        assert_eq!(
+            config.max_poll_interval(),
+            Duration::from_millis(config.eth_node_max_poll_interval)
+        );
+        assert_eq!(
+            config.ws_fallback_interval(),
+            Duration::from_millis(config.eth_node_ws_fallback_interval)
+        );
+        assert_eq!(
+            config.validator_status_poll_interval(),
+            Duration::from_secs(config.validator_status_poll_interval)
+        );
     }
 }
@@ -4,7 +4,7 @@ pub use self::{
     dev_liquidity_token_watcher::DevLiquidityTokenWatcherConfig, eth_client::ETHClientConfig,
     eth_sender::ETHSenderConfig, eth_watch::ETHWatchConfig, event_listener::EventListenerConfig,
     forced_exit_requests::ForcedExitRequestsConfig, gateway_watcher::GatewayWatcherConfig,
-    misc::MiscConfig, prover::ProverConfig, ticker::TickerConfig,
+    misc::MiscConfig, object_store::ObjectStoreConfig, prover::ProverConfig, ticker::TickerConfig,
     token_handler::TokenHandlerConfig,
 };
 
@@ -20,6 +20,7 @@ pub mod event_listener;
 pub mod forced_exit_requests;
 pub mod gateway_watcher;
 pub mod misc;
+pub mod object_store;
 pub mod prover;
 pub mod ticker;
 pub mod token_handler;
@@ -27,16 +28,15 @@ pub mod token_handler;
 #[cfg(test)]
 pub(crate) mod test_utils;
 
-/// Convenience macro that loads the structure from the environment variable given the prefix.
+/// Convenience macro that loads the structure from the configuration variables given the prefix.
+/// See the `loader` module for the layers the variables are taken from.
 ///
 /// # Panics
 ///
-/// Panics if the config cannot be loaded from the environment variables.
+/// Panics if the config cannot be loaded from the configuration variables.
 #[macro_export]
 macro_rules! envy_load {
     ($name:expr, $prefix:expr) => {
-        envy::prefixed($prefix)
-            .from_env()
-            .unwrap_or_else(|err| panic!("Cannot load config <{}>: {}", $name, err))
+        $crate::loader::load($name, $prefix).unwrap_or_else(|err| panic!("{}", err))
     };
 }
@@ -0,0 +1,76 @@
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Storage backend for the prover artifacts, i.e. block witnesses and proofs.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ObjectStoreMode {
+    /// Artifacts are stored in the database itself.
+    Database,
+    /// Artifacts are stored in the local directory, which is useful for development.
+    File,
+    /// Artifacts are stored in the S3 bucket.
+    S3,
+    /// Artifacts are stored in the Google Cloud Storage bucket via its S3-compatible API.
+    Gcs,
+}
+
+/// Configuration of the object storage for the prover artifacts.
+/// With any mode other than `database`, only the keys of the objects are stored in the database.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ObjectStoreConfig {
+    pub mode: ObjectStoreMode,
+    /// Name of the bucket, or the path to the root directory in the `file` mode.
+    pub bucket: String,
+    /// Region of the bucket.
+    pub region: String,
+    /// Custom endpoint of the S3-compatible storage. If not set, the default endpoint of
+    /// the chosen mode is used.
+    pub endpoint: Option<String>,
+    /// Prefix of the object keys, so several environments can share the same bucket.
+    pub key_prefix: String,
+    /// Level of the zstd compression of the stored objects. Objects are stored uncompressed
+    /// if not set.
+    pub compression_level: Option<i32>,
+}
+
+impl ObjectStoreConfig {
+    pub fn from_env() -> Self {
+        envy_load!("object_store", "OBJECT_STORE_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> ObjectStoreConfig {
+        ObjectStoreConfig {
+            mode: ObjectStoreMode::S3,
+            bucket: "zksync-artifacts".into(),
+            region: "us-east-1".into(),
+            endpoint: Some("http://127.0.0.1:9000".into()),
+            key_prefix: "localhost".into(),
+            compression_level: Some(3),
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+OBJECT_STORE_MODE="s3"
+OBJECT_STORE_BUCKET="zksync-artifacts"
+OBJECT_STORE_REGION="us-east-1"
+OBJECT_STORE_ENDPOINT="http://127.0.0.1:9000"
+OBJECT_STORE_KEY_PREFIX="localhost"
+OBJECT_STORE_COMPRESSION_LEVEL="3"
+        "#;
+        set_env(config);
+
+        let actual = ObjectStoreConfig::from_env();
+        assert_eq!(actual, expected_config());
+    }
+}
@@ -11,6 +11,7 @@ pub struct ProverConfig {
     pub prover: Prover,
     pub core: Core,
     pub witness_generator: WitnessGenerator,
+    pub dummy_prover: DummyProver,
 }
 
 impl ProverConfig {
@@ -19,6 +20,7 @@ impl ProverConfig {
             prover: envy_load!("prover.prover", "PROVER_PROVER_"),
             core: envy_load!("prover.core", "PROVER_CORE_"),
             witness_generator: envy_load!("prover.witness_generator", "PROVER_WITNESS_GENERATOR_"),
+            dummy_prover: envy_load!("prover.dummy_prover", "PROVER_DUMMY_PROVER_"),
         }
     }
 }
@@ -34,6 +36,15 @@ pub struct Prover {
     pub request_timeout: u64,
     /// Flag for dying after proving cycle
     pub die_after_proof: bool,
+    /// Amount of the threads used for proving, all the available CPUs are used if set to 0.
+    pub proving_threads: usize,
+    /// Memory available on the machine required to accept a new job in megabytes, 0 disables the check.
+    pub min_available_memory: u64,
+    /// CPUs the proving threads are pinned to. If not set, threads are not pinned.
+    pub cpu_affinity: Option<Vec<usize>>,
+    /// Level of the zstd compression of the proofs sent over the job stream. Proofs are sent
+    /// uncompressed if not set.
+    pub compression_level: Option<i32>,
 }
 
 impl Prover {
@@ -56,10 +67,21 @@ impl Prover {
 /// Core settings related to the prover applications interacting with it.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Core {
-    /// Timeout to consider prover gone in ms.
+    /// Timeout to consider prover gone in ms, its job is returned to the queue afterwards.
     pub gone_timeout: u64,
     /// Amount of provers in the cluser if there is no pending jobs.
     pub idle_provers: u32,
+    /// Time after which an idle job is moved to the urgent lane of the job queue in seconds.
+    pub urgent_job_timeout: u64,
+    /// Amount of the times a job can be returned to the queue after its prover is gone.
+    /// Once it's reached, the job is quarantined and is not assigned to provers anymore.
+    pub max_job_attempts: u32,
+    /// Whether the received proofs are verified before they're stored. Invalid proofs are rejected
+    /// and their jobs are returned to the queue.
+    pub verify_proofs: bool,
+    /// Level of the zstd compression of the jobs sent to the provers over the job stream.
+    /// Jobs are sent uncompressed if not set.
+    pub compression_level: Option<i32>,
 }
 
 impl Core {
@@ -67,6 +89,11 @@ impl Core {
     pub fn gone_timeout(&self) -> Duration {
         Duration::from_millis(self.gone_timeout)
     }
+
+    /// Converts `self.urgent_job_timeout` into `Duration`.
+    pub fn urgent_job_timeout(&self) -> Duration {
+        Duration::from_secs(self.urgent_job_timeout)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -75,6 +102,13 @@ pub struct WitnessGenerator {
     pub prepare_data_interval: u64,
     /// Amount of witness generator threads.
     pub witness_generators: usize,
+    /// Whether the witnesses are generated by the standalone witness generator service,
+    /// in which case the prover server doesn't run the witness generators itself.
+    pub standalone: bool,
+    /// Maximum amount of the witnesses waiting for their proofs. Witnesses of the next blocks
+    /// are generated while the previous blocks are proven, until the limit is reached.
+    /// 0 means there is no limit.
+    pub max_witnesses_in_flight: usize,
 }
 
 impl WitnessGenerator {
@@ -84,6 +118,75 @@ impl WitnessGenerator {
     }
 }
 
+/// Distribution of the proving time of the dummy prover.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LatencyDistribution {
+    /// Every proof takes `min_latency`.
+    Fixed,
+    /// Proving time is distributed uniformly between `min_latency` and `max_latency`.
+    Uniform,
+    /// Proving time exceeds `min_latency` by the exponentially distributed time, so that the mean
+    /// proving time is `mean_latency`. It's bounded by `max_latency`.
+    Exponential,
+}
+
+/// Dummy prover settings, used to simulate the real provers in the development environments.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct DummyProver {
+    /// Distribution of the time it takes to create a proof.
+    pub latency_distribution: LatencyDistribution,
+    /// Minimal proving time in ms.
+    pub min_latency: u64,
+    /// Mean proving time in ms, used by the exponential distribution.
+    pub mean_latency: u64,
+    /// Maximal proving time in ms.
+    pub max_latency: u64,
+    /// Probability of the proving to fail once the proving time has passed.
+    pub failure_probability: f64,
+    /// Probability of the proving to hang for `timeout` before failing.
+    pub timeout_probability: f64,
+    /// Time the proving hangs for in ms.
+    pub timeout: u64,
+}
+
+impl Default for DummyProver {
+    /// Dummy prover which creates proofs instantly and never fails.
+    fn default() -> Self {
+        Self {
+            latency_distribution: LatencyDistribution::Fixed,
+            min_latency: 0,
+            mean_latency: 0,
+            max_latency: 0,
+            failure_probability: 0.0,
+            timeout_probability: 0.0,
+            timeout: 0,
+        }
+    }
+}
+
+impl DummyProver {
+    /// Converts `self.min_latency` into `Duration`.
+    pub fn min_latency(&self) -> Duration {
+        Duration::from_millis(self.min_latency)
+    }
+
+    /// Converts `self.mean_latency` into `Duration`.
+    pub fn mean_latency(&self) -> Duration {
+        Duration::from_millis(self.mean_latency)
+    }
+
+    /// Converts `self.max_latency` into `Duration`.
+    pub fn max_latency(&self) -> Duration {
+        Duration::from_millis(self.max_latency)
+    }
+
+    /// Converts `self.timeout` into `Duration`.
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,14 +199,33 @@ mod tests {
                 cycle_wait: 500,
                 request_timeout: 10,
                 die_after_proof: false,
+                proving_threads: 4,
+                min_available_memory: 8192,
+                cpu_affinity: Some(vec![0, 1, 2, 3]),
+                compression_level: Some(3),
             },
             core: Core {
                 gone_timeout: 60000,
                 idle_provers: 1,
+                urgent_job_timeout: 1800,
+                max_job_attempts: 3,
+                verify_proofs: true,
+                compression_level: Some(3),
             },
             witness_generator: WitnessGenerator {
                 prepare_data_interval: 500,
                 witness_generators: 2,
+                standalone: false,
+                max_witnesses_in_flight: 8,
+            },
+            dummy_prover: DummyProver {
+                latency_distribution: LatencyDistribution::Exponential,
+                min_latency: 1000,
+                mean_latency: 5000,
+                max_latency: 30000,
+                failure_probability: 0.01,
+                timeout_probability: 0.005,
+                timeout: 120000,
             },
         }
     }
@@ -115,10 +237,27 @@ PROVER_PROVER_HEARTBEAT_INTERVAL="1000"
 PROVER_PROVER_CYCLE_WAIT="500"
 PROVER_PROVER_REQUEST_TIMEOUT="10"
 PROVER_PROVER_DIE_AFTER_PROOF=false
+PROVER_PROVER_PROVING_THREADS="4"
+PROVER_PROVER_MIN_AVAILABLE_MEMORY="8192"
+PROVER_PROVER_CPU_AFFINITY="0,1,2,3"
+PROVER_PROVER_COMPRESSION_LEVEL="3"
 PROVER_CORE_GONE_TIMEOUT="60000"
 PROVER_CORE_IDLE_PROVERS="1"
+PROVER_CORE_URGENT_JOB_TIMEOUT="1800"
+PROVER_CORE_MAX_JOB_ATTEMPTS="3"
+PROVER_CORE_VERIFY_PROOFS="true"
+PROVER_CORE_COMPRESSION_LEVEL="3"
 PROVER_WITNESS_GENERATOR_PREPARE_DATA_INTERVAL="500"
 PROVER_WITNESS_GENERATOR_WITNESS_GENERATORS="2"
+PROVER_WITNESS_GENERATOR_STANDALONE="false"
+PROVER_WITNESS_GENERATOR_MAX_WITNESSES_IN_FLIGHT="8"
+PROVER_DUMMY_PROVER_LATENCY_DISTRIBUTION="exponential"
+PROVER_DUMMY_PROVER_MIN_LATENCY="1000"
+PROVER_DUMMY_PROVER_MEAN_LATENCY="5000"
+PROVER_DUMMY_PROVER_MAX_LATENCY="30000"
+PROVER_DUMMY_PROVER_FAILURE_PROBABILITY="0.01"
+PROVER_DUMMY_PROVER_TIMEOUT_PROBABILITY="0.005"
+PROVER_DUMMY_PROVER_TIMEOUT="120000"
         "#;
         set_env(config);
 
@@ -148,10 +287,31 @@ PROVER_WITNESS_GENERATOR_WITNESS_GENERATORS="2"
             config.core.gone_timeout(),
             Duration::from_millis(config.core.gone_timeout)
         );
+        assert_eq!(
+            config.core.urgent_job_timeout(),
+            Duration::from_secs(config.core.urgent_job_timeout)
+        );
 
         assert_eq!(
             config.witness_generator.prepare_data_interval(),
             Duration::from_millis(config.witness_generator.prepare_data_interval)
         );
+
+        assert_eq!(
+            config.dummy_prover.min_latency(),
+            Duration::from_millis(config.dummy_prover.min_latency)
+        );
+        assert_eq!(
+            config.dummy_prover.mean_latency(),
+            Duration::from_millis(config.dummy_prover.mean_latency)
+        );
+        assert_eq!(
+            config.dummy_prover.max_latency(),
+            Duration::from_millis(config.dummy_prover.max_latency)
+        );
+        assert_eq!(
+            config.dummy_prover.timeout(),
+            Duration::from_millis(config.dummy_prover.timeout)
+        );
     }
 }
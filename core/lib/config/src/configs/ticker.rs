@@ -1,5 +1,7 @@
-use num::{rational::Ratio, BigUint};
+// Built-in uses
+use std::{collections::HashMap, time::Duration};
 // External uses
+use num::{rational::Ratio, BigUint};
 use serde::Deserialize;
 // Workspace uses
 use zksync_types::Address;
@@ -11,6 +13,10 @@ use crate::envy_load;
 pub enum TokenPriceSource {
     CoinGecko,
     CoinMarketCap,
+    /// Time-weighted average price of the Uniswap V3 pool of the token and the USD stablecoin.
+    UniswapTwap,
+    /// Chainlink price feed of the token, resolved via the Chainlink feed registry.
+    Chainlink,
 }
 
 /// Configuration for the fee ticker.
@@ -18,18 +24,42 @@ pub enum TokenPriceSource {
 pub struct TickerConfig {
     /// Indicator of the API to be used for getting token prices.
     pub token_price_source: TokenPriceSource,
+    /// Indicator of the API to be used if the primary one fails or returns the price out of the sanity bounds.
+    pub fallback_token_price_source: Option<TokenPriceSource>,
+    /// Maximal change of the token price in percent between two subsequent updates,
+    /// the bigger changes are considered invalid. Set to 0 to disable the check.
+    pub max_price_change_percent: u32,
     /// URL of CoinMarketCap API. Can be set to the mock server for local development.
     pub coinmarketcap_base_url: String,
     /// URL of CoinGecko API. Can be set to the mock server for local development.
     pub coingecko_base_url: String,
+    /// Address of the Chainlink feed registry contract, required by the `Chainlink` price source.
+    pub chainlink_feed_registry_addr: Option<Address>,
+    /// Address of the Uniswap V3 factory contract, required by the `UniswapTwap` price source.
+    pub uniswap_v3_factory_addr: Option<Address>,
+    /// Address of the USD stablecoin the Uniswap TWAP prices are quoted in.
+    pub uniswap_twap_quote_token_addr: Option<Address>,
+    /// Address of the WETH token, the Uniswap TWAP price of ETH is the price of WETH.
+    pub uniswap_twap_weth_addr: Option<Address>,
+    /// Fee tier of the Uniswap V3 pools used for TWAP, in hundredths of a bip.
+    pub uniswap_twap_pool_fee: u32,
+    /// Period the Uniswap TWAP is calculated for.
+    pub uniswap_twap_period_secs: u32,
     /// Coefficient for scaling all fees in percent.
     pub scale_fee_percent: u32,
+    /// Coefficient in percent the gas price is scaled by, to cover the gas price growth.
+    pub gas_price_scale_percent: u32,
+    /// Fee markups of the tokens in percent, in the `<token address>:<markup percent>` format.
+    /// Fees paid in the volatile tokens are increased to cover the price swings.
+    pub token_fee_markups: Vec<String>,
     /// Coefficient for the fee price for fast withdrawal requests.
     pub fast_processing_coeff: f64,
     /// Url to uniswap api
     pub uniswap_url: String,
     /// The volume of tokens to confirm their liquidity
     pub liquidity_volume: f64,
+    /// The market cap of tokens in USD required to enable them as fee tokens, 0 disables the check.
+    pub min_market_cap_usd: f64,
     /// Time when liquidity check results are valid
     pub available_liquidity_seconds: u64,
     /// List of the tokens that are unconditionally acceptable for paying fee in.
@@ -40,6 +70,19 @@ pub struct TickerConfig {
     pub number_of_ticker_actors: u8,
     /// Subsidized price for ChangePubKey in cents scaled by SUBSIDY_USD_AMOUNTS_SCALE
     pub subsidy_cpk_price_usd_scaled: u64,
+    /// Time the token price is cached in memory before it's requested from the database again.
+    pub price_cache_ttl_secs: u64,
+    /// Cache TTLs of the tokens overriding `price_cache_ttl_secs`, in the `<token address>:<ttl secs>` format.
+    /// Prices of the volatile tokens can be cached for less time than the others.
+    pub price_cache_ttl_overrides: Vec<String>,
+    /// Age of the token price after which it's considered stale, the stale prices are still
+    /// used for fee quoting but are reported to the metrics.
+    pub stale_price_secs: u64,
+    /// Age of the token price after which it's not used for fee quoting anymore.
+    pub max_price_age_secs: u64,
+    /// Share of the issued fee quotes persisted for the offline analysis of the fee model,
+    /// from 0 (disabled) to 1 (every quote).
+    pub fee_quote_sample_rate: f64,
 }
 
 impl TickerConfig {
@@ -51,13 +94,64 @@ impl TickerConfig {
         envy_load!("fee_ticker", "FEE_TICKER_")
     }
 
-    /// Returns the token price source type and the corresponding API URL.
-    pub fn price_source(&self) -> (TokenPriceSource, String) {
-        let url = match self.token_price_source {
-            TokenPriceSource::CoinGecko => self.coingecko_base_url.clone(),
-            TokenPriceSource::CoinMarketCap => self.coinmarketcap_base_url.clone(),
-        };
-        (self.token_price_source, url)
+    pub fn price_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.price_cache_ttl_secs)
+    }
+
+    /// Returns the cache TTLs of the tokens overriding the `price_cache_ttl`.
+    pub fn price_cache_ttl_overrides(&self) -> HashMap<Address, Duration> {
+        self.price_cache_ttl_overrides
+            .iter()
+            .filter(|ttl| !ttl.is_empty())
+            .map(|ttl| {
+                let mut parts = ttl.splitn(2, ':');
+                let token = parts.next().unwrap().trim_start_matches("0x").parse();
+                let secs = parts.next().map(str::parse);
+                match (token, secs) {
+                    (Ok(token), Some(Ok(secs))) => (token, Duration::from_secs(secs)),
+                    _ => panic!("Invalid token price cache TTL: {}", ttl),
+                }
+            })
+            .collect()
+    }
+
+    pub fn stale_price_age(&self) -> Duration {
+        Duration::from_secs(self.stale_price_secs)
+    }
+
+    pub fn max_price_age(&self) -> Duration {
+        Duration::from_secs(self.max_price_age_secs)
+    }
+
+    /// Returns the fee markups of the tokens in percent.
+    pub fn token_fee_markups(&self) -> HashMap<Address, u32> {
+        self.token_fee_markups
+            .iter()
+            .filter(|markup| !markup.is_empty())
+            .map(|markup| {
+                let mut parts = markup.splitn(2, ':');
+                let token = parts.next().unwrap().trim_start_matches("0x").parse();
+                let percent = parts.next().map(str::parse);
+                match (token, percent) {
+                    (Ok(token), Some(Ok(percent))) => (token, percent),
+                    _ => panic!("Invalid token fee markup: {}", markup),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the primary token price source type and the fallback one, if any.
+    pub fn price_sources(&self) -> (TokenPriceSource, Option<TokenPriceSource>) {
+        (self.token_price_source, self.fallback_token_price_source)
+    }
+
+    /// Returns the API URL of the token price source, `None` for the on-chain sources.
+    pub fn base_url(&self, source: TokenPriceSource) -> Option<String> {
+        match source {
+            TokenPriceSource::CoinGecko => Some(self.coingecko_base_url.clone()),
+            TokenPriceSource::CoinMarketCap => Some(self.coinmarketcap_base_url.clone()),
+            TokenPriceSource::UniswapTwap | TokenPriceSource::Chainlink => None,
+        }
     }
 }
 
@@ -69,17 +163,38 @@ mod tests {
     fn expected_config() -> TickerConfig {
         TickerConfig {
             token_price_source: TokenPriceSource::CoinGecko,
+            fallback_token_price_source: Some(TokenPriceSource::Chainlink),
+            max_price_change_percent: 50,
             coinmarketcap_base_url: "http://127.0.0.1:9876".into(),
             coingecko_base_url: "http://127.0.0.1:9876".into(),
+            chainlink_feed_registry_addr: Some(addr("47fb2585d2c56fe188d0e6ec628a38b74fceeedf")),
+            uniswap_v3_factory_addr: None,
+            uniswap_twap_quote_token_addr: None,
+            uniswap_twap_weth_addr: None,
+            uniswap_twap_pool_fee: 3000,
+            uniswap_twap_period_secs: 1800,
             scale_fee_percent: 100,
+            gas_price_scale_percent: 130,
+            token_fee_markups: vec![
+                "0x34083bbd70d394110487feaa087da875a54624ec:20".to_string(),
+                "0x38a2fdc11f526ddd5a607c1f251c065f40fbf2f7:50".to_string(),
+            ],
             fast_processing_coeff: 10.0f64,
             uniswap_url: "http://127.0.0.1:9975/graphql".to_string(),
             liquidity_volume: 100.0,
+            min_market_cap_usd: 1000000.0,
             available_liquidity_seconds: 1000,
             unconditionally_valid_tokens: vec![addr("0000000000000000000000000000000000000000")],
             token_market_update_time: 120,
             number_of_ticker_actors: 4,
             subsidy_cpk_price_usd_scaled: 100,
+            price_cache_ttl_secs: 60,
+            price_cache_ttl_overrides: vec![
+                "0x38a2fdc11f526ddd5a607c1f251c065f40fbf2f7:10".to_string()
+            ],
+            stale_price_secs: 1800,
+            max_price_age_secs: 86400,
+            fee_quote_sample_rate: 0.01,
         }
     }
 
@@ -87,18 +202,31 @@ mod tests {
     fn from_env() {
         let config = r#"
 FEE_TICKER_TOKEN_PRICE_SOURCE="CoinGecko"
+FEE_TICKER_FALLBACK_TOKEN_PRICE_SOURCE="Chainlink"
+FEE_TICKER_MAX_PRICE_CHANGE_PERCENT=50
 FEE_TICKER_COINMARKETCAP_BASE_URL="http://127.0.0.1:9876"
 FEE_TICKER_COINGECKO_BASE_URL="http://127.0.0.1:9876"
+FEE_TICKER_CHAINLINK_FEED_REGISTRY_ADDR="0x47fb2585d2c56fe188d0e6ec628a38b74fceeedf"
+FEE_TICKER_UNISWAP_TWAP_POOL_FEE=3000
+FEE_TICKER_UNISWAP_TWAP_PERIOD_SECS=1800
 FEE_TICKER_FAST_PROCESSING_COEFF="10"
 FEE_TICKER_UNISWAP_URL=http://127.0.0.1:9975/graphql
 FEE_TICKER_AVAILABLE_LIQUIDITY_SECONDS=1000
 FEE_TICKER_TOKEN_MARKET_UPDATE_TIME=120
 FEE_TICKER_UNCONDITIONALLY_VALID_TOKENS="0x0000000000000000000000000000000000000000"
 FEE_TICKER_LIQUIDITY_VOLUME=100
+FEE_TICKER_MIN_MARKET_CAP_USD=1000000
 FEE_TICKER_NUMBER_OF_TICKER_ACTORS="4"
 FEE_TICKER_SUBSIDIZED_TOKENS_LIMITS=156
 FEE_TICKER_SCALE_FEE_PERCENT=100
+FEE_TICKER_GAS_PRICE_SCALE_PERCENT=130
+FEE_TICKER_TOKEN_FEE_MARKUPS="0x34083bbd70d394110487feaa087da875a54624ec:20,0x38a2fdc11f526ddd5a607c1f251c065f40fbf2f7:50"
 FEE_TICKER_SUBSIDY_CPK_PRICE_USD_SCALED=100
+FEE_TICKER_PRICE_CACHE_TTL_SECS=60
+FEE_TICKER_PRICE_CACHE_TTL_OVERRIDES="0x38a2fdc11f526ddd5a607c1f251c065f40fbf2f7:10"
+FEE_TICKER_STALE_PRICE_SECS=1800
+FEE_TICKER_MAX_PRICE_AGE_SECS=86400
+FEE_TICKER_FEE_QUOTE_SAMPLE_RATE="0.01"
         "#;
         set_env(config);
 
@@ -118,15 +246,49 @@ FEE_TICKER_SUBSIDY_CPK_PRICE_USD_SCALED=100
         config.coinmarketcap_base_url = COINMARKETCAP_URL.into();
 
         config.token_price_source = TokenPriceSource::CoinGecko;
+        config.fallback_token_price_source = None;
+        assert_eq!(config.price_sources(), (TokenPriceSource::CoinGecko, None));
         assert_eq!(
-            config.price_source(),
-            (TokenPriceSource::CoinGecko, COINGECKO_URL.into())
+            config.base_url(TokenPriceSource::CoinGecko),
+            Some(COINGECKO_URL.into())
         );
 
         config.token_price_source = TokenPriceSource::CoinMarketCap;
+        config.fallback_token_price_source = Some(TokenPriceSource::UniswapTwap);
+        assert_eq!(
+            config.price_sources(),
+            (
+                TokenPriceSource::CoinMarketCap,
+                Some(TokenPriceSource::UniswapTwap)
+            )
+        );
+        assert_eq!(
+            config.base_url(TokenPriceSource::CoinMarketCap),
+            Some(COINMARKETCAP_URL.into())
+        );
+        assert_eq!(config.base_url(TokenPriceSource::UniswapTwap), None);
+        assert_eq!(config.base_url(TokenPriceSource::Chainlink), None);
+
+        let markups = config.token_fee_markups();
+        assert_eq!(markups.len(), 2);
+        assert_eq!(
+            markups[&addr("34083bbd70d394110487feaa087da875a54624ec")],
+            20
+        );
+        assert_eq!(
+            markups[&addr("38a2fdc11f526ddd5a607c1f251c065f40fbf2f7")],
+            50
+        );
+        config.token_fee_markups = vec!["".to_string()];
+        assert!(config.token_fee_markups().is_empty());
+
+        let ttl_overrides = config.price_cache_ttl_overrides();
+        assert_eq!(ttl_overrides.len(), 1);
         assert_eq!(
-            config.price_source(),
-            (TokenPriceSource::CoinMarketCap, COINMARKETCAP_URL.into())
+            ttl_overrides[&addr("38a2fdc11f526ddd5a607c1f251c065f40fbf2f7")],
+            Duration::from_secs(10)
         );
+        config.price_cache_ttl_overrides = vec!["".to_string()];
+        assert!(config.price_cache_ttl_overrides().is_empty());
     }
 }
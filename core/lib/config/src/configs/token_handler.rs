@@ -17,6 +17,11 @@ pub struct TokenHandlerConfig {
     pub poll_interval: u64,
     /// Link to MatterMost channel for token list notification.
     pub webhook_url: String,
+    /// The number of seconds between the checks of the stored token metadata against L1.
+    pub metadata_check_interval: u64,
+    /// Whether the token decimals mismatching L1 should be fixed in the database automatically.
+    /// Otherwise, the mismatches are only reported.
+    pub fix_token_decimals: bool,
 }
 
 impl TokenHandlerConfig {
@@ -33,6 +38,11 @@ impl TokenHandlerConfig {
         Duration::from_secs(self.poll_interval)
     }
 
+    /// Converts self.metadata_check_interval into Duration.
+    pub fn metadata_check_interval(&self) -> Duration {
+        Duration::from_secs(self.metadata_check_interval)
+    }
+
     pub fn token_list(&self) -> Vec<TokenInfo> {
         let token_list_name = self.token_list_file();
         let path = format!("./etc/token-lists/{}.json", token_list_name);
@@ -52,6 +62,8 @@ mod tests {
             token_list_name: "localhost".to_string(),
             poll_interval: 1,
             webhook_url: "http://127.0.0.1".to_string(),
+            metadata_check_interval: 3600,
+            fix_token_decimals: true,
         }
     }
 
@@ -61,6 +73,8 @@ mod tests {
 TOKEN_HANDLER_POLL_INTERVAL=1
 TOKEN_HANDLER_WEBHOOK_URL="http://127.0.0.1"
 TOKEN_HANDLER_TOKEN_LIST_NAME="localhost"
+TOKEN_HANDLER_METADATA_CHECK_INTERVAL=3600
+TOKEN_HANDLER_FIX_TOKEN_DECIMALS="true"
         "#;
         set_env(config);
 
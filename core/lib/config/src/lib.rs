@@ -2,11 +2,12 @@
 pub use crate::configs::{
     ApiConfig, ChainConfig, ContractsConfig, DBConfig, DevLiquidityTokenWatcherConfig,
     ETHClientConfig, ETHSenderConfig, ETHWatchConfig, EventListenerConfig,
-    ForcedExitRequestsConfig, GatewayWatcherConfig, MiscConfig, ProverConfig, TickerConfig,
-    TokenHandlerConfig,
+    ForcedExitRequestsConfig, GatewayWatcherConfig, MiscConfig, ObjectStoreConfig, ProverConfig,
+    TickerConfig, TokenHandlerConfig,
 };
 
 pub mod configs;
+pub mod loader;
 pub mod test_config;
 
 #[derive(Debug, Clone)]
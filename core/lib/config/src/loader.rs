@@ -0,0 +1,471 @@
+//! Layered loading of the configuration.
+//!
+//! The configuration values are taken from the following layers, each next layer overrides
+//! the values of the previous ones:
+//!
+//! 1. Defaults: the TOML files from the directory set by the `ZKSYNC_CONFIG_DEFAULTS_DIR` env variable
+//!    (e.g. `$ZKSYNC_HOME/etc/env/base`).
+//! 2. File: the TOML file set by the `ZKSYNC_CONFIG_FILE` env variable.
+//! 3. Environment variables.
+//!
+//! Both file layers are optional, so without them the configuration is loaded from the environment only.
+//! The TOML files are converted to the variables the same way `zk config compile` does it:
+//! `[api.common] caches_size = 10` becomes `API_COMMON_CACHES_SIZE=10`, arrays are joined with commas.
+//!
+//! The files are read once. `reload` re-reads them and updates the values created with `Reloadable`,
+//! which is how the designated subset of the configuration is changed without restarting the application.
+//! The environment of the process can't change at runtime, so on `reload` the values changed in the files
+//! override the environment. Otherwise nothing could be reloaded in the deployments which set all the
+//! variables from the compiled `.env` file. The variables not changed in the files are still taken from
+//! the environment, so the explicit overrides are kept.
+
+// Built-in uses
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+// External uses
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+
+/// Env variable with the path to the directory with the default config files.
+pub const CONFIG_DEFAULTS_DIR_VAR: &str = "ZKSYNC_CONFIG_DEFAULTS_DIR";
+/// Env variable with the path to the config file.
+pub const CONFIG_FILE_VAR: &str = "ZKSYNC_CONFIG_FILE";
+
+/// Variables loaded from the config files, `None` until they are loaded for the first time.
+static FILE_VARIABLES: Lazy<RwLock<Option<Arc<Variables>>>> = Lazy::new(|| RwLock::new(None));
+/// Values that are updated on `reload`.
+static RELOADABLE: Lazy<Mutex<Vec<Weak<dyn ReloadTarget>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Layer the configuration value is taken from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigSource {
+    Defaults(PathBuf),
+    File(PathBuf),
+    Env,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Defaults(path) => write!(f, "defaults file {}", path.display()),
+            Self::File(path) => write!(f, "config file {}", path.display()),
+            Self::Env => write!(f, "environment"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The config file can't be read.
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    /// The config file is not a valid TOML.
+    Parse {
+        path: PathBuf,
+        error: toml::de::Error,
+    },
+    /// The config section can't be deserialized from the variables.
+    Invalid { name: String, message: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => {
+                write!(f, "Cannot read config file {}: {}", path.display(), error)
+            }
+            Self::Parse { path, error } => {
+                write!(f, "Cannot parse config file {}: {}", path.display(), error)
+            }
+            Self::Invalid { name, message } => {
+                write!(f, "Cannot load config <{}>: {}", name, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Configuration variables along with the layers they are taken from.
+#[derive(Debug, Clone, Default)]
+pub struct Variables(HashMap<String, (String, ConfigSource)>);
+
+impl Variables {
+    /// Converts the TOML config to the variables.
+    pub fn from_toml(contents: &str, source: ConfigSource) -> Result<Self, toml::de::Error> {
+        let table: toml::value::Table = toml::from_str(contents)?;
+        let mut variables = Self::default();
+        variables.collect("", &table, &source);
+        Ok(variables)
+    }
+
+    /// Takes the variables from the environment.
+    pub fn from_env() -> Self {
+        Self(
+            std::env::vars()
+                .map(|(name, value)| (name, (value, ConfigSource::Env)))
+                .collect(),
+        )
+    }
+
+    fn collect(&mut self, prefix: &str, table: &toml::value::Table, source: &ConfigSource) {
+        for (key, value) in table {
+            let name = format!("{}{}", prefix, key.to_uppercase());
+            match value {
+                toml::Value::Table(table) => self.collect(&format!("{}_", name), table, source),
+                value => {
+                    self.0
+                        .insert(name, (toml_value_to_string(value), source.clone()));
+                }
+            }
+        }
+    }
+
+    /// Overrides the variables with the ones from the next layer.
+    pub fn extend(&mut self, layer: Variables) {
+        self.0.extend(layer.0);
+    }
+
+    /// Returns the variables which are added or have another value than in the `previous` ones.
+    fn changed_since(&self, previous: &Variables) -> Variables {
+        Self(
+            self.0
+                .iter()
+                .filter(|(name, (value, _))| previous.get(name) != Some(value.as_str()))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(|(value, _)| value.as_str())
+    }
+
+    /// Deserializes the config section from the variables with the given prefix.
+    pub fn load<T: DeserializeOwned>(&self, name: &str, prefix: &str) -> Result<T, ConfigError> {
+        let variables = self
+            .0
+            .iter()
+            .map(|(name, (value, _))| (name.clone(), value.clone()));
+        envy::prefixed(prefix)
+            .from_iter(variables)
+            .map_err(|error| ConfigError::Invalid {
+                name: name.to_string(),
+                message: self.describe_error(prefix, error),
+            })
+    }
+
+    /// Names the variable that caused the error and the layer it's taken from.
+    fn describe_error(&self, prefix: &str, error: envy::Error) -> String {
+        match error {
+            envy::Error::MissingValue(field) => {
+                format!("variable {}{} is not set", prefix, field.to_uppercase())
+            }
+            envy::Error::Custom(message) => {
+                let source = self
+                    .0
+                    .iter()
+                    .filter(|(name, _)| name.starts_with(prefix) && message.contains(name.as_str()))
+                    .max_by_key(|(name, _)| name.len())
+                    .map(|(name, (_, source))| format!(" ({} is set in the {})", name, source));
+                format!("{}{}", message, source.unwrap_or_default())
+            }
+        }
+    }
+}
+
+fn toml_value_to_string(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(value) => value.clone(),
+        toml::Value::Array(values) => values
+            .iter()
+            .map(toml_value_to_string)
+            .collect::<Vec<_>>()
+            .join(","),
+        value => value.to_string(),
+    }
+}
+
+fn read_toml_file(path: &Path, source: ConfigSource) -> Result<Variables, ConfigError> {
+    let contents = std::fs::read_to_string(path).map_err(|error| ConfigError::Io {
+        path: path.to_owned(),
+        error,
+    })?;
+    Variables::from_toml(&contents, source).map_err(|error| ConfigError::Parse {
+        path: path.to_owned(),
+        error,
+    })
+}
+
+/// Reads the defaults and the config file layers.
+fn read_file_variables() -> Result<Variables, ConfigError> {
+    let mut variables = Variables::default();
+
+    if let Ok(dir) = std::env::var(CONFIG_DEFAULTS_DIR_VAR) {
+        let dir = PathBuf::from(dir);
+        let entries = std::fs::read_dir(&dir).map_err(|error| ConfigError::Io {
+            path: dir.clone(),
+            error,
+        })?;
+        let mut paths = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|error| ConfigError::Io {
+                    path: dir.clone(),
+                    error,
+                })?
+                .path();
+            if path
+                .extension()
+                .map_or(false, |extension| extension == "toml")
+            {
+                paths.push(path);
+            }
+        }
+        // Files are applied in the same order on every load.
+        paths.sort();
+        for path in paths {
+            let source = ConfigSource::Defaults(path.clone());
+            variables.extend(read_toml_file(&path, source)?);
+        }
+    }
+
+    if let Ok(path) = std::env::var(CONFIG_FILE_VAR) {
+        let path = PathBuf::from(path);
+        let source = ConfigSource::File(path.clone());
+        variables.extend(read_toml_file(&path, source)?);
+    }
+    Ok(variables)
+}
+
+fn file_variables() -> Result<Arc<Variables>, ConfigError> {
+    if let Some(variables) = FILE_VARIABLES.read().unwrap().as_ref() {
+        return Ok(variables.clone());
+    }
+    let variables = Arc::new(read_file_variables()?);
+    *FILE_VARIABLES.write().unwrap() = Some(variables.clone());
+    Ok(variables)
+}
+
+/// Returns the variables of all the layers.
+pub fn variables() -> Result<Variables, ConfigError> {
+    let mut variables = file_variables()?.as_ref().clone();
+    variables.extend(Variables::from_env());
+    Ok(variables)
+}
+
+/// Loads the config section from the variables with the given prefix.
+pub fn load<T: DeserializeOwned>(name: &str, prefix: &str) -> Result<T, ConfigError> {
+    variables()?.load(name, prefix)
+}
+
+/// Re-reads the config files and updates the `Reloadable` values, returns the reloaded variables.
+///
+/// The values changed in the files since the previous load override the environment.
+/// The new values are validated first, so nothing is changed if any of them is invalid.
+pub fn reload() -> Result<Variables, ConfigError> {
+    let previous = file_variables()?;
+    reload_layers(&previous, read_file_variables()?, Variables::from_env())
+}
+
+fn reload_layers(
+    previous: &Variables,
+    file_variables: Variables,
+    env: Variables,
+) -> Result<Variables, ConfigError> {
+    let mut variables = file_variables.clone();
+    variables.extend(env);
+    variables.extend(file_variables.changed_since(previous));
+
+    let targets: Vec<_> = {
+        let mut reloadable = RELOADABLE.lock().unwrap();
+        reloadable.retain(|target| target.strong_count() > 0);
+        reloadable.iter().filter_map(Weak::upgrade).collect()
+    };
+    for target in &targets {
+        target.check(&variables)?;
+    }
+    for target in &targets {
+        target.apply(&variables);
+    }
+
+    *FILE_VARIABLES.write().unwrap() = Some(Arc::new(file_variables));
+    Ok(variables)
+}
+
+trait ReloadTarget: Send + Sync {
+    fn check(&self, variables: &Variables) -> Result<(), ConfigError>;
+
+    fn apply(&self, variables: &Variables);
+}
+
+#[derive(Debug)]
+struct ReloadableValue<T> {
+    name: String,
+    prefix: String,
+    value: RwLock<Arc<T>>,
+}
+
+impl<T> ReloadTarget for ReloadableValue<T>
+where
+    T: DeserializeOwned + Send + Sync,
+{
+    fn check(&self, variables: &Variables) -> Result<(), ConfigError> {
+        variables.load::<T>(&self.name, &self.prefix).map(drop)
+    }
+
+    fn apply(&self, variables: &Variables) {
+        // The variables are already checked.
+        let value: T = variables
+            .load(&self.name, &self.prefix)
+            .expect("Config was checked");
+        *self.value.write().unwrap() = Arc::new(value);
+    }
+}
+
+/// Config section updated on `reload`. The clones share the value.
+#[derive(Debug)]
+pub struct Reloadable<T> {
+    inner: Arc<ReloadableValue<T>>,
+}
+
+impl<T> Clone for Reloadable<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Reloadable<T>
+where
+    T: DeserializeOwned + Send + Sync + 'static,
+{
+    /// Creates the section with the initial `value`, it's reloaded from the variables with the given prefix.
+    pub fn new(name: &str, prefix: &str, value: T) -> Self {
+        let inner = Arc::new(ReloadableValue {
+            name: name.to_string(),
+            prefix: prefix.to_string(),
+            value: RwLock::new(Arc::new(value)),
+        });
+        let target: Arc<dyn ReloadTarget> = inner.clone();
+        RELOADABLE.lock().unwrap().push(Arc::downgrade(&target));
+        Self { inner }
+    }
+
+    /// Returns the current value.
+    pub fn get(&self) -> Arc<T> {
+        self.inner.value.read().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct TestConfig {
+        port: u16,
+        accounts: Vec<u32>,
+        name: String,
+    }
+
+    const DEFAULTS: &str = r#"
+        [test.section]
+        port = 3000
+        accounts = [1, 2]
+        name = "defaults"
+    "#;
+
+    #[test]
+    fn layered_variables() {
+        let mut variables =
+            Variables::from_toml(DEFAULTS, ConfigSource::Defaults("base.toml".into())).unwrap();
+        assert_eq!(variables.get("TEST_SECTION_PORT"), Some("3000"));
+        assert_eq!(variables.get("TEST_SECTION_ACCOUNTS"), Some("1,2"));
+
+        let file = "[test.section]\nname = \"file\"";
+        variables
+            .extend(Variables::from_toml(file, ConfigSource::File("config.toml".into())).unwrap());
+        let env = Variables(
+            vec![(
+                "TEST_SECTION_PORT".to_string(),
+                ("4000".to_string(), ConfigSource::Env),
+            )]
+            .into_iter()
+            .collect(),
+        );
+        variables.extend(env);
+
+        let config: TestConfig = variables.load("test", "TEST_SECTION_").unwrap();
+        assert_eq!(
+            config,
+            TestConfig {
+                port: 4000,
+                accounts: vec![1, 2],
+                name: "file".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn precise_config_errors() {
+        let variables =
+            Variables::from_toml("[test.section]\nport = 3000", ConfigSource::Env).unwrap();
+        let err = variables
+            .load::<TestConfig>("test", "TEST_SECTION_")
+            .unwrap_err();
+        assert!(err.to_string().contains("TEST_SECTION_ACCOUNTS"), "{}", err);
+
+        assert!(Variables::from_toml("[test.section\nport = 3000", ConfigSource::Env).is_err());
+    }
+
+    #[test]
+    fn reload_reloadable_values() {
+        let file = |contents: &str| {
+            Variables::from_toml(contents, ConfigSource::File("config.toml".into())).unwrap()
+        };
+        let env = |port: &str| {
+            // All the variables are set in the environment, as with the compiled `.env` file.
+            Variables(
+                vec![
+                    ("TEST_RELOAD_PORT", port),
+                    ("TEST_RELOAD_ACCOUNTS", "1,2"),
+                    ("TEST_RELOAD_NAME", "file"),
+                ]
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), (value.to_string(), ConfigSource::Env)))
+                .collect(),
+            )
+        };
+        let initial = file("[test.reload]\nport = 3000\naccounts = [1, 2]\nname = \"file\"");
+        let value = Reloadable::new(
+            "test",
+            "TEST_RELOAD_",
+            initial.load::<TestConfig>("test", "TEST_RELOAD_").unwrap(),
+        );
+
+        // The values changed in the file override the environment, the unchanged ones don't.
+        let changed = file("[test.reload]\nport = 3000\naccounts = [1, 2]\nname = \"reloaded\"");
+        reload_layers(&initial, changed.clone(), env("4000")).unwrap();
+        assert_eq!(
+            *value.get(),
+            TestConfig {
+                port: 4000,
+                accounts: vec![1, 2],
+                name: "reloaded".to_string(),
+            }
+        );
+
+        // Nothing is changed if the reloaded config is invalid.
+        let invalid = file("[test.reload]\nport = \"port\"\naccounts = [1, 2]\nname = \"invalid\"");
+        let err = reload_layers(&changed, invalid, env("4000")).unwrap_err();
+        assert!(err.to_string().contains("TEST_RELOAD_PORT"), "{}", err);
+        assert_eq!(value.get().name, "reloaded");
+    }
+}
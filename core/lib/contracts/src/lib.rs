@@ -13,10 +13,13 @@ const GOVERNANCE_CONTRACT_FILE: &str =
     "contracts/artifacts/cache/solpp-generated-contracts/Governance.sol/Governance.json";
 const IERC20_CONTRACT_FILE: &str =
     "contracts/artifacts/cache/solpp-generated-contracts/IERC20.sol/IERC20.json";
+const IERC20_PERMIT_CONTRACT_FILE: &str =
+    "contracts/artifacts/cache/solpp-generated-contracts/IERC20Permit.sol/IERC20Permit.json";
 const IEIP1271_CONTRACT_FILE: &str =
     "contracts/artifacts/cache/solpp-generated-contracts/dev-contracts/IEIP1271.sol/IEIP1271.json";
 const UPGRADE_GATEKEEPER_CONTRACT_FILE: &str =
     "contracts/artifacts/cache/solpp-generated-contracts/UpgradeGatekeeper.sol/UpgradeGatekeeper.json";
+const ERC20_METADATA_CONTRACT_FILE: &str = "etc/web3-abi/ERC20.json";
 const FORCED_EXIT_CONTRACT_FILE: &str =
     "contracts/artifacts/cache/solpp-generated-contracts/ForcedExit.sol/ForcedExit.json";
 
@@ -91,6 +94,24 @@ pub fn erc20_contract() -> Contract {
     Contract::load(abi_string.as_bytes()).expect("erc20 contract abi")
 }
 
+/// ERC20 contract ABI including the optional metadata methods (`name`, `symbol` and `decimals`),
+/// which are not the part of the `IERC20` interface.
+pub fn erc20_metadata_contract() -> Contract {
+    let abi_string = read_file_to_json_value(ERC20_METADATA_CONTRACT_FILE)
+        .expect("couldn't read ERC20_METADATA_CONTRACT_FILE")
+        .to_string();
+    Contract::load(abi_string.as_bytes()).expect("erc20 metadata contract abi")
+}
+
+pub fn erc20_permit_contract() -> Contract {
+    let abi_string = read_file_to_json_value(IERC20_PERMIT_CONTRACT_FILE)
+        .expect("couldn't read IERC20_PERMIT_CONTRACT_FILE")
+        .get("abi")
+        .expect("couldn't get abi from IERC20_PERMIT_CONTRACT_FILE")
+        .to_string();
+    Contract::load(abi_string.as_bytes()).expect("erc20 permit contract abi")
+}
+
 pub fn eip1271_contract() -> Contract {
     let abi_string = read_file_to_json_value(IEIP1271_CONTRACT_FILE)
         .expect("couldn't read IEIP1271_CONTRACT_FILE")
@@ -19,3 +19,19 @@ pub enum ConversionError {
     #[error("Cannot convert into prime field value: {0}")]
     PrimeFieldDecodingError(#[from] ff::PrimeFieldDecodingError),
 }
+
+#[derive(Debug, Error, PartialEq)]
+pub enum MusigError {
+    #[error("At least one participant is required")]
+    NoParticipants,
+    #[error("Signer public key is not among the participants")]
+    UnknownParticipant,
+    #[error("Expected {expected} values, one for each participant, got {actual}")]
+    ParticipantsCountMismatch { expected: usize, actual: usize },
+    #[error("Nonce commitments of all participants are required before revealing the nonces")]
+    CommitmentsNotSet,
+    #[error("Nonce of the participant {0} doesn't match its commitment")]
+    InvalidNonce(usize),
+    #[error("Nonces of all participants are required before signing")]
+    NoncesNotSet,
+}
@@ -24,6 +24,7 @@ pub mod circuit;
 pub mod convert;
 pub mod error;
 pub mod merkle_tree;
+pub mod musig;
 pub mod params;
 pub mod primitives;
 pub mod proof;
@@ -0,0 +1,450 @@
+//! MuSig multi-party signing.
+//!
+//! Several parties holding their own private keys produce a single signature, which is verified
+//! the same way as the signature of one key (`PublicKey::verify_musig_rescue`) against the
+//! aggregated public key of all the parties. An account with the hash of the aggregated key set
+//! as its signing key thus requires all the parties to sign its transactions.
+//!
+//! Every party follows the same rounds:
+//!
+//! 1. Aggregates the public keys of the participants, listed in the same order by everyone,
+//!    with `AggregatedPublicKey::new`.
+//! 2. Starts a `MusigSession` and sends the commitment to its nonce to the others.
+//! 3. Once the commitments of all the participants are received, reveals its nonce.
+//! 4. Once the nonces of all the participants are received, signs the message.
+//!
+//! Any party can then combine the partial signatures with `aggregate_signatures`.
+//! Note that the message is signed as is, so transaction bytes should be hashed with
+//! `rescue_hash_tx_msg` beforehand, the same as `musig_rescue_sign` does.
+
+use crate::franklin_crypto::{
+    bellman::pairing::ff::{Field, PrimeField},
+    circuit::multipack,
+    eddsa::Signature,
+    jubjub::{edwards::Point, FixedGenerators, JubjubParams, Unknown},
+    rescue::StatefulRescue,
+};
+use crate::{
+    circuit::utils::{append_le_fixed_width, le_bit_vector_into_field_element},
+    error::MusigError,
+    merkle_tree::hasher::Hasher,
+    params::{JUBJUB_PARAMS, RESCUE_HASHER, RESCUE_PARAMS},
+    primitives::BitConvert,
+    public_key_from_private,
+    rand::{Rand, Rng},
+    Engine, Fr, Fs, PrivateKey, PublicKey,
+};
+
+/// Bit width of the field element when it's hashed.
+const FIELD_ELEMENT_BIT_WIDTH: usize = 256;
+/// Bit width of the signature challenge hash input, the same as in the circuit.
+const CHALLENGE_INPUT_BIT_WIDTH: usize = 768;
+
+const GENERATOR: FixedGenerators = FixedGenerators::SpendingKeyGenerator;
+
+/// Bits of the point coordinate as they are hashed into the signature challenge:
+/// little-endian bytes, each one starting from the most significant bit.
+fn coordinate_bits(coordinate: &Fr) -> Vec<bool> {
+    let mut bits = Vec::with_capacity(FIELD_ELEMENT_BIT_WIDTH);
+    append_le_fixed_width(&mut bits, coordinate, FIELD_ELEMENT_BIT_WIDTH);
+    BitConvert::from_be_bytes(&BitConvert::into_bytes(bits))
+}
+
+fn point_bits(point: &Point<Engine, Unknown>) -> Vec<bool> {
+    let (x, y) = point.into_xy();
+    let mut bits = coordinate_bits(&x);
+    bits.extend(coordinate_bits(&y));
+    bits
+}
+
+/// Computes the signature challenge the same way as the signature verification does:
+/// the rescue hash of the public key and nonce `x` coordinates and the message.
+///
+/// # Panics
+///
+/// Panics if the message is longer than 32 bytes.
+pub fn signature_challenge(
+    public_key: &PublicKey,
+    nonce: &Point<Engine, Unknown>,
+    msg: &[u8],
+) -> Fs {
+    assert!(msg.len() <= 32, "Signed message should be at most 32 bytes");
+
+    let mut bits = coordinate_bits(&public_key.0.into_xy().0);
+    bits.extend(coordinate_bits(&nonce.into_xy().0));
+    let mut msg = msg.to_vec();
+    msg.resize(32, 0);
+    bits.extend(BitConvert::from_be_bytes(&msg));
+    bits.resize(CHALLENGE_INPUT_BIT_WIDTH, false);
+    let input = multipack::compute_multipacking::<Engine>(&bits);
+
+    let mut sponge = StatefulRescue::<Engine>::new(&RESCUE_PARAMS);
+    sponge.specialize(input.len() as u8);
+    sponge.absorb(&input);
+
+    // The challenge is composed of the lower halves of the two squeezed elements.
+    let half_width = (Fs::CAPACITY / 2) as usize;
+    let mut challenge_bits = Vec::with_capacity(half_width * 2);
+    for _ in 0..2 {
+        append_le_fixed_width(
+            &mut challenge_bits,
+            &sponge.squeeze_out_single(),
+            half_width,
+        );
+    }
+    le_bit_vector_into_field_element(&challenge_bits)
+}
+
+/// Commitment to the signer nonce, which is sent to the other participants before the nonce itself.
+pub fn nonce_commitment(nonce: &Point<Engine, Unknown>) -> Fr {
+    RESCUE_HASHER.hash_bits(point_bits(nonce))
+}
+
+/// Public key of all the participants, which verifies their aggregated signature.
+///
+/// Every key is weighted with the coefficient depending on all the keys, so no participant
+/// can choose its key to cancel out the keys of the others.
+#[derive(Clone)]
+pub struct AggregatedPublicKey {
+    participants: Vec<PublicKey>,
+    coefficients: Vec<Fs>,
+    public_key: PublicKey,
+}
+
+impl AggregatedPublicKey {
+    /// Aggregates the public keys of the participants.
+    /// All the participants should list the keys in the same order.
+    pub fn new(participants: Vec<PublicKey>) -> Result<Self, MusigError> {
+        if participants.is_empty() {
+            return Err(MusigError::NoParticipants);
+        }
+
+        let keys_bits: Vec<bool> = participants
+            .iter()
+            .flat_map(|key| point_bits(&key.0))
+            .collect();
+        let coefficients: Vec<Fs> = participants
+            .iter()
+            .map(|key| {
+                let mut bits = keys_bits.clone();
+                bits.extend(point_bits(&key.0));
+                let hash = RESCUE_HASHER.hash_bits(bits);
+
+                let mut hash_bits = Vec::with_capacity(Fs::CAPACITY as usize);
+                append_le_fixed_width(&mut hash_bits, &hash, Fs::CAPACITY as usize);
+                le_bit_vector_into_field_element(&hash_bits)
+            })
+            .collect();
+
+        let aggregated = participants.iter().zip(&coefficients).fold(
+            Point::zero(),
+            |acc, (key, coefficient)| {
+                acc.add(
+                    &key.0.mul(coefficient.into_repr(), &JUBJUB_PARAMS),
+                    &JUBJUB_PARAMS,
+                )
+            },
+        );
+
+        Ok(Self {
+            participants,
+            coefficients,
+            public_key: PublicKey(aggregated),
+        })
+    }
+
+    /// Aggregated public key.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Public keys of the participants in the order they were aggregated.
+    pub fn participants(&self) -> &[PublicKey] {
+        &self.participants
+    }
+
+    fn position(&self, key: &PublicKey) -> Option<usize> {
+        self.participants
+            .iter()
+            .position(|participant| participant.0 == key.0)
+    }
+
+    fn check_count(&self, actual: usize) -> Result<(), MusigError> {
+        if actual != self.participants.len() {
+            return Err(MusigError::ParticipantsCountMismatch {
+                expected: self.participants.len(),
+                actual,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Signing session of one participant.
+///
+/// The session should never be reused for several messages, since it would leak the private key,
+/// so it's consumed by `MusigSession::sign`.
+pub struct MusigSession {
+    aggregated_key: AggregatedPublicKey,
+    private_key: Fs,
+    position: usize,
+    nonce_secret: Fs,
+    nonce: Point<Engine, Unknown>,
+    commitments: Option<Vec<Fr>>,
+    aggregated_nonce: Option<Point<Engine, Unknown>>,
+}
+
+impl MusigSession {
+    /// Starts the session with the random nonce.
+    pub fn new<R: Rng>(
+        rng: &mut R,
+        private_key: &PrivateKey,
+        aggregated_key: AggregatedPublicKey,
+    ) -> Result<Self, MusigError> {
+        let position = aggregated_key
+            .position(&public_key_from_private(private_key))
+            .ok_or(MusigError::UnknownParticipant)?;
+        let nonce_secret = Fs::rand(rng);
+        let nonce = JUBJUB_PARAMS
+            .generator(GENERATOR)
+            .mul(nonce_secret.into_repr(), &JUBJUB_PARAMS)
+            .into();
+
+        Ok(Self {
+            aggregated_key,
+            private_key: private_key.0,
+            position,
+            nonce_secret,
+            nonce,
+            commitments: None,
+            aggregated_nonce: None,
+        })
+    }
+
+    /// Position of the signer among the participants.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Commitment to the nonce, it should be sent to the other participants first.
+    pub fn nonce_commitment(&self) -> Fr {
+        nonce_commitment(&self.nonce)
+    }
+
+    /// Nonce of the signer, it should be revealed once the commitments of all the participants are set.
+    pub fn nonce(&self) -> &Point<Engine, Unknown> {
+        &self.nonce
+    }
+
+    /// Aggregated nonce of all the participants, available once their nonces are set.
+    pub fn aggregated_nonce(&self) -> Option<&Point<Engine, Unknown>> {
+        self.aggregated_nonce.as_ref()
+    }
+
+    /// Sets the nonce commitments of all the participants, including the signer itself.
+    pub fn set_nonce_commitments(&mut self, commitments: Vec<Fr>) -> Result<(), MusigError> {
+        self.aggregated_key.check_count(commitments.len())?;
+        if commitments[self.position] != self.nonce_commitment() {
+            return Err(MusigError::InvalidNonce(self.position));
+        }
+        self.commitments = Some(commitments);
+        Ok(())
+    }
+
+    /// Sets the nonces of all the participants, including the signer itself,
+    /// and checks them against the commitments.
+    pub fn set_nonces(&mut self, nonces: Vec<Point<Engine, Unknown>>) -> Result<(), MusigError> {
+        let commitments = self
+            .commitments
+            .as_ref()
+            .ok_or(MusigError::CommitmentsNotSet)?;
+        self.aggregated_key.check_count(nonces.len())?;
+        if let Some(position) = nonces
+            .iter()
+            .zip(commitments)
+            .position(|(nonce, commitment)| nonce_commitment(nonce) != *commitment)
+        {
+            return Err(MusigError::InvalidNonce(position));
+        }
+
+        self.aggregated_nonce = Some(
+            nonces
+                .iter()
+                .fold(Point::zero(), |acc, nonce| acc.add(nonce, &JUBJUB_PARAMS)),
+        );
+        Ok(())
+    }
+
+    /// Produces the partial signature of the message.
+    pub fn sign(self, msg: &[u8]) -> Result<Fs, MusigError> {
+        let aggregated_nonce = self
+            .aggregated_nonce
+            .as_ref()
+            .ok_or(MusigError::NoncesNotSet)?;
+        let challenge =
+            signature_challenge(self.aggregated_key.public_key(), aggregated_nonce, msg);
+
+        let mut signature = challenge;
+        signature.mul_assign(&self.aggregated_key.coefficients[self.position]);
+        signature.mul_assign(&self.private_key);
+        signature.add_assign(&self.nonce_secret);
+        Ok(signature)
+    }
+}
+
+/// Checks the partial signature of the participant at the given position.
+pub fn verify_partial_signature(
+    aggregated_key: &AggregatedPublicKey,
+    position: usize,
+    nonce: &Point<Engine, Unknown>,
+    aggregated_nonce: &Point<Engine, Unknown>,
+    msg: &[u8],
+    partial_signature: &Fs,
+) -> bool {
+    let (participant, coefficient) = match (
+        aggregated_key.participants.get(position),
+        aggregated_key.coefficients.get(position),
+    ) {
+        (Some(participant), Some(coefficient)) => (participant, coefficient),
+        _ => return false,
+    };
+
+    let mut weight = signature_challenge(aggregated_key.public_key(), aggregated_nonce, msg);
+    weight.mul_assign(coefficient);
+
+    let lhs: Point<Engine, Unknown> = JUBJUB_PARAMS
+        .generator(GENERATOR)
+        .mul(partial_signature.into_repr(), &JUBJUB_PARAMS)
+        .into();
+    let rhs = nonce.add(
+        &participant.0.mul(weight.into_repr(), &JUBJUB_PARAMS),
+        &JUBJUB_PARAMS,
+    );
+    lhs == rhs
+}
+
+/// Combines the partial signatures of all the participants into the signature
+/// verified against the aggregated public key.
+pub fn aggregate_signatures(
+    aggregated_nonce: &Point<Engine, Unknown>,
+    partial_signatures: &[Fs],
+) -> Signature<Engine> {
+    let s = partial_signatures
+        .iter()
+        .fold(Fs::zero(), |mut acc, signature| {
+            acc.add_assign(signature);
+            acc
+        });
+
+    Signature {
+        r: aggregated_nonce.clone(),
+        s,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        primitives::rescue_hash_tx_msg,
+        priv_key_from_fs,
+        rand::{SeedableRng, XorShiftRng},
+    };
+
+    fn verify(public_key: &PublicKey, msg: &[u8], signature: &Signature<Engine>) -> bool {
+        public_key.verify_musig_rescue(msg, signature, GENERATOR, &RESCUE_PARAMS, &JUBJUB_PARAMS)
+    }
+
+    /// Checks that the challenge matches the one of the signature verification.
+    #[test]
+    fn signature_challenge_matches_verification() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let private_key = priv_key_from_fs(Fs::rand(&mut rng));
+        let public_key = public_key_from_private(&private_key);
+        let msg = rescue_hash_tx_msg(b"single signer");
+
+        let nonce_secret = Fs::rand(&mut rng);
+        let nonce: Point<Engine, Unknown> = JUBJUB_PARAMS
+            .generator(GENERATOR)
+            .mul(nonce_secret.into_repr(), &JUBJUB_PARAMS)
+            .into();
+        let mut s = signature_challenge(&public_key, &nonce, &msg);
+        s.mul_assign(&private_key.0);
+        s.add_assign(&nonce_secret);
+
+        assert!(verify(&public_key, &msg, &Signature { r: nonce, s }));
+    }
+
+    /// Signs the message by two participants and checks the aggregated signature.
+    #[test]
+    fn two_of_two_signature() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let private_keys: Vec<_> = (0..2)
+            .map(|_| priv_key_from_fs(Fs::rand(&mut rng)))
+            .collect();
+        let aggregated_key =
+            AggregatedPublicKey::new(private_keys.iter().map(public_key_from_private).collect())
+                .unwrap();
+        let msg = rescue_hash_tx_msg(b"two signers");
+
+        let mut sessions: Vec<_> = private_keys
+            .iter()
+            .map(|key| MusigSession::new(&mut rng, key, aggregated_key.clone()).unwrap())
+            .collect();
+        let commitments: Vec<_> = sessions.iter().map(|s| s.nonce_commitment()).collect();
+        let nonces: Vec<_> = sessions.iter().map(|s| s.nonce().clone()).collect();
+        for session in &mut sessions {
+            assert_eq!(
+                session.set_nonces(nonces.clone()),
+                Err(MusigError::CommitmentsNotSet)
+            );
+            session.set_nonce_commitments(commitments.clone()).unwrap();
+            session.set_nonces(nonces.clone()).unwrap();
+        }
+
+        let aggregated_nonce = sessions[0].aggregated_nonce().unwrap().clone();
+        let partial_signatures: Vec<_> = sessions
+            .into_iter()
+            .map(|session| session.sign(&msg).unwrap())
+            .collect();
+        for (position, signature) in partial_signatures.iter().enumerate() {
+            assert!(verify_partial_signature(
+                &aggregated_key,
+                position,
+                &nonces[position],
+                &aggregated_nonce,
+                &msg,
+                signature
+            ));
+        }
+
+        let signature = aggregate_signatures(&aggregated_nonce, &partial_signatures);
+        assert!(verify(aggregated_key.public_key(), &msg, &signature));
+        // Neither participant alone can produce the signature.
+        let partial = aggregate_signatures(&aggregated_nonce, &partial_signatures[..1]);
+        assert!(!verify(aggregated_key.public_key(), &msg, &partial));
+    }
+
+    /// Checks that the nonce not matching its commitment is rejected.
+    #[test]
+    fn invalid_nonce() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let private_keys: Vec<_> = (0..2)
+            .map(|_| priv_key_from_fs(Fs::rand(&mut rng)))
+            .collect();
+        let aggregated_key =
+            AggregatedPublicKey::new(private_keys.iter().map(public_key_from_private).collect())
+                .unwrap();
+        let mut session =
+            MusigSession::new(&mut rng, &private_keys[0], aggregated_key.clone()).unwrap();
+        let other = MusigSession::new(&mut rng, &private_keys[1], aggregated_key).unwrap();
+
+        session
+            .set_nonce_commitments(vec![session.nonce_commitment(), other.nonce_commitment()])
+            .unwrap();
+        assert_eq!(
+            session.set_nonces(vec![session.nonce().clone(), session.nonce().clone()]),
+            Err(MusigError::InvalidNonce(1))
+        );
+    }
+}
@@ -21,7 +21,13 @@ use web3::{
 
 // Workspace uses
 use zksync_eth_signer::{raw_ethereum_tx::RawTransaction, EthereumSigner};
-use zksync_types::ChainId;
+use zksync_types::{
+    tx::{
+        eip712_signature::{EIP712TypedStructure, Eip712Domain},
+        Erc20Permit, PackedEthSignature,
+    },
+    ChainId,
+};
 
 use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, SignedCallResult};
 /// Gas limit value to be used in transaction if for some reason
@@ -30,6 +36,10 @@ use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, SignedCallResult};
 /// This is an emergency value, which will not be used normally.
 const FALLBACK_GAS_LIMIT: u64 = 3_000_000;
 
+/// Version of the EIP-712 domain the ERC-20 permits are signed within.
+/// The version is not exposed by the token contracts, the most common one is used.
+const ERC20_PERMIT_DOMAIN_VERSION: &str = "1";
+
 struct ETHDirectClientInner<S: EthereumSigner> {
     eth_signer: S,
     sender_account: Address,
@@ -370,6 +380,69 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         Ok(res)
     }
 
+    /// Signs the [EIP-2612](https://eips.ethereum.org/EIPS/eip-2612) permit allowing the main
+    /// contract to spend `value` of the sender tokens until `deadline`.
+    ///
+    /// Fails if the token domain separator doesn't match the domain built from the token name,
+    /// since the signature wouldn't be accepted by the token contract.
+    pub async fn sign_erc20_permit(
+        &self,
+        token_address: Address,
+        erc20_permit_abi: ethabi::Contract,
+        value: U256,
+        deadline: U256,
+    ) -> Result<(Erc20Permit, PackedEthSignature), anyhow::Error> {
+        let nonce: U256 = self
+            .call_contract_function(
+                "nonces",
+                self.inner.sender_account,
+                None,
+                Options::default(),
+                None,
+                token_address,
+                erc20_permit_abi.clone(),
+            )
+            .await?;
+        let name: String = self
+            .call_contract_function(
+                "name",
+                (),
+                None,
+                Options::default(),
+                None,
+                token_address,
+                erc20_permit_abi.clone(),
+            )
+            .await?;
+        let domain_separator: H256 = self
+            .call_contract_function(
+                "DOMAIN_SEPARATOR",
+                (),
+                None,
+                Options::default(),
+                None,
+                token_address,
+                erc20_permit_abi,
+            )
+            .await?;
+
+        let domain =
+            erc20_permit_domain(name, self.inner.chain_id, token_address, domain_separator)?;
+        let permit = Erc20Permit {
+            owner: self.inner.sender_account,
+            spender: self.inner.contract_addr,
+            value,
+            nonce,
+            deadline,
+        };
+        let signature = self
+            .inner
+            .eth_signer
+            .sign_typed_data(&domain, &permit)
+            .await?;
+        Ok((permit, signature))
+    }
+
     pub async fn call_main_contract_function<R, A, P, B>(
         &self,
         func: &str,
@@ -517,3 +590,99 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         Ok(tx)
     }
 }
+
+/// Builds the EIP-712 domain of the token permits, checking it against the `DOMAIN_SEPARATOR` of the token.
+fn erc20_permit_domain(
+    token_name: String,
+    chain_id: ChainId,
+    token_address: Address,
+    domain_separator: H256,
+) -> Result<Eip712Domain, anyhow::Error> {
+    let domain = Eip712Domain::with_verifying_contract(
+        token_name,
+        ERC20_PERMIT_DOMAIN_VERSION.to_string(),
+        chain_id,
+        token_address,
+    );
+    if domain.hash_struct() != domain_separator {
+        anyhow::bail!(
+            "Permit domain of the token {:?} is not supported",
+            token_address
+        );
+    }
+    Ok(domain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_eth_signer::PrivateKeySigner;
+
+    /// Checks that the permit is signed by the sender within the domain of the token,
+    /// and the tokens with the unsupported domain are rejected.
+    #[tokio::test]
+    async fn sign_erc20_permit() {
+        let token_address = Address::repeat_byte(1);
+        let chain_id = ChainId(9);
+        let expected_domain = Eip712Domain::with_verifying_contract(
+            "Permit Token".to_string(),
+            "1".to_string(),
+            chain_id,
+            token_address,
+        );
+        let domain_separator = expected_domain.hash_struct();
+
+        let domain = erc20_permit_domain(
+            "Permit Token".to_string(),
+            chain_id,
+            token_address,
+            domain_separator,
+        )
+        .unwrap();
+        assert_eq!(domain.hash_struct(), domain_separator);
+
+        // The name, the chain and the token must match the domain separator.
+        assert!(erc20_permit_domain(
+            "Other Token".to_string(),
+            chain_id,
+            token_address,
+            domain_separator
+        )
+        .is_err());
+        assert!(erc20_permit_domain(
+            "Permit Token".to_string(),
+            ChainId(1),
+            token_address,
+            domain_separator
+        )
+        .is_err());
+        assert!(erc20_permit_domain(
+            "Permit Token".to_string(),
+            chain_id,
+            Address::repeat_byte(2),
+            domain_separator
+        )
+        .is_err());
+
+        let private_key = H256::repeat_byte(7);
+        let owner = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let permit = Erc20Permit {
+            owner,
+            spender: Address::repeat_byte(3),
+            value: U256::from(1000u32),
+            nonce: U256::zero(),
+            deadline: U256::max_value(),
+        };
+        let signature = PrivateKeySigner::new(private_key)
+            .sign_typed_data(&domain, &permit)
+            .await
+            .unwrap();
+        let message = PackedEthSignature::typed_data_to_signed_message(&domain, &permit);
+        assert_eq!(
+            signature
+                .signature_recover_signer_from_typed_data_message(&message)
+                .unwrap(),
+            owner
+        );
+    }
+}
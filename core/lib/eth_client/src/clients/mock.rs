@@ -7,7 +7,7 @@ use tokio::sync::RwLock;
 use web3::contract::tokens::{Detokenize, Tokenize};
 use web3::contract::Options;
 use web3::transports::Http;
-use web3::types::{BlockId, Filter, Log, Transaction, U64};
+use web3::types::{Block, BlockId, BlockNumber, Filter, Log, Transaction, U64};
 
 use zksync_types::{TransactionReceipt, H160, H256, U256};
 
@@ -20,6 +20,7 @@ use crate::{
 struct MockEthereumInner {
     block_number: u64,
     gas_price: U256,
+    nonce: U256,
     tx_statuses: Arc<RwLock<HashMap<H256, ExecutedTxStatus>>>,
     sent_txs: Arc<RwLock<HashSet<Vec<u8>>>>,
 }
@@ -35,6 +36,7 @@ impl Default for MockEthereumInner {
         Self {
             block_number: 1,
             gas_price: 100.into(),
+            nonce: 0.into(),
             tx_statuses: Default::default(),
             sent_txs: Default::default(),
         }
@@ -124,6 +126,12 @@ impl MockEthereum {
         Ok(self.inner.gas_price)
     }
 
+    /// Sets the operator account nonce reported by both `current_nonce` and `pending_nonce`.
+    pub async fn set_nonce(&mut self, val: U256) -> anyhow::Result<U256> {
+        Arc::get_mut(&mut self.inner).unwrap().nonce = val;
+        Ok(self.inner.nonce)
+    }
+
     pub async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, anyhow::Error> {
         // Cut hash of transaction
         let mut hash: [u8; 32] = Default::default();
@@ -165,11 +173,11 @@ impl MockEthereum {
     }
 
     pub async fn pending_nonce(&self) -> Result<U256, Error> {
-        unreachable!()
+        Ok(self.inner.nonce)
     }
 
     pub async fn current_nonce(&self) -> Result<U256, Error> {
-        unreachable!()
+        Ok(self.inner.nonce)
     }
 
     pub async fn sender_eth_balance(&self) -> Result<U256, Error> {
@@ -269,4 +277,35 @@ impl MockEthereum {
     pub async fn get_tx(&self, _hash: H256) -> Result<Option<Transaction>, anyhow::Error> {
         unreachable!()
     }
+
+    /// Returns the mock block up to the current block number, whose hash is derived from its number.
+    pub async fn block(&self, id: BlockId) -> Result<Option<Block<H256>>, anyhow::Error> {
+        let number = match id {
+            BlockId::Number(BlockNumber::Number(number)) => number.as_u64(),
+            BlockId::Number(BlockNumber::Earliest) => 0,
+            BlockId::Number(_) => self.inner.block_number,
+            BlockId::Hash(hash) => match hash.to_low_u64_be().checked_sub(1) {
+                Some(number) if Self::block_hash(number) == hash => number,
+                _ => return Ok(None),
+            },
+        };
+        if number > self.inner.block_number {
+            return Ok(None);
+        }
+
+        Ok(Some(Block {
+            hash: Some(Self::block_hash(number)),
+            parent_hash: number
+                .checked_sub(1)
+                .map(Self::block_hash)
+                .unwrap_or_default(),
+            number: Some(number.into()),
+            ..Default::default()
+        }))
+    }
+
+    /// Hash of the mock block, it's never zero so the genesis block has no parent.
+    pub fn block_hash(number: u64) -> H256 {
+        H256::from_low_u64_be(number + 1)
+    }
 }
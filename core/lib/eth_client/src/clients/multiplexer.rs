@@ -5,7 +5,7 @@ use web3::{
     contract::tokens::{Detokenize, Tokenize},
     contract::Options,
     transports::Http,
-    types::{Address, BlockId, Filter, Log, Transaction, U64},
+    types::{Address, Block, BlockId, Filter, Log, Transaction, U64},
 };
 use zksync_eth_signer::PrivateKeySigner;
 use zksync_types::{TransactionReceipt, H160, H256, U256};
@@ -13,9 +13,36 @@ use zksync_types::{TransactionReceipt, H160, H256, U256};
 use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, SignedCallResult};
 use crate::ETHDirectClient;
 
+/// Amount of consecutive failed requests after which the gateway is considered unhealthy.
+/// Unhealthy gateways are only used when all the healthy ones have failed.
+const MAX_CONSECUTIVE_FAILURES: usize = 3;
+
+/// Health of a single gateway based on the results of the recent requests.
+#[derive(Debug, Default)]
+struct GatewayHealth {
+    consecutive_failures: AtomicUsize,
+}
+
+impl GatewayHealth {
+    fn is_healthy(&self) -> bool {
+        self.consecutive_failures.load(Ordering::Relaxed) < MAX_CONSECUTIVE_FAILURES
+    }
+
+    /// Resets the failures counter. Returns `true` if the gateway has recovered.
+    fn report_success(&self) -> bool {
+        self.consecutive_failures.swap(0, Ordering::Relaxed) >= MAX_CONSECUTIVE_FAILURES
+    }
+
+    /// Increments the failures counter. Returns `true` if the gateway has just become unhealthy.
+    fn report_failure(&self) -> bool {
+        self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1 == MAX_CONSECUTIVE_FAILURES
+    }
+}
+
 #[derive(Debug, Default)]
 struct MultiplexerEthereumClientInner {
     clients: Vec<(String, ETHDirectClient<PrivateKeySigner>)>,
+    health: Vec<GatewayHealth>,
     preferred: AtomicUsize,
 }
 
@@ -28,8 +55,14 @@ macro_rules! multiple_call {
     ($self:expr, $func:ident($($attr:expr),*)) => {
         for (name, client) in $self.clients() {
             match client.$func($($attr.clone()),*).await {
-                Ok(res) => return Ok(res),
-                Err(err) => vlog::error!("Error in interface: {}, {} ", name, err),
+                Ok(res) => {
+                    $self.report_success(name);
+                    return Ok(res);
+                }
+                Err(err) => {
+                    vlog::error!("Error in interface: {}, {} ", name, err);
+                    $self.report_failure(name);
+                }
             }
         }
         anyhow::bail!("All interfaces was wrong please try again")
@@ -46,10 +79,9 @@ impl MultiplexerEthereumClient {
         name: String,
         client: ETHDirectClient<PrivateKeySigner>,
     ) -> &mut Self {
-        Arc::get_mut(&mut self.inner)
-            .unwrap()
-            .clients
-            .push((name, client));
+        let inner = Arc::get_mut(&mut self.inner).unwrap();
+        inner.clients.push((name, client));
+        inner.health.push(GatewayHealth::default());
         self
     }
 
@@ -61,21 +93,62 @@ impl MultiplexerEthereumClient {
         }
     }
 
+    /// Records the successful request to the gateway, so it becomes healthy again if
+    /// it wasn't.
+    pub fn report_success(&self, name: &str) {
+        if let Some(idx) = self.client_index(name) {
+            if self.inner.health[idx].report_success() {
+                vlog::info!("Ethereum Gateway `{}` has recovered", name);
+            }
+        }
+    }
+
+    /// Records the failed request to the gateway. After several consecutive failures
+    /// the gateway is considered unhealthy and is only used as a last resort.
+    pub fn report_failure(&self, name: &str) {
+        if let Some(idx) = self.client_index(name) {
+            if self.inner.health[idx].report_failure() {
+                vlog::warn!(
+                    "Ethereum Gateway `{}` is unhealthy, switching to the other gateways",
+                    name
+                );
+                #[cfg(feature = "with-metrics")]
+                metrics::increment_counter!("eth_client.multiplexed.failover", "address" => name.to_owned());
+            }
+        }
+    }
+
+    /// Checks whether the gateway is healthy. Unknown gateways are considered unhealthy.
+    pub fn is_healthy(&self, name: &str) -> bool {
+        self.client_index(name)
+            .map(|idx| self.inner.health[idx].is_healthy())
+            .unwrap_or(false)
+    }
+
+    /// Returns the clients in the order they should be used: the healthy ones starting from
+    /// the preferred client, then the unhealthy ones in case all the healthy ones fail.
     pub fn clients(&self) -> impl Iterator<Item = (&str, &ETHDirectClient<PrivateKeySigner>)> {
+        let order = self.clients_order();
+        order.into_iter().map(move |idx| {
+            let (name, client) = &self.inner.clients[idx];
+            (name.as_str(), client)
+        })
+    }
+
+    fn client_index(&self, name: &str) -> Option<usize> {
+        self.inner.clients.iter().position(|(key, _)| key == name)
+    }
+
+    fn clients_order(&self) -> Vec<usize> {
         let preferred = self.inner.preferred.load(Ordering::Relaxed);
-        self.inner
-            .clients
-            .get(preferred)
-            .into_iter()
-            .chain(self.inner.clients.get(..preferred).unwrap_or(&[]).iter())
-            .chain(
-                self.inner
-                    .clients
-                    .get(1 + preferred..)
-                    .unwrap_or(&[])
-                    .iter(),
-            )
-            .map(|(name, client)| (name.as_str(), client))
+        let health: Vec<_> = self
+            .inner
+            .health
+            .iter()
+            .map(GatewayHealth::is_healthy)
+            .collect();
+
+        prioritized_order(preferred, &health)
     }
 
     pub fn create_contract(
@@ -102,6 +175,10 @@ impl MultiplexerEthereumClient {
         multiple_call!(self, block_number());
     }
 
+    pub async fn block(&self, id: BlockId) -> Result<Option<Block<H256>>, anyhow::Error> {
+        multiple_call!(self, block(id));
+    }
+
     pub async fn get_gas_price(&self) -> Result<U256, anyhow::Error> {
         multiple_call!(self, get_gas_price());
     }
@@ -227,3 +304,63 @@ impl MultiplexerEthereumClient {
         multiple_call!(self, get_tx(hash));
     }
 }
+
+/// Orders the gateways so the preferred one goes first and the rest keep their relative order,
+/// while all the unhealthy gateways are moved to the end.
+fn prioritized_order(preferred: usize, health: &[bool]) -> Vec<usize> {
+    let preferred = preferred.min(health.len().saturating_sub(1));
+    let rotation = std::iter::once(preferred)
+        .chain(0..preferred)
+        .chain(preferred + 1..health.len())
+        .filter(|&idx| idx < health.len());
+
+    let (healthy, unhealthy): (Vec<_>, Vec<_>) = rotation.partition(|&idx| health[idx]);
+    healthy.into_iter().chain(unhealthy).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that the gateway becomes unhealthy after several consecutive failures
+    /// and recovers after the first successful request.
+    #[test]
+    fn gateway_health() {
+        let health = GatewayHealth::default();
+        assert!(health.is_healthy());
+
+        for _ in 1..MAX_CONSECUTIVE_FAILURES {
+            assert!(!health.report_failure());
+            assert!(health.is_healthy());
+        }
+        assert!(health.report_failure());
+        assert!(!health.is_healthy());
+        // The transition is reported only once.
+        assert!(!health.report_failure());
+        assert!(!health.is_healthy());
+
+        assert!(health.report_success());
+        assert!(health.is_healthy());
+        assert!(!health.report_success());
+
+        // Failures must be consecutive.
+        for _ in 0..MAX_CONSECUTIVE_FAILURES * 2 {
+            health.report_failure();
+            health.report_success();
+        }
+        assert!(health.is_healthy());
+    }
+
+    /// Checks the order in which the gateways are used.
+    #[test]
+    fn gateways_order() {
+        assert_eq!(prioritized_order(0, &[]), Vec::<usize>::new());
+        assert_eq!(prioritized_order(0, &[true, true, true]), vec![0, 1, 2]);
+        assert_eq!(prioritized_order(1, &[true, true, true]), vec![1, 0, 2]);
+        assert_eq!(prioritized_order(2, &[true, true, true]), vec![2, 0, 1]);
+        // Unhealthy gateways are used last, even if preferred.
+        assert_eq!(prioritized_order(1, &[true, false, true]), vec![0, 2, 1]);
+        assert_eq!(prioritized_order(0, &[false, true, false]), vec![1, 0, 2]);
+        assert_eq!(prioritized_order(2, &[false, false, false]), vec![2, 0, 1]);
+    }
+}
@@ -1,7 +1,7 @@
 use web3::contract::tokens::{Detokenize, Tokenize};
 use web3::contract::{Contract, Options};
 use web3::transports::Http;
-use web3::types::{Address, BlockId, Filter, Log, Transaction, U64};
+use web3::types::{Address, Block, BlockId, Filter, Log, Transaction, U64};
 
 use std::fmt::Debug;
 use zksync_config::{ETHClientConfig, ETHSenderConfig};
@@ -123,6 +123,10 @@ impl EthereumGateway {
         delegate_call!(self.block_number())
     }
 
+    pub async fn block(&self, id: BlockId) -> Result<Option<Block<H256>>, anyhow::Error> {
+        delegate_call!(self.block(id))
+    }
+
     pub async fn get_gas_price(&self) -> Result<U256, anyhow::Error> {
         delegate_call!(self.get_gas_price())
     }
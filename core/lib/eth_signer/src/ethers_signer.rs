@@ -0,0 +1,358 @@
+//! Adapter allowing to use any `ethers` signer (local wallet, Ledger, AWS KMS, etc.)
+//! as the `EthereumSigner`.
+
+use std::sync::Arc;
+
+use ethers_core::types::{
+    transaction::{
+        eip2718::TypedTransaction,
+        eip2930::{AccessList, AccessListItem},
+        eip712::{EIP712Domain, Eip712},
+    },
+    Address as EthersAddress, Eip1559TransactionRequest, Eip2930TransactionRequest,
+    Signature as EthersSignature, TransactionRequest, H256 as EthersH256, U256 as EthersU256,
+};
+use ethers_signers::Signer;
+use web3::signing::Signature;
+
+use zksync_types::eip712_signature::{EIP712TypedStructure, Eip712Domain};
+use zksync_types::tx::{PackedEthSignature, TxEthSignature};
+use zksync_types::{Address, H256, U256};
+
+use crate::raw_ethereum_tx::{RawTransaction, Transaction, ACCESSLISTS_TX_ID, EIP1559_TX_ID};
+use crate::{EthereumSigner, SignerError};
+
+/// `EthereumSigner` backed by the `ethers` signer.
+///
+/// The signer is shared between the clones, so it doesn't have to be `Clone` itself
+/// (e.g. the hardware wallets are not).
+#[derive(Debug)]
+pub struct EthersSigner<S: Signer> {
+    signer: Arc<S>,
+}
+
+impl<S: Signer> Clone for EthersSigner<S> {
+    fn clone(&self) -> Self {
+        Self {
+            signer: self.signer.clone(),
+        }
+    }
+}
+
+impl<S: Signer> EthersSigner<S> {
+    pub fn new(signer: S) -> Self {
+        Self {
+            signer: Arc::new(signer),
+        }
+    }
+
+    /// Returns the underlying `ethers` signer.
+    pub fn inner(&self) -> &S {
+        &self.signer
+    }
+}
+
+impl<S: Signer> From<S> for EthersSigner<S> {
+    fn from(signer: S) -> Self {
+        Self::new(signer)
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: Signer + 'static> EthereumSigner for EthersSigner<S> {
+    async fn get_address(&self) -> Result<Address, SignerError> {
+        Ok(Address::from_slice(self.signer.address().as_bytes()))
+    }
+
+    /// The sign method calculates an Ethereum specific signature with:
+    /// sign(keccak256("\x19Ethereum Signed Message:\n" + len(message) + message))).
+    async fn sign_message(&self, message: &[u8]) -> Result<TxEthSignature, SignerError> {
+        let signature = self
+            .signer
+            .sign_message(message)
+            .await
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+        Ok(TxEthSignature::EthereumSignature(to_packed_signature(
+            signature,
+        )?))
+    }
+
+    /// Signs and returns the RLP-encoded transaction.
+    ///
+    /// The `ethers` signer only provides the signature, the transaction itself is encoded
+    /// the same way as by the other signers.
+    async fn sign_transaction(&self, raw_tx: RawTransaction) -> Result<Vec<u8>, SignerError> {
+        let chain_id = raw_tx.chain_id;
+        let tx = Transaction::from(raw_tx);
+        let typed_tx = to_typed_transaction(self.signer.address(), &tx, chain_id);
+        let signature = self
+            .signer
+            .sign_transaction(&typed_tx)
+            .await
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+
+        let v = if tx.is_legacy() {
+            signature.v
+        } else {
+            recovery_id(signature.v, chain_id)
+        };
+        let signature = Signature {
+            v,
+            r: from_ethers_u256(signature.r),
+            s: from_ethers_u256(signature.s),
+        };
+        Ok(tx.encode_signed(chain_id, &signature))
+    }
+
+    /// Signs the typed data. The hashes of the domain and of the struct are calculated
+    /// by zkSync, so the result doesn't depend on how the `ethers` signer encodes the payload.
+    async fn sign_typed_data<T: EIP712TypedStructure + Sync>(
+        &self,
+        eip712_domain: &Eip712Domain,
+        typed_struct: &T,
+    ) -> Result<PackedEthSignature, SignerError> {
+        let payload = TypedData {
+            domain: eip712_domain,
+            domain_separator: eip712_domain.hash_struct(),
+            struct_hash: typed_struct.hash_struct(),
+        };
+        let signature = self
+            .signer
+            .sign_typed_data(&payload)
+            .await
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+        let signature = to_packed_signature(signature)?;
+
+        // Some signers (e.g. Ledger) encode the payload on their own rather than using
+        // the provided hashes, make sure the signature matches the zkSync typed data.
+        let signed_bytes =
+            PackedEthSignature::typed_data_to_signed_bytes(eip712_domain, typed_struct);
+        let signer = signature
+            .signature_recover_signer_from_hash(signed_bytes)
+            .map_err(|err| SignerError::RecoverAddress(err.to_string()))?;
+        if signer != self.get_address().await? {
+            return Err(SignerError::SigningFailed(
+                "typed data signature does not match the zkSync domain".to_string(),
+            ));
+        }
+
+        Ok(signature)
+    }
+}
+
+/// EIP-712 payload with the hashes precalculated by zkSync.
+struct TypedData<'a> {
+    domain: &'a Eip712Domain,
+    domain_separator: H256,
+    struct_hash: H256,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("type hash is not available for the zkSync typed data")]
+struct TypeHashUnavailable;
+
+impl Eip712 for TypedData<'_> {
+    type Error = TypeHashUnavailable;
+
+    fn domain_separator(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(self.domain_separator.to_fixed_bytes())
+    }
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(EIP712Domain {
+            name: self.domain.name.clone(),
+            version: self.domain.version.clone(),
+            chain_id: to_ethers_u256(self.domain.chain_id),
+            verifying_contract: self
+                .domain
+                .verifying_contract
+                .map(|address| EthersAddress::from_slice(address.as_bytes()))
+                .unwrap_or_default(),
+            salt: None,
+        })
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Err(TypeHashUnavailable)
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(self.struct_hash.to_fixed_bytes())
+    }
+}
+
+fn to_typed_transaction(from: EthersAddress, tx: &Transaction, chain_id: u64) -> TypedTransaction {
+    let to = tx
+        .to
+        .map(|address| EthersAddress::from_slice(address.as_bytes()));
+    let access_list = AccessList(
+        tx.access_list
+            .iter()
+            .map(|item| AccessListItem {
+                address: EthersAddress::from_slice(item.address.as_bytes()),
+                storage_keys: item
+                    .storage_keys
+                    .iter()
+                    .map(|key| EthersH256::from_slice(key.as_bytes()))
+                    .collect(),
+            })
+            .collect(),
+    );
+
+    let mut legacy = TransactionRequest::new()
+        .from(from)
+        .nonce(to_ethers_u256(tx.nonce))
+        .gas(to_ethers_u256(tx.gas))
+        .gas_price(to_ethers_u256(tx.gas_price))
+        .value(to_ethers_u256(tx.value))
+        .data(tx.data.clone())
+        .chain_id(chain_id);
+    legacy.to = to.map(Into::into);
+
+    // `Transaction` stores the max fee per gas of EIP-1559 transactions as the gas price.
+    match tx.transaction_type.map(|t| t.as_u64()) {
+        Some(ACCESSLISTS_TX_ID) => Eip2930TransactionRequest::new(legacy, access_list).into(),
+        Some(EIP1559_TX_ID) => {
+            let mut eip1559 = Eip1559TransactionRequest::new()
+                .from(from)
+                .nonce(to_ethers_u256(tx.nonce))
+                .gas(to_ethers_u256(tx.gas))
+                .max_fee_per_gas(to_ethers_u256(tx.gas_price))
+                .max_priority_fee_per_gas(to_ethers_u256(tx.max_priority_fee_per_gas))
+                .value(to_ethers_u256(tx.value))
+                .data(tx.data.clone())
+                .access_list(access_list)
+                .chain_id(chain_id);
+            eip1559.to = to.map(Into::into);
+            eip1559.into()
+        }
+        _ => legacy.into(),
+    }
+}
+
+/// Typed transactions are signed with the recovery id as `v`, but some signers
+/// return it in the legacy (`27 + id`) or in the EIP-155 form.
+fn recovery_id(v: u64, chain_id: u64) -> u64 {
+    if v >= 35 {
+        (v - 35 - chain_id * 2) % 2
+    } else if v >= 27 {
+        v - 27
+    } else {
+        v
+    }
+}
+
+fn to_packed_signature(signature: EthersSignature) -> Result<PackedEthSignature, SignerError> {
+    PackedEthSignature::deserialize_packed(&signature.to_vec())
+        .map_err(|err| SignerError::SigningFailed(err.to_string()))
+}
+
+fn to_ethers_u256(value: U256) -> EthersU256 {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    EthersU256::from_big_endian(&bytes)
+}
+
+fn from_ethers_u256(value: EthersU256) -> H256 {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    H256::from(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::EthersSigner;
+    use crate::{EthereumSigner, PrivateKeySigner, RawTransaction};
+    use ethers_signers::LocalWallet;
+    use web3::types::U64;
+    use zksync_types::tx::{eip712_signature::Eip712Domain, ChangePubKey, TxEthSignature};
+    use zksync_types::{AccountId, ChainId, Nonce, PubKeyHash, H160, H256, U256};
+
+    const PRIVATE_KEY: [u8; 32] = [5; 32];
+
+    fn signers() -> (PrivateKeySigner, EthersSigner<LocalWallet>) {
+        let wallet: LocalWallet = hex::encode(PRIVATE_KEY).parse().unwrap();
+        (
+            PrivateKeySigner::new(H256::from(PRIVATE_KEY)),
+            EthersSigner::new(wallet),
+        )
+    }
+
+    #[tokio::test]
+    async fn same_as_private_key_signer() {
+        let (pk_signer, ethers_signer) = signers();
+        assert_eq!(
+            pk_signer.get_address().await.unwrap(),
+            ethers_signer.get_address().await.unwrap()
+        );
+
+        let message = b"zkSync";
+        match (
+            pk_signer.sign_message(message).await.unwrap(),
+            ethers_signer.sign_message(message).await.unwrap(),
+        ) {
+            (
+                TxEthSignature::EthereumSignature(expected),
+                TxEthSignature::EthereumSignature(signature),
+            ) => assert_eq!(expected, signature),
+            _ => panic!("Ethereum signatures expected"),
+        }
+
+        let chain_id = ChainId(31337);
+        let domain = Eip712Domain::new(chain_id);
+        let change_pub_key = ChangePubKey::new(
+            AccountId(0xdeadba),
+            Default::default(),
+            PubKeyHash::from_hex("sync:fefefefefefefefefefefefefefefefefefefefe").unwrap(),
+            Default::default(),
+            Default::default(),
+            Nonce(0x11223344),
+            Default::default(),
+            None,
+            None,
+            Some(chain_id),
+        );
+        assert_eq!(
+            pk_signer
+                .sign_typed_data(&domain, &change_pub_key)
+                .await
+                .unwrap(),
+            ethers_signer
+                .sign_typed_data(&domain, &change_pub_key)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn sign_transaction() {
+        let (pk_signer, ethers_signer) = signers();
+        for &transaction_type in &[None, Some(U64::from(1u32)), Some(U64::from(2u32))] {
+            let raw_transaction = RawTransaction {
+                nonce: U256::from(1u32),
+                to: Some(H160::default()),
+                gas: U256::from(21000u32),
+                gas_price: U256::from(2u32),
+                max_fee_per_gas: Some(U256::from(2u32)),
+                max_priority_fee_per_gas: Some(U256::from(1u32)),
+                value: Default::default(),
+                data: vec![1, 2, 3],
+                chain_id: 270,
+                transaction_type,
+                access_list: None,
+            };
+            assert_eq!(
+                pk_signer
+                    .sign_transaction(raw_transaction.clone())
+                    .await
+                    .unwrap(),
+                ethers_signer
+                    .sign_transaction(raw_transaction)
+                    .await
+                    .unwrap(),
+                "transaction type {:?}",
+                transaction_type
+            );
+        }
+    }
+}
@@ -8,12 +8,16 @@ use error::SignerError;
 use zksync_types::tx::{PackedEthSignature, TxEthSignature};
 use zksync_types::Address;
 
+#[cfg(feature = "ethers")]
+pub use ethers_signer::EthersSigner;
 pub use json_rpc_signer::JsonRpcSigner;
 pub use pk_signer::PrivateKeySigner;
 pub use raw_ethereum_tx::RawTransaction;
 use zksync_types::eip712_signature::{EIP712TypedStructure, Eip712Domain};
 
 pub mod error;
+#[cfg(feature = "ethers")]
+pub mod ethers_signer;
 pub mod json_rpc_signer;
 pub mod pk_signer;
 pub mod raw_ethereum_tx;
@@ -15,8 +15,8 @@ use web3::{
 };
 
 const LEGACY_TX_ID: u64 = 0;
-const ACCESSLISTS_TX_ID: u64 = 1;
-const EIP1559_TX_ID: u64 = 2;
+pub(crate) const ACCESSLISTS_TX_ID: u64 = 1;
+pub(crate) const EIP1559_TX_ID: u64 = 2;
 
 #[derive(Clone, Debug, PartialEq, Default)]
 pub struct RawTransaction {
@@ -58,6 +58,26 @@ pub struct Transaction {
     pub max_priority_fee_per_gas: U256,
 }
 
+impl From<RawTransaction> for Transaction {
+    fn from(raw_tx: RawTransaction) -> Self {
+        let gas_price = match raw_tx.max_fee_per_gas {
+            Some(val) => val,
+            None => raw_tx.gas_price,
+        };
+        Self {
+            to: raw_tx.to,
+            nonce: raw_tx.nonce,
+            gas: raw_tx.gas,
+            gas_price,
+            value: raw_tx.value,
+            data: raw_tx.data,
+            transaction_type: raw_tx.transaction_type,
+            access_list: raw_tx.access_list.unwrap_or_default(),
+            max_priority_fee_per_gas: raw_tx.max_priority_fee_per_gas.unwrap_or_default(),
+        }
+    }
+}
+
 impl Transaction {
     fn rlp_append_legacy(&self, stream: &mut RlpStream) {
         stream.append(&self.nonce);
@@ -185,12 +205,24 @@ impl Transaction {
         }
     }
 
-    /// Sign and return a raw signed transaction.
-    pub fn sign(self, sign: impl signing::Key, chain_id: u64) -> SignedTransaction {
-        let adjust_v_value = matches!(
+    /// Returns the raw transaction signed with the signature obtained elsewhere, e.g. from
+    /// the external signer. For the legacy transactions, `v` must already include the chain id
+    /// (see EIP-155), for the typed ones it's the recovery id.
+    pub fn encode_signed(&self, chain_id: u64, signature: &Signature) -> Vec<u8> {
+        self.encode(chain_id, Some(signature))
+    }
+
+    /// Returns `true` for the legacy (untyped) transactions, which are signed according to EIP-155.
+    pub fn is_legacy(&self) -> bool {
+        matches!(
             self.transaction_type.map(|t| t.as_u64()),
             Some(LEGACY_TX_ID) | None
-        );
+        )
+    }
+
+    /// Sign and return a raw signed transaction.
+    pub fn sign(self, sign: impl signing::Key, chain_id: u64) -> SignedTransaction {
+        let adjust_v_value = self.is_legacy();
 
         let encoded = self.encode(chain_id, None);
 
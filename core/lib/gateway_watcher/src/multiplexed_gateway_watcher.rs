@@ -116,7 +116,8 @@ impl MultiplexedGatewayWatcher {
     }
 
     /// Checks multiplexed client gateways and prioritizes one with longest chain,
-    /// most frequent hash and lowest latency.
+    /// most frequent hash and lowest latency. Results of the checks are reported to the client
+    /// to keep the health of the gateways up to date.
     async fn check_client_gateways(&self) {
         // Fetch latest block for each client.
         // Each request will resolve to (client key, client latest block) pair.
@@ -147,6 +148,7 @@ impl MultiplexedGatewayWatcher {
                             "Failed to get latest block from Ethereum Gateway `{}` within specified timeout",
                             key
                         );
+                        self.client.report_failure(key);
                         None
                     }
                 })
@@ -192,9 +194,14 @@ impl MultiplexedGatewayWatcher {
             if self.client.prioritize_client(preferred_client_key) {
                 vlog::info!("Prioritized Ethereum Gateway: `{}`", preferred_client_key);
             }
+            // Gateways that fell behind or are on a different chain are as bad as unreachable ones.
+            // Successful checks bring the gateways previously marked as unhealthy back into use.
             for (key, block, _) in &client_latest_blocks {
                 if let Err(err) = Self::verify_blocks(latest_block, block) {
                     vlog::error!("Ethereum Gateway `{}` - check failed: {}", key, err);
+                    self.client.report_failure(key);
+                } else {
+                    self.client.report_success(key);
                 }
             }
         }
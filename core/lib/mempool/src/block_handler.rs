@@ -1,6 +1,9 @@
+use std::collections::HashSet;
+
 use futures::channel::{mpsc, oneshot};
 use futures::StreamExt;
 
+use zksync_prometheus_exporter::labels::{report_op_processed, report_op_received};
 use zksync_types::{
     mempool::SignedTxVariant,
     tx::{error::TxAddError, TxHash},
@@ -13,6 +16,9 @@ use crate::state::MempoolState;
 pub struct ProposedBlock {
     pub priority_ops: Vec<PriorityOp>,
     pub txs: Vec<SignedTxVariant>,
+    /// Transactions whose fee doesn't cover their cost at the current fee token price,
+    /// they're rejected on execution. The batch is rejected if any of its transactions is.
+    pub underpriced_txs: HashSet<TxHash>,
 }
 
 impl ProposedBlock {
@@ -72,6 +78,8 @@ impl MempoolBlocksHandler {
                 &self.mempool_state,
             )
             .await?;
+        // The fees are checked again, since the token prices could drop after the fees are quoted.
+        let underpriced_txs = self.mempool_state.underpriced_txs(&txs).await?;
 
         if !priority_ops.is_empty() || !txs.is_empty() {
             vlog::debug!(
@@ -85,26 +93,28 @@ impl MempoolBlocksHandler {
         metrics::histogram!("mempool.propose_new_block", start.elapsed());
 
         for pr_op in &priority_ops {
-            let labels = vec![
-                ("stage", "propose_block".to_string()),
-                ("name", pr_op.data.variance_name()),
-                ("token", pr_op.data.token_id().to_string()),
-            ];
-
-            metrics::increment_counter!("process_tx_count", &labels)
+            report_op_received(
+                "propose_block",
+                pr_op.data.variance_name(),
+                pr_op.data.token_id(),
+            );
         }
 
         for tx_variant in &txs {
             for tx in tx_variant.get_transactions() {
-                let labels = vec![
-                    ("stage", "propose_block".to_string()),
-                    ("name", tx.tx.variance_name()),
-                    ("token", tx.tx.token_id().to_string()),
-                ];
-                metrics::histogram!("process_tx", tx.elapsed(), &labels);
+                report_op_processed(
+                    "propose_block",
+                    tx.tx.variance_name(),
+                    tx.tx.token_id(),
+                    tx.elapsed(),
+                );
             }
         }
-        Ok(ProposedBlock { priority_ops, txs })
+        Ok(ProposedBlock {
+            priority_ops,
+            txs,
+            underpriced_txs,
+        })
     }
 
     pub async fn run(mut self) {
@@ -0,0 +1,82 @@
+//! Accounts and transaction types accepted without the fee, the fee is covered by the operator.
+//!
+//! The API waives the fee of such transactions, and the mempool rejects the transactions sent
+//! with the waived fee unless they are whitelisted.
+
+// Built-in uses
+use std::collections::HashSet;
+use std::sync::Arc;
+// Workspace uses
+use zksync_types::{event::transaction::TransactionType, AccountId, ZkSyncTx};
+
+/// Whitelist of the fee-free accounts and transaction types, the clones share it.
+#[derive(Debug, Clone, Default)]
+pub struct FeeFreeWhitelist(Arc<(HashSet<AccountId>, HashSet<TransactionType>)>);
+
+impl FeeFreeWhitelist {
+    pub fn new(
+        accounts: impl IntoIterator<Item = AccountId>,
+        tx_types: impl IntoIterator<Item = TransactionType>,
+    ) -> Self {
+        Self(Arc::new((
+            accounts.into_iter().collect(),
+            tx_types.into_iter().collect(),
+        )))
+    }
+
+    /// Checks whether the transaction is accepted without the fee, since either its initiator
+    /// or its type is whitelisted.
+    pub fn is_fee_free(&self, tx: &ZkSyncTx) -> bool {
+        let (accounts, tx_types) = self.0.as_ref();
+        let is_whitelisted_initiator = tx
+            .account_id()
+            .map(|account_id| accounts.contains(&account_id))
+            .unwrap_or(false);
+        let is_whitelisted_type = tx
+            .tx_type()
+            .map(|tx_type| tx_types.contains(&tx_type))
+            .unwrap_or(false);
+        is_whitelisted_initiator || is_whitelisted_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::{Address, Nonce, TokenId, Transfer, Withdraw};
+
+    #[test]
+    fn fee_free_whitelist() {
+        let transfer = |account_id| {
+            ZkSyncTx::from(Transfer::new(
+                AccountId(account_id),
+                Address::repeat_byte(1),
+                Address::repeat_byte(2),
+                TokenId(0),
+                100u32.into(),
+                0u32.into(),
+                Nonce(0),
+                Default::default(),
+                None,
+            ))
+        };
+        let withdraw = ZkSyncTx::from(Withdraw::new(
+            AccountId(2),
+            Address::repeat_byte(1),
+            Address::repeat_byte(1),
+            TokenId(0),
+            100u32.into(),
+            0u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        ));
+
+        let whitelist = FeeFreeWhitelist::new(vec![AccountId(1)], vec![TransactionType::Withdraw]);
+        assert!(whitelist.is_fee_free(&transfer(1)));
+        assert!(!whitelist.is_fee_free(&transfer(2)));
+        assert!(whitelist.is_fee_free(&withdraw));
+
+        assert!(!FeeFreeWhitelist::default().is_fee_free(&transfer(1)));
+    }
+}
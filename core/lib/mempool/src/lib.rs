@@ -17,18 +17,25 @@ use tokio::task::JoinHandle;
 
 // Workspace uses
 use zksync_storage::ConnectionPool;
+use zksync_utils::shutdown::ShutdownSignal;
 
 // Local uses
 use crate::block_handler::MempoolBlocksHandler;
 pub use crate::block_handler::{GetBlockRequest, MempoolBlocksRequest, ProposedBlock};
+use crate::fee_free_whitelist::FeeFreeWhitelist;
 use crate::mempool_transactions_queue::MempoolTransactionsQueue;
+use crate::pipeline_pauses::PausedStages;
 use crate::state::MempoolState;
+use crate::token_blacklist::TokenBlacklist;
 pub use crate::transactions_handler::MempoolTransactionRequest;
 use crate::transactions_handler::MempoolTransactionsHandler;
 
 mod block_handler;
+pub mod fee_free_whitelist;
 mod mempool_transactions_queue;
+pub mod pipeline_pauses;
 mod state;
+pub mod token_blacklist;
 mod transactions_handler;
 
 // Due channel based nature, for better performance,
@@ -38,6 +45,10 @@ pub fn run_mempool_tx_handler(
     db_pool: ConnectionPool,
     tx_requests: mpsc::Receiver<MempoolTransactionRequest>,
     block_chunk_sizes: Vec<usize>,
+    paused_stages: PausedStages,
+    token_blacklist: TokenBlacklist,
+    fee_free_whitelist: FeeFreeWhitelist,
+    shutdown: ShutdownSignal,
 ) -> JoinHandle<()> {
     let mempool_state = MempoolState::new(db_pool.clone());
     let max_block_size_chunks = *block_chunk_sizes
@@ -49,6 +60,10 @@ pub fn run_mempool_tx_handler(
         mempool_state,
         requests: tx_requests,
         max_block_size_chunks,
+        paused_stages,
+        token_blacklist,
+        fee_free_whitelist,
+        shutdown,
     };
     tokio::spawn(handler.run())
 }
@@ -0,0 +1,79 @@
+//! Pauses of the pipeline stages set by the operators via the admin API.
+//!
+//! The pauses are persisted in the database, so they're shared by the server replicas and
+//! survive the restarts. The `eth_sender` checks them on its own, while the mempool and the state
+//! keeper rely on the `PausedStages` updated periodically, so that the database isn't queried
+//! for every transaction.
+
+// Built-in uses
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+// External uses
+use tokio::{task::JoinHandle, time};
+// Workspace uses
+use zksync_storage::ConnectionPool;
+use zksync_types::pipeline::PipelineStage;
+
+/// Interval of loading the paused stages from the database.
+const PAUSED_STAGES_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Pipeline stages paused at the moment, the clones of the handle share them.
+#[derive(Debug, Clone, Default)]
+pub struct PausedStages(Arc<RwLock<HashSet<PipelineStage>>>);
+
+impl PausedStages {
+    /// Creates the handle with the stages currently paused in the database.
+    pub async fn load(connection_pool: &ConnectionPool) -> anyhow::Result<Self> {
+        let paused_stages = Self::default();
+        paused_stages.update(load_paused_stages(connection_pool).await?);
+        Ok(paused_stages)
+    }
+
+    pub fn is_paused(&self, stage: PipelineStage) -> bool {
+        self.0.read().unwrap().contains(&stage)
+    }
+
+    /// Replaces the paused stages, reporting the changed ones.
+    pub fn update(&self, stages: HashSet<PipelineStage>) {
+        let mut paused = self.0.write().unwrap();
+        for &stage in PipelineStage::ALL.iter() {
+            match (paused.contains(&stage), stages.contains(&stage)) {
+                (false, true) => vlog::warn!("Pipeline stage {} is paused", stage),
+                (true, false) => vlog::info!("Pipeline stage {} is resumed", stage),
+                _ => {}
+            }
+            let value = if stages.contains(&stage) { 1.0 } else { 0.0 };
+            metrics::gauge!("pipeline.paused", value, "stage" => stage.as_str());
+        }
+        *paused = stages;
+    }
+}
+
+async fn load_paused_stages(
+    connection_pool: &ConnectionPool,
+) -> anyhow::Result<HashSet<PipelineStage>> {
+    let mut storage = connection_pool.access_storage().await?;
+    let pauses = storage.misc_schema().load_paused_pipeline_stages().await?;
+    Ok(pauses.into_iter().map(|pause| pause.stage).collect())
+}
+
+/// Runs the actor keeping the `paused_stages` in sync with the database.
+#[must_use]
+pub fn run_paused_stages_updater(
+    connection_pool: ConnectionPool,
+    paused_stages: PausedStages,
+) -> JoinHandle<()> {
+    let mut timer = time::interval(PAUSED_STAGES_UPDATE_INTERVAL);
+
+    tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+            match load_paused_stages(&connection_pool).await {
+                Ok(stages) => paused_stages.update(stages),
+                // Keeping the previous pauses is safer than resuming the paused stages.
+                Err(err) => vlog::warn!("Unable to load the paused pipeline stages: {}", err),
+            }
+        }
+    })
+}
@@ -148,4 +148,52 @@ impl MempoolState {
 
         Ok(transactions_queue)
     }
+
+    /// Returns the transactions paying the fee in the tokens whose price has dropped below
+    /// the floor stored with the fee quote, so the fee doesn't cover their cost anymore.
+    pub async fn underpriced_txs(
+        &self,
+        txs: &[SignedTxVariant],
+    ) -> Result<HashSet<TxHash>, TxAddError> {
+        let tx_hashes: Vec<_> = txs.iter().flat_map(SignedTxVariant::hashes).collect();
+        let mut storage = self
+            .db_pool
+            .access_storage()
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+        let floors = storage
+            .chain()
+            .mempool_schema()
+            .load_fee_token_price_floors(&tx_hashes)
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+
+        let mut underpriced_txs = HashSet::new();
+        for (tx_hash, floor) in floors {
+            let price = storage
+                .tokens_schema()
+                .get_historical_ticker_price(floor.token_id)
+                .await
+                .map_err(|_| TxAddError::DbError)?;
+            match price {
+                Some(price) if price.usd_price < floor.min_usd_price => {
+                    vlog::warn!(
+                        "Fee of the tx {} doesn't cover its cost at the current price of the token {}",
+                        tx_hash.to_string(),
+                        floor.token_id
+                    );
+                    metrics::increment_counter!("mempool.underpriced_txs");
+                    underpriced_txs.insert(tx_hash);
+                }
+                Some(_) => {}
+                // The fee is quoted using the stored price, so it's not expected to disappear.
+                None => vlog::warn!(
+                    "Price of the token {} is unknown, the fee of the tx {} isn't checked",
+                    floor.token_id,
+                    tx_hash.to_string()
+                ),
+            }
+        }
+        Ok(underpriced_txs)
+    }
 }
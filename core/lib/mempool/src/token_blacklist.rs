@@ -0,0 +1,122 @@
+//! Tokens blacklisted by the operators, the transactions using them are rejected.
+//!
+//! The blacklist is persisted in the database. The API and the mempools rely on the `TokenBlacklist`
+//! updated periodically, so that the database isn't queried for every transaction.
+
+// Built-in uses
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+// External uses
+use tokio::{task::JoinHandle, time};
+// Workspace uses
+use zksync_storage::ConnectionPool;
+use zksync_types::{TokenId, ZkSyncTx};
+
+/// Interval of loading the token blacklist from the database.
+const TOKEN_BLACKLIST_UPDATE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Tokens blacklisted at the moment, the clones of the handle share them.
+#[derive(Debug, Clone, Default)]
+pub struct TokenBlacklist(Arc<RwLock<HashSet<TokenId>>>);
+
+impl TokenBlacklist {
+    /// Creates the handle with the tokens currently blacklisted in the database.
+    pub async fn load(connection_pool: &ConnectionPool) -> anyhow::Result<Self> {
+        let blacklist = Self::default();
+        blacklist.update(load_token_blacklist(connection_pool).await?);
+        Ok(blacklist)
+    }
+
+    /// Returns the blacklisted token used by the transaction, see `ZkSyncTx::blacklisted_token`.
+    pub fn blacklisted_token(&self, tx: &ZkSyncTx) -> Option<TokenId> {
+        tx.blacklisted_token(&self.0.read().unwrap())
+    }
+
+    /// Replaces the blacklisted tokens, reporting the changed ones.
+    pub fn update(&self, tokens: HashSet<TokenId>) {
+        let mut blacklist = self.0.write().unwrap();
+        for token in tokens.difference(&blacklist) {
+            vlog::warn!("Token {} is blacklisted", token);
+        }
+        for token in blacklist.difference(&tokens) {
+            vlog::info!("Token {} is removed from the blacklist", token);
+        }
+        metrics::gauge!("mempool.blacklisted_tokens", tokens.len() as f64);
+        *blacklist = tokens;
+    }
+}
+
+async fn load_token_blacklist(
+    connection_pool: &ConnectionPool,
+) -> anyhow::Result<HashSet<TokenId>> {
+    let mut storage = connection_pool.access_storage().await?;
+    let blacklist = storage.tokens_schema().load_token_blacklist().await?;
+    Ok(blacklist)
+}
+
+/// Runs the actor keeping the `token_blacklist` in sync with the database.
+#[must_use]
+pub fn run_token_blacklist_updater(
+    connection_pool: ConnectionPool,
+    token_blacklist: TokenBlacklist,
+) -> JoinHandle<()> {
+    let mut timer = time::interval(TOKEN_BLACKLIST_UPDATE_INTERVAL);
+
+    tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+            match load_token_blacklist(&connection_pool).await {
+                Ok(tokens) => token_blacklist.update(tokens),
+                // Keeping the previous blacklist is safer than accepting the blacklisted tokens.
+                Err(err) => vlog::warn!("Unable to load the token blacklist: {}", err),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::{Address, Nonce, Transfer, Withdraw};
+
+    #[test]
+    fn token_blacklist_update() {
+        let owner = Address::repeat_byte(1);
+        let transfer = ZkSyncTx::from(Transfer::new(
+            Default::default(),
+            owner,
+            Address::repeat_byte(2),
+            TokenId(1),
+            100u32.into(),
+            0u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        ));
+        let withdraw_to_owner = ZkSyncTx::from(Withdraw::new(
+            Default::default(),
+            owner,
+            owner,
+            TokenId(1),
+            100u32.into(),
+            0u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        ));
+
+        let blacklist = TokenBlacklist::default();
+        assert_eq!(blacklist.blacklisted_token(&transfer), None);
+
+        // The clones share the blacklist.
+        blacklist
+            .clone()
+            .update(vec![TokenId(1)].into_iter().collect());
+        assert_eq!(blacklist.blacklisted_token(&transfer), Some(TokenId(1)));
+        assert_eq!(blacklist.blacklisted_token(&withdraw_to_owner), None);
+
+        blacklist.update(HashSet::new());
+        assert_eq!(blacklist.blacklisted_token(&transfer), None);
+    }
+}
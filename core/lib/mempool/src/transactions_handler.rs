@@ -1,21 +1,36 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use futures::channel::{mpsc, oneshot};
 use futures::StreamExt;
+use vlog::Instrument;
 
+use zksync_prometheus_exporter::labels::{report_op_processed, report_op_received, NAME, TOKEN};
 use zksync_storage::ConnectionPool;
 use zksync_types::{
     mempool::SignedTxsBatch,
-    tx::{error::TxAddError, TxEthSignature},
+    pipeline::PipelineStage,
+    tx::{error::TxAddError, TxEthSignature, TxHash},
     PriorityOp, SignedZkSyncTx,
 };
+use zksync_utils::shutdown::ShutdownSignal;
 
+use crate::fee_free_whitelist::FeeFreeWhitelist;
+use crate::pipeline_pauses::PausedStages;
 use crate::state::MempoolState;
+use crate::token_blacklist::TokenBlacklist;
 
 #[derive(Debug)]
 pub enum MempoolTransactionRequest {
     /// Add new transaction to mempool, transaction should be previously checked
     /// for correctness (including its Ethereum and ZKSync signatures).
+    /// The flag is set if the fee of the transaction is waived, such a transaction
+    /// is rejected unless it's whitelisted as fee-free.
     /// oneshot is used to receive tx add result.
-    NewTx(Box<SignedZkSyncTx>, oneshot::Sender<Result<(), TxAddError>>),
+    NewTx(
+        Box<SignedZkSyncTx>,
+        bool,
+        oneshot::Sender<Result<(), TxAddError>>,
+    ),
 
     /// Add new priority ops, confirmed or not
     NewPriorityOps(
@@ -26,12 +41,66 @@ pub enum MempoolTransactionRequest {
     /// Add a new batch of transactions to the mempool. All transactions in batch must
     /// be either executed successfully, or otherwise fail all together.
     /// Invariants for each individual transaction in the batch are the same as in
-    /// `NewTx` variant of this enum.
+    /// `NewTx` variant of this enum, the hashes of the transactions with the waived fee
+    /// are provided.
     NewTxsBatch(
         Vec<SignedZkSyncTx>,
         Vec<TxEthSignature>,
+        Vec<TxHash>,
         oneshot::Sender<Result<(), TxAddError>>,
     ),
+    /// Remove priority ops received in the Ethereum blocks after the provided one,
+    /// since these blocks were reverted by the chain reorganization.
+    RevertPriorityOps(u64, oneshot::Sender<Result<(), TxAddError>>),
+}
+
+/// Rejects the transaction whose validity window has already passed, since it would fail
+/// in the state keeper anyway.
+fn ensure_not_expired(tx: &SignedZkSyncTx) -> Result<(), TxAddError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("failed to get system time")
+        .as_secs();
+    if tx.tx.time_range().is_expired(now) {
+        metrics::increment_counter!("mempool.expired_txs", NAME => tx.tx.variance_name());
+        return Err(TxAddError::TransactionExpired);
+    }
+    Ok(())
+}
+
+/// Rejects the transaction using the blacklisted tokens, unless it withdraws them
+/// to the owner's own address.
+fn ensure_not_blacklisted(
+    tx: &SignedZkSyncTx,
+    token_blacklist: &TokenBlacklist,
+) -> Result<(), TxAddError> {
+    if let Some(token) = token_blacklist.blacklisted_token(&tx.tx) {
+        metrics::increment_counter!("mempool.blacklisted_token_txs", TOKEN => token.to_string());
+        return Err(TxAddError::TokenBlacklisted(token));
+    }
+    Ok(())
+}
+
+/// Rejects the transaction sent with the waived fee unless it's whitelisted as fee-free,
+/// so the fee isn't waived by the API instance with the outdated configuration.
+fn ensure_fee_free(
+    tx: &SignedZkSyncTx,
+    fee_free_whitelist: &FeeFreeWhitelist,
+) -> Result<(), TxAddError> {
+    if !fee_free_whitelist.is_fee_free(&tx.tx) {
+        metrics::increment_counter!("mempool.not_fee_free_txs", NAME => tx.tx.variance_name());
+        return Err(TxAddError::TxFeeTooLow);
+    }
+    Ok(())
+}
+
+/// Rejects the transactions while their acceptance is paused by the operator.
+fn ensure_tx_acceptance_not_paused(paused_stages: &PausedStages) -> Result<(), TxAddError> {
+    if paused_stages.is_paused(PipelineStage::TxAcceptance) {
+        metrics::increment_counter!("mempool.paused_txs");
+        return Err(TxAddError::TxAcceptancePaused);
+    }
+    Ok(())
 }
 
 pub(crate) struct MempoolTransactionsHandler {
@@ -39,12 +108,19 @@ pub(crate) struct MempoolTransactionsHandler {
     pub mempool_state: MempoolState,
     pub requests: mpsc::Receiver<MempoolTransactionRequest>,
     pub max_block_size_chunks: usize,
+    pub paused_stages: PausedStages,
+    pub token_blacklist: TokenBlacklist,
+    pub fee_free_whitelist: FeeFreeWhitelist,
+    /// Once the shutdown is triggered, new transactions are rejected so that the state keeper
+    /// could seal the pending block. Priority operations are still accepted.
+    pub shutdown: ShutdownSignal,
 }
 
 impl MempoolTransactionsHandler {
-    async fn add_tx(&mut self, tx: SignedZkSyncTx) -> Result<(), TxAddError> {
+    async fn add_tx(&mut self, tx: SignedZkSyncTx, fee_waived: bool) -> Result<(), TxAddError> {
         // Correctness should be checked by `signature_checker`, thus
         // `tx.check_correctness()` is not invoked here.
+        ensure_tx_acceptance_not_paused(&self.paused_stages)?;
         let mut storage = self.db_pool.access_storage().await.map_err(|err| {
             vlog::error!("Mempool storage access error: {}", err);
             TxAddError::DbError
@@ -62,6 +138,11 @@ impl MempoolTransactionsHandler {
         if tx.nonce() < nonce {
             return Err(TxAddError::NonceMismatch);
         }
+        ensure_not_expired(&tx)?;
+        ensure_not_blacklisted(&tx, &self.token_blacklist)?;
+        if fee_waived {
+            ensure_fee_free(&tx, &self.fee_free_whitelist)?;
+        }
 
         storage
             .chain()
@@ -73,12 +154,13 @@ impl MempoolTransactionsHandler {
                 TxAddError::DbError
             })?;
 
-        let labels = vec![
-            ("stage", "mempool".to_string()),
-            ("name", tx.tx.variance_name()),
-            ("token", tx.tx.token_id().to_string()),
-        ];
-        metrics::histogram!("process_tx", tx.elapsed(), &labels);
+        report_op_processed(
+            "mempool",
+            tx.tx.variance_name(),
+            tx.tx.token_id(),
+            tx.elapsed(),
+        );
+        vlog::debug!("Transaction is added to the mempool");
 
         Ok(())
     }
@@ -121,12 +203,61 @@ impl MempoolTransactionsHandler {
 
         if confirmed {
             for op in &ops {
-                let labels = vec![
-                    ("stage", "mempool".to_string()),
-                    ("name", op.data.variance_name()),
-                    ("token", op.data.token_id().to_string()),
-                ];
-                metrics::increment_counter!("process_tx_count", &labels);
+                report_op_received("mempool", op.data.variance_name(), op.data.token_id());
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn revert_priority_ops(&mut self, last_valid_eth_block: u64) -> Result<(), TxAddError> {
+        let mut storage = self.db_pool.access_storage().await.map_err(|err| {
+            vlog::error!("Mempool storage access error: {}", err);
+            TxAddError::DbError
+        })?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+
+        let removed_ops = transaction
+            .chain()
+            .mempool_schema()
+            .remove_priority_ops_after_eth_block(last_valid_eth_block)
+            .await
+            .map_err(|err| {
+                vlog::error!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+        let last_processed_priority_op = transaction
+            .chain()
+            .operations_schema()
+            .get_max_priority_op_serial_id()
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+        transaction
+            .commit()
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+
+        if !removed_ops.is_empty() {
+            vlog::warn!(
+                "Removed priority operations {:?} received after the reverted Ethereum block {}",
+                removed_ops,
+                last_valid_eth_block
+            );
+        }
+        // Already executed operations can't be reverted, this situation requires manual intervention.
+        if let Some(serial_id) = last_processed_priority_op {
+            let executed_ops: Vec<_> = removed_ops
+                .iter()
+                .filter(|removed_id| **removed_id <= serial_id)
+                .collect();
+            if !executed_ops.is_empty() {
+                vlog::error!(
+                    "Priority operations {:?} from the reverted Ethereum blocks were already executed",
+                    executed_ops
+                );
             }
         }
 
@@ -137,6 +268,7 @@ impl MempoolTransactionsHandler {
         &mut self,
         txs: Vec<SignedZkSyncTx>,
         eth_signatures: Vec<TxEthSignature>,
+        fee_waived_txs: Vec<TxHash>,
     ) -> Result<(), TxAddError> {
         let batch = SignedTxsBatch {
             txs: txs.clone(),
@@ -144,6 +276,7 @@ impl MempoolTransactionsHandler {
             eth_signatures: eth_signatures.clone(),
         };
 
+        ensure_tx_acceptance_not_paused(&self.paused_stages)?;
         let mut storage = self.db_pool.access_storage().await.map_err(|err| {
             vlog::error!("Mempool storage access error: {}", err);
             TxAddError::DbError
@@ -164,6 +297,12 @@ impl MempoolTransactionsHandler {
             if tx.nonce() < nonce {
                 return Err(TxAddError::NonceMismatch);
             }
+            // The batch can't be executed if any of its transactions expired.
+            ensure_not_expired(tx)?;
+            ensure_not_blacklisted(tx, &self.token_blacklist)?;
+            if fee_waived_txs.contains(&tx.hash()) {
+                ensure_fee_free(tx, &self.fee_free_whitelist)?;
+            }
         }
 
         if self.mempool_state.chunks_for_batch(&batch).await? > self.max_block_size_chunks {
@@ -171,13 +310,12 @@ impl MempoolTransactionsHandler {
         }
 
         for tx in &batch.txs {
-            let labels = vec![
-                ("stage", "mempool".to_string()),
-                ("name", tx.tx.variance_name()),
-                ("token", tx.tx.token_id().to_string()),
-            ];
-
-            metrics::histogram!("process_tx", tx.elapsed(), &labels);
+            report_op_processed(
+                "mempool",
+                tx.tx.variance_name(),
+                tx.tx.token_id(),
+                tx.elapsed(),
+            );
         }
 
         storage
@@ -189,6 +327,7 @@ impl MempoolTransactionsHandler {
                 vlog::warn!("Mempool storage access error: {}", err);
                 TxAddError::DbError
             })?;
+        vlog::debug!("Transactions batch is added to the mempool");
 
         Ok(())
     }
@@ -197,18 +336,45 @@ impl MempoolTransactionsHandler {
         vlog::info!("Transaction mempool handler is running");
         while let Some(request) = self.requests.next().await {
             match request {
-                MempoolTransactionRequest::NewTx(tx, resp) => {
-                    let tx_add_result = self.add_tx(*tx).await;
+                MempoolTransactionRequest::NewTx(_, _, resp) if self.shutdown.is_triggered() => {
+                    resp.send(Err(TxAddError::ServerShuttingDown))
+                        .unwrap_or_default();
+                }
+                MempoolTransactionRequest::NewTxsBatch(_, _, _, resp)
+                    if self.shutdown.is_triggered() =>
+                {
+                    resp.send(Err(TxAddError::ServerShuttingDown))
+                        .unwrap_or_default();
+                }
+                MempoolTransactionRequest::NewTx(tx, fee_waived, resp) => {
+                    let span = vlog::info_span!("mempool_add_tx", tx_hash = %tx.hash().to_string());
+                    let tx_add_result = self.add_tx(*tx, fee_waived).instrument(span).await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
-                MempoolTransactionRequest::NewTxsBatch(txs, eth_signatures, resp) => {
-                    let tx_add_result = self.add_batch(txs, eth_signatures).await;
+                MempoolTransactionRequest::NewTxsBatch(
+                    txs,
+                    eth_signatures,
+                    fee_waived_txs,
+                    resp,
+                ) => {
+                    let tx_hashes: Vec<_> = txs.iter().map(|tx| tx.hash()).collect();
+                    let batch_hash = TxHash::batch_hash(&tx_hashes);
+                    let span =
+                        vlog::info_span!("mempool_add_batch", batch_hash = %batch_hash.to_string());
+                    let tx_add_result = self
+                        .add_batch(txs, eth_signatures, fee_waived_txs)
+                        .instrument(span)
+                        .await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
                 MempoolTransactionRequest::NewPriorityOps(ops, confirmed, resp) => {
                     let tx_add_result = self.add_priority_ops(ops, confirmed).await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
+                MempoolTransactionRequest::RevertPriorityOps(last_valid_eth_block, resp) => {
+                    let revert_result = self.revert_priority_ops(last_valid_eth_block).await;
+                    resp.send(revert_result).unwrap_or_default();
+                }
             }
         }
     }
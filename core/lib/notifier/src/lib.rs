@@ -26,4 +26,20 @@ impl Notifier {
 
         Ok(())
     }
+
+    pub async fn send_token_metadata_mismatch_notify(
+        &self,
+        token: &Token,
+        mismatch: &str,
+    ) -> anyhow::Result<()> {
+        let msg = format!(
+            "Token metadata mismatch: id = {}, address = {}, symbol = {}: {}",
+            token.id, token.address, token.symbol, mismatch,
+        );
+        self.matter_most_notifier
+            .send_notify("token_handler_bot", &msg)
+            .await?;
+
+        Ok(())
+    }
 }
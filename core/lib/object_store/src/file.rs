@@ -0,0 +1,48 @@
+// Built-in deps
+use std::path::PathBuf;
+// External uses
+use tokio::fs;
+// Local uses
+use crate::ObjectStore;
+
+/// Object storage in the local directory, each object is stored in its own file.
+#[derive(Debug, Clone)]
+pub struct FileObjectStore {
+    root: PathBuf,
+}
+
+impl FileObjectStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for FileObjectStore {
+    async fn put(&self, key: &str, data: Vec<u8>) -> anyhow::Result<()> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(path, data).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>> {
+        let data = fs::read(self.root.join(key))
+            .await
+            .map_err(|err| anyhow::format_err!("Failed to read object {}: {}", key, err))?;
+        Ok(data)
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        match fs::remove_file(self.root.join(key)).await {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(anyhow::format_err!(
+                "Failed to delete object {}: {}",
+                key,
+                err
+            )),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! Object storage for the prover artifacts, i.e. block witnesses and proofs.
+//!
+//! Witnesses may take gigabytes, so instead of the database they can be kept in the S3 or GCS
+//! bucket, with only the keys of the objects stored in the database. `ArtifactStore` decides where
+//! the artifact goes according to the configuration, and restores the artifacts referenced by
+//! the database regardless of where they are stored.
+//!
+//! Objects may be compressed with zstd. Compressed objects are recognized on load, so the
+//! compression can be enabled or disabled without affecting the objects stored before.
+
+// Built-in deps
+use std::{fmt, sync::Arc, time::Instant};
+// External uses
+use serde::{de::DeserializeOwned, Serialize};
+// Workspace uses
+use zksync_config::configs::object_store::{ObjectStoreConfig, ObjectStoreMode};
+use zksync_types::{prover::StoredArtifact, BlockNumber};
+use zksync_utils::compression;
+// Local uses
+pub use self::{file::FileObjectStore, s3::S3ObjectStore};
+
+mod file;
+mod s3;
+
+/// Storage of the binary objects addressed by their keys.
+#[async_trait::async_trait]
+pub trait ObjectStore: fmt::Debug + Send + Sync {
+    /// Stores the object, overwriting the existing one with the same key.
+    async fn put(&self, key: &str, data: Vec<u8>) -> anyhow::Result<()>;
+
+    /// Loads the object, which must exist.
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>>;
+
+    /// Removes the object, does nothing if it doesn't exist.
+    async fn delete(&self, key: &str) -> anyhow::Result<()>;
+}
+
+/// Store of the prover artifacts, which keeps them either in the database or in the object storage.
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactStore {
+    /// Object storage for the new artifacts, they are stored in the database if not set.
+    object_store: Option<Arc<dyn ObjectStore>>,
+    key_prefix: String,
+    /// Level of the zstd compression of the uploaded objects, they're not compressed if not set.
+    compression_level: Option<i32>,
+}
+
+impl ArtifactStore {
+    /// Creates the store that keeps the artifacts in the object storage.
+    pub fn new(object_store: Arc<dyn ObjectStore>, key_prefix: impl Into<String>) -> Self {
+        Self {
+            object_store: Some(object_store),
+            key_prefix: key_prefix.into(),
+            compression_level: None,
+        }
+    }
+
+    /// Sets the level of the zstd compression of the uploaded objects.
+    pub fn with_compression_level(mut self, compression_level: Option<i32>) -> Self {
+        self.compression_level = compression_level;
+        self
+    }
+
+    pub fn from_config(config: &ObjectStoreConfig) -> anyhow::Result<Self> {
+        let object_store: Arc<dyn ObjectStore> = match config.mode {
+            ObjectStoreMode::Database => return Ok(Self::default()),
+            ObjectStoreMode::File => Arc::new(FileObjectStore::new(&config.bucket)),
+            ObjectStoreMode::S3 | ObjectStoreMode::Gcs => Arc::new(S3ObjectStore::new(config)?),
+        };
+        vlog::info!(
+            "Prover artifacts are stored in the object storage: {:?}",
+            object_store
+        );
+
+        Ok(Self::new(object_store, config.key_prefix.clone())
+            .with_compression_level(config.compression_level))
+    }
+
+    /// Returns the key of the object for the block witness.
+    pub fn witness_key(&self, block: BlockNumber) -> String {
+        self.object_key(&format!("witnesses/{}.json", block))
+    }
+
+    /// Returns the key of the object for the single block proof.
+    pub fn proof_key(&self, block: BlockNumber) -> String {
+        self.object_key(&format!("proofs/{}.json", block))
+    }
+
+    /// Returns the key of the object for the aggregated proof of the blocks.
+    pub fn aggregated_proof_key(
+        &self,
+        first_block: BlockNumber,
+        last_block: BlockNumber,
+    ) -> String {
+        self.object_key(&format!(
+            "aggregated_proofs/{}_{}.json",
+            first_block, last_block
+        ))
+    }
+
+    fn object_key(&self, name: &str) -> String {
+        if self.key_prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", self.key_prefix, name)
+        }
+    }
+
+    /// Uploads the artifact to the object storage under the given key. Returns the artifact
+    /// itself if it should be stored in the database.
+    pub async fn save<T: Serialize>(
+        &self,
+        key: String,
+        artifact: T,
+    ) -> anyhow::Result<StoredArtifact<T>> {
+        let object_store = match &self.object_store {
+            Some(object_store) => object_store,
+            None => return Ok(StoredArtifact::Inline(artifact)),
+        };
+
+        let start = Instant::now();
+        let data = compression::compress_with_level(
+            serde_json::to_vec(&artifact)?,
+            self.compression_level,
+        );
+        metrics::histogram!("object_store.artifact_size", data.len() as f64);
+        object_store.put(&key, data).await?;
+
+        metrics::histogram!("object_store", start.elapsed(), "method" => "put");
+        Ok(StoredArtifact::Object(key))
+    }
+
+    /// Restores the artifact referenced by the database.
+    pub async fn load<T: DeserializeOwned>(
+        &self,
+        artifact: StoredArtifact<T>,
+    ) -> anyhow::Result<T> {
+        let key = match artifact {
+            StoredArtifact::Inline(artifact) => return Ok(artifact),
+            StoredArtifact::Object(key) => key,
+        };
+        let object_store = self.object_store.as_ref().ok_or_else(|| {
+            anyhow::format_err!(
+                "Artifact {} is stored in the object storage, which is not configured",
+                key
+            )
+        })?;
+
+        let start = Instant::now();
+        let data = object_store.get(&key).await?;
+        let artifact = serde_json::from_slice(&compression::decompress(&data)?)?;
+
+        metrics::histogram!("object_store", start.elapsed(), "method" => "get");
+        Ok(artifact)
+    }
+
+    /// Removes the uploaded artifact which ended up not referenced by the database,
+    /// e.g. since storing the reference failed.
+    pub async fn remove(&self, key: &str) -> anyhow::Result<()> {
+        let object_store = match &self.object_store {
+            Some(object_store) => object_store,
+            None => return Ok(()),
+        };
+
+        let start = Instant::now();
+        object_store.delete(key).await?;
+
+        metrics::histogram!("object_store", start.elapsed(), "method" => "delete");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that the artifacts are kept inline if there is no object storage.
+    #[tokio::test]
+    async fn database_artifacts() -> anyhow::Result<()> {
+        let store = ArtifactStore::default();
+        let key = store.witness_key(BlockNumber(1));
+        assert_eq!(key, "witnesses/1.json");
+
+        let artifact = store.save(key.clone(), "witness".to_string()).await?;
+        assert_eq!(artifact, StoredArtifact::Inline("witness".to_string()));
+        assert_eq!(store.load(artifact).await?, "witness");
+
+        let err = store
+            .load::<String>(StoredArtifact::Object(key))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("is not configured"));
+        Ok(())
+    }
+
+    /// Checks that the artifacts are uploaded to the object storage and loaded back.
+    #[tokio::test]
+    async fn object_store_artifacts() -> anyhow::Result<()> {
+        let root = std::env::temp_dir().join(format!("zksync_object_store_{}", std::process::id()));
+        let store = ArtifactStore::new(Arc::new(FileObjectStore::new(&root)), "test");
+
+        let key = store.aggregated_proof_key(BlockNumber(1), BlockNumber(4));
+        assert_eq!(key, "test/aggregated_proofs/1_4.json");
+        let artifact = store.save(key.clone(), vec![1u64, 2, 3]).await?;
+        assert_eq!(artifact, StoredArtifact::Object(key.clone()));
+        assert_eq!(store.load(artifact.clone()).await?, vec![1u64, 2, 3]);
+
+        // Inline artifacts stored before the object storage was configured are still available.
+        assert_eq!(store.load(StoredArtifact::Inline(5u64)).await?, 5);
+        assert!(store
+            .load::<u64>(StoredArtifact::Object(store.proof_key(BlockNumber(1))))
+            .await
+            .is_err());
+
+        // Removed artifacts can't be loaded anymore, removing them again is fine.
+        store.remove(&key).await?;
+        assert!(store.load(artifact).await.is_err());
+        store.remove(&key).await?;
+
+        tokio::fs::remove_dir_all(root).await?;
+        Ok(())
+    }
+
+    /// Checks that the compressed objects are loaded back, as well as the uncompressed objects
+    /// uploaded before the compression was enabled.
+    #[tokio::test]
+    async fn compressed_artifacts() -> anyhow::Result<()> {
+        let root = std::env::temp_dir().join(format!(
+            "zksync_object_store_compressed_{}",
+            std::process::id()
+        ));
+        let object_store = Arc::new(FileObjectStore::new(&root));
+        let plain_store = ArtifactStore::new(object_store.clone(), "test");
+        let store =
+            ArtifactStore::new(object_store.clone(), "test").with_compression_level(Some(3));
+
+        let proof = vec![42u64; 1000];
+        let key = store.proof_key(BlockNumber(1));
+        let artifact = store.save(key.clone(), proof.clone()).await?;
+        let data = object_store.get(&key).await?;
+        assert!(compression::is_compressed(&data));
+        assert_eq!(store.load(artifact.clone()).await?, proof);
+        assert_eq!(plain_store.load(artifact).await?, proof);
+
+        let plain_key = store.proof_key(BlockNumber(2));
+        let plain_artifact = plain_store.save(plain_key, proof.clone()).await?;
+        assert_eq!(store.load(plain_artifact).await?, proof);
+
+        tokio::fs::remove_dir_all(root).await?;
+        Ok(())
+    }
+}
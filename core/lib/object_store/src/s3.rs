@@ -0,0 +1,100 @@
+// External uses
+use rusoto_core::{credential::ChainProvider, HttpClient, Region};
+use rusoto_s3::{DeleteObjectRequest, GetObjectRequest, PutObjectRequest, S3Client, S3};
+use tokio::io::AsyncReadExt;
+// Workspace uses
+use zksync_config::configs::object_store::{ObjectStoreConfig, ObjectStoreMode};
+// Local uses
+use crate::ObjectStore;
+
+/// Endpoint of the S3-compatible API of the Google Cloud Storage.
+const GCS_ENDPOINT: &str = "https://storage.googleapis.com";
+
+/// Object storage in the S3 bucket. Any S3-compatible storage can be used with a custom endpoint,
+/// including the Google Cloud Storage.
+pub struct S3ObjectStore {
+    client: S3Client,
+    bucket: String,
+}
+
+impl std::fmt::Debug for S3ObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("S3ObjectStore")
+            .field("bucket", &self.bucket)
+            .finish()
+    }
+}
+
+impl S3ObjectStore {
+    pub fn new(config: &ObjectStoreConfig) -> anyhow::Result<Self> {
+        let endpoint = match (config.mode, &config.endpoint) {
+            (_, Some(endpoint)) => Some(endpoint.clone()),
+            (ObjectStoreMode::Gcs, None) => Some(GCS_ENDPOINT.to_string()),
+            _ => None,
+        };
+        let region = match endpoint {
+            Some(endpoint) => Region::Custom {
+                name: config.region.clone(),
+                endpoint,
+            },
+            None => config.region.parse()?,
+        };
+        let client = S3Client::new_with(HttpClient::new()?, ChainProvider::new(), region);
+
+        Ok(Self {
+            client,
+            bucket: config.bucket.clone(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for S3ObjectStore {
+    async fn put(&self, key: &str, data: Vec<u8>) -> anyhow::Result<()> {
+        let request = PutObjectRequest {
+            bucket: self.bucket.clone(),
+            key: key.to_string(),
+            body: Some(data.into()),
+            ..Default::default()
+        };
+        self.client
+            .put_object(request)
+            .await
+            .map_err(|err| anyhow::format_err!("Failed to put object {}: {}", key, err))?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>> {
+        let request = GetObjectRequest {
+            bucket: self.bucket.clone(),
+            key: key.to_string(),
+            ..Default::default()
+        };
+        let output = self
+            .client
+            .get_object(request)
+            .await
+            .map_err(|err| anyhow::format_err!("Failed to get object {}: {}", key, err))?;
+        let body = output
+            .body
+            .ok_or_else(|| anyhow::format_err!("Object {} has no body", key))?;
+
+        let mut data = Vec::new();
+        body.into_async_read().read_to_end(&mut data).await?;
+        Ok(data)
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        // S3 doesn't report the missing objects on deletion.
+        let request = DeleteObjectRequest {
+            bucket: self.bucket.clone(),
+            key: key.to_string(),
+            ..Default::default()
+        };
+        self.client
+            .delete_object(request)
+            .await
+            .map_err(|err| anyhow::format_err!("Failed to delete object {}: {}", key, err))?;
+        Ok(())
+    }
+}
@@ -14,6 +14,8 @@ use zksync_types::aggregated_operations::AggregatedActionType::*;
 use zksync_types::block::IncompleteBlock;
 use zksync_types::{ExecutedOperations, TokenId};
 
+pub use zksync_utils::metrics_labels as labels;
+
 const QUERY_INTERVAL: Duration = Duration::from_secs(30);
 
 pub fn run_operation_counter(connection_pool: ConnectionPool) -> JoinHandle<()> {
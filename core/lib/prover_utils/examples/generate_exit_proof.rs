@@ -1,38 +1,12 @@
 //! Generate exit proof for exodus mode given account and token
 //! correct verified state should be present in the db (could be restored using `data-restore` module)
 
-use serde::Serialize;
 use std::time::Instant;
 use structopt::StructOpt;
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
-use zksync_crypto::proof::EncodedSingleProof;
+use zksync_prover_utils::exit_proof::{ExitProofData, StoredBlockInfo};
 use zksync_storage::{ConnectionPool, StorageProcessor};
-use zksync_types::{block::Block, AccountId, Address, BlockNumber, TokenId, TokenLike, H256, NFT};
-use zksync_utils::BigUintSerdeWrapper;
-
-#[derive(Serialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct StoredBlockInfo {
-    block_number: BlockNumber,
-    priority_operations: u64,
-    pending_onchain_operations_hash: H256,
-    timestamp: u64,
-    state_hash: H256,
-    commitment: H256,
-}
-
-impl StoredBlockInfo {
-    pub fn from_block(block: &Block) -> Self {
-        Self {
-            block_number: block.block_number,
-            priority_operations: block.number_of_processed_prior_ops(),
-            pending_onchain_operations_hash: block.get_onchain_operations_block_info().1,
-            timestamp: block.timestamp,
-            state_hash: block.get_eth_encoded_root(),
-            commitment: block.block_commitment,
-        }
-    }
-}
+use zksync_types::{AccountId, Address, TokenLike, H256, NFT};
 
 #[derive(Debug)]
 struct NFTInfo {
@@ -42,22 +16,6 @@ struct NFTInfo {
     content_hash: H256,
 }
 
-#[derive(Serialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct ExitProofData {
-    stored_block_info: StoredBlockInfo,
-    owner: Address,
-    account_id: AccountId,
-    token_id: TokenId,
-    amount: BigUintSerdeWrapper,
-    nft_creator_id: AccountId,
-    nft_creator_address: Address,
-    nft_serial_id: u32,
-    nft_content_hash: H256,
-    proof: EncodedSingleProof,
-    token_address: Address,
-}
-
 #[derive(StructOpt)]
 #[structopt(
     name = "zkSync operator node",
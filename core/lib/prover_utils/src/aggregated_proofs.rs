@@ -1,6 +1,6 @@
 use crate::fs_utils::get_recursive_verification_key_path;
+use crate::key_registry::open_key;
 use crate::{get_universal_setup_monomial_form, PlonkVerificationKey};
-use std::fs::File;
 use std::time::Instant;
 use zksync_crypto::bellman::pairing::{CurveAffine, Engine as EngineTrait};
 use zksync_crypto::bellman::plonk::better_better_cs::{
@@ -114,7 +114,7 @@ pub fn gen_aggregate_proof(
     .expect("failed to create_recursive_circuit_vk_and_setup");
 
     let vk_for_recursive_circuit = VkAggregate::read(
-        File::open(get_recursive_verification_key_path(proofs.len()))
+        open_key(&get_recursive_verification_key_path(proofs.len()))
             .expect("recursive verification key not found"),
     )
     .expect("recursive verification key read fail");
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use zksync_circuit::serialization::ProverData;
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
-use zksync_types::BlockNumber;
+use zksync_types::{prover::ProverWorkClass, BlockNumber};
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ProverInputRequest {
@@ -13,6 +13,9 @@ pub struct ProverInputRequest {
 pub struct ProverInputRequestAuxData {
     pub prefer_aggregated_proof: Option<bool>,
     pub preferred_block_size: Option<usize>,
+    /// Work classes of the jobs the prover is able to handle, any job can be assigned if empty.
+    #[serde(default)]
+    pub work_classes: Vec<ProverWorkClass>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -58,3 +61,12 @@ pub struct WorkingOn {
 pub struct ProverStopped {
     pub prover_name: String,
 }
+
+/// Report of the prover that failed to prove the job, so the job is returned to the queue
+/// without waiting for it to become stale.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct JobFailed {
+    pub prover_name: String,
+    pub job_id: i32,
+    pub error: String,
+}
@@ -3,12 +3,98 @@
 use crate::gen_verified_proof_for_exit_circuit;
 use anyhow::format_err;
 use num::BigUint;
+use serde::{Deserialize, Serialize};
 use std::time::Instant;
 use zksync_circuit::exit_circuit::create_exit_circuit_with_public_input;
 use zksync_crypto::circuit::account::CircuitAccount;
 use zksync_crypto::circuit::CircuitAccountTree;
 use zksync_crypto::proof::EncodedSingleProof;
-use zksync_types::{AccountId, AccountMap, Address, TokenId, H256};
+use zksync_types::{block::Block, AccountId, AccountMap, Address, BlockNumber, TokenId, H256};
+use zksync_utils::BigUintSerdeWrapper;
+
+/// Block the exit proof is created for, in the format of the `StoredBlockInfo`
+/// structure of the zkSync contract.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredBlockInfo {
+    pub block_number: BlockNumber,
+    pub priority_operations: u64,
+    pub pending_onchain_operations_hash: H256,
+    pub timestamp: u64,
+    pub state_hash: H256,
+    pub commitment: H256,
+}
+
+impl StoredBlockInfo {
+    pub fn from_block(block: &Block) -> Self {
+        Self {
+            block_number: block.block_number,
+            priority_operations: block.number_of_processed_prior_ops(),
+            pending_onchain_operations_hash: block.get_onchain_operations_block_info().1,
+            timestamp: block.timestamp,
+            state_hash: block.get_eth_encoded_root(),
+            commitment: block.block_commitment,
+        }
+    }
+}
+
+/// Input data of the exit transaction on the zkSync contract.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExitProofData {
+    pub stored_block_info: StoredBlockInfo,
+    pub owner: Address,
+    pub account_id: AccountId,
+    pub token_id: TokenId,
+    pub amount: BigUintSerdeWrapper,
+    pub nft_creator_id: AccountId,
+    pub nft_creator_address: Address,
+    pub nft_serial_id: u32,
+    pub nft_content_hash: H256,
+    pub proof: EncodedSingleProof,
+    pub token_address: Address,
+}
+
+/// Creates the circuit account tree of the state the exit proofs are created for.
+pub fn build_exit_account_tree(accounts: &AccountMap) -> CircuitAccountTree {
+    let mut circuit_account_tree =
+        CircuitAccountTree::new(zksync_crypto::params::account_tree_depth());
+    for (id, account) in accounts {
+        circuit_account_tree.insert(**id, CircuitAccount::from(account.clone()));
+    }
+    circuit_account_tree
+}
+
+/// Creates the exit proof using the already built account tree, so the tree can be reused for
+/// the proofs of the different accounts and tokens. The state of the tree is not changed.
+pub fn create_exit_proof_with_tree(
+    circuit_account_tree: &mut CircuitAccountTree,
+    account_id: AccountId,
+    token_id: TokenId,
+    nft_creator_id: AccountId,
+    nft_serial_id: u32,
+    nft_content_hash: H256,
+) -> Result<EncodedSingleProof, anyhow::Error> {
+    let timer = Instant::now();
+    let zksync_exit_circuit = create_exit_circuit_with_public_input(
+        circuit_account_tree,
+        account_id,
+        token_id,
+        nft_creator_id,
+        nft_serial_id,
+        nft_content_hash,
+    );
+    let commitment = zksync_exit_circuit
+        .pub_data_commitment
+        .expect("Witness should contract commitment");
+    vlog::info!("Proof commitment: {:?}", commitment);
+
+    let proof = gen_verified_proof_for_exit_circuit(zksync_exit_circuit)
+        .map_err(|e| format_err!("Failed to generate proof: {}", e))?;
+
+    vlog::info!("Exit proof created: {} s", timer.elapsed().as_secs());
+    Ok(proof.serialize_single_proof())
+}
 
 fn create_exit_proof(
     accounts: AccountMap,
@@ -19,19 +105,10 @@ fn create_exit_proof(
     nft_serial_id: u32,
     nft_content_hash: H256,
 ) -> Result<(EncodedSingleProof, BigUint), anyhow::Error> {
-    let timer = Instant::now();
-    let mut circuit_account_tree =
-        CircuitAccountTree::new(zksync_crypto::params::account_tree_depth());
-
-    let mut target_account = None;
-    for (id, account) in accounts {
-        if id == account_id {
-            target_account = Some(account.clone());
-        }
-        circuit_account_tree.insert(*id, CircuitAccount::from(account));
-    }
+    let mut circuit_account_tree = build_exit_account_tree(&accounts);
 
-    let balance = target_account
+    let balance = accounts
+        .get(&account_id)
         .map(|acc| acc.get_balance(token_id))
         .ok_or_else(|| {
             format_err!(
@@ -41,24 +118,15 @@ fn create_exit_proof(
             )
         })?;
 
-    let zksync_exit_circuit = create_exit_circuit_with_public_input(
+    let proof = create_exit_proof_with_tree(
         &mut circuit_account_tree,
         account_id,
         token_id,
         nft_creator_id,
         nft_serial_id,
         nft_content_hash,
-    );
-    let commitment = zksync_exit_circuit
-        .pub_data_commitment
-        .expect("Witness should contract commitment");
-    vlog::info!("Proof commitment: {:?}", commitment);
-
-    let proof = gen_verified_proof_for_exit_circuit(zksync_exit_circuit)
-        .map_err(|e| format_err!("Failed to generate proof: {}", e))?;
-
-    vlog::info!("Exit proof created: {} s", timer.elapsed().as_secs());
-    Ok((proof.serialize_single_proof(), balance))
+    )?;
+    Ok((proof, balance))
 }
 
 pub fn create_exit_proof_fungible(
@@ -1,4 +1,5 @@
 use super::{SETUP_MAX_POW2, SETUP_MIN_POW2};
+use crate::key_registry::open_key;
 use anyhow::format_err;
 use std::fs::File;
 use std::io::{copy, BufReader, Read};
@@ -123,6 +124,6 @@ pub fn get_precomputed_proofs_path() -> PathBuf {
 
 pub fn load_precomputed_proofs() -> anyhow::Result<PrecomputedSampleProofs> {
     let path = get_precomputed_proofs_path();
-    let file = File::open(path)?;
+    let file = open_key(&path)?;
     Ok(serde_json::from_reader(file)?)
 }
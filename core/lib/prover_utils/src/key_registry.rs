@@ -0,0 +1,255 @@
+//! Registry of the verification keys and other circuit artifacts.
+//!
+//! The registry is a JSON file in the keys directory which maps the file name of every key
+//! to the SHA-256 hash of its content. It is created by the key generator along with the keys.
+//! Keys are published in the network storage under their hashes, so the provers only need the
+//! registry to download the keys they use: every key is verified against its hash before use
+//! and is cached in the keys directory afterwards.
+//!
+//! If there is no registry in the keys directory, keys are read from the disk as is.
+
+// Built-in deps
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+// External deps
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+// Local deps
+use crate::{fs_utils::get_keys_root_dir, network_utils};
+
+const REGISTRY_FILE_NAME: &str = "registry.json";
+
+lazy_static! {
+    /// Whether the key was already verified by this process, so it's not hashed on every read.
+    /// Every key has its own lock, so the keys are verified and downloaded independently.
+    static ref VERIFIED_KEYS: Mutex<HashMap<PathBuf, Arc<Mutex<bool>>>> = Mutex::new(HashMap::new());
+}
+
+/// Mapping of the key file names to the hashes of their content.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyRegistry {
+    pub keys: BTreeMap<String, String>,
+}
+
+impl KeyRegistry {
+    pub fn registry_path(keys_dir: &Path) -> PathBuf {
+        keys_dir.join(REGISTRY_FILE_NAME)
+    }
+
+    /// Creates the registry of all the files in the keys directory.
+    pub fn compute(keys_dir: &Path) -> anyhow::Result<Self> {
+        let mut keys = BTreeMap::new();
+        for entry in fs::read_dir(keys_dir)? {
+            let path = entry?.path();
+            let file_name = match path.file_name().and_then(|name| name.to_str()) {
+                Some(file_name) => file_name.to_owned(),
+                None => continue,
+            };
+            if path.is_file() && file_name != REGISTRY_FILE_NAME {
+                keys.insert(file_name, content_hash(&path)?);
+            }
+        }
+        Ok(Self { keys })
+    }
+
+    /// Loads the registry from the keys directory, returns `None` if there is no registry.
+    pub fn load(keys_dir: &Path) -> anyhow::Result<Option<Self>> {
+        let path = Self::registry_path(keys_dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let registry = serde_json::from_reader(File::open(path)?)?;
+        Ok(Some(registry))
+    }
+
+    pub fn save(&self, keys_dir: &Path) -> anyhow::Result<()> {
+        let file = File::create(Self::registry_path(keys_dir))?;
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
+    /// Copies the keys into the directory under their hashes, so the directory can be
+    /// published as the network storage of the keys.
+    pub fn export(&self, keys_dir: &Path, export_dir: &Path) -> anyhow::Result<()> {
+        fs::create_dir_all(export_dir)?;
+        for (file_name, hash) in &self.keys {
+            fs::copy(keys_dir.join(file_name), export_dir.join(hash))?;
+        }
+        Ok(())
+    }
+
+    /// Makes sure that the key at the given path matches the registry, downloading the key
+    /// if it is missing or corrupted.
+    pub fn ensure_key(&self, path: &Path) -> anyhow::Result<()> {
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow::format_err!("Invalid key path: {}", path.display()))?;
+        let hash = self
+            .keys
+            .get(file_name)
+            .ok_or_else(|| anyhow::format_err!("Key {} is not in the registry", file_name))?;
+
+        if path.exists() {
+            let actual_hash = content_hash(path)?;
+            if &actual_hash == hash {
+                return Ok(());
+            }
+            vlog::warn!(
+                "Key {} is corrupted: expected hash {}, actual {}",
+                file_name,
+                hash,
+                actual_hash
+            );
+        }
+
+        let content = network_utils::download_key(hash)?;
+        let actual_hash = hex::encode(Sha256::digest(&content));
+        anyhow::ensure!(
+            &actual_hash == hash,
+            "Downloaded key {} has hash {}, expected {}",
+            file_name,
+            actual_hash,
+            hash
+        );
+        // The key is written under the temporary name first, so the partially written
+        // file is never taken for the key.
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp_path = path.with_extension("download");
+        fs::write(&tmp_path, content)?;
+        fs::rename(tmp_path, path)?;
+        vlog::info!("Key {} is downloaded to {}", file_name, path.display());
+        Ok(())
+    }
+}
+
+/// Opens the key file, verifying it against the registry if there is one in the keys directory.
+pub fn open_key(path: &Path) -> anyhow::Result<File> {
+    open_key_with_registry(&get_keys_root_dir(), path)
+}
+
+fn key_verification_lock(path: &Path) -> Arc<Mutex<bool>> {
+    VERIFIED_KEYS
+        .lock()
+        .unwrap()
+        .entry(path.to_owned())
+        .or_default()
+        .clone()
+}
+
+fn open_key_with_registry(keys_dir: &Path, path: &Path) -> anyhow::Result<File> {
+    // Only the lock of this key is held during the download, so the other keys can be opened
+    // meanwhile, while the concurrent readers of the same key wait for it to be downloaded once.
+    let verification_lock = key_verification_lock(path);
+    let mut verified = verification_lock.lock().unwrap();
+    if !*verified {
+        if let Some(registry) = KeyRegistry::load(keys_dir)? {
+            registry.ensure_key(path)?;
+        }
+        *verified = true;
+    }
+    drop(verified);
+
+    File::open(path)
+        .map_err(|e| anyhow::format_err!("Failed to open key {}: {}", path.display(), e))
+}
+
+/// Returns the SHA-256 hash of the file content in the hex form.
+pub fn content_hash(path: &Path) -> anyhow::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 16];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.input(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.result()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that the registry is created for the keys directory, exported and verified.
+    #[test]
+    fn key_registry() {
+        let keys_dir = std::env::temp_dir().join(format!("key_registry_{}", std::process::id()));
+        let export_dir = keys_dir.join("export");
+        fs::create_dir_all(&keys_dir).unwrap();
+        fs::write(keys_dir.join("verification_block_6.key"), b"block key").unwrap();
+        fs::write(keys_dir.join("verification_exit.key"), b"exit key").unwrap();
+
+        let registry = KeyRegistry::compute(&keys_dir).unwrap();
+        assert_eq!(registry.keys.len(), 2);
+        assert_eq!(
+            registry.keys["verification_exit.key"],
+            hex::encode(Sha256::digest(b"exit key"))
+        );
+
+        registry.save(&keys_dir).unwrap();
+        assert_eq!(
+            KeyRegistry::load(&keys_dir).unwrap(),
+            Some(registry.clone())
+        );
+        // The registry itself is not a key.
+        assert_eq!(KeyRegistry::compute(&keys_dir).unwrap(), registry);
+
+        registry.export(&keys_dir, &export_dir).unwrap();
+        let exported_key = export_dir.join(&registry.keys["verification_block_6.key"]);
+        assert_eq!(fs::read(exported_key).unwrap(), b"block key");
+
+        registry
+            .ensure_key(&keys_dir.join("verification_exit.key"))
+            .unwrap();
+        registry
+            .ensure_key(&keys_dir.join("recursive_1.key"))
+            .unwrap_err();
+
+        fs::remove_dir_all(keys_dir).unwrap();
+    }
+
+    /// Checks that the key can be opened while another key is being downloaded.
+    #[test]
+    fn open_key_during_download() {
+        let keys_dir =
+            std::env::temp_dir().join(format!("key_registry_open_{}", std::process::id()));
+        fs::create_dir_all(&keys_dir).unwrap();
+        let block_key = keys_dir.join("verification_block_6.key");
+        let exit_key = keys_dir.join("verification_exit.key");
+        fs::write(&exit_key, b"exit key").unwrap();
+        KeyRegistry::compute(&keys_dir)
+            .unwrap()
+            .save(&keys_dir)
+            .unwrap();
+
+        // The download of the block key holds its lock.
+        let block_key_lock = key_verification_lock(&block_key);
+        let _downloading = block_key_lock.lock().unwrap();
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let thread_keys_dir = keys_dir.clone();
+        std::thread::spawn(move || {
+            let mut content = Vec::new();
+            open_key_with_registry(&thread_keys_dir, &exit_key)
+                .unwrap()
+                .read_to_end(&mut content)
+                .unwrap();
+            sender.send(content).unwrap();
+        });
+        let content = receiver
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .expect("Key wasn't opened during the download of another key");
+        assert_eq!(content, b"exit key");
+
+        fs::remove_dir_all(keys_dir).unwrap();
+    }
+}
@@ -1,6 +1,5 @@
 use lazy_static::lazy_static;
 use std::collections::HashMap;
-use std::fs::File;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use zksync_crypto::bellman::kate_commitment::{Crs, CrsForMonomialForm};
@@ -24,12 +23,17 @@ use zksync_crypto::recursive_aggregation_circuit::circuit::create_vks_tree;
 use zksync_crypto::{Engine, Fr};
 
 use crate::fs_utils::{get_block_verification_key_path, get_exodus_verification_key_path};
+use crate::key_registry::open_key;
 
 pub mod aggregated_proofs;
 pub mod api;
 pub mod exit_proof;
 pub mod fs_utils;
+pub mod key_registry;
 pub mod network_utils;
+pub mod proof_verification;
+pub mod prover_tokens;
+pub mod stream;
 
 pub const SETUP_MIN_POW2: u32 = 20;
 pub const SETUP_MAX_POW2: u32 = 26;
@@ -41,13 +45,13 @@ impl PlonkVerificationKey {
         block_chunks: usize,
     ) -> Result<Self, anyhow::Error> {
         let verification_key =
-            VerificationKey::read(File::open(get_block_verification_key_path(block_chunks))?)?;
+            VerificationKey::read(open_key(&get_block_verification_key_path(block_chunks))?)?;
         Ok(Self(verification_key))
     }
 
     pub fn read_verification_key_for_exit_circuit() -> Result<Self, anyhow::Error> {
         let verification_key =
-            VerificationKey::read(File::open(get_exodus_verification_key_path())?)?;
+            VerificationKey::read(open_key(&get_exodus_verification_key_path())?)?;
         Ok(Self(verification_key))
     }
 
@@ -158,7 +162,7 @@ impl Drop for SetupForStepByStepProver {
 pub fn gen_verified_proof_for_exit_circuit<C: Circuit<Engine> + Clone>(
     circuit: C,
 ) -> Result<SingleProof, anyhow::Error> {
-    let vk = VerificationKey::read(File::open(get_exodus_verification_key_path())?)?;
+    let vk = VerificationKey::read(open_key(&get_exodus_verification_key_path())?)?;
 
     vlog::info!("Proof for circuit started");
 
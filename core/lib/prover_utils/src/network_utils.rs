@@ -46,6 +46,65 @@ fn try_to_download_setup(power_of_two: u32) -> Result<Response, backoff::Error<a
     reqwest::blocking::get(&setup_dl_path).map_err(|e| backoff::Error::Transient(e.into()))
 }
 
+/// Downloads the key with the given content hash from the first network location it's available at.
+/// Locations are configured by the comma-separated `MISC_PROVER_KEYS_NETWORK_URLS` variable.
+pub fn download_key(hash: &str) -> Result<Vec<u8>, anyhow::Error> {
+    let urls = std::env::var("MISC_PROVER_KEYS_NETWORK_URLS").unwrap_or_default();
+    let urls: Vec<_> = urls
+        .split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty() && *url != "-")
+        .collect();
+    anyhow::ensure!(
+        !urls.is_empty(),
+        "Key {} is not available locally and no network locations of the keys are configured",
+        hash
+    );
+
+    let mut retry_op = move || try_to_download_key(&urls, hash);
+
+    retry_op
+        .retry_notify(&mut get_backoff(), |err, next_after: Duration| {
+            let duration_secs = next_after.as_millis() as f32 / 1000.0f32;
+
+            vlog::warn!(
+                "Failed to download key err: <{}>, retrying after: {:.1}s",
+                err,
+                duration_secs,
+            )
+        })
+        .map_err(|e| {
+            format_err!(
+                "Can't download key, max elapsed time of the backoff reached: {}",
+                e
+            )
+        })
+}
+
+fn try_to_download_key(
+    urls: &[&str],
+    hash: &str,
+) -> Result<Vec<u8>, backoff::Error<anyhow::Error>> {
+    let mut last_error = None;
+    for url in urls {
+        let key_dl_path = format!("{}/{}", url.trim_end_matches('/'), hash);
+        vlog::info!("Downloading key from {}", &key_dl_path);
+
+        let response = reqwest::blocking::get(&key_dl_path)
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.bytes());
+        match response {
+            Ok(content) => return Ok(content.to_vec()),
+            Err(err) => {
+                vlog::warn!("Failed to download key from {}: {}", key_dl_path, err);
+                last_error = Some(err);
+            }
+        }
+    }
+    // `urls` are checked to be non-empty, so there is an error.
+    Err(backoff::Error::Transient(last_error.unwrap().into()))
+}
+
 fn get_backoff() -> backoff::ExponentialBackoff {
     backoff::ExponentialBackoff {
         current_interval: Duration::from_secs(5),
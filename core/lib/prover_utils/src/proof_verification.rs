@@ -0,0 +1,56 @@
+//! Verification of the proofs received from the provers, so the invalid proofs are discovered
+//! before they are sent to the L1.
+
+use crate::fs_utils::get_recursive_verification_key_path;
+use crate::key_registry::open_key;
+use crate::PlonkVerificationKey;
+use zksync_circuit::witness::utils::fr_from_bytes;
+use zksync_crypto::bellman::plonk::better_better_cs::{
+    setup::VerificationKey as VkAggregate, verifier::verify as verify_aggregated,
+};
+use zksync_crypto::bellman::plonk::better_cs::verifier::verify;
+use zksync_crypto::ff::ScalarEngine;
+use zksync_crypto::franklin_crypto::bellman::plonk::commitments::transcript::keccak_transcript::RollingKeccakTranscript;
+use zksync_crypto::franklin_crypto::plonk::circuit::bigint::field::RnsParameters;
+use zksync_crypto::franklin_crypto::rescue::bn256::Bn256RescueParams;
+use zksync_crypto::franklin_crypto::rescue::rescue_transcript::RescueTranscriptForRNS;
+use zksync_crypto::pairing::Engine as EngineTrait;
+use zksync_crypto::proof::{AggregatedProof, SingleProof};
+use zksync_crypto::{Engine, Fr};
+use zksync_types::block::Block;
+
+/// Returns the public input of the block proof, i.e. the block commitment truncated to fit the field.
+pub fn block_proof_input(block: &Block) -> Fr {
+    let mut block_commitment = block.block_commitment.as_bytes().to_vec();
+    block_commitment[0] &= 0xffu8 >> 3;
+    fr_from_bytes(block_commitment)
+}
+
+/// Verifies the single block proof against the verification key of the given block size.
+pub fn verify_block_proof(proof: &SingleProof, block_size: usize) -> anyhow::Result<bool> {
+    let vk = PlonkVerificationKey::read_verification_key_for_main_circuit(block_size)?;
+    let rns_params =
+        RnsParameters::<Engine, <Engine as EngineTrait>::Fq>::new_for_field(68, 110, 4);
+    let rescue_params = Bn256RescueParams::new_checked_2_into_1();
+
+    let valid = verify::<_, _, RescueTranscriptForRNS<Engine>>(
+        &proof.0,
+        &vk.0,
+        Some((&rescue_params, &rns_params)),
+    )?;
+    Ok(valid)
+}
+
+/// Verifies the aggregated proof against the verification key of the recursive circuit.
+pub fn verify_aggregated_proof(proof: &AggregatedProof) -> anyhow::Result<bool> {
+    let vk = VkAggregate::read(open_key(&get_recursive_verification_key_path(
+        proof.individual_vk_inputs.len(),
+    ))?)?;
+
+    let valid = verify_aggregated::<_, _, RollingKeccakTranscript<<Engine as ScalarEngine>::Fr>>(
+        &vk,
+        &proof.proof,
+        None,
+    )?;
+    Ok(valid)
+}
@@ -0,0 +1,33 @@
+//! Authentication tokens of the prover machines.
+//!
+//! Every prover machine gets its own random token instead of the shared secret of the prover API,
+//! so a misbehaving machine can be isolated by revoking its token. The server only stores
+//! the hashes of the tokens.
+
+use sha2::{Digest, Sha256};
+use zksync_crypto::rand::{thread_rng, Rng};
+
+/// Generates a new random token in the hex form.
+pub fn generate_prover_token() -> String {
+    let token: [u8; 32] = thread_rng().gen();
+    hex::encode(token)
+}
+
+/// Returns the hash of the token the server stores, in the hex form.
+pub fn prover_token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prover_tokens() {
+        let token = generate_prover_token();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, generate_prover_token());
+        assert_eq!(prover_token_hash(&token), prover_token_hash(&token));
+        assert_ne!(prover_token_hash(&token), token);
+    }
+}
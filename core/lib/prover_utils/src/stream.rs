@@ -0,0 +1,68 @@
+//! Streaming protocol between the prover and the prover server.
+//!
+//! Instead of polling the server for the jobs, the prover keeps a single bidirectional stream open:
+//! the server pushes the jobs to the prover as soon as they are available, and the prover sends
+//! the heartbeats and the proofs over the same stream. Once the stream is closed, the jobs of
+//! the prover are returned to the queue right away.
+//!
+//! The stream is authorized with the same token as the HTTP API, passed in the `authorization`
+//! metadata of the request opening the stream.
+//!
+//! Jobs and proofs may be large, so each side can compress the payloads it sends with zstd.
+//! Compressed payloads are recognized on receipt, so the compression doesn't have to be
+//! configured the same way on the prover and the server.
+
+// External deps
+use serde::{de::DeserializeOwned, Serialize};
+use tonic::Status;
+// Workspace deps
+use zksync_utils::compression;
+
+#[allow(clippy::all)]
+pub mod proto {
+    tonic::include_proto!("prover");
+}
+
+pub use self::proto::{
+    prover_message,
+    prover_stream_client::ProverStreamClient,
+    prover_stream_server::{ProverStream, ProverStreamServer},
+    server_message, Heartbeat, Job, JobFailed, Proof, ProofStored, ProverMessage, Register,
+    ServerMessage,
+};
+
+/// Metadata key of the authorization token.
+pub const AUTHORIZATION_KEY: &str = "authorization";
+
+/// Encodes the API type passed over the stream, compressing it if the level is set.
+pub fn encode_payload<T: Serialize>(value: &T, compression_level: Option<i32>) -> Vec<u8> {
+    let payload = serde_json::to_vec(value).expect("Failed to serialize the stream payload");
+    compression::compress_with_level(payload, compression_level)
+}
+
+/// Decodes the API type passed over the stream, either compressed or not.
+pub fn decode_payload<T: DeserializeOwned>(payload: &[u8]) -> Result<T, Status> {
+    let payload = compression::decompress(payload)
+        .map_err(|e| Status::invalid_argument(format!("malformed compressed payload: {}", e)))?;
+    serde_json::from_slice(&payload)
+        .map_err(|e| Status::invalid_argument(format!("malformed payload: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_compression() {
+        let value = vec!["proof".to_string(); 100];
+
+        let plain = encode_payload(&value, None);
+        let compressed = encode_payload(&value, Some(3));
+        assert!(compressed.len() < plain.len());
+        assert_eq!(decode_payload::<Vec<String>>(&plain).unwrap(), value);
+        assert_eq!(decode_payload::<Vec<String>>(&compressed).unwrap(), value);
+
+        let err = decode_payload::<Vec<String>>(&compressed[..8]).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+}
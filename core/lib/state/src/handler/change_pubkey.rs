@@ -3,7 +3,7 @@ use zksync_crypto::params;
 use zksync_types::{
     operations::{ChangePubKeyOp, ZkSyncOp},
     tx::ChangePubKey,
-    AccountUpdate, AccountUpdates, Nonce,
+    AccountUpdate, AccountUpdates, FeeAmount, Nonce,
 };
 
 use crate::{
@@ -92,11 +92,11 @@ impl TxHandler<ChangePubKey> for ZkSyncState {
         account.pub_key_hash = op.tx.new_pk_hash;
 
         // Subract fees.
-        invariant!(
-            old_balance >= op.tx.fee,
-            ChangePubKeyOpError::InsufficientBalance
-        );
-        account.sub_balance(op.tx.fee_token, &op.tx.fee);
+        let fee = FeeAmount::from_biguint(op.tx.fee_token, &op.tx.fee)
+            .map_err(|_| ChangePubKeyOpError::FeeTooBig)?;
+        account
+            .checked_sub_balance(op.tx.fee_token, fee.into_amount())
+            .map_err(|_| ChangePubKeyOpError::InsufficientBalance)?;
 
         let new_pub_key_hash = account.pub_key_hash;
         let new_nonce = account.nonce;
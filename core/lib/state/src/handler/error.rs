@@ -20,6 +20,8 @@ pub enum ChangePubKeyOpError {
     NonceMismatch,
     #[error("Not enough balance")]
     InsufficientBalance,
+    #[error("ChangePubKey fee exceeds the protocol limit")]
+    FeeTooBig,
 }
 
 #[derive(Clone, Debug, Error, PartialEq)]
@@ -62,6 +64,8 @@ pub enum ForcedExitOpError {
     NonceMismatch,
     #[error("Initiator account: Not enough balance to cover fees")]
     InitiatorInsufficientBalance,
+    #[error("ForcedExit fee exceeds the protocol limit")]
+    FeeTooBig,
     #[error("Target account: Target account balance is not equal to the withdrawal amount")]
     TargetAccountBalanceMismatch,
 }
@@ -90,6 +94,10 @@ pub enum TransferOpError {
     NonceMismatch,
     #[error("Not enough balance")]
     InsufficientBalance,
+    #[error("Transfer amount or fee exceeds the protocol limit")]
+    AmountTooBig,
+    #[error("Recipient balance exceeds the protocol limit")]
+    BalanceOverflow,
     #[error("Bug: transfer to self should not be called")]
     CannotTransferToSelf,
 }
@@ -114,6 +122,8 @@ pub enum MintNFTOpError {
     NonceMismatch,
     #[error("Not enough balance")]
     InsufficientBalance,
+    #[error("MintNFT fee exceeds the protocol limit")]
+    FeeTooBig,
     #[error("NFT token is already in account")]
     TokenIsAlreadyInAccount,
 }
@@ -138,6 +148,8 @@ pub enum WithdrawNFTOpError {
     NonceMismatch,
     #[error("Not enough balance")]
     InsufficientBalance,
+    #[error("WithdrawNFT fee exceeds the protocol limit")]
+    FeeTooBig,
     #[error("Not enough nft balance")]
     InsufficientNFTBalance,
     #[error("NFT was not found")]
@@ -162,6 +174,8 @@ pub enum WithdrawOpError {
     NonceMismatch,
     #[error("Not enough balance")]
     InsufficientBalance,
+    #[error("Withdraw amount or fee exceeds the protocol limit")]
+    AmountTooBig,
 }
 #[derive(Clone, Debug, Error, PartialEq)]
 pub enum SwapOpError {
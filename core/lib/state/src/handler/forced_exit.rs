@@ -1,7 +1,8 @@
 use std::time::Instant;
 use zksync_crypto::params;
 use zksync_types::{
-    AccountUpdate, AccountUpdates, Address, ForcedExit, ForcedExitOp, PubKeyHash, ZkSyncOp,
+    AccountUpdate, AccountUpdates, Address, FeeAmount, ForcedExit, ForcedExitOp, PubKeyHash,
+    ZkSyncOp,
 };
 use zksync_utils::BigUintSerdeWrapper;
 
@@ -120,10 +121,14 @@ impl TxHandler<ForcedExit> for ZkSyncState {
             op.tx.nonce == initiator_old_nonce,
             ForcedExitOpError::NonceMismatch
         );
-        invariant!(
-            initiator_old_balance >= op.tx.fee,
-            ForcedExitOpError::InitiatorInsufficientBalance
-        );
+        let fee = FeeAmount::from_biguint(op.tx.token, &op.tx.fee)
+            .map_err(|_| ForcedExitOpError::FeeTooBig)?;
+
+        // Take fees from the initiator account (and update initiator account nonce).
+        initiator_account
+            .checked_sub_balance(op.tx.token, fee.into_amount())
+            .map_err(|_| ForcedExitOpError::InitiatorInsufficientBalance)?;
+        *initiator_account.nonce += 1;
 
         // Check that target account has required amount of tokens to withdraw.
         // (normally, it should, since we're declaring this amount ourselves, but
@@ -134,10 +139,6 @@ impl TxHandler<ForcedExit> for ZkSyncState {
             ForcedExitOpError::TargetAccountBalanceMismatch
         );
 
-        // Take fees from the initiator account (and update initiator account nonce).
-        initiator_account.sub_balance(op.tx.token, &op.tx.fee);
-        *initiator_account.nonce += 1;
-
         // Withdraw funds from the target account (note that target account nonce is not affected).
         target_account.sub_balance(op.tx.token, &amount);
 
@@ -5,7 +5,8 @@ use zksync_types::{
     operations::MintNFTOp,
     tokens::NFT,
     tx::{calculate_token_address, calculate_token_data, calculate_token_hash},
-    Account, AccountUpdate, AccountUpdates, Address, MintNFT, Nonce, PubKeyHash, TokenId, ZkSyncOp,
+    Account, AccountUpdate, AccountUpdates, Address, FeeAmount, MintNFT, Nonce, PubKeyHash,
+    TokenId, ZkSyncOp,
 };
 
 use zksync_crypto::params::{
@@ -86,11 +87,11 @@ impl TxHandler<MintNFT> for ZkSyncState {
         let nonce = creator_account.nonce;
         invariant!(nonce == op.tx.nonce, MintNFTOpError::NonceMismatch);
 
-        invariant!(
-            old_balance >= op.tx.fee,
-            MintNFTOpError::InsufficientBalance
-        );
-        creator_account.sub_balance(op.tx.fee_token, &op.tx.fee);
+        let fee = FeeAmount::from_biguint(op.tx.fee_token, &op.tx.fee)
+            .map_err(|_| MintNFTOpError::FeeTooBig)?;
+        creator_account
+            .checked_sub_balance(op.tx.fee_token, fee.into_amount())
+            .map_err(|_| MintNFTOpError::InsufficientBalance)?;
         let new_balance = creator_account.get_balance(op.tx.fee_token);
         *creator_account.nonce += 1;
         updates.push((
@@ -3,8 +3,8 @@ use std::time::Instant;
 
 use zksync_crypto::params::{self, max_account_id};
 use zksync_types::{
-    Account, AccountUpdate, AccountUpdates, Address, PubKeyHash, Transfer, TransferOp,
-    TransferToNewOp,
+    Account, AccountUpdate, AccountUpdates, Address, FeeAmount, PubKeyHash, TokenAmount, Transfer,
+    TransferOp, TransferToNewOp,
 };
 
 use crate::{
@@ -86,6 +86,15 @@ impl TxHandler<Transfer> for ZkSyncState {
 }
 
 impl ZkSyncState {
+    /// Amount and fee of the transfer, both limited by the protocol.
+    fn transfer_amount_and_fee(tx: &Transfer) -> Result<(TokenAmount, FeeAmount), TransferOpError> {
+        let amount = TokenAmount::from_biguint(tx.token, &tx.amount)
+            .map_err(|_| TransferOpError::AmountTooBig)?;
+        let fee = FeeAmount::from_biguint(tx.token, &tx.fee)
+            .map_err(|_| TransferOpError::AmountTooBig)?;
+        Ok((amount, fee))
+    }
+
     fn apply_transfer_op(
         &mut self,
         op: &TransferOp,
@@ -116,12 +125,13 @@ impl ZkSyncState {
             op.tx.nonce == from_old_nonce,
             TransferOpError::NonceMismatch
         );
-        invariant!(
-            from_old_balance >= &op.tx.amount + &op.tx.fee,
-            TransferOpError::InsufficientBalance
-        );
-
-        from_account.sub_balance(op.tx.token, &(&op.tx.amount + &op.tx.fee));
+        let (amount, fee) = Self::transfer_amount_and_fee(&op.tx)?;
+        let total_amount = amount
+            .checked_add_fee(fee)
+            .map_err(|_| TransferOpError::InsufficientBalance)?;
+        from_account
+            .checked_sub_balance(op.tx.token, total_amount)
+            .map_err(|_| TransferOpError::InsufficientBalance)?;
         *from_account.nonce += 1;
 
         let from_new_balance = from_account.get_balance(op.tx.token);
@@ -130,7 +140,9 @@ impl ZkSyncState {
         let to_old_balance = to_account.get_balance(op.tx.token);
         let to_account_nonce = to_account.nonce;
 
-        to_account.add_balance(op.tx.token, &op.tx.amount);
+        to_account
+            .checked_add_balance(op.tx.token, amount)
+            .map_err(|_| TransferOpError::BalanceOverflow)?;
 
         let to_new_balance = to_account.get_balance(op.tx.token);
 
@@ -183,12 +195,21 @@ impl ZkSyncState {
         let old_nonce = account.nonce;
 
         invariant!(op.tx.nonce == old_nonce, TransferOpError::NonceMismatch);
+        let (amount, fee) = Self::transfer_amount_and_fee(&op.tx)?;
+        let total_amount = amount
+            .checked_add_fee(fee)
+            .map_err(|_| TransferOpError::InsufficientBalance)?;
         invariant!(
-            old_balance >= &op.tx.amount + &op.tx.fee,
+            account
+                .get_balance_amount(op.tx.token)
+                .and_then(|balance| balance.checked_sub(total_amount))
+                .is_ok(),
             TransferOpError::InsufficientBalance
         );
 
-        account.sub_balance(op.tx.token, &op.tx.fee);
+        account
+            .checked_sub_balance(op.tx.token, fee.into_amount())
+            .map_err(|_| TransferOpError::InsufficientBalance)?;
         *account.nonce += 1;
 
         let new_balance = account.get_balance(op.tx.token);
@@ -251,18 +272,22 @@ impl ZkSyncState {
             op.tx.nonce == from_old_nonce,
             TransferOpError::NonceMismatch
         );
-        invariant!(
-            from_old_balance >= &op.tx.amount + &op.tx.fee,
-            TransferOpError::InsufficientBalance
-        );
-        from_account.sub_balance(op.tx.token, &(&op.tx.amount + &op.tx.fee));
+        let (amount, fee) = Self::transfer_amount_and_fee(&op.tx)?;
+        let total_amount = amount
+            .checked_add_fee(fee)
+            .map_err(|_| TransferOpError::InsufficientBalance)?;
+        from_account
+            .checked_sub_balance(op.tx.token, total_amount)
+            .map_err(|_| TransferOpError::InsufficientBalance)?;
         *from_account.nonce += 1;
         let from_new_balance = from_account.get_balance(op.tx.token);
         let from_new_nonce = from_account.nonce;
 
         let to_old_balance = to_account.get_balance(op.tx.token);
         let to_account_nonce = to_account.nonce;
-        to_account.add_balance(op.tx.token, &op.tx.amount);
+        to_account
+            .checked_add_balance(op.tx.token, amount)
+            .map_err(|_| TransferOpError::BalanceOverflow)?;
         let to_new_balance = to_account.get_balance(op.tx.token);
 
         self.insert_account(op.from, from_account);
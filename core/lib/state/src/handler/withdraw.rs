@@ -1,6 +1,9 @@
 use std::time::Instant;
 use zksync_crypto::params::{self, max_account_id};
-use zksync_types::{AccountUpdate, AccountUpdates, PubKeyHash, Withdraw, WithdrawOp, ZkSyncOp};
+use zksync_types::{
+    AccountUpdate, AccountUpdates, FeeAmount, PubKeyHash, TokenAmount, Withdraw, WithdrawOp,
+    ZkSyncOp,
+};
 
 use crate::{
     handler::{error::WithdrawOpError, TxHandler},
@@ -78,12 +81,16 @@ impl TxHandler<Withdraw> for ZkSyncState {
             op.tx.nonce == from_old_nonce,
             WithdrawOpError::NonceMismatch
         );
-        invariant!(
-            from_old_balance >= &op.tx.amount + &op.tx.fee,
-            WithdrawOpError::InsufficientBalance
-        );
-
-        from_account.sub_balance(op.tx.token, &(&op.tx.amount + &op.tx.fee));
+        let amount = TokenAmount::from_biguint(op.tx.token, &op.tx.amount)
+            .map_err(|_| WithdrawOpError::AmountTooBig)?;
+        let fee = FeeAmount::from_biguint(op.tx.token, &op.tx.fee)
+            .map_err(|_| WithdrawOpError::AmountTooBig)?;
+        let total_amount = amount
+            .checked_add_fee(fee)
+            .map_err(|_| WithdrawOpError::InsufficientBalance)?;
+        from_account
+            .checked_sub_balance(op.tx.token, total_amount)
+            .map_err(|_| WithdrawOpError::InsufficientBalance)?;
         *from_account.nonce += 1;
 
         let from_new_balance = from_account.get_balance(op.tx.token);
@@ -120,11 +120,11 @@ impl TxHandler<WithdrawNFT> for ZkSyncState {
 
         let from_old_balance = from_account.get_balance(op.tx.fee_token);
 
-        invariant!(
-            from_old_balance >= op.tx.fee,
-            WithdrawNFTOpError::InsufficientBalance
-        );
-        from_account.sub_balance(op.tx.fee_token, &op.tx.fee);
+        let fee = FeeAmount::from_biguint(op.tx.fee_token, &op.tx.fee)
+            .map_err(|_| WithdrawNFTOpError::FeeTooBig)?;
+        from_account
+            .checked_sub_balance(op.tx.fee_token, fee.into_amount())
+            .map_err(|_| WithdrawNFTOpError::InsufficientBalance)?;
         let from_new_balance = from_account.get_balance(op.tx.fee_token);
         // Pay fee
         updates.push((
@@ -2,8 +2,9 @@ mod collect_fee;
 mod operations;
 mod timestamp;
 
-use crate::state::ZkSyncState;
+use crate::{handler::TxHandler, state::ZkSyncState};
 use num::BigUint;
+use std::fmt;
 use web3::types::H256;
 use zksync_crypto::{
     priv_key_from_fs,
@@ -129,6 +130,32 @@ impl PlasmaTestBuilder {
         );
     }
 
+    /// Applies the operation bypassing `create_op`, whose signature check can't be done for
+    /// the amounts that don't fit into the packed form.
+    pub fn test_op_fail<Tx>(
+        &mut self,
+        op: &<ZkSyncState as TxHandler<Tx>>::Op,
+        expected_error_message: &str,
+    ) where
+        ZkSyncState: TxHandler<Tx>,
+        <ZkSyncState as TxHandler<Tx>>::OpError: fmt::Display,
+    {
+        let state_clone = self.state.clone();
+        let error = <ZkSyncState as TxHandler<Tx>>::apply_op(&mut self.state, op)
+            .expect_err("operation didn't fail");
+
+        assert_eq!(
+            error.to_string().as_str(),
+            expected_error_message,
+            "unexpected error message"
+        );
+        assert_eq!(
+            self.state.root_hash(),
+            state_clone.root_hash(),
+            "state has changed, but it should not"
+        );
+    }
+
     pub fn test_txs_batch_success(
         &mut self,
         txs: &[SignedZkSyncTx],
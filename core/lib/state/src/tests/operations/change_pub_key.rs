@@ -1,6 +1,8 @@
 use crate::tests::{AccountState::*, PlasmaTestBuilder};
+use num::BigUint;
 use zksync_types::{
     account::{AccountUpdate, PubKeyHash},
+    operations::ChangePubKeyOp,
     tx::ChangePubKey,
     AccountId, TokenId,
 };
@@ -105,3 +107,33 @@ fn invalid_account_id() {
         "ChangePubKey account id is incorrect",
     );
 }
+
+/// Check that ChangePubKey fails if the fee doesn't fit into the protocol limit
+#[test]
+fn fee_too_big() {
+    let mut tb = PlasmaTestBuilder::new();
+    let token_id = TokenId(1);
+    let (account_id, account, sk) = tb.add_account(Locked);
+    tb.set_balance(account_id, token_id, u128::MAX);
+    let new_pub_key_hash = PubKeyHash::from_privkey(&sk);
+
+    // The fee can't be packed, so the transaction can't be signed.
+    let change_pub_key = ChangePubKey::new(
+        account_id,
+        account.address,
+        new_pub_key_hash,
+        token_id,
+        BigUint::from(u128::MAX) + 1u32,
+        account.nonce,
+        Default::default(),
+        None,
+        None,
+        None,
+    );
+    let op = ChangePubKeyOp {
+        tx: change_pub_key,
+        account_id,
+    };
+
+    tb.test_op_fail::<ChangePubKey>(&op, "ChangePubKey fee exceeds the protocol limit");
+}
@@ -1,6 +1,8 @@
 use crate::tests::{AccountState::*, PlasmaTestBuilder};
 use num::{BigUint, Zero};
-use zksync_types::{account::AccountUpdate, tx::ForcedExit, AccountId, TokenId};
+use zksync_types::{
+    account::AccountUpdate, operations::ForcedExitOp, tx::ForcedExit, AccountId, TokenId,
+};
 
 /// Check ForcedExit operation
 #[test]
@@ -172,3 +174,37 @@ fn invalid_account_id() {
 
     tb.test_tx_fail(forced_exit.into(), "Initiator account does not exist")
 }
+
+/// Check ForcedExit failure if the fee doesn't fit into the protocol limit
+#[test]
+fn fee_too_big() {
+    let token_id = TokenId(0);
+    let amount = BigUint::from(100u32);
+    let fee = BigUint::from(u128::MAX) + 1u32;
+
+    let mut tb = PlasmaTestBuilder::new();
+
+    let (initiator_account_id, initiator_account, _) = tb.add_account(Unlocked);
+    let (target_account_id, target_account, _) = tb.add_account(Locked);
+
+    tb.set_balance(initiator_account_id, token_id, u128::MAX);
+    tb.set_balance(target_account_id, token_id, amount.clone());
+
+    // The fee can't be packed, so the transaction can't be signed.
+    let forced_exit = ForcedExit::new(
+        initiator_account_id,
+        target_account.address,
+        token_id,
+        fee,
+        initiator_account.nonce,
+        Default::default(),
+        None,
+    );
+    let op = ForcedExitOp {
+        tx: forced_exit,
+        target_account_id,
+        withdraw_amount: Some(amount.into()),
+    };
+
+    tb.test_op_fail::<ForcedExit>(&op, "ForcedExit fee exceeds the protocol limit");
+}
@@ -5,6 +5,7 @@ use zksync_crypto::params::{
     MIN_NFT_TOKEN_ID, NFT_STORAGE_ACCOUNT_ADDRESS, NFT_STORAGE_ACCOUNT_ID, NFT_TOKEN_ID,
 };
 use zksync_types::{
+    operations::MintNFTOp,
     tokens::NFT,
     tx::{calculate_token_address, calculate_token_data, calculate_token_hash},
     AccountUpdate, MintNFT, Nonce, SignedZkSyncTx, TokenId, Transfer, ZkSyncTx, H160,
@@ -469,3 +470,36 @@ fn nonce_mismatched() {
 
     tb.test_tx_fail(mint_nft.into(), "Nonce mismatch")
 }
+
+/// Check MintNFT failure if the fee doesn't fit into the protocol limit
+#[test]
+fn mint_fee_too_big() {
+    let fee_token_id = TokenId(0);
+    let fee = BigUint::from(u128::MAX) + 1u32;
+
+    let mut tb = PlasmaTestBuilder::new();
+
+    let (creator_account_id, creator_account, _) = tb.add_account(Unlocked);
+    tb.set_balance(creator_account_id, fee_token_id, u128::MAX);
+
+    let (to_account_id, to_account, _to_sk) = tb.add_account(Locked);
+
+    // The fee can't be packed, so the transaction can't be signed.
+    let mint_nft = MintNFT::new(
+        creator_account_id,
+        creator_account.address,
+        H256::default(),
+        to_account.address,
+        fee,
+        fee_token_id,
+        creator_account.nonce,
+        None,
+    );
+    let op = MintNFTOp {
+        tx: mint_nft,
+        creator_account_id,
+        recipient_account_id: to_account_id,
+    };
+
+    tb.test_op_fail::<MintNFT>(&op, "MintNFT fee exceeds the protocol limit");
+}
@@ -1,8 +1,14 @@
-use crate::tests::{AccountState::*, PlasmaTestBuilder};
+use crate::{
+    state::TransferOutcome,
+    tests::{AccountState::*, PlasmaTestBuilder},
+};
 use chrono::Utc;
 use num::{BigUint, Zero};
 use web3::types::H160;
-use zksync_types::{AccountId, AccountUpdate, Nonce, SignedZkSyncTx, TokenId, Transfer, ZkSyncTx};
+use zksync_types::{
+    operations::TransferOp, AccountId, AccountUpdate, Nonce, SignedZkSyncTx, TokenId, Transfer,
+    ZkSyncTx,
+};
 
 /// Check Transfer operation to existing account
 #[test]
@@ -398,3 +404,72 @@ fn execute_txs_batch_success_transfers() {
         ],
     );
 }
+
+/// Check Transfer failure if the amount doesn't fit into the protocol limit
+#[test]
+fn amount_too_big() {
+    let token_id = TokenId(0);
+    let amount = BigUint::from(u128::MAX) + 1u32;
+    let fee = BigUint::from(10u32);
+
+    let mut tb = PlasmaTestBuilder::new();
+
+    let (from_account_id, from_account, _from_sk) = tb.add_account(Unlocked);
+    tb.set_balance(from_account_id, token_id, u128::MAX);
+
+    let (to_account_id, to_account, _to_sk) = tb.add_account(Locked);
+
+    // The amount can't be packed, so the transaction can't be signed.
+    let transfer = Transfer::new(
+        from_account_id,
+        from_account.address,
+        to_account.address,
+        token_id,
+        amount,
+        fee,
+        from_account.nonce,
+        Default::default(),
+        None,
+    );
+    let op = TransferOutcome::Transfer(TransferOp {
+        tx: transfer,
+        from: from_account_id,
+        to: to_account_id,
+    });
+
+    tb.test_op_fail::<Transfer>(&op, "Transfer amount or fee exceeds the protocol limit");
+}
+
+/// Check Transfer failure if the recipient balance overflows the protocol limit
+#[test]
+fn recipient_balance_overflow() {
+    let token_id = TokenId(0);
+    let amount = BigUint::from(100u32);
+    let fee = BigUint::from(10u32);
+
+    let mut tb = PlasmaTestBuilder::new();
+
+    let (from_account_id, from_account, from_sk) = tb.add_account(Unlocked);
+    tb.set_balance(from_account_id, token_id, &amount + &fee);
+
+    let (to_account_id, to_account, _to_sk) = tb.add_account(Locked);
+    tb.set_balance(to_account_id, token_id, u128::MAX);
+
+    let transfer = Transfer::new_signed(
+        from_account_id,
+        from_account.address,
+        to_account.address,
+        token_id,
+        amount,
+        fee,
+        from_account.nonce,
+        Default::default(),
+        &from_sk,
+    )
+    .unwrap();
+
+    tb.test_tx_fail(
+        transfer.into(),
+        "Recipient balance exceeds the protocol limit",
+    );
+}
@@ -1,6 +1,8 @@
 use crate::tests::{AccountState::*, PlasmaTestBuilder};
 use num::{BigUint, Zero};
-use zksync_types::{account::AccountUpdate, tx::Withdraw, AccountId, TokenId};
+use zksync_types::{
+    account::AccountUpdate, operations::WithdrawOp, tx::Withdraw, AccountId, TokenId,
+};
 
 /// Check withdraw operation
 #[test]
@@ -125,3 +127,35 @@ fn invalid_account_id() {
 
     tb.test_tx_fail(withdraw.into(), "Withdraw account id is incorrect")
 }
+
+/// Check Withdraw failure if the amount doesn't fit into the protocol limit
+#[test]
+fn amount_too_big() {
+    let token_id = TokenId(0);
+    let amount = BigUint::from(u128::MAX) + 1u32;
+    let fee = BigUint::from(10u32);
+
+    let mut tb = PlasmaTestBuilder::new();
+
+    let (account_id, account, _sk) = tb.add_account(Unlocked);
+    tb.set_balance(account_id, token_id, u128::MAX);
+
+    // The amount can't be packed, so the transaction can't be signed.
+    let withdraw = Withdraw::new(
+        account_id,
+        account.address,
+        account.address,
+        token_id,
+        amount,
+        fee,
+        account.nonce,
+        Default::default(),
+        None,
+    );
+    let op = WithdrawOp {
+        tx: withdraw,
+        account_id,
+    };
+
+    tb.test_op_fail::<Withdraw>(&op, "Withdraw amount or fee exceeds the protocol limit");
+}
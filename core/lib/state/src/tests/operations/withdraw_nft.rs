@@ -2,7 +2,9 @@ use crate::tests::{AccountState::*, PlasmaTestBuilder};
 use num::{BigUint, Zero};
 use web3::types::H256;
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
-use zksync_types::{account::AccountUpdate, tx::WithdrawNFT, AccountId, TokenId};
+use zksync_types::{
+    account::AccountUpdate, operations::WithdrawNFTOp, tx::WithdrawNFT, AccountId, TokenId,
+};
 
 /// Check withdraw nft operation
 #[test]
@@ -202,3 +204,41 @@ fn invalid_account_id() {
 
     tb.test_tx_fail(withdraw.into(), "Withdraw account id is incorrect")
 }
+
+/// Check WithdrawNFT failure if the fee doesn't fit into the protocol limit
+#[test]
+fn fee_too_big() {
+    let fee_token_id = TokenId(0);
+    let fee = BigUint::from(u128::MAX) + 1u32;
+
+    let mut tb = PlasmaTestBuilder::new();
+
+    let (creator_account_id, creator_account, _) = tb.add_account(Unlocked);
+    let (account_id, account, _) = tb.add_account(Unlocked);
+    let content_hash = H256::random();
+    let token_id = TokenId(MIN_NFT_TOKEN_ID);
+    tb.set_balance(account_id, fee_token_id, u128::MAX);
+    tb.mint_nft(token_id, content_hash, account_id, creator_account_id);
+
+    // The fee can't be packed, so the transaction can't be signed.
+    let withdraw = WithdrawNFT::new(
+        account_id,
+        account.address,
+        account.address,
+        token_id,
+        fee_token_id,
+        fee,
+        account.nonce,
+        Default::default(),
+        None,
+    );
+    let op = WithdrawNFTOp {
+        tx: withdraw,
+        creator_id: creator_account_id,
+        creator_address: creator_account.address,
+        serial_id: 0,
+        content_hash,
+    };
+
+    tb.test_op_fail::<WithdrawNFT>(&op, "WithdrawNFT fee exceeds the protocol limit");
+}
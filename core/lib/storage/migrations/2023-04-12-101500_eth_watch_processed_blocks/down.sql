@@ -0,0 +1 @@
+DROP TABLE IF EXISTS eth_watch_processed_blocks;
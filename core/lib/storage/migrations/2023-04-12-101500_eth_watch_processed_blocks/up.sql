@@ -0,0 +1,5 @@
+-- Hashes of the Ethereum blocks processed by `eth_watch`, used to detect chain reorganizations.
+CREATE TABLE eth_watch_processed_blocks (
+    block_number BIGINT PRIMARY KEY,
+    block_hash BYTEA NOT NULL
+);
@@ -0,0 +1,7 @@
+-- Validators statuses as set in the Governance contract.
+CREATE TABLE governance_validators (
+    address BYTEA PRIMARY KEY,
+    is_active BOOLEAN NOT NULL,
+    -- Ethereum block of the latest status update.
+    eth_block BIGINT NOT NULL
+);
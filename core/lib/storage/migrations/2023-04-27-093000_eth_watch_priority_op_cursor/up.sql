@@ -0,0 +1,7 @@
+-- Position of `eth_watch` in the priority queue of the zkSync contract.
+-- The table contains at most a single row.
+CREATE TABLE eth_watch_priority_op_cursor (
+    id BOOL PRIMARY KEY DEFAULT true CHECK (id),
+    last_processed_block BIGINT NOT NULL,
+    next_priority_op_id BIGINT NOT NULL
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS quarantined_prover_jobs;
+ALTER TABLE prover_job_queue DROP COLUMN IF EXISTS attempts;
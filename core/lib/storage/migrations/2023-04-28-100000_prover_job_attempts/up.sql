@@ -0,0 +1,14 @@
+ALTER TABLE prover_job_queue ADD COLUMN attempts INT NOT NULL DEFAULT 0;
+
+-- Diagnostic records of the jobs that failed to be proven too many times.
+CREATE TABLE quarantined_prover_jobs
+(
+    job_id      INT PRIMARY KEY REFERENCES prover_job_queue (id) ON DELETE CASCADE,
+    job_type    TEXT                     NOT NULL,
+    first_block BIGINT                   NOT NULL,
+    last_block  BIGINT                   NOT NULL,
+    attempts    INT                      NOT NULL,
+    last_prover TEXT                     NOT NULL,
+    reason      TEXT                     NOT NULL,
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
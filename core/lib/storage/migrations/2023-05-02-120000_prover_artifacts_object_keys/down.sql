@@ -0,0 +1,15 @@
+-- Artifacts stored in the object storage can't be restored, so they have to be generated again.
+DELETE FROM block_witness WHERE witness IS NULL;
+ALTER TABLE block_witness DROP CONSTRAINT block_witness_location;
+ALTER TABLE block_witness DROP COLUMN object_key;
+ALTER TABLE block_witness ALTER COLUMN witness SET NOT NULL;
+
+DELETE FROM proofs WHERE proof IS NULL;
+ALTER TABLE proofs DROP CONSTRAINT proofs_location;
+ALTER TABLE proofs DROP COLUMN object_key;
+ALTER TABLE proofs ALTER COLUMN proof SET NOT NULL;
+
+DELETE FROM aggregated_proofs WHERE proof IS NULL;
+ALTER TABLE aggregated_proofs DROP CONSTRAINT aggregated_proofs_location;
+ALTER TABLE aggregated_proofs DROP COLUMN object_key;
+ALTER TABLE aggregated_proofs ALTER COLUMN proof SET NOT NULL;
@@ -0,0 +1,15 @@
+-- Witnesses and proofs can be stored in the object storage, with only the object keys in the database.
+ALTER TABLE block_witness ALTER COLUMN witness DROP NOT NULL;
+ALTER TABLE block_witness ADD COLUMN object_key TEXT;
+ALTER TABLE block_witness ADD CONSTRAINT block_witness_location
+    CHECK ((witness IS NULL) <> (object_key IS NULL));
+
+ALTER TABLE proofs ALTER COLUMN proof DROP NOT NULL;
+ALTER TABLE proofs ADD COLUMN object_key TEXT;
+ALTER TABLE proofs ADD CONSTRAINT proofs_location
+    CHECK ((proof IS NULL) <> (object_key IS NULL));
+
+ALTER TABLE aggregated_proofs ALTER COLUMN proof DROP NOT NULL;
+ALTER TABLE aggregated_proofs ADD COLUMN object_key TEXT;
+ALTER TABLE aggregated_proofs ADD CONSTRAINT aggregated_proofs_location
+    CHECK ((proof IS NULL) <> (object_key IS NULL));
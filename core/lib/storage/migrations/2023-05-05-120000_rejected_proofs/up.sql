@@ -0,0 +1,11 @@
+-- Proofs that were received from the provers but failed the verification.
+CREATE TABLE rejected_proofs
+(
+    id          SERIAL PRIMARY KEY,
+    job_id      INT                      NOT NULL REFERENCES prover_job_queue (id) ON DELETE CASCADE,
+    first_block BIGINT                   NOT NULL,
+    last_block  BIGINT                   NOT NULL,
+    prover_name TEXT                     NOT NULL,
+    reason      TEXT                     NOT NULL,
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
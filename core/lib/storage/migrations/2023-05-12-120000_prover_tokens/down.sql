@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS prover_job_audit;
+DROP TABLE IF EXISTS prover_tokens;
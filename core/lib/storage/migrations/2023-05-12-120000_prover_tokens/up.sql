@@ -0,0 +1,23 @@
+-- Authentication tokens of the provers, only the hashes of the tokens are stored.
+CREATE TABLE prover_tokens
+(
+    prover_name TEXT PRIMARY KEY,
+    token_hash  TEXT                     NOT NULL UNIQUE,
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    revoked_at  TIMESTAMP WITH TIME ZONE
+);
+
+-- Assignments of the prover jobs to the provers, with the time the proof was published at.
+CREATE TABLE prover_job_audit
+(
+    id           SERIAL PRIMARY KEY,
+    job_id       INT                      NOT NULL REFERENCES prover_job_queue (id) ON DELETE CASCADE,
+    prover_name  TEXT                     NOT NULL,
+    first_block  BIGINT                   NOT NULL,
+    last_block   BIGINT                   NOT NULL,
+    assigned_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    published_at TIMESTAMP WITH TIME ZONE,
+    published_by TEXT
+);
+CREATE INDEX prover_job_audit_job_id_idx ON prover_job_audit (job_id);
+CREATE INDEX prover_job_audit_prover_name_idx ON prover_job_audit (prover_name);
@@ -0,0 +1,24 @@
+-- Compressed values can't be restored by SQL, so they have to be generated again.
+DELETE FROM block_witness WHERE witness_compressed IS NOT NULL;
+ALTER TABLE block_witness DROP CONSTRAINT block_witness_location;
+ALTER TABLE block_witness DROP COLUMN witness_compressed;
+ALTER TABLE block_witness ADD CONSTRAINT block_witness_location
+    CHECK ((witness IS NULL) <> (object_key IS NULL));
+
+DELETE FROM proofs WHERE proof_compressed IS NOT NULL;
+ALTER TABLE proofs DROP CONSTRAINT proofs_location;
+ALTER TABLE proofs DROP COLUMN proof_compressed;
+ALTER TABLE proofs ADD CONSTRAINT proofs_location
+    CHECK ((proof IS NULL) <> (object_key IS NULL));
+
+DELETE FROM aggregated_proofs WHERE proof_compressed IS NOT NULL;
+ALTER TABLE aggregated_proofs DROP CONSTRAINT aggregated_proofs_location;
+ALTER TABLE aggregated_proofs DROP COLUMN proof_compressed;
+ALTER TABLE aggregated_proofs ADD CONSTRAINT aggregated_proofs_location
+    CHECK ((proof IS NULL) <> (object_key IS NULL));
+
+-- Compressed aggregated operations can't be removed without breaking the Ethereum operations,
+-- so the migration can be reverted only if none of them are compressed.
+ALTER TABLE aggregate_operations DROP CONSTRAINT aggregate_operations_arguments;
+ALTER TABLE aggregate_operations DROP COLUMN arguments_compressed;
+ALTER TABLE aggregate_operations ALTER COLUMN arguments SET NOT NULL;
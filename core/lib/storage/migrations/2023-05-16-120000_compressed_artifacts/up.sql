@@ -0,0 +1,20 @@
+-- Proofs, witnesses and aggregated operations can be stored compressed with zstd.
+ALTER TABLE block_witness ADD COLUMN witness_compressed BYTEA;
+ALTER TABLE block_witness DROP CONSTRAINT block_witness_location;
+ALTER TABLE block_witness ADD CONSTRAINT block_witness_location
+    CHECK (num_nonnulls(witness, witness_compressed, object_key) = 1);
+
+ALTER TABLE proofs ADD COLUMN proof_compressed BYTEA;
+ALTER TABLE proofs DROP CONSTRAINT proofs_location;
+ALTER TABLE proofs ADD CONSTRAINT proofs_location
+    CHECK (num_nonnulls(proof, proof_compressed, object_key) = 1);
+
+ALTER TABLE aggregated_proofs ADD COLUMN proof_compressed BYTEA;
+ALTER TABLE aggregated_proofs DROP CONSTRAINT aggregated_proofs_location;
+ALTER TABLE aggregated_proofs ADD CONSTRAINT aggregated_proofs_location
+    CHECK (num_nonnulls(proof, proof_compressed, object_key) = 1);
+
+ALTER TABLE aggregate_operations ALTER COLUMN arguments DROP NOT NULL;
+ALTER TABLE aggregate_operations ADD COLUMN arguments_compressed BYTEA;
+ALTER TABLE aggregate_operations ADD CONSTRAINT aggregate_operations_arguments
+    CHECK ((arguments IS NULL) <> (arguments_compressed IS NULL));
@@ -0,0 +1,8 @@
+-- Fee model parameters updated via the admin API, overriding the ones from the config.
+CREATE TABLE fee_model_params
+(
+    id         BOOL PRIMARY KEY         NOT NULL DEFAULT true,
+    params     JSONB                    NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    CONSTRAINT single_row CHECK (id)
+);
@@ -0,0 +1,23 @@
+-- Current decisions of the fee token policy, whether the token can be used to pay fees.
+CREATE TABLE fee_token_eligibility (
+    token_id INTEGER NOT NULL REFERENCES tokens(id) ON UPDATE CASCADE,
+    eligible BOOLEAN NOT NULL,
+    reason TEXT NOT NULL,
+    market_volume NUMERIC NOT NULL,
+    market_cap NUMERIC,
+    decided_at TIMESTAMP with time zone NOT NULL,
+    PRIMARY KEY (token_id)
+);
+
+-- All the decisions of the fee token policy, for auditing.
+CREATE TABLE fee_token_eligibility_history (
+    id BIGSERIAL PRIMARY KEY,
+    token_id INTEGER NOT NULL REFERENCES tokens(id) ON UPDATE CASCADE,
+    eligible BOOLEAN NOT NULL,
+    reason TEXT NOT NULL,
+    market_volume NUMERIC NOT NULL,
+    market_cap NUMERIC,
+    decided_at TIMESTAMP with time zone NOT NULL
+);
+
+CREATE INDEX fee_token_eligibility_history_token_id_idx ON fee_token_eligibility_history (token_id, decided_at);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS tokens_display_symbol_lower_idx;
+ALTER TABLE tokens DROP COLUMN IF EXISTS display_symbol;
+ALTER TABLE tokens ADD UNIQUE (symbol);
@@ -0,0 +1,13 @@
+-- Tokens can share a symbol now, the display symbol is the unique token identifier instead.
+ALTER TABLE tokens DROP CONSTRAINT IF EXISTS tokens_symbol_key;
+ALTER TABLE tokens ADD COLUMN display_symbol TEXT;
+UPDATE tokens SET display_symbol = symbol;
+-- The old constraint was case-sensitive, so the symbols may differ only in case. Such tokens except the first
+-- listed one get the address suffix, which is unique, like the display symbols generated by the server.
+UPDATE tokens SET display_symbol = symbol || '-' || substring(address FROM 3)
+WHERE EXISTS (
+    SELECT 1 FROM tokens AS listed
+    WHERE lower(listed.symbol) = lower(tokens.symbol) AND listed.id < tokens.id
+);
+ALTER TABLE tokens ALTER COLUMN display_symbol SET NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS tokens_display_symbol_lower_idx ON tokens (lower(display_symbol));
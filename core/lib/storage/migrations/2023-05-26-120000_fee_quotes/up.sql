@@ -0,0 +1,17 @@
+-- Sampled fee quotes issued by the fee ticker along with the inputs they were calculated from,
+-- used to analyze the accuracy of the fee model against the actual L1 costs offline.
+CREATE TABLE fee_quotes (
+    id BIGSERIAL PRIMARY KEY,
+    fee_type TEXT NOT NULL,
+    token_id INTEGER NOT NULL,
+    gas_price_wei NUMERIC NOT NULL,
+    scaled_gas_price_wei NUMERIC NOT NULL,
+    eth_price_usd NUMERIC NOT NULL,
+    token_price_usd NUMERIC NOT NULL,
+    gas_tx_amount NUMERIC NOT NULL,
+    zkp_fee NUMERIC NOT NULL,
+    gas_fee NUMERIC NOT NULL,
+    total_fee NUMERIC NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+CREATE INDEX IF NOT EXISTS fee_quotes_created_at_idx ON fee_quotes (created_at);
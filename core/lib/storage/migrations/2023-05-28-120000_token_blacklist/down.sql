@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS withdraw_only_deposits;
+DROP TABLE IF EXISTS token_blacklist;
@@ -0,0 +1,19 @@
+-- Tokens frozen for legal reasons: transactions with them are rejected,
+-- except for the withdrawals to the owner's own L1 address.
+CREATE TABLE token_blacklist (
+    token_id INTEGER PRIMARY KEY,
+    reason TEXT NOT NULL,
+    added_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+-- Deposits of the blacklisted tokens. Priority operations can't be rejected, so the deposited
+-- funds are credited as usual and can only be withdrawn back.
+CREATE TABLE withdraw_only_deposits (
+    serial_id BIGINT PRIMARY KEY,
+    token_id INTEGER NOT NULL,
+    address TEXT NOT NULL,
+    amount NUMERIC NOT NULL,
+    eth_hash BYTEA NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+CREATE INDEX IF NOT EXISTS withdraw_only_deposits_address_idx ON withdraw_only_deposits (address);
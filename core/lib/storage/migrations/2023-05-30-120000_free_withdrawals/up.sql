@@ -0,0 +1,6 @@
+-- The last fee-free withdrawal of the account granted by the free withdrawal quota.
+CREATE TABLE free_withdrawals (
+    account_id INTEGER PRIMARY KEY,
+    tx_hash BYTEA NOT NULL,
+    used_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
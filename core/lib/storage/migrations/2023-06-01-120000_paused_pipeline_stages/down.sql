@@ -0,0 +1 @@
+DROP TABLE IF EXISTS paused_pipeline_stages;
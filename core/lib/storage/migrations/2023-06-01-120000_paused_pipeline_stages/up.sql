@@ -0,0 +1,7 @@
+-- Pipeline stages paused by the operators via the admin API, the stage is resumed once its row is removed.
+CREATE TABLE paused_pipeline_stages
+(
+    stage     TEXT PRIMARY KEY         NOT NULL,
+    reason    TEXT                     NOT NULL,
+    paused_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
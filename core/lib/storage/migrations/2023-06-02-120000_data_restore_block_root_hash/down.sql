@@ -0,0 +1 @@
+ALTER TABLE data_restore_rollup_blocks DROP COLUMN root_hash;
@@ -0,0 +1 @@
+ALTER TABLE data_restore_rollup_blocks ADD root_hash bytea;
@@ -0,0 +1,7 @@
+-- Lowest prices of the fee tokens at which the fees paid by the mempool transactions
+-- still cover their costs. The fees are checked again when the transactions are proposed for the block.
+CREATE TABLE fee_token_price_floors (
+    tx_hash TEXT PRIMARY KEY,
+    token_id INTEGER NOT NULL,
+    min_usd_price NUMERIC NOT NULL
+);
@@ -0,0 +1 @@
+ALTER TABLE rejected_proofs DROP COLUMN cause;
@@ -0,0 +1,2 @@
+-- Distinguishes the proofs that failed the verification from the jobs the provers failed to prove.
+ALTER TABLE rejected_proofs ADD COLUMN cause INT NOT NULL DEFAULT 0;
@@ -17,6 +17,7 @@ use zksync_types::{
 };
 // Local imports
 use crate::chain::operations::records::StoredAggregatedOperation;
+use crate::compression::decode_json;
 use crate::utils::affected_accounts;
 use crate::{
     chain::{
@@ -218,11 +219,9 @@ impl NewExecutedTransaction {
 
 impl StoredAggregatedOperation {
     pub(crate) fn into_aggregated_op(self) -> (i64, AggregatedOperation) {
-        (
-            self.id,
-            serde_json::from_value(self.arguments)
-                .expect("Incorrect serialized aggregated operation in storage"),
-        )
+        let op = decode_json(self.arguments, self.arguments_compressed)
+            .expect("Stored aggregated operation has no arguments");
+        (self.id, op)
     }
 }
 
@@ -1,5 +1,10 @@
 // Built-in deps
-use std::{collections::VecDeque, convert::TryFrom, str::FromStr, time::Instant};
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::TryFrom,
+    str::FromStr,
+    time::Instant,
+};
 // External imports
 use itertools::Itertools;
 // Workspace imports
@@ -12,11 +17,12 @@ use zksync_types::{
     mempool::SignedTxVariant,
     tx::{TxEthSignature, TxHash},
     AccountId, Address, BlockNumber, ExecutedOperations, ExecutedPriorityOp, ExecutedTx,
-    PriorityOp, SerialId, SignedZkSyncTx, ZkSyncPriorityOp, H256,
+    FeeTokenPriceFloor, PriorityOp, SerialId, SignedZkSyncTx, TokenId, ZkSyncPriorityOp, H256,
 };
+use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
 // Local imports
 use self::records::{MempoolPriorityOp, MempoolTx, QueuedBatchTx, RevertedBlock};
-use crate::{QueryResult, StorageProcessor};
+use crate::{tokens::STORED_USD_PRICE_PRECISION, QueryResult, StorageProcessor};
 
 use crate::chain::operations::records::{
     StoredExecutedPriorityOperation, StoredExecutedTransaction,
@@ -292,18 +298,92 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         let start = Instant::now();
         let tx_hashes: Vec<_> = txs.iter().map(hex::encode).collect();
 
+        let mut transaction = self.0.start_transaction().await?;
         sqlx::query!(
             "DELETE FROM mempool_txs
             WHERE tx_hash = ANY($1)",
             &tx_hashes
         )
-        .execute(self.0.conn())
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM fee_token_price_floors
+            WHERE tx_hash = ANY($1)",
+            &tx_hashes
+        )
+        .execute(transaction.conn())
         .await?;
+        transaction.commit().await?;
 
         metrics::histogram!("sql.chain.mempool.remove_txs", start.elapsed());
         Ok(())
     }
 
+    /// Stores the fee token price floor of the transaction, the fee is checked against it
+    /// when the transaction is proposed for the block.
+    pub async fn store_fee_token_price_floor(
+        &mut self,
+        tx_hash: TxHash,
+        floor: &FeeTokenPriceFloor,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let tx_hash = hex::encode(tx_hash.as_ref());
+        let min_usd_price = ratio_to_big_decimal(&floor.min_usd_price, STORED_USD_PRICE_PRECISION);
+
+        sqlx::query!(
+            "INSERT INTO fee_token_price_floors (tx_hash, token_id, min_usd_price)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (tx_hash)
+            DO UPDATE SET token_id = $2, min_usd_price = $3",
+            tx_hash,
+            *floor.token_id as i32,
+            min_usd_price,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.mempool.store_fee_token_price_floor",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Loads the fee token price floors of the given transactions, the transactions without
+    /// the floor are skipped.
+    pub async fn load_fee_token_price_floors(
+        &mut self,
+        txs: &[TxHash],
+    ) -> QueryResult<HashMap<TxHash, FeeTokenPriceFloor>> {
+        let start = Instant::now();
+        let tx_hashes: HashMap<_, _> = txs.iter().map(|&tx| (hex::encode(tx), tx)).collect();
+        let hex_hashes: Vec<_> = tx_hashes.keys().cloned().collect();
+
+        let floors = sqlx::query!(
+            "SELECT tx_hash, token_id, min_usd_price FROM fee_token_price_floors
+            WHERE tx_hash = ANY($1)",
+            &hex_hashes
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| {
+            let floor = FeeTokenPriceFloor {
+                token_id: TokenId(record.token_id as u32),
+                min_usd_price: big_decimal_to_ratio(&record.min_usd_price)
+                    .expect("Price could not be negative"),
+            };
+            (tx_hashes[&record.tx_hash], floor)
+        })
+        .collect();
+
+        metrics::histogram!(
+            "sql.chain.mempool.load_fee_token_price_floors",
+            start.elapsed()
+        );
+        Ok(floors)
+    }
+
     /// Checks if the memory pool contains transaction with the given hash.
     pub async fn contains_tx(&mut self, tx_hash: TxHash) -> QueryResult<bool> {
         let start = Instant::now();
@@ -402,6 +482,13 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         }
 
         self.remove_txs(&tx_hashes_to_remove).await?;
+        // The floors of the transactions rejected by the mempool aren't needed anymore.
+        sqlx::query!(
+            "DELETE FROM fee_token_price_floors
+            WHERE tx_hash NOT IN (SELECT tx_hash FROM mempool_txs)"
+        )
+        .execute(self.0.conn())
+        .await?;
 
         let priority_ops = self.get_confirmed_priority_ops().await?;
         let mut priority_ops_to_remove = Vec::new();
@@ -597,6 +684,50 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Returns the serial ids of the priority operations from the given list that are stored in the mempool.
+    pub async fn get_known_priority_op_ids(
+        &mut self,
+        serial_ids: &[SerialId],
+    ) -> QueryResult<Vec<SerialId>> {
+        let start = Instant::now();
+        let serial_ids: Vec<_> = serial_ids.iter().map(|id| *id as i64).collect();
+        let known_ids = sqlx::query!(
+            "SELECT serial_id FROM mempool_priority_operations WHERE serial_id = ANY($1)",
+            &serial_ids
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| row.serial_id as SerialId)
+        .collect();
+
+        metrics::histogram!("sql.chain", start.elapsed(), "schema" => "mempool", "method" => "get_known_priority_op_ids");
+        Ok(known_ids)
+    }
+
+    /// Removes the priority operations received in the Ethereum blocks newer than
+    /// `last_valid_eth_block`. Returns the serial ids of the removed operations.
+    ///
+    /// Should be used once the Ethereum chain reorganization is detected.
+    pub async fn remove_priority_ops_after_eth_block(
+        &mut self,
+        last_valid_eth_block: u64,
+    ) -> QueryResult<Vec<u64>> {
+        let start = Instant::now();
+        let removed_ids = sqlx::query!(
+            "DELETE FROM mempool_priority_operations WHERE eth_block > $1 RETURNING serial_id",
+            last_valid_eth_block as i64
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| row.serial_id as u64)
+        .collect();
+
+        metrics::histogram!("sql.chain", start.elapsed(), "schema" => "mempool", "method" => "remove_priority_ops_after_eth_block");
+        Ok(removed_ids)
+    }
+
     /// Returns mempool size.
     pub async fn get_mempool_size(&mut self) -> QueryResult<u32> {
         let start = Instant::now();
@@ -15,6 +15,7 @@ use self::records::{
 };
 use crate::chain::operations::records::StoredExecutedTransaction;
 use crate::chain::operations_ext::OperationsExtSchema;
+use crate::compression::encode_json;
 use crate::ethereum::EthereumSchema;
 use crate::{chain::mempool::MempoolSchema, QueryResult, StorageProcessor};
 
@@ -509,14 +510,18 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         let aggregated_action_type = operation.get_action_type();
         let (from_block, to_block) = operation.get_block_range();
 
+        // The operation contains the pubdata of the blocks, so it's compressed if configured.
+        let (arguments, arguments_compressed) =
+            encode_json(&operation, transaction.compression_level());
         let id = sqlx::query!(
-            "INSERT INTO aggregate_operations (action_type, arguments, from_block, to_block)
-            VALUES ($1, $2, $3, $4)
+            "INSERT INTO aggregate_operations (action_type, arguments, arguments_compressed, from_block, to_block)
+            VALUES ($1, $2, $3, $4, $5)
             ON CONFLICT (id)
             DO NOTHING
             RETURNING id",
             aggregated_action_type.to_string(),
-            serde_json::to_value(operation.clone()).expect("aggregated op serialize fail"),
+            arguments,
+            arguments_compressed,
             i64::from(*from_block),
             i64::from(*to_block)
         )
@@ -596,12 +601,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         )
         .fetch_optional(self.0.conn())
         .await?
-        .map(|op| {
-            (
-                op.id,
-                serde_json::from_value(op.arguments).expect("unparsable aggregated op"),
-            )
-        });
+        .map(StoredAggregatedOperation::into_aggregated_op);
         Ok(aggregated_op)
     }
 
@@ -123,9 +123,11 @@ pub(crate) struct StoredCompleteWithdrawalsTransaction {
 pub struct StoredAggregatedOperation {
     pub id: i64,
     pub action_type: String,
-    pub arguments: serde_json::Value,
+    pub arguments: Option<serde_json::Value>,
     pub from_block: i64,
     pub to_block: i64,
     pub created_at: DateTime<Utc>,
     pub confirmed: bool,
+    /// Compressed JSON of the operation, if it's stored compressed.
+    pub arguments_compressed: Option<Vec<u8>>,
 }
@@ -312,8 +312,8 @@ impl<'a, 'c> StateSchema<'a, 'c> {
                 let address = address_to_stored_string(&Address::from_slice(&upd.address));
                 sqlx::query!(
                     r#"
-                    INSERT INTO tokens ( id, address, symbol, decimals, kind )
-                    VALUES ( $1, $2, $3, $4, 'NFT'::token_kind )
+                    INSERT INTO tokens ( id, address, symbol, decimals, kind, display_symbol )
+                    VALUES ( $1, $2, $3, $4, 'NFT'::token_kind, $3 )
                     "#,
                     upd.token_id,
                     address,
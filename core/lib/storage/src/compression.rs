@@ -0,0 +1,48 @@
+//! Compression of the large values stored in the database, i.e. proofs, witnesses and
+//! aggregated operations (which contain the blocks pubdata).
+//!
+//! Values are compressed with zstd if the compression level of the `StorageProcessor` is set
+//! (see `DBConfig::compression_level`), and are stored in the `BYTEA` columns next to the columns
+//! of the uncompressed values. Values stored either way are read regardless of the configuration.
+
+// External imports
+use serde::{de::DeserializeOwned, Serialize};
+// Workspace imports
+use zksync_utils::compression;
+
+/// Returns either the JSON of the value or its compressed form, if the compression level is set.
+pub(crate) fn encode_json<T: Serialize>(
+    value: &T,
+    compression_level: Option<i32>,
+) -> (Option<serde_json::Value>, Option<Vec<u8>>) {
+    match compression_level {
+        Some(level) => {
+            let json = serde_json::to_vec(value).expect("Failed to serialize the stored value");
+            (None, Some(compression::compress(&json, level)))
+        }
+        None => (
+            Some(serde_json::to_value(value).expect("Failed to serialize the stored value")),
+            None,
+        ),
+    }
+}
+
+/// Restores the value stored either as JSON or in the compressed form.
+pub(crate) fn decode_json<T: DeserializeOwned>(
+    json: Option<serde_json::Value>,
+    compressed: Option<Vec<u8>>,
+) -> Option<T> {
+    match (json, compressed) {
+        (Some(json), _) => {
+            Some(serde_json::from_value(json).expect("Failed to deserialize the stored value"))
+        }
+        (None, Some(compressed)) => Some(decompress_json(&compressed)),
+        (None, None) => None,
+    }
+}
+
+/// Restores the value compressed by `encode_json`.
+pub(crate) fn decompress_json<T: DeserializeOwned>(compressed: &[u8]) -> T {
+    let json = compression::decompress(compressed).expect("Failed to decompress the stored value");
+    serde_json::from_slice(&json).expect("Failed to deserialize the stored value")
+}
@@ -55,6 +55,7 @@ impl Manager for DbPool {
 #[derive(Clone)]
 pub struct ConnectionPool {
     pool: Pool,
+    compression_level: Option<i32>,
 }
 
 impl fmt::Debug for ConnectionPool {
@@ -73,7 +74,10 @@ impl ConnectionPool {
 
         let pool = DbPool::create(database_url, max_size as usize);
 
-        Self { pool }
+        Self {
+            pool,
+            compression_level: None,
+        }
     }
 
     /// Establishes a pool of the connections to the replica of database and
@@ -86,8 +90,19 @@ impl ConnectionPool {
 
         let pool = DbPool::create(database_url, max_size as usize);
 
-        Self { pool }
+        Self {
+            pool,
+            compression_level: None,
+        }
     }
+
+    /// Sets the level of the zstd compression of the large values stored via the connections
+    /// of the pool, see `DBConfig::compression_level`.
+    pub fn with_compression_level(mut self, compression_level: Option<i32>) -> Self {
+        self.compression_level = compression_level;
+        self
+    }
+
     /// Creates a `StorageProcessor` entity over a recoverable connection.
     /// Upon a database outage connection will block the thread until
     /// it will be able to recover the connection (or, if connection cannot
@@ -101,7 +116,9 @@ impl ConnectionPool {
         let connection = self.get_pooled_connection().await;
         metrics::histogram!("sql.connection_acquire", start.elapsed());
 
-        Ok(StorageProcessor::from_pool(connection))
+        let mut storage = StorageProcessor::from_pool(connection);
+        storage.set_compression_level(self.compression_level);
+        Ok(storage)
     }
 
     async fn get_pooled_connection(&self) -> PooledConnection {
@@ -17,7 +17,7 @@ use self::records::{
 
 use crate::chain::operations::OperationsSchema;
 use crate::{
-    chain::state::StateSchema,
+    chain::{block::BlockSchema, state::StateSchema, tree_cache::TreeCacheSchemaJSON},
     tokens::{StoreTokenError, TokensSchema},
 };
 use crate::{QueryResult, StorageProcessor};
@@ -32,10 +32,12 @@ pub mod records;
 pub struct DataRestoreSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
 
 impl<'a, 'c> DataRestoreSchema<'a, 'c> {
+    /// Saves the confirmed commitment of the blocks, and their execution if it's provided,
+    /// i.e. if the blocks are already verified on the contract.
     pub async fn save_block_operations(
         &mut self,
         commit_op: BlocksCommitOperation,
-        execute_op: BlocksExecuteOperation,
+        execute_op: Option<BlocksExecuteOperation>,
     ) -> QueryResult<()> {
         let start = Instant::now();
         let new_state = self.new_storage_state("None");
@@ -44,25 +46,48 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
         OperationsSchema(&mut transaction)
             .store_aggregated_action(AggregatedOperation::CommitBlocks(commit_op.clone()))
             .await?;
+        OperationsSchema(&mut transaction)
+            .confirm_aggregated_operations(
+                commit_op.blocks.first().unwrap().block_number,
+                commit_op.blocks.last().unwrap().block_number,
+                AggregatedActionType::CommitBlocks,
+            )
+            .await?;
+
+        if let Some(execute_op) = execute_op {
+            DataRestoreSchema(&mut transaction)
+                .save_blocks_execution(execute_op)
+                .await?;
+        }
+
+        DataRestoreSchema(&mut transaction)
+            .update_storage_state(new_state)
+            .await?;
+        transaction.commit().await?;
+        metrics::histogram!("sql.data_restore.save_block_operations", start.elapsed());
+        Ok(())
+    }
+
+    /// Saves the confirmed execution of the previously committed blocks
+    /// and applies their state updates.
+    pub async fn save_blocks_execution(
+        &mut self,
+        execute_op: BlocksExecuteOperation,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
         OperationsSchema(&mut transaction)
             .store_aggregated_action(AggregatedOperation::ExecuteBlocks(execute_op.clone()))
             .await?;
         // The state is expected to be updated, so it's necessary
         // to do it here.
-        for block in commit_op.blocks.iter() {
+        for block in execute_op.blocks.iter() {
             StateSchema(&mut transaction)
                 .apply_state_update(block.block_number)
                 .await?;
         }
 
-        OperationsSchema(&mut transaction)
-            .confirm_aggregated_operations(
-                commit_op.blocks.first().unwrap().block_number,
-                commit_op.blocks.last().unwrap().block_number,
-                AggregatedActionType::CommitBlocks,
-            )
-            .await?;
-
         OperationsSchema(&mut transaction)
             .confirm_aggregated_operations(
                 execute_op.blocks.first().unwrap().block_number,
@@ -71,11 +96,75 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
             )
             .await?;
 
-        DataRestoreSchema(&mut transaction)
-            .update_storage_state(new_state)
+        transaction.commit().await?;
+        metrics::histogram!("sql.data_restore.save_blocks_execution", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes the blocks with number greater than `last_block`, which commitments are reverted
+    /// on the contract. Only the blocks which are not executed can be reverted, so their state
+    /// updates are not applied yet and are simply removed.
+    pub async fn revert_blocks(&mut self, last_block: BlockNumber) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        BlockSchema(&mut transaction)
+            .remove_blocks(last_block)
+            .await?;
+        StateSchema(&mut transaction)
+            .remove_account_balance_updates(last_block)
             .await?;
+        StateSchema(&mut transaction)
+            .remove_account_creates(last_block)
+            .await?;
+        StateSchema(&mut transaction)
+            .remove_account_pubkey_updates(last_block)
+            .await?;
+        StateSchema(&mut transaction)
+            .remove_mint_nft_updates(last_block)
+            .await?;
+        StateSchema(&mut transaction)
+            .clear_current_nonce_table(last_block)
+            .await?;
+        OperationsSchema(&mut transaction)
+            .remove_aggregate_operations_and_bindings(last_block)
+            .await?;
+        TreeCacheSchemaJSON(&mut transaction)
+            .remove_new_account_tree_cache(last_block)
+            .await?;
+
+        // Unlike the server, there's no mempool to return the executed operations to.
+        sqlx::query!(
+            "DELETE FROM tx_filters WHERE tx_hash IN (
+                SELECT tx_hash FROM executed_transactions WHERE block_number > $1
+            )",
+            *last_block as i64
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM tx_filters WHERE tx_hash IN (
+                SELECT tx_hash FROM executed_priority_operations WHERE block_number > $1
+            )",
+            *last_block as i64
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM executed_transactions WHERE block_number > $1",
+            *last_block as i64
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM executed_priority_operations WHERE block_number > $1",
+            *last_block as i64
+        )
+        .execute(transaction.conn())
+        .await?;
+
         transaction.commit().await?;
-        metrics::histogram!("sql.data_restore.save_block_operations", start.elapsed());
+        metrics::histogram!("sql.data_restore.revert_blocks", start.elapsed());
         Ok(())
     }
 
@@ -105,7 +194,7 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
         let stored_blocks = sqlx::query_as!(
             StoredRollupOpsBlock,
             r#"SELECT blocks.block_num AS "block_num!", ops, fee_account as "fee_account!",
-            timestamp, previous_block_root_hash, contract_version as "contract_version!"
+            timestamp, previous_block_root_hash, root_hash, contract_version as "contract_version!"
             FROM data_restore_rollup_blocks AS blocks
             JOIN (
                 SELECT block_num, array_agg(operation ORDER BY id) as ops
@@ -171,6 +260,26 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
         Ok(stored)
     }
 
+    /// Loads the last seen Ethereum block number, returns `None` if the data restore
+    /// has never been started, i.e. there's no progress to resume.
+    pub async fn find_last_watched_block_number(
+        &mut self,
+    ) -> QueryResult<Option<StoredLastWatchedEthBlockNumber>> {
+        let start = Instant::now();
+        let stored = sqlx::query_as!(
+            StoredLastWatchedEthBlockNumber,
+            "SELECT * FROM data_restore_last_watched_eth_block LIMIT 1",
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.data_restore.find_last_watched_block_number",
+            start.elapsed()
+        );
+        Ok(stored)
+    }
+
     fn new_storage_state(&self, state: impl ToString) -> NewStorageState {
         NewStorageState {
             storage_state: state.to_string(),
@@ -323,12 +432,13 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
 
         for block in rollup_blocks {
             sqlx::query!(
-                "INSERT INTO data_restore_rollup_blocks
-                VALUES ($1, $2, $3, $4)",
+                "INSERT INTO data_restore_rollup_blocks (block_num, fee_account, timestamp, previous_block_root_hash, root_hash)
+                VALUES ($1, $2, $3, $4, $5)",
                 i64::from(*block.block_num),
                 i64::from(*block.fee_account),
                 block.timestamp.map(|t| t as i64),
-                Some(block.previous_block_root_hash.as_bytes().to_vec())
+                Some(block.previous_block_root_hash.as_bytes().to_vec()),
+                Some(block.root_hash.as_bytes().to_vec())
             )
             .execute(transaction.conn())
             .await?;
@@ -0,0 +1,142 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+use zksync_types::{SerialId, H256};
+// Local imports
+use self::records::{StoredPriorityOpCursor, StoredProcessedEthBlock};
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Eth watch schema stores the state of the `eth_watch` module, which is
+/// required to resume the Ethereum events processing after the server restart.
+///
+/// Processed blocks are used to detect the Ethereum chain reorganizations: if the hash of
+/// a processed block doesn't match the one in the canonical chain anymore, every event
+/// received after the common ancestor has to be reverted.
+///
+/// Priority operation cursor is the last Ethereum block which priority operations are fully
+/// processed and the serial id of the next expected priority operation. Operations with lower
+/// serial ids are considered processed, since the watcher never accepts the operations with gaps.
+#[derive(Debug)]
+pub struct EthWatchSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> EthWatchSchema<'a, 'c> {
+    /// Stores the hash of the processed Ethereum block, removing the blocks that are
+    /// older than `oldest_tracked_block` from the database.
+    pub async fn save_processed_block(
+        &mut self,
+        block_number: u64,
+        block_hash: H256,
+        oldest_tracked_block: u64,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        sqlx::query!(
+            "INSERT INTO eth_watch_processed_blocks (block_number, block_hash)
+            VALUES ($1, $2)
+            ON CONFLICT (block_number) DO UPDATE SET block_hash = $2",
+            block_number as i64,
+            block_hash.as_bytes()
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM eth_watch_processed_blocks WHERE block_number < $1",
+            oldest_tracked_block as i64
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        transaction.commit().await?;
+        metrics::histogram!("sql.eth_watch.save_processed_block", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the processed Ethereum blocks in the ascending order.
+    pub async fn load_processed_blocks(&mut self) -> QueryResult<Vec<(u64, H256)>> {
+        let start = Instant::now();
+        let blocks = sqlx::query_as!(
+            StoredProcessedEthBlock,
+            "SELECT * FROM eth_watch_processed_blocks ORDER BY block_number ASC"
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|block| {
+            (
+                block.block_number as u64,
+                H256::from_slice(&block.block_hash),
+            )
+        })
+        .collect();
+
+        metrics::histogram!("sql.eth_watch.load_processed_blocks", start.elapsed());
+        Ok(blocks)
+    }
+
+    /// Removes the processed blocks that are newer than `last_valid_block`.
+    /// Should be used once the chain reorganization is detected.
+    pub async fn remove_processed_blocks_after(
+        &mut self,
+        last_valid_block: u64,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM eth_watch_processed_blocks WHERE block_number > $1",
+            last_valid_block as i64
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.eth_watch.remove_processed_blocks_after",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Stores the position of `eth_watch` in the priority queue, replacing the previous one.
+    pub async fn save_priority_op_cursor(
+        &mut self,
+        last_processed_block: u64,
+        next_priority_op_id: SerialId,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO eth_watch_priority_op_cursor (id, last_processed_block, next_priority_op_id)
+            VALUES (true, $1, $2)
+            ON CONFLICT (id) DO UPDATE SET last_processed_block = $1, next_priority_op_id = $2",
+            last_processed_block as i64,
+            next_priority_op_id as i64
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.eth_watch.save_priority_op_cursor", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the last processed Ethereum block and the serial id of the next priority operation.
+    /// Returns `None` if the cursor was never stored.
+    pub async fn load_priority_op_cursor(&mut self) -> QueryResult<Option<(u64, SerialId)>> {
+        let start = Instant::now();
+        let cursor = sqlx::query_as!(
+            StoredPriorityOpCursor,
+            "SELECT last_processed_block, next_priority_op_id FROM eth_watch_priority_op_cursor"
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|cursor| {
+            (
+                cursor.last_processed_block as u64,
+                cursor.next_priority_op_id as SerialId,
+            )
+        });
+
+        metrics::histogram!("sql.eth_watch.load_priority_op_cursor", start.elapsed());
+        Ok(cursor)
+    }
+}
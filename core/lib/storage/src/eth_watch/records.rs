@@ -0,0 +1,18 @@
+// External imports
+use sqlx::FromRow;
+// Workspace imports
+// Local imports
+
+/// Ethereum block processed by the `eth_watch` module.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StoredProcessedEthBlock {
+    pub block_number: i64,
+    pub block_hash: Vec<u8>,
+}
+
+/// Position of the `eth_watch` module in the priority queue.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StoredPriorityOpCursor {
+    pub last_processed_block: i64,
+    pub next_priority_op_id: i64,
+}
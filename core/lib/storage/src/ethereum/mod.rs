@@ -15,7 +15,10 @@ use zksync_types::{
 };
 // Local imports
 use self::records::{ETHOperationData, ETHParams, ETHStats, ETHTxHash, StorageETHOperation};
-use crate::{chain::operations::records::StoredAggregatedOperation, QueryResult, StorageProcessor};
+use crate::{
+    chain::operations::records::StoredAggregatedOperation, compression::decode_json, QueryResult,
+    StorageProcessor,
+};
 use chrono::{DateTime, Utc};
 
 pub mod records;
@@ -46,7 +49,8 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
             r#"
                 SELECT eth_operations.*,
                     aggregate_operations.id as "agg_op_id?",
-                    aggregate_operations.arguments as "arguments?"
+                    aggregate_operations.arguments as "arguments?",
+                    aggregate_operations.arguments_compressed as "arguments_compressed?"
                 FROM eth_operations
                 LEFT JOIN eth_aggregated_ops_binding
                     ON eth_aggregated_ops_binding.eth_op_id = eth_operations.id
@@ -82,9 +86,11 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
 
             // If there is an operation, convert it to the `AggregatedOperation` type.
             let op = eth_op.agg_op_id.map(|id| {
-                let op: AggregatedOperation =
-                    serde_json::from_value(eth_op.arguments.clone().unwrap())
-                        .expect("Incorrect serialized aggregated operation in storage");
+                let op: AggregatedOperation = decode_json(
+                    eth_op.arguments.clone(),
+                    eth_op.arguments_compressed.clone(),
+                )
+                .expect("Stored aggregated operation has no arguments");
                 (id, op)
             });
 
@@ -162,9 +168,9 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
             r#"
             SELECT
                 id as "id!", action_type as "action_type!",
-                arguments as "arguments!", from_block as "from_block!",
+                arguments, from_block as "from_block!",
                 to_block as "to_block!", created_at as "created_at!",
-                confirmed as "confirmed!"
+                confirmed as "confirmed!", arguments_compressed
             FROM aggregate_operations
             WHERE EXISTS (SELECT * FROM eth_unprocessed_aggregated_ops WHERE op_id = aggregate_operations.id)
             ORDER BY id ASC
@@ -468,6 +474,15 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(average_gas_price)
     }
 
+    /// Loads the nonce that will be assigned to the next Ethereum operation.
+    pub async fn load_next_nonce(&mut self) -> QueryResult<i64> {
+        let start = Instant::now();
+        let params = self.load_eth_params().await?;
+
+        metrics::histogram!("sql.ethereum.load_next_nonce", start.elapsed());
+        Ok(params.nonce)
+    }
+
     /// Loads the stored Ethereum operations stats.
     pub async fn load_stats(&mut self) -> QueryResult<ETHStats> {
         let start = Instant::now();
@@ -29,6 +29,7 @@ pub struct ETHOperationData {
     pub last_used_gas_price: BigDecimal,
     pub agg_op_id: Option<i64>,
     pub arguments: Option<serde_json::Value>,
+    pub arguments_compressed: Option<Vec<u8>>,
     pub created_at: Option<DateTime<Utc>>,
 }
 
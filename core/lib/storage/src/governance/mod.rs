@@ -0,0 +1,75 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+use zksync_types::Address;
+// Local imports
+use self::records::StoredValidator;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Governance schema mirrors the state of the Governance contract that isn't stored elsewhere,
+/// e.g. the statuses of the validators.
+#[derive(Debug)]
+pub struct GovernanceSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> GovernanceSchema<'a, 'c> {
+    /// Stores the validator status set in the Ethereum block `eth_block`.
+    /// Status updates from the blocks older than the stored one are ignored, so the events
+    /// may be processed several times.
+    pub async fn store_validator_status(
+        &mut self,
+        address: Address,
+        is_active: bool,
+        eth_block: u64,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO governance_validators (address, is_active, eth_block)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (address) DO UPDATE SET is_active = $2, eth_block = $3
+            WHERE governance_validators.eth_block <= $3",
+            address.as_bytes(),
+            is_active,
+            eth_block as i64
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.governance.store_validator_status", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the known validators along with their statuses.
+    pub async fn load_validators(&mut self) -> QueryResult<Vec<(Address, bool)>> {
+        let start = Instant::now();
+        let validators = sqlx::query_as!(
+            StoredValidator,
+            "SELECT * FROM governance_validators ORDER BY address"
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|validator| (Address::from_slice(&validator.address), validator.is_active))
+        .collect();
+
+        metrics::histogram!("sql.governance.load_validators", start.elapsed());
+        Ok(validators)
+    }
+
+    /// Returns the status of the validator, or `None` if it's unknown.
+    pub async fn is_active_validator(&mut self, address: Address) -> QueryResult<Option<bool>> {
+        let start = Instant::now();
+        let is_active = sqlx::query!(
+            "SELECT is_active FROM governance_validators WHERE address = $1",
+            address.as_bytes()
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|record| record.is_active);
+
+        metrics::histogram!("sql.governance.is_active_validator", start.elapsed());
+        Ok(is_active)
+    }
+}
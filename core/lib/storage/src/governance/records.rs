@@ -0,0 +1,12 @@
+// External imports
+use sqlx::FromRow;
+// Workspace imports
+// Local imports
+
+/// Validator status as set in the Governance contract.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StoredValidator {
+    pub address: Vec<u8>,
+    pub is_active: bool,
+    pub eth_block: i64,
+}
@@ -34,7 +34,9 @@
 //!
 //! - config, for the server config.
 //! - data_restore, for the data_restore crate.
+//! - eth_watch, for the state of the Ethereum events watcher.
 //! - ethereum, for the data associated with the Ethereum blockchain.
+//! - governance, for the state of the Governance contract.
 //! - prover, for the data on prover jobs, proofs, etc.
 //! - tokens, for storing and loading known tokens.
 //! - chain - the biggest one, which includes several schemas for the ZKSync sidechain itself.
@@ -89,13 +91,16 @@ use crate::connection::{holder::ConnectionHolder, PooledConnection};
 mod tests;
 
 pub mod chain;
+mod compression;
 pub mod config;
 pub mod connection;
 pub mod data_restore;
 pub mod diff;
+pub mod eth_watch;
 pub mod ethereum;
 pub mod event;
 pub mod forced_exit_requests;
+pub mod governance;
 pub mod listener;
 pub mod misc;
 pub mod prover;
@@ -132,6 +137,8 @@ pub fn get_database_url() -> String {
 pub struct StorageProcessor<'a> {
     conn: ConnectionHolder<'a>,
     in_transaction: bool,
+    /// Level of the zstd compression of the large stored values, see the `compression` module.
+    compression_level: Option<i32>,
 }
 
 #[derive(sqlx::Type, Debug, Clone, PartialEq, Eq)]
@@ -158,20 +165,41 @@ impl<'a> StorageProcessor<'a> {
         Ok(StorageProcessor {
             conn: ConnectionHolder::Direct(connection),
             in_transaction: false,
+            compression_level: None,
         })
     }
 
     pub async fn start_transaction<'c: 'b, 'b>(
         &'c mut self,
     ) -> Result<StorageProcessor<'b>, anyhow::Error> {
+        let compression_level = self.compression_level;
         let transaction = self.conn().begin().await?;
 
         let mut processor = StorageProcessor::from_transaction(transaction);
         processor.in_transaction = true;
+        processor.compression_level = compression_level;
 
         Ok(processor)
     }
 
+    /// Starts the transaction with the `REPEATABLE READ` isolation level, so all its queries
+    /// see the same snapshot of the database, even if other transactions are committed meanwhile.
+    ///
+    /// Fails if the `StorageProcessor` is already within the transaction, since the isolation
+    /// level can't be set for the nested one.
+    pub async fn start_repeatable_read_transaction<'c: 'b, 'b>(
+        &'c mut self,
+    ) -> Result<StorageProcessor<'b>, anyhow::Error> {
+        if self.in_transaction {
+            anyhow::bail!("Isolation level can't be set for the nested transaction");
+        }
+        let mut transaction = self.start_transaction().await?;
+        sqlx::query!("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .execute(transaction.conn())
+            .await?;
+        Ok(transaction)
+    }
+
     /// Checks if the `StorageProcessor` is currently within database transaction.
     pub fn in_transaction(&self) -> bool {
         self.in_transaction
@@ -181,9 +209,20 @@ impl<'a> StorageProcessor<'a> {
         StorageProcessor {
             conn: ConnectionHolder::Transaction(conn),
             in_transaction: true,
+            compression_level: None,
         }
     }
 
+    /// Sets the level of the zstd compression of the stored proofs, witnesses and
+    /// aggregated operations. They are stored uncompressed if the level is not set.
+    pub fn set_compression_level(&mut self, compression_level: Option<i32>) {
+        self.compression_level = compression_level;
+    }
+
+    pub fn compression_level(&self) -> Option<i32> {
+        self.compression_level
+    }
+
     pub async fn commit(self) -> QueryResult<()> {
         if let ConnectionHolder::Transaction(transaction) = self.conn {
             transaction.commit().await?;
@@ -200,6 +239,7 @@ impl<'a> StorageProcessor<'a> {
         Self {
             conn: ConnectionHolder::Pooled(conn),
             in_transaction: false,
+            compression_level: None,
         }
     }
 
@@ -218,11 +258,21 @@ impl<'a> StorageProcessor<'a> {
         data_restore::DataRestoreSchema(self)
     }
 
+    /// Gains access to the `EthWatch` schema.
+    pub fn eth_watch_schema(&mut self) -> eth_watch::EthWatchSchema<'_, 'a> {
+        eth_watch::EthWatchSchema(self)
+    }
+
     /// Gains access to the `Ethereum` schema.
     pub fn ethereum_schema(&mut self) -> ethereum::EthereumSchema<'_, 'a> {
         ethereum::EthereumSchema(self)
     }
 
+    /// Gains access to the `Governance` schema.
+    pub fn governance_schema(&mut self) -> governance::GovernanceSchema<'_, 'a> {
+        governance::GovernanceSchema(self)
+    }
+
     /// Gains access to the `Prover` schema.
     pub fn prover_schema(&mut self) -> prover::ProverSchema<'_, 'a> {
         prover::ProverSchema(self)
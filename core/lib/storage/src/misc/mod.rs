@@ -1,10 +1,17 @@
 // Built-in deps
 use std::time::Instant;
 // External imports
+use chrono::{DateTime, Utc};
 use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{
+    pipeline::{PipelineStage, PipelineStagePause},
+    tx::TxHash,
+    AccountId,
+};
 // Local imports
 
-use self::records::Subsidy;
+use self::records::{FeeQuote, StoragePipelineStagePause, Subsidy};
 use crate::{QueryResult, StorageProcessor};
 use num::ToPrimitive;
 
@@ -64,4 +71,237 @@ impl<'a, 'c> MiscSchema<'a, 'c> {
         metrics::histogram!("sql.token.get_total_used_subsidy_for_type", start.elapsed());
         Ok(sum)
     }
+
+    /// Loads the fee model parameters set via the admin API, if any.
+    pub async fn load_fee_model_params(&mut self) -> QueryResult<Option<serde_json::Value>> {
+        let start = Instant::now();
+        let params = sqlx::query!("SELECT params FROM fee_model_params WHERE id = true")
+            .fetch_optional(self.0.conn())
+            .await?
+            .map(|record| record.params);
+
+        metrics::histogram!("sql.misc.load_fee_model_params", start.elapsed());
+        Ok(params)
+    }
+
+    /// Stores the fee model parameters, replacing the previous ones.
+    pub async fn store_fee_model_params(&mut self, params: serde_json::Value) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO fee_model_params ( id, params, updated_at )
+            VALUES ( true, $1, now() )
+            ON CONFLICT (id)
+            DO
+              UPDATE SET params = $1, updated_at = now()
+            "#,
+            params
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.misc.store_fee_model_params", start.elapsed());
+        Ok(())
+    }
+
+    /// Persists the sampled fee quotes.
+    pub async fn store_fee_quotes(&mut self, quotes: Vec<FeeQuote>) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        for quote in quotes {
+            sqlx::query!(
+                r#"
+                INSERT INTO fee_quotes (
+                    fee_type, token_id, gas_price_wei, scaled_gas_price_wei, eth_price_usd,
+                    token_price_usd, gas_tx_amount, zkp_fee, gas_fee, total_fee, created_at
+                )
+                VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11 )
+                "#,
+                quote.fee_type,
+                quote.token_id.0 as i32,
+                quote.gas_price_wei,
+                quote.scaled_gas_price_wei,
+                quote.eth_price_usd,
+                quote.token_price_usd,
+                quote.gas_tx_amount,
+                quote.zkp_fee,
+                quote.gas_fee,
+                quote.total_fee,
+                quote.created_at
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.misc.store_fee_quotes", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the number of the stored fee quotes.
+    pub async fn get_fee_quotes_count(&mut self) -> QueryResult<i64> {
+        let count = sqlx::query!(r#"SELECT COUNT(*) as "count!" FROM fee_quotes"#)
+            .fetch_one(self.0.conn())
+            .await?
+            .count;
+        Ok(count)
+    }
+
+    /// Grants the fee-free withdrawal to the account, unless it has already used one after
+    /// `period_start`. Returns `true` if the withdrawal is granted.
+    pub async fn use_free_withdrawal(
+        &mut self,
+        account_id: AccountId,
+        tx_hash: TxHash,
+        period_start: DateTime<Utc>,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        // The check and the update are done in one statement, so the concurrent
+        // withdrawals of the same account can't both be granted.
+        let granted = sqlx::query!(
+            r#"
+            INSERT INTO free_withdrawals ( account_id, tx_hash, used_at )
+            VALUES ( $1, $2, now() )
+            ON CONFLICT (account_id)
+            DO
+              UPDATE SET tx_hash = $2, used_at = now()
+              WHERE free_withdrawals.used_at < $3
+            RETURNING account_id
+            "#,
+            account_id.0 as i32,
+            tx_hash.as_ref(),
+            period_start
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .is_some();
+
+        metrics::histogram!("sql.misc.use_free_withdrawal", start.elapsed());
+        Ok(granted)
+    }
+
+    /// Returns the fee-free withdrawal back to the quota if the transaction was not accepted.
+    pub async fn revert_free_withdrawal(&mut self, tx_hash: TxHash) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM free_withdrawals WHERE tx_hash = $1",
+            tx_hash.as_ref()
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.misc.revert_free_withdrawal", start.elapsed());
+        Ok(())
+    }
+
+    /// Tries to acquire the session-level advisory lock, without waiting for it.
+    /// The lock is held until it's released or the connection is closed.
+    pub async fn try_advisory_lock(&mut self, lock_id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let locked = sqlx::query!(r#"SELECT pg_try_advisory_lock($1) AS "locked!""#, lock_id)
+            .fetch_one(self.0.conn())
+            .await?
+            .locked;
+
+        metrics::histogram!("sql.misc.try_advisory_lock", start.elapsed());
+        Ok(locked)
+    }
+
+    /// Checks whether the advisory lock is held by the current connection.
+    pub async fn holds_advisory_lock(&mut self, lock_id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        // The `bigint` lock key is stored as two halves in the `classid` and `objid` columns.
+        let held = sqlx::query!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM pg_locks
+                WHERE locktype = 'advisory' AND pid = pg_backend_pid() AND granted
+                    AND objsubid = 1 AND ((classid::bigint << 32) | objid::bigint) = $1
+            ) AS "held!"
+            "#,
+            lock_id
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .held;
+
+        metrics::histogram!("sql.misc.holds_advisory_lock", start.elapsed());
+        Ok(held)
+    }
+
+    /// Releases the advisory lock. Returns `false` if the lock wasn't held.
+    pub async fn advisory_unlock(&mut self, lock_id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let unlocked = sqlx::query!(r#"SELECT pg_advisory_unlock($1) AS "unlocked!""#, lock_id)
+            .fetch_one(self.0.conn())
+            .await?
+            .unlocked;
+
+        metrics::histogram!("sql.misc.advisory_unlock", start.elapsed());
+        Ok(unlocked)
+    }
+
+    /// Loads the pipeline stages paused by the operators.
+    pub async fn load_paused_pipeline_stages(&mut self) -> QueryResult<Vec<PipelineStagePause>> {
+        let start = Instant::now();
+        let pauses = sqlx::query_as!(
+            StoragePipelineStagePause,
+            "SELECT * FROM paused_pipeline_stages ORDER BY paused_at"
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|pause| {
+            Ok(PipelineStagePause {
+                stage: pause.stage.parse().map_err(anyhow::Error::msg)?,
+                reason: pause.reason,
+                paused_at: pause.paused_at,
+            })
+        })
+        .collect::<QueryResult<_>>()?;
+
+        metrics::histogram!("sql.misc.load_paused_pipeline_stages", start.elapsed());
+        Ok(pauses)
+    }
+
+    /// Pauses the pipeline stage, the reason is updated if the stage is already paused.
+    pub async fn pause_pipeline_stage(
+        &mut self,
+        stage: PipelineStage,
+        reason: &str,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO paused_pipeline_stages ( stage, reason, paused_at )
+            VALUES ( $1, $2, now() )
+            ON CONFLICT (stage)
+            DO
+              UPDATE SET reason = $2
+            "#,
+            stage.as_str(),
+            reason
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.misc.pause_pipeline_stage", start.elapsed());
+        Ok(())
+    }
+
+    /// Resumes the pipeline stage. Returns `false` if the stage wasn't paused.
+    pub async fn resume_pipeline_stage(&mut self, stage: PipelineStage) -> QueryResult<bool> {
+        let start = Instant::now();
+        let resumed = sqlx::query!(
+            "DELETE FROM paused_pipeline_stages WHERE stage = $1",
+            stage.as_str()
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            > 0;
+
+        metrics::histogram!("sql.misc.resume_pipeline_stage", start.elapsed());
+        Ok(resumed)
+    }
 }
@@ -1,4 +1,5 @@
 // External imports
+use chrono::{DateTime, Utc};
 use sqlx::types::BigDecimal;
 // Workspace imports
 // Local imports
@@ -13,3 +14,29 @@ pub struct Subsidy {
     pub full_cost_token: BigDecimal,
     pub subsidy_type: String,
 }
+
+/// Fee quote issued by the fee ticker along with the inputs it was calculated from.
+/// Fees are denominated in the token base units, prices are in USD for one token.
+#[derive(Debug, Clone)]
+pub struct FeeQuote {
+    /// Operation type the fee is calculated for, `Batch` for the batch quotes.
+    pub fee_type: String,
+    pub token_id: TokenId,
+    pub gas_price_wei: BigDecimal,
+    /// Gas price scaled by the risk coefficient, which is actually used in the fee.
+    pub scaled_gas_price_wei: BigDecimal,
+    pub eth_price_usd: BigDecimal,
+    pub token_price_usd: BigDecimal,
+    pub gas_tx_amount: BigDecimal,
+    pub zkp_fee: BigDecimal,
+    pub gas_fee: BigDecimal,
+    pub total_fee: BigDecimal,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoragePipelineStagePause {
+    pub stage: String,
+    pub reason: String,
+    pub paused_at: DateTime<Utc>,
+}
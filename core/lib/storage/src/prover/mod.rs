@@ -1,18 +1,27 @@
 // Built-in deps
-use std::time::Instant;
+use std::time::{Duration, Instant};
 // External imports
 use anyhow::format_err;
 // Workspace imports
 use zksync_types::BlockNumber;
 // Local imports
-use self::records::{StorageProverJobQueue, StoredAggregatedProof, StoredProof};
+use self::records::{
+    StorageProverJobAudit, StorageProverJobQueue, StorageProverToken, StorageQuarantinedProverJob,
+    StorageRejectedProof, StoredAggregatedProof, StoredProof,
+};
 use crate::chain::operations::OperationsSchema;
+use crate::compression::{decode_json, decompress_json, encode_json};
 use crate::prover::records::StorageBlockWitness;
 use crate::{QueryResult, StorageProcessor};
 use chrono::{TimeZone, Utc};
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
 use zksync_types::aggregated_operations::AggregatedActionType;
-use zksync_types::prover::{ProverJob, ProverJobStatus, ProverJobType};
+use zksync_types::prover::{
+    ProofRejectionCause, ProverJob, ProverJobStatus, ProverJobType, ProverQueueStats,
+    ProverWorkClass, ProverWorkClassQueue, StoredArtifact, URGENT_JOB_PRIORITY,
+};
+use zksync_utils::compression;
+use zksync_utils::metrics_labels::report_block_processed;
 
 pub mod records;
 
@@ -27,8 +36,9 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
     pub async fn pending_jobs_count(&mut self) -> QueryResult<u32> {
         let start = Instant::now();
         let pending_jobs_count = sqlx::query!(
-            "SELECT COUNT(*) FROM prover_job_queue WHERE job_status != $1",
-            ProverJobStatus::Done.to_number()
+            "SELECT COUNT(*) FROM prover_job_queue WHERE job_status != $1 AND job_status != $2",
+            ProverJobStatus::Done.to_number(),
+            ProverJobStatus::Quarantined.to_number()
         )
         .fetch_one(self.0.conn())
         .await?
@@ -38,6 +48,46 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(pending_jobs_count)
     }
 
+    /// Returns the amount of the idle and in progress jobs along with the oldest job
+    /// which is not proven yet.
+    pub async fn prover_queue_stats(&mut self) -> QueryResult<ProverQueueStats> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let counts = sqlx::query!(
+            r#"
+                SELECT COUNT(*) FILTER (WHERE job_status = $1) AS idle_jobs,
+                    COUNT(*) FILTER (WHERE job_status = $2) AS in_progress_jobs
+                FROM prover_job_queue
+            "#,
+            ProverJobStatus::Idle.to_number(),
+            ProverJobStatus::InProgress.to_number()
+        )
+        .fetch_one(transaction.conn())
+        .await?;
+        let oldest_job = sqlx::query!(
+            "SELECT first_block, created_at FROM prover_job_queue
+            WHERE job_status = $1 OR job_status = $2
+            ORDER BY first_block, created_at
+            LIMIT 1",
+            ProverJobStatus::Idle.to_number(),
+            ProverJobStatus::InProgress.to_number()
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        let stats = ProverQueueStats {
+            idle_jobs: counts.idle_jobs.unwrap_or_default() as u32,
+            in_progress_jobs: counts.in_progress_jobs.unwrap_or_default() as u32,
+            oldest_unproven_block: oldest_job
+                .as_ref()
+                .map(|job| BlockNumber(job.first_block as u32)),
+            oldest_unproven_block_created_at: oldest_job.map(|job| job.created_at),
+        };
+        metrics::histogram!("sql", start.elapsed(), "prover" => "prover_queue_stats");
+        Ok(stats)
+    }
+
     pub async fn add_prover_job_to_job_queue(
         &mut self,
         first_block: BlockNumber,
@@ -68,21 +118,406 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(())
     }
 
-    pub async fn mark_stale_jobs_as_idle(&mut self) -> QueryResult<()> {
+    /// Returns the jobs of the provers that didn't send heartbeats for `heartbeat_timeout` to the queue.
+    /// Jobs that were assigned `max_attempts` times already are quarantined instead, so they don't
+    /// take the provers anymore. Returns the diagnostic records of the quarantined jobs.
+    pub async fn mark_stale_jobs_as_idle(
+        &mut self,
+        heartbeat_timeout: Duration,
+        max_attempts: u32,
+    ) -> QueryResult<Vec<StorageQuarantinedProverJob>> {
         let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let heartbeat_timeout = heartbeat_timeout.as_secs_f64();
+        let max_attempts = max_attempts as i32;
+
         let result = sqlx::query!(
-            "UPDATE prover_job_queue SET (job_status, updated_at, updated_by) = ($1, now(), 'server_clean_idle')
-            WHERE job_status = $2 AND (now() - INTERVAL '120 seconds') >= updated_at RETURNING id",
+            "UPDATE prover_job_queue
+            SET (job_status, updated_at, updated_by, attempts) = ($1, now(), 'server_clean_idle', attempts + 1)
+            WHERE job_status = $2 AND (now() - make_interval(secs => $3)) >= updated_at
+                AND attempts + 1 < $4
+            RETURNING id",
             ProverJobStatus::Idle.to_number(),
             ProverJobStatus::InProgress.to_number(),
+            heartbeat_timeout,
+            max_attempts,
         )
-        .fetch_all(self.0.conn())
+        .fetch_all(transaction.conn())
         .await?;
+        let quarantined_jobs = sqlx::query_as!(
+            StorageQuarantinedProverJob,
+            r#"
+                WITH quarantined AS (
+                    UPDATE prover_job_queue
+                    SET (job_status, updated_at, attempts) = ($1, now(), attempts + 1)
+                    WHERE job_status = $2 AND (now() - make_interval(secs => $3)) >= updated_at
+                        AND attempts + 1 >= $4
+                    RETURNING id, job_type, first_block, last_block, attempts, updated_by
+                )
+                INSERT INTO quarantined_prover_jobs (job_id, job_type, first_block, last_block, attempts, last_prover, reason)
+                SELECT id, job_type, first_block, last_block, attempts, updated_by, $5 FROM quarantined
+                RETURNING *
+            "#,
+            ProverJobStatus::Quarantined.to_number(),
+            ProverJobStatus::InProgress.to_number(),
+            heartbeat_timeout,
+            max_attempts,
+            format!("no heartbeats from the prover for {} seconds", heartbeat_timeout),
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
         metrics::counter!("stale_jobs", result.len() as u64);
+        metrics::counter!("quarantined_jobs", quarantined_jobs.len() as u64);
         metrics::histogram!("sql", start.elapsed(), "prover" => "mark_stale_jobs_as_idle");
+        Ok(quarantined_jobs)
+    }
+
+    /// Loads the diagnostic records of the quarantined jobs.
+    pub async fn quarantined_prover_jobs(
+        &mut self,
+    ) -> QueryResult<Vec<StorageQuarantinedProverJob>> {
+        let start = Instant::now();
+        let quarantined_jobs = sqlx::query_as!(
+            StorageQuarantinedProverJob,
+            "SELECT * FROM quarantined_prover_jobs ORDER BY job_id"
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "quarantined_prover_jobs");
+        Ok(quarantined_jobs)
+    }
+
+    /// Returns the idle or quarantined job to the queue with the reset attempts counter,
+    /// so it's assigned to the next free prover.
+    ///
+    /// The job in progress is requeued only if `force` is set, since it is proven twice otherwise.
+    /// Returns `false` if there is no such job or it can't be requeued.
+    pub async fn requeue_prover_job(
+        &mut self,
+        job_id: i32,
+        updated_by: &str,
+        force: bool,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut statuses = vec![
+            ProverJobStatus::Idle.to_number(),
+            ProverJobStatus::Quarantined.to_number(),
+        ];
+        if force {
+            statuses.push(ProverJobStatus::InProgress.to_number());
+        }
+        let mut transaction = self.0.start_transaction().await?;
+        let requeued = sqlx::query!(
+            "UPDATE prover_job_queue
+            SET (job_status, updated_at, updated_by, attempts) = ($1, now(), $2, 0)
+            WHERE id = $3 AND job_status = ANY($4)
+            RETURNING id",
+            ProverJobStatus::Idle.to_number(),
+            updated_by,
+            job_id,
+            &statuses,
+        )
+        .fetch_optional(transaction.conn())
+        .await?
+        .is_some();
+        if requeued {
+            // The job may be quarantined again, so the outdated record is removed.
+            sqlx::query!(
+                "DELETE FROM quarantined_prover_jobs WHERE job_id = $1",
+                job_id
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "requeue_prover_job");
+        Ok(requeued)
+    }
+
+    /// Records the proof of the job that failed the verification (or the failure to prove the job,
+    /// depending on the `cause`) and returns the job to the queue, so it is proven again.
+    /// The rejected proof counts as a failed attempt, the job is quarantined once it was attempted
+    /// `max_attempts` times. Returns the diagnostic record of the job if it was quarantined.
+    ///
+    /// If `prover_name` is not provided, the rejected proof is attributed to the last prover that
+    /// was working on the job.
+    pub async fn reject_proof(
+        &mut self,
+        job_id: i32,
+        prover_name: Option<&str>,
+        cause: ProofRejectionCause,
+        reason: &str,
+        max_attempts: u32,
+    ) -> QueryResult<Option<StorageQuarantinedProverJob>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let max_attempts = max_attempts as i32;
+
+        sqlx::query!(
+            "INSERT INTO rejected_proofs (job_id, first_block, last_block, prover_name, reason, cause)
+            SELECT id, first_block, last_block, COALESCE($2, updated_by), $3, $4
+            FROM prover_job_queue WHERE id = $1",
+            job_id,
+            prover_name,
+            reason,
+            cause.to_number(),
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "UPDATE prover_job_queue
+            SET (job_status, updated_at, updated_by, attempts) = ($1, now(), 'server_reject_proof', attempts + 1)
+            WHERE id = $2 AND job_status = $3 AND attempts + 1 < $4",
+            ProverJobStatus::Idle.to_number(),
+            job_id,
+            ProverJobStatus::InProgress.to_number(),
+            max_attempts,
+        )
+        .execute(transaction.conn())
+        .await?;
+        let quarantined_job = sqlx::query_as!(
+            StorageQuarantinedProverJob,
+            r#"
+                WITH quarantined AS (
+                    UPDATE prover_job_queue
+                    SET (job_status, updated_at, attempts) = ($1, now(), attempts + 1)
+                    WHERE id = $2 AND job_status = $3 AND attempts + 1 >= $4
+                    RETURNING id, job_type, first_block, last_block, attempts, updated_by
+                )
+                INSERT INTO quarantined_prover_jobs (job_id, job_type, first_block, last_block, attempts, last_prover, reason)
+                SELECT id, job_type, first_block, last_block, attempts, COALESCE($5, updated_by), $6 FROM quarantined
+                RETURNING *
+            "#,
+            ProverJobStatus::Quarantined.to_number(),
+            job_id,
+            ProverJobStatus::InProgress.to_number(),
+            max_attempts,
+            prover_name,
+            reason,
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        match cause {
+            ProofRejectionCause::InvalidProof => metrics::counter!("rejected_proofs", 1),
+            ProofRejectionCause::ProvingFailed => metrics::counter!("failed_prover_jobs", 1),
+        }
+        if quarantined_job.is_some() {
+            metrics::counter!("quarantined_jobs", 1);
+        }
+        metrics::histogram!("sql", start.elapsed(), "prover" => "reject_proof");
+        Ok(quarantined_job)
+    }
+
+    /// Loads the diagnostic records of the rejected proofs and of the failures to prove the jobs.
+    pub async fn rejected_proofs(&mut self) -> QueryResult<Vec<StorageRejectedProof>> {
+        let start = Instant::now();
+        let rejected_proofs = sqlx::query_as!(
+            StorageRejectedProof,
+            "SELECT * FROM rejected_proofs ORDER BY id"
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "rejected_proofs");
+        Ok(rejected_proofs)
+    }
+
+    /// Stores the hash of the prover authentication token. The previous token of the prover
+    /// is replaced, even if it was revoked.
+    pub async fn store_prover_token(
+        &mut self,
+        prover_name: &str,
+        token_hash: &str,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO prover_tokens (prover_name, token_hash)
+            VALUES ($1, $2)
+            ON CONFLICT (prover_name)
+            DO UPDATE SET (token_hash, created_at, revoked_at) = ($2, now(), NULL)",
+            prover_name,
+            token_hash,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "store_prover_token");
         Ok(())
     }
 
+    /// Returns the name of the prover the token was issued to, if the token is not revoked.
+    pub async fn prover_name_by_token_hash(
+        &mut self,
+        token_hash: &str,
+    ) -> QueryResult<Option<String>> {
+        let start = Instant::now();
+        let prover_name = sqlx::query!(
+            "SELECT prover_name FROM prover_tokens WHERE token_hash = $1 AND revoked_at IS NULL",
+            token_hash,
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|record| record.prover_name);
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "prover_name_by_token_hash");
+        Ok(prover_name)
+    }
+
+    /// Revokes the token of the prover and returns the jobs it's working on to the queue,
+    /// so the prover is isolated at once. Returns `false` if the prover has no active token.
+    pub async fn revoke_prover_token(&mut self, prover_name: &str) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let revoked = sqlx::query!(
+            "UPDATE prover_tokens SET revoked_at = now()
+            WHERE prover_name = $1 AND revoked_at IS NULL",
+            prover_name,
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected()
+            > 0;
+        transaction
+            .prover_schema()
+            .record_prover_stop(prover_name)
+            .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "revoke_prover_token");
+        Ok(revoked)
+    }
+
+    /// Loads the tokens issued to the provers, including the revoked ones.
+    pub async fn prover_tokens(&mut self) -> QueryResult<Vec<StorageProverToken>> {
+        let start = Instant::now();
+        let tokens = sqlx::query_as!(
+            StorageProverToken,
+            "SELECT prover_name, created_at, revoked_at FROM prover_tokens ORDER BY prover_name"
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "prover_tokens");
+        Ok(tokens)
+    }
+
+    /// Records that the job was assigned to the prover.
+    pub async fn record_prover_job_assignment(
+        &mut self,
+        job_id: i32,
+        prover_name: &str,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO prover_job_audit (job_id, prover_name, first_block, last_block)
+            SELECT id, $2, first_block, last_block FROM prover_job_queue WHERE id = $1",
+            job_id,
+            prover_name,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "record_prover_job_assignment");
+        Ok(())
+    }
+
+    /// Records that the proof of the job was published. The latest assignment of the job
+    /// is updated. If `published_by` is not provided, the proof is attributed to the prover
+    /// the job was assigned to.
+    pub async fn record_prover_job_published(
+        &mut self,
+        job_id: i32,
+        published_by: Option<&str>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE prover_job_audit SET (published_at, published_by) = (now(), COALESCE($2, prover_name))
+            WHERE id = (
+                SELECT id FROM prover_job_audit
+                WHERE job_id = $1 AND published_at IS NULL
+                ORDER BY id DESC
+                LIMIT 1
+            )",
+            job_id,
+            published_by,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "record_prover_job_published");
+        Ok(())
+    }
+
+    /// Returns the name of the prover the job was assigned to the last time.
+    pub async fn prover_job_assignee(&mut self, job_id: i32) -> QueryResult<Option<String>> {
+        let start = Instant::now();
+        let prover_name = sqlx::query!(
+            "SELECT prover_name FROM prover_job_audit
+            WHERE job_id = $1
+            ORDER BY id DESC
+            LIMIT 1",
+            job_id,
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|record| record.prover_name);
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "prover_job_assignee");
+        Ok(prover_name)
+    }
+
+    /// Loads the latest job assignments, optionally only the ones of the given prover.
+    pub async fn prover_job_audit(
+        &mut self,
+        prover_name: Option<&str>,
+        limit: u32,
+    ) -> QueryResult<Vec<StorageProverJobAudit>> {
+        let start = Instant::now();
+        let records = sqlx::query_as!(
+            StorageProverJobAudit,
+            "SELECT * FROM prover_job_audit
+            WHERE $1::text IS NULL OR prover_name = $1
+            ORDER BY id DESC
+            LIMIT $2",
+            prover_name,
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "prover_job_audit");
+        Ok(records)
+    }
+
+    /// Moves the idle jobs that wait in the queue for longer than `urgent_job_timeout` to the
+    /// urgent lane, so they are assigned before the other jobs. Returns the amount of moved jobs.
+    pub async fn move_jobs_to_urgent_lane(
+        &mut self,
+        urgent_job_timeout: Duration,
+    ) -> QueryResult<usize> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            "UPDATE prover_job_queue SET job_priority = $1
+            WHERE job_status = $2 AND job_priority > $1
+                AND created_at <= now() - make_interval(secs => $3)
+            RETURNING id",
+            URGENT_JOB_PRIORITY,
+            ProverJobStatus::Idle.to_number(),
+            urgent_job_timeout.as_secs_f64(),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+        metrics::counter!("urgent_jobs", result.len() as u64);
+        metrics::histogram!("sql", start.elapsed(), "prover" => "move_jobs_to_urgent_lane");
+        Ok(result.len())
+    }
+
     pub async fn get_idle_prover_job_from_job_queue(&mut self) -> QueryResult<Option<ProverJob>> {
         let start = Instant::now();
         // Select the block to prove.
@@ -93,7 +528,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             r#"
                 SELECT * FROM prover_job_queue
                 WHERE job_status = $1
-                ORDER BY (job_priority, id, first_block)
+                ORDER BY (job_priority, first_block, id)
                 FOR UPDATE SKIP LOCKED
                 LIMIT 1
             "#,
@@ -129,6 +564,103 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(prover_job)
     }
 
+    /// Returns the idle jobs grouped by the work class.
+    pub async fn idle_jobs_by_work_class(&mut self) -> QueryResult<Vec<ProverWorkClassQueue>> {
+        let start = Instant::now();
+        let records = sqlx::query!(
+            r#"
+                SELECT job_type, (job_data->'BlockProof'->>1)::bigint AS block_size,
+                    MIN(job_priority) AS job_priority, COUNT(*) AS idle_jobs
+                FROM prover_job_queue
+                WHERE job_status = $1
+                GROUP BY job_type, (job_data->'BlockProof'->>1)::bigint
+            "#,
+            ProverJobStatus::Idle.to_number()
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let queues = records
+            .into_iter()
+            .map(|record| {
+                let work_class = match (record.job_type.as_str(), record.block_size) {
+                    ("SINGLE_PROOF", Some(block_size)) => ProverWorkClass::SingleProof {
+                        block_size: block_size as usize,
+                    },
+                    ("AGGREGATED_PROOF", _) => ProverWorkClass::AggregatedProof,
+                    (job_type, block_size) => {
+                        return Err(format_err!(
+                            "Unexpected prover job type {} with the block size {:?}",
+                            job_type,
+                            block_size
+                        ))
+                    }
+                };
+
+                Ok(ProverWorkClassQueue {
+                    work_class,
+                    job_priority: record.job_priority.unwrap_or_default(),
+                    idle_jobs: record.idle_jobs.unwrap_or_default() as u32,
+                })
+            })
+            .collect();
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "idle_jobs_by_work_class");
+        queues
+    }
+
+    /// Takes the idle job of the given work class with the highest priority.
+    pub async fn get_idle_prover_job_of_work_class(
+        &mut self,
+        work_class: ProverWorkClass,
+    ) -> QueryResult<Option<ProverJob>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let prover_job_queue = sqlx::query_as!(
+            StorageProverJobQueue,
+            r#"
+                SELECT * FROM prover_job_queue
+                WHERE job_status = $1 AND job_type = $2
+                    AND ($3::bigint IS NULL OR (job_data->'BlockProof'->>1)::bigint = $3)
+                ORDER BY (job_priority, first_block, id)
+                FOR UPDATE SKIP LOCKED
+                LIMIT 1
+            "#,
+            ProverJobStatus::Idle.to_number(),
+            work_class.job_type().to_string(),
+            work_class.block_size().map(|block_size| block_size as i64)
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+
+        let prover_job = if let Some(job) = prover_job_queue {
+            sqlx::query!(
+                r#"
+                UPDATE prover_job_queue
+                SET (job_status, updated_at, updated_by) = ($1, now(), 'server_give_job')
+                WHERE id = $2;
+            "#,
+                ProverJobStatus::InProgress.to_number(),
+                job.id,
+            )
+            .execute(transaction.conn())
+            .await?;
+
+            Some(ProverJob::new(
+                job.id,
+                BlockNumber(job.first_block as u32),
+                BlockNumber(job.last_block as u32),
+                job.job_data,
+            ))
+        } else {
+            None
+        };
+        transaction.commit().await?;
+        metrics::histogram!("sql", start.elapsed(), "prover" => "get_idle_prover_job_of_work_class");
+        Ok(prover_job)
+    }
+
     /// Updates the state of ongoing prover job.
     pub async fn record_prover_is_working(
         &mut self,
@@ -150,22 +682,56 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(())
     }
 
-    /// Marks the prover as stopped.
-    pub async fn record_prover_stop(&mut self, prover_name: &str) -> QueryResult<()> {
+    /// Marks the prover as stopped and returns its jobs to the queue. The stop counts as a failed
+    /// attempt, since the prover may stop because of the job, e.g. if proving it crashes or hangs.
+    /// Jobs that were attempted `max_attempts` times are quarantined instead. Returns the diagnostic
+    /// records of the quarantined jobs.
+    pub async fn record_prover_stop(
+        &mut self,
+        prover_name: &str,
+        max_attempts: u32,
+    ) -> QueryResult<Vec<StorageQuarantinedProverJob>> {
         let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let max_attempts = max_attempts as i32;
+
         sqlx::query!(
             "UPDATE prover_job_queue
-            SET (updated_at, job_status) = (now(), $1)
-            WHERE updated_by = $2 and job_status = $3",
+            SET (updated_at, job_status, attempts) = (now(), $1, attempts + 1)
+            WHERE updated_by = $2 AND job_status = $3 AND attempts + 1 < $4",
             ProverJobStatus::Idle.to_number(),
             prover_name,
-            ProverJobStatus::InProgress.to_number()
+            ProverJobStatus::InProgress.to_number(),
+            max_attempts,
         )
-        .execute(self.0.conn())
+        .execute(transaction.conn())
         .await?;
+        let quarantined_jobs = sqlx::query_as!(
+            StorageQuarantinedProverJob,
+            r#"
+                WITH quarantined AS (
+                    UPDATE prover_job_queue
+                    SET (job_status, updated_at, attempts) = ($1, now(), attempts + 1)
+                    WHERE updated_by = $2 AND job_status = $3 AND attempts + 1 >= $4
+                    RETURNING id, job_type, first_block, last_block, attempts, updated_by
+                )
+                INSERT INTO quarantined_prover_jobs (job_id, job_type, first_block, last_block, attempts, last_prover, reason)
+                SELECT id, job_type, first_block, last_block, attempts, updated_by, $5 FROM quarantined
+                RETURNING *
+            "#,
+            ProverJobStatus::Quarantined.to_number(),
+            prover_name,
+            ProverJobStatus::InProgress.to_number(),
+            max_attempts,
+            "the prover stopped while working on the job",
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        transaction.commit().await?;
 
+        metrics::counter!("quarantined_jobs", quarantined_jobs.len() as u64);
         metrics::histogram!("sql", start.elapsed(), "prover" => "record_prover_stop");
-        Ok(())
+        Ok(quarantined_jobs)
     }
 
     /// Stores the proof for a block.
@@ -173,7 +739,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         &mut self,
         job_id: i32,
         block_number: BlockNumber,
-        proof: &SingleProof,
+        proof: StoredArtifact<&SingleProof>,
     ) -> QueryResult<()> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
@@ -193,11 +759,19 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             return Err(format_err!("Missing job for stored proof"));
         }
 
+        let ((proof, proof_compressed), object_key) = match proof {
+            StoredArtifact::Inline(proof) => {
+                (encode_json(proof, transaction.compression_level()), None)
+            }
+            StoredArtifact::Object(object_key) => ((None, None), Some(object_key)),
+        };
         sqlx::query!(
-            "INSERT INTO proofs (block_number, proof)
-            VALUES ($1, $2)",
+            "INSERT INTO proofs (block_number, proof, proof_compressed, object_key)
+            VALUES ($1, $2, $3, $4)",
             i64::from(*block_number),
-            serde_json::to_value(proof).unwrap()
+            proof,
+            proof_compressed,
+            object_key
         )
         .execute(transaction.conn())
         .await?;
@@ -229,8 +803,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
                 let time = Utc.timestamp(block.timestamp.unwrap_or_default(), 0);
                 // It's almost impossible situation, but it could be triggered in tests
                 let duration = (Utc::now() - time).to_std().unwrap_or_default();
-                let labels = vec![("stage", stage.clone())];
-                metrics::histogram!("process_block", duration, &labels);
+                report_block_processed(&stage, block_number, duration);
             } else {
                 vlog::error!("Block for proof doesn't exist")
             }
@@ -243,7 +816,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         job_id: i32,
         first_block: BlockNumber,
         last_block: BlockNumber,
-        proof: &AggregatedProof,
+        proof: StoredArtifact<&AggregatedProof>,
     ) -> QueryResult<()> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
@@ -263,12 +836,20 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             return Err(format_err!("Missing job for stored aggregated proof"));
         }
 
+        let ((proof, proof_compressed), object_key) = match proof {
+            StoredArtifact::Inline(proof) => {
+                (encode_json(proof, transaction.compression_level()), None)
+            }
+            StoredArtifact::Object(object_key) => ((None, None), Some(object_key)),
+        };
         sqlx::query!(
-            "INSERT INTO aggregated_proofs (first_block, last_block, proof)
-            VALUES ($1, $2, $3)",
+            "INSERT INTO aggregated_proofs (first_block, last_block, proof, proof_compressed, object_key)
+            VALUES ($1, $2, $3, $4, $5)",
             i64::from(*first_block),
             i64::from(*last_block),
-            serde_json::to_value(proof).unwrap()
+            proof,
+            proof_compressed,
+            object_key
         )
         .execute(transaction.conn())
         .await?;
@@ -286,7 +867,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
     pub async fn load_proof(
         &mut self,
         block_number: BlockNumber,
-    ) -> QueryResult<Option<SingleProof>> {
+    ) -> QueryResult<Option<StoredArtifact<SingleProof>>> {
         let start = Instant::now();
         let proof = sqlx::query_as!(
             StoredProof,
@@ -295,18 +876,21 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         )
         .fetch_optional(self.0.conn())
         .await?
-        .map(|stored| serde_json::from_value(stored.proof).unwrap());
+        .map(|stored| {
+            let proof = decode_json(stored.proof, stored.proof_compressed);
+            stored_artifact(proof, stored.object_key)
+        });
 
         metrics::histogram!("sql", start.elapsed(), "prover" => "load_proof");
         Ok(proof)
     }
 
-    /// Gets the stored proof for a block.
+    /// Gets the stored aggregated proof for blocks.
     pub async fn load_aggregated_proof(
         &mut self,
         first_block: BlockNumber,
         last_block: BlockNumber,
-    ) -> QueryResult<Option<AggregatedProof>> {
+    ) -> QueryResult<Option<StoredArtifact<AggregatedProof>>> {
         let start = Instant::now();
         let proof = sqlx::query_as!(
             StoredAggregatedProof,
@@ -316,7 +900,10 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         )
         .fetch_optional(self.0.conn())
         .await?
-        .map(|stored| serde_json::from_value(stored.proof).unwrap());
+        .map(|stored| {
+            let proof = decode_json(stored.proof, stored.proof_compressed);
+            stored_artifact(proof, stored.object_key)
+        });
 
         metrics::histogram!("sql", start.elapsed(), "prover" => "load_aggregated_proof");
         Ok(proof)
@@ -326,17 +913,33 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
     pub async fn store_witness(
         &mut self,
         block: BlockNumber,
-        witness: serde_json::Value,
+        witness: StoredArtifact<serde_json::Value>,
     ) -> QueryResult<()> {
         let start = Instant::now();
-        let witness_str = serde_json::to_string(&witness).expect("Failed to serialize witness");
+        let (witness_str, witness_compressed, object_key) = match witness {
+            StoredArtifact::Inline(witness) => {
+                let witness_str =
+                    serde_json::to_string(&witness).expect("Failed to serialize witness");
+                match self.0.compression_level() {
+                    Some(level) => (
+                        None,
+                        Some(compression::compress(witness_str.as_bytes(), level)),
+                        None,
+                    ),
+                    None => (Some(witness_str), None, None),
+                }
+            }
+            StoredArtifact::Object(object_key) => (None, None, Some(object_key)),
+        };
         sqlx::query!(
-            "INSERT INTO block_witness (block, witness)
-            VALUES ($1, $2)
+            "INSERT INTO block_witness (block, witness, witness_compressed, object_key)
+            VALUES ($1, $2, $3, $4)
             ON CONFLICT (block)
             DO NOTHING",
             i64::from(*block),
-            witness_str
+            witness_str,
+            witness_compressed,
+            object_key
         )
         .execute(self.0.conn())
         .await?;
@@ -349,7 +952,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
     pub async fn get_witness(
         &mut self,
         block_number: BlockNumber,
-    ) -> QueryResult<Option<serde_json::Value>> {
+    ) -> QueryResult<Option<StoredArtifact<serde_json::Value>>> {
         let start = Instant::now();
         let block_witness = sqlx::query_as!(
             StorageBlockWitness,
@@ -360,8 +963,33 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         .await?;
 
         metrics::histogram!("sql", start.elapsed(), "prover" => "get_witness");
-        Ok(block_witness
-            .map(|w| serde_json::from_str(&w.witness).expect("Failed to deserialize witness")))
+        Ok(block_witness.map(|w| {
+            let witness = match (w.witness, w.witness_compressed) {
+                (Some(witness), _) => {
+                    Some(serde_json::from_str(&witness).expect("Failed to deserialize witness"))
+                }
+                (None, Some(compressed)) => Some(decompress_json(&compressed)),
+                (None, None) => None,
+            };
+            stored_artifact(witness, w.object_key)
+        }))
+    }
+
+    /// Checks whether the object is referenced by any stored witness or proof.
+    pub async fn is_artifact_referenced(&mut self, object_key: &str) -> QueryResult<bool> {
+        let start = Instant::now();
+        let referenced = sqlx::query!(
+            r#"SELECT EXISTS(SELECT 1 FROM block_witness WHERE object_key = $1)
+                OR EXISTS(SELECT 1 FROM proofs WHERE object_key = $1)
+                OR EXISTS(SELECT 1 FROM aggregated_proofs WHERE object_key = $1) AS "referenced!""#,
+            object_key
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .referenced;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "is_artifact_referenced");
+        Ok(referenced)
     }
 
     pub async fn get_last_block_prover_job_queue(
@@ -406,6 +1034,40 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(result)
     }
 
+    /// Returns the oldest block which single proof is not stored yet: the first block of the oldest
+    /// single proof job which is not done, or the block following the last single proof job if all
+    /// of them are done.
+    ///
+    /// The witnesses are only generated for the blocks close to this one, so the amount of the
+    /// witnesses waiting for their proofs is bounded.
+    pub async fn first_unproven_block(&mut self) -> QueryResult<BlockNumber> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let first_block = sqlx::query!(
+            "SELECT min(first_block) FROM prover_job_queue
+            WHERE job_type = $1 AND job_status != $2",
+            ProverJobType::SingleProof.to_string(),
+            ProverJobStatus::Done.to_number()
+        )
+        .fetch_one(transaction.conn())
+        .await?
+        .min;
+
+        let result = match first_block {
+            Some(first_block) => BlockNumber(first_block as u32),
+            None => {
+                ProverSchema(&mut transaction)
+                    .get_last_block_prover_job_queue(ProverJobType::SingleProof)
+                    .await?
+                    + 1
+            }
+        };
+        transaction.commit().await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "first_unproven_block");
+        Ok(result)
+    }
+
     // Removes witnesses for blocks with number greater than `last_block`
     pub async fn remove_witnesses(&mut self, last_block: BlockNumber) -> QueryResult<()> {
         let start = Instant::now();
@@ -471,3 +1133,13 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(())
     }
 }
+
+/// Restores the artifact from the columns of the artifact itself and its object key.
+/// Exactly one of them is set, which is ensured by the table constraints.
+fn stored_artifact<T>(inline: Option<T>, object_key: Option<String>) -> StoredArtifact<T> {
+    match (inline, object_key) {
+        (Some(artifact), _) => StoredArtifact::Inline(artifact),
+        (None, Some(object_key)) => StoredArtifact::Object(object_key),
+        (None, None) => panic!("Stored prover artifact has neither a value nor an object key"),
+    }
+}
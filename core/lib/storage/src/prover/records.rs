@@ -23,16 +23,24 @@ pub struct NewProof {
 #[derive(Debug, FromRow)]
 pub struct StoredProof {
     pub block_number: i64,
-    pub proof: serde_json::Value,
+    pub proof: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
+    /// Key of the proof in the object storage, if it's not stored in the database.
+    pub object_key: Option<String>,
+    /// Compressed JSON of the proof, if it's stored compressed.
+    pub proof_compressed: Option<Vec<u8>>,
 }
 
 #[derive(Debug, FromRow)]
 pub struct StoredAggregatedProof {
     pub first_block: i64,
     pub last_block: i64,
-    pub proof: serde_json::Value,
+    pub proof: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
+    /// Key of the proof in the object storage, if it's not stored in the database.
+    pub object_key: Option<String>,
+    /// Compressed JSON of the proof, if it's stored compressed.
+    pub proof_compressed: Option<Vec<u8>>,
 }
 
 // Every time before a prover worker starts generating the proof, a prover run is recorded for monitoring purposes
@@ -53,7 +61,11 @@ pub struct IntegerNumber {
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct StorageBlockWitness {
     pub block: i64,
-    pub witness: String,
+    pub witness: Option<String>,
+    /// Key of the witness in the object storage, if it's not stored in the database.
+    pub object_key: Option<String>,
+    /// Compressed JSON of the witness, if it's stored compressed.
+    pub witness_compressed: Option<Vec<u8>>,
 }
 
 #[derive(Debug, FromRow)]
@@ -68,4 +80,57 @@ pub struct StorageProverJobQueue {
     pub first_block: i64,
     pub last_block: i64,
     pub job_data: serde_json::Value,
+    /// Amount of the times the job was assigned to a prover that stopped sending heartbeats.
+    pub attempts: i32,
+}
+
+/// Diagnostic record of the job that failed to be proven too many times.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageQuarantinedProverJob {
+    pub job_id: i32,
+    pub job_type: String,
+    pub first_block: i64,
+    pub last_block: i64,
+    pub attempts: i32,
+    /// Prover that was working on the job the last time.
+    pub last_prover: String,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Diagnostic record of the proof that failed the verification, or of the job the prover
+/// failed to prove.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageRejectedProof {
+    pub id: i32,
+    pub job_id: i32,
+    pub first_block: i64,
+    pub last_block: i64,
+    pub prover_name: String,
+    pub reason: String,
+    /// See `ProofRejectionCause`.
+    pub cause: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Authentication token of a prover machine, the token itself is not stored.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageProverToken {
+    pub prover_name: String,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Audit record of the job assigned to the prover.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageProverJobAudit {
+    pub id: i32,
+    pub job_id: i32,
+    pub prover_name: String,
+    pub first_block: i64,
+    pub last_block: i64,
+    pub assigned_at: DateTime<Utc>,
+    pub published_at: Option<DateTime<Utc>>,
+    /// Prover that published the proof, it may differ from the prover the job was assigned to.
+    pub published_by: Option<String>,
 }
@@ -337,6 +337,7 @@ async fn test_get_account_nft_balance(mut storage: StorageProcessor<'_>) -> Quer
             decimals: 0,
             kind: TokenKind::NFT,
             is_nft: true,
+            display_symbol: "NFT".to_string(),
         })
         .await?;
     storage
@@ -348,6 +349,7 @@ async fn test_get_account_nft_balance(mut storage: StorageProcessor<'_>) -> Quer
             decimals: 0,
             kind: TokenKind::NFT,
             is_nft: true,
+            display_symbol: "SPECIAL".to_string(),
         })
         .await?;
 
@@ -467,6 +469,7 @@ async fn test_get_nft_owner(mut storage: StorageProcessor<'_>) -> QueryResult<()
             decimals: 0,
             kind: TokenKind::NFT,
             is_nft: true,
+            display_symbol: "NFT".to_string(),
         })
         .await?;
     storage
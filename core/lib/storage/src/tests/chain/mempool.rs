@@ -1,5 +1,6 @@
 // External imports
 use chrono::Utc;
+use num::{rational::Ratio, BigUint};
 // Workspace imports
 use zksync_crypto::rand::{Rng, SeedableRng, XorShiftRng};
 use zksync_types::{
@@ -7,8 +8,9 @@ use zksync_types::{
     mempool::SignedTxVariant,
     priority_ops::FullExit,
     tx::{ChangePubKey, Transfer, TxHash, Withdraw},
-    AccountId, Address, BlockNumber, ExecutedPriorityOp, ExecutedTx, FullExitOp, Nonce, PriorityOp,
-    SignedZkSyncTx, TokenId, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx, H256,
+    AccountId, Address, BlockNumber, ExecutedPriorityOp, ExecutedTx, FeeTokenPriceFloor,
+    FullExitOp, Nonce, PriorityOp, SignedZkSyncTx, TokenId, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx,
+    H256,
 };
 // Local imports
 use crate::test_data::gen_eth_sign_data;
@@ -271,6 +273,44 @@ async fn remove_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that the fee token price floors are stored and removed along with the transactions.
+#[db_test]
+async fn fee_token_price_floors(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = zksync_txs();
+    for tx in &txs[..2] {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    let hashes: Vec<_> = txs.iter().map(|tx| tx.hash()).collect();
+    let floor = FeeTokenPriceFloor {
+        token_id: TokenId(1),
+        min_usd_price: Ratio::new(BigUint::from(15u32), BigUint::from(10u32)),
+    };
+    // The floor of the last transaction isn't in the mempool, e.g. it's rejected by the mempool.
+    for &hash in &[hashes[0], hashes[1], hashes[3]] {
+        MempoolSchema(&mut storage)
+            .store_fee_token_price_floor(hash, &floor)
+            .await?;
+    }
+
+    let floors = MempoolSchema(&mut storage)
+        .load_fee_token_price_floors(&hashes[..3])
+        .await?;
+    assert_eq!(floors.len(), 2);
+    assert_eq!(floors[&hashes[0]], floor);
+    assert_eq!(floors[&hashes[1]], floor);
+
+    // Floors are removed with the transactions and by the garbage collection.
+    MempoolSchema(&mut storage).remove_txs(&hashes[..1]).await?;
+    MempoolSchema(&mut storage).collect_garbage().await?;
+    let floors = MempoolSchema(&mut storage)
+        .load_fee_token_price_floors(&hashes)
+        .await?;
+    assert_eq!(floors.len(), 1);
+    assert!(floors.contains_key(&hashes[1]));
+
+    Ok(())
+}
+
 /// Checks that already committed txs are removed by `collect_garbage` method.
 #[db_test]
 async fn collect_garbage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -525,3 +565,90 @@ async fn test_return_executed_txs_to_mempool(mut storage: StorageProcessor<'_>)
     assert_eq!(block_tx.variance_name(), "FullExit");
     Ok(())
 }
+
+/// Checks that the priority operations from the reverted Ethereum blocks are removed from the mempool.
+#[db_test]
+async fn remove_priority_ops_after_eth_block(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let priority_ops: Vec<_> = (0..4u64)
+        .map(|serial_id| PriorityOp {
+            serial_id,
+            data: ZkSyncPriorityOp::FullExit(FullExit {
+                account_id: AccountId(serial_id as u32),
+                eth_address: Address::from_low_u64_be(serial_id),
+                token: TokenId(0),
+                is_legacy: false,
+            }),
+            deadline_block: 100,
+            eth_hash: H256::from_low_u64_be(serial_id),
+            // Operations are received in the blocks 10, 11, 12 and 13.
+            eth_block: 10 + serial_id,
+            eth_block_index: Some(0),
+        })
+        .collect();
+    MempoolSchema(&mut storage)
+        .insert_priority_ops(&priority_ops[..2], true)
+        .await?;
+    MempoolSchema(&mut storage)
+        .insert_priority_ops(&priority_ops[2..], false)
+        .await?;
+
+    // Operations from the blocks 12 and 13 were reverted.
+    let mut removed_ids = MempoolSchema(&mut storage)
+        .remove_priority_ops_after_eth_block(11)
+        .await?;
+    removed_ids.sort_unstable();
+    assert_eq!(removed_ids, vec![2, 3]);
+
+    let remaining_ids: Vec<_> = MempoolSchema(&mut storage)
+        .get_confirmed_priority_ops()
+        .await?
+        .into_iter()
+        .map(|op| op.serial_id)
+        .collect();
+    assert_eq!(remaining_ids, vec![0, 1]);
+
+    // Nothing should be removed for the block range without operations.
+    assert!(MempoolSchema(&mut storage)
+        .remove_priority_ops_after_eth_block(13)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+/// Checks that the priority operations stored in the mempool are recognized.
+#[db_test]
+async fn get_known_priority_op_ids(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let priority_ops: Vec<_> = [1u64, 3]
+        .iter()
+        .map(|&serial_id| PriorityOp {
+            serial_id,
+            data: ZkSyncPriorityOp::FullExit(FullExit {
+                account_id: AccountId(serial_id as u32),
+                eth_address: Address::from_low_u64_be(serial_id),
+                token: TokenId(0),
+                is_legacy: false,
+            }),
+            deadline_block: 100,
+            eth_hash: H256::from_low_u64_be(serial_id),
+            eth_block: 10 + serial_id,
+            eth_block_index: Some(0),
+        })
+        .collect();
+    MempoolSchema(&mut storage)
+        .insert_priority_ops(&priority_ops, true)
+        .await?;
+
+    let mut known_ids = MempoolSchema(&mut storage)
+        .get_known_priority_op_ids(&[0, 1, 2, 3, 4])
+        .await?;
+    known_ids.sort_unstable();
+    assert_eq!(known_ids, vec![1, 3]);
+
+    assert!(MempoolSchema(&mut storage)
+        .get_known_priority_op_ids(&[])
+        .await?
+        .is_empty());
+
+    Ok(())
+}
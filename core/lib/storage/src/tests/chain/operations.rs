@@ -46,6 +46,43 @@ async fn aggregated_operations(mut storage: StorageProcessor<'_>) -> QueryResult
     Ok(())
 }
 
+/// Checks that the aggregated operations are stored compressed if configured,
+/// and both the compressed and uncompressed operations are loaded.
+#[db_test]
+async fn compressed_aggregated_operations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let action_type = AggregatedActionType::CommitBlocks;
+    let plain_operation = gen_unique_aggregated_operation(BlockNumber(1), action_type, 100);
+    let compressed_operation = gen_unique_aggregated_operation(BlockNumber(2), action_type, 100);
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(plain_operation.clone())
+        .await?;
+    storage.set_compression_level(Some(3));
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(compressed_operation.clone())
+        .await?;
+    storage.set_compression_level(None);
+
+    let stored_operation = OperationsSchema(&mut storage)
+        .get_stored_aggregated_operation(BlockNumber(2), action_type)
+        .await
+        .unwrap();
+    assert!(stored_operation.arguments.is_none());
+    assert!(stored_operation.arguments_compressed.is_some());
+
+    for (block_number, operation) in [(1, plain_operation), (2, compressed_operation)] {
+        let (_, loaded) = OperationsSchema(&mut storage)
+            .get_aggregated_op_that_affects_block(action_type, BlockNumber(block_number))
+            .await?
+            .expect("Aggregated operation is not stored");
+        assert_eq!(
+            serde_json::to_value(loaded).unwrap(),
+            serde_json::to_value(operation).unwrap()
+        );
+    }
+
+    Ok(())
+}
+
 /// Checks the save&load routine for executed operations.
 #[db_test]
 async fn executed_operations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -16,6 +16,10 @@ async fn last_watched_block(mut storage: StorageProcessor<'_>) -> QueryResult<()
         last_watched_block_number.is_err(),
         "There should be no stored block number in the database"
     );
+    let last_watched_block_number = DataRestoreSchema(&mut storage)
+        .find_last_watched_block_number()
+        .await?;
+    assert!(last_watched_block_number.is_none());
 
     // Store the block number.
     DataRestoreSchema(&mut storage)
@@ -28,6 +32,11 @@ async fn last_watched_block(mut storage: StorageProcessor<'_>) -> QueryResult<()
         .await?;
 
     assert_eq!(last_watched_block_number.block_number, "0");
+    let last_watched_block_number = DataRestoreSchema(&mut storage)
+        .find_last_watched_block_number()
+        .await?
+        .expect("Block number should be stored");
+    assert_eq!(last_watched_block_number.block_number, "0");
 
     // Repeat save/load with other values.
     DataRestoreSchema(&mut storage)
@@ -0,0 +1,81 @@
+use zksync_types::H256;
+
+use crate::eth_watch::EthWatchSchema;
+use crate::tests::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+/// Checks that the processed Ethereum blocks are stored, pruned and reverted correctly.
+#[db_test]
+async fn processed_blocks(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let blocks: Vec<_> = (1..=5u64)
+        .map(|number| (number, H256::from_low_u64_be(number)))
+        .collect();
+
+    assert!(EthWatchSchema(&mut storage)
+        .load_processed_blocks()
+        .await?
+        .is_empty());
+
+    for (number, hash) in &blocks {
+        EthWatchSchema(&mut storage)
+            .save_processed_block(*number, *hash, 0)
+            .await?;
+    }
+    assert_eq!(
+        EthWatchSchema(&mut storage).load_processed_blocks().await?,
+        blocks
+    );
+
+    // Re-saving the block after the reorg should replace its hash, older blocks should be pruned.
+    let new_hash = H256::repeat_byte(0xff);
+    EthWatchSchema(&mut storage)
+        .save_processed_block(5, new_hash, 3)
+        .await?;
+    assert_eq!(
+        EthWatchSchema(&mut storage).load_processed_blocks().await?,
+        vec![blocks[2], blocks[3], (5, new_hash)]
+    );
+
+    EthWatchSchema(&mut storage)
+        .remove_processed_blocks_after(3)
+        .await?;
+    assert_eq!(
+        EthWatchSchema(&mut storage).load_processed_blocks().await?,
+        vec![blocks[2]]
+    );
+
+    Ok(())
+}
+
+/// Checks that the priority operation cursor is stored and replaced correctly.
+#[db_test]
+async fn priority_op_cursor(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert_eq!(
+        EthWatchSchema(&mut storage)
+            .load_priority_op_cursor()
+            .await?,
+        None
+    );
+
+    EthWatchSchema(&mut storage)
+        .save_priority_op_cursor(10, 3)
+        .await?;
+    assert_eq!(
+        EthWatchSchema(&mut storage)
+            .load_priority_op_cursor()
+            .await?,
+        Some((10, 3))
+    );
+
+    EthWatchSchema(&mut storage)
+        .save_priority_op_cursor(8, 2)
+        .await?;
+    assert_eq!(
+        EthWatchSchema(&mut storage)
+            .load_priority_op_cursor()
+            .await?,
+        Some((8, 2))
+    );
+
+    Ok(())
+}
@@ -88,6 +88,11 @@ async fn ethereum_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()>
         let actual_next_nonce = EthereumSchema(&mut storage).get_next_nonce().await?;
 
         assert_eq!(actual_next_nonce, expected_next_nonce);
+        // Loading the nonce must not affect the stored value.
+        assert_eq!(
+            EthereumSchema(&mut storage).load_next_nonce().await?,
+            expected_next_nonce + 1
+        );
     }
 
     let unconfirmed_operations = EthereumSchema(&mut storage)
@@ -0,0 +1,49 @@
+use zksync_types::Address;
+
+use crate::governance::GovernanceSchema;
+use crate::tests::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+/// Checks that the validator statuses are stored and updated correctly.
+#[db_test]
+async fn validator_statuses(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let (first, second) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+
+    assert!(GovernanceSchema(&mut storage)
+        .load_validators()
+        .await?
+        .is_empty());
+    assert_eq!(
+        GovernanceSchema(&mut storage)
+            .is_active_validator(first)
+            .await?,
+        None
+    );
+
+    GovernanceSchema(&mut storage)
+        .store_validator_status(first, true, 10)
+        .await?;
+    GovernanceSchema(&mut storage)
+        .store_validator_status(second, true, 10)
+        .await?;
+    GovernanceSchema(&mut storage)
+        .store_validator_status(second, false, 12)
+        .await?;
+    assert_eq!(
+        GovernanceSchema(&mut storage).load_validators().await?,
+        vec![(first, true), (second, false)]
+    );
+
+    // Outdated updates must be ignored.
+    GovernanceSchema(&mut storage)
+        .store_validator_status(second, true, 11)
+        .await?;
+    assert_eq!(
+        GovernanceSchema(&mut storage)
+            .is_active_validator(second)
+            .await?,
+        Some(false)
+    );
+
+    Ok(())
+}
@@ -1,8 +1,12 @@
+use chrono::{Duration, Utc};
 use sqlx::types::BigDecimal;
-use zksync_types::TokenId;
+use zksync_types::{pipeline::PipelineStage, tx::TxHash, AccountId, TokenId};
 
 use crate::tests::db_test;
-use crate::{misc::records::Subsidy, misc::MiscSchema};
+use crate::{
+    misc::records::{FeeQuote, Subsidy},
+    misc::MiscSchema,
+};
 use crate::{QueryResult, StorageProcessor};
 
 fn get_subsidy(name: String, value: u64) -> Subsidy {
@@ -54,3 +58,189 @@ async fn stored_subsidy(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
 
     Ok(())
 }
+
+/// Checks that the fee model parameters are stored and replaced.
+#[db_test]
+async fn stored_fee_model_params(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert!(MiscSchema(&mut storage)
+        .load_fee_model_params()
+        .await?
+        .is_none());
+
+    let params = serde_json::json!({ "gasPriceScalePercent": 130 });
+    MiscSchema(&mut storage)
+        .store_fee_model_params(params.clone())
+        .await?;
+    assert_eq!(
+        MiscSchema(&mut storage).load_fee_model_params().await?,
+        Some(params)
+    );
+
+    let params = serde_json::json!({ "gasPriceScalePercent": 150 });
+    MiscSchema(&mut storage)
+        .store_fee_model_params(params.clone())
+        .await?;
+    assert_eq!(
+        MiscSchema(&mut storage).load_fee_model_params().await?,
+        Some(params)
+    );
+
+    Ok(())
+}
+
+/// Checks that the sampled fee quotes are stored.
+#[db_test]
+async fn stored_fee_quotes(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert_eq!(MiscSchema(&mut storage).get_fee_quotes_count().await?, 0);
+
+    let quote = FeeQuote {
+        fee_type: "Transfer".to_string(),
+        token_id: TokenId(0),
+        gas_price_wei: BigDecimal::from(10),
+        scaled_gas_price_wei: BigDecimal::from(13),
+        eth_price_usd: BigDecimal::from(2000),
+        token_price_usd: BigDecimal::from(2000),
+        gas_tx_amount: BigDecimal::from(2000),
+        zkp_fee: BigDecimal::from(100),
+        gas_fee: BigDecimal::from(26000),
+        total_fee: BigDecimal::from(26100),
+        created_at: chrono::Utc::now(),
+    };
+    MiscSchema(&mut storage)
+        .store_fee_quotes(vec![quote.clone(), quote])
+        .await?;
+    assert_eq!(MiscSchema(&mut storage).get_fee_quotes_count().await?, 2);
+
+    Ok(())
+}
+
+/// Checks that the fee-free withdrawal is granted once per period.
+#[db_test]
+async fn free_withdrawals_quota(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let account_id = AccountId(1);
+    let tx_hash = |byte: u8| TxHash::from_slice(&[byte; 32]).unwrap();
+    let period_start = Utc::now() - Duration::days(30);
+
+    assert!(
+        MiscSchema(&mut storage)
+            .use_free_withdrawal(account_id, tx_hash(1), period_start)
+            .await?
+    );
+    // The quota is already used in this period.
+    assert!(
+        !MiscSchema(&mut storage)
+            .use_free_withdrawal(account_id, tx_hash(2), period_start)
+            .await?
+    );
+    // Quotas of the other accounts are independent.
+    assert!(
+        MiscSchema(&mut storage)
+            .use_free_withdrawal(AccountId(2), tx_hash(3), period_start)
+            .await?
+    );
+
+    // The reverted withdrawal is returned to the quota.
+    MiscSchema(&mut storage)
+        .revert_free_withdrawal(tx_hash(1))
+        .await?;
+    assert!(
+        MiscSchema(&mut storage)
+            .use_free_withdrawal(account_id, tx_hash(4), period_start)
+            .await?
+    );
+
+    // The quota is renewed in the next period.
+    assert!(
+        MiscSchema(&mut storage)
+            .use_free_withdrawal(account_id, tx_hash(5), Utc::now() + Duration::seconds(1))
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Checks that the advisory lock can be acquired and released.
+#[db_test]
+async fn advisory_lock(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let lock_id = 0x7a6b_5379_6e63;
+
+    assert!(
+        !MiscSchema(&mut storage)
+            .holds_advisory_lock(lock_id)
+            .await?
+    );
+    assert!(MiscSchema(&mut storage).try_advisory_lock(lock_id).await?);
+    assert!(
+        MiscSchema(&mut storage)
+            .holds_advisory_lock(lock_id)
+            .await?
+    );
+    // Other locks are independent.
+    assert!(
+        !MiscSchema(&mut storage)
+            .holds_advisory_lock(lock_id + 1)
+            .await?
+    );
+
+    // The session-level lock isn't released with the test transaction, so it's released explicitly.
+    assert!(MiscSchema(&mut storage).advisory_unlock(lock_id).await?);
+    assert!(
+        !MiscSchema(&mut storage)
+            .holds_advisory_lock(lock_id)
+            .await?
+    );
+    assert!(!MiscSchema(&mut storage).advisory_unlock(lock_id).await?);
+
+    Ok(())
+}
+
+/// Checks that the pipeline stages can be paused and resumed independently.
+#[db_test]
+async fn paused_pipeline_stages(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert!(MiscSchema(&mut storage)
+        .load_paused_pipeline_stages()
+        .await?
+        .is_empty());
+
+    MiscSchema(&mut storage)
+        .pause_pipeline_stage(PipelineStage::CommitSending, "gas price spike")
+        .await?;
+    MiscSchema(&mut storage)
+        .pause_pipeline_stage(PipelineStage::TxAcceptance, "incident")
+        .await?;
+    // Pausing the paused stage updates the reason.
+    MiscSchema(&mut storage)
+        .pause_pipeline_stage(PipelineStage::CommitSending, "contract upgrade")
+        .await?;
+
+    let pauses = MiscSchema(&mut storage)
+        .load_paused_pipeline_stages()
+        .await?;
+    assert_eq!(pauses.len(), 2);
+    assert!(!pauses
+        .iter()
+        .any(|pause| pause.stage == PipelineStage::BlockSealing));
+    let commit_pause = pauses
+        .iter()
+        .find(|pause| pause.stage == PipelineStage::CommitSending)
+        .unwrap();
+    assert_eq!(commit_pause.reason, "contract upgrade");
+
+    assert!(
+        MiscSchema(&mut storage)
+            .resume_pipeline_stage(PipelineStage::CommitSending)
+            .await?
+    );
+    assert!(
+        !MiscSchema(&mut storage)
+            .resume_pipeline_stage(PipelineStage::CommitSending)
+            .await?
+    );
+    let pauses = MiscSchema(&mut storage)
+        .load_paused_pipeline_stages()
+        .await?;
+    assert_eq!(pauses.len(), 1);
+    assert_eq!(pauses[0].stage, PipelineStage::TxAcceptance);
+
+    Ok(())
+}
@@ -26,9 +26,11 @@ use zksync_crypto::rand::{SeedableRng, XorShiftRng};
 pub(crate) mod chain;
 mod config;
 mod data_restore;
+mod eth_watch;
 mod ethereum;
 mod event;
 mod forced_exit_requests;
+mod governance;
 mod misc;
 mod prover;
 mod tokens;
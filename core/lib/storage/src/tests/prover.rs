@@ -1,10 +1,15 @@
+// Built-in imports
+use std::time::Duration;
 // External imports
 use anyhow::format_err;
 use once_cell::sync::Lazy;
 use tokio::sync::Mutex;
 // Workspace imports
 use zksync_types::{
-    prover::{ProverJob, ProverJobType},
+    prover::{
+        ProofRejectionCause, ProverJob, ProverJobType, ProverWorkClass, ProverWorkClassQueue,
+        StoredArtifact, AGGREGATED_PROOF_JOB_PRIORITY, SINGLE_PROOF_JOB_PRIORITY,
+    },
     BlockNumber,
 };
 // Local imports
@@ -55,10 +60,15 @@ async fn test_store_proof(storage: &mut StorageProcessor<'_>) -> QueryResult<()>
     let aggregated_proof = get_sample_aggregated_proof();
 
     let stored_proof = ProverSchema(storage)
-        .store_proof(1, BlockNumber(1), &proof)
+        .store_proof(1, BlockNumber(1), StoredArtifact::Inline(&proof))
         .await;
     let stored_aggregated_proof = ProverSchema(storage)
-        .store_aggregated_proof(1, BlockNumber(1), BlockNumber(1), &aggregated_proof)
+        .store_aggregated_proof(
+            1,
+            BlockNumber(1),
+            BlockNumber(1),
+            StoredArtifact::Inline(&aggregated_proof),
+        )
         .await;
 
     assert!(stored_proof
@@ -102,14 +112,18 @@ async fn test_store_proof(storage: &mut StorageProcessor<'_>) -> QueryResult<()>
 
     // Store proofs.
     let stored_proof = ProverSchema(storage)
-        .store_proof(stored_job_id, BlockNumber(1), &proof)
+        .store_proof(
+            stored_job_id,
+            BlockNumber(1),
+            StoredArtifact::Inline(&proof),
+        )
         .await;
     let stored_aggregated_proof = ProverSchema(storage)
         .store_aggregated_proof(
             stored_aggregated_job_id,
             BlockNumber(1),
             BlockNumber(1),
-            &aggregated_proof,
+            StoredArtifact::Inline(&aggregated_proof),
         )
         .await;
 
@@ -197,15 +211,16 @@ async fn pending_jobs_count(storage: &mut StorageProcessor<'_>) -> QueryResult<(
         .store_proof(
             third_job.job_id,
             third_job.first_block,
-            &get_sample_single_proof(),
+            StoredArtifact::Inline(&get_sample_single_proof()),
         )
         .await?;
     let jobs_count = ProverSchema(storage).pending_jobs_count().await?;
     assert_eq!(jobs_count, 2);
 
-    ProverSchema(storage)
-        .record_prover_stop("test_prover")
-        .await?;
+    assert!(ProverSchema(storage)
+        .record_prover_stop("test_prover", 3)
+        .await?
+        .is_empty());
 
     let jobs_count = ProverSchema(storage).pending_jobs_count().await?;
     assert_eq!(jobs_count, 2);
@@ -241,7 +256,7 @@ async fn test_store_witness(mut storage: StorageProcessor<'_>) -> QueryResult<()
     let witness = serde_json::to_value(expected.clone()).unwrap();
     storage
         .prover_schema()
-        .store_witness(BLOCK_NUMBER, witness)
+        .store_witness(BLOCK_NUMBER, StoredArtifact::Inline(witness))
         .await?;
 
     // Now load it.
@@ -249,7 +264,7 @@ async fn test_store_witness(mut storage: StorageProcessor<'_>) -> QueryResult<()
         .prover_schema()
         .get_witness(BLOCK_NUMBER)
         .await?
-        .map(|value| serde_json::from_value(value).unwrap());
+        .map(inline_witness);
     assert_eq!(loaded.as_ref(), Some(&expected));
 
     // Do nothing on conflict.
@@ -257,20 +272,58 @@ async fn test_store_witness(mut storage: StorageProcessor<'_>) -> QueryResult<()
     let witness = serde_json::to_value(expected.clone()).unwrap();
     storage
         .prover_schema()
-        .store_witness(BLOCK_NUMBER, witness)
+        .store_witness(BLOCK_NUMBER, StoredArtifact::Inline(witness))
         .await?;
 
     let loaded = storage
         .prover_schema()
         .get_witness(BLOCK_NUMBER)
         .await?
-        .map(|value| serde_json::from_value(value).unwrap());
+        .map(inline_witness);
     assert_ne!(loaded, Some(not_expected));
     assert_eq!(loaded, Some(expected));
 
+    // Witness stored in the object storage is referenced by its key.
+    let object_block = BlockNumber(2);
+    storage
+        .chain()
+        .block_schema()
+        .save_full_block(gen_sample_block(
+            object_block,
+            BLOCK_SIZE,
+            Default::default(),
+        ))
+        .await?;
+    let object_key = String::from("witnesses/2.json");
+    storage
+        .prover_schema()
+        .store_witness(object_block, StoredArtifact::Object(object_key.clone()))
+        .await?;
+    let loaded = storage.prover_schema().get_witness(object_block).await?;
+    assert_eq!(loaded, Some(StoredArtifact::Object(object_key.clone())));
+    assert!(
+        storage
+            .prover_schema()
+            .is_artifact_referenced(&object_key)
+            .await?
+    );
+    assert!(
+        !storage
+            .prover_schema()
+            .is_artifact_referenced("witnesses/3.json")
+            .await?
+    );
+
     Ok(())
 }
 
+fn inline_witness(witness: StoredArtifact<serde_json::Value>) -> String {
+    match witness {
+        StoredArtifact::Inline(value) => serde_json::from_value(value).unwrap(),
+        StoredArtifact::Object(object_key) => panic!("Unexpected witness object {}", object_key),
+    }
+}
+
 /// Checks that block witnesses are removed correctly.
 #[db_test]
 async fn test_remove_witnesses(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -288,7 +341,7 @@ async fn test_remove_witnesses(mut storage: StorageProcessor<'_>) -> QueryResult
         let witness = serde_json::to_value(String::from("test")).unwrap();
         storage
             .prover_schema()
-            .store_witness(BlockNumber(block_number), witness)
+            .store_witness(BlockNumber(block_number), StoredArtifact::Inline(witness))
             .await?;
     }
     // Remove witnesses for the 4th and 5th blocks.
@@ -334,7 +387,11 @@ async fn test_remove_proofs(mut storage: StorageProcessor<'_>) -> QueryResult<()
             .await?;
         let job_id = get_idle_job_from_queue(&mut storage).await?.job_id;
         ProverSchema(&mut storage)
-            .store_proof(job_id, BlockNumber(block_number), &proof)
+            .store_proof(
+                job_id,
+                BlockNumber(block_number),
+                StoredArtifact::Inline(&proof),
+            )
             .await?;
     }
 
@@ -367,7 +424,12 @@ async fn test_remove_proofs(mut storage: StorageProcessor<'_>) -> QueryResult<()
         .await?;
     let job_id = get_idle_job_from_queue(&mut storage).await?.job_id;
     ProverSchema(&mut storage)
-        .store_aggregated_proof(job_id, BlockNumber(1), BlockNumber(2), &aggregated_proof)
+        .store_aggregated_proof(
+            job_id,
+            BlockNumber(1),
+            BlockNumber(2),
+            StoredArtifact::Inline(&aggregated_proof),
+        )
         .await?;
 
     ProverSchema(&mut storage)
@@ -381,7 +443,12 @@ async fn test_remove_proofs(mut storage: StorageProcessor<'_>) -> QueryResult<()
         .await?;
     let job_id = get_idle_job_from_queue(&mut storage).await?.job_id;
     ProverSchema(&mut storage)
-        .store_aggregated_proof(job_id, BlockNumber(3), BlockNumber(5), &aggregated_proof)
+        .store_aggregated_proof(
+            job_id,
+            BlockNumber(3),
+            BlockNumber(5),
+            StoredArtifact::Inline(&aggregated_proof),
+        )
         .await?;
 
     // Remove aggregated proofs for blocks with numbers greater than 3. It means that proof for 3-5 blocks should be deleted.
@@ -441,3 +508,705 @@ async fn test_remove_prover_jobs(mut storage: StorageProcessor<'_>) -> QueryResu
 
     Ok(())
 }
+
+/// Checks that the idle jobs are grouped and taken by the work class.
+#[db_test]
+async fn prover_job_work_classes(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    let block_proof = |block_size: usize| serde_json::json!({ "BlockProof": [{}, block_size] });
+    for (block, block_size) in [(1, 10), (2, 32), (3, 10)] {
+        ProverSchema(&mut storage)
+            .add_prover_job_to_job_queue(
+                BlockNumber(block),
+                BlockNumber(block),
+                block_proof(block_size),
+                1,
+                ProverJobType::SingleProof,
+            )
+            .await?;
+    }
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(2),
+            serde_json::json!({ "AggregatedBlockProof": [] }),
+            0,
+            ProverJobType::AggregatedProof,
+        )
+        .await?;
+
+    let mut queues = ProverSchema(&mut storage).idle_jobs_by_work_class().await?;
+    queues.sort_by_key(|queue| queue.work_class);
+    assert_eq!(
+        queues,
+        vec![
+            ProverWorkClassQueue {
+                work_class: ProverWorkClass::SingleProof { block_size: 10 },
+                job_priority: 1,
+                idle_jobs: 2,
+            },
+            ProverWorkClassQueue {
+                work_class: ProverWorkClass::SingleProof { block_size: 32 },
+                job_priority: 1,
+                idle_jobs: 1,
+            },
+            ProverWorkClassQueue {
+                work_class: ProverWorkClass::AggregatedProof,
+                job_priority: 0,
+                idle_jobs: 1,
+            },
+        ]
+    );
+
+    let job = ProverSchema(&mut storage)
+        .get_idle_prover_job_of_work_class(ProverWorkClass::SingleProof { block_size: 32 })
+        .await?
+        .expect("expect idle job of the work class");
+    assert_eq!(job.first_block, BlockNumber(2));
+    assert!(ProverSchema(&mut storage)
+        .get_idle_prover_job_of_work_class(ProverWorkClass::SingleProof { block_size: 32 })
+        .await?
+        .is_none());
+    let job = ProverSchema(&mut storage)
+        .get_idle_prover_job_of_work_class(ProverWorkClass::AggregatedProof)
+        .await?
+        .expect("expect idle job of the work class");
+    assert_eq!(job.last_block, BlockNumber(2));
+    assert!(ProverSchema(&mut storage)
+        .get_idle_prover_job_of_work_class(ProverWorkClass::SingleProof { block_size: 72 })
+        .await?
+        .is_none());
+
+    Ok(())
+}
+
+/// Checks that the jobs waiting for too long are moved to the urgent lane and are assigned first,
+/// and the jobs within the lane are assigned starting from the oldest block.
+#[db_test]
+async fn prover_job_urgent_lane(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    let urgent_job_timeout = Duration::from_secs(1);
+    for block in [3, 2] {
+        ProverSchema(&mut storage)
+            .add_prover_job_to_job_queue(
+                BlockNumber(block),
+                BlockNumber(block),
+                Default::default(),
+                SINGLE_PROOF_JOB_PRIORITY,
+                ProverJobType::SingleProof,
+            )
+            .await?;
+    }
+    assert_eq!(
+        ProverSchema(&mut storage)
+            .move_jobs_to_urgent_lane(urgent_job_timeout)
+            .await?,
+        0
+    );
+
+    tokio::time::sleep(urgent_job_timeout * 2).await;
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(1),
+            Default::default(),
+            AGGREGATED_PROOF_JOB_PRIORITY,
+            ProverJobType::AggregatedProof,
+        )
+        .await?;
+    assert_eq!(
+        ProverSchema(&mut storage)
+            .move_jobs_to_urgent_lane(urgent_job_timeout)
+            .await?,
+        2
+    );
+
+    // Urgent jobs go first, the job of the older block is assigned despite being added later.
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(job.first_block, BlockNumber(2));
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(job.first_block, BlockNumber(3));
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(job.first_block, BlockNumber(1));
+
+    Ok(())
+}
+
+/// Checks that the jobs of the dead provers are returned to the queue and the jobs that fail
+/// repeatedly are quarantined.
+#[db_test]
+async fn prover_job_quarantine(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    let heartbeat_timeout = Duration::from_secs(1);
+    let max_attempts = 2;
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(1),
+            Default::default(),
+            SINGLE_PROOF_JOB_PRIORITY,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    ProverSchema(&mut storage)
+        .record_prover_is_working(job.job_id, "dead_prover")
+        .await?;
+    // The job in progress is requeued by the operator only if it's forced.
+    assert!(
+        !ProverSchema(&mut storage)
+            .requeue_prover_job(job.job_id, "admin", false)
+            .await?
+    );
+    // The prover sends heartbeats, so the job is not requeued.
+    assert!(ProverSchema(&mut storage)
+        .mark_stale_jobs_as_idle(heartbeat_timeout * 10, max_attempts)
+        .await?
+        .is_empty());
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 1);
+
+    // The prover dies, the job is returned to the queue.
+    tokio::time::sleep(heartbeat_timeout * 2).await;
+    assert!(ProverSchema(&mut storage)
+        .mark_stale_jobs_as_idle(heartbeat_timeout, max_attempts)
+        .await?
+        .is_empty());
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(job.first_block, BlockNumber(1));
+    ProverSchema(&mut storage)
+        .record_prover_is_working(job.job_id, "dead_prover")
+        .await?;
+
+    // The second attempt fails as well, the job is quarantined.
+    tokio::time::sleep(heartbeat_timeout * 2).await;
+    let quarantined_jobs = ProverSchema(&mut storage)
+        .mark_stale_jobs_as_idle(heartbeat_timeout, max_attempts)
+        .await?;
+    assert_eq!(quarantined_jobs.len(), 1);
+    assert_eq!(quarantined_jobs[0].job_id, job.job_id);
+    assert_eq!(quarantined_jobs[0].attempts, 2);
+    assert_eq!(quarantined_jobs[0].last_prover, "dead_prover");
+    assert_eq!(
+        ProverSchema(&mut storage).quarantined_prover_jobs().await?,
+        quarantined_jobs
+    );
+
+    // Quarantined jobs are neither assigned nor counted as pending.
+    assert!(ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue()
+        .await?
+        .is_none());
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 0);
+
+    // The operator requeues the job manually, it gets the new attempts.
+    assert!(
+        ProverSchema(&mut storage)
+            .requeue_prover_job(job.job_id, "admin", false)
+            .await?
+    );
+    assert!(ProverSchema(&mut storage)
+        .quarantined_prover_jobs()
+        .await?
+        .is_empty());
+    let requeued_job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(requeued_job.job_id, job.job_id);
+    // Now the job is in progress again, so it's requeued only with `force`.
+    assert!(
+        !ProverSchema(&mut storage)
+            .requeue_prover_job(job.job_id, "admin", false)
+            .await?
+    );
+    assert!(
+        ProverSchema(&mut storage)
+            .requeue_prover_job(job.job_id, "admin", true)
+            .await?
+    );
+    assert_eq!(
+        get_idle_job_from_queue(&mut storage).await?.job_id,
+        job.job_id
+    );
+    // Unknown jobs can't be requeued.
+    assert!(
+        !ProverSchema(&mut storage)
+            .requeue_prover_job(job.job_id + 1, "admin", true)
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Checks that the stops of the provers count as failed attempts, so the job that stops
+/// its provers repeatedly is quarantined.
+#[db_test]
+async fn prover_stops_quarantine_job(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    let max_attempts = 2;
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(1),
+            Default::default(),
+            SINGLE_PROOF_JOB_PRIORITY,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+
+    // The first stop returns the job to the queue.
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    ProverSchema(&mut storage)
+        .record_prover_is_working(job.job_id, "crashing_prover")
+        .await?;
+    assert!(ProverSchema(&mut storage)
+        .record_prover_stop("crashing_prover", max_attempts)
+        .await?
+        .is_empty());
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 1);
+
+    // Stops of the other provers don't affect the job.
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    ProverSchema(&mut storage)
+        .record_prover_is_working(job.job_id, "crashing_prover")
+        .await?;
+    assert!(ProverSchema(&mut storage)
+        .record_prover_stop("other_prover", max_attempts)
+        .await?
+        .is_empty());
+
+    // The second stop exhausts the attempts, the job is quarantined.
+    let quarantined_jobs = ProverSchema(&mut storage)
+        .record_prover_stop("crashing_prover", max_attempts)
+        .await?;
+    assert_eq!(quarantined_jobs.len(), 1);
+    assert_eq!(quarantined_jobs[0].job_id, job.job_id);
+    assert_eq!(quarantined_jobs[0].attempts, 2);
+    assert_eq!(quarantined_jobs[0].last_prover, "crashing_prover");
+    assert_eq!(
+        ProverSchema(&mut storage).quarantined_prover_jobs().await?,
+        quarantined_jobs
+    );
+    assert!(ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue()
+        .await?
+        .is_none());
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 0);
+
+    Ok(())
+}
+
+/// Checks that the rejected proofs are recorded, their jobs are returned to the queue and the jobs
+/// with too many rejected proofs are quarantined.
+#[db_test]
+async fn prover_job_rejected_proofs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    let max_attempts = 2;
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(1),
+            Default::default(),
+            SINGLE_PROOF_JOB_PRIORITY,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+
+    // The rejected proof is attributed to the prover that sent it, the job is returned to the queue.
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    assert!(ProverSchema(&mut storage)
+        .reject_proof(
+            job.job_id,
+            Some("bad_prover"),
+            ProofRejectionCause::InvalidProof,
+            "invalid proof",
+            max_attempts
+        )
+        .await?
+        .is_none());
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 1);
+
+    // The job is proven again, the second failure quarantines it. The prover is not provided,
+    // so the failure is attributed to the prover that was working on the job.
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(job.first_block, BlockNumber(1));
+    ProverSchema(&mut storage)
+        .record_prover_is_working(job.job_id, "another_bad_prover")
+        .await?;
+    let quarantined_job = ProverSchema(&mut storage)
+        .reject_proof(
+            job.job_id,
+            None,
+            ProofRejectionCause::ProvingFailed,
+            "out of memory",
+            max_attempts,
+        )
+        .await?
+        .expect("job should be quarantined");
+    assert_eq!(quarantined_job.job_id, job.job_id);
+    assert_eq!(quarantined_job.attempts, 2);
+    assert_eq!(quarantined_job.last_prover, "another_bad_prover");
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 0);
+
+    let rejected_proofs = ProverSchema(&mut storage).rejected_proofs().await?;
+    assert_eq!(rejected_proofs.len(), 2);
+    assert_eq!(rejected_proofs[0].prover_name, "bad_prover");
+    assert_eq!(rejected_proofs[1].prover_name, "another_bad_prover");
+    assert!(rejected_proofs
+        .iter()
+        .all(|proof| proof.job_id == job.job_id));
+    // The proving failures are told apart from the invalid proofs.
+    assert_eq!(
+        (rejected_proofs[0].cause, rejected_proofs[0].reason.as_str()),
+        (
+            ProofRejectionCause::InvalidProof.to_number(),
+            "invalid proof"
+        )
+    );
+    assert_eq!(
+        (rejected_proofs[1].cause, rejected_proofs[1].reason.as_str()),
+        (
+            ProofRejectionCause::ProvingFailed.to_number(),
+            "out of memory"
+        )
+    );
+
+    // Proofs of the quarantined jobs don't change them anymore.
+    assert!(ProverSchema(&mut storage)
+        .reject_proof(
+            job.job_id,
+            None,
+            ProofRejectionCause::InvalidProof,
+            "invalid proof",
+            max_attempts
+        )
+        .await?
+        .is_none());
+
+    Ok(())
+}
+
+/// Checks that the queue stats count the idle and in progress jobs and report the oldest of them.
+#[db_test]
+async fn prover_queue_stats(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    let stats = ProverSchema(&mut storage).prover_queue_stats().await?;
+    assert_eq!(stats, Default::default());
+
+    for block_number in 1..=3 {
+        ProverSchema(&mut storage)
+            .add_prover_job_to_job_queue(
+                BlockNumber(block_number),
+                BlockNumber(block_number),
+                Default::default(),
+                SINGLE_PROOF_JOB_PRIORITY,
+                ProverJobType::SingleProof,
+            )
+            .await?;
+    }
+    let stats = ProverSchema(&mut storage).prover_queue_stats().await?;
+    assert_eq!(stats.idle_jobs, 3);
+    assert_eq!(stats.in_progress_jobs, 0);
+    assert_eq!(stats.oldest_unproven_block, Some(BlockNumber(1)));
+    assert!(stats.oldest_unproven_block_created_at.is_some());
+
+    // Once the oldest job is proven, the next one becomes the oldest.
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    let stats = ProverSchema(&mut storage).prover_queue_stats().await?;
+    assert_eq!((stats.idle_jobs, stats.in_progress_jobs), (2, 1));
+    assert_eq!(stats.oldest_unproven_block, Some(BlockNumber(1)));
+
+    ProverSchema(&mut storage)
+        .store_proof(
+            job.job_id,
+            job.first_block,
+            StoredArtifact::Inline(&get_sample_single_proof()),
+        )
+        .await?;
+    let stats = ProverSchema(&mut storage).prover_queue_stats().await?;
+    assert_eq!((stats.idle_jobs, stats.in_progress_jobs), (2, 0));
+    assert_eq!(stats.oldest_unproven_block, Some(BlockNumber(2)));
+
+    Ok(())
+}
+
+/// Checks that the first unproven block moves forward once the oldest single proof is stored.
+#[db_test]
+async fn first_unproven_block(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    assert_eq!(
+        ProverSchema(&mut storage).first_unproven_block().await?,
+        BlockNumber(1)
+    );
+
+    for block_number in 1..=3 {
+        ProverSchema(&mut storage)
+            .add_prover_job_to_job_queue(
+                BlockNumber(block_number),
+                BlockNumber(block_number),
+                Default::default(),
+                SINGLE_PROOF_JOB_PRIORITY,
+                ProverJobType::SingleProof,
+            )
+            .await?;
+    }
+    assert_eq!(
+        ProverSchema(&mut storage).first_unproven_block().await?,
+        BlockNumber(1)
+    );
+
+    for block_number in 1..=3 {
+        let job = get_idle_job_from_queue(&mut storage).await?;
+        ProverSchema(&mut storage)
+            .store_proof(
+                job.job_id,
+                job.first_block,
+                StoredArtifact::Inline(&get_sample_single_proof()),
+            )
+            .await?;
+        // Once all the jobs are done, the block following the last job is the first unproven one.
+        assert_eq!(
+            ProverSchema(&mut storage).first_unproven_block().await?,
+            BlockNumber(block_number + 1)
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that the prover tokens are resolved to the prover names until they are revoked,
+/// and the revoked prover loses its jobs.
+#[db_test]
+async fn prover_tokens(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    ProverSchema(&mut storage)
+        .store_prover_token("prover_1", "hash_1")
+        .await?;
+    ProverSchema(&mut storage)
+        .store_prover_token("prover_2", "hash_2")
+        .await?;
+    assert_eq!(
+        ProverSchema(&mut storage)
+            .prover_name_by_token_hash("hash_1")
+            .await?,
+        Some("prover_1".to_string())
+    );
+    assert_eq!(
+        ProverSchema(&mut storage)
+            .prover_name_by_token_hash("unknown_hash")
+            .await?,
+        None
+    );
+
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(1),
+            Default::default(),
+            SINGLE_PROOF_JOB_PRIORITY,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    ProverSchema(&mut storage)
+        .record_prover_is_working(job.job_id, "prover_1")
+        .await?;
+
+    // The revoked token is not accepted anymore and the job of the prover is returned to the queue.
+    assert!(
+        ProverSchema(&mut storage)
+            .revoke_prover_token("prover_1")
+            .await?
+    );
+    assert!(
+        !ProverSchema(&mut storage)
+            .revoke_prover_token("prover_1")
+            .await?
+    );
+    assert_eq!(
+        ProverSchema(&mut storage)
+            .prover_name_by_token_hash("hash_1")
+            .await?,
+        None
+    );
+    assert_eq!(
+        get_idle_job_from_queue(&mut storage).await?.job_id,
+        job.job_id
+    );
+
+    let tokens = ProverSchema(&mut storage).prover_tokens().await?;
+    assert_eq!(tokens.len(), 2);
+    assert!(tokens[0].revoked_at.is_some());
+    assert!(tokens[1].revoked_at.is_none());
+
+    // The new token of the revoked prover is accepted.
+    ProverSchema(&mut storage)
+        .store_prover_token("prover_1", "hash_3")
+        .await?;
+    assert_eq!(
+        ProverSchema(&mut storage)
+            .prover_name_by_token_hash("hash_3")
+            .await?,
+        Some("prover_1".to_string())
+    );
+
+    Ok(())
+}
+
+/// Checks that the job assignments and the published proofs are recorded in the audit trail.
+#[db_test]
+async fn prover_job_audit(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(2),
+            Default::default(),
+            AGGREGATED_PROOF_JOB_PRIORITY,
+            ProverJobType::AggregatedProof,
+        )
+        .await?;
+    let job = get_idle_job_from_queue(&mut storage).await?;
+
+    // The job is reassigned to the second prover, which publishes the proof.
+    ProverSchema(&mut storage)
+        .record_prover_job_assignment(job.job_id, "prover_1")
+        .await?;
+    ProverSchema(&mut storage)
+        .record_prover_job_assignment(job.job_id, "prover_2")
+        .await?;
+    ProverSchema(&mut storage)
+        .record_prover_job_published(job.job_id, Some("prover_2"))
+        .await?;
+
+    assert_eq!(
+        ProverSchema(&mut storage)
+            .prover_job_assignee(job.job_id)
+            .await?,
+        Some("prover_2".to_string())
+    );
+    assert_eq!(
+        ProverSchema(&mut storage)
+            .prover_job_assignee(job.job_id + 1)
+            .await?,
+        None
+    );
+
+    let audit = ProverSchema(&mut storage)
+        .prover_job_audit(None, 10)
+        .await?;
+    assert_eq!(audit.len(), 2);
+    assert_eq!(audit[0].prover_name, "prover_2");
+    assert_eq!((audit[0].first_block, audit[0].last_block), (1, 2));
+    assert!(audit[0].published_at.is_some());
+    assert_eq!(audit[0].published_by.as_deref(), Some("prover_2"));
+    assert!(audit[1].published_at.is_none());
+
+    let audit = ProverSchema(&mut storage)
+        .prover_job_audit(Some("prover_1"), 10)
+        .await?;
+    assert_eq!(audit.len(), 1);
+    assert_eq!(audit[0].job_id, job.job_id);
+
+    Ok(())
+}
+
+/// Checks that the proofs and witnesses are stored compressed if configured,
+/// and both the compressed and uncompressed ones are loaded.
+#[db_test]
+async fn compressed_artifacts(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    for block_number in 1..=2 {
+        storage
+            .chain()
+            .block_schema()
+            .save_full_block(gen_sample_block(
+                BlockNumber(block_number),
+                100,
+                Default::default(),
+            ))
+            .await?;
+    }
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(2),
+            BlockNumber(2),
+            Default::default(),
+            0,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+    let job_id = get_idle_job_from_queue(&mut storage).await?.job_id;
+
+    let witness = serde_json::to_value("witness").unwrap();
+    let proof = get_sample_single_proof();
+    ProverSchema(&mut storage)
+        .store_witness(BlockNumber(1), StoredArtifact::Inline(witness.clone()))
+        .await?;
+    storage.set_compression_level(Some(3));
+    ProverSchema(&mut storage)
+        .store_witness(BlockNumber(2), StoredArtifact::Inline(witness.clone()))
+        .await?;
+    ProverSchema(&mut storage)
+        .store_proof(job_id, BlockNumber(2), StoredArtifact::Inline(&proof))
+        .await?;
+    storage.set_compression_level(None);
+
+    let witness_compressed = sqlx::query!(
+        r#"SELECT witness_compressed IS NOT NULL AS "compressed!" FROM block_witness WHERE block = $1"#,
+        2i64
+    )
+    .fetch_one(storage.conn())
+    .await?
+    .compressed;
+    let proof_compressed = sqlx::query!(
+        r#"SELECT proof_compressed IS NOT NULL AS "compressed!" FROM proofs WHERE block_number = $1"#,
+        2i64
+    )
+    .fetch_one(storage.conn())
+    .await?
+    .compressed;
+    assert!(witness_compressed);
+    assert!(proof_compressed);
+
+    for block_number in 1..=2 {
+        let loaded = ProverSchema(&mut storage)
+            .get_witness(BlockNumber(block_number))
+            .await?;
+        assert_eq!(loaded, Some(StoredArtifact::Inline(witness.clone())));
+    }
+    let loaded_proof = match ProverSchema(&mut storage)
+        .load_proof(BlockNumber(2))
+        .await?
+    {
+        Some(StoredArtifact::Inline(proof)) => proof,
+        Some(StoredArtifact::Object(object_key)) => {
+            panic!("Unexpected proof object {}", object_key)
+        }
+        None => panic!("Proof is not stored"),
+    };
+    assert_eq!(
+        serde_json::to_value(loaded_proof).unwrap(),
+        serde_json::to_value(proof).unwrap()
+    );
+
+    Ok(())
+}
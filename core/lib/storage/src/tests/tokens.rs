@@ -6,16 +6,18 @@ use num::{rational::Ratio, BigUint};
 // Workspace imports
 use zksync_test_account::ZkSyncAccount;
 use zksync_types::{
-    tokens::TokenMarketVolume, AccountId, Address, BlockNumber, ExecutedOperations, ExecutedTx,
-    Token, TokenId, TokenKind, TokenLike, TokenPrice, WithdrawNFTOp, ZkSyncOp, H256,
+    tokens::{FeeTokenEligibility, TokenMarketVolume},
+    AccountId, Address, BlockNumber, Deposit, ExecutedOperations, ExecutedTx, FullExit, PriorityOp,
+    Token, TokenId, TokenKind, TokenLike, TokenPrice, WithdrawNFTOp, ZkSyncOp, ZkSyncPriorityOp,
+    H256,
 };
-use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
+use zksync_utils::{big_decimal_to_ratio, biguint_to_big_decimal, ratio_to_big_decimal};
 // Local imports
 use crate::tests::db_test;
 use crate::{
-    chain::account::records::StorageMintNFTUpdate,
+    chain::account::records::{StorageAccountCreation, StorageAccountUpdate, StorageMintNFTUpdate},
     diff::StorageAccountDiff,
-    tokens::{TokensSchema, STORED_USD_PRICE_PRECISION},
+    tokens::{StoreTokenError, TokensSchema, STORED_USD_PRICE_PRECISION},
     QueryResult, StorageProcessor,
 };
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
@@ -39,6 +41,7 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         decimals: 18,
         kind: TokenKind::ERC20,
         is_nft: false,
+        display_symbol: "ETH".into(),
     };
     assert_eq!(tokens[&TokenId(0)], eth_token);
 
@@ -50,6 +53,7 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         decimals: 9,
         kind: TokenKind::ERC20,
         is_nft: false,
+        display_symbol: "ABC".into(),
     };
     let token_b = Token {
         id: TokenId(2),
@@ -58,6 +62,7 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         decimals: 6,
         kind: TokenKind::None,
         is_nft: false,
+        display_symbol: "DEF".into(),
     };
     let nft = Token {
         id: TokenId(MIN_NFT_TOKEN_ID),
@@ -66,6 +71,7 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         decimals: 0,
         kind: TokenKind::NFT,
         is_nft: true,
+        display_symbol: "NFT".into(),
     };
 
     TokensSchema(&mut storage)
@@ -255,6 +261,218 @@ async fn test_market_volume(mut storage: StorageProcessor<'_>) -> QueryResult<()
     Ok(())
 }
 
+/// Checks that the tokens sharing a symbol get the unique display symbols
+/// and can be found by them.
+#[db_test]
+async fn test_tokens_sharing_symbol(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let first = Token::new(
+        TokenId(1),
+        Address::from_str("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap(),
+        "USDC",
+        6,
+        TokenKind::ERC20,
+    );
+    let second = Token::new(
+        TokenId(2),
+        Address::from_str("a0b8000000000000000000000000000000000002").unwrap(),
+        "USDC",
+        18,
+        TokenKind::ERC20,
+    );
+    let third = Token::new(
+        TokenId(3),
+        Address::from_str("a0b8000000000000000000000000000000000003").unwrap(),
+        "usdc",
+        18,
+        TokenKind::ERC20,
+    );
+    for token in [&first, &second, &third] {
+        storage
+            .tokens_schema()
+            .store_token(token.clone())
+            .await
+            .expect("Tokens sharing a symbol must be stored");
+    }
+
+    let tokens = storage.tokens_schema().load_tokens().await?;
+    // The first listed token keeps the symbol as the display one.
+    assert_eq!(tokens[&first.id].display_symbol, "USDC");
+    assert_eq!(tokens[&second.id].display_symbol, "USDC-a0b8");
+    // The short suffix is taken (case-insensitively), so the longer one is used.
+    assert_eq!(tokens[&third.id].display_symbol, "usdc-a0b80000");
+    assert_eq!(tokens[&third.id].symbol, "usdc");
+
+    for token in tokens.values().filter(|token| *token.id != 0) {
+        let found = storage
+            .tokens_schema()
+            .get_token(TokenLike::Symbol(token.display_symbol.clone()))
+            .await?
+            .expect("token by display symbol not found");
+        assert_eq!(&found, token);
+    }
+
+    // The display symbol is kept when the token is updated with the same symbol.
+    let mut updated = second.clone();
+    updated.decimals = 6;
+    storage
+        .tokens_schema()
+        .store_or_update_token(updated)
+        .await?;
+    let loaded = storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(second.id))
+        .await?
+        .unwrap();
+    assert_eq!(loaded.display_symbol, "USDC-a0b8");
+    assert_eq!(loaded.decimals, 6);
+
+    Ok(())
+}
+
+/// Checks that the token is not stored if all its display symbol candidates are taken.
+#[db_test]
+async fn display_symbol_candidates_taken(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let address = "ab12000000000000000000000000000000000000";
+    let taken_symbols = [4, 8, 16, 40]
+        .iter()
+        .map(|len| format!("DAI-{}", &address[..*len]))
+        .chain(std::iter::once("DAI".to_string()));
+    for (id, symbol) in (1..).zip(taken_symbols) {
+        storage
+            .tokens_schema()
+            .store_token(Token::new(
+                TokenId(id),
+                Address::from_low_u64_be(id as u64),
+                &symbol,
+                18,
+                TokenKind::ERC20,
+            ))
+            .await
+            .expect("Token with the unique symbol must be stored");
+    }
+
+    let token = Token::new(
+        TokenId(10),
+        Address::from_str(address).unwrap(),
+        "DAI",
+        18,
+        TokenKind::ERC20,
+    );
+    let result = storage.tokens_schema().store_token(token).await;
+    assert!(matches!(result, Err(StoreTokenError::Other(_))));
+    assert!(storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(TokenId(10)))
+        .await?
+        .is_none());
+
+    Ok(())
+}
+
+/// Checks that the decisions of the fee token policy are stored with the history
+/// and the disabled tokens are filtered out by market volume.
+#[db_test]
+async fn test_fee_token_eligibility(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    const TOKEN_ID: TokenId = TokenId(1);
+
+    storage
+        .tokens_schema()
+        .store_or_update_token(Token {
+            id: TOKEN_ID,
+            address: Address::random(),
+            ..Default::default()
+        })
+        .await?;
+    let market_volume = Ratio::new(BigUint::from(2u32), BigUint::from(5u32));
+    storage
+        .tokens_schema()
+        .update_historical_ticker_price(
+            TOKEN_ID,
+            TokenPrice {
+                usd_price: Ratio::new(BigUint::from(4u32), BigUint::from(9u32)),
+                last_updated: Utc::now(),
+            },
+        )
+        .await?;
+    storage
+        .tokens_schema()
+        .update_token_market_volume(
+            TOKEN_ID,
+            TokenMarketVolume {
+                market_volume: market_volume.clone(),
+                last_updated: Utc::now(),
+            },
+        )
+        .await?;
+    assert!(storage
+        .tokens_schema()
+        .get_fee_token_eligibility(TOKEN_ID)
+        .await?
+        .is_none());
+
+    let enabled = FeeTokenEligibility {
+        token_id: TOKEN_ID,
+        eligible: true,
+        reason: "Enough liquidity".to_string(),
+        market_volume: market_volume.clone(),
+        market_cap: None,
+        decided_at: Utc::now(),
+    };
+    let disabled = FeeTokenEligibility {
+        eligible: false,
+        reason: "Not enough liquidity".to_string(),
+        market_cap: Some(Ratio::from_integer(BigUint::from(1000u32))),
+        ..enabled.clone()
+    };
+    for eligibility in [enabled.clone(), disabled.clone()].iter() {
+        storage
+            .tokens_schema()
+            .store_fee_token_eligibility(eligibility.clone())
+            .await?;
+    }
+
+    let current = storage
+        .tokens_schema()
+        .get_fee_token_eligibility(TOKEN_ID)
+        .await?
+        .expect("couldn't load the fee token eligibility");
+    assert!(!current.eligible);
+    assert_eq!(current.reason, disabled.reason);
+    assert_eq!(current.market_cap, disabled.market_cap);
+
+    let history = storage
+        .tokens_schema()
+        .load_fee_token_eligibility_history(TOKEN_ID, 10)
+        .await?;
+    assert_eq!(history.len(), 2);
+    assert!(!history[0].eligible);
+    assert!(history[1].eligible);
+
+    // The disabled token isn't acceptable for fees regardless of its market volume.
+    let tokens = storage
+        .tokens_schema()
+        .load_tokens_by_market_volume(market_volume.clone())
+        .await?;
+    assert!(!tokens.contains_key(&TOKEN_ID));
+    let tokens = storage
+        .tokens_schema()
+        .filter_tokens_by_market_volume(vec![TOKEN_ID], &market_volume)
+        .await?;
+    assert!(tokens.is_empty());
+
+    storage
+        .tokens_schema()
+        .store_fee_token_eligibility(enabled)
+        .await?;
+    let tokens = storage
+        .tokens_schema()
+        .filter_tokens_by_market_volume(vec![TOKEN_ID], &market_volume)
+        .await?;
+    assert!(tokens.contains(&TOKEN_ID));
+
+    Ok(())
+}
+
 /// Checks the store/load factories for nft
 #[db_test]
 async fn test_nfts_with_factories(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -375,3 +593,181 @@ async fn test_nfts_with_factories(mut storage: StorageProcessor<'_>) -> QueryRes
 
     Ok(())
 }
+
+/// Checks the token blacklist and the withdraw-only deposits storing.
+#[db_test]
+async fn test_token_blacklist(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert!(storage
+        .tokens_schema()
+        .load_token_blacklist()
+        .await?
+        .is_empty());
+
+    storage
+        .tokens_schema()
+        .add_token_to_blacklist(TokenId(1), "Court order".to_string())
+        .await?;
+    storage
+        .tokens_schema()
+        .add_token_to_blacklist(TokenId(2), "Court order".to_string())
+        .await?;
+    // Adding the token again only updates the reason.
+    storage
+        .tokens_schema()
+        .add_token_to_blacklist(TokenId(2), "Sanctions".to_string())
+        .await?;
+    storage
+        .tokens_schema()
+        .remove_token_from_blacklist(TokenId(1))
+        .await?;
+    let blacklist = storage.tokens_schema().load_token_blacklist().await?;
+    assert_eq!(blacklist.into_iter().collect::<Vec<_>>(), vec![TokenId(2)]);
+
+    let account = Address::repeat_byte(0x11);
+    let priority_op = |serial_id: u64, data: ZkSyncPriorityOp| PriorityOp {
+        serial_id,
+        data,
+        deadline_block: 100,
+        eth_hash: H256::repeat_byte(serial_id as u8),
+        eth_block: 10,
+        eth_block_index: Some(serial_id),
+    };
+    let ops = vec![
+        priority_op(
+            1,
+            ZkSyncPriorityOp::Deposit(Deposit {
+                from: Address::repeat_byte(0x22),
+                token: TokenId(2),
+                amount: BigUint::from(100u32),
+                to: account,
+            }),
+        ),
+        priority_op(
+            2,
+            ZkSyncPriorityOp::FullExit(FullExit {
+                account_id: AccountId(1),
+                eth_address: account,
+                token: TokenId(2),
+                is_legacy: false,
+            }),
+        ),
+    ];
+    storage
+        .tokens_schema()
+        .store_withdraw_only_deposits(&ops)
+        .await?;
+    // Already stored deposits are skipped.
+    storage
+        .tokens_schema()
+        .store_withdraw_only_deposits(&ops)
+        .await?;
+
+    // Only the deposit is stored.
+    let deposits = storage
+        .tokens_schema()
+        .load_withdraw_only_deposits(account)
+        .await?;
+    assert_eq!(deposits.len(), 1);
+    assert_eq!(deposits[0].serial_id, 1);
+    assert_eq!(deposits[0].token_id, 2);
+    assert_eq!(
+        deposits[0].eth_hash,
+        H256::repeat_byte(1).as_bytes().to_vec()
+    );
+    assert_eq!(
+        big_decimal_to_ratio(&deposits[0].amount).unwrap(),
+        Ratio::from_integer(BigUint::from(100u32))
+    );
+    assert!(storage
+        .tokens_schema()
+        .load_withdraw_only_deposits(Address::repeat_byte(0x22))
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+/// Checks that the tokens added by the reverted Ethereum blocks are removed with their prices,
+/// unless they already have balances.
+#[db_test]
+async fn test_remove_reverted_tokens(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    const UNUSED_TOKEN_ID: TokenId = TokenId(1);
+    const USED_TOKEN_ID: TokenId = TokenId(2);
+
+    for token_id in &[UNUSED_TOKEN_ID, USED_TOKEN_ID] {
+        storage
+            .tokens_schema()
+            .store_or_update_token(Token::new(
+                *token_id,
+                Address::random(),
+                &format!("ERC20-{}", token_id),
+                18,
+                TokenKind::ERC20,
+            ))
+            .await?;
+        storage
+            .tokens_schema()
+            .update_historical_ticker_price(
+                *token_id,
+                TokenPrice {
+                    usd_price: Ratio::new(BigUint::from(1u32), BigUint::from(2u32)),
+                    last_updated: Utc::now(),
+                },
+            )
+            .await?;
+    }
+
+    let account_id = 1;
+    storage
+        .chain()
+        .state_schema()
+        .apply_storage_account_diff(StorageAccountDiff::Create(StorageAccountCreation {
+            account_id,
+            is_create: true,
+            block_number: 1,
+            address: Address::random().as_bytes().to_vec(),
+            nonce: 0,
+            update_order_id: 0,
+        }))
+        .await?;
+    storage
+        .chain()
+        .state_schema()
+        .apply_storage_account_diff(StorageAccountDiff::BalanceUpdate(StorageAccountUpdate {
+            balance_update_id: 0,
+            account_id,
+            block_number: 1,
+            coin_id: *USED_TOKEN_ID as i32,
+            old_balance: Default::default(),
+            new_balance: biguint_to_big_decimal(BigUint::from(100u32)),
+            old_nonce: 0,
+            new_nonce: 0,
+            update_order_id: 1,
+        }))
+        .await?;
+
+    let removed = storage
+        .tokens_schema()
+        .remove_reverted_tokens(&[UNUSED_TOKEN_ID, USED_TOKEN_ID, TokenId(3)])
+        .await?;
+    assert_eq!(removed, vec![UNUSED_TOKEN_ID]);
+
+    assert!(storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(UNUSED_TOKEN_ID))
+        .await?
+        .is_none());
+    assert!(storage
+        .tokens_schema()
+        .get_historical_ticker_price(UNUSED_TOKEN_ID)
+        .await?
+        .is_none());
+    assert!(storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(USED_TOKEN_ID))
+        .await?
+        .is_some());
+    assert_eq!(storage.tokens_schema().get_max_token_id().await?, 2);
+
+    Ok(())
+}
@@ -2,6 +2,7 @@
 use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 // External imports
+use anyhow::format_err;
 use num::{rational::Ratio, BigUint};
 
 use thiserror::Error;
@@ -10,19 +11,26 @@ use zksync_api_types::v02::{
     pagination::{PaginationDirection, PaginationQuery},
     token::ApiNFT,
 };
-use zksync_types::{AccountId, Address, Token, TokenId, TokenLike, TokenPrice, NFT};
-use zksync_utils::ratio_to_big_decimal;
+use zksync_types::{
+    AccountId, Address, PriorityOp, Token, TokenId, TokenLike, TokenPrice, ZkSyncPriorityOp, NFT,
+};
+use zksync_utils::{biguint_to_big_decimal, ratio_to_big_decimal};
 // Local imports
-use self::records::{DBMarketVolume, DbTickerPrice, DbToken, StorageApiNFT, StorageNFT, TokenKind};
+use self::records::{
+    DBMarketVolume, DbFeeTokenEligibility, DbTickerPrice, DbToken, StorageApiNFT, StorageNFT,
+    TokenKind, WithdrawOnlyDeposit,
+};
 
 use crate::utils::address_to_stored_string;
 use crate::{QueryResult, StorageProcessor};
-use zksync_types::tokens::TokenMarketVolume;
+use zksync_types::tokens::{FeeTokenEligibility, TokenMarketVolume};
 
 pub mod records;
 
 /// Precision of the USD price per token
 pub(crate) const STORED_USD_PRICE_PRECISION: usize = 6;
+/// Lengths of the address suffixes tried in turn to make the display symbol of the token unique.
+const DISPLAY_SYMBOL_SUFFIX_LENGTHS: [usize; 4] = [4, 8, 16, 40];
 
 /// Tokens schema handles the `tokens` table, providing methods to
 /// get and store new tokens.
@@ -38,20 +46,70 @@ pub enum StoreTokenError {
 }
 
 impl<'a, 'c> TokensSchema<'a, 'c> {
+    /// Generates the unique display symbol of the token, since several tokens can share a symbol.
+    ///
+    /// The symbol itself is used if it's not taken by the other tokens, otherwise the shortest
+    /// unique address suffix is appended to it (e.g. "USDC-a0b8"). The display symbol of the
+    /// already stored token is kept as long as its symbol isn't changed.
+    async fn generate_display_symbol(&mut self, token: &Token) -> QueryResult<String> {
+        let stored = sqlx::query!(
+            "SELECT symbol, display_symbol FROM tokens WHERE id = $1",
+            *token.id as i32
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+        if let Some(stored) = stored {
+            if stored.symbol == token.symbol {
+                return Ok(stored.display_symbol);
+            }
+        }
+
+        let address = hex::encode(token.address.as_bytes());
+        let candidates = std::iter::once(token.symbol.clone()).chain(
+            DISPLAY_SYMBOL_SUFFIX_LENGTHS
+                .iter()
+                .map(|len| format!("{}-{}", token.symbol, &address[..*len])),
+        );
+        for candidate in candidates {
+            let is_taken = sqlx::query!(
+                r#"
+                SELECT EXISTS(
+                    SELECT 1 FROM tokens WHERE lower(display_symbol) = lower($1) AND id != $2
+                ) as "is_taken!"
+                "#,
+                candidate,
+                *token.id as i32
+            )
+            .fetch_one(self.0.conn())
+            .await?
+            .is_taken;
+
+            if !is_taken {
+                return Ok(candidate);
+            }
+        }
+        // Addresses are unique, so the symbol with the whole address can only be taken
+        // by a display symbol stored not by this method.
+        Err(format_err!(
+            "Unable to generate a unique display symbol for the token {}",
+            token.address
+        ))
+    }
+
     /// Persists the new token in the database.
+    /// The display symbol of the token is generated, the provided one is ignored.
     pub async fn store_token(&mut self, token: Token) -> Result<(), StoreTokenError> {
         let start = Instant::now();
 
         let token_from_db: Option<Token> = sqlx::query_as!(
             DbToken,
             r#"
-            SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
-            WHERE id = $1 OR address = $2 OR symbol = $3
+            SELECT id, address, decimals, kind as "kind: _", symbol, display_symbol FROM tokens
+            WHERE id = $1 OR address = $2
             LIMIT 1
             "#,
             *token.id as i32,
             address_to_stored_string(&token.address),
-            token.symbol,
         )
         .fetch_optional(self.0.conn())
         .await
@@ -64,9 +122,6 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
             if token_from_db.id == token.id {
                 matched_parameters.push(format!("id = {}", token.id));
             }
-            if token_from_db.symbol == token.symbol {
-                matched_parameters.push(format!("symbol = {}", token.symbol));
-            }
             if token_from_db.address == token.address {
                 matched_parameters.push(format!("address = {}", token.address));
             }
@@ -79,17 +134,22 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
             return Err(StoreTokenError::TokenAlreadyExistsError(error_message));
         }
 
+        let display_symbol = self
+            .generate_display_symbol(&token)
+            .await
+            .map_err(StoreTokenError::Other)?;
         let kind: TokenKind = token.kind.into();
         sqlx::query!(
             r#"
-            INSERT INTO tokens ( id, address, symbol, decimals, kind )
-            VALUES ( $1, $2, $3, $4, $5 )
+            INSERT INTO tokens ( id, address, symbol, decimals, kind, display_symbol )
+            VALUES ( $1, $2, $3, $4, $5, $6 )
             "#,
             token.id.0 as i32,
             address_to_stored_string(&token.address),
             token.symbol,
             i16::from(token.decimals),
-            kind as TokenKind
+            kind as TokenKind,
+            display_symbol
         )
         .execute(self.0.conn())
         .await
@@ -101,22 +161,25 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
 
     /// If a token with a given ID exists, then it replaces the information about the
     /// token with a new one, otherwise, saves the token.
+    /// The display symbol of the token is generated, the provided one is ignored.
     pub async fn store_or_update_token(&mut self, token: Token) -> QueryResult<()> {
         let start = Instant::now();
+        let display_symbol = self.generate_display_symbol(&token).await?;
         let kind: TokenKind = token.kind.into();
         sqlx::query!(
             r#"
-            INSERT INTO tokens ( id, address, symbol, decimals, kind )
-            VALUES ( $1, $2, $3, $4, $5 )
+            INSERT INTO tokens ( id, address, symbol, decimals, kind, display_symbol )
+            VALUES ( $1, $2, $3, $4, $5, $6 )
             ON CONFLICT (id)
             DO
-              UPDATE SET address = $2, symbol = $3, decimals = $4, kind = $5
+              UPDATE SET address = $2, symbol = $3, decimals = $4, kind = $5, display_symbol = $6
             "#,
             *token.id as i32,
             address_to_stored_string(&token.address),
             token.symbol,
             i16::from(token.decimals),
-            kind as TokenKind
+            kind as TokenKind,
+            display_symbol
         )
         .execute(self.0.conn())
         .await?;
@@ -125,6 +188,69 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Removes the tokens added by the Ethereum blocks reverted by the chain reorganization,
+    /// together with their prices, market volumes and fee eligibility.
+    /// Tokens that already have balances can't be removed and are kept.
+    /// Returns the ids of the removed tokens.
+    pub async fn remove_reverted_tokens(
+        &mut self,
+        token_ids: &[TokenId],
+    ) -> QueryResult<Vec<TokenId>> {
+        let start = Instant::now();
+        let token_ids: Vec<i32> = token_ids.iter().map(|id| **id as i32).collect();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let removed_ids: Vec<i32> = sqlx::query!(
+            r#"
+            SELECT id FROM tokens
+            WHERE id = ANY($1)
+                AND NOT EXISTS (SELECT 1 FROM balances WHERE coin_id = tokens.id)
+                AND NOT EXISTS (SELECT 1 FROM account_balance_updates WHERE coin_id = tokens.id)
+            "#,
+            &token_ids
+        )
+        .fetch_all(transaction.conn())
+        .await?
+        .into_iter()
+        .map(|record| record.id)
+        .collect();
+
+        sqlx::query!(
+            "DELETE FROM ticker_price WHERE token_id = ANY($1)",
+            &removed_ids
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM token_market_volume WHERE token_id = ANY($1)",
+            &removed_ids
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM fee_token_eligibility WHERE token_id = ANY($1)",
+            &removed_ids
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM fee_token_eligibility_history WHERE token_id = ANY($1)",
+            &removed_ids
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!("DELETE FROM tokens WHERE id = ANY($1)", &removed_ids)
+            .execute(transaction.conn())
+            .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.token.remove_reverted_tokens", start.elapsed());
+        Ok(removed_ids
+            .into_iter()
+            .map(|id| TokenId(id as u32))
+            .collect())
+    }
+
     /// Loads tokens from the database starting from the given id with the given limit in the ascending order.
     pub async fn load_tokens_asc(
         &mut self,
@@ -136,7 +262,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         let tokens = sqlx::query_as!(
             DbToken,
             r#"
-            SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+            SELECT id, address, decimals, kind as "kind: _", symbol, display_symbol FROM tokens
             WHERE id >= $1 AND kind = 'ERC20'::token_kind
             ORDER BY id ASC
             LIMIT $2
@@ -163,7 +289,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         let tokens = sqlx::query_as!(
             DbToken,
             r#"
-            SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+            SELECT id, address, decimals, kind as "kind: _", symbol, display_symbol FROM tokens
             WHERE id <= $1 AND kind = 'ERC20'::token_kind
             ORDER BY id DESC
             LIMIT $2
@@ -233,6 +359,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
     /// Loads all the stored tokens, which have market_volume (ticker_market_volume table)
     /// not less than parameter (min_market_volume)
     /// And have not null price in ticker_price
+    /// And are not disabled by the fee token policy (fee_token_eligibility table)
     pub async fn load_tokens_by_market_volume(
         &mut self,
         min_market_volume: Ratio<BigUint>,
@@ -241,7 +368,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         let tokens = sqlx::query_as!(
             DbToken,
             r#"
-            SELECT id, address, decimals, kind as "kind: _", symbol
+            SELECT id, address, decimals, kind as "kind: _", symbol, display_symbol
             FROM tokens
             INNER JOIN ticker_market_volume
             ON tokens.id = ticker_market_volume.token_id
@@ -250,6 +377,10 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
             WHERE ticker_market_volume.market_volume >= $1
             AND ticker_price.usd_price > 0
             AND kind = 'ERC20'::token_kind
+            AND NOT EXISTS (
+                SELECT 1 FROM fee_token_eligibility
+                WHERE fee_token_eligibility.token_id = tokens.id AND NOT fee_token_eligibility.eligible
+            )
             ORDER BY id ASC
             "#,
             ratio_to_big_decimal(&min_market_volume, STORED_USD_PRICE_PRECISION)
@@ -269,7 +400,8 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         result
     }
 
-    /// Filters out tokens whose market volume is less than the specified limit (min_market_volume).
+    /// Filters out tokens whose market volume is less than the specified limit (min_market_volume)
+    /// and the tokens disabled by the fee token policy.
     pub async fn filter_tokens_by_market_volume(
         &mut self,
         tokens_to_check: Vec<TokenId>,
@@ -285,6 +417,11 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
             ON ticker_market_volume.token_id = ticker_price.token_id
             WHERE ticker_market_volume.token_id = ANY($1) AND market_volume >= $2
             AND ticker_price.usd_price > 0
+            AND NOT EXISTS (
+                SELECT 1 FROM fee_token_eligibility
+                WHERE fee_token_eligibility.token_id = ticker_market_volume.token_id
+                AND NOT fee_token_eligibility.eligible
+            )
             "#,
             &tokens_to_check,
             ratio_to_big_decimal(min_market_volume, STORED_USD_PRICE_PRECISION)
@@ -417,7 +554,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
                 sqlx::query_as!(
                     DbToken,
                     r#"
-                    SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+                    SELECT id, address, decimals, kind as "kind: _", symbol, display_symbol FROM tokens
                     WHERE id = $1
                     LIMIT 1
                     "#,
@@ -430,7 +567,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
                 sqlx::query_as!(
                     DbToken,
                     r#"
-                    SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+                    SELECT id, address, decimals, kind as "kind: _", symbol, display_symbol FROM tokens
                     WHERE address = $1
                     LIMIT 1
                     "#,
@@ -446,11 +583,13 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
                 // 1) don't have too much tokens.
                 // 2) most tokens requests will be handled by `TokenDbCache` anyway,
                 // so it shouldn't be a problem.
+                // Several tokens can share a symbol, so the unique display symbol is used for lookups.
+                // The first listed token is found by its symbol, since it's equal to the display one.
                 sqlx::query_as!(
                     DbToken,
                     r#"
-                    SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
-                    WHERE lower(symbol) = lower($1)
+                    SELECT id, address, decimals, kind as "kind: _", symbol, display_symbol FROM tokens
+                    WHERE lower(display_symbol) = lower($1)
                     LIMIT 1
                     "#,
                     token_symbol
@@ -515,6 +654,218 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Loads the current decision of the fee token policy for the token, if any.
+    pub async fn get_fee_token_eligibility(
+        &mut self,
+        token_id: TokenId,
+    ) -> QueryResult<Option<FeeTokenEligibility>> {
+        let start = Instant::now();
+        let eligibility = sqlx::query_as!(
+            DbFeeTokenEligibility,
+            r#"
+            SELECT token_id, eligible, reason, market_volume, market_cap, decided_at
+            FROM fee_token_eligibility
+            WHERE token_id = $1
+            "#,
+            *token_id as i32
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.get_fee_token_eligibility", start.elapsed());
+        Ok(eligibility.map(|eligibility| eligibility.into()))
+    }
+
+    /// Stores the decision of the fee token policy, replacing the current one
+    /// and recording it in the decision history.
+    pub async fn store_fee_token_eligibility(
+        &mut self,
+        eligibility: FeeTokenEligibility,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let market_volume =
+            ratio_to_big_decimal(&eligibility.market_volume, STORED_USD_PRICE_PRECISION);
+        let market_cap = eligibility
+            .market_cap
+            .as_ref()
+            .map(|market_cap| ratio_to_big_decimal(market_cap, STORED_USD_PRICE_PRECISION));
+
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO fee_token_eligibility ( token_id, eligible, reason, market_volume, market_cap, decided_at )
+            VALUES ( $1, $2, $3, $4, $5, $6 )
+            ON CONFLICT (token_id)
+            DO
+              UPDATE SET eligible = $2, reason = $3, market_volume = $4, market_cap = $5, decided_at = $6
+            "#,
+            *eligibility.token_id as i32,
+            eligibility.eligible,
+            eligibility.reason,
+            market_volume,
+            market_cap,
+            eligibility.decided_at
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO fee_token_eligibility_history ( token_id, eligible, reason, market_volume, market_cap, decided_at )
+            VALUES ( $1, $2, $3, $4, $5, $6 )
+            "#,
+            *eligibility.token_id as i32,
+            eligibility.eligible,
+            eligibility.reason,
+            market_volume,
+            market_cap,
+            eligibility.decided_at
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.token.store_fee_token_eligibility", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the latest decisions of the fee token policy for the token, newest first.
+    pub async fn load_fee_token_eligibility_history(
+        &mut self,
+        token_id: TokenId,
+        limit: u32,
+    ) -> QueryResult<Vec<FeeTokenEligibility>> {
+        let start = Instant::now();
+        let history = sqlx::query_as!(
+            DbFeeTokenEligibility,
+            r#"
+            SELECT token_id, eligible, reason, market_volume, market_cap, decided_at
+            FROM fee_token_eligibility_history
+            WHERE token_id = $1
+            ORDER BY id DESC
+            LIMIT $2
+            "#,
+            *token_id as i32,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.token.load_fee_token_eligibility_history",
+            start.elapsed()
+        );
+        Ok(history.into_iter().map(|record| record.into()).collect())
+    }
+
+    /// Adds the token to the blacklist. Transactions with the blacklisted tokens are rejected,
+    /// except for the withdrawals to the owner's own address.
+    pub async fn add_token_to_blacklist(
+        &mut self,
+        token_id: TokenId,
+        reason: String,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO token_blacklist ( token_id, reason, added_at )
+            VALUES ( $1, $2, now() )
+            ON CONFLICT (token_id)
+            DO
+              UPDATE SET reason = $2
+            "#,
+            *token_id as i32,
+            reason
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.add_token_to_blacklist", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes the token from the blacklist.
+    pub async fn remove_token_from_blacklist(&mut self, token_id: TokenId) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM token_blacklist WHERE token_id = $1",
+            *token_id as i32
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.remove_token_from_blacklist", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the ids of the blacklisted tokens.
+    pub async fn load_token_blacklist(&mut self) -> QueryResult<HashSet<TokenId>> {
+        let start = Instant::now();
+        let blacklist = sqlx::query!("SELECT token_id FROM token_blacklist")
+            .fetch_all(self.0.conn())
+            .await?
+            .into_iter()
+            .map(|record| TokenId(record.token_id as u32))
+            .collect();
+
+        metrics::histogram!("sql.token.load_token_blacklist", start.elapsed());
+        Ok(blacklist)
+    }
+
+    /// Marks the deposits among the given priority operations as withdraw-only.
+    /// Operations that are already marked are skipped.
+    pub async fn store_withdraw_only_deposits(&mut self, ops: &[PriorityOp]) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        for op in ops {
+            let deposit = match &op.data {
+                ZkSyncPriorityOp::Deposit(deposit) => deposit,
+                ZkSyncPriorityOp::FullExit(_) => continue,
+            };
+            sqlx::query!(
+                r#"
+                INSERT INTO withdraw_only_deposits ( serial_id, token_id, address, amount, eth_hash, created_at )
+                VALUES ( $1, $2, $3, $4, $5, now() )
+                ON CONFLICT (serial_id)
+                DO NOTHING
+                "#,
+                op.serial_id as i64,
+                *deposit.token as i32,
+                address_to_stored_string(&deposit.to),
+                biguint_to_big_decimal(deposit.amount.clone()),
+                op.eth_hash.as_bytes()
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.token.store_withdraw_only_deposits", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the withdraw-only deposits to the given account.
+    pub async fn load_withdraw_only_deposits(
+        &mut self,
+        address: Address,
+    ) -> QueryResult<Vec<WithdrawOnlyDeposit>> {
+        let start = Instant::now();
+        let deposits = sqlx::query_as!(
+            WithdrawOnlyDeposit,
+            r#"
+            SELECT * FROM withdraw_only_deposits
+            WHERE address = $1
+            ORDER BY serial_id ASC
+            "#,
+            address_to_stored_string(&address)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.load_withdraw_only_deposits", start.elapsed());
+        Ok(deposits)
+    }
+
     /// Given token id, returns its price in USD and a timestamp of the last update.
     pub async fn get_historical_ticker_price(
         &mut self,
@@ -9,7 +9,7 @@ use crate::utils::{address_to_stored_string, stored_str_address_to_address};
 use chrono::{DateTime, Utc};
 use zksync_api_types::v02::token::ApiNFT;
 use zksync_types::{
-    tokens::{TokenMarketVolume, TokenPrice},
+    tokens::{FeeTokenEligibility, TokenMarketVolume, TokenPrice},
     AccountId, Address, Token, TokenId, H256, NFT,
 };
 use zksync_utils::big_decimal_to_ratio;
@@ -21,6 +21,7 @@ pub struct DbToken {
     pub symbol: String,
     pub decimals: i16,
     pub kind: TokenKind,
+    pub display_symbol: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq, sqlx::Type)]
@@ -59,19 +60,22 @@ impl From<Token> for DbToken {
             symbol: token.symbol,
             decimals: token.decimals as i16,
             kind: token.kind.into(),
+            display_symbol: token.display_symbol,
         }
     }
 }
 
 impl From<DbToken> for Token {
     fn from(val: DbToken) -> Token {
-        Token::new(
+        let mut token = Token::new(
             TokenId(val.id as u32),
             stored_str_address_to_address(&val.address),
             &val.symbol,
             val.decimals as u8,
             val.kind.into(),
-        )
+        );
+        token.display_symbol = val.display_symbol;
+        token
     }
 }
 
@@ -168,3 +172,41 @@ impl From<DBMarketVolume> for TokenMarketVolume {
         }
     }
 }
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbFeeTokenEligibility {
+    pub token_id: i32,
+    pub eligible: bool,
+    pub reason: String,
+    pub market_volume: BigDecimal,
+    pub market_cap: Option<BigDecimal>,
+    pub decided_at: DateTime<Utc>,
+}
+
+impl From<DbFeeTokenEligibility> for FeeTokenEligibility {
+    fn from(val: DbFeeTokenEligibility) -> Self {
+        Self {
+            token_id: TokenId(val.token_id as u32),
+            eligible: val.eligible,
+            reason: val.reason,
+            market_volume: big_decimal_to_ratio(&val.market_volume)
+                .expect("Market volume could not be negative"),
+            market_cap: val.market_cap.map(|market_cap| {
+                big_decimal_to_ratio(&market_cap).expect("Market cap could not be negative")
+            }),
+            decided_at: val.decided_at,
+        }
+    }
+}
+
+/// Deposit of the blacklisted token, which funds can only be withdrawn back.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct WithdrawOnlyDeposit {
+    pub serial_id: i64,
+    pub token_id: i32,
+    /// L2 account the funds were deposited to.
+    pub address: String,
+    pub amount: BigDecimal,
+    pub eth_hash: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+}
@@ -114,7 +114,9 @@ impl TokenDBCache {
         let tokens = Self::get_all_tokens(storage).await.unwrap();
         let mut cache = self.cache.write().await;
         for token in tokens {
-            let symbol = TokenLike::Symbol(token.symbol.clone());
+            // Several tokens can share the raw symbol, so the tokens are looked up by the unique
+            // display symbol, the same way the storage does.
+            let symbol = TokenLike::Symbol(token.display_symbol.clone());
             let token_id = TokenLike::Id(token.id);
             let address = TokenLike::Address(token.address);
             cache.insert(symbol.to_lowercase(), (token.clone(), Instant::now()));
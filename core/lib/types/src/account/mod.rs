@@ -14,7 +14,9 @@ use zksync_crypto::{
 };
 use zksync_utils::BigUintSerdeWrapper;
 // Local uses
-use super::{AccountId, AccountUpdates, Address, Fr, Nonce, TokenId, NFT};
+use super::{
+    AccountId, AccountUpdates, Address, AmountError, Fr, Nonce, TokenAmount, TokenId, NFT,
+};
 
 pub use self::{account_update::AccountUpdate, pubkey_hash::PubKeyHash};
 
@@ -176,6 +178,35 @@ impl Account {
         self.balances.insert(token, balance);
     }
 
+    /// Returns the token balance for the account as the amount limited by the protocol.
+    /// The stored balance can exceed `BALANCE_BIT_WIDTH` bits only if it was set bypassing
+    /// the checked operations, which is reported as `AmountError::TooBig`.
+    pub fn get_balance_amount(&self, token: TokenId) -> Result<TokenAmount, AmountError> {
+        TokenAmount::from_biguint(token, &self.get_balance(token))
+    }
+
+    /// Adds the provided amount to the token balance unless it overflows the protocol limit.
+    pub fn checked_add_balance(
+        &mut self,
+        token: TokenId,
+        amount: TokenAmount,
+    ) -> Result<(), AmountError> {
+        let balance = self.get_balance_amount(token)?.checked_add(amount)?;
+        self.set_balance(token, balance.into());
+        Ok(())
+    }
+
+    /// Subtracts the provided amount from the token balance unless the balance is insufficient.
+    pub fn checked_sub_balance(
+        &mut self,
+        token: TokenId,
+        amount: TokenAmount,
+    ) -> Result<(), AmountError> {
+        let balance = self.get_balance_amount(token)?.checked_sub(amount)?;
+        self.set_balance(token, balance.into());
+        Ok(())
+    }
+
     /// Given the list of updates to apply, changes the account state.
     pub fn apply_updates(mut account: Option<Self>, updates: &[AccountUpdate]) -> Option<Self> {
         for update in updates {
@@ -381,6 +412,40 @@ mod test {
         assert_ne!(account_2, account_1);
     }
 
+    #[test]
+    fn checked_balance_operations() {
+        let token = TokenId(0);
+        let mut account = Account::default();
+
+        account
+            .checked_add_balance(token, TokenAmount::new(token, u128::MAX))
+            .unwrap();
+        assert_eq!(
+            account.checked_add_balance(token, TokenAmount::new(token, 1)),
+            Err(AmountError::Overflow)
+        );
+        account
+            .checked_sub_balance(token, TokenAmount::new(token, u128::MAX))
+            .unwrap();
+        assert_eq!(
+            account.checked_sub_balance(token, TokenAmount::new(token, 1)),
+            Err(AmountError::Underflow)
+        );
+        assert_eq!(account.get_balance(token), BigUint::zero());
+
+        // The balance set bypassing the checked operations is reported instead of a panic.
+        let balance = BigUint::from(u128::MAX) + 1u32;
+        account.set_balance(token, balance.clone());
+        assert_eq!(
+            account.get_balance_amount(token),
+            Err(AmountError::TooBig(balance.clone()))
+        );
+        assert_eq!(
+            account.checked_sub_balance(token, TokenAmount::new(token, 1)),
+            Err(AmountError::TooBig(balance))
+        );
+    }
+
     #[test]
     fn test_default_account() {
         let a = Account::default();
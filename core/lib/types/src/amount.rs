@@ -0,0 +1,240 @@
+//! Token amounts with the protocol limits enforced.
+//!
+//! Balances, amounts and fees are represented as `BigUint` in the transactions and the account
+//! state, while the protocol limits them to `BALANCE_BIT_WIDTH` bits. [`TokenAmount`] and
+//! [`FeeAmount`] check the limit on construction and provide only the checked arithmetic,
+//! so an overflowing operation is reported instead of producing an amount the circuit can't prove.
+//! Fees are a separate type, so they can't be used in place of an amount by mistake.
+//!
+//! In debug builds, the amounts also carry the token id and the operations on amounts of
+//! different tokens panic.
+
+use std::fmt;
+
+use num::{BigUint, ToPrimitive};
+use thiserror::Error;
+use zksync_crypto::params::BALANCE_BIT_WIDTH;
+
+use crate::TokenId;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum AmountError {
+    #[error("Amount {0} doesn't fit into {} bits", BALANCE_BIT_WIDTH)]
+    TooBig(BigUint),
+    #[error("Amount overflow")]
+    Overflow,
+    #[error("Amount underflow")]
+    Underflow,
+}
+
+/// Amount of the token limited by the `BALANCE_BIT_WIDTH` bits.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TokenAmount {
+    value: u128,
+    #[cfg(debug_assertions)]
+    token: TokenId,
+}
+
+impl TokenAmount {
+    pub fn new(token: TokenId, value: u128) -> Self {
+        #[cfg(not(debug_assertions))]
+        let _ = token;
+        Self {
+            value,
+            #[cfg(debug_assertions)]
+            token,
+        }
+    }
+
+    pub fn zero(token: TokenId) -> Self {
+        Self::new(token, 0)
+    }
+
+    /// Checks that the amount doesn't exceed the protocol limit.
+    pub fn from_biguint(token: TokenId, value: &BigUint) -> Result<Self, AmountError> {
+        // `BALANCE_BIT_WIDTH` is exactly 128 bits, so the limit is checked by the conversion.
+        let value = value
+            .to_u128()
+            .ok_or_else(|| AmountError::TooBig(value.clone()))?;
+        Ok(Self::new(token, value))
+    }
+
+    pub fn value(&self) -> u128 {
+        self.value
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+
+    pub fn to_biguint(&self) -> BigUint {
+        BigUint::from(self.value)
+    }
+
+    pub fn checked_add(self, other: Self) -> Result<Self, AmountError> {
+        self.check_token(&other);
+        let value = self
+            .value
+            .checked_add(other.value)
+            .ok_or(AmountError::Overflow)?;
+        Ok(Self { value, ..self })
+    }
+
+    pub fn checked_sub(self, other: Self) -> Result<Self, AmountError> {
+        self.check_token(&other);
+        let value = self
+            .value
+            .checked_sub(other.value)
+            .ok_or(AmountError::Underflow)?;
+        Ok(Self { value, ..self })
+    }
+
+    /// Total amount to be charged from the account: the amount itself and the fee.
+    pub fn checked_add_fee(self, fee: FeeAmount) -> Result<Self, AmountError> {
+        self.checked_add(fee.0)
+    }
+
+    /// Amount remaining after the fee is charged from it.
+    pub fn checked_sub_fee(self, fee: FeeAmount) -> Result<Self, AmountError> {
+        self.checked_sub(fee.0)
+    }
+
+    #[cfg(debug_assertions)]
+    fn check_token(&self, other: &Self) {
+        assert_eq!(
+            self.token, other.token,
+            "Operation on the amounts of different tokens"
+        );
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn check_token(&self, _other: &Self) {}
+}
+
+impl From<TokenAmount> for BigUint {
+    fn from(amount: TokenAmount) -> Self {
+        amount.to_biguint()
+    }
+}
+
+impl fmt::Debug for TokenAmount {
+    #[cfg(debug_assertions)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (token {})", self.value, self.token)
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+/// Fee of the transaction, paid in the transaction token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FeeAmount(TokenAmount);
+
+impl FeeAmount {
+    pub fn new(token: TokenId, value: u128) -> Self {
+        Self(TokenAmount::new(token, value))
+    }
+
+    pub fn zero(token: TokenId) -> Self {
+        Self(TokenAmount::zero(token))
+    }
+
+    /// Checks that the fee doesn't exceed the protocol limit.
+    pub fn from_biguint(token: TokenId, value: &BigUint) -> Result<Self, AmountError> {
+        TokenAmount::from_biguint(token, value).map(Self)
+    }
+
+    pub fn value(&self) -> u128 {
+        self.0.value()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn to_biguint(&self) -> BigUint {
+        self.0.to_biguint()
+    }
+
+    pub fn checked_add(self, other: Self) -> Result<Self, AmountError> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Result<Self, AmountError> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    /// Fee as the amount to be collected by the fee account.
+    pub fn into_amount(self) -> TokenAmount {
+        self.0
+    }
+}
+
+impl From<FeeAmount> for BigUint {
+    fn from(fee: FeeAmount) -> Self {
+        fee.to_biguint()
+    }
+}
+
+impl fmt::Display for FeeAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amount_limit() {
+        let max = BigUint::from(u128::MAX);
+        assert_eq!(
+            TokenAmount::from_biguint(TokenId(0), &max).unwrap().value(),
+            u128::MAX
+        );
+        let too_big = max + 1u32;
+        assert_eq!(
+            TokenAmount::from_biguint(TokenId(0), &too_big),
+            Err(AmountError::TooBig(too_big.clone()))
+        );
+        assert!(FeeAmount::from_biguint(TokenId(0), &too_big).is_err());
+    }
+
+    #[test]
+    fn checked_arithmetic() {
+        let token = TokenId(1);
+        let amount = TokenAmount::new(token, 100);
+        let fee = FeeAmount::new(token, 5);
+
+        assert_eq!(
+            amount.checked_add_fee(fee),
+            Ok(TokenAmount::new(token, 105))
+        );
+        assert_eq!(amount.checked_sub_fee(fee), Ok(TokenAmount::new(token, 95)));
+        assert_eq!(
+            TokenAmount::new(token, u128::MAX).checked_add_fee(fee),
+            Err(AmountError::Overflow)
+        );
+        assert_eq!(
+            fee.checked_sub(FeeAmount::new(token, 6)),
+            Err(AmountError::Underflow)
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Operation on the amounts of different tokens")]
+    fn different_tokens() {
+        let _ = TokenAmount::new(TokenId(0), 1).checked_add(TokenAmount::new(TokenId(1), 1));
+    }
+}
@@ -359,6 +359,18 @@ impl Block {
         self.processed_priority_ops.1 - self.processed_priority_ops.0
     }
 
+    /// Returns the earliest Ethereum deadline block among the priority operations
+    /// processed in this block, if there are any.
+    pub fn priority_ops_deadline_block(&self) -> Option<u64> {
+        self.block_transactions
+            .iter()
+            .filter_map(|op| match op {
+                ExecutedOperations::PriorityOp(op) => Some(op.priority_op.deadline_block),
+                ExecutedOperations::Tx(_) => None,
+            })
+            .min()
+    }
+
     fn chunks_used(&self) -> usize {
         self.block_transactions
             .iter()
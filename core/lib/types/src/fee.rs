@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::helpers::{pack_fee_amount, unpack_fee_amount};
 use crate::tokens::ChangePubKeyFeeTypeArg;
+use crate::TokenId;
 use zksync_utils::{round_precision, BigUintSerdeAsRadix10Str};
 
 /// Type of the fee calculation pattern.
@@ -57,6 +58,16 @@ pub struct TotalFee {
     pub total_fee: BigUint,
 }
 
+/// Lowest price of the fee token at which the fee paid by the transaction still covers its cost.
+/// The token price can drop between the fee quote and the transaction execution, so the fee
+/// is checked against the floor again when the transaction is proposed for the block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeTokenPriceFloor {
+    pub token_id: TokenId,
+    /// Price in USD for one token.
+    pub min_usd_price: Ratio<BigUint>,
+}
+
 impl BatchFee {
     pub fn new(zkp_fee: Ratio<BigUint>, gas_fee: Ratio<BigUint>) -> Self {
         let (zkp_fee, gas_fee, total_fee) = total_fee(&zkp_fee, &gas_fee);
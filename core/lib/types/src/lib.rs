@@ -39,6 +39,7 @@
 
 pub mod account;
 pub mod aggregated_operations;
+pub mod amount;
 pub mod block;
 pub mod ethereum;
 pub mod event;
@@ -49,20 +50,24 @@ pub mod helpers;
 pub mod mempool;
 pub mod network;
 pub mod operations;
+pub mod packing;
+pub mod pipeline;
 pub mod priority_ops;
 pub mod prover;
 pub mod register_factory;
 pub mod tokens;
 pub mod tx;
 mod utils;
+pub mod validator_status;
 
 #[cfg(test)]
 mod tests;
 pub mod withdrawals;
 
 pub use self::account::{Account, AccountUpdate, PubKeyHash};
+pub use self::amount::{AmountError, FeeAmount, TokenAmount};
 pub use self::block::{ExecutedOperations, ExecutedPriorityOp, ExecutedTx};
-pub use self::fee::{BatchFee, Fee, OutputFeeType, TotalFee};
+pub use self::fee::{BatchFee, Fee, FeeTokenPriceFloor, OutputFeeType, TotalFee};
 pub use self::operations::{
     ChangePubKeyOp, DepositOp, ForcedExitOp, FullExitOp, MintNFTOp, SwapOp, TransferOp,
     TransferToNewOp, WithdrawNFTOp, WithdrawOp, ZkSyncOp,
@@ -75,6 +80,8 @@ pub use self::tokens::{
 pub use self::tx::{
     ForcedExit, MintNFT, Order, SignedZkSyncTx, Swap, Transfer, Withdraw, WithdrawNFT, ZkSyncTx,
 };
+pub use self::utils::chain_bound_ethereum_message;
+pub use self::validator_status::ValidatorStatusUpdateEvent;
 
 pub use self::tx::eip712_signature;
 
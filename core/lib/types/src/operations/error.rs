@@ -147,6 +147,8 @@ pub enum PublicDataDecodeError {
     EmptyData,
     #[error("Unknown operation type")]
     UnknownOperationType,
+    #[error("Public data ends in the middle of the operation")]
+    TruncatedData,
     #[error(transparent)]
     ChangePubkeyOpError(#[from] ChangePubkeyOpError),
     #[error(transparent)]
@@ -31,6 +31,23 @@ pub use self::{
 };
 use crate::operations::error::{PublicDataDecodeError, UnexpectedOperationType};
 
+/// Version of the operations public data encoding.
+///
+/// Unlike the signed transaction bytes, the public data can't carry the version byte: its layout
+/// is fixed by the circuit and the contract, so the version is defined by the contract the block
+/// was committed to. A new layout gets a new version, so the blocks committed before the upgrade
+/// are still decoded with the previous one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PubdataVersion {
+    /// Encoding prior to the v6 upgrade, the token id bit width is 2 bytes instead of 4.
+    Legacy,
+    V1,
+}
+
+impl PubdataVersion {
+    pub const CURRENT: Self = Self::V1;
+}
+
 /// zkSync network operation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -183,6 +200,37 @@ impl ZkSyncOp {
         }
     }
 
+    /// Attempts to restore the operation from the public data encoded with the given version.
+    pub fn from_versioned_public_data(
+        bytes: &[u8],
+        version: PubdataVersion,
+    ) -> Result<Self, PublicDataDecodeError> {
+        match version {
+            PubdataVersion::Legacy => Self::from_legacy_public_data(bytes),
+            PubdataVersion::V1 => Self::from_public_data(bytes),
+        }
+    }
+
+    /// Splits the public data of the block into the operations encoded with the given version.
+    pub fn ops_from_public_data(
+        data: &[u8],
+        version: PubdataVersion,
+    ) -> Result<Vec<Self>, PublicDataDecodeError> {
+        let mut data = data;
+        let mut ops = Vec::new();
+        while let Some(&op_type) = data.first() {
+            let op_data_len = Self::versioned_public_data_length(op_type, version)
+                .map_err(|_| PublicDataDecodeError::UnknownOperationType)?;
+            if data.len() < op_data_len {
+                return Err(PublicDataDecodeError::TruncatedData);
+            }
+            let (op_data, rest) = data.split_at(op_data_len);
+            ops.push(Self::from_versioned_public_data(op_data, version)?);
+            data = rest;
+        }
+        Ok(ops)
+    }
+
     /// Attempts to restore the operation from the public data committed on the Ethereum smart contract
     /// prior to v6 upgrade. The token id bit width is 2 bytes instead of 4.
     ///
@@ -257,6 +305,17 @@ impl ZkSyncOp {
         .map(|chunks| chunks * LEGACY_CHUNK_BYTES)
     }
 
+    /// Returns the public data length for a certain type of operation encoded with the given version.
+    pub fn versioned_public_data_length(
+        op_type: u8,
+        version: PubdataVersion,
+    ) -> Result<usize, UnexpectedOperationType> {
+        match version {
+            PubdataVersion::Legacy => Self::legacy_public_data_length(op_type),
+            PubdataVersion::V1 => Self::public_data_length(op_type),
+        }
+    }
+
     /// Attempts to interpret the operation as the L2 transaction.
     pub fn try_get_tx(&self) -> Result<ZkSyncTx, UnexpectedOperationType> {
         match self {
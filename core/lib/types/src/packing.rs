@@ -0,0 +1,288 @@
+//! Packing of the token amounts and fees.
+//!
+//! To reduce the size of the pubdata, amounts and fees of the transactions are stored
+//! in the floating-point form: `mantissa * 10^exponent`, where the token amount has
+//! `AMOUNT_MANTISSA_BIT_WIDTH` bits of the mantissa and `AMOUNT_EXPONENT_BIT_WIDTH` bits
+//! of the exponent, and the fee has `FEE_MANTISSA_BIT_WIDTH` and `FEE_EXPONENT_BIT_WIDTH` bits
+//! respectively. Only the values representable in this form can be used in the transactions,
+//! see `is_token_amount_packable` and `is_fee_amount_packable`.
+//!
+//! The server, the circuit witness and the SDK use this module, so the packing rules are
+//! implemented in a single place. The following is guaranteed:
+//!
+//! - unpacking a packed packable value returns the same value;
+//! - repacking an unpacked value returns the same value, but not necessarily the same bytes:
+//!   a value may have several packed forms (e.g. `10 * 10^0` and `1 * 10^1`), and packing
+//!   always produces the same one of them;
+//! - `closest_packable_*` functions return the greatest packable value not exceeding the input,
+//!   and `closest_greater_or_eq_packable_*` functions return the smallest packable value not less
+//!   than the input.
+
+use num::{BigUint, FromPrimitive};
+use zksync_crypto::params;
+use zksync_crypto::primitives::FloatConversions;
+
+/// Transforms the token amount into packed form.
+/// If the provided token amount is not packable, it is rounded down to the
+/// closest amount that fits in packed form. As a result, some precision will be lost.
+pub fn pack_token_amount(amount: &BigUint) -> Vec<u8> {
+    FloatConversions::pack(
+        amount,
+        params::AMOUNT_EXPONENT_BIT_WIDTH,
+        params::AMOUNT_MANTISSA_BIT_WIDTH,
+    )
+}
+
+/// Transforms the token amount into packed form.
+/// If the provided token amount is not packable, it is rounded up to the
+/// closest amount that fits in packed form. As a result, some precision will be lost.
+pub fn pack_token_amount_up(amount: &BigUint) -> Vec<u8> {
+    FloatConversions::pack_up(
+        amount,
+        params::AMOUNT_EXPONENT_BIT_WIDTH,
+        params::AMOUNT_MANTISSA_BIT_WIDTH,
+    )
+}
+
+/// Transforms the fee amount into the packed form.
+/// As the packed form for fee is smaller than one for the token,
+/// the same value must be packable as a token amount, but not packable
+/// as a fee amount.
+/// If the provided fee amount is not packable, it is rounded down to the
+/// closest amount that fits in packed form. As a result, some precision will be lost.
+pub fn pack_fee_amount(amount: &BigUint) -> Vec<u8> {
+    FloatConversions::pack(
+        amount,
+        params::FEE_EXPONENT_BIT_WIDTH,
+        params::FEE_MANTISSA_BIT_WIDTH,
+    )
+}
+
+/// Transforms the fee amount into the packed form.
+/// As the packed form for fee is smaller than one for the token,
+/// the same value must be packable as a token amount, but not packable
+/// as a fee amount.
+/// If the provided fee amount is not packable, it is rounded up to the
+/// closest amount that fits in packed form. As a result, some precision will be lost.
+pub fn pack_fee_amount_up(amount: &BigUint) -> Vec<u8> {
+    FloatConversions::pack_up(
+        amount,
+        params::FEE_EXPONENT_BIT_WIDTH,
+        params::FEE_MANTISSA_BIT_WIDTH,
+    )
+}
+
+/// Checks whether the token amount can be packed (and thus used in the transaction).
+pub fn is_token_amount_packable(amount: &BigUint) -> bool {
+    Some(amount.clone()) == unpack_token_amount(&pack_token_amount(amount))
+}
+
+/// Checks whether the fee amount can be packed (and thus used in the transaction).
+pub fn is_fee_amount_packable(amount: &BigUint) -> bool {
+    Some(amount.clone()) == unpack_fee_amount(&pack_fee_amount(amount))
+}
+
+/// Attempts to unpack the token amount.
+pub fn unpack_token_amount(data: &[u8]) -> Option<BigUint> {
+    FloatConversions::unpack(
+        data,
+        params::AMOUNT_EXPONENT_BIT_WIDTH,
+        params::AMOUNT_MANTISSA_BIT_WIDTH,
+    )
+    .and_then(BigUint::from_u128)
+}
+
+/// Attempts to unpack the fee amount.
+pub fn unpack_fee_amount(data: &[u8]) -> Option<BigUint> {
+    FloatConversions::unpack(
+        data,
+        params::FEE_EXPONENT_BIT_WIDTH,
+        params::FEE_MANTISSA_BIT_WIDTH,
+    )
+    .and_then(BigUint::from_u128)
+}
+
+/// Returns the closest possible packable fee amount.
+/// Returned amount is always less or equal to the provided amount.
+pub fn closest_packable_fee_amount(amount: &BigUint) -> BigUint {
+    let fee_packed = pack_fee_amount(amount);
+    unpack_fee_amount(&fee_packed).expect("fee repacking")
+}
+
+/// Returns the closest possible packable fee amount.
+/// Returned amount is always greater or equal to the provided amount.
+pub fn closest_greater_or_eq_packable_fee_amount(amount: &BigUint) -> BigUint {
+    let fee_packed = pack_fee_amount_up(amount);
+    unpack_fee_amount(&fee_packed).expect("fee repacking")
+}
+
+/// Returns the closest possible packable token amount.
+/// Returned amount is always less or equal to the provided amount.
+pub fn closest_packable_token_amount(amount: &BigUint) -> BigUint {
+    let amount_packed = pack_token_amount(amount);
+    unpack_token_amount(&amount_packed).expect("token amount repacking")
+}
+
+/// Returns the closest possible packable token amount.
+/// Returned amount is always greater or equal to the provided amount.
+pub fn closest_greater_or_eq_packable_token_amount(amount: &BigUint) -> BigUint {
+    let amount_packed = pack_token_amount_up(amount);
+    unpack_token_amount(&amount_packed).expect("token amount repacking")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_crypto::rand::{Rng, SeedableRng, XorShiftRng};
+
+    #[test]
+    fn test_roundtrip() {
+        let zero = BigUint::from_u32(0).unwrap();
+        let one = BigUint::from_u32(1).unwrap();
+        {
+            let round_trip_zero = unpack_token_amount(&pack_token_amount(&zero));
+            let round_trip_one = unpack_token_amount(&pack_token_amount(&one));
+            assert_eq!(Some(zero.clone()), round_trip_zero);
+            assert_eq!(Some(one.clone()), round_trip_one);
+        }
+        {
+            let round_trip_zero = unpack_fee_amount(&pack_fee_amount(&zero));
+            let round_trip_one = unpack_fee_amount(&pack_fee_amount(&one));
+            assert_eq!(Some(zero), round_trip_zero);
+            assert_eq!(Some(one), round_trip_one);
+        }
+    }
+
+    #[test]
+    fn detect_unpackable() {
+        let max_mantissa_token =
+            BigUint::from_u128((1u128 << params::AMOUNT_MANTISSA_BIT_WIDTH) - 1).unwrap();
+        let max_mantissa_fee =
+            BigUint::from_u128((1u128 << params::FEE_MANTISSA_BIT_WIDTH) - 1).unwrap();
+        assert!(is_token_amount_packable(&max_mantissa_token));
+        assert!(is_fee_amount_packable(&max_mantissa_fee));
+        assert!(!is_token_amount_packable(
+            &(max_mantissa_token + BigUint::from(1u32))
+        ));
+        assert!(!is_fee_amount_packable(
+            &(max_mantissa_fee + BigUint::from(1u32))
+        ));
+    }
+
+    #[test]
+    fn pack_to_closest_packable() {
+        let fee = BigUint::from(1_234_123_424u32);
+        assert!(
+            !is_fee_amount_packable(&fee),
+            "fee should not be packable for this test"
+        );
+        let closest_packable_fee = closest_packable_fee_amount(&fee);
+        assert!(
+            is_fee_amount_packable(&closest_packable_fee),
+            "repacked fee should be packable"
+        );
+        assert_ne!(
+            closest_packable_fee,
+            BigUint::from(0u32),
+            "repacked fee should not be 0"
+        );
+        assert!(
+            closest_packable_fee < fee,
+            "packable fee should be less then original"
+        );
+        println!(
+            "fee: original: {}, truncated: {}",
+            fee, closest_packable_fee
+        );
+
+        let token = BigUint::from(123_456_789_123_456_789u64);
+        assert!(
+            !is_token_amount_packable(&token),
+            "token should not be packable for this test"
+        );
+        let closest_packable_token = closest_packable_token_amount(&token);
+        assert!(
+            is_token_amount_packable(&closest_packable_token),
+            "repacked token amount should be packable"
+        );
+        assert_ne!(
+            closest_packable_token,
+            BigUint::from(0u32),
+            "repacked token should not be 0"
+        );
+        assert!(
+            closest_packable_token < token,
+            "packable token should be less then original"
+        );
+        println!(
+            "token: original: {}, packable: {}",
+            token, closest_packable_token
+        );
+    }
+
+    /// Checks that every packed fee is unpacked and packed back to the same value.
+    #[test]
+    fn fee_packing_roundtrip_exhaustive() {
+        assert_eq!(
+            params::FEE_EXPONENT_BIT_WIDTH + params::FEE_MANTISSA_BIT_WIDTH,
+            16
+        );
+        for packed in 0..=u16::MAX {
+            let fee = unpack_fee_amount(&packed.to_be_bytes()).expect("fee unpacking");
+            assert!(is_fee_amount_packable(&fee));
+            // The same value can have several packed forms, e.g. `10 * 10^0` and `1 * 10^1`.
+            let repacked = pack_fee_amount(&fee);
+            assert_eq!(unpack_fee_amount(&repacked), Some(fee.clone()));
+            assert_eq!(
+                pack_fee_amount(&unpack_fee_amount(&repacked).unwrap()),
+                repacked
+            );
+            assert_eq!(closest_packable_fee_amount(&fee), fee);
+            assert_eq!(closest_greater_or_eq_packable_fee_amount(&fee), fee);
+        }
+    }
+
+    /// Checks the round trip of the token amounts with every exponent and random mantissas.
+    #[test]
+    fn token_amount_packing_roundtrip() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let max_exponent = (1u32 << params::AMOUNT_EXPONENT_BIT_WIDTH) - 1;
+        for exponent in 0..=max_exponent {
+            for _ in 0..100 {
+                let mantissa = rng.gen::<u64>() % (1u64 << params::AMOUNT_MANTISSA_BIT_WIDTH);
+                let amount = BigUint::from(mantissa) * BigUint::from(10u32).pow(exponent);
+                if amount.bits() > params::BALANCE_BIT_WIDTH as u64 {
+                    continue;
+                }
+                assert!(is_token_amount_packable(&amount), "{}", amount);
+                assert_eq!(
+                    unpack_token_amount(&pack_token_amount(&amount)),
+                    Some(amount.clone())
+                );
+                assert_eq!(closest_packable_token_amount(&amount), amount);
+                assert_eq!(closest_greater_or_eq_packable_token_amount(&amount), amount);
+            }
+        }
+    }
+
+    /// Checks that the closest packable values are the nearest ones from below and above.
+    #[test]
+    fn closest_packable_bounds() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        for _ in 0..1000 {
+            let amount = BigUint::from(rng.gen::<u64>());
+
+            let lower = closest_packable_token_amount(&amount);
+            let upper = closest_greater_or_eq_packable_token_amount(&amount);
+            assert!(lower <= amount && amount <= upper);
+            assert!(is_token_amount_packable(&lower) && is_token_amount_packable(&upper));
+            assert_eq!(lower == upper, is_token_amount_packable(&amount));
+
+            let lower = closest_packable_fee_amount(&amount);
+            let upper = closest_greater_or_eq_packable_fee_amount(&amount);
+            assert!(lower <= amount && amount <= upper);
+            assert!(is_fee_amount_packable(&lower) && is_fee_amount_packable(&upper));
+            assert_eq!(lower == upper, is_fee_amount_packable(&amount));
+        }
+    }
+}
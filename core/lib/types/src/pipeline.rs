@@ -0,0 +1,82 @@
+//! Stages of the block processing pipeline, which can be paused by the operators
+//! independently of each other, e.g. to respond to an incident without stopping the server.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    /// Accepting new transactions to the mempool, priority operations are still accepted.
+    TxAcceptance,
+    /// Executing the operations and sealing the blocks. While it's paused, no operations are executed,
+    /// including the priority ones, since any of them may seal the pending block.
+    BlockSealing,
+    /// Sending the block commitments to Ethereum.
+    CommitSending,
+    /// Sending the block proofs to Ethereum.
+    ProofSubmission,
+}
+
+impl PipelineStage {
+    pub const ALL: [PipelineStage; 4] = [
+        PipelineStage::TxAcceptance,
+        PipelineStage::BlockSealing,
+        PipelineStage::CommitSending,
+        PipelineStage::ProofSubmission,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PipelineStage::TxAcceptance => "tx_acceptance",
+            PipelineStage::BlockSealing => "block_sealing",
+            PipelineStage::CommitSending => "commit_sending",
+            PipelineStage::ProofSubmission => "proof_submission",
+        }
+    }
+}
+
+impl fmt::Display for PipelineStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PipelineStage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|stage| stage.as_str() == s)
+            .ok_or_else(|| format!("Unknown pipeline stage: {}", s))
+    }
+}
+
+/// Pause of the pipeline stage set by the operator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineStagePause {
+    pub stage: PipelineStage,
+    pub reason: String,
+    pub paused_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pipeline_stage_string_roundtrip() {
+        for stage in PipelineStage::ALL.iter() {
+            assert_eq!(stage.to_string().parse::<PipelineStage>(), Ok(*stage));
+            assert_eq!(
+                serde_json::to_value(stage).unwrap(),
+                serde_json::Value::String(stage.to_string())
+            );
+        }
+        assert!("sealing".parse::<PipelineStage>().is_err());
+    }
+}
@@ -8,4 +8,18 @@ pub enum LogParseError {
     UnsupportedPriorityOpType,
     #[error("Ethereum ABI error: {0}")]
     AbiError(#[from] ethabi::Error),
+    #[error("Failed to decode the `{field}` field of the priority request event: {reason} (data: {data})")]
+    InvalidEventField {
+        field: &'static str,
+        reason: String,
+        data: String,
+    },
+    #[error("Priority request event has no {0}")]
+    MissingEventMetadata(&'static str),
+    #[error("Failed to parse the pubdata of the priority operation of type {op_type}: {reason} (pubdata: {pubdata})")]
+    InvalidPubdata {
+        op_type: u8,
+        reason: Box<LogParseError>,
+        pubdata: String,
+    },
 }
@@ -1,6 +1,6 @@
 //! Definition of zkSync network priority operations: operations initiated from the L1.
 
-use ethabi::{decode, ParamType};
+use ethabi::{decode, ParamType, Token};
 use num::{BigUint, ToPrimitive};
 use parity_crypto::digest::sha256;
 use serde::{Deserialize, Serialize};
@@ -10,7 +10,7 @@ use zksync_crypto::params::{
     ACCOUNT_ID_BIT_WIDTH, BALANCE_BIT_WIDTH, CONTENT_HASH_WIDTH, ETH_ADDRESS_BIT_WIDTH,
     FR_ADDRESS_LEN, LEGACY_TOKEN_BIT_WIDTH, SERIAL_ID_WIDTH, TOKEN_BIT_WIDTH, TX_TYPE_BIT_WIDTH,
 };
-use zksync_utils::BigUintSerdeAsRadix10Str;
+use zksync_utils::{BigUintSerdeAsRadix10Str, Prefix, ZeroxPrefix};
 
 use super::{
     operations::{DepositOp, FullExitOp},
@@ -404,73 +404,122 @@ pub struct PriorityOp {
     pub eth_block_index: Option<u64>,
 }
 
+/// Data of the `NewPriorityRequest` event, see contracts/contracts/Events.sol.
+#[derive(Debug)]
+struct PriorityRequestEvent {
+    sender: Address,
+    serial_id: SerialId,
+    op_type: u8,
+    pubdata: Vec<u8>,
+    expiration_block: u64,
+}
+
+impl PriorityRequestEvent {
+    const FIELDS: [&'static str; 5] =
+        ["sender", "serialId", "opType", "pubData", "expirationBlock"];
+
+    fn params() -> [ParamType; 5] {
+        [
+            ParamType::Address,
+            ParamType::Uint(64),
+            ParamType::Uint(8),
+            ParamType::Bytes,
+            ParamType::Uint(256),
+        ]
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, LogParseError> {
+        let params = Self::params();
+        let tokens = decode(&params, data).map_err(|err| {
+            // The ABI decoder doesn't report the failed parameter, so find the first one
+            // that can't be decoded.
+            let failed = (1..=params.len())
+                .find(|&len| decode(&params[..len], data).is_err())
+                .unwrap_or(params.len());
+            LogParseError::InvalidEventField {
+                field: Self::FIELDS[failed - 1],
+                reason: err.to_string(),
+                data: to_prefixed_hex(data),
+            }
+        })?;
+        // The decoder returns exactly the requested token types.
+        let mut tokens = tokens.into_iter();
+        let sender = tokens.next().and_then(Token::into_address).unwrap();
+        let serial_id = tokens.next().and_then(Token::into_uint).unwrap();
+        let op_type = tokens.next().and_then(Token::into_uint).unwrap();
+        let pubdata = tokens.next().and_then(Token::into_bytes).unwrap();
+        let expiration_block = tokens.next().and_then(Token::into_uint).unwrap();
+
+        Ok(Self {
+            sender,
+            serial_id: Self::uint_field(Self::FIELDS[1], serial_id, 64)?,
+            op_type: Self::uint_field(Self::FIELDS[2], op_type, 8)? as u8,
+            pubdata,
+            expiration_block: Self::uint_field(Self::FIELDS[4], expiration_block, 64)?,
+        })
+    }
+
+    /// Checks that the value fits into `bits` bits (at most 64).
+    fn uint_field(field: &'static str, value: U256, bits: usize) -> Result<u64, LogParseError> {
+        if value.bits() > bits {
+            let mut bytes = [0u8; 32];
+            value.to_big_endian(&mut bytes);
+            return Err(LogParseError::InvalidEventField {
+                field,
+                reason: format!("value doesn't fit into {} bits", bits),
+                data: to_prefixed_hex(&bytes),
+            });
+        }
+        Ok(value.as_u64())
+    }
+}
+
+fn to_prefixed_hex(bytes: &[u8]) -> String {
+    format!("{}{}", ZeroxPrefix::prefix(), hex::encode(bytes))
+}
+
 impl TryFrom<Log> for PriorityOp {
     type Error = LogParseError;
 
     fn try_from(event: Log) -> Result<PriorityOp, LogParseError> {
-        let mut dec_ev = decode(
-            &[
-                ParamType::Address,
-                ParamType::Uint(64),  // Serial id
-                ParamType::Uint(8),   // OpType
-                ParamType::Bytes,     // Pubdata
-                ParamType::Uint(256), // expir. block
-            ],
-            &event.data.0,
-        )?;
-
-        let sender = dec_ev.remove(0).into_address().unwrap();
+        let PriorityRequestEvent {
+            sender,
+            serial_id,
+            op_type,
+            pubdata,
+            expiration_block,
+        } = PriorityRequestEvent::decode(&event.data.0)?;
+
+        let data = ZkSyncPriorityOp::parse_from_priority_queue_logs(&pubdata, op_type, sender)
+            // If parsing was unsuccessful it was probably because of the legacy pub data.
+            // First, try parsing it with a tx type specified.
+            .or_else(|_| {
+                ZkSyncPriorityOp::legacy_parse_from_priority_queue_logs(
+                    &pubdata, op_type, sender, true,
+                )
+            })
+            // The last attempt without tx type.
+            .or_else(|_| {
+                ZkSyncPriorityOp::legacy_parse_from_priority_queue_logs(
+                    &pubdata, op_type, sender, false,
+                )
+            })
+            .map_err(|err| LogParseError::InvalidPubdata {
+                op_type,
+                reason: Box::new(err),
+                pubdata: to_prefixed_hex(&pubdata),
+            })?;
+
         Ok(PriorityOp {
-            serial_id: dec_ev
-                .remove(0)
-                .into_uint()
-                .as_ref()
-                .map(U256::as_u64)
-                .unwrap(),
-            data: {
-                let op_type = dec_ev
-                    .remove(0)
-                    .into_uint()
-                    .as_ref()
-                    .map(|ui| U256::as_u32(ui) as u8)
-                    .unwrap();
-                let op_pubdata = dec_ev.remove(0).into_bytes().unwrap();
-                let result =
-                    ZkSyncPriorityOp::parse_from_priority_queue_logs(&op_pubdata, op_type, sender);
-
-                // If parsing was unsuccessful it was probably because of the legacy pub data
-                result
-                    .or_else(|_| {
-                        // First, try parsing it with a tx type specified.
-                        ZkSyncPriorityOp::legacy_parse_from_priority_queue_logs(
-                            &op_pubdata,
-                            op_type,
-                            sender,
-                            true,
-                        )
-                    })
-                    .or_else(|_| {
-                        // The last attempt without tx type.
-                        ZkSyncPriorityOp::legacy_parse_from_priority_queue_logs(
-                            &op_pubdata,
-                            op_type,
-                            sender,
-                            false,
-                        )
-                    })?
-            },
-            deadline_block: dec_ev
-                .remove(0)
-                .into_uint()
-                .as_ref()
-                .map(U256::as_u64)
-                .unwrap(),
+            serial_id,
+            data,
+            deadline_block: expiration_block,
             eth_hash: event
                 .transaction_hash
-                .expect("Event transaction hash is missing"),
+                .ok_or(LogParseError::MissingEventMetadata("transaction hash"))?,
             eth_block: event
                 .block_number
-                .expect("Event block number is missing")
+                .ok_or(LogParseError::MissingEventMetadata("block number"))?
                 .as_u64(),
             eth_block_index: event.transaction_index.map(|index| index.as_u64()),
         })
@@ -112,3 +112,86 @@ mod backward_compatibility {
         let _new_value: PriorityOp = serde_json::from_value(old_serialized).unwrap();
     }
 }
+
+mod log_parsing {
+    use std::convert::TryFrom;
+
+    use ethabi::Token;
+    use zksync_basic_types::{Log, U256};
+
+    use super::*;
+    use crate::priority_ops::error::LogParseError;
+
+    fn event_log(data: Vec<u8>) -> Log {
+        Log {
+            address: Address::default(),
+            topics: Vec::new(),
+            data: data.into(),
+            block_hash: Some(H256::zero()),
+            block_number: Some(1.into()),
+            transaction_hash: Some(H256::zero()),
+            transaction_index: Some(0.into()),
+            log_index: Some(0.into()),
+            transaction_log_index: None,
+            log_type: None,
+            removed: Some(false),
+        }
+    }
+
+    fn event_data(op_type: U256, pubdata: Vec<u8>) -> Vec<u8> {
+        ethabi::encode(&[
+            Token::Address(Address::repeat_byte(0x11)),
+            Token::Uint(7.into()),
+            Token::Uint(op_type),
+            Token::Bytes(pubdata),
+            Token::Uint(100.into()),
+        ])
+    }
+
+    #[test]
+    fn truncated_event_data() {
+        let mut data = event_data(1.into(), vec![1, 2, 3]);
+        data.truncate(64);
+
+        let err = PriorityOp::try_from(event_log(data.clone())).unwrap_err();
+        match err {
+            LogParseError::InvalidEventField {
+                field, data: hex, ..
+            } => {
+                assert_eq!(field, "opType");
+                assert_eq!(hex, format!("0x{}", hex::encode(&data)));
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn op_type_out_of_range() {
+        let err = PriorityOp::try_from(event_log(event_data(256.into(), Vec::new()))).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                LogParseError::InvalidEventField {
+                    field: "opType",
+                    ..
+                }
+            ),
+            "Unexpected error: {:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn invalid_pubdata() {
+        let err = PriorityOp::try_from(event_log(event_data(1.into(), vec![0xab; 3]))).unwrap_err();
+        match err {
+            LogParseError::InvalidPubdata {
+                op_type, pubdata, ..
+            } => {
+                assert_eq!(op_type, 1);
+                assert_eq!(pubdata, "0xababab");
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+}
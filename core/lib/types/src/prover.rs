@@ -1,3 +1,7 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use zksync_basic_types::BlockNumber;
 
@@ -6,6 +10,8 @@ pub enum ProverJobStatus {
     Idle = 0,
     InProgress = 1,
     Done = 2,
+    /// The job failed to be proven too many times and is not assigned anymore.
+    Quarantined = 3,
 }
 
 impl ProverJobStatus {
@@ -14,6 +20,7 @@ impl ProverJobStatus {
             ProverJobStatus::Idle => 0,
             ProverJobStatus::InProgress => 1,
             ProverJobStatus::Done => 2,
+            ProverJobStatus::Quarantined => 3,
         }
     }
 
@@ -22,13 +29,35 @@ impl ProverJobStatus {
             0 => Self::Idle,
             1 => Self::InProgress,
             2 => Self::Done,
+            3 => Self::Quarantined,
             _ => return Err(IncorrectProverJobStatus(num)),
         })
     }
 }
 
+/// Cause of the failed job attempt recorded in the `rejected_proofs`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProofRejectionCause {
+    /// The proof received from the prover failed the verification.
+    InvalidProof = 0,
+    /// The prover reported that it failed to prove the job.
+    ProvingFailed = 1,
+}
+
+impl ProofRejectionCause {
+    pub fn to_number(self) -> i32 {
+        match self {
+            ProofRejectionCause::InvalidProof => 0,
+            ProofRejectionCause::ProvingFailed => 1,
+        }
+    }
+}
+
 pub const SINGLE_PROOF_JOB_PRIORITY: i32 = 1;
 pub const AGGREGATED_PROOF_JOB_PRIORITY: i32 = 0;
+/// Priority of the jobs whose blocks are nearing the verification deadline.
+/// Jobs with the lower priority value are assigned first.
+pub const URGENT_JOB_PRIORITY: i32 = -1;
 
 #[derive(Debug, Clone)]
 pub struct ProverJob {
@@ -54,6 +83,26 @@ impl ProverJob {
     }
 }
 
+/// Idle jobs of a single work class.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProverWorkClassQueue {
+    pub work_class: ProverWorkClass,
+    /// The highest priority among the idle jobs, lower values are more important.
+    pub job_priority: i32,
+    pub idle_jobs: u32,
+}
+
+/// Load of the prover job queue, used to decide how many provers are required.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProverQueueStats {
+    pub idle_jobs: u32,
+    pub in_progress_jobs: u32,
+    /// The first block of the oldest job which is not proven yet.
+    pub oldest_unproven_block: Option<BlockNumber>,
+    /// Time the oldest job which is not proven yet was created at.
+    pub oldest_unproven_block_created_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Clone)]
 pub enum ProverJobType {
     SingleProof,
@@ -69,6 +118,51 @@ impl ToString for ProverJobType {
     }
 }
 
+/// Class of the prover jobs. Provers may be able to handle only some of the classes,
+/// e.g. only the blocks of certain sizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ProverWorkClass {
+    /// Proof for a single block of the given size in chunks.
+    SingleProof { block_size: usize },
+    /// Aggregated proof for several blocks.
+    AggregatedProof,
+}
+
+impl ProverWorkClass {
+    pub fn job_type(&self) -> ProverJobType {
+        match self {
+            ProverWorkClass::SingleProof { .. } => ProverJobType::SingleProof,
+            ProverWorkClass::AggregatedProof => ProverJobType::AggregatedProof,
+        }
+    }
+
+    pub fn block_size(&self) -> Option<usize> {
+        match self {
+            ProverWorkClass::SingleProof { block_size } => Some(*block_size),
+            ProverWorkClass::AggregatedProof => None,
+        }
+    }
+}
+
+impl fmt::Display for ProverWorkClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProverWorkClass::SingleProof { block_size } => write!(f, "single_proof_{}", block_size),
+            ProverWorkClass::AggregatedProof => write!(f, "aggregated_proof"),
+        }
+    }
+}
+
+/// Prover artifact, i.e. a block witness or a proof, as it's referenced by the database.
+/// Large artifacts can be kept in the object storage, with only their keys in the database.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StoredArtifact<T> {
+    /// The artifact is stored in the database itself.
+    Inline(T),
+    /// Key of the object containing the artifact in the object storage.
+    Object(String),
+}
+
 #[derive(Debug, Error, PartialEq)]
 #[error("Incorrect ProverJobStatus number: {0}")]
 pub struct IncorrectProverJobStatus(pub i32);
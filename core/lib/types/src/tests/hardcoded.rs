@@ -29,8 +29,9 @@ use zksync_basic_types::{AccountId, Address, Nonce, TokenId, H256};
 #[cfg(test)]
 pub mod operations_test {
     use super::*;
+    use crate::operations::PubdataVersion;
     use crate::tx::{ChangePubKeyECDSAData, ChangePubKeyEthAuthData};
-    use crate::{MintNFT, MintNFTOp};
+    use crate::{MintNFT, MintNFTOp, ZkSyncOp};
     use zksync_crypto::params::MIN_NFT_TOKEN_ID;
 
     // Public data parameters, using them we can restore `ZkSyncOp`.
@@ -321,6 +322,55 @@ pub mod operations_test {
         );
     }
 
+    #[test]
+    fn test_versioned_public_data() {
+        // The same operations encoded prior to the v6 upgrade, with 2 bytes token ids.
+        const LEGACY_NOOP_PUBLIC_DATA: &str = "000000000000000000";
+        const LEGACY_TRANSFER_PUBLIC_DATA: &str = "0500000001002a0000000200000005400540";
+
+        let current_data =
+            hex::decode(format!("{}{}", NOOP_PUBLIC_DATA, TRANSFER_PUBLIC_DATA)).unwrap();
+        let legacy_data = hex::decode(format!(
+            "{}{}",
+            LEGACY_NOOP_PUBLIC_DATA, LEGACY_TRANSFER_PUBLIC_DATA
+        ))
+        .unwrap();
+
+        for (data, version) in [
+            (current_data, PubdataVersion::V1),
+            (legacy_data, PubdataVersion::Legacy),
+        ] {
+            let ops = ZkSyncOp::ops_from_public_data(&data, version).unwrap();
+            assert_eq!(ops.len(), 2, "{:?}", version);
+            assert!(matches!(ops[0], ZkSyncOp::Noop(_)), "{:?}", version);
+            match &ops[1] {
+                ZkSyncOp::Transfer(op) => {
+                    assert_eq!((op.from, op.to), (AccountId(1), AccountId(2)));
+                    assert_eq!(op.tx.token, TokenId(42));
+                    assert_eq!(op.tx.amount, BigUint::from(42u32));
+                    assert_eq!(op.tx.fee, BigUint::from(42u32));
+                }
+                op => panic!("unexpected operation {:?} for {:?}", op, version),
+            }
+        }
+
+        let legacy_transfer = hex::decode(LEGACY_TRANSFER_PUBLIC_DATA).unwrap();
+        assert_eq!(
+            ZkSyncOp::ops_from_public_data(&legacy_transfer, PubdataVersion::V1)
+                .unwrap_err()
+                .to_string(),
+            "Public data ends in the middle of the operation"
+        );
+        // Swaps were introduced by the v6 upgrade.
+        let swap = hex::decode(SWAP_PUBLIC_DATA).unwrap();
+        assert_eq!(
+            ZkSyncOp::ops_from_public_data(&swap, PubdataVersion::Legacy)
+                .unwrap_err()
+                .to_string(),
+            "Unknown operation type"
+        );
+    }
+
     #[test]
     fn test_withdrawal_data() {
         let (withdraw, forced_exit, full_exit) = (
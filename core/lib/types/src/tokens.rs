@@ -121,6 +121,11 @@ pub struct Token {
     pub decimals: u8,
     pub kind: TokenKind,
     pub is_nft: bool,
+    /// Unique identifier of the token, since several tokens can share a symbol.
+    /// Equals to the symbol for the first listed token, the next ones get the short
+    /// address suffix (e.g. "USDC-a0b8"). Assigned by the storage when the token is stored.
+    #[serde(default)]
+    pub display_symbol: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
@@ -145,6 +150,7 @@ impl Token {
             decimals,
             kind,
             is_nft: matches!(kind, TokenKind::NFT),
+            display_symbol: symbol.to_string(),
         }
     }
 
@@ -156,6 +162,7 @@ impl Token {
             decimals: 0,
             kind: TokenKind::NFT,
             is_nft: true,
+            display_symbol: symbol.to_string(),
         }
     }
 }
@@ -233,6 +240,20 @@ pub struct TokenPrice {
     pub last_updated: DateTime<Utc>,
 }
 
+/// Decision of the fee token policy whether the token can be used to pay fees.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeTokenEligibility {
+    pub token_id: TokenId,
+    pub eligible: bool,
+    /// Human-readable reason of the decision.
+    pub reason: String,
+    /// Market volume of the token the decision is based on.
+    pub market_volume: Ratio<BigUint>,
+    /// Market cap of the token in USD the decision is based on, if it's known.
+    pub market_cap: Option<Ratio<BigUint>>,
+    pub decided_at: DateTime<Utc>,
+}
+
 /// Token price known to the zkSync network.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenMarketVolume {
@@ -0,0 +1,123 @@
+//! Canonical JSON representation of the signed transactions.
+//!
+//! The regular serde representation of the transactions depends on the field declaration order
+//! (and on whether `serde_json` preserves the insertion order in the workspace), and the zkSync
+//! signatures are encoded as hex strings without a prefix. The canonical form is intended for
+//! the third-party signers that need to reproduce the payload and its hash byte-for-byte:
+//!
+//! - object keys are sorted lexicographically at every nesting level;
+//! - no insignificant whitespace is emitted;
+//! - the zkSync public keys and signatures are lowercase hex with the `0x` prefix, the same
+//!   way the addresses are stored (see `address_to_stored_string` in the storage crate).
+//!
+//! The rest of the values (addresses, Ethereum signatures, amounts as decimal strings) use
+//! their regular serde representation, which is already unambiguous.
+
+use parity_crypto::digest::sha256;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use zksync_basic_types::H256;
+
+use super::ZkSyncTx;
+
+/// Prefix of the hex-encoded values in the canonical representation.
+const HEX_PREFIX: &str = "0x";
+
+/// Serializes the value into the canonical JSON string.
+pub fn to_canonical_json<T: Serialize>(value: &T) -> serde_json::Result<String> {
+    let value = canonicalize(serde_json::to_value(value)?);
+    serde_json::to_string(&value)
+}
+
+/// SHA256 hash of the canonical JSON representation of the value.
+pub fn canonical_json_hash<T: Serialize>(value: &T) -> serde_json::Result<H256> {
+    let json = to_canonical_json(value)?;
+    Ok(H256::from_slice(&sha256(json.as_bytes())))
+}
+
+impl ZkSyncTx {
+    /// Canonical JSON representation of the signed transaction.
+    pub fn canonical_json(&self) -> String {
+        to_canonical_json(self).expect("Transaction is always serializable")
+    }
+
+    /// SHA256 hash of the canonical JSON representation of the signed transaction.
+    ///
+    /// Note that it's not the transaction hash: the latter is calculated over
+    /// the transaction bytes, see [`ZkSyncTx::hash`].
+    pub fn canonical_json_hash(&self) -> H256 {
+        canonical_json_hash(self).expect("Transaction is always serializable")
+    }
+}
+
+fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let is_zksync_signature = is_zksync_signature(&map);
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+
+            let map: Map<String, Value> = entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(hex) if is_zksync_signature => {
+                            Value::String(prefixed_hex(hex))
+                        }
+                        value => canonicalize(value),
+                    };
+                    (key, value)
+                })
+                .collect();
+            Value::Object(map)
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(canonicalize).collect()),
+        value => value,
+    }
+}
+
+/// Checks whether the object is the serialized `TxSignature`.
+fn is_zksync_signature(map: &Map<String, Value>) -> bool {
+    map.len() == 2
+        && matches!(map.get("pubKey"), Some(Value::String(_)))
+        && matches!(map.get("signature"), Some(Value::String(_)))
+}
+
+fn prefixed_hex(hex: String) -> String {
+    let hex = hex.to_lowercase();
+    if hex.starts_with(HEX_PREFIX) {
+        hex
+    } else {
+        format!("{}{}", HEX_PREFIX, hex)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn keys_are_sorted() {
+        let value = json!({
+            "b": 1,
+            "a": { "d": [{ "f": 1, "e": 2 }], "c": "C" },
+        });
+        assert_eq!(
+            to_canonical_json(&value).unwrap(),
+            r#"{"a":{"c":"C","d":[{"e":2,"f":1}]},"b":1}"#
+        );
+    }
+
+    #[test]
+    fn zksync_signature_is_prefixed() {
+        let value = json!({
+            "signature": { "signature": "ABCD", "pubKey": "0x0123" },
+            "ethSignature": "0xef",
+        });
+        assert_eq!(
+            to_canonical_json(&value).unwrap(),
+            r#"{"ethSignature":"0xef","signature":{"pubKey":"0x0123","signature":"0xabcd"}}"#
+        );
+    }
+}
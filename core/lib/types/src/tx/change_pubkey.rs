@@ -1,4 +1,4 @@
-use num::{BigUint, Zero};
+use num::BigUint;
 use std::fmt::{Display, Formatter};
 
 use parity_crypto::Keccak256;
@@ -7,12 +7,12 @@ use thiserror::Error;
 
 use zksync_basic_types::{Address, ChainId, TokenId, H256};
 use zksync_crypto::{
-    params::{max_account_id, max_processable_token, CURRENT_TX_VERSION},
+    params::{max_account_id, max_processable_token, CURRENT_TX_VERSION, FR_ADDRESS_LEN},
     PrivateKey,
 };
-use zksync_utils::{format_units, BigUintSerdeAsRadix10Str};
+use zksync_utils::BigUintSerdeAsRadix10Str;
 
-use super::{PackedEthSignature, TimeRange, TxSignature, VerifiedSignatureCache};
+use super::{PackedEthSignature, TimeRange, TxMessage, TxSignature, VerifiedSignatureCache};
 use crate::tx::error::{
     ChangePubkeySignedDataError, FEE_AMOUNT_IS_NOT_PACKABLE, INVALID_AUTH_DATA, WRONG_ACCOUNT_ID,
     WRONG_FEE_ERROR, WRONG_SIGNATURE, WRONG_TIME_RANGE, WRONG_TOKEN_FOR_PAYING_FEE,
@@ -24,7 +24,11 @@ use crate::{
     tokens::ChangePubKeyFeeTypeArg,
     tx::{
         primitives::eip712_signature::{EIP712TypedStructure, Eip712Domain, StructBuilder},
-        version::TxVersion,
+        version::{
+            ensure_decoded, take_account_id, take_address, take_bytes, take_nonce,
+            take_optional_time_range, take_packed_fee, take_time_range, take_token, TxDecodeError,
+            TxVersion,
+        },
     },
     AccountId, Nonce, TxFeeTypes,
 };
@@ -259,7 +263,7 @@ impl ChangePubKey {
             eth_signature,
             chain_id,
         );
-        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        tx.signature = TxSignature::sign(TxVersion::V1, private_key, &tx.get_bytes());
         tx.check_correctness()?;
         Ok(tx)
     }
@@ -271,14 +275,13 @@ impl ChangePubKey {
         } else {
             if let Some(res) = self
                 .signature
-                .verify_musig(&self.get_old_bytes())
-                .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+                .verify(TxVersion::Legacy, &self.get_old_bytes())
             {
                 return Some((res, TxVersion::Legacy));
             }
             self.signature
-                .verify_musig(&self.get_bytes())
-                .map(|pub_key| (PubKeyHash::from_pubkey(&pub_key), TxVersion::V1))
+                .verify(TxVersion::V1, &self.get_bytes())
+                .map(|pub_key_hash| (pub_key_hash, TxVersion::V1))
         }
     }
 
@@ -318,6 +321,44 @@ impl ChangePubKey {
         out
     }
 
+    /// Decodes the transaction from the bytes produced by `get_bytes_with_version` or
+    /// `get_old_bytes`, dispatching on the encoding version.
+    /// Neither the signatures nor the Ethereum authorization data are encoded, so the decoded
+    /// transaction has none of them.
+    pub fn from_signed_bytes(bytes: &[u8]) -> Result<(Self, TxVersion), TxDecodeError> {
+        let (version, mut bytes) = TxVersion::split_signed_bytes(Self::TX_TYPE, bytes)?;
+        let bytes = &mut bytes;
+
+        let account_id = take_account_id(bytes)?;
+        let account = take_address(bytes)?;
+        let new_pk_hash = PubKeyHash::from_bytes(take_bytes(bytes, FR_ADDRESS_LEN)?).unwrap();
+        let fee_token = take_token(bytes, version)?;
+        let fee = take_packed_fee(bytes)?;
+        let nonce = take_nonce(bytes)?;
+        // The time range is optional only for the legacy transactions.
+        let time_range = match version {
+            TxVersion::Legacy => take_optional_time_range(bytes)?,
+            TxVersion::V1 => Some(take_time_range(bytes)?),
+        };
+        ensure_decoded(bytes)?;
+
+        let tx = Self {
+            account_id,
+            account,
+            new_pk_hash,
+            fee_token,
+            fee,
+            nonce,
+            signature: Default::default(),
+            eth_signature: None,
+            eth_auth_data: None,
+            time_range,
+            chain_id: None,
+            cached_signer: VerifiedSignatureCache::NotCached,
+        };
+        Ok((tx, version))
+    }
+
     /// Provides a message to be signed with the Ethereum private key.
     pub fn get_eth_signed_data(&self) -> Result<Vec<u8>, ChangePubkeySignedDataError> {
         // Fee data is not included into ETH signature input, since it would require
@@ -459,21 +500,13 @@ impl ChangePubKey {
     ///
     /// Note that the second line is optional.
     pub fn get_ethereum_sign_message_part(&self, token_symbol: &str, decimals: u8) -> String {
-        let mut message = format!(
-            "Set signing key: {}",
-            hex::encode(self.new_pk_hash.data).to_ascii_lowercase()
-        );
-        if !self.fee.is_zero() {
-            message.push_str(
-                format!(
-                    "\nFee: {fee} {token}",
-                    fee = format_units(&self.fee, decimals),
-                    token = token_symbol,
-                )
-                .as_str(),
-            );
-        }
-        message
+        TxMessage::new()
+            .line(format!(
+                "Set signing key: {}",
+                hex::encode(self.new_pk_hash.data).to_ascii_lowercase()
+            ))
+            .fee(&self.fee, token_symbol, decimals)
+            .build()
     }
 
     pub fn get_change_pubkey_fee_type(&self) -> ChangePubKeyFeeTypeArg {
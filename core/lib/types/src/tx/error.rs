@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use zksync_basic_types::TokenId;
 
 use crate::tx::{
     change_pubkey, close, forced_exit, mint_nft, swap, transfer, withdraw, withdraw_nft,
@@ -57,6 +58,18 @@ pub enum TxAddError {
 
     #[error("Too many Ethereum signatures provided")]
     EthSignaturesLimitExceeded,
+
+    #[error("Transaction validity window has already passed")]
+    TransactionExpired,
+
+    #[error("Token {0} is blacklisted, only withdrawals to the owner's address are allowed")]
+    TokenBlacklisted(TokenId),
+
+    #[error("Server is shutting down, transactions are not accepted")]
+    ServerShuttingDown,
+
+    #[error("Transactions acceptance is paused by the operator, try again later")]
+    TxAcceptancePaused,
 }
 
 #[derive(Error, Debug, Copy, Clone, Serialize, Deserialize)]
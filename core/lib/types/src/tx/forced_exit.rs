@@ -1,4 +1,4 @@
-use num::{BigUint, Zero};
+use num::BigUint;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use thiserror::Error;
@@ -8,14 +8,17 @@ use zksync_crypto::{
     franklin_crypto::eddsa::PrivateKey,
     params::{max_account_id, max_fungible_token_id, max_processable_token, CURRENT_TX_VERSION},
 };
-use zksync_utils::{format_units, BigUintSerdeAsRadix10Str};
+use zksync_utils::BigUintSerdeAsRadix10Str;
 
-use super::{TxSignature, VerifiedSignatureCache};
+use super::{TxMessage, TxSignature, VerifiedSignatureCache};
 use crate::tx::error::{
     FEE_AMOUNT_IS_NOT_PACKABLE, WRONG_ACCOUNT_ID, WRONG_FEE_ERROR, WRONG_SIGNATURE,
     WRONG_TIME_RANGE, WRONG_TOKEN, WRONG_TOKEN_FOR_PAYING_FEE,
 };
-use crate::tx::version::TxVersion;
+use crate::tx::version::{
+    ensure_decoded, take_account_id, take_address, take_nonce, take_packed_fee, take_time_range,
+    take_token, TxDecodeError, TxVersion,
+};
 use crate::tx::TimeRange;
 use crate::{account::PubKeyHash, Engine};
 use crate::{
@@ -111,7 +114,7 @@ impl ForcedExit {
             time_range,
             None,
         );
-        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        tx.signature = TxSignature::sign(TxVersion::V1, private_key, &tx.get_bytes());
         tx.check_correctness()?;
         Ok(tx)
     }
@@ -147,6 +150,34 @@ impl ForcedExit {
         out
     }
 
+    /// Decodes the transaction from the bytes produced by `get_bytes_with_version` or
+    /// `get_old_bytes`, dispatching on the encoding version.
+    /// The signature is not encoded, so the decoded transaction has the default one.
+    pub fn from_signed_bytes(bytes: &[u8]) -> Result<(Self, TxVersion), TxDecodeError> {
+        let (version, mut bytes) = TxVersion::split_signed_bytes(Self::TX_TYPE, bytes)?;
+        let bytes = &mut bytes;
+
+        let initiator_account_id = take_account_id(bytes)?;
+        let target = take_address(bytes)?;
+        let token = take_token(bytes, version)?;
+        let fee = take_packed_fee(bytes)?;
+        let nonce = take_nonce(bytes)?;
+        let time_range = take_time_range(bytes)?;
+        ensure_decoded(bytes)?;
+
+        let tx = Self {
+            initiator_account_id,
+            target,
+            token,
+            fee,
+            nonce,
+            signature: Default::default(),
+            cached_signer: VerifiedSignatureCache::NotCached,
+            time_range: Some(time_range),
+        };
+        Ok((tx, version))
+    }
+
     /// Restores the `PubKeyHash` from the transaction signature.
     pub fn verify_signature(&self) -> Option<(PubKeyHash, TxVersion)> {
         if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
@@ -154,14 +185,13 @@ impl ForcedExit {
         } else {
             if let Some(res) = self
                 .signature
-                .verify_musig(&self.get_old_bytes())
-                .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+                .verify(TxVersion::Legacy, &self.get_old_bytes())
             {
                 return Some((res, TxVersion::Legacy));
             }
             self.signature
-                .verify_musig(&self.get_bytes())
-                .map(|pub_key| (PubKeyHash::from_pubkey(&pub_key), TxVersion::V1))
+                .verify(TxVersion::V1, &self.get_bytes())
+                .map(|pub_key_hash| (pub_key_hash, TxVersion::V1))
         }
     }
 
@@ -174,29 +204,22 @@ impl ForcedExit {
     ///
     /// Note that the second line is optional.
     pub fn get_ethereum_sign_message_part(&self, token_symbol: &str, decimals: u8) -> String {
-        let mut message = format!(
-            "ForcedExit {token} to: {to:?}",
-            token = token_symbol,
-            to = self.target
-        );
-        if !self.fee.is_zero() {
-            message.push_str(
-                format!(
-                    "\nFee: {fee} {token}",
-                    fee = format_units(&self.fee, decimals),
-                    token = token_symbol,
-                )
-                .as_str(),
-            );
-        }
-        message
+        TxMessage::new()
+            .line(format!(
+                "ForcedExit {token} to: {to:?}",
+                token = token_symbol,
+                to = self.target
+            ))
+            .fee(&self.fee, token_symbol, decimals)
+            .build()
     }
 
     /// Gets message that should be signed by Ethereum keys of the account for 2-Factor authentication.
     pub fn get_ethereum_sign_message(&self, token_symbol: &str, decimals: u8) -> String {
-        let mut message = self.get_ethereum_sign_message_part(token_symbol, decimals);
-        message.push_str(format!("\nNonce: {}", self.nonce).as_str());
-        message
+        TxMessage::new()
+            .line(self.get_ethereum_sign_message_part(token_symbol, decimals))
+            .nonce(self.nonce)
+            .build()
     }
 
     /// Helper method to remove cache and test transaction behavior without the signature cache.
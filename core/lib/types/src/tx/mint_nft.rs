@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
-use num::{BigUint, Zero};
+use num::BigUint;
 use thiserror::Error;
 
 use zksync_crypto::{
@@ -12,13 +12,17 @@ use zksync_crypto::{
     PrivateKey,
 };
 
-use zksync_utils::{format_units, BigUintSerdeAsRadix10Str};
+use zksync_utils::BigUintSerdeAsRadix10Str;
 
 use crate::tx::error::{
     FEE_AMOUNT_IS_NOT_PACKABLE, WRONG_ACCOUNT_ID, WRONG_FEE_ERROR, WRONG_SIGNATURE,
     WRONG_TOKEN_FOR_PAYING_FEE,
 };
-use crate::tx::version::TxVersion;
+use crate::tx::version::{
+    ensure_decoded, take_account_id, take_address, take_bytes, take_nonce, take_packed_fee,
+    take_token, TxDecodeError, TxVersion,
+};
+use crate::tx::TxMessage;
 use crate::{
     helpers::{is_fee_amount_packable, pack_fee_amount},
     tx::{TxSignature, VerifiedSignatureCache},
@@ -109,7 +113,7 @@ impl MintNFT {
             nonce,
             None,
         );
-        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        tx.signature = TxSignature::sign(TxVersion::V1, private_key, &tx.get_bytes());
         tx.check_correctness()?;
         Ok(tx)
     }
@@ -133,14 +137,46 @@ impl MintNFT {
         out
     }
 
+    /// Decodes the transaction from the bytes produced by `get_bytes_with_version`.
+    /// The signature is not encoded, so the decoded transaction has the default one.
+    pub fn from_signed_bytes(bytes: &[u8]) -> Result<(Self, TxVersion), TxDecodeError> {
+        let (version, mut bytes) = TxVersion::split_signed_bytes(Self::TX_TYPE, bytes)?;
+        if version == TxVersion::Legacy {
+            return Err(TxDecodeError::NoLegacyEncoding);
+        }
+        let bytes = &mut bytes;
+
+        let creator_id = take_account_id(bytes)?;
+        let creator_address = take_address(bytes)?;
+        let content_hash = H256::from_slice(take_bytes(bytes, 32)?);
+        let recipient = take_address(bytes)?;
+        let fee_token = take_token(bytes, version)?;
+        let fee = take_packed_fee(bytes)?;
+        let nonce = take_nonce(bytes)?;
+        ensure_decoded(bytes)?;
+
+        let tx = Self {
+            creator_id,
+            creator_address,
+            content_hash,
+            recipient,
+            fee,
+            fee_token,
+            nonce,
+            signature: Default::default(),
+            cached_signer: VerifiedSignatureCache::NotCached,
+        };
+        Ok((tx, version))
+    }
+
     /// Restores the `PubKeyHash` from the transaction signature.
     pub fn verify_signature(&self) -> Option<(PubKeyHash, TxVersion)> {
         if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
             *cached_signer
         } else {
             self.signature
-                .verify_musig(&self.get_bytes())
-                .map(|pub_key| (PubKeyHash::from_pubkey(&pub_key), TxVersion::V1))
+                .verify(TxVersion::V1, &self.get_bytes())
+                .map(|pub_key_hash| (pub_key_hash, TxVersion::V1))
         }
     }
 
@@ -148,33 +184,22 @@ impl MintNFT {
     /// The only difference is the missing `nonce` since it's added at the end of the transactions
     /// batch message.
     pub fn get_ethereum_sign_message_part(&self, token_symbol: &str, decimals: u8) -> String {
-        let mut message = format!(
-            "MintNFT {content:?} for: {recipient:?}",
-            content = self.content_hash,
-            recipient = self.recipient
-        );
-        if !self.fee.is_zero() {
-            message.push('\n');
-            message.push_str(
-                format!(
-                    "Fee: {fee} {token}",
-                    fee = format_units(self.fee.clone(), decimals),
-                    token = token_symbol
-                )
-                .as_str(),
-            );
-        }
-        message
+        TxMessage::new()
+            .line(format!(
+                "MintNFT {content:?} for: {recipient:?}",
+                content = self.content_hash,
+                recipient = self.recipient
+            ))
+            .fee(&self.fee, token_symbol, decimals)
+            .build()
     }
 
     /// Gets message that should be signed by Ethereum keys of the account for 2-Factor authentication.
     pub fn get_ethereum_sign_message(&self, token_symbol: &str, decimals: u8) -> String {
-        let mut message = self.get_ethereum_sign_message_part(token_symbol, decimals);
-        if !message.is_empty() {
-            message.push('\n');
-        }
-        message.push_str(format!("Nonce: {}", self.nonce).as_str());
-        message
+        TxMessage::new()
+            .line(self.get_ethereum_sign_message_part(token_symbol, decimals))
+            .nonce(self.nonce)
+            .build()
     }
 
     /// Helper method to remove cache and test transaction behavior without the signature cache.
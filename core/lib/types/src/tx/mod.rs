@@ -1,5 +1,6 @@
 //! zkSync network L2 transactions.
 
+mod canonical;
 mod change_pubkey;
 mod close;
 mod forced_exit;
@@ -20,6 +21,7 @@ mod tests;
 #[doc(hidden)]
 pub use self::close::Close;
 pub use self::{
+    canonical::{canonical_json_hash, to_canonical_json},
     change_pubkey::{
         ChangePubKey, ChangePubKeyCREATE2Data, ChangePubKeyECDSAData, ChangePubKeyEIP712Data,
         ChangePubKeyEthAuthData, ChangePubKeyType,
@@ -29,7 +31,7 @@ pub use self::{
     mint_nft::{calculate_token_address, calculate_token_data, calculate_token_hash, MintNFT},
     swap::{Order, Swap},
     transfer::Transfer,
-    version::TxVersion,
+    version::{TxDecodeError, TxVersion},
     withdraw::Withdraw,
     withdraw_nft::WithdrawNFT,
     zksync_tx::{EthSignData, SignedZkSyncTx, ZkSyncTx},
@@ -39,6 +41,7 @@ pub use self::{
 pub use self::primitives::{
     eip1271_signature::EIP1271Signature,
     eip712_signature,
+    erc20_permit::Erc20Permit,
     eth_batch_sign_data::EthBatchSignData,
     eth_batch_signature::EthBatchSignatures,
     eth_signature::{TxEthSignature, TxEthSignatureVariant},
@@ -46,8 +49,10 @@ pub use self::primitives::{
     packed_public_key::PackedPublicKey,
     packed_signature::PackedSignature,
     signature::TxSignature,
+    signature_scheme::{MusigRescue, SignatureScheme},
     time_range::TimeRange,
     tx_hash::TxHash,
+    tx_message::TxMessage,
 };
 
 pub(crate) use self::primitives::signature_cache::VerifiedSignatureCache;
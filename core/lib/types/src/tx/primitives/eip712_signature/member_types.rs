@@ -1,3 +1,4 @@
+use num::BigUint;
 use parity_crypto::Keccak256;
 
 use zksync_basic_types::{Address, H256, U256};
@@ -83,6 +84,27 @@ impl StructMember for U256 {
     }
 }
 
+/// Token amounts are encoded as `uint256`, the values which don't fit are saturated
+/// (such amounts are rejected by the transactions correctness checks anyway).
+impl StructMember for BigUint {
+    const MEMBER_TYPE: &'static str = "uint256";
+    const IS_REFERENCE_TYPE: bool = false;
+
+    fn get_inner_members(&self) -> Vec<EncodedStructureMember> {
+        Vec::new()
+    }
+
+    fn encode_member_data(&self) -> H256 {
+        let bytes = self.to_bytes_be();
+        let value = if bytes.len() > 32 {
+            U256::max_value()
+        } else {
+            U256::from_big_endian(&bytes)
+        };
+        value.encode_member_data()
+    }
+}
+
 impl StructMember for H256 {
     const MEMBER_TYPE: &'static str = "uint256";
     const IS_REFERENCE_TYPE: bool = false;
@@ -3,11 +3,11 @@ use crate::eip712_signature::{
     typed_structure::{EIP712TypedStructure, Eip712Domain},
     utils::get_eip712_json,
 };
-use crate::tx::PackedEthSignature;
+use crate::tx::{Erc20Permit, PackedEthSignature};
 use parity_crypto::Keccak256;
 use serde::Serialize;
 use std::str::FromStr;
-use zksync_basic_types::{Address, H256, U256};
+use zksync_basic_types::{Address, ChainId, H256, U256};
 
 #[derive(Clone, Serialize)]
 struct Person {
@@ -48,6 +48,7 @@ fn test_encode_eip712_typed_struct() {
         name: "Ether Mail".to_owned(),
         version: "1".to_owned(),
         chain_id: U256::from(1u8),
+        verifying_contract: None,
     };
 
     let message = Mail {
@@ -129,6 +130,7 @@ fn test_get_eip712_json() {
         name: "Ether Mail".to_owned(),
         version: "1".to_owned(),
         chain_id: U256::from(1u8),
+        verifying_contract: None,
     };
 
     let message = Mail {
@@ -214,3 +216,45 @@ fn test_get_eip712_json() {
         serde_json::from_str::<serde_json::Value>(expected_value).unwrap()
     );
 }
+
+/// Checks that the ERC-20 permit and its domain are encoded as defined by EIP-2612.
+#[test]
+fn test_encode_erc20_permit() {
+    let token = Address::from_str("6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+    let domain = Eip712Domain::with_verifying_contract(
+        "Dai Stablecoin".to_owned(),
+        "1".to_owned(),
+        ChainId(1),
+        token,
+    );
+    assert_eq!(
+        domain.encode_type(),
+        "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+    );
+
+    let permit = Erc20Permit {
+        owner: Address::from_str("CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826").unwrap(),
+        spender: Address::from_str("aBEA9132b05A70803a4E85094fD0e1800777fBEF").unwrap(),
+        value: U256::from(1000u32),
+        nonce: U256::zero(),
+        deadline: U256::max_value(),
+    };
+    assert_eq!(
+        permit.encode_type(),
+        "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)"
+    );
+    assert_eq!(
+        get_eip712_json(&domain, &permit)["domain"]["verifyingContract"],
+        serde_json::to_value(token).unwrap()
+    );
+
+    let private_key = b"cow".keccak256().into();
+    let signature = PackedEthSignature::sign_typed_data(&private_key, &domain, &permit).unwrap();
+    let signed_bytes = PackedEthSignature::typed_data_to_signed_bytes(&domain, &permit);
+    assert_eq!(
+        PackedEthSignature::address_from_private_key(&private_key).unwrap(),
+        signature
+            .signature_recover_signer_from_hash(signed_bytes)
+            .unwrap()
+    );
+}
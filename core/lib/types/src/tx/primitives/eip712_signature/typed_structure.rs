@@ -1,7 +1,7 @@
 use parity_crypto::Keccak256;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use zksync_basic_types::ChainId;
+use zksync_basic_types::{Address, ChainId};
 
 use crate::eip712_signature::{EncodeBuilder, StructBuilder, TypeBuilder};
 use crate::{H256, U256};
@@ -157,6 +157,9 @@ pub struct Eip712Domain {
     pub version: String,
     /// The [EIP-155](https://eips.ethereum.org/EIPS/eip-155) chain id.
     pub chain_id: U256,
+    /// The address of the contract that will verify the signature, not used by the zkSync domain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verifying_contract: Option<Address>,
 }
 
 impl Eip712Domain {
@@ -169,6 +172,23 @@ impl Eip712Domain {
             name: Self::NAME.to_string(),
             version: Self::VERSION.to_string(),
             chain_id: U256::from(chain_id.0),
+            verifying_contract: None,
+        }
+    }
+
+    /// Creates the domain of the signatures verified by the given contract, e.g. the
+    /// [EIP-2612](https://eips.ethereum.org/EIPS/eip-2612) permits of the ERC-20 token.
+    pub fn with_verifying_contract(
+        name: String,
+        version: String,
+        chain_id: ChainId,
+        verifying_contract: Address,
+    ) -> Self {
+        Self {
+            name,
+            version,
+            chain_id: U256::from(chain_id.0),
+            verifying_contract: Some(verifying_contract),
         }
     }
 }
@@ -180,5 +200,8 @@ impl EIP712TypedStructure for Eip712Domain {
         builder.add_member("name", &self.name);
         builder.add_member("version", &self.version);
         builder.add_member("chainId", &self.chain_id);
+        if let Some(verifying_contract) = &self.verifying_contract {
+            builder.add_member("verifyingContract", verifying_contract);
+        }
     }
 }
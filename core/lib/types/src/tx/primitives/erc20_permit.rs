@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{Address, U256};
+
+use crate::eip712_signature::{EIP712TypedStructure, StructBuilder};
+
+/// Approval of the ERC-20 token spending signed by the token holder according to
+/// [EIP-2612](https://eips.ethereum.org/EIPS/eip-2612).
+/// Allows to deposit the token with a single Ethereum transaction, without the separate `approve` call.
+///
+/// The permit is signed as the EIP-712 typed data within the domain of the token contract,
+/// see `Eip712Domain::with_verifying_contract`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Erc20Permit {
+    /// Holder of the tokens.
+    pub owner: Address,
+    /// Address allowed to spend the tokens, i.e. the zkSync contract.
+    pub spender: Address,
+    /// Amount of tokens allowed to be spent.
+    pub value: U256,
+    /// Permit nonce of the owner, as returned by the `nonces` method of the token.
+    pub nonce: U256,
+    /// UNIX timestamp (in seconds) after which the permit can't be used.
+    pub deadline: U256,
+}
+
+impl EIP712TypedStructure for Erc20Permit {
+    const TYPE_NAME: &'static str = "Permit";
+
+    fn build_structure<BUILDER: StructBuilder>(&self, builder: &mut BUILDER) {
+        builder.add_member("owner", &self.owner);
+        builder.add_member("spender", &self.spender);
+        builder.add_member("value", &self.value);
+        builder.add_member("nonce", &self.nonce);
+        builder.add_member("deadline", &self.deadline);
+    }
+}
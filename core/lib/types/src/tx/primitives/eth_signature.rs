@@ -6,12 +6,14 @@ use crate::tx::{EIP1271Signature, PackedEthSignature};
 /// Representation of the signature secured by L1.
 /// May be either a signature generated via Ethereum private key
 /// corresponding to the account address,
-/// or on-chain signature via EIP-1271.
+/// on-chain signature via EIP-1271,
+/// or a signature of the EIP-712 typed data generated via Ethereum private key.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", content = "signature")]
 pub enum TxEthSignature {
     EthereumSignature(PackedEthSignature),
     EIP1271Signature(EIP1271Signature),
+    EIP712Signature(PackedEthSignature),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -61,7 +63,7 @@ impl TxEthSignatureVariant {
 impl Display for TxEthSignature {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::EthereumSignature(sign) => {
+            Self::EthereumSignature(sign) | Self::EIP712Signature(sign) => {
                 write!(f, "0x{}", hex::encode(sign.serialize_packed()))
             }
             Self::EIP1271Signature(sign) => write!(f, "0x{}", hex::encode(sign.0.clone())),
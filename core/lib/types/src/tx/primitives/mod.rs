@@ -1,5 +1,6 @@
 pub mod eip1271_signature;
 pub mod eip712_signature;
+pub mod erc20_permit;
 pub mod eth_batch_sign_data;
 pub mod eth_batch_signature;
 pub mod eth_signature;
@@ -8,8 +9,10 @@ pub mod packed_public_key;
 pub mod packed_signature;
 pub mod signature;
 pub mod signature_cache;
+pub mod signature_scheme;
 pub mod time_range;
 pub mod tx_hash;
+pub mod tx_message;
 
 #[cfg(test)]
 mod tests;
@@ -100,6 +100,16 @@ impl PackedEthSignature {
         Ok(public_to_address(&public_key))
     }
 
+    /// Checks the EIP-712 signature and returns ethereum address of the signer.
+    /// message should be the typed data message (`"\x19\x01" ‖ domainSeparator ‖ hashStruct`)
+    /// as returned by `typed_data_to_signed_message`, it is hashed without any prefixes.
+    pub fn signature_recover_signer_from_typed_data_message(
+        &self,
+        msg: &[u8],
+    ) -> Result<Address, PackedETHSignatureError> {
+        self.signature_recover_signer_from_hash(msg.keccak256().into())
+    }
+
     /// Get Ethereum address from private key.
     pub fn address_from_private_key(
         private_key: &H256,
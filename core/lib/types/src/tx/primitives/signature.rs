@@ -6,16 +6,19 @@ use thiserror::Error;
 use zksync_crypto::{
     franklin_crypto::{
         eddsa::{PrivateKey, PublicKey, Seed},
-        jubjub::FixedGenerators,
+        jubjub::{edwards::Point, FixedGenerators, Unknown},
         rescue::RescueEngine,
     },
+    musig::{aggregate_signatures, AggregatedPublicKey},
     params::{JUBJUB_PARAMS, RESCUE_PARAMS},
     primitives::rescue_hash_tx_msg,
+    Fs,
 };
 
+use crate::account::PubKeyHash;
 use crate::tx::{
     primitives::{packed_public_key, packed_signature},
-    PackedPublicKey, PackedSignature,
+    PackedPublicKey, PackedSignature, TxVersion,
 };
 
 /// zkSync transaction signature.
@@ -29,6 +32,19 @@ pub struct TxSignature {
 }
 
 impl TxSignature {
+    /// Signs the transaction bytes encoded in the given version via provided private key,
+    /// using the signature scheme of the version.
+    pub fn sign(version: TxVersion, pk: &PrivateKey<Engine>, msg: &[u8]) -> Self {
+        version.signature_scheme().sign(pk, msg)
+    }
+
+    /// Verifies the signature of the transaction bytes encoded in the given version,
+    /// using the signature scheme of the version.
+    /// Returns `None` if the signature is incorrect.
+    pub fn verify(&self, version: TxVersion, msg: &[u8]) -> Option<PubKeyHash> {
+        version.signature_scheme().verify(self, msg)
+    }
+
     /// Signs the message via provided private key.
     ///
     /// Currently an alias for `TxSignature::sign_musig_rescue`.
@@ -57,6 +73,25 @@ impl TxSignature {
         }
     }
 
+    /// Message to be signed by every participant of the MuSig session
+    /// (see `zksync_crypto::musig`) in order to sign the transaction bytes.
+    pub fn musig_session_message(msg: &[u8]) -> Vec<u8> {
+        rescue_hash_tx_msg(msg)
+    }
+
+    /// Combines the partial signatures of the MuSig session participants.
+    /// The resulting signature is verified against their aggregated public key.
+    pub fn from_musig_partial_signatures(
+        aggregated_key: &AggregatedPublicKey,
+        aggregated_nonce: &Point<Engine, Unknown>,
+        partial_signatures: &[Fs],
+    ) -> Self {
+        Self {
+            pub_key: PackedPublicKey(aggregated_key.public_key().clone()),
+            signature: PackedSignature(aggregate_signatures(aggregated_nonce, partial_signatures)),
+        }
+    }
+
     /// Restores a public key from the signature given the initial message.
     /// Returns `None` if an address cannot be recovered from the provided (signature, message) pair.
     ///
@@ -0,0 +1,36 @@
+//! Schemes of the zkSync transactions signatures.
+//!
+//! The transactions are signed by the MuSig Rescue signatures, but the scheme is chosen
+//! by the transaction version (see `TxVersion::signature_scheme`), so a new scheme can be
+//! introduced together with a new transaction version while the transactions signed
+//! in the previous versions are still accepted.
+
+use zksync_crypto::franklin_crypto::eddsa::PrivateKey;
+
+use crate::{account::PubKeyHash, tx::TxSignature, Engine};
+
+/// Signature scheme of the L2 transactions.
+pub trait SignatureScheme: Send + Sync {
+    /// Signs the transaction bytes via provided private key.
+    fn sign(&self, private_key: &PrivateKey<Engine>, msg: &[u8]) -> TxSignature;
+
+    /// Verifies the signature of the transaction bytes.
+    /// Returns the `PubKeyHash` of the signer or `None` if the signature is incorrect.
+    fn verify(&self, signature: &TxSignature, msg: &[u8]) -> Option<PubKeyHash>;
+}
+
+/// MuSig Rescue signature, used by all the transaction versions so far.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MusigRescue;
+
+impl SignatureScheme for MusigRescue {
+    fn sign(&self, private_key: &PrivateKey<Engine>, msg: &[u8]) -> TxSignature {
+        TxSignature::sign_musig_rescue(private_key, msg)
+    }
+
+    fn verify(&self, signature: &TxSignature, msg: &[u8]) -> Option<PubKeyHash> {
+        signature
+            .verify_musig_rescue(msg)
+            .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+    }
+}
@@ -131,3 +131,23 @@ fn test_batch_message() {
     let message = EthBatchSignData::get_batch_sign_message(txs);
     assert_eq!(message, expected.into_bytes());
 }
+
+#[test]
+fn tx_message_skips_zero_amounts() {
+    let to = Address::from_str("2e46cd9538248826ede540012c0e8d13f223d587").unwrap();
+    let message = TxMessage::new()
+        .transfer("Transfer", &1_500u32.into(), "USDC", 3, &to)
+        .fee(&0u32.into(), "USDC", 3)
+        .nonce(Nonce(7))
+        .build();
+    assert_eq!(
+        message,
+        "Transfer 1.5 USDC to: 0x2e46cd9538248826ede540012c0e8d13f223d587\nNonce: 7"
+    );
+
+    let message = TxMessage::new()
+        .transfer("Withdraw", &0u32.into(), "ETH", 18, &to)
+        .labeled_fee("Swap fee", &1u32.into(), "ETH", 0)
+        .build();
+    assert_eq!(message, "Swap fee: 1.0 ETH");
+}
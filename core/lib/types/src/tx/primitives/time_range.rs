@@ -36,6 +36,12 @@ impl TimeRange {
         self.valid_from <= block_timestamp && block_timestamp <= self.valid_until
     }
 
+    /// Checks whether the time range ended before the given timestamp, so the transaction
+    /// can't be executed anymore.
+    pub fn is_expired(&self, timestamp: u64) -> bool {
+        timestamp > self.valid_until
+    }
+
     pub fn intersects(&self, other: Self) -> bool {
         self.valid_from <= other.valid_until && other.valid_from <= self.valid_until
     }
@@ -0,0 +1,84 @@
+use num::{BigUint, Zero};
+use zksync_basic_types::{Address, Nonce};
+use zksync_utils::format_units;
+
+/// Builder of the human-readable message signed by the Ethereum key of the account
+/// for 2-Factor authentication of the transaction.
+///
+/// The message consists of the lines separated by `\n`, e.g. for the `Transfer`:
+///
+/// ```text
+/// Transfer {amount} {token} to: {to}
+/// Fee: {fee} {token}
+/// Nonce: {nonce}
+/// ```
+///
+/// Lines with zero amounts are omitted. Messages of all the transactions are built with it,
+/// both when the server verifies the signatures and when the SDK signs the transactions.
+#[derive(Debug, Clone, Default)]
+pub struct TxMessage {
+    lines: Vec<String>,
+}
+
+impl TxMessage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends the line, unless it's empty.
+    pub fn line(mut self, line: impl Into<String>) -> Self {
+        let line = line.into();
+        if !line.is_empty() {
+            self.lines.push(line);
+        }
+        self
+    }
+
+    /// Appends the `{transaction} {amount} {token} to: {to}` line, unless the amount is zero.
+    pub fn transfer(
+        self,
+        transaction: &str,
+        amount: &BigUint,
+        token_symbol: &str,
+        decimals: u8,
+        to: &Address,
+    ) -> Self {
+        if amount.is_zero() {
+            return self;
+        }
+        self.line(format!(
+            "{transaction} {amount} {token} to: {to:?}",
+            transaction = transaction,
+            amount = format_units(amount, decimals),
+            token = token_symbol,
+            to = to
+        ))
+    }
+
+    /// Appends the `Fee: {fee} {token}` line, unless the fee is zero.
+    pub fn fee(self, fee: &BigUint, token_symbol: &str, decimals: u8) -> Self {
+        self.labeled_fee("Fee", fee, token_symbol, decimals)
+    }
+
+    /// Appends the `{label}: {fee} {token}` line, unless the fee is zero.
+    pub fn labeled_fee(self, label: &str, fee: &BigUint, token_symbol: &str, decimals: u8) -> Self {
+        if fee.is_zero() {
+            return self;
+        }
+        self.line(format!(
+            "{label}: {fee} {token}",
+            label = label,
+            fee = format_units(fee, decimals),
+            token = token_symbol
+        ))
+    }
+
+    /// Appends the `Nonce: {nonce}` line.
+    pub fn nonce(self, nonce: Nonce) -> Self {
+        self.line(format!("Nonce: {}", nonce))
+    }
+
+    pub fn build(self) -> String {
+        self.lines.join("\n")
+    }
+}
@@ -13,7 +13,7 @@ use zksync_crypto::{
 };
 use zksync_utils::{format_units, BigUintPairSerdeAsRadix10Str, BigUintSerdeAsRadix10Str};
 
-use super::{TxSignature, VerifiedSignatureCache};
+use super::{TxMessage, TxSignature, VerifiedSignatureCache};
 use crate::account::PubKeyHash;
 use crate::tx::error::{
     AMOUNT_IS_NOT_PACKABLE, FEE_AMOUNT_IS_NOT_PACKABLE, WRONG_ACCOUNT_ID, WRONG_AMOUNT_ERROR,
@@ -91,9 +91,7 @@ impl Order {
     }
 
     pub fn verify_signature(&self) -> Option<PubKeyHash> {
-        self.signature
-            .verify_musig(&self.get_bytes())
-            .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+        self.signature.verify(TxVersion::V1, &self.get_bytes())
     }
 
     pub fn get_ethereum_sign_message(
@@ -102,27 +100,22 @@ impl Order {
         token_buy: &str,
         decimals: u8,
     ) -> String {
-        let mut message = if self.amount.is_zero() {
-            format!("Limit order for {} -> {}\n", token_sell, token_buy)
+        let order = if self.amount.is_zero() {
+            format!("Limit order for {} -> {}", token_sell, token_buy)
         } else {
             format!(
-                "Order for {} {} -> {}\n",
+                "Order for {} {} -> {}",
                 format_units(&self.amount, decimals),
                 token_sell,
                 token_buy
             )
         };
-        message += format!(
-            "Ratio: {sell}:{buy}\n\
-            Address: {recipient:?}\n\
-            Nonce: {nonce}",
-            sell = self.price.0,
-            buy = self.price.1,
-            recipient = self.recipient_address,
-            nonce = self.nonce
-        )
-        .as_str();
-        message
+        TxMessage::new()
+            .line(order)
+            .line(format!("Ratio: {}:{}", self.price.0, self.price.1))
+            .line(format!("Address: {:?}", self.recipient_address))
+            .nonce(self.nonce)
+            .build()
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -148,7 +141,7 @@ impl Order {
             time_range,
             signature: Default::default(),
         };
-        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        tx.signature = TxSignature::sign(TxVersion::V1, private_key, &tx.get_bytes());
         tx.check_correctness()?;
         Ok(tx)
     }
@@ -255,7 +248,7 @@ impl Swap {
             fee_token,
             None,
         );
-        tx.signature = TxSignature::sign_musig(private_key, &tx.get_sign_bytes());
+        tx.signature = TxSignature::sign(TxVersion::V1, private_key, &tx.get_sign_bytes());
         tx.check_correctness()?;
         Ok(tx)
     }
@@ -350,8 +343,8 @@ impl Swap {
             *cached_signer
         } else {
             self.signature
-                .verify_musig(&self.get_sign_bytes())
-                .map(|pub_key| (PubKeyHash::from_pubkey(&pub_key), TxVersion::V1))
+                .verify(TxVersion::V1, &self.get_sign_bytes())
+                .map(|pub_key_hash| (pub_key_hash, TxVersion::V1))
         }
     }
 
@@ -359,25 +352,17 @@ impl Swap {
     /// The only difference is the missing `nonce` since it's added at the end of the transactions
     /// batch message.
     pub fn get_ethereum_sign_message_part(&self, token_symbol: &str, decimals: u8) -> String {
-        if !self.fee.is_zero() {
-            format!(
-                "Swap fee: {fee} {token}",
-                fee = format_units(&self.fee, decimals),
-                token = token_symbol
-            )
-        } else {
-            String::new()
-        }
+        TxMessage::new()
+            .labeled_fee("Swap fee", &self.fee, token_symbol, decimals)
+            .build()
     }
 
     /// Gets message that should be signed by Ethereum keys of the account for 2-Factor authentication.
     pub fn get_ethereum_sign_message(&self, token_symbol: &str, decimals: u8) -> String {
-        let mut message = self.get_ethereum_sign_message_part(token_symbol, decimals);
-        if !message.is_empty() {
-            message.push('\n');
-        }
-        message.push_str(format!("Nonce: {}", self.nonce).as_str());
-        message
+        TxMessage::new()
+            .line(self.get_ethereum_sign_message_part(token_symbol, decimals))
+            .nonce(self.nonce)
+            .build()
     }
 
     /// Helper method to remove cache and test transaction behavior without the signature cache.
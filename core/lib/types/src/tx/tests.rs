@@ -1,11 +1,14 @@
+use std::collections::HashSet;
+
 use num::{BigUint, ToPrimitive};
 use serde::{Deserialize, Serialize};
-use zksync_basic_types::Address;
+use zksync_basic_types::{Address, ChainId};
 use zksync_crypto::{
     franklin_crypto::{
         eddsa::{PrivateKey, PublicKey},
         jubjub::FixedGenerators,
     },
+    musig::{AggregatedPublicKey, MusigSession},
     pairing::bn256::Bn256,
     params::{max_account_id, max_fungible_token_id, CURRENT_TX_VERSION, JUBJUB_PARAMS},
     primitives::rescue_hash_orders,
@@ -452,6 +455,58 @@ fn test_musig_rescue_signing_verification() {
     }
 }
 
+/// Checks that the transfer signed by two parties is verified against their aggregated key.
+#[test]
+fn musig_two_of_two_transfer_signature() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let private_keys: Vec<PrivateKey<Engine>> = (0..2).map(|_| PrivateKey(rng.gen())).collect();
+    let aggregated_key =
+        AggregatedPublicKey::new(private_keys.iter().map(public_key_from_private).collect())
+            .unwrap();
+
+    let mut transfer = Transfer::new(
+        AccountId(1),
+        Address::random(),
+        Address::random(),
+        TokenId(0),
+        BigUint::from(100u32),
+        BigUint::from(1u32),
+        Nonce(0),
+        Default::default(),
+        None,
+    );
+    let msg = TxSignature::musig_session_message(&transfer.get_bytes());
+
+    let mut sessions: Vec<_> = private_keys
+        .iter()
+        .map(|key| MusigSession::new(&mut rng, key, aggregated_key.clone()).unwrap())
+        .collect();
+    let commitments: Vec<_> = sessions.iter().map(|s| s.nonce_commitment()).collect();
+    let nonces: Vec<_> = sessions.iter().map(|s| s.nonce().clone()).collect();
+    for session in &mut sessions {
+        session.set_nonce_commitments(commitments.clone()).unwrap();
+        session.set_nonces(nonces.clone()).unwrap();
+    }
+    let aggregated_nonce = sessions[0].aggregated_nonce().unwrap().clone();
+    let partial_signatures: Vec<_> = sessions
+        .into_iter()
+        .map(|session| session.sign(&msg).unwrap())
+        .collect();
+
+    transfer.signature = TxSignature::from_musig_partial_signatures(
+        &aggregated_key,
+        &aggregated_nonce,
+        &partial_signatures,
+    );
+    assert_eq!(
+        transfer.verify_signature(),
+        Some((
+            PubKeyHash::from_pubkey(aggregated_key.public_key()),
+            TxVersion::V1
+        ))
+    );
+}
+
 #[test]
 fn test_ethereum_signature_verify_with_serialization() {
     let address: Address = "52312AD6f01657413b2eaE9287f6B9ADaD93D5FE".parse().unwrap();
@@ -576,6 +631,82 @@ fn eth_sign_data_compatibility() {
     assert_eq!(deserialized.message, eth_sign_data.message);
 }
 
+/// Checks that the transfer signed as the EIP-712 typed data is accepted only within its domain.
+#[test]
+fn eip712_transfer_signature() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let private_key: H256 = "0b43c0f5b5a13a7047408d1f8c8ad32ba5879902ea6212184e0a5d1157281d76"
+        .parse()
+        .unwrap();
+    let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+    let transfer = Transfer::new(
+        gen_account_id(&mut rng),
+        address,
+        Address::from(rng.gen::<[u8; 20]>()),
+        gen_token_id(&mut rng),
+        BigUint::from(12_340_000_000_000u64),
+        BigUint::from(56_700_000_000u64),
+        Nonce(rng.gen()),
+        Default::default(),
+        None,
+    );
+    let tx = ZkSyncTx::from(transfer.clone());
+
+    let packed_signature = PackedEthSignature::sign_typed_data(
+        &private_key,
+        &eip712_signature::Eip712Domain::new(ChainId(9)),
+        &transfer,
+    )
+    .unwrap();
+    let message = tx.get_eip712_sign_message(ChainId(9)).unwrap();
+    assert_eq!(
+        packed_signature
+            .signature_recover_signer_from_typed_data_message(&message)
+            .unwrap(),
+        address
+    );
+    // The signature can't be replayed on another network.
+    let other_message = tx.get_eip712_sign_message(ChainId(1)).unwrap();
+    assert_ne!(
+        packed_signature
+            .signature_recover_signer_from_typed_data_message(&other_message)
+            .unwrap(),
+        address
+    );
+
+    let signature = TxEthSignature::EIP712Signature(packed_signature);
+    let value = serde_json::to_value(&signature).unwrap();
+    assert_eq!(value["type"], "EIP712Signature");
+    let deserialized: TxEthSignature = serde_json::from_value(value).unwrap();
+    assert_eq!(deserialized, signature);
+}
+
+/// Checks that the signature of the chain-bound message can't be used on another network.
+#[test]
+fn chain_bound_ethereum_message_signature() {
+    let private_key: H256 = "0b43c0f5b5a13a7047408d1f8c8ad32ba5879902ea6212184e0a5d1157281d76"
+        .parse()
+        .unwrap();
+    let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+    let message = b"Transfer 1.0 ETH\nNonce: 1";
+
+    let bound_message = crate::chain_bound_ethereum_message(message, ChainId(4));
+    assert_eq!(bound_message, b"Transfer 1.0 ETH\nNonce: 1\nChain ID: 4");
+
+    let signature = PackedEthSignature::sign(&private_key, &bound_message).unwrap();
+    let recover = |message: &[u8]| {
+        signature
+            .signature_recover_signer_from_raw_message(message)
+            .unwrap()
+    };
+    assert_eq!(recover(&bound_message), address);
+    assert_ne!(
+        recover(&crate::chain_bound_ethereum_message(message, ChainId(1))),
+        address
+    );
+    assert_ne!(recover(message), address);
+}
+
 #[test]
 fn test_check_signature() {
     let (pk, msg) = gen_pk_and_msg();
@@ -586,3 +717,259 @@ fn test_check_signature() {
 
     assert_eq!(hex::encode(signature), "4e3298ac8cc13868dbbc94ad6fb41085ffe05b3c2eee22f88b05e69b7a5126aea723d7a3e7282ef5a32d9479c9c8dde52b3e3c462dd445dcd8158ebb6edb6000");
 }
+
+/// Checks that the transfer is decoded from the bytes of both the legacy and the current version.
+#[test]
+fn transfer_from_signed_bytes() {
+    let transfer = Transfer::new(
+        AccountId(3),
+        Address::random(),
+        Address::random(),
+        TokenId(2),
+        BigUint::from(12_340_000u32),
+        BigUint::from(1_000u32),
+        Nonce(7),
+        TimeRange::new(10, 20),
+        None,
+    );
+
+    let bytes = transfer.get_bytes();
+    let (decoded, version) = Transfer::from_signed_bytes(&bytes).unwrap();
+    assert_eq!(version, TxVersion::V1);
+    assert_eq!(decoded.get_bytes(), bytes);
+
+    let old_bytes = transfer.get_old_bytes();
+    let (decoded, version) = Transfer::from_signed_bytes(&old_bytes).unwrap();
+    assert_eq!(version, TxVersion::Legacy);
+    assert_eq!(decoded.get_old_bytes(), old_bytes);
+    assert_eq!(decoded.token, transfer.token);
+
+    let mut unsupported = bytes.clone();
+    unsupported[1] = CURRENT_TX_VERSION + 1;
+    assert_eq!(
+        Transfer::from_signed_bytes(&unsupported).unwrap_err(),
+        TxDecodeError::UnsupportedVersion(CURRENT_TX_VERSION + 1)
+    );
+    assert_eq!(
+        Transfer::from_signed_bytes(&bytes[..bytes.len() - 1]).unwrap_err(),
+        TxDecodeError::UnexpectedEnd
+    );
+    assert_eq!(
+        Transfer::from_signed_bytes(&[Withdraw::TX_TYPE]).unwrap_err(),
+        TxDecodeError::UnexpectedTxType(Withdraw::TX_TYPE)
+    );
+}
+
+/// Checks that the other transaction types are decoded from the bytes of every encoding they have.
+#[test]
+fn other_txs_from_signed_bytes() {
+    let withdraw = Withdraw::new(
+        AccountId(3),
+        Address::random(),
+        Address::random(),
+        TokenId(2),
+        BigUint::from(12_345_678u32),
+        BigUint::from(1_000u32),
+        Nonce(7),
+        TimeRange::new(10, 20),
+        None,
+    );
+    for bytes in vec![withdraw.get_bytes(), withdraw.get_old_bytes()] {
+        let (decoded, _) = Withdraw::from_signed_bytes(&bytes).unwrap();
+        assert_eq!(decoded.amount, withdraw.amount);
+        assert_eq!(decoded.time_range, withdraw.time_range);
+    }
+
+    let forced_exit = ForcedExit::new(
+        AccountId(3),
+        Address::random(),
+        TokenId(2),
+        BigUint::from(1_000u32),
+        Nonce(7),
+        TimeRange::new(10, 20),
+        None,
+    );
+    let bytes = forced_exit.get_bytes();
+    let (decoded, version) = ForcedExit::from_signed_bytes(&bytes).unwrap();
+    assert_eq!(version, TxVersion::V1);
+    assert_eq!(decoded.get_bytes(), bytes);
+    let old_bytes = forced_exit.get_old_bytes();
+    let (decoded, version) = ForcedExit::from_signed_bytes(&old_bytes).unwrap();
+    assert_eq!(version, TxVersion::Legacy);
+    assert_eq!(decoded.get_old_bytes(), old_bytes);
+
+    let change_pubkey = ChangePubKey::new(
+        AccountId(3),
+        Address::random(),
+        PubKeyHash::from_bytes(&[5; 20]).unwrap(),
+        TokenId(2),
+        BigUint::from(1_000u32),
+        Nonce(7),
+        TimeRange::new(10, 20),
+        None,
+        None,
+        None,
+    );
+    let bytes = change_pubkey.get_bytes();
+    let (decoded, version) = ChangePubKey::from_signed_bytes(&bytes).unwrap();
+    assert_eq!(version, TxVersion::V1);
+    assert_eq!(decoded.get_bytes(), bytes);
+    let old_bytes = change_pubkey.get_old_bytes();
+    let (decoded, version) = ChangePubKey::from_signed_bytes(&old_bytes).unwrap();
+    assert_eq!(version, TxVersion::Legacy);
+    assert_eq!(decoded.get_old_bytes(), old_bytes);
+
+    let mint_nft = MintNFT::new(
+        AccountId(3),
+        Address::random(),
+        H256::random(),
+        Address::random(),
+        BigUint::from(1_000u32),
+        TokenId(2),
+        Nonce(7),
+        None,
+    );
+    let bytes = mint_nft.get_bytes();
+    let (decoded, version) = MintNFT::from_signed_bytes(&bytes).unwrap();
+    assert_eq!(version, TxVersion::V1);
+    assert_eq!(decoded.get_bytes(), bytes);
+
+    let withdraw_nft = WithdrawNFT::new(
+        AccountId(3),
+        Address::random(),
+        Address::random(),
+        TokenId(70_000),
+        TokenId(2),
+        BigUint::from(1_000u32),
+        Nonce(7),
+        TimeRange::new(10, 20),
+        None,
+    );
+    let bytes = withdraw_nft.get_bytes();
+    let (decoded, version) = WithdrawNFT::from_signed_bytes(&bytes).unwrap();
+    assert_eq!(version, TxVersion::V1);
+    assert_eq!(decoded.get_bytes(), bytes);
+
+    // The NFT transactions have no legacy encoding.
+    let mut legacy = bytes;
+    legacy[0] = WithdrawNFT::TX_TYPE;
+    assert_eq!(
+        WithdrawNFT::from_signed_bytes(&legacy).unwrap_err(),
+        TxDecodeError::NoLegacyEncoding
+    );
+}
+
+#[test]
+fn test_time_range_expiration() {
+    let time_range = TimeRange::new(10, 20);
+    assert!(!time_range.is_expired(5));
+    assert!(!time_range.is_expired(20));
+    assert!(time_range.is_expired(21));
+    assert!(!TimeRange::default().is_expired(u64::MAX));
+}
+
+#[test]
+fn test_transfer_canonical_json() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let key = gen_pk_and_msg().0;
+    let transfer = Transfer::new_signed(
+        gen_account_id(&mut rng),
+        Address::from(rng.gen::<[u8; 20]>()),
+        Address::from(rng.gen::<[u8; 20]>()),
+        gen_token_id(&mut rng),
+        BigUint::from(12_340_000_000_000u64),
+        BigUint::from(56_700_000_000u64),
+        Nonce(rng.gen()),
+        Default::default(),
+        &key,
+    )
+    .expect("failed to sign transfer");
+    let tx = ZkSyncTx::from(transfer.clone());
+
+    let json = tx.canonical_json();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let keys: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+    let mut sorted_keys = keys.clone();
+    sorted_keys.sort();
+    assert_eq!(keys, sorted_keys);
+    assert_eq!(value["from"], format!("0x{:x}", transfer.from));
+    let pub_key = value["signature"]["pubKey"].as_str().unwrap();
+    assert_eq!(
+        pub_key,
+        format!(
+            "0x{}",
+            hex::encode(transfer.signature.pub_key.serialize_packed().unwrap())
+        )
+    );
+
+    // The representation doesn't depend on the way the transaction was obtained.
+    let restored: ZkSyncTx = serde_json::from_str(&serde_json::to_string(&tx).unwrap()).unwrap();
+    assert_eq!(restored.canonical_json(), json);
+    assert_eq!(restored.canonical_json_hash(), tx.canonical_json_hash());
+}
+
+#[test]
+fn test_signature_scheme_by_version() {
+    let (pk, messages) = gen_pk_and_msg();
+    let pub_key_hash = PubKeyHash::from_privkey(&pk);
+    for &version in &[TxVersion::Legacy, TxVersion::V1] {
+        for msg in &messages {
+            let signature = TxSignature::sign(version, &pk, msg);
+            assert_eq!(signature.verify(version, msg), Some(pub_key_hash));
+            assert_eq!(signature.verify(version, b"other message"), None);
+        }
+    }
+}
+
+#[test]
+fn test_blacklisted_token() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let owner = Address::from(rng.gen::<[u8; 20]>());
+    let other = Address::from(rng.gen::<[u8; 20]>());
+    let blacklist: HashSet<_> = vec![TokenId(1)].into_iter().collect();
+    let withdraw = |to: Address, token: TokenId| {
+        ZkSyncTx::from(Withdraw::new(
+            AccountId(1),
+            owner,
+            to,
+            token,
+            BigUint::from(100u32),
+            BigUint::from(1u32),
+            Nonce(0),
+            Default::default(),
+            None,
+        ))
+    };
+    let transfer = |token: TokenId| {
+        ZkSyncTx::from(Transfer::new(
+            AccountId(1),
+            owner,
+            other,
+            token,
+            BigUint::from(100u32),
+            BigUint::from(1u32),
+            Nonce(0),
+            Default::default(),
+            None,
+        ))
+    };
+
+    assert_eq!(transfer(TokenId(0)).blacklisted_token(&blacklist), None);
+    assert_eq!(
+        transfer(TokenId(1)).blacklisted_token(&blacklist),
+        Some(TokenId(1))
+    );
+    // Blacklisted funds can only be withdrawn to the owner's own address.
+    assert_eq!(
+        withdraw(owner, TokenId(1)).blacklisted_token(&blacklist),
+        None
+    );
+    assert_eq!(
+        withdraw(other, TokenId(1)).blacklisted_token(&blacklist),
+        Some(TokenId(1))
+    );
+    assert_eq!(
+        withdraw(other, TokenId(2)).blacklisted_token(&blacklist),
+        None
+    );
+}
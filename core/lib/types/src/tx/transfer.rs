@@ -19,6 +19,7 @@ use crate::{
     helpers::{
         is_fee_amount_packable, is_token_amount_packable, pack_fee_amount, pack_token_amount,
     },
+    tx::primitives::eip712_signature::{EIP712TypedStructure, StructBuilder},
     tx::TimeRange,
     AccountId, Nonce, TokenId,
 };
@@ -28,8 +29,11 @@ use crate::tx::error::{
     WRONG_FEE_ERROR, WRONG_SIGNATURE, WRONG_TIME_RANGE, WRONG_TOKEN, WRONG_TOKEN_FOR_PAYING_FEE,
     WRONG_TO_ADDRESS,
 };
-use crate::tx::version::TxVersion;
-use crate::{account::PubKeyHash, utils::ethereum_sign_message_part, Engine};
+use crate::tx::version::{
+    ensure_decoded, take_account_id, take_address, take_nonce, take_optional_time_range,
+    take_packed_amount, take_packed_fee, take_time_range, take_token, TxDecodeError, TxVersion,
+};
+use crate::{account::PubKeyHash, tx::TxMessage, Engine};
 
 /// `Transfer` transaction performs a move of funds from one zkSync account to another.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -116,7 +120,7 @@ impl Transfer {
         let mut tx = Self::new(
             account_id, from, to, token, amount, fee, nonce, time_range, None,
         );
-        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        tx.signature = TxSignature::sign(TxVersion::V1, private_key, &tx.get_bytes());
         tx.check_correctness()?;
         Ok(tx)
     }
@@ -146,6 +150,42 @@ impl Transfer {
         self.token.0 < MIN_NFT_TOKEN_ID
     }
 
+    /// Decodes the transaction from the bytes produced by `get_bytes_with_version` or
+    /// `get_old_bytes`, dispatching on the encoding version.
+    /// The signature is not encoded, so the decoded transaction has the default one.
+    pub fn from_signed_bytes(bytes: &[u8]) -> Result<(Self, TxVersion), TxDecodeError> {
+        let (version, mut bytes) = TxVersion::split_signed_bytes(Self::TX_TYPE, bytes)?;
+        let bytes = &mut bytes;
+
+        let account_id = take_account_id(bytes)?;
+        let from = take_address(bytes)?;
+        let to = take_address(bytes)?;
+        let token = take_token(bytes, version)?;
+        let amount = take_packed_amount(bytes)?;
+        let fee = take_packed_fee(bytes)?;
+        let nonce = take_nonce(bytes)?;
+        // The time range is optional only for the legacy transactions.
+        let time_range = match version {
+            TxVersion::Legacy => take_optional_time_range(bytes)?,
+            TxVersion::V1 => Some(take_time_range(bytes)?),
+        };
+        ensure_decoded(bytes)?;
+
+        let tx = Self {
+            account_id,
+            from,
+            to,
+            token,
+            amount,
+            fee,
+            nonce,
+            time_range,
+            signature: Default::default(),
+            cached_signer: VerifiedSignatureCache::NotCached,
+        };
+        Ok((tx, version))
+    }
+
     /// Encodes the transaction data as the byte sequence according to the old zkSync protocol with 2 bytes token.
     pub fn get_old_bytes(&self) -> Vec<u8> {
         if !self.is_backwards_compatible() {
@@ -175,15 +215,14 @@ impl Transfer {
             if self.token.0 < MIN_NFT_TOKEN_ID {
                 if let Some(res) = self
                     .signature
-                    .verify_musig(&self.get_old_bytes())
-                    .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+                    .verify(TxVersion::Legacy, &self.get_old_bytes())
                 {
                     return Some((res, TxVersion::Legacy));
                 }
             }
             self.signature
-                .verify_musig(&self.get_bytes())
-                .map(|pub_key| (PubKeyHash::from_pubkey(&pub_key), TxVersion::V1))
+                .verify(TxVersion::V1, &self.get_bytes())
+                .map(|pub_key_hash| (pub_key_hash, TxVersion::V1))
         }
     }
 
@@ -191,24 +230,18 @@ impl Transfer {
     /// The only difference is the missing `nonce` since it's added at the end of the transactions
     /// batch message.
     pub fn get_ethereum_sign_message_part(&self, token_symbol: &str, decimals: u8) -> String {
-        ethereum_sign_message_part(
-            "Transfer",
-            token_symbol,
-            decimals,
-            &self.amount,
-            &self.fee,
-            &self.to,
-        )
+        TxMessage::new()
+            .transfer("Transfer", &self.amount, token_symbol, decimals, &self.to)
+            .fee(&self.fee, token_symbol, decimals)
+            .build()
     }
 
     /// Gets message that should be signed by Ethereum keys of the account for 2-Factor authentication.
     pub fn get_ethereum_sign_message(&self, token_symbol: &str, decimals: u8) -> String {
-        let mut message = self.get_ethereum_sign_message_part(token_symbol, decimals);
-        if !message.is_empty() {
-            message.push('\n');
-        }
-        message.push_str(format!("Nonce: {}", self.nonce).as_str());
-        message
+        TxMessage::new()
+            .line(self.get_ethereum_sign_message_part(token_symbol, decimals))
+            .nonce(self.nonce)
+            .build()
     }
 
     /// Returns an old-format message that should be signed by Ethereum account key.
@@ -319,3 +352,22 @@ impl Display for TransactionError {
         write!(f, "{}", error)
     }
 }
+
+/// Typed data of the transfer signed by the Ethereum keys of the account for 2-Factor
+/// authentication, as an alternative to the text message.
+impl EIP712TypedStructure for Transfer {
+    const TYPE_NAME: &'static str = "Transfer";
+
+    fn build_structure<BUILDER: StructBuilder>(&self, builder: &mut BUILDER) {
+        let time_range = self.time_range.unwrap_or_default();
+        builder.add_member("accountId", &self.account_id.0);
+        builder.add_member("from", &self.from);
+        builder.add_member("to", &self.to);
+        builder.add_member("token", &self.token.0);
+        builder.add_member("amount", &self.amount);
+        builder.add_member("fee", &self.fee);
+        builder.add_member("nonce", &self.nonce.0);
+        builder.add_member("validFrom", &time_range.valid_from);
+        builder.add_member("validUntil", &time_range.valid_until);
+    }
+}
@@ -1,7 +1,164 @@
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub enum TxVersion {
-    Legacy,
-    V1,
-}
+use std::convert::TryInto;
+
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use zksync_basic_types::Address;
+use zksync_crypto::params::{
+    AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH, FEE_EXPONENT_BIT_WIDTH,
+    FEE_MANTISSA_BIT_WIDTH,
+};
+
+use super::{MusigRescue, SignatureScheme, TimeRange};
+use crate::{
+    helpers::{unpack_fee_amount, unpack_token_amount},
+    AccountId, Nonce, TokenId,
+};
+
+/// Version of the transaction encoding used for signing.
+///
+/// Legacy transactions are encoded starting with the transaction type, while the versioned ones
+/// start with the `255 - tx_type` marker followed by the version byte. Adding a new version allows
+/// to change the transaction format (e.g. add a new field) while still accepting the transactions
+/// signed in the previous format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TxVersion {
+    Legacy,
+    V1,
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TxDecodeError {
+    #[error("Unexpected end of the transaction bytes")]
+    UnexpectedEnd,
+    #[error("Unexpected transaction type {0}")]
+    UnexpectedTxType(u8),
+    #[error("Unsupported transaction version {0}")]
+    UnsupportedVersion(u8),
+    #[error("Transaction type has no legacy encoding")]
+    NoLegacyEncoding,
+    #[error("Amount can't be unpacked")]
+    InvalidAmount,
+    #[error("Fee can't be unpacked")]
+    InvalidFee,
+    #[error("Transaction bytes have {0} unexpected trailing bytes")]
+    TrailingBytes(usize),
+}
+
+impl TxVersion {
+    /// Version byte of the versioned transaction encoding, `None` for the legacy one.
+    pub fn byte(self) -> Option<u8> {
+        match self {
+            Self::Legacy => None,
+            Self::V1 => Some(1),
+        }
+    }
+
+    pub fn from_byte(version: u8) -> Result<Self, TxDecodeError> {
+        match version {
+            1 => Ok(Self::V1),
+            _ => Err(TxDecodeError::UnsupportedVersion(version)),
+        }
+    }
+
+    /// Scheme of the signatures of the transactions encoded in this version.
+    pub fn signature_scheme(self) -> &'static dyn SignatureScheme {
+        match self {
+            Self::Legacy | Self::V1 => &MusigRescue,
+        }
+    }
+
+    /// Detects the encoding version of the transaction bytes and returns it together with
+    /// the bytes following the type and version.
+    pub fn split_signed_bytes(tx_type: u8, bytes: &[u8]) -> Result<(Self, &[u8]), TxDecodeError> {
+        let (&marker, rest) = bytes.split_first().ok_or(TxDecodeError::UnexpectedEnd)?;
+        if marker == tx_type {
+            return Ok((Self::Legacy, rest));
+        }
+        if marker != 255 - tx_type {
+            return Err(TxDecodeError::UnexpectedTxType(marker));
+        }
+        let (&version, rest) = rest.split_first().ok_or(TxDecodeError::UnexpectedEnd)?;
+        Ok((Self::from_byte(version)?, rest))
+    }
+}
+
+/// Reads the next `len` bytes of the encoded transaction.
+pub(crate) fn take_bytes<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], TxDecodeError> {
+    if bytes.len() < len {
+        return Err(TxDecodeError::UnexpectedEnd);
+    }
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    Ok(head)
+}
+
+pub(crate) fn take_u32(bytes: &mut &[u8]) -> Result<u32, TxDecodeError> {
+    Ok(u32::from_be_bytes(
+        take_bytes(bytes, 4)?.try_into().unwrap(),
+    ))
+}
+
+pub(crate) fn take_account_id(bytes: &mut &[u8]) -> Result<AccountId, TxDecodeError> {
+    take_u32(bytes).map(AccountId)
+}
+
+pub(crate) fn take_nonce(bytes: &mut &[u8]) -> Result<Nonce, TxDecodeError> {
+    take_u32(bytes).map(Nonce)
+}
+
+pub(crate) fn take_address(bytes: &mut &[u8]) -> Result<Address, TxDecodeError> {
+    take_bytes(bytes, 20).map(Address::from_slice)
+}
+
+/// Reads the token, which takes 2 bytes in the legacy encoding and 4 bytes in the versioned one.
+pub(crate) fn take_token(bytes: &mut &[u8], version: TxVersion) -> Result<TokenId, TxDecodeError> {
+    match version {
+        TxVersion::Legacy => Ok(TokenId(
+            u16::from_be_bytes(take_bytes(bytes, 2)?.try_into().unwrap()).into(),
+        )),
+        TxVersion::V1 => take_u32(bytes).map(TokenId),
+    }
+}
+
+pub(crate) fn take_packed_amount(bytes: &mut &[u8]) -> Result<BigUint, TxDecodeError> {
+    let len = (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+    unpack_token_amount(take_bytes(bytes, len)?).ok_or(TxDecodeError::InvalidAmount)
+}
+
+/// Reads the amount which isn't packed, as the withdrawals store it.
+pub(crate) fn take_full_amount(bytes: &mut &[u8]) -> Result<BigUint, TxDecodeError> {
+    Ok(u128::from_be_bytes(take_bytes(bytes, 16)?.try_into().unwrap()).into())
+}
+
+pub(crate) fn take_packed_fee(bytes: &mut &[u8]) -> Result<BigUint, TxDecodeError> {
+    let len = (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+    unpack_fee_amount(take_bytes(bytes, len)?).ok_or(TxDecodeError::InvalidFee)
+}
+
+pub(crate) fn take_time_range(bytes: &mut &[u8]) -> Result<TimeRange, TxDecodeError> {
+    let valid_from = u64::from_be_bytes(take_bytes(bytes, 8)?.try_into().unwrap());
+    let valid_until = u64::from_be_bytes(take_bytes(bytes, 8)?.try_into().unwrap());
+    Ok(TimeRange::new(valid_from, valid_until))
+}
+
+/// Reads the time range, which is omitted by the transactions created before it was introduced.
+pub(crate) fn take_optional_time_range(
+    bytes: &mut &[u8],
+) -> Result<Option<TimeRange>, TxDecodeError> {
+    if bytes.is_empty() {
+        Ok(None)
+    } else {
+        take_time_range(bytes).map(Some)
+    }
+}
+
+/// Checks that all the transaction bytes are decoded.
+pub(crate) fn ensure_decoded(bytes: &[u8]) -> Result<(), TxDecodeError> {
+    if bytes.is_empty() {
+        Ok(())
+    } else {
+        Err(TxDecodeError::TrailingBytes(bytes.len()))
+    }
+}
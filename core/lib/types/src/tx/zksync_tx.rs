@@ -2,17 +2,20 @@ use chrono::{DateTime, Utc};
 use num::BigUint;
 use parity_crypto::digest::sha256;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::time::Duration;
 
-use zksync_basic_types::{AccountId, Address};
+use zksync_basic_types::{AccountId, Address, ChainId};
 use zksync_crypto::params::ETH_TOKEN_ID;
 
 use crate::{
+    event::transaction::TransactionType,
     operations::{ChangePubKeyOp, MintNFTOp},
     tx::{
+        eip712_signature::Eip712Domain,
         error::{CloseOperationsDisabled, TransactionError},
-        ChangePubKey, Close, ForcedExit, MintNFT, Swap, TimeRange, Transfer, TxEthSignature,
-        TxHash, TxSignature, Withdraw, WithdrawNFT,
+        ChangePubKey, Close, ForcedExit, MintNFT, PackedEthSignature, Swap, TimeRange, Transfer,
+        TxEthSignature, TxHash, TxSignature, Withdraw, WithdrawNFT,
     },
     utils::deserialize_eth_message,
     CloseOp, ForcedExitOp, Nonce, SwapOp, Token, TokenId, TokenLike, TransferOp, TxFeeTypes,
@@ -184,6 +187,24 @@ impl ZkSyncTx {
         tokens
     }
 
+    /// Returns the blacklisted token used by the transaction, if any.
+    ///
+    /// Funds in the blacklisted tokens are frozen, so the only allowed operation with them
+    /// is the withdrawal to the owner's own L1 address.
+    pub fn blacklisted_token(&self, blacklist: &HashSet<TokenId>) -> Option<TokenId> {
+        let withdraws_to_owner = match self {
+            ZkSyncTx::Withdraw(tx) => tx.from == tx.to,
+            ZkSyncTx::ForcedExit(_) => true,
+            _ => false,
+        };
+        if withdraws_to_owner {
+            return None;
+        }
+        self.tokens()
+            .into_iter()
+            .find(|token| blacklist.contains(token))
+    }
+
     pub fn account_id(&self) -> Result<AccountId, CloseOperationsDisabled> {
         match self {
             ZkSyncTx::Transfer(tx) => Ok(tx.account_id),
@@ -296,6 +317,22 @@ impl ZkSyncTx {
         }
     }
 
+    /// Returns the EIP-712 typed data message that user can sign instead of the text message
+    /// to send the transaction. The domain is bound to the chain id of the network.
+    /// If the transaction can't be signed with the typed data, returns `None`.
+    pub fn get_eip712_sign_message(&self, chain_id: ChainId) -> Option<Vec<u8>> {
+        let domain = Eip712Domain::new(chain_id);
+        match self {
+            ZkSyncTx::Transfer(tx) => Some(PackedEthSignature::typed_data_to_signed_message(
+                &domain, &**tx,
+            )),
+            ZkSyncTx::Withdraw(tx) => Some(PackedEthSignature::typed_data_to_signed_message(
+                &domain, &**tx,
+            )),
+            _ => None,
+        }
+    }
+
     /// Returns a message that user has to sign to send the transaction in the old format.
     /// If the transaction doesn't need a message signature, returns `None`.
     /// Needed for backwards compatibility.
@@ -509,4 +546,18 @@ impl ZkSyncTx {
             ZkSyncTx::WithdrawNFT(_) => "WithdrawNFT".to_string(),
         }
     }
+
+    /// Returns the type of the transaction, `None` for the `Close` transactions which are disabled.
+    pub fn tx_type(&self) -> Option<TransactionType> {
+        match self {
+            ZkSyncTx::Transfer(_) => Some(TransactionType::Transfer),
+            ZkSyncTx::Withdraw(_) => Some(TransactionType::Withdraw),
+            ZkSyncTx::Close(_) => None,
+            ZkSyncTx::ChangePubKey(_) => Some(TransactionType::ChangePubKey),
+            ZkSyncTx::ForcedExit(_) => Some(TransactionType::ForcedExit),
+            ZkSyncTx::MintNFT(_) => Some(TransactionType::MintNFT),
+            ZkSyncTx::Swap(_) => Some(TransactionType::Swap),
+            ZkSyncTx::WithdrawNFT(_) => Some(TransactionType::WithdrawNFT),
+        }
+    }
 }
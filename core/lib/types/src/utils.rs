@@ -1,17 +1,13 @@
 //! Utilities used in tx module.
 
 // External uses.
-use num::{BigUint, Zero};
 use serde::{
     de::{value::SeqAccessDeserializer, Error, SeqAccess, Visitor},
     Deserialize, Deserializer,
 };
 
-// Workspace uses.
-use zksync_utils::format_units;
-
 // Local uses.
-use crate::Address;
+use crate::ChainId;
 
 /// Deserializes either a `String` or `Vec<u8>` into `Vec<u8>`.
 /// The reason we cannot expect just a vector is backward compatibility: messages
@@ -82,44 +78,18 @@ pub mod h256_as_vec {
     }
 }
 
-/// Construct the first part of the message that should be signed by Ethereum key.
-/// The pattern is as follows:
+/// Binds the message signed by Ethereum key to the network by appending the line
 ///
-/// [{Transfer/Withdraw} {amount} {token} to: {to_address}]
-/// [Fee: {fee} {token}]
+/// `Chain ID: {chain_id}`
 ///
-/// Note that both lines are optional.
-pub fn ethereum_sign_message_part(
-    transaction: &str,
-    token_symbol: &str,
-    decimals: u8,
-    amount: &BigUint,
-    fee: &BigUint,
-    to: &Address,
-) -> String {
-    let mut message = if !amount.is_zero() {
-        format!(
-            "{transaction} {amount} {token} to: {to:?}",
-            transaction = transaction,
-            amount = format_units(amount, decimals),
-            token = token_symbol,
-            to = to
-        )
-    } else {
-        String::new()
-    };
-    if !fee.is_zero() {
-        if !message.is_empty() {
-            message.push('\n');
-        }
-        message.push_str(
-            format!(
-                "Fee: {fee} {token}",
-                fee = format_units(fee, decimals),
-                token = token_symbol
-            )
-            .as_str(),
-        );
-    }
-    message
+/// so the signature of the transaction can't be replayed on another network.
+///
+/// The zkSync signature of the transaction is checked by the circuit against the transaction
+/// bytes it serializes itself, so the chain id can't be added to `get_bytes`. Instead, the clients
+/// derive the zkSync keys from the message including the chain id of the testnets, so the keys
+/// and their signatures differ between the networks.
+pub fn chain_bound_ethereum_message(message: &[u8], chain_id: ChainId) -> Vec<u8> {
+    let mut bound_message = message.to_vec();
+    bound_message.extend_from_slice(format!("\nChain ID: {}", chain_id.0).as_bytes());
+    bound_message
 }
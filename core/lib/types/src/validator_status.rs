@@ -0,0 +1,57 @@
+use std::convert::TryFrom;
+
+use ethabi::{decode, ParamType};
+use thiserror::Error;
+
+use zksync_basic_types::Log;
+
+use crate::Address;
+
+#[derive(Debug, Error)]
+#[allow(clippy::large_enum_variant)]
+pub enum ValidatorStatusUpdateEventParseError {
+    #[error("Cannot parse log for Validator Status Update Event {0:?}")]
+    ParseLogError(Log),
+    #[error("Cannot parse log for Validator Status Update Event {0:?}")]
+    ParseError(ethabi::Error),
+}
+
+/// Event emitted by the Governance contract once the validator is activated or deactivated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidatorStatusUpdateEvent {
+    pub validator_address: Address,
+    pub is_active: bool,
+    pub eth_block: u64,
+    /// Index of the event in the Ethereum block, so the updates within the block are ordered.
+    pub log_index: u64,
+}
+
+impl TryFrom<Log> for ValidatorStatusUpdateEvent {
+    type Error = ValidatorStatusUpdateEventParseError;
+
+    fn try_from(event: Log) -> Result<Self, Self::Error> {
+        let (eth_block, log_index) = match (event.block_number, event.log_index) {
+            (Some(block_number), Some(log_index)) => (block_number.as_u64(), log_index.as_u64()),
+            _ => return Err(ValidatorStatusUpdateEventParseError::ParseLogError(event)),
+        };
+        if event.topics.len() < 2 {
+            return Err(ValidatorStatusUpdateEventParseError::ParseLogError(event));
+        }
+
+        let mut decoded_event = decode(
+            &[
+                ParamType::Bool, // isActive
+            ],
+            &event.data.0,
+        )
+        .map_err(ValidatorStatusUpdateEventParseError::ParseError)?;
+        let validator_address = Address::from_slice(&event.topics[1].as_fixed_bytes()[12..]);
+        let is_active = decoded_event.remove(0).into_bool().unwrap();
+        Ok(Self {
+            validator_address,
+            is_active,
+            eth_block,
+            log_index,
+        })
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+/// Payload of the JsonWebToken signed with the shared secret, which the server components
+/// (and the operators' tools) are authorized with.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PayloadAuthToken {
+    /// Subject (whom auth token refers to).
+    pub sub: String,
+    /// Expiration time (as UTC timestamp).
+    pub exp: usize,
+}
+
+impl PayloadAuthToken {
+    pub fn new(exp: usize) -> Self {
+        Self {
+            sub: "Authorization".to_string(),
+            exp,
+        }
+    }
+}
+
+/// The structure that stores the secret key for checking JsonWebToken matching.
+#[cfg(feature = "with-auth-validator")]
+pub struct AuthTokenValidator<'a> {
+    decoding_key: jsonwebtoken::DecodingKey<'a>,
+}
+
+#[cfg(feature = "with-auth-validator")]
+impl<'a> AuthTokenValidator<'a> {
+    pub fn new(secret: &'a str) -> Self {
+        Self {
+            decoding_key: jsonwebtoken::DecodingKey::from_secret(secret.as_ref()),
+        }
+    }
+
+    /// Checks whether the secret key and the authorization token match.
+    pub fn validate_auth_token(&self, token: &str) -> Result<(), jsonwebtoken::errors::Error> {
+        jsonwebtoken::decode::<PayloadAuthToken>(
+            token,
+            &self.decoding_key,
+            &jsonwebtoken::Validation::default(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Validates the bearer token of the request, to be used with the `HttpAuthentication` middleware.
+    pub async fn validator(
+        &self,
+        req: actix_web::dev::ServiceRequest,
+        credentials: actix_web_httpauth::extractors::bearer::BearerAuth,
+    ) -> actix_web::Result<actix_web::dev::ServiceRequest> {
+        use actix_web_httpauth::extractors::{bearer::Config, AuthenticationError};
+
+        let config = req.app_data::<Config>().cloned().unwrap_or_default();
+
+        self.validate_auth_token(credentials.token())
+            .map_err(|_| AuthenticationError::from(config))?;
+
+        Ok(req)
+    }
+}
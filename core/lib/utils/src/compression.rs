@@ -0,0 +1,73 @@
+//! Compression of the large blobs, e.g. proofs and block pubdata, stored in the database or
+//! passed over the network.
+//!
+//! Data is compressed with `zstd`. Compressed data is recognized by the magic number of the zstd
+//! frame, so the readers accept both the compressed and the plain data, and the compression can
+//! be enabled without migrating the data stored before.
+
+// Built-in deps
+use std::{borrow::Cow, io};
+
+/// Magic number every zstd frame starts with. Neither JSON nor the data we compress can start
+/// with these bytes.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Compresses the data with the given zstd compression level.
+pub fn compress(data: &[u8], level: i32) -> Vec<u8> {
+    zstd::encode_all(data, level).expect("Compressing into the memory buffer can't fail")
+}
+
+/// Compresses the data if the compression level is set, otherwise returns the data as is.
+pub fn compress_with_level(data: Vec<u8>, level: Option<i32>) -> Vec<u8> {
+    match level {
+        Some(level) => compress(&data, level),
+        None => data,
+    }
+}
+
+/// Checks whether the data is compressed.
+pub fn is_compressed(data: &[u8]) -> bool {
+    data.starts_with(&ZSTD_MAGIC)
+}
+
+/// Decompresses the data if it's compressed, the plain data is returned as is.
+pub fn decompress(data: &[u8]) -> io::Result<Cow<'_, [u8]>> {
+    if is_compressed(data) {
+        zstd::decode_all(data).map(Cow::Owned)
+    } else {
+        Ok(Cow::Borrowed(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compression_roundtrip() {
+        let data = serde_json::to_vec(&vec!["pubdata"; 100]).unwrap();
+
+        let compressed = compress(&data, 3);
+        assert!(is_compressed(&compressed));
+        assert!(compressed.len() < data.len());
+        assert_eq!(decompress(&compressed).unwrap(), data.as_slice());
+    }
+
+    #[test]
+    fn plain_data_is_not_decompressed() {
+        let data = serde_json::to_vec(&serde_json::json!({ "proof": [1, 2, 3] })).unwrap();
+
+        assert!(!is_compressed(&data));
+        assert!(matches!(decompress(&data).unwrap(), Cow::Borrowed(_)));
+        assert_eq!(compress_with_level(data.clone(), None), data);
+        assert!(is_compressed(&compress_with_level(data, Some(1))));
+    }
+
+    #[test]
+    fn corrupted_data_is_rejected() {
+        let mut compressed = compress(b"witness", 3);
+        compressed.truncate(ZSTD_MAGIC.len() + 2);
+
+        assert!(decompress(&compressed).is_err());
+    }
+}
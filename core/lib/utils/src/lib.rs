@@ -1,13 +1,18 @@
 //! Various helpers used in the zkSync stack.
 
+mod auth_token;
+pub mod compression;
 mod convert;
 mod env_tools;
 mod format;
 mod macros;
+pub mod metrics_labels;
 pub mod panic_notify;
 mod serde_wrappers;
+pub mod shutdown;
 mod string;
 
+pub use auth_token::*;
 pub use convert::*;
 pub use env_tools::*;
 pub use format::*;
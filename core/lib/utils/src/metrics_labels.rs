@@ -0,0 +1,72 @@
+//! Labels shared by the metrics of the pipeline stages, so the operations and blocks
+//! can be followed through the pipeline: API -> mempool -> state keeper -> eth_sender -> prover.
+//!
+//! The API, `eth_watch` and the mempool count the received operations in `process_tx_count`,
+//! the mempool, the state keeper and `eth_sender` report the processed ones to the `process_tx`
+//! histogram, and the state keeper, `eth_sender` and the prover server report the processed blocks
+//! to the `process_block` histogram. The rest of the metrics keep their own labels.
+//! The module is re-exported by `zksync_prometheus_exporter` as `labels`.
+
+use std::fmt;
+use std::time::Duration;
+
+/// Stage of the pipeline which reports the metric, e.g. `mempool` or `seal_block`.
+pub const STAGE: &str = "stage";
+/// Type of the operation, e.g. `Transfer` or `Deposit`.
+pub const NAME: &str = "name";
+/// Id of the operation token.
+pub const TOKEN: &str = "token";
+
+/// Labels of the metrics describing the operation processed by the stage.
+pub fn op_labels(
+    stage: &str,
+    op_type: String,
+    token: impl fmt::Display,
+) -> Vec<(&'static str, String)> {
+    vec![
+        (STAGE, stage.to_string()),
+        (NAME, op_type),
+        (TOKEN, token.to_string()),
+    ]
+}
+
+/// Reports the operation processed by the stage, `elapsed` is the time since it was received.
+pub fn report_op_processed(
+    stage: &str,
+    op_type: String,
+    token: impl fmt::Display,
+    elapsed: Duration,
+) {
+    metrics::histogram!("process_tx", elapsed, &op_labels(stage, op_type, token));
+}
+
+/// Counts the operation received by the stage.
+pub fn report_op_received(stage: &str, op_type: String, token: impl fmt::Display) {
+    metrics::increment_counter!("process_tx_count", &op_labels(stage, op_type, token));
+}
+
+/// Reports the block processed by the stage, `elapsed` is the time since it was created.
+/// The number of the last processed block is exported as the `last_processed_block` gauge,
+/// since the block number label would create a new time series for every block.
+pub fn report_block_processed(stage: &str, block_number: u32, elapsed: Duration) {
+    let labels = vec![(STAGE, stage.to_string())];
+    metrics::histogram!("process_block", elapsed, &labels);
+    metrics::gauge!("last_processed_block", block_number as f64, &labels);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_op_labels() {
+        assert_eq!(
+            op_labels("mempool", "Transfer".to_string(), 1),
+            vec![
+                (STAGE, "mempool".to_string()),
+                (NAME, "Transfer".to_string()),
+                (TOKEN, "1".to_string()),
+            ]
+        );
+    }
+}
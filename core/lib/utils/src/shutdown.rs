@@ -0,0 +1,137 @@
+//! Coordinated graceful shutdown of the actors.
+//!
+//! `ShutdownController` notifies the actors about the shutdown and waits until every actor
+//! holding a `ShutdownGuard` finishes its cleanup and drops the guard. Actors that only need to
+//! react to the shutdown (e.g. to stop accepting new requests) hold a `ShutdownSignal`.
+
+// Built-in deps
+use std::time::Duration;
+// External uses
+use tokio::sync::{mpsc, watch};
+// Local uses
+
+#[derive(Debug)]
+pub struct ShutdownController {
+    trigger: watch::Sender<bool>,
+    signal: ShutdownSignal,
+    guard_sender: mpsc::Sender<()>,
+    guards_dropped: mpsc::Receiver<()>,
+}
+
+impl Default for ShutdownController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShutdownController {
+    pub fn new() -> Self {
+        let (trigger, receiver) = watch::channel(false);
+        // Nothing is ever sent through the channel, it's closed once all the guards are dropped.
+        let (guard_sender, guards_dropped) = mpsc::channel(1);
+        Self {
+            trigger,
+            signal: ShutdownSignal(receiver),
+            guard_sender,
+            guards_dropped,
+        }
+    }
+
+    /// Returns the signal for the actor that the shutdown doesn't wait for.
+    pub fn signal(&self) -> ShutdownSignal {
+        self.signal.clone()
+    }
+
+    /// Returns the guard for the actor the shutdown waits for.
+    /// The actor should drop the guard once its cleanup is finished.
+    pub fn guard(&self) -> ShutdownGuard {
+        ShutdownGuard {
+            signal: self.signal(),
+            _sender: self.guard_sender.clone(),
+        }
+    }
+
+    /// Notifies the actors about the shutdown and waits until all the guards are dropped.
+    /// Returns `false` if the actors didn't finish their cleanup within the `timeout`.
+    pub async fn shutdown(self, timeout: Duration) -> bool {
+        let Self {
+            trigger,
+            signal: _signal,
+            guard_sender,
+            mut guards_dropped,
+        } = self;
+
+        trigger.send(true).unwrap_or_default();
+        drop(guard_sender);
+        tokio::time::timeout(timeout, guards_dropped.recv())
+            .await
+            .is_ok()
+    }
+}
+
+/// Notification about the shutdown, the clones are notified at the same time.
+#[derive(Debug, Clone)]
+pub struct ShutdownSignal(watch::Receiver<bool>);
+
+impl ShutdownSignal {
+    pub fn is_triggered(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Resolves once the shutdown is triggered, never resolves if the controller is dropped without it.
+    pub async fn triggered(&mut self) {
+        while !self.is_triggered() {
+            if self.0.changed().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        }
+    }
+}
+
+/// Notification about the shutdown, which is delayed until the guard is dropped.
+#[derive(Debug, Clone)]
+pub struct ShutdownGuard {
+    signal: ShutdownSignal,
+    _sender: mpsc::Sender<()>,
+}
+
+impl ShutdownGuard {
+    pub fn is_triggered(&self) -> bool {
+        self.signal.is_triggered()
+    }
+
+    /// Resolves once the shutdown is triggered.
+    pub async fn triggered(&mut self) {
+        self.signal.triggered().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn shutdown_waits_for_guards() {
+        let controller = ShutdownController::new();
+        let mut signal = controller.signal();
+        let mut guard = controller.guard();
+        assert!(!signal.is_triggered());
+
+        let actor = tokio::spawn(async move {
+            guard.triggered().await;
+            // Cleanup takes some time, the shutdown must wait for it.
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            drop(guard);
+        });
+
+        assert!(controller.shutdown(Duration::from_secs(5)).await);
+        assert!(actor.await.is_ok());
+        signal.triggered().await;
+        assert!(signal.is_triggered());
+
+        // Shutdown is interrupted if the guard isn't dropped in time.
+        let controller = ShutdownController::new();
+        let _guard = controller.guard();
+        assert!(!controller.shutdown(Duration::from_millis(10)).await);
+    }
+}
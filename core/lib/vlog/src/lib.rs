@@ -6,6 +6,12 @@
 //!
 //! The format of the logs in stdout can be `plain` or` json` and is set by the `MISC_LOG_FORMAT` env variable.
 //!
+//! The log levels are set per module by the `RUST_LOG` env variable (e.g. `zksync_api=debug,zksync_core=info`)
+//! and can be changed at runtime with `set_log_filter` without restarting the application.
+//!
+//! Logs related to the same transaction share the `tx_hash` (or `batch_hash` for batches) field,
+//! so the transaction can be followed from the API request through the mempool to the state keeper.
+//!
 //! Full documentation for the `tracing` crate here https://docs.rs/tracing/
 //!
 //! Integration with sentry for catching errors and react on them immediately
@@ -13,6 +19,7 @@
 //!
 
 use chrono::Duration;
+use once_cell::sync::OnceCell;
 use std::{borrow::Cow, str::FromStr};
 
 pub use sentry;
@@ -20,8 +27,12 @@ use sentry::protocol::Event;
 use sentry::{types::Dsn, ClientInitGuard, ClientOptions};
 
 pub use tracing as __tracing;
-pub use tracing::{debug, info, log, trace};
+pub use tracing::{debug, info, info_span, log, trace, Instrument};
 use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{prelude::*, reload, EnvFilter, Registry};
+
+/// Handle to replace the log filter of the installed logger.
+static LOG_FILTER_HANDLE: OnceCell<reload::Handle<EnvFilter, Registry>> = OnceCell::new();
 
 #[macro_export]
 macro_rules! warn {
@@ -73,24 +84,30 @@ fn get_sentry_url() -> Option<Dsn> {
 pub fn init() -> VlogGuard {
     let log_format = std::env::var("MISC_LOG_FORMAT").unwrap_or_else(|_| "plain".to_string());
     let (non_blocking, _logger_guard) = tracing_appender::non_blocking(std::io::stdout());
+    let (filter, filter_handle) = reload::Layer::new(EnvFilter::from_default_env());
+    let registry = Registry::default().with(filter);
     match log_format.as_str() {
         "plain" => {
-            tracing_subscriber::fmt::Subscriber::builder()
-                .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-                .with_writer(non_blocking)
+            registry
+                .with(tracing_subscriber::fmt::layer().with_writer(non_blocking))
                 .init();
         }
         "json" => {
             let timer = tracing_subscriber::fmt::time::ChronoUtc::rfc3339();
-            tracing_subscriber::fmt::Subscriber::builder()
-                .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-                .with_writer(non_blocking)
-                .with_timer(timer)
-                .json()
+            registry
+                .with(
+                    tracing_subscriber::fmt::layer()
+                        .json()
+                        .with_timer(timer)
+                        .with_writer(non_blocking),
+                )
                 .init();
         }
         _ => panic!("MISC_LOG_FORMAT has an unexpected value {}", log_format),
     };
+    LOG_FILTER_HANDLE
+        .set(filter_handle)
+        .expect("Logger is already initialized");
 
     let _sentry_guard = get_sentry_url().map(|sentry_url| {
         let options = sentry::ClientOptions {
@@ -113,6 +130,24 @@ pub fn init() -> VlogGuard {
     }
 }
 
+/// Replaces the log filter of the logger installed by `init`.
+///
+/// `directives` have the same format as the `RUST_LOG` env variable,
+/// e.g. `zksync_api=debug,zksync_core::state_keeper=trace`.
+pub fn set_log_filter(directives: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let handle = LOG_FILTER_HANDLE.get().ok_or("Logger is not initialized")?;
+    let filter = EnvFilter::try_new(directives)?;
+    handle.reload(filter)?;
+    Ok(())
+}
+
+/// Returns the current log filter of the logger installed by `init`.
+pub fn log_filter() -> Option<String> {
+    LOG_FILTER_HANDLE
+        .get()
+        .and_then(|handle| handle.with_current(|filter| filter.to_string()).ok())
+}
+
 struct AddIntervalToFingerprintIntegration {
     panic_interval: Duration,
     error_interval: Duration,
@@ -155,3 +190,34 @@ impl sentry::Integration for AddIntervalToFingerprintIntegration {
         Some(event)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::level_filters::LevelFilter;
+
+    /// The logger can be installed only once per process, so all the checks are in a single test.
+    #[test]
+    fn set_log_filter_reloads_filter() {
+        assert!(set_log_filter("debug").is_err());
+        assert_eq!(log_filter(), None);
+
+        let _guard = init();
+
+        set_log_filter("debug").unwrap();
+        assert_eq!(log_filter().as_deref(), Some("debug"));
+        assert_eq!(LevelFilter::current(), LevelFilter::DEBUG);
+
+        set_log_filter("zksync_api=trace,warn").unwrap();
+        assert!(log_filter().unwrap().contains("zksync_api=trace"));
+        assert_eq!(LevelFilter::current(), LevelFilter::TRACE);
+
+        set_log_filter("warn").unwrap();
+        assert_eq!(LevelFilter::current(), LevelFilter::WARN);
+
+        // The invalid filter is rejected and the previous one is kept.
+        assert!(set_log_filter("zksync_api=verbose").is_err());
+        assert_eq!(log_filter().as_deref(), Some("warn"));
+        assert_eq!(LevelFilter::current(), LevelFilter::WARN);
+    }
+}
@@ -126,7 +126,7 @@ impl AccountLifespan {
 
             let label = match result {
                 Ok(label) => label,
-                Err(ClientError::NetworkError(_)) | Err(ClientError::OperationTimeout) => {
+                Err(ClientError::TransportError(_)) | Err(ClientError::OperationTimeout) => {
                     if attempt < MAX_RETRIES {
                         // Retry operation.
                         attempt += 1;
@@ -38,9 +38,7 @@ impl AccountLifespan {
         // For example, we will retry operation if fee ticker returned an error,
         // but will panic if transaction cannot be signed.
         match err {
-            ClientError::NetworkError(_)
-            | ClientError::RpcError(_)
-            | ClientError::MalformedResponse(_) => err,
+            ClientError::TransportError(_) | ClientError::ServerError(_) => err,
             _ => panic!("Transaction should be correct"),
         }
     }
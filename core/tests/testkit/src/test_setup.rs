@@ -321,6 +321,7 @@ impl TestSetup {
         let block = ProposedBlock {
             priority_ops: Vec::new(),
             txs: vec![SignedTxVariant::from(SignedZkSyncTx::from(tx))],
+            underpriced_txs: Default::default(),
         };
 
         // Request miniblock execution.
@@ -402,6 +403,7 @@ impl TestSetup {
         let block = ProposedBlock {
             priority_ops: vec![op],
             txs: Vec::new(),
+            underpriced_txs: Default::default(),
         };
 
         // Request miniblock execution.
@@ -943,6 +945,9 @@ impl TestSetup {
                 CommitRequest::RemoveRevertedBlock(_) => {
                     // Remove reverted blocks are ignored
                 }
+                CommitRequest::Flush(sender) => {
+                    sender.send(()).unwrap_or_default();
+                }
             }
         }
         panic!("Proposed blocks receiver dropped");
@@ -975,6 +980,9 @@ impl TestSetup {
             CommitRequest::RemoveRevertedBlock(_) => {
                 // Nothing to be done.
             }
+            CommitRequest::Flush(_) => {
+                panic!("Expected pending block, got flush request");
+            }
         }
     }
 
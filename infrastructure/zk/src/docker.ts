@@ -14,7 +14,8 @@ const IMAGES = [
     'dev-liquidity-token-watcher',
     'zk-environment',
     'event-listener',
-    'data-restore'
+    'data-restore',
+    'witness-generator'
 ];
 
 async function dockerCommand(command: 'push' | 'build', image: string) {
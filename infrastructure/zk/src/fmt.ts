@@ -21,9 +21,11 @@ export async function rustfmt(check: boolean = false) {
 
     await utils.spawn(command);
 
-    process.chdir('sdk/zksync-crypto');
-    await utils.spawn(command);
-    process.chdir(process.env.ZKSYNC_HOME as string);
+    for (const crate of ['sdk/zksync-signing-core', 'sdk/zksync-crypto']) {
+        process.chdir(crate);
+        await utils.spawn(command);
+        process.chdir(process.env.ZKSYNC_HOME as string);
+    }
 }
 
 export const command = new Command('fmt')
@@ -39,9 +39,11 @@ async function clippy() {
     await utils.spawn('cargo clippy  --all --tests --benches -- -D warnings -A clippy::upper-case-acronyms');
     delete process.env.SQLX_OFFLINE;
 
-    process.chdir('sdk/zksync-crypto');
-    await utils.spawn('cargo clippy  --all --tests --benches -- -D warnings -A clippy::upper-case-acronyms');
-    process.chdir(process.env.ZKSYNC_HOME as string);
+    for (const crate of ['sdk/zksync-signing-core', 'sdk/zksync-crypto']) {
+        process.chdir(crate);
+        await utils.spawn('cargo clippy  --all --tests --benches -- -D warnings -A clippy::upper-case-acronyms');
+        process.chdir(process.env.ZKSYNC_HOME as string);
+    }
 }
 
 export const command = new Command('lint')
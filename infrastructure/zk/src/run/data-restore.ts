@@ -32,6 +32,11 @@ export async function run() {
     await utils.spawn('cargo run --bin zksync_data_restore --release -- --genesis --finite');
 }
 
+export async function follow(committed: boolean) {
+    const followCommitted = committed ? '--follow_committed' : '';
+    await utils.spawn(`cargo run --bin zksync_data_restore --release -- --genesis ${followCommitted}`);
+}
+
 export async function check(expectedHash: string) {
     await db.reset();
     await utils.spawn(
@@ -49,6 +54,13 @@ export const command = new Command('data-restore');
 command.command('restart').description('wipe the database and run data restore in finite mode').action(restart);
 command.command('resume').description('run data restore in "resume" mode').action(resume);
 command.command('run').description('do not wipe the database and run data restore in finite mode').action(run);
+command
+    .command('follow')
+    .description('run data restore and keep following the new blocks after restoring the existing ones')
+    .option('--committed', 'apply the committed blocks without waiting for their verification')
+    .action(async (cmd: Command) => {
+        await follow(cmd.committed);
+    });
 
 command
     .command('check <hash>')
@@ -24,6 +24,14 @@ export async function apiNode() {
     );
 }
 
+export async function witnessGenerator() {
+    await utils.spawn('cargo run --bin zksync_witness_generator --release');
+}
+
+export async function exitProofServer() {
+    await utils.spawn('cargo run --bin zksync_exit_proof_server --release');
+}
+
 export async function server() {
     // By the time this function is run the server is most likely not be running yet
     // However, it does not matter, since the only thing the function does is depositing
@@ -112,3 +120,11 @@ export const command = new Command('server')
 command.command('api').description('start api node').action(apiNode);
 command.command('web3').description('start web3 node').action(web3Node);
 command.command('core').description('start core').action(core);
+command
+    .command('witness-generator')
+    .description('start standalone witness generator')
+    .action(witnessGenerator);
+command
+    .command('exit-proof-server')
+    .description('start the server generating exit proofs against the last verified state')
+    .action(exitProofServer);
@@ -66,9 +66,11 @@ export async function js() {
 }
 
 async function rustCryptoTests() {
-    process.chdir('sdk/zksync-crypto');
-    await utils.spawn('cargo test --release');
-    process.chdir(process.env.ZKSYNC_HOME as string);
+    for (const crate of ['sdk/zksync-signing-core', 'sdk/zksync-crypto']) {
+        process.chdir(crate);
+        await utils.spawn('cargo test --release');
+        process.chdir(process.env.ZKSYNC_HOME as string);
+    }
 }
 
 export async function serverRust() {
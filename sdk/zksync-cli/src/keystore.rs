@@ -0,0 +1,96 @@
+//! Storage of the Ethereum private keys in the encrypted keystore files (Web3 Secret Storage).
+
+use std::path::{Path, PathBuf};
+
+use anyhow::ensure;
+use zksync_types::{tx::PackedEthSignature, Address, H256};
+
+/// Ethereum account stored in the keystore.
+#[derive(Debug)]
+pub struct StoredKey {
+    pub address: Address,
+    pub path: PathBuf,
+}
+
+/// Generates a new private key and stores it into a keystore file in the `dir` directory.
+pub fn create(dir: &Path, password: &str) -> anyhow::Result<StoredKey> {
+    let (private_key, file_name) = eth_keystore::new(dir, &mut rand::thread_rng(), password)?;
+    let private_key = to_private_key(&private_key)?;
+
+    stored_key(dir, file_name, &private_key)
+}
+
+/// Encrypts the provided private key into a keystore file in the `dir` directory.
+pub fn import(dir: &Path, private_key: &H256, password: &str) -> anyhow::Result<StoredKey> {
+    let file_name = eth_keystore::encrypt_key(
+        dir,
+        &mut rand::thread_rng(),
+        private_key.as_bytes(),
+        password,
+    )?;
+
+    stored_key(dir, file_name, private_key)
+}
+
+/// Reads the private key from the keystore file.
+pub fn decrypt(path: &Path, password: &str) -> anyhow::Result<H256> {
+    let private_key = eth_keystore::decrypt_key(path, password)?;
+    to_private_key(&private_key)
+}
+
+/// Parses the hex encoded private key, with or without the `0x` prefix.
+pub fn parse_private_key(value: &str) -> anyhow::Result<H256> {
+    let value = value.strip_prefix("0x").unwrap_or(value);
+    to_private_key(&hex::decode(value)?)
+}
+
+fn to_private_key(bytes: &[u8]) -> anyhow::Result<H256> {
+    ensure!(bytes.len() == 32, "Private key must be 32 bytes long");
+    Ok(H256::from_slice(bytes))
+}
+
+fn stored_key(dir: &Path, file_name: String, private_key: &H256) -> anyhow::Result<StoredKey> {
+    let address = PackedEthSignature::address_from_private_key(private_key)?;
+
+    Ok(StoredKey {
+        address,
+        path: dir.join(file_name),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keystore_roundtrip() {
+        let dir = std::env::temp_dir().join("zksync-cli-keystore-test");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let private_key = H256::repeat_byte(0x11);
+        let stored_key = import(&dir, &private_key, "password").unwrap();
+        assert_eq!(
+            stored_key.address,
+            PackedEthSignature::address_from_private_key(&private_key).unwrap()
+        );
+
+        assert_eq!(decrypt(&stored_key.path, "password").unwrap(), private_key);
+        assert!(decrypt(&stored_key.path, "wrong password").is_err());
+
+        std::fs::remove_file(&stored_key.path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_private_key() {
+        let private_key = H256::repeat_byte(0xab);
+        let encoded = hex::encode(private_key.as_bytes());
+
+        assert_eq!(parse_private_key(&encoded).unwrap(), private_key);
+        assert_eq!(
+            parse_private_key(&format!("0x{}", encoded)).unwrap(),
+            private_key
+        );
+        assert!(parse_private_key("0xabcd").is_err());
+        assert!(parse_private_key("not a hex").is_err());
+    }
+}
@@ -0,0 +1,456 @@
+//! Command line wallet for zkSync built on top of the `zksync` Rust SDK.
+//!
+//! Every command prints its result as JSON to stdout, so the tool can be used in scripts.
+//! The Ethereum private key of the account is read either from the encrypted keystore file
+//! (`--keystore` and `--password`) or from the `--private-key` option. Both the password and
+//! the private key can be passed via the environment variables instead.
+
+mod keystore;
+mod utils;
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, ensure};
+use serde_json::json;
+use structopt::StructOpt;
+
+use zksync::{
+    provider::Provider, tokens_cache::TokensCache, types::BlockStatus, utils::biguint_to_u256,
+    Network, NetworkConfig, RpcProvider, Wallet, WalletCredentials,
+};
+use zksync_eth_signer::PrivateKeySigner;
+use zksync_types::{
+    tx::{PackedEthSignature, TxHash},
+    Address, Token, TokenLike, H256,
+};
+use zksync_utils::format_units;
+
+use crate::utils::parse_units;
+
+#[derive(StructOpt)]
+enum Command {
+    /// Manage the encrypted keystore files
+    Keystore(KeystoreCommand),
+    /// Print the balances of the account, the one of the loaded key if the address is not set
+    Balance {
+        /// Address of the account to query
+        #[structopt(long)]
+        address: Option<Address>,
+        /// Token to query the balance of, all the balances are printed if not set
+        #[structopt(long)]
+        token: Option<String>,
+        /// Query the balances of the verified state instead of the committed one
+        #[structopt(long)]
+        verified: bool,
+    },
+    /// Deposit the funds from Ethereum to zkSync, the ERC20 deposits are approved if needed
+    Deposit {
+        /// Token to deposit
+        #[structopt(long, default_value = "ETH")]
+        token: String,
+        /// Amount to deposit in the token units, e.g. 1.5
+        amount: String,
+        /// Recipient of the deposit, the account itself if not set
+        #[structopt(long)]
+        to: Option<Address>,
+        /// Wait for the Ethereum transaction to be mined
+        #[structopt(long)]
+        wait: bool,
+    },
+    /// Transfer the funds to another zkSync account
+    Transfer {
+        /// Recipient of the transfer
+        to: Address,
+        /// Amount to transfer in the token units, e.g. 1.5
+        amount: String,
+        /// Token to transfer, the fee is paid in the same token
+        #[structopt(long, default_value = "ETH")]
+        token: String,
+        /// Fee in the token units, requested from the server if not set
+        #[structopt(long)]
+        fee: Option<String>,
+        /// Wait for the transaction to be committed
+        #[structopt(long)]
+        wait: bool,
+    },
+    /// Withdraw the funds from zkSync to Ethereum
+    Withdraw {
+        /// Amount to withdraw in the token units, e.g. 1.5
+        amount: String,
+        /// Ethereum recipient of the withdrawal, the account itself if not set
+        #[structopt(long)]
+        to: Option<Address>,
+        /// Token to withdraw, the fee is paid in the same token
+        #[structopt(long, default_value = "ETH")]
+        token: String,
+        /// Fee in the token units, requested from the server if not set
+        #[structopt(long)]
+        fee: Option<String>,
+        /// Wait for the transaction to be committed
+        #[structopt(long)]
+        wait: bool,
+    },
+    /// Set the zkSync signing key of the account, authorized by the Ethereum signature or onchain
+    ChangePubkey {
+        /// Token to pay the fee in
+        #[structopt(long, default_value = "ETH")]
+        fee_token: String,
+        /// Authorize the key by the zkSync contract call instead of the signature, requires `--web3-url`
+        #[structopt(long)]
+        onchain: bool,
+        /// Fee in the token units, requested from the server if not set
+        #[structopt(long)]
+        fee: Option<String>,
+        /// Wait for the transaction to be committed
+        #[structopt(long)]
+        wait: bool,
+    },
+    /// Print the status of the zkSync transaction
+    TxStatus {
+        /// Hash of the transaction, e.g. sync-tx:0123...
+        hash: TxHash,
+    },
+}
+
+#[derive(StructOpt)]
+enum KeystoreCommand {
+    /// Generate a new Ethereum account and store its key into the keystore directory
+    New {
+        /// Directory to store the keystore file in
+        #[structopt(long, default_value = ".")]
+        dir: PathBuf,
+    },
+    /// Store the key provided via `--private-key` into the keystore directory
+    Import {
+        /// Directory to store the keystore file in
+        #[structopt(long, default_value = ".")]
+        dir: PathBuf,
+    },
+}
+
+#[derive(StructOpt)]
+#[structopt(name = "zkSync CLI wallet", author = "Matter Labs")]
+struct Opt {
+    /// Network to operate in: mainnet, rinkeby, ropsten, goerli or localhost
+    #[structopt(long, env = "ZKSYNC_NETWORK", default_value = "localhost")]
+    network: Network,
+    /// Address of the zkSync JSON RPC server, the default one of the network is used if not set
+    #[structopt(long, env = "ZKSYNC_RPC_URL")]
+    rpc_url: Option<String>,
+    /// Address of the Ethereum node, required for the deposits and the onchain key authorization
+    #[structopt(long, env = "ZKSYNC_WEB3_URL")]
+    web3_url: Option<String>,
+    /// Keystore file with the Ethereum private key of the account
+    #[structopt(long, env = "ZKSYNC_KEYSTORE")]
+    keystore: Option<PathBuf>,
+    /// Password of the keystore file
+    #[structopt(long, env = "ZKSYNC_KEYSTORE_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
+    /// Ethereum private key of the account, used if the keystore file is not set
+    #[structopt(long, env = "ZKSYNC_PRIVATE_KEY", hide_env_values = true)]
+    private_key: Option<String>,
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+impl Opt {
+    /// Connects to the server, checking that it uses the known zkSync contract of the network.
+    async fn provider(&self) -> anyhow::Result<RpcProvider> {
+        let config = match &self.rpc_url {
+            Some(rpc_url) => NetworkConfig::Custom {
+                network: self.network,
+                rpc_addr: rpc_url.clone(),
+                main_contract: None,
+            },
+            None => NetworkConfig::from(self.network),
+        };
+
+        Ok(config.connect().await?)
+    }
+
+    fn web3_url(&self) -> anyhow::Result<&str> {
+        self.web3_url
+            .as_deref()
+            .ok_or_else(|| anyhow!("Ethereum node address is required, set it via `--web3-url`"))
+    }
+
+    fn password(&self) -> anyhow::Result<&str> {
+        self.password
+            .as_deref()
+            .ok_or_else(|| anyhow!("Keystore password is required, set it via `--password`"))
+    }
+
+    fn private_key(&self) -> anyhow::Result<H256> {
+        match (&self.keystore, &self.private_key) {
+            (Some(keystore), _) => keystore::decrypt(keystore, self.password()?),
+            (None, Some(private_key)) => keystore::parse_private_key(private_key),
+            (None, None) => bail!("Account key is required, set `--keystore` or `--private-key`"),
+        }
+    }
+
+    fn address(&self) -> anyhow::Result<Address> {
+        Ok(PackedEthSignature::address_from_private_key(
+            &self.private_key()?,
+        )?)
+    }
+
+    async fn wallet(&self) -> anyhow::Result<Wallet<PrivateKeySigner, RpcProvider>> {
+        let private_key = self.private_key()?;
+        let address = PackedEthSignature::address_from_private_key(&private_key)?;
+        let credentials = WalletCredentials::from_eth_signer(
+            address,
+            PrivateKeySigner::new(private_key),
+            self.network,
+        )
+        .await?;
+
+        Ok(Wallet::new(self.provider().await?, credentials).await?)
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+
+    let output = match &opt.command {
+        Command::Keystore(command) => keystore_command(&opt, command)?,
+        Command::Balance {
+            address,
+            token,
+            verified,
+        } => {
+            let address = match address {
+                Some(address) => *address,
+                None => opt.address()?,
+            };
+            let block_status = if *verified {
+                BlockStatus::Verified
+            } else {
+                BlockStatus::Committed
+            };
+            balance(
+                &opt.provider().await?,
+                address,
+                token.as_deref(),
+                block_status,
+            )
+            .await?
+        }
+        Command::Deposit {
+            token,
+            amount,
+            to,
+            wait,
+        } => {
+            let wallet = opt.wallet().await?;
+            let ethereum = wallet.ethereum(opt.web3_url()?).await?;
+
+            let token = resolve_token(&wallet.tokens, token)?;
+            let amount = parse_units(amount, token.decimals)?;
+            if !wallet.tokens.is_eth(token.id.into())
+                && !ethereum.is_erc20_deposit_approved(token.id).await?
+            {
+                let approve_hash = ethereum.approve_erc20_token_deposits(token.id).await?;
+                ethereum.wait_for_tx(approve_hash).await?;
+            }
+
+            let tx_hash = ethereum
+                .deposit(
+                    token.id,
+                    biguint_to_u256(amount),
+                    to.unwrap_or_else(|| wallet.address()),
+                )
+                .await?;
+            let receipt = if *wait {
+                Some(ethereum.wait_for_tx(tx_hash).await?)
+            } else {
+                None
+            };
+
+            json!({
+                "ethTxHash": tx_hash,
+                "success": receipt.map(|receipt| receipt.status == Some(1.into())),
+            })
+        }
+        Command::Transfer {
+            to,
+            amount,
+            token,
+            fee,
+            wait,
+        } => {
+            let wallet = opt.wallet().await?;
+            ensure_account_exists(&wallet)?;
+
+            let token = resolve_token(&wallet.tokens, token)?;
+            let mut builder = wallet
+                .start_transfer()
+                .to(*to)
+                .token(token.id)?
+                .amount_exact(parse_units(amount, token.decimals)?)?;
+            if let Some(fee) = fee {
+                builder = builder.fee_exact(parse_units(fee, token.decimals)?)?;
+            }
+
+            let handle = builder.send().await?;
+            let info = if *wait {
+                Some(handle.wait_for_commit().await?)
+            } else {
+                None
+            };
+
+            json!({ "txHash": handle.hash(), "info": info })
+        }
+        Command::Withdraw {
+            amount,
+            to,
+            token,
+            fee,
+            wait,
+        } => {
+            let wallet = opt.wallet().await?;
+            ensure_account_exists(&wallet)?;
+
+            let token = resolve_token(&wallet.tokens, token)?;
+            let mut builder = wallet
+                .start_withdraw()
+                .to(to.unwrap_or_else(|| wallet.address()))
+                .token(token.id)?
+                .amount_exact(parse_units(amount, token.decimals)?)?;
+            if let Some(fee) = fee {
+                builder = builder.fee_exact(parse_units(fee, token.decimals)?)?;
+            }
+
+            let handle = builder.send().await?;
+            let info = if *wait {
+                Some(handle.wait_for_commit().await?)
+            } else {
+                None
+            };
+
+            json!({ "txHash": handle.hash(), "info": info })
+        }
+        Command::ChangePubkey {
+            fee_token,
+            onchain,
+            fee,
+            wait,
+        } => {
+            let wallet = opt.wallet().await?;
+            ensure_account_exists(&wallet)?;
+
+            // The command is idempotent, so the scripts may run it unconditionally.
+            if wallet.is_signing_key_set().await? {
+                json!({ "txHash": null, "info": null, "signingKeySet": true })
+            } else {
+                let fee_token = resolve_token(&wallet.tokens, fee_token)?;
+                let mut builder = wallet.start_change_pubkey().fee_token(fee_token.id)?;
+                if *onchain {
+                    let ethereum = wallet.ethereum(opt.web3_url()?).await?;
+                    let nonce = wallet.authorize_signing_key_onchain(&ethereum).await?;
+                    builder = builder.onchain_auth(true).nonce(nonce);
+                }
+                if let Some(fee) = fee {
+                    builder = builder.fee_exact(parse_units(fee, fee_token.decimals)?)?;
+                }
+
+                let handle = builder.send().await?;
+                let info = if *wait {
+                    Some(handle.wait_for_commit().await?)
+                } else {
+                    None
+                };
+
+                json!({ "txHash": handle.hash(), "info": info, "signingKeySet": false })
+            }
+        }
+        Command::TxStatus { hash } => {
+            let info = opt.provider().await?.tx_info(*hash).await?;
+            serde_json::to_value(info)?
+        }
+    };
+
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
+
+fn keystore_command(opt: &Opt, command: &KeystoreCommand) -> anyhow::Result<serde_json::Value> {
+    let stored_key = match command {
+        KeystoreCommand::New { dir } => keystore::create(dir, opt.password()?)?,
+        KeystoreCommand::Import { dir } => {
+            let private_key = opt
+                .private_key
+                .as_deref()
+                .ok_or_else(|| anyhow!("Import requires `--private-key` to be set"))?;
+            keystore::import(
+                dir,
+                &keystore::parse_private_key(private_key)?,
+                opt.password()?,
+            )?
+        }
+    };
+
+    Ok(json!({
+        "address": stored_key.address,
+        "keystore": stored_key.path.display().to_string(),
+    }))
+}
+
+async fn balance(
+    provider: &RpcProvider,
+    address: Address,
+    token: Option<&str>,
+    block_status: BlockStatus,
+) -> anyhow::Result<serde_json::Value> {
+    let tokens = TokensCache::new(provider.tokens().await?);
+    let account_info = provider.account_info(address).await?;
+    let account_state = match block_status {
+        BlockStatus::Committed => account_info.committed,
+        BlockStatus::Verified => account_info.verified,
+    };
+
+    let format_balance = |token: &Token| {
+        let balance = account_state
+            .balances
+            .get(&token.symbol)
+            .map(|balance| balance.0.clone())
+            .unwrap_or_default();
+        format_units(balance, token.decimals)
+    };
+
+    let output = match token {
+        Some(token) => {
+            let token = resolve_token(&tokens, token)?;
+            json!({
+                "address": address,
+                "token": token.symbol,
+                "balance": format_balance(&token),
+            })
+        }
+        None => {
+            let mut balances = serde_json::Map::new();
+            for symbol in account_state.balances.keys() {
+                let token = resolve_token(&tokens, symbol)?;
+                balances.insert(token.symbol.clone(), format_balance(&token).into());
+            }
+            json!({ "address": address, "balances": balances })
+        }
+    };
+
+    Ok(output)
+}
+
+fn resolve_token(tokens: &TokensCache, token: &str) -> anyhow::Result<Token> {
+    tokens
+        .resolve(TokenLike::parse(token))
+        .ok_or_else(|| anyhow!("Token '{}' is not supported by zkSync", token))
+}
+
+fn ensure_account_exists(wallet: &Wallet<PrivateKeySigner, RpcProvider>) -> anyhow::Result<()> {
+    ensure!(
+        wallet.account_id().is_some(),
+        "Account {:?} doesn't exist in zkSync yet, make a deposit to create it",
+        wallet.address()
+    );
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use num::BigUint;
+
+/// Parses the decimal amount of tokens into the amount in the smallest token units.
+/// Behaves just like ethers.utils.parseUnits, i.e. `parse_units("1.5", 18)` is `1.5 * 10^18`.
+pub fn parse_units(value: &str, decimals: u8) -> anyhow::Result<BigUint> {
+    Ok(zksync::utils::parse_units(value, decimals)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_units() {
+        // Test vector of (decimals, input, expected amount)
+        let vectors = vec![
+            (18, "1", "1000000000000000000"),
+            (18, "1.5", "1500000000000000000"),
+            (18, "0.000000000000000001", "1"),
+            (6, ".25", "250000"),
+            (6, "12.", "12000000"),
+            (6, "1.100000000", "1100000"),
+            (0, "42", "42"),
+            (0, "42.0", "42"),
+        ];
+
+        for (decimals, input, expected) in vectors {
+            let expected: BigUint = expected.parse().unwrap();
+            assert_eq!(
+                parse_units(input, decimals).unwrap(),
+                expected,
+                "Incorrect parsing of {} with {} decimals",
+                input,
+                decimals
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_units_errors() {
+        for &(decimals, input) in &[
+            (18, ""),
+            (18, "."),
+            (18, "-1"),
+            (18, "1e18"),
+            (18, "1.2.3"),
+            (6, "0.0000001"),
+            (0, "1.5"),
+        ] {
+            assert!(
+                parse_units(input, decimals).is_err(),
+                "{} with {} decimals must not be parsed",
+                input,
+                decimals
+            );
+        }
+    }
+}
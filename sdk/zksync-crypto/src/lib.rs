@@ -1,36 +1,19 @@
 //! Utils for signing zksync transactions.
-//! This crate is compiled into wasm to be used in `zksync.js`.
+//! This crate is compiled into wasm to be used in `zksync.js`, the signing logic
+//! itself is implemented by `zksync-signing-core`.
 
 #[cfg(test)]
 mod tests;
 mod utils;
 
-const PACKED_POINT_SIZE: usize = 32;
-const PACKED_SIGNATURE_SIZE: usize = 64;
-
-pub use franklin_crypto::bellman::pairing::bn256::{Bn256 as Engine, Fr};
-use franklin_crypto::rescue::bn256::Bn256RescueParams;
-
-pub type Fs = <Engine as JubjubEngine>::Fs;
-
-thread_local! {
-    pub static JUBJUB_PARAMS: AltJubjubBn256 = AltJubjubBn256::new();
-    pub static RESCUE_PARAMS: Bn256RescueParams = Bn256RescueParams::new_checked_2_into_1();
-}
-
 use wasm_bindgen::prelude::*;
 
-use franklin_crypto::{
-    alt_babyjubjub::{edwards, fs::FsRepr, AltJubjubBn256, FixedGenerators},
-    bellman::pairing::ff::{PrimeField, PrimeFieldRepr},
-    eddsa::{PrivateKey, PublicKey, Seed, Signature as EddsaSignature},
-    jubjub::JubjubEngine,
+use franklin_crypto::eddsa::PrivateKey;
+use zksync_signing_core::{
+    hash, keys, ChangePubKey, Engine, Error, Transfer, Withdraw, JUBJUB_PARAMS, RESCUE_PARAMS,
 };
 
-pub type Signature = EddsaSignature<Engine>;
-
 use crate::utils::set_panic_hook;
-use sha2::{Digest, Sha256};
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
@@ -38,6 +21,10 @@ use sha2::{Digest, Sha256};
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
+fn js_error(err: Error) -> JsValue {
+    JsValue::from_str(&err.to_string())
+}
+
 #[wasm_bindgen(start)]
 /// This method initializes params for current thread, otherwise they will be initialized when signing
 /// first message.
@@ -49,88 +36,38 @@ pub fn zksync_crypto_init() {
 
 #[wasm_bindgen(js_name = privateKeyFromSeed)]
 pub fn private_key_from_seed(seed: &[u8]) -> Result<Vec<u8>, JsValue> {
-    if seed.len() < 32 {
-        return Err(JsValue::from_str("Seed is too short"));
-    };
-
-    let sha256_bytes = |input: &[u8]| -> Vec<u8> {
-        let mut hasher = Sha256::new();
-        hasher.input(input);
-        hasher.result().to_vec()
-    };
-
-    let mut effective_seed = sha256_bytes(seed);
-
-    loop {
-        let raw_priv_key = sha256_bytes(&effective_seed);
-        let mut fs_repr = FsRepr::default();
-        fs_repr
-            .read_be(&raw_priv_key[..])
-            .expect("failed to read raw_priv_key");
-        if Fs::from_repr(fs_repr).is_ok() {
-            return Ok(raw_priv_key);
-        } else {
-            effective_seed = raw_priv_key;
-        }
-    }
+    zksync_signing_core::private_key_from_seed(seed).map_err(js_error)
 }
 
 fn read_signing_key(private_key: &[u8]) -> Result<PrivateKey<Engine>, JsValue> {
-    let mut fs_repr = FsRepr::default();
-    fs_repr
-        .read_be(private_key)
-        .map_err(|_| JsValue::from_str("couldn't read private key repr"))?;
-    Ok(PrivateKey::<Engine>(
-        Fs::from_repr(fs_repr).expect("couldn't read private key from repr"),
-    ))
-}
-
-fn privkey_to_pubkey_internal(private_key: &[u8]) -> Result<PublicKey<Engine>, JsValue> {
-    let p_g = FixedGenerators::SpendingKeyGenerator;
-
-    let sk = read_signing_key(private_key)?;
-
-    Ok(JUBJUB_PARAMS.with(|params| PublicKey::from_private(&sk, p_g, params)))
+    keys::read_private_key(private_key).map_err(js_error)
 }
 
 #[wasm_bindgen(js_name = pubKeyHash)]
 pub fn pub_key_hash(pubkey: &[u8]) -> Result<Vec<u8>, JsValue> {
-    let pubkey = JUBJUB_PARAMS
-        .with(|params| PublicKey::read(pubkey, params))
-        .map_err(|_| JsValue::from_str("couldn't read public key"))?;
-    Ok(utils::pub_key_hash(&pubkey))
+    zksync_signing_core::pub_key_hash(pubkey).map_err(js_error)
 }
 
 #[wasm_bindgen]
 pub fn private_key_to_pubkey_hash(private_key: &[u8]) -> Result<Vec<u8>, JsValue> {
-    Ok(utils::pub_key_hash(&privkey_to_pubkey_internal(
-        private_key,
-    )?))
+    zksync_signing_core::private_key_to_pubkey_hash(private_key).map_err(js_error)
 }
 
 #[wasm_bindgen]
 pub fn private_key_to_pubkey(private_key: &[u8]) -> Result<Vec<u8>, JsValue> {
-    let mut pubkey_buf = Vec::with_capacity(PACKED_POINT_SIZE);
-
-    let pubkey = privkey_to_pubkey_internal(private_key)?;
-
-    pubkey
-        .write(&mut pubkey_buf)
-        .expect("failed to write pubkey to buffer");
-
-    Ok(pubkey_buf)
+    zksync_signing_core::private_key_to_pubkey(private_key).map_err(js_error)
 }
 
 #[wasm_bindgen(js_name = "rescueHash")]
 pub fn rescue_hash_tx_msg(msg: &[u8]) -> Vec<u8> {
-    utils::rescue_hash_tx_msg(msg)
+    hash::rescue_hash_tx_msg(msg)
 }
 
 /// `msg` should be represented by 2 concatenated
 /// serialized orders of the swap transaction
 #[wasm_bindgen(js_name = "rescueHashOrders")]
 pub fn rescue_hash_orders(msg: &[u8]) -> Vec<u8> {
-    utils::rescue_hash_orders(msg)
+    hash::rescue_hash_orders(msg)
 }
 
 #[wasm_bindgen]
@@ -141,88 +78,113 @@ pub fn rescue_hash_orders(msg: &[u8]) -> Vec<u8> {
 /// [32..64] - packed r point of the signature.
 /// [64..96] - s poing of the signature.
 pub fn sign_musig(private_key: &[u8], msg: &[u8]) -> Result<Vec<u8>, JsValue> {
-    let mut packed_full_signature = Vec::with_capacity(PACKED_POINT_SIZE + PACKED_SIGNATURE_SIZE);
-    let p_g = FixedGenerators::SpendingKeyGenerator;
-    let private_key = read_signing_key(private_key)?;
-
-    {
-        let public_key =
-            JUBJUB_PARAMS.with(|params| PublicKey::from_private(&private_key, p_g, params));
-        public_key
-            .write(&mut packed_full_signature)
-            .expect("failed to write pubkey to packed_point");
-    };
-
-    let signature = JUBJUB_PARAMS.with(|jubjub_params| {
-        RESCUE_PARAMS.with(|rescue_params| {
-            let hashed_msg = utils::rescue_hash_tx_msg(msg);
-            let seed = Seed::deterministic_seed(&private_key, &hashed_msg);
-            private_key.musig_rescue_sign(&hashed_msg, &seed, p_g, rescue_params, jubjub_params)
-        })
-    });
-
-    signature
-        .r
-        .write(&mut packed_full_signature)
-        .expect("failed to write signature");
-    signature
-        .s
-        .into_repr()
-        .write_le(&mut packed_full_signature)
-        .expect("failed to write signature repr");
-
-    assert_eq!(
-        packed_full_signature.len(),
-        PACKED_POINT_SIZE + PACKED_SIGNATURE_SIZE,
-        "incorrect signature size when signing"
-    );
-
-    Ok(packed_full_signature)
+    zksync_signing_core::sign_musig(private_key, msg).map_err(js_error)
 }
 
 #[wasm_bindgen]
 pub fn verify_musig(msg: &[u8], signature: &[u8]) -> Result<bool, JsValue> {
-    if signature.len() != PACKED_POINT_SIZE + PACKED_SIGNATURE_SIZE {
-        return Err(JsValue::from_str("Signature length is not 96 bytes. Make sure it contains both the public key and the signature itself."));
-    }
-
-    let pubkey = &signature[..PACKED_POINT_SIZE];
-    let pubkey = JUBJUB_PARAMS
-        .with(|params| edwards::Point::read(pubkey, params).map(PublicKey))
-        .map_err(|_| JsValue::from_str("couldn't read public key"))?;
-
-    let signature = deserialize_signature(&signature[PACKED_POINT_SIZE..])?;
-
-    let msg = utils::rescue_hash_tx_msg(msg);
-    let value = JUBJUB_PARAMS.with(|jubjub_params| {
-        RESCUE_PARAMS.with(|rescue_params| {
-            pubkey.verify_musig_rescue(
-                &msg,
-                &signature,
-                FixedGenerators::SpendingKeyGenerator,
-                rescue_params,
-                jubjub_params,
-            )
-        })
-    });
-
-    Ok(value)
+    zksync_signing_core::verify_musig(msg, signature).map_err(js_error)
 }
 
-fn deserialize_signature(bytes: &[u8]) -> Result<Signature, JsValue> {
-    let (r_bar, s_bar) = bytes.split_at(PACKED_POINT_SIZE);
+/// Returns the bytes of the transfer to be signed with `sign_musig`.
+/// Amounts are passed as decimal strings, since they don't fit into the JS numbers.
+#[wasm_bindgen(js_name = "transferBytes")]
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_bytes(
+    account_id: u32,
+    from: &[u8],
+    to: &[u8],
+    token: u32,
+    amount: &str,
+    fee: &str,
+    nonce: u32,
+    valid_from: u64,
+    valid_until: u64,
+) -> Result<Vec<u8>, JsValue> {
+    Transfer {
+        account_id,
+        from: read_address(from)?,
+        to: read_address(to)?,
+        token,
+        amount: read_amount(amount)?,
+        fee: read_amount(fee)?,
+        nonce,
+        valid_from,
+        valid_until,
+    }
+    .get_bytes()
+    .map_err(js_error)
+}
 
-    let r = JUBJUB_PARAMS
-        .with(|params| edwards::Point::read(r_bar, params))
-        .map_err(|_| JsValue::from_str("Failed to parse signature"))?;
+/// Returns the bytes of the withdraw to be signed with `sign_musig`.
+/// Amounts are passed as decimal strings, since they don't fit into the JS numbers.
+#[wasm_bindgen(js_name = "withdrawBytes")]
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_bytes(
+    account_id: u32,
+    from: &[u8],
+    to: &[u8],
+    token: u32,
+    amount: &str,
+    fee: &str,
+    nonce: u32,
+    valid_from: u64,
+    valid_until: u64,
+) -> Result<Vec<u8>, JsValue> {
+    Withdraw {
+        account_id,
+        from: read_address(from)?,
+        to: read_address(to)?,
+        token,
+        amount: read_amount(amount)?,
+        fee: read_amount(fee)?,
+        nonce,
+        valid_from,
+        valid_until,
+    }
+    .get_bytes()
+    .map_err(js_error)
+}
 
-    let mut s_repr = FsRepr::default();
-    s_repr
-        .read_le(s_bar)
-        .map_err(|_| JsValue::from_str("Failed to parse signature"))?;
+/// Returns the bytes of the change pubkey transaction to be signed with `sign_musig`.
+/// The fee is passed as a decimal string, since it doesn't fit into the JS numbers.
+#[wasm_bindgen(js_name = "changePubKeyBytes")]
+#[allow(clippy::too_many_arguments)]
+pub fn change_pub_key_bytes(
+    account_id: u32,
+    account: &[u8],
+    new_pk_hash: &[u8],
+    fee_token: u32,
+    fee: &str,
+    nonce: u32,
+    valid_from: u64,
+    valid_until: u64,
+) -> Result<Vec<u8>, JsValue> {
+    ChangePubKey {
+        account_id,
+        account: read_address(account)?,
+        new_pk_hash: read_address(new_pk_hash)?,
+        fee_token,
+        fee: read_amount(fee)?,
+        nonce,
+        valid_from,
+        valid_until,
+    }
+    .get_bytes()
+    .map_err(js_error)
+}
 
-    let s = <Engine as JubjubEngine>::Fs::from_repr(s_repr)
-        .map_err(|_| JsValue::from_str("Failed to parse signature"))?;
+fn read_address(bytes: &[u8]) -> Result<[u8; 20], JsValue> {
+    if bytes.len() != 20 {
+        return Err(JsValue::from_str("Address must be 20 bytes long"));
+    }
+    let mut address = [0; 20];
+    address.copy_from_slice(bytes);
+    Ok(address)
+}
 
-    Ok(Signature { r, s })
+fn read_amount(amount: &str) -> Result<u128, JsValue> {
+    amount
+        .parse()
+        .map_err(|_| JsValue::from_str("Amount must be a decimal number less than 2^128"))
 }
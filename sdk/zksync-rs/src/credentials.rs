@@ -5,6 +5,9 @@ use zksync_crypto::PrivateKey;
 use zksync_eth_signer::{EthereumSigner, PrivateKeySigner};
 use zksync_types::{network::Network, tx::TxEthSignature};
 
+#[cfg(feature = "ethers")]
+use zksync_eth_signer::EthersSigner;
+
 pub struct WalletCredentials<S: EthereumSigner> {
     pub(crate) eth_signer: Option<S>,
     pub(crate) eth_address: Address,
@@ -116,3 +119,19 @@ impl<S: EthereumSigner> WalletCredentials<S> {
         }
     }
 }
+
+#[cfg(feature = "ethers")]
+impl<S: ethers_signers::Signer + 'static> WalletCredentials<EthersSigner<S>> {
+    /// Creates wallet credentials from the provided `ethers` signer (e.g. a local wallet,
+    /// Ledger or AWS KMS signer), which is used for all the Ethereum signatures.
+    /// The address of the Ethereum wallet is taken from the signer.
+    ///
+    /// ## Arguments
+    ///
+    /// - `eth_signer`: `ethers` signer that signs messages and transactions.
+    /// - `network`: Network this wallet is used on.
+    pub async fn from_ethers_signer(eth_signer: S, network: Network) -> Result<Self, ClientError> {
+        let eth_address = Address::from_slice(eth_signer.address().as_bytes());
+        Self::from_eth_signer(eth_address, EthersSigner::new(eth_signer), network).await
+    }
+}
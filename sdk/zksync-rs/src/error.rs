@@ -1,17 +1,17 @@
 pub use jsonrpc_core::types::response::Failure as RpcFailure;
+use jsonrpc_core::ErrorCode;
 use thiserror::Error;
+use web3::types::{Address, H256};
 use zksync_eth_signer::error::SignerError;
 
 #[derive(Debug, Error, PartialEq)]
 pub enum ClientError {
     #[error("Network '{0}' is not supported")]
     NetworkNotSupported(String),
-    #[error("Unable to decode server response: {0}")]
-    MalformedResponse(String),
-    #[error("RPC error: {0:?}")]
-    RpcError(RpcFailure),
-    #[error("Network error: {0}")]
-    NetworkError(String),
+    #[error("Transport error: {0}")]
+    TransportError(TransportError),
+    #[error("Server error: {0}")]
+    ServerError(ServerError),
 
     #[error("Provided account credentials are incorrect")]
     IncorrectCredentials,
@@ -34,13 +34,213 @@ pub enum ClientError {
 
     #[error("Ethereum private key was not provided for this wallet")]
     NoEthereumPrivateKey,
+    #[error("Ethereum transaction {0:?} failed")]
+    EthereumTxFailed(H256),
+    #[error("Server uses the zkSync contract {actual:?} instead of the expected {expected:?}")]
+    ContractAddressMismatch { expected: Address, actual: Address },
 
     #[error("Provided value is not packable")]
     NotPackableValue,
+    #[error("Incorrect amount: {0}")]
+    IncorrectAmount(String),
 
     #[error("Provided function arguments are incorrect")]
     IncorrectInput,
+    #[error("Account balance is not enough to pay for the transaction and its fee")]
+    InsufficientBalance,
+    #[error("Target account has the signing key set, so it can't be forced to exit")]
+    TargetSigningKeySet,
 
     #[error("Other")]
     Other,
 }
+
+/// Category of the `ClientError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The request didn't reach the server or its response couldn't be received.
+    Transport,
+    /// The server processed the request and responded with an error.
+    Server,
+    /// The transaction couldn't be signed.
+    Signing,
+    /// The provided arguments are incorrect, the request wasn't sent.
+    Validation,
+    Other,
+}
+
+impl ClientError {
+    /// Returns the category of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::TransportError(_) | Self::OperationTimeout => ErrorKind::Transport,
+            Self::ServerError(_) => ErrorKind::Server,
+            Self::SigningError(_) => ErrorKind::Signing,
+            Self::NetworkNotSupported(_)
+            | Self::IncorrectCredentials
+            | Self::SeedTooShort
+            | Self::UnknownToken
+            | Self::IncorrectAddress
+            | Self::PollingIntervalIsTooSmall
+            | Self::MissingRequiredField(_)
+            | Self::NoEthereumPrivateKey
+            | Self::NotPackableValue
+            | Self::IncorrectAmount(_)
+            | Self::IncorrectInput
+            | Self::InsufficientBalance
+            | Self::TargetSigningKeySet
+            | Self::ContractAddressMismatch { .. } => ErrorKind::Validation,
+            Self::EthereumTxFailed(_) | Self::Other => ErrorKind::Other,
+        }
+    }
+
+    /// Returns `true` if the error is transient, so the request may succeed if repeated.
+    ///
+    /// Note that the retry is always safe only for the read requests: a timed out
+    /// or failed on the server side transaction submission may still be accepted.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::TransportError(err) => err.is_retryable(),
+            Self::ServerError(err) => err.code.is_retryable(),
+            Self::OperationTimeout => true,
+            _ => false,
+        }
+    }
+}
+
+impl From<TransportError> for ClientError {
+    fn from(err: TransportError) -> Self {
+        Self::TransportError(err)
+    }
+}
+
+impl From<ServerError> for ClientError {
+    fn from(err: ServerError) -> Self {
+        Self::ServerError(err)
+    }
+}
+
+impl From<RpcFailure> for ClientError {
+    fn from(failure: RpcFailure) -> Self {
+        Self::ServerError(failure.error.into())
+    }
+}
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum TransportError {
+    /// The request couldn't be sent, e.g. the connection is refused.
+    #[error("Request failed: {0}")]
+    RequestFailed(String),
+    #[error("Server responded with a non-OK HTTP status: {0}")]
+    HttpStatus(u16),
+    #[error("Unable to decode server response: {0}")]
+    MalformedResponse(String),
+}
+
+impl TransportError {
+    /// Returns `true` if the request most likely haven't been processed and can be repeated.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RequestFailed(_) => true,
+            // Server is overloaded or temporarily unavailable.
+            Self::HttpStatus(status) => *status == 429 || *status >= 500,
+            Self::MalformedResponse(_) => false,
+        }
+    }
+}
+
+/// Error returned by the server in response to the JSON RPC request.
+#[derive(Debug, Error, Clone, PartialEq)]
+#[error("{message} ({code:?})")]
+pub struct ServerError {
+    pub code: ServerErrorCode,
+    pub message: String,
+    pub data: Option<serde_json::Value>,
+}
+
+impl From<jsonrpc_core::Error> for ServerError {
+    fn from(err: jsonrpc_core::Error) -> Self {
+        Self {
+            code: err.code.into(),
+            message: err.message,
+            data: err.data,
+        }
+    }
+}
+
+/// Error codes of the zkSync JSON RPC server along with the standard JSON RPC ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerErrorCode {
+    ParseError,
+    InvalidRequest,
+    MethodNotFound,
+    InvalidParams,
+    InternalError,
+
+    NonceMismatch,
+    IncorrectTx,
+    FeeTooLow,
+    InappropriateFeeToken,
+    TokenBlacklisted,
+    /// The server is shutting down and doesn't accept new transactions.
+    ServerShuttingDown,
+    /// Acceptance of the new transactions is paused by the operator.
+    TxAcceptancePaused,
+
+    MissingEthSignature,
+    EIP1271SignatureVerificationFail,
+    IncorrectEthSignature,
+    ChangePkNotAuthorized,
+
+    /// Error of the communication between the server components.
+    Other,
+    AccountCloseDisabled,
+    OperationsLimitReached,
+    UnsupportedFastProcessing,
+    Toggle2FA,
+
+    /// The code is not known to this version of the SDK.
+    Unknown(i64),
+}
+
+impl ServerErrorCode {
+    /// Returns `true` if the error is caused by a temporary server malfunction
+    /// rather than by the request itself.
+    pub fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            Self::InternalError | Self::Other | Self::ServerShuttingDown | Self::TxAcceptancePaused
+        )
+    }
+}
+
+impl From<ErrorCode> for ServerErrorCode {
+    fn from(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::ParseError => Self::ParseError,
+            ErrorCode::InvalidRequest => Self::InvalidRequest,
+            ErrorCode::MethodNotFound => Self::MethodNotFound,
+            ErrorCode::InvalidParams => Self::InvalidParams,
+            ErrorCode::InternalError => Self::InternalError,
+            ErrorCode::ServerError(code) => match code {
+                101 => Self::NonceMismatch,
+                103 => Self::IncorrectTx,
+                104 => Self::FeeTooLow,
+                105 => Self::InappropriateFeeToken,
+                106 => Self::TokenBlacklisted,
+                107 => Self::ServerShuttingDown,
+                108 => Self::TxAcceptancePaused,
+                200 => Self::MissingEthSignature,
+                201 => Self::EIP1271SignatureVerificationFail,
+                202 => Self::IncorrectEthSignature,
+                203 => Self::ChangePkNotAuthorized,
+                300 => Self::Other,
+                301 => Self::AccountCloseDisabled,
+                302 => Self::OperationsLimitReached,
+                303 => Self::UnsupportedFastProcessing,
+                304 => Self::Toggle2FA,
+                code => Self::Unknown(code),
+            },
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! This module contains the handler for sent deposits, tracking them from the Ethereum
+//! transaction to the execution in zkSync.
+
+use std::time::{Duration, Instant};
+
+use web3::types::H256;
+use zksync_eth_signer::EthereumSigner;
+use zksync_types::{Address, PriorityOpId, Token};
+
+use crate::{
+    error::{ClientError, TransportError},
+    ethereum::{EthereumProvider, PriorityOpHolder},
+    provider::Provider,
+    types::BlockInfo,
+};
+
+/// Progress of the deposit. The deposit goes through the statuses in the order of declaration,
+/// unless its Ethereum transaction fails.
+#[derive(Debug, Clone)]
+pub enum DepositStatus {
+    /// The Ethereum transaction is not mined yet.
+    Pending,
+    /// The Ethereum transaction is reverted, the deposit will never be processed.
+    Failed,
+    /// The Ethereum transaction is mined, but zkSync hasn't detected the deposit yet:
+    /// the server waits for the required number of block confirmations first.
+    Mined {
+        serial_id: PriorityOpId,
+        eth_block: u64,
+        confirmations: u64,
+    },
+    /// zkSync has detected the deposit, it will be executed once the Ethereum network
+    /// reaches the expected block.
+    Detected {
+        serial_id: PriorityOpId,
+        expected_accept_block: u64,
+    },
+    /// The deposit is executed in the zkSync block, which is committed and maybe verified.
+    Executed {
+        serial_id: PriorityOpId,
+        block: BlockInfo,
+    },
+}
+
+impl DepositStatus {
+    /// Returns the serial ID of the deposit priority operation, known since the transaction is mined.
+    pub fn serial_id(&self) -> Option<PriorityOpId> {
+        match self {
+            Self::Pending | Self::Failed => None,
+            Self::Mined { serial_id, .. }
+            | Self::Detected { serial_id, .. }
+            | Self::Executed { serial_id, .. } => Some(*serial_id),
+        }
+    }
+
+    fn is_mined(&self) -> bool {
+        !matches!(self, Self::Pending)
+    }
+
+    fn is_detected(&self) -> bool {
+        matches!(
+            self,
+            Self::Failed | Self::Detected { .. } | Self::Executed { .. }
+        )
+    }
+
+    fn is_committed(&self) -> bool {
+        match self {
+            Self::Failed => true,
+            Self::Executed { block, .. } => block.committed,
+            _ => false,
+        }
+    }
+
+    fn is_verified(&self) -> bool {
+        match self {
+            Self::Failed => true,
+            Self::Executed { block, .. } => block.verified,
+            _ => false,
+        }
+    }
+}
+
+/// Handle for the deposit, providing an interface to track its progress.
+/// For obtained handle it's possible to set the polling interval and timeout values.
+///
+/// The detection of the deposit is checked via the depositing balances of the recipient account,
+/// so the handle can't distinguish several simultaneous deposits of the same token to the account.
+///
+/// By default, awaiting for deposit may run up to forever, and the polling is
+/// performed once a second.
+#[derive(Debug)]
+pub struct DepositHandle<'a, S: EthereumSigner, P: Provider> {
+    eth_tx_hash: H256,
+    token: Token,
+    to: Address,
+    ethereum: &'a EthereumProvider<S>,
+    provider: P,
+    polling_interval: Duration,
+    timeout: Option<Duration>,
+}
+
+impl<'a, S: EthereumSigner, P: Provider> DepositHandle<'a, S, P> {
+    pub fn new(
+        eth_tx_hash: H256,
+        token: Token,
+        to: Address,
+        ethereum: &'a EthereumProvider<S>,
+        provider: P,
+    ) -> Self {
+        Self {
+            eth_tx_hash,
+            token,
+            to,
+            ethereum,
+            provider,
+            polling_interval: Duration::from_secs(1), // 1 second.
+            timeout: None,                            // Wait until forever
+        }
+    }
+
+    const MIN_POLLING_INTERVAL: Duration = Duration::from_millis(200);
+
+    /// Sets the polling interval. Must be at least 200 milliseconds.
+    pub fn polling_interval(&mut self, polling_interval: Duration) -> Result<(), ClientError> {
+        if polling_interval >= Self::MIN_POLLING_INTERVAL {
+            self.polling_interval = polling_interval;
+            Ok(())
+        } else {
+            Err(ClientError::PollingIntervalIsTooSmall)
+        }
+    }
+
+    /// Returns the hash of the deposit Ethereum transaction.
+    pub fn eth_tx_hash(&self) -> H256 {
+        self.eth_tx_hash
+    }
+
+    /// Sets the timeout for each of the `wait_for_*` methods.
+    /// With this value set, they will return a `ClientError::OperationTimeout` error
+    /// if the deposit doesn't reach the awaited status within provided time range.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the current status of the deposit.
+    pub async fn status(&self) -> Result<DepositStatus, ClientError> {
+        let client = self.ethereum.client();
+        let receipt = client
+            .tx_receipt(self.eth_tx_hash)
+            .await
+            .map_err(|err| TransportError::RequestFailed(err.to_string()))?;
+        let receipt = match receipt {
+            Some(receipt) => receipt,
+            None => return Ok(DepositStatus::Pending),
+        };
+        if receipt.status == Some(0.into()) {
+            return Ok(DepositStatus::Failed);
+        }
+
+        let serial_id = receipt
+            .priority_op()
+            .map(|op| PriorityOpId(op.serial_id))
+            .ok_or_else(|| {
+                TransportError::MalformedResponse(
+                    "Deposit transaction receipt has no priority operation".to_string(),
+                )
+            })?;
+
+        let ethop_info = self.provider.ethop_info(*serial_id as u32).await?;
+        if let (true, Some(block)) = (ethop_info.executed, ethop_info.block) {
+            return Ok(DepositStatus::Executed { serial_id, block });
+        }
+
+        let account_info = self.provider.account_info(self.to).await?;
+        if let Some(depositing) = account_info.depositing.balances.get(&self.token.symbol) {
+            return Ok(DepositStatus::Detected {
+                serial_id,
+                expected_accept_block: depositing.expected_accept_block,
+            });
+        }
+
+        let eth_block = receipt.block_number.unwrap_or_default().as_u64();
+        let current_block = client
+            .block_number()
+            .await
+            .map_err(|err| TransportError::RequestFailed(err.to_string()))?
+            .as_u64();
+        Ok(DepositStatus::Mined {
+            serial_id,
+            eth_block,
+            confirmations: (current_block + 1).saturating_sub(eth_block),
+        })
+    }
+
+    /// Awaits for the Ethereum transaction to be mined.
+    pub async fn wait_for_mined(&self) -> Result<DepositStatus, ClientError> {
+        self.wait_for(DepositStatus::is_mined).await
+    }
+
+    /// Awaits for the deposit to be detected by zkSync. Returns if the transaction fails.
+    pub async fn wait_for_detection(&self) -> Result<DepositStatus, ClientError> {
+        self.wait_for(DepositStatus::is_detected).await
+    }
+
+    /// Awaits for the deposit to be executed in the committed block. Returns if the transaction fails.
+    pub async fn wait_for_commit(&self) -> Result<DepositStatus, ClientError> {
+        self.wait_for(DepositStatus::is_committed).await
+    }
+
+    /// Awaits for the deposit to be executed in the verified block. Returns if the transaction fails.
+    pub async fn wait_for_verify(&self) -> Result<DepositStatus, ClientError> {
+        self.wait_for(DepositStatus::is_verified).await
+    }
+
+    /// Awaits for the deposit to reach given status.
+    async fn wait_for<WaitPredicate>(
+        &self,
+        pred: WaitPredicate,
+    ) -> Result<DepositStatus, ClientError>
+    where
+        WaitPredicate: Fn(&DepositStatus) -> bool,
+    {
+        let mut timer = tokio::time::interval(self.polling_interval);
+        let start = Instant::now();
+
+        loop {
+            timer.tick().await;
+
+            if let Some(timeout) = self.timeout {
+                if start.elapsed() >= timeout {
+                    return Err(ClientError::OperationTimeout);
+                }
+            }
+
+            let status = self.status().await?;
+            if pred(&status) {
+                return Ok(status);
+            }
+        }
+    }
+}
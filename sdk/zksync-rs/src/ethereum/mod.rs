@@ -6,25 +6,39 @@ use std::{convert::TryFrom, time::Duration};
 use std::{str::FromStr, time::Instant};
 use web3::contract::tokens::Tokenize;
 use web3::contract::Options;
+use web3::signing::keccak256;
 use web3::transports::Http;
 use web3::types::{TransactionReceipt, H160, H256, U256};
 
 use zksync_eth_client::ETHDirectClient;
-use zksync_eth_signer::EthereumSigner;
-use zksync_types::{AccountId, Address, PriorityOp, PriorityOpId, TokenId, TokenLike};
+use zksync_eth_signer::{error::SignerError, EthereumSigner};
+use zksync_types::{
+    AccountId, Address, Nonce, PriorityOp, PriorityOpId, PubKeyHash, TokenId, TokenLike,
+};
 
 use crate::{
-    error::ClientError, provider::Provider, tokens_cache::TokensCache, utils::u256_to_biguint,
+    error::{ClientError, TransportError},
+    network_config::main_contract_address,
+    provider::Provider,
+    tokens_cache::TokensCache,
+    utils::u256_to_biguint,
 };
 
-pub use self::priority_op_handle::PriorityOpHandle;
+pub use self::{
+    deposit_handle::{DepositHandle, DepositStatus},
+    priority_op_handle::PriorityOpHandle,
+};
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
 
+mod deposit_handle;
 mod priority_op_handle;
 
 const IERC20_INTERFACE: &str = include_str!("abi/IERC20.json");
+const IERC20_PERMIT_INTERFACE: &str = include_str!("abi/IERC20Permit.json");
 const ZKSYNC_INTERFACE: &str = include_str!("abi/ZkSync.json");
 const RAW_ERC20_DEPOSIT_GAS_LIMIT: &str = include_str!("DepositERC20GasLimit.json");
+/// Additional gas required to check the permit of the deposited token.
+const PERMIT_GAS_LIMIT_OVERHEAD: u64 = 100_000;
 
 fn load_contract(raw_abi_string: &str) -> ethabi::Contract {
     let abi_string = serde_json::Value::from_str(raw_abi_string)
@@ -45,6 +59,11 @@ pub fn ierc20_contract() -> ethabi::Contract {
     load_contract(IERC20_INTERFACE)
 }
 
+/// Returns `ethabi::Contract` object for EIP-2612 permit extension of the ERC-20 smart contract interface.
+pub fn ierc20_permit_contract() -> ethabi::Contract {
+    load_contract(IERC20_PERMIT_INTERFACE)
+}
+
 /// `EthereumProvider` gains access to on-chain operations, such as deposits and full exits.
 /// Methods to interact with Ethereum return corresponding Ethereum transaction hash.
 /// In order to monitor transaction execution, an Ethereum node `web3` API is exposed
@@ -54,6 +73,7 @@ pub struct EthereumProvider<S: EthereumSigner> {
     tokens_cache: TokensCache,
     eth_client: ETHDirectClient<S>,
     erc20_abi: ethabi::Contract,
+    erc20_permit_abi: ethabi::Contract,
     confirmation_timeout: Duration,
     poll_time: Duration,
 }
@@ -67,35 +87,30 @@ impl<S: EthereumSigner> EthereumProvider<S> {
         eth_signer: S,
         eth_addr: H160,
     ) -> Result<Self, ClientError> {
-        let transport = Http::new(eth_web3_url.as_ref())
-            .map_err(|err| ClientError::NetworkError(err.to_string()))?;
+        let transport = Http::new(eth_web3_url.as_ref()).map_err(|err| {
+            ClientError::TransportError(TransportError::RequestFailed(err.to_string()))
+        })?;
 
         let network = provider.network();
 
-        let address_response = provider.contract_address().await?;
-        let contract_address =
-            if let Some(main_contract) = address_response.main_contract.strip_prefix("0x") {
-                main_contract
-            } else {
-                &address_response.main_contract
-            };
+        let contract_address = main_contract_address(&provider.contract_address().await?)?;
 
         let eth_client = ETHDirectClient::new(
             transport,
             zksync_contract(),
             eth_addr,
             eth_signer,
-            contract_address
-                .parse()
-                .map_err(|err| ClientError::MalformedResponse(format!("{}", err)))?,
+            contract_address,
             network.chain_id(),
             1.5f64,
         );
         let erc20_abi = ierc20_contract();
+        let erc20_permit_abi = ierc20_permit_contract();
 
         Ok(Self {
             eth_client,
             erc20_abi,
+            erc20_permit_abi,
             tokens_cache,
             confirmation_timeout: Duration::from_secs(30),
             poll_time: Duration::from_millis(1000),
@@ -117,7 +132,9 @@ impl<S: EthereumSigner> EthereumProvider<S> {
         self.client()
             .sender_eth_balance()
             .await
-            .map_err(|err| ClientError::NetworkError(err.to_string()))
+            .map_err(|err| {
+                ClientError::TransportError(TransportError::RequestFailed(err.to_string()))
+            })
             .map(u256_to_biguint)
     }
 
@@ -144,16 +161,17 @@ impl<S: EthereumSigner> EthereumProvider<S> {
                 self.erc20_abi.clone(),
             )
             .await
-            .map_err(|err| ClientError::NetworkError(err.to_string()))?;
+            .map_err(|err| {
+                ClientError::TransportError(TransportError::RequestFailed(err.to_string()))
+            })?;
         Ok(res)
     }
 
     /// Returns the pending nonce for the Ethereum account.
     pub async fn nonce(&self) -> Result<U256, ClientError> {
-        self.client()
-            .pending_nonce()
-            .await
-            .map_err(|err| ClientError::NetworkError(err.to_string()))
+        self.client().pending_nonce().await.map_err(|err| {
+            ClientError::TransportError(TransportError::RequestFailed(err.to_string()))
+        })
     }
 
     /// Checks whether ERC20 of a certain token deposit is approved for account.
@@ -181,7 +199,9 @@ impl<S: EthereumSigner> EthereumProvider<S> {
             .client()
             .allowance(token.address, self.erc20_abi.clone())
             .await
-            .map_err(|err| ClientError::NetworkError(err.to_string()))?;
+            .map_err(|err| {
+                ClientError::TransportError(TransportError::RequestFailed(err.to_string()))
+            })?;
 
         Ok(current_allowance >= erc20_approve_threshold)
     }
@@ -230,11 +250,13 @@ impl<S: EthereumSigner> EthereumProvider<S> {
             .await
             .map_err(|_| ClientError::IncorrectCredentials)?;
 
-        let transaction_hash = self
-            .client()
-            .send_raw_tx(signed_tx.raw_tx)
-            .await
-            .map_err(|err| ClientError::NetworkError(err.to_string()))?;
+        let transaction_hash =
+            self.client()
+                .send_raw_tx(signed_tx.raw_tx)
+                .await
+                .map_err(|err| {
+                    ClientError::TransportError(TransportError::RequestFailed(err.to_string()))
+                })?;
 
         Ok(transaction_hash)
     }
@@ -286,11 +308,13 @@ impl<S: EthereumSigner> EthereumProvider<S> {
                 .map_err(|_| ClientError::IncorrectCredentials)?
         };
 
-        let transaction_hash = self
-            .client()
-            .send_raw_tx(signed_tx.raw_tx)
-            .await
-            .map_err(|err| ClientError::NetworkError(err.to_string()))?;
+        let transaction_hash =
+            self.client()
+                .send_raw_tx(signed_tx.raw_tx)
+                .await
+                .map_err(|err| {
+                    ClientError::TransportError(TransportError::RequestFailed(err.to_string()))
+                })?;
 
         Ok(transaction_hash)
     }
@@ -340,13 +364,16 @@ impl<S: EthereumSigner> EthereumProvider<S> {
             .eth_client
             .send_raw_tx(signed_tx.raw_tx)
             .await
-            .map_err(|err| ClientError::NetworkError(err.to_string()))?;
+            .map_err(|err| {
+                ClientError::TransportError(TransportError::RequestFailed(err.to_string()))
+            })?;
 
         Ok(transaction_hash)
     }
 
     /// Performs a deposit in zkSync network.
     /// For ERC20 tokens, a deposit must be approved beforehand via the `EthereumProvider::approve_erc20_token_deposits` method.
+    /// The progress of the deposit can be tracked via the `EthereumProvider::track_deposit` method.
     pub async fn deposit(
         &self,
         token: impl Into<TokenLike>,
@@ -372,21 +399,8 @@ impl<S: EthereumSigner> EthereumProvider<S> {
                 .await
                 .map_err(|_| ClientError::IncorrectCredentials)?
         } else {
-            let gas_limits: Map<String, Value> = serde_json::from_str(RAW_ERC20_DEPOSIT_GAS_LIMIT)
-                .map_err(|_| ClientError::Other)?;
-            let address_str = format!("{:?}", token_info.address);
-            let is_mainnet = self.client().chain_id().0 == 1;
-            let gas_limit = if is_mainnet && gas_limits.contains_key(&address_str) {
-                gas_limits
-                    .get(&address_str)
-                    .unwrap()
-                    .as_u64()
-                    .ok_or(ClientError::Other)?
-            } else {
-                300000u64
-            };
             let options = Options {
-                gas: Some(gas_limit.into()),
+                gas: Some(self.erc20_deposit_gas_limit(token_info.address)?.into()),
                 ..Default::default()
             };
             let params = (token_info.address, amount, sync_address);
@@ -398,15 +412,158 @@ impl<S: EthereumSigner> EthereumProvider<S> {
                 .map_err(|_| ClientError::IncorrectCredentials)?
         };
 
-        let transaction_hash = self
+        let transaction_hash =
+            self.client()
+                .send_raw_tx(signed_tx.raw_tx)
+                .await
+                .map_err(|err| {
+                    ClientError::TransportError(TransportError::RequestFailed(err.to_string()))
+                })?;
+
+        Ok(transaction_hash)
+    }
+
+    /// Performs a deposit of the ERC20 token supporting [EIP-2612](https://eips.ethereum.org/EIPS/eip-2612) permits
+    /// in zkSync network. Unlike `EthereumProvider::deposit`, the deposit doesn't have to be approved beforehand:
+    /// the approval is signed as a permit valid until `deadline` and checked within the same transaction.
+    pub async fn deposit_with_permit(
+        &self,
+        token: impl Into<TokenLike>,
+        amount: U256,
+        sync_address: H160,
+        deadline: U256,
+    ) -> Result<H256, ClientError> {
+        let token = token.into();
+        let token_info = self
+            .tokens_cache
+            .resolve(token.clone())
+            .ok_or(ClientError::UnknownToken)?;
+        if self.tokens_cache.is_eth(token) {
+            // ETH deposits don't need an approval.
+            return Err(ClientError::IncorrectInput);
+        }
+
+        let (_, permit_signature) = self
             .client()
-            .send_raw_tx(signed_tx.raw_tx)
+            .sign_erc20_permit(
+                token_info.address,
+                self.erc20_permit_abi.clone(),
+                amount,
+                deadline,
+            )
+            .await
+            .map_err(|err| {
+                ClientError::SigningError(SignerError::SigningFailed(err.to_string()))
+            })?;
+        let packed_signature = permit_signature.serialize_packed();
+        let r = H256::from_slice(&packed_signature[0..32]);
+        let s = H256::from_slice(&packed_signature[32..64]);
+        let v = U256::from(packed_signature[64]);
+
+        let options = Options {
+            gas: Some(
+                (self.erc20_deposit_gas_limit(token_info.address)? + PERMIT_GAS_LIMIT_OVERHEAD)
+                    .into(),
+            ),
+            ..Default::default()
+        };
+        let params = (token_info.address, amount, sync_address, deadline, v, r, s);
+        let data = self
+            .client()
+            .encode_tx_data("depositERC20WithPermit", params);
+        let signed_tx = self
+            .client()
+            .sign_prepared_tx(data, options)
+            .await
+            .map_err(|_| ClientError::IncorrectCredentials)?;
+
+        let transaction_hash =
+            self.client()
+                .send_raw_tx(signed_tx.raw_tx)
+                .await
+                .map_err(|err| {
+                    ClientError::TransportError(TransportError::RequestFailed(err.to_string()))
+                })?;
+
+        Ok(transaction_hash)
+    }
+
+    /// Returns the gas limit of the ERC20 token deposit.
+    fn erc20_deposit_gas_limit(&self, token_address: Address) -> Result<u64, ClientError> {
+        let gas_limits: Map<String, Value> =
+            serde_json::from_str(RAW_ERC20_DEPOSIT_GAS_LIMIT).map_err(|_| ClientError::Other)?;
+        let address_str = format!("{:?}", token_address);
+        let is_mainnet = self.client().chain_id().0 == 1;
+        if is_mainnet && gas_limits.contains_key(&address_str) {
+            gas_limits
+                .get(&address_str)
+                .unwrap()
+                .as_u64()
+                .ok_or(ClientError::Other)
+        } else {
+            Ok(300000u64)
+        }
+    }
+
+    /// Authorizes the new zkSync public key hash of the account for the `ChangePubKey` transaction
+    /// with the given nonce by the zkSync contract call. Unlike the authorization by the signature,
+    /// is suitable for the accounts which can't sign messages, e.g. smart contract wallets.
+    pub async fn set_auth_pubkey_hash(
+        &self,
+        pub_key_hash: &PubKeyHash,
+        nonce: Nonce,
+    ) -> Result<H256, ClientError> {
+        let options = Options {
+            gas: Some(200_000.into()),
+            ..Default::default()
+        };
+
+        let data = self.client().encode_tx_data(
+            "setAuthPubkeyHash",
+            (pub_key_hash.data.to_vec(), u64::from(*nonce)),
+        );
+        let signed_tx = self
+            .client()
+            .sign_prepared_tx(data, options)
             .await
-            .map_err(|err| ClientError::NetworkError(err.to_string()))?;
+            .map_err(|_| ClientError::IncorrectCredentials)?;
+
+        let transaction_hash =
+            self.client()
+                .send_raw_tx(signed_tx.raw_tx)
+                .await
+                .map_err(|err| {
+                    ClientError::TransportError(TransportError::RequestFailed(err.to_string()))
+                })?;
 
         Ok(transaction_hash)
     }
 
+    /// Checks whether the zkSync public key hash is authorized onchain for the `ChangePubKey`
+    /// transaction of the account with the given nonce.
+    pub async fn is_pubkey_hash_authorized(
+        &self,
+        address: Address,
+        pub_key_hash: &PubKeyHash,
+        nonce: Nonce,
+    ) -> Result<bool, ClientError> {
+        let auth_fact: H256 = self
+            .client()
+            .call_main_contract_function(
+                "authFacts",
+                (address, u64::from(*nonce)),
+                None,
+                Options::default(),
+                None,
+            )
+            .await
+            .map_err(|err| {
+                ClientError::TransportError(TransportError::RequestFailed(err.to_string()))
+            })?;
+
+        Ok(auth_fact.as_bytes() == keccak256(&pub_key_hash.data))
+    }
+
     /// Performs a full exit for a certain token.
     pub async fn full_exit(
         &self,
@@ -434,11 +591,13 @@ impl<S: EthereumSigner> EthereumProvider<S> {
             .await
             .map_err(|_| ClientError::IncorrectCredentials)?;
 
-        let transaction_hash = self
-            .client()
-            .send_raw_tx(signed_tx.raw_tx)
-            .await
-            .map_err(|err| ClientError::NetworkError(err.to_string()))?;
+        let transaction_hash =
+            self.client()
+                .send_raw_tx(signed_tx.raw_tx)
+                .await
+                .map_err(|err| {
+                    ClientError::TransportError(TransportError::RequestFailed(err.to_string()))
+                })?;
 
         Ok(transaction_hash)
     }
@@ -471,11 +630,36 @@ impl<S: EthereumSigner> EthereumProvider<S> {
             .eth_client
             .send_raw_tx(signed_tx.raw_tx)
             .await
-            .map_err(|err| ClientError::NetworkError(err.to_string()))?;
+            .map_err(|err| {
+                ClientError::TransportError(TransportError::RequestFailed(err.to_string()))
+            })?;
 
         Ok(transaction_hash)
     }
 
+    /// Returns the handle tracking the deposit sent by the Ethereum transaction with the given hash.
+    /// The token and the recipient must be the same as of the deposit.
+    pub fn track_deposit<P: Provider>(
+        &self,
+        eth_tx_hash: H256,
+        token: impl Into<TokenLike>,
+        sync_address: H160,
+        provider: P,
+    ) -> Result<DepositHandle<'_, S, P>, ClientError> {
+        let token = self
+            .tokens_cache
+            .resolve(token.into())
+            .ok_or(ClientError::UnknownToken)?;
+
+        Ok(DepositHandle::new(
+            eth_tx_hash,
+            token,
+            sync_address,
+            self,
+            provider,
+        ))
+    }
+
     /// Sets the timeout to wait for transactions to appear in the Ethereum network.
     /// By default it is set to 30 seconds.
     pub fn set_confirmation_timeout(&mut self, timeout: Duration) {
@@ -494,12 +678,9 @@ impl<S: EthereumSigner> EthereumProvider<S> {
 
         let start = Instant::now();
         loop {
-            if let Some(receipt) = self
-                .client()
-                .tx_receipt(tx_hash)
-                .await
-                .map_err(|err| ClientError::NetworkError(err.to_string()))?
-            {
+            if let Some(receipt) = self.client().tx_receipt(tx_hash).await.map_err(|err| {
+                ClientError::TransportError(TransportError::RequestFailed(err.to_string()))
+            })? {
                 return Ok(receipt);
             }
 
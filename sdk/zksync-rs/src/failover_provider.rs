@@ -0,0 +1,455 @@
+//! Provider switching between several zkSync servers, e.g. between the redundant API clusters.
+//!
+//! The requests are sent to the active endpoint. Once it fails, the request is repeated
+//! on the other endpoints, and the first one that succeeded becomes active (the errors
+//! which are retried are the same as for the `RetryProvider`).
+//!
+//! Besides, the endpoints are periodically health-checked. If the REST API address of
+//! the endpoint is known, its last committed block is requested, and an endpoint lagging
+//! behind the others is considered unhealthy. Otherwise, the endpoint is only checked to respond.
+//! The healthy endpoint going first in the list becomes active, so the provider switches
+//! back to the primary endpoint once it recovers.
+//!
+//! The endpoints are served by the `RpcProvider` by default, other providers can be used
+//! via `FailoverProvider::from_providers`, e.g. to wrap them into the `InstrumentedProvider`.
+
+// Built-in imports
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+// External uses
+use async_trait::async_trait;
+use futures::future::join_all;
+use num::BigUint;
+
+// Workspace uses
+use zksync_types::{
+    network::Network,
+    tx::{PackedEthSignature, TxHash, ZkSyncTx},
+    AccountId, Address, Swap, TokenId, TokenLike, TxFeeTypes,
+};
+
+// Local uses
+use crate::{
+    error::ClientError,
+    provider::{Provider, ResponseResult, RpcProvider},
+    retry_provider::RequestKind,
+    types::*,
+};
+
+/// Addresses of the zkSync server used by the `FailoverProvider`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Endpoint {
+    /// Address of the JSON RPC API.
+    pub rpc_addr: String,
+    /// Address of the REST API v0.2, e.g. `https://api.zksync.io/api/v0.2`.
+    /// Required to check the block height of the server.
+    pub rest_api_addr: Option<String>,
+}
+
+impl Endpoint {
+    pub fn new(rpc_addr: impl Into<String>) -> Self {
+        Self {
+            rpc_addr: rpc_addr.into(),
+            rest_api_addr: None,
+        }
+    }
+
+    /// Sets the address of the REST API v0.2 to check the block height of the server.
+    pub fn with_rest_api(mut self, rest_api_addr: impl Into<String>) -> Self {
+        self.rest_api_addr = Some(rest_api_addr.into());
+        self
+    }
+}
+
+impl From<&str> for Endpoint {
+    fn from(rpc_addr: &str) -> Self {
+        Self::new(rpc_addr)
+    }
+}
+
+impl From<String> for Endpoint {
+    fn from(rpc_addr: String) -> Self {
+        Self::new(rpc_addr)
+    }
+}
+
+/// Parameters of the `FailoverProvider`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailoverConfig {
+    /// Minimal interval between the health checks. The check is performed
+    /// before the request once the interval since the previous one has passed.
+    pub health_check_interval: Duration,
+    /// Endpoint is considered stale if its last committed block is behind the most
+    /// recent one among the endpoints by more than this amount of blocks.
+    pub max_block_lag: u32,
+    /// Upper bound of the delay between the retries of the failed requests on the same
+    /// endpoint, see `RpcProvider::retry_timeout`. Zero by default, i.e. the provider switches
+    /// to another endpoint right away.
+    pub retry_timeout: Duration,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            health_check_interval: Duration::from_secs(30),
+            max_block_lag: 5,
+            retry_timeout: Duration::from_secs(0),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct EndpointState<P> {
+    rpc_addr: String,
+    provider: P,
+    rest_api_addr: Option<String>,
+    healthy: AtomicBool,
+}
+
+#[derive(Debug)]
+struct Inner<P> {
+    endpoints: Vec<EndpointState<P>>,
+    active: AtomicUsize,
+    last_health_check: Mutex<Option<Instant>>,
+    client: reqwest::Client,
+    network: Network,
+    config: FailoverConfig,
+}
+
+/// `FailoverProvider` is capable of interacting with the zkSync node via its JSON RPC
+/// interface, switching between several endpoints in case of errors. The clones share
+/// the state of the endpoints.
+#[derive(Debug)]
+pub struct FailoverProvider<P: Provider = RpcProvider> {
+    inner: Arc<Inner<P>>,
+}
+
+impl<P: Provider> Clone for FailoverProvider<P> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl FailoverProvider {
+    /// Creates a new `FailoverProvider` connected to the endpoints of the desired zkSync network.
+    /// The order of the endpoints defines their priority. Returns an error if no endpoints
+    /// are provided.
+    pub fn new<E: Into<Endpoint>>(
+        endpoints: impl IntoIterator<Item = E>,
+        network: Network,
+    ) -> ResponseResult<Self> {
+        Self::with_config(endpoints, network, FailoverConfig::default())
+    }
+
+    /// Creates a new `FailoverProvider` with the provided `FailoverConfig`.
+    pub fn with_config<E: Into<Endpoint>>(
+        endpoints: impl IntoIterator<Item = E>,
+        network: Network,
+        config: FailoverConfig,
+    ) -> ResponseResult<Self> {
+        let retry_timeout = config.retry_timeout;
+        let endpoints = endpoints.into_iter().map(|endpoint| {
+            let endpoint = endpoint.into();
+            let provider = RpcProvider::from_addr_and_network(endpoint.rpc_addr.clone(), network)
+                .retry_timeout(retry_timeout);
+            (endpoint, provider)
+        });
+        Self::from_providers(endpoints, network, config)
+    }
+}
+
+impl<P: Provider> FailoverProvider<P> {
+    /// Creates a new `FailoverProvider` switching between the providers of the endpoints.
+    /// The providers are used as is, so the `FailoverConfig::retry_timeout` doesn't apply to them.
+    /// Returns an error if no endpoints are provided.
+    pub fn from_providers(
+        endpoints: impl IntoIterator<Item = (Endpoint, P)>,
+        network: Network,
+        config: FailoverConfig,
+    ) -> ResponseResult<Self> {
+        let endpoints: Vec<_> = endpoints
+            .into_iter()
+            .map(|(endpoint, provider)| EndpointState {
+                provider,
+                rpc_addr: endpoint.rpc_addr,
+                rest_api_addr: endpoint.rest_api_addr,
+                healthy: AtomicBool::new(true),
+            })
+            .collect();
+        if endpoints.is_empty() {
+            return Err(ClientError::IncorrectInput);
+        }
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                endpoints,
+                active: AtomicUsize::new(0),
+                last_health_check: Mutex::new(None),
+                client: reqwest::Client::new(),
+                network,
+                config,
+            }),
+        })
+    }
+
+    /// Returns the JSON RPC address of the endpoint the requests are sent to.
+    pub fn active_endpoint(&self) -> &str {
+        let active = self.inner.active.load(Ordering::SeqCst);
+        &self.inner.endpoints[active].rpc_addr
+    }
+
+    /// Checks all the endpoints and switches to the first healthy one.
+    pub async fn health_check(&self) {
+        *self.inner.last_health_check.lock().unwrap() = Some(Instant::now());
+
+        let statuses = join_all(
+            self.inner
+                .endpoints
+                .iter()
+                .map(|endpoint| self.endpoint_status(endpoint)),
+        )
+        .await;
+        let head = statuses.iter().flatten().flatten().max().copied();
+
+        for (endpoint, status) in self.inner.endpoints.iter().zip(&statuses) {
+            let healthy = match (status, head) {
+                (None, _) => false,
+                (Some(Some(block)), Some(head)) => {
+                    block + u64::from(self.inner.config.max_block_lag) >= head
+                }
+                (Some(_), _) => true,
+            };
+            endpoint.healthy.store(healthy, Ordering::SeqCst);
+        }
+
+        let healthy = self
+            .inner
+            .endpoints
+            .iter()
+            .position(|endpoint| endpoint.healthy.load(Ordering::SeqCst));
+        if let Some(idx) = healthy {
+            self.inner.active.store(idx, Ordering::SeqCst);
+        }
+    }
+
+    /// Returns `None` if the endpoint doesn't respond, and the last committed block
+    /// of the endpoint if it's known.
+    async fn endpoint_status(&self, endpoint: &EndpointState<P>) -> Option<Option<u64>> {
+        match &endpoint.rest_api_addr {
+            Some(rest_api_addr) => {
+                let status: serde_json::Value = self
+                    .inner
+                    .client
+                    .get(format!("{}/networkStatus", rest_api_addr))
+                    .send()
+                    .await
+                    .ok()?
+                    .error_for_status()
+                    .ok()?
+                    .json()
+                    .await
+                    .ok()?;
+                let last_committed = status["result"]["lastCommitted"].as_u64()?;
+                Some(Some(last_committed))
+            }
+            None => endpoint
+                .provider
+                .contract_address()
+                .await
+                .ok()
+                .map(|_| None),
+        }
+    }
+
+    async fn health_check_if_needed(&self) {
+        let last_health_check = *self.inner.last_health_check.lock().unwrap();
+        let check_needed = match last_health_check {
+            Some(time) => time.elapsed() >= self.inner.config.health_check_interval,
+            None => true,
+        };
+        if check_needed {
+            self.health_check().await;
+        }
+    }
+
+    /// Sends the request to the active endpoint, then to the other healthy endpoints,
+    /// and then to the unhealthy ones, until it succeeds.
+    async fn request<'a, F, Fut, R>(
+        &'a self,
+        request: RequestKind,
+        mut call: F,
+    ) -> ResponseResult<R>
+    where
+        F: FnMut(&'a P) -> Fut,
+        Fut: Future<Output = ResponseResult<R>>,
+    {
+        self.health_check_if_needed().await;
+
+        let endpoints = &self.inner.endpoints;
+        let active = self.inner.active.load(Ordering::SeqCst);
+        let mut order: Vec<_> = (0..endpoints.len())
+            .map(|offset| (active + offset) % endpoints.len())
+            .collect();
+        // Stable sort keeps the active endpoint first.
+        order.sort_by_key(|&idx| !endpoints[idx].healthy.load(Ordering::SeqCst));
+
+        let mut last_error = None;
+        for idx in order {
+            let endpoint = &endpoints[idx];
+            match call(&endpoint.provider).await {
+                Err(err) if request.should_retry(&err) => {
+                    endpoint.healthy.store(false, Ordering::SeqCst);
+                    last_error = Some(err);
+                }
+                result => {
+                    endpoint.healthy.store(true, Ordering::SeqCst);
+                    self.inner.active.store(idx, Ordering::SeqCst);
+                    return result;
+                }
+            }
+        }
+
+        Err(last_error.expect("There is at least one endpoint"))
+    }
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync> Provider for FailoverProvider<P> {
+    async fn account_info(&self, address: Address) -> ResponseResult<AccountInfo> {
+        self.request(RequestKind::Read, |provider| provider.account_info(address))
+            .await
+    }
+
+    async fn tokens(&self) -> ResponseResult<Tokens> {
+        self.request(RequestKind::Read, |provider| provider.tokens())
+            .await
+    }
+
+    async fn tx_info(&self, tx_hash: TxHash) -> ResponseResult<TransactionInfo> {
+        self.request(RequestKind::Read, |provider| provider.tx_info(tx_hash))
+            .await
+    }
+
+    async fn get_tx_fee(
+        &self,
+        tx_type: TxFeeTypes,
+        address: Address,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> ResponseResult<Fee> {
+        let token = token.into();
+        self.request(RequestKind::Read, |provider| {
+            provider.get_tx_fee(tx_type, address, token.clone())
+        })
+        .await
+    }
+
+    async fn get_txs_batch_fee(
+        &self,
+        tx_types: Vec<TxFeeTypes>,
+        addresses: Vec<Address>,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> ResponseResult<BigUint> {
+        let token = token.into();
+        self.request(RequestKind::Read, |provider| {
+            Provider::get_txs_batch_fee(
+                provider,
+                tx_types.clone(),
+                addresses.clone(),
+                token.clone(),
+            )
+        })
+        .await
+    }
+
+    async fn ethop_info(&self, serial_id: u32) -> ResponseResult<EthOpInfo> {
+        self.request(RequestKind::Read, |provider| {
+            Provider::ethop_info(provider, serial_id)
+        })
+        .await
+    }
+
+    async fn get_eth_tx_for_withdrawal(
+        &self,
+        withdrawal_hash: TxHash,
+    ) -> ResponseResult<Option<String>> {
+        self.request(RequestKind::Read, |provider| {
+            Provider::get_eth_tx_for_withdrawal(provider, withdrawal_hash)
+        })
+        .await
+    }
+
+    async fn contract_address(&self) -> ResponseResult<ContractAddress> {
+        self.request(RequestKind::Read, |provider| provider.contract_address())
+            .await
+    }
+
+    async fn get_nft(&self, token_id: TokenId) -> ResponseResult<Option<NFTInfo>> {
+        self.request(RequestKind::Read, |provider| provider.get_nft(token_id))
+            .await
+    }
+
+    async fn get_nft_owner(&self, token_id: TokenId) -> ResponseResult<Option<AccountId>> {
+        self.request(RequestKind::Read, |provider| {
+            provider.get_nft_owner(token_id)
+        })
+        .await
+    }
+
+    async fn get_nft_id_by_tx_hash(&self, tx_hash: TxHash) -> ResponseResult<Option<TokenId>> {
+        self.request(RequestKind::Read, |provider| {
+            provider.get_nft_id_by_tx_hash(tx_hash)
+        })
+        .await
+    }
+
+    async fn send_tx(
+        &self,
+        tx: ZkSyncTx,
+        eth_signature: Option<PackedEthSignature>,
+    ) -> ResponseResult<TxHash> {
+        self.request(RequestKind::Submit, |provider| {
+            provider.send_tx(tx.clone(), eth_signature.clone())
+        })
+        .await
+    }
+
+    async fn send_txs_batch(
+        &self,
+        txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+        eth_signature: Option<PackedEthSignature>,
+    ) -> ResponseResult<Vec<TxHash>> {
+        self.request(RequestKind::Submit, |provider| {
+            Provider::send_txs_batch(provider, txs_signed.clone(), eth_signature.clone())
+        })
+        .await
+    }
+
+    async fn send_swap(
+        &self,
+        swap: Swap,
+        eth_signature: Option<PackedEthSignature>,
+        orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+    ) -> ResponseResult<TxHash> {
+        self.request(RequestKind::Submit, |provider| {
+            provider.send_swap(
+                swap.clone(),
+                eth_signature.clone(),
+                orders_eth_signatures.clone(),
+            )
+        })
+        .await
+    }
+
+    fn network(&self) -> Network {
+        self.inner.network
+    }
+}
@@ -0,0 +1,256 @@
+//! Provider of the account history via the zkSync REST API.
+//!
+//! The history is paginated: the transactions can be requested page by page,
+//! or as a `Stream` which requests the subsequent pages lazily, once the previous
+//! ones are consumed.
+
+// Built-in imports
+use std::{future::Future, str::FromStr};
+
+// External uses
+use futures::{stream, Stream, TryStreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+
+// Workspace uses
+use zksync_api_client::rest::client::{Client, ClientError as RestError};
+use zksync_api_types::v02::{
+    pagination::{ApiEither, Paginated, PaginationDirection, PaginationQuery, MAX_LIMIT},
+    transaction::{L1Transaction, Transaction, TransactionData, TxHashSerializeWrapper},
+    Response, ResultStatus,
+};
+use zksync_types::{network::Network, tx::TxHash, Address, SerialId};
+
+// Local uses
+use crate::error::{ClientError, ServerError, ServerErrorCode, TransportError};
+
+/// Returns a corresponding REST API address for a provided network name.
+pub fn get_rest_addr(network: Network) -> &'static str {
+    match network {
+        Network::Mainnet => "https://api.zksync.io",
+        Network::Rinkeby => "https://rinkeby-api.zksync.io",
+        Network::Ropsten => "https://ropsten-api.zksync.io",
+        Network::Localhost => "http://127.0.0.1:3001",
+        Network::Unknown => panic!("Attempt to create a provider from an unknown network"),
+        Network::Test => panic!("Attempt to create a provider from an test network"),
+        Network::Goerli => "https://goerli-api.zksync.io",
+    }
+}
+
+fn rest_error(err: RestError) -> ClientError {
+    match err {
+        RestError::BadRequest { http_code, .. } => {
+            TransportError::HttpStatus(http_code.as_u16()).into()
+        }
+        RestError::NotFound(_) => TransportError::HttpStatus(404).into(),
+        RestError::Parse(err) => TransportError::MalformedResponse(err.to_string()).into(),
+        RestError::Other(err) => TransportError::RequestFailed(err.to_string()).into(),
+    }
+}
+
+/// Extracts the result of the request from the REST API response.
+fn response_result<T: DeserializeOwned>(response: Response) -> Result<T, ClientError> {
+    match response.status {
+        ResultStatus::Success => {
+            let result = response.result.ok_or_else(|| {
+                TransportError::MalformedResponse("Response has no result".to_string())
+            })?;
+            serde_json::from_value(result)
+                .map_err(|err| TransportError::MalformedResponse(err.to_string()).into())
+        }
+        ResultStatus::Error => {
+            let error = response.error.unwrap_or_default();
+            // The REST API error codes differ from the JSON RPC ones, so they are not mapped.
+            let code = error["code"].as_i64().unwrap_or_default();
+            let message = error["message"].as_str().unwrap_or_default().to_string();
+
+            Err(ServerError {
+                code: ServerErrorCode::Unknown(code),
+                message,
+                data: Some(error),
+            }
+            .into())
+        }
+    }
+}
+
+/// Returns the serial ID of the pending priority operation, to continue the history from.
+fn serial_id(tx: &Transaction) -> Option<SerialId> {
+    match &tx.op {
+        TransactionData::L1(L1Transaction::Deposit(deposit)) => Some(deposit.id),
+        TransactionData::L1(L1Transaction::FullExit(full_exit)) => Some(full_exit.id),
+        TransactionData::L2(_) => None,
+    }
+}
+
+/// `HistoryProvider` queries the history of the accounts via the zkSync REST API,
+/// complementing the JSON RPC `Provider`.
+///
+/// The streams returned by the provider are not `Unpin`, so they should be pinned
+/// before polling, e.g. with `futures::pin_mut!`.
+#[derive(Debug, Clone)]
+pub struct HistoryProvider {
+    client: Client,
+    page_size: u32,
+}
+
+impl HistoryProvider {
+    /// Creates a new `HistoryProvider` connected to the desired zkSync network.
+    pub fn new(network: Network) -> Self {
+        Self::from_addr(get_rest_addr(network))
+    }
+
+    /// Creates a new `HistoryProvider` with the custom REST API address.
+    pub fn from_addr(rest_addr: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(rest_addr.into()),
+            page_size: MAX_LIMIT,
+        }
+    }
+
+    /// Sets the number of transactions requested at once by the streams.
+    /// The value is clamped to the range of 2 to 100 transactions.
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size.max(2).min(MAX_LIMIT);
+        self
+    }
+
+    /// Requests a page of the account transactions, starting from the transaction with
+    /// the hash `from` (inclusive), or from the latest transaction if it's not provided.
+    pub async fn account_txs_page(
+        &self,
+        address: Address,
+        from: Option<TxHash>,
+        limit: u32,
+        direction: PaginationDirection,
+    ) -> Result<Paginated<Transaction, TxHashSerializeWrapper>, ClientError> {
+        let query = pagination_query(from, limit, direction);
+        let response = self
+            .client
+            .account_txs(&query, &format!("{:?}", address))
+            .await
+            .map_err(rest_error)?;
+
+        response_result(response)
+    }
+
+    /// Requests a page of the account priority operations which are not executed yet,
+    /// starting from the operation with the serial ID `from` (inclusive), or from
+    /// the latest operation if it's not provided.
+    pub async fn account_pending_ops_page(
+        &self,
+        address: Address,
+        from: Option<SerialId>,
+        limit: u32,
+        direction: PaginationDirection,
+    ) -> Result<Paginated<Transaction, SerialId>, ClientError> {
+        let query = pagination_query(from, limit, direction);
+        let response = self
+            .client
+            .account_pending_txs(&query, &format!("{:?}", address))
+            .await
+            .map_err(rest_error)?;
+
+        response_result(response)
+    }
+
+    /// Returns the stream of the account transactions, starting from the transaction with
+    /// the hash `from` (inclusive), or from the latest transaction if it's not provided.
+    pub fn account_txs(
+        &self,
+        address: Address,
+        from: Option<TxHash>,
+        direction: PaginationDirection,
+    ) -> impl Stream<Item = Result<Transaction, ClientError>> + '_ {
+        self.paginate(
+            from,
+            move |from| async move {
+                self.account_txs_page(address, from, self.page_size, direction)
+                    .await
+                    .map(|page| page.list)
+            },
+            |tx| Some(tx.tx_hash),
+        )
+    }
+
+    /// Returns the stream of the account priority operations which are not executed yet,
+    /// starting from the operation with the serial ID `from` (inclusive), or from
+    /// the latest operation if it's not provided.
+    pub fn account_pending_ops(
+        &self,
+        address: Address,
+        from: Option<SerialId>,
+        direction: PaginationDirection,
+    ) -> impl Stream<Item = Result<Transaction, ClientError>> + '_ {
+        self.paginate(
+            from,
+            move |from| async move {
+                self.account_pending_ops_page(address, from, self.page_size, direction)
+                    .await
+                    .map(|page| page.list)
+            },
+            serial_id,
+        )
+    }
+
+    /// Requests the pages one by one, each next page starts from the last item of the
+    /// previous one. Since the `from` bound is inclusive, the repeated item is skipped.
+    fn paginate<'a, Id, F, Fut>(
+        &self,
+        from: Option<Id>,
+        fetch: F,
+        cursor: fn(&Transaction) -> Option<Id>,
+    ) -> impl Stream<Item = Result<Transaction, ClientError>> + 'a
+    where
+        Id: 'a,
+        F: Fn(Option<Id>) -> Fut + 'a,
+        Fut: Future<Output = Result<Vec<Transaction>, ClientError>> + 'a,
+    {
+        let page_size = self.page_size as usize;
+        // State is the start of the next page along with the last returned transaction,
+        // or `None` if the history is over.
+        let initial_state: Option<(Option<Id>, Option<TxHash>)> = Some((from, None));
+
+        stream::try_unfold(initial_state, move |state| {
+            let request = state.map(|(from, last_tx)| (fetch(from), last_tx));
+            async move {
+                let (request, last_tx) = match request {
+                    Some(request) => request,
+                    None => return Ok(None),
+                };
+
+                let mut list = request.await?;
+                let is_last_page = list.len() < page_size;
+                if last_tx.is_some() && list.first().map(|tx| tx.tx_hash) == last_tx {
+                    list.remove(0);
+                }
+
+                let next_state = match list.last() {
+                    Some(tx) if !is_last_page => {
+                        cursor(tx).map(|from| (Some(from), Some(tx.tx_hash)))
+                    }
+                    _ => None,
+                };
+                Ok(Some((list, next_state)))
+            }
+        })
+        .map_ok(|list| stream::iter(list.into_iter().map(Ok::<_, ClientError>)))
+        .try_flatten()
+    }
+}
+
+fn pagination_query<Id: Serialize + FromStr>(
+    from: Option<Id>,
+    limit: u32,
+    direction: PaginationDirection,
+) -> PaginationQuery<ApiEither<Id>> {
+    let from = match from {
+        Some(from) => ApiEither::from(from),
+        None => ApiEither::from_str("latest").expect("`latest` is always parsed"),
+    };
+
+    PaginationQuery {
+        from,
+        limit,
+        direction,
+    }
+}
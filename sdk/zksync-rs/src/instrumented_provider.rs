@@ -0,0 +1,263 @@
+//! Provider wrapper reporting every request of the wrapped provider, so the SDK traffic can be
+//! plugged into the observability stack of the application.
+//!
+//! The finished requests are reported to the `ProviderHook` along with their latency and outcome.
+//! With the `tracing` feature enabled, each request is also wrapped into the `provider_call`
+//! span with the `method` field, and its outcome is emitted as an event within the span.
+
+// Built-in imports
+use std::{
+    fmt,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+// External uses
+use async_trait::async_trait;
+use num::BigUint;
+
+// Workspace uses
+use zksync_types::{
+    network::Network,
+    tx::{PackedEthSignature, TxHash, ZkSyncTx},
+    AccountId, Address, Swap, TokenId, TokenLike, TxFeeTypes,
+};
+
+// Local uses
+use crate::{
+    error::ClientError,
+    provider::{Provider, ResponseResult},
+    types::*,
+};
+
+/// Finished request of the provider.
+#[derive(Debug, Clone, Copy)]
+pub struct ProviderCall<'a> {
+    /// Name of the `Provider` method, e.g. `account_info`.
+    pub method: &'static str,
+    pub latency: Duration,
+    /// Error returned by the request, `None` if it succeeded.
+    pub error: Option<&'a ClientError>,
+}
+
+impl ProviderCall<'_> {
+    /// Returns `true` if the request succeeded.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Callbacks invoked by the `InstrumentedProvider` around every request.
+///
+/// The hook is implemented for the closures accepting `&ProviderCall`, which are invoked
+/// once the request is finished.
+pub trait ProviderHook: Send + Sync {
+    /// Invoked before the request is sent.
+    fn on_request(&self, _method: &'static str) {}
+
+    /// Invoked once the request is finished.
+    fn on_response(&self, call: &ProviderCall<'_>);
+}
+
+impl<F> ProviderHook for F
+where
+    F: Fn(&ProviderCall<'_>) + Send + Sync,
+{
+    fn on_response(&self, call: &ProviderCall<'_>) {
+        self(call)
+    }
+}
+
+/// `InstrumentedProvider` wraps another `Provider` and reports its requests
+/// to the `ProviderHook` and, with the `tracing` feature enabled, to `tracing`.
+#[derive(Clone)]
+pub struct InstrumentedProvider<P: Provider> {
+    inner: P,
+    hook: Option<Arc<dyn ProviderHook>>,
+}
+
+impl<P: Provider + fmt::Debug> fmt::Debug for InstrumentedProvider<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InstrumentedProvider")
+            .field("inner", &self.inner)
+            .field("hook", &self.hook.is_some())
+            .finish()
+    }
+}
+
+impl<P: Provider> InstrumentedProvider<P> {
+    /// Wraps the provider without the hook, the requests are only reported to `tracing`.
+    pub fn new(inner: P) -> Self {
+        Self { inner, hook: None }
+    }
+
+    /// Sets the hook to report the requests to.
+    pub fn with_hook(mut self, hook: impl ProviderHook + 'static) -> Self {
+        self.hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Returns the wrapped provider.
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    async fn observe<Fut, R>(&self, method: &'static str, call: Fut) -> ResponseResult<R>
+    where
+        Fut: Future<Output = ResponseResult<R>>,
+    {
+        if let Some(hook) = &self.hook {
+            hook.on_request(method);
+        }
+
+        let start = Instant::now();
+        #[cfg(feature = "tracing")]
+        let result = {
+            use tracing::Instrument;
+            call.instrument(tracing::info_span!("provider_call", method))
+                .await
+        };
+        #[cfg(not(feature = "tracing"))]
+        let result = call.await;
+        let latency = start.elapsed();
+
+        #[cfg(feature = "tracing")]
+        {
+            let latency_ms = latency.as_millis() as u64;
+            match &result {
+                Ok(_) => tracing::debug!(method, latency_ms, "Provider call succeeded"),
+                Err(err) => {
+                    tracing::warn!(method, latency_ms, error = %err, "Provider call failed")
+                }
+            }
+        }
+
+        if let Some(hook) = &self.hook {
+            hook.on_response(&ProviderCall {
+                method,
+                latency,
+                error: result.as_ref().err(),
+            });
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync> Provider for InstrumentedProvider<P> {
+    async fn account_info(&self, address: Address) -> ResponseResult<AccountInfo> {
+        self.observe("account_info", self.inner.account_info(address))
+            .await
+    }
+
+    async fn tokens(&self) -> ResponseResult<Tokens> {
+        self.observe("tokens", self.inner.tokens()).await
+    }
+
+    async fn tx_info(&self, tx_hash: TxHash) -> ResponseResult<TransactionInfo> {
+        self.observe("tx_info", self.inner.tx_info(tx_hash)).await
+    }
+
+    async fn get_tx_fee(
+        &self,
+        tx_type: TxFeeTypes,
+        address: Address,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> ResponseResult<Fee> {
+        let token = token.into();
+        self.observe("get_tx_fee", self.inner.get_tx_fee(tx_type, address, token))
+            .await
+    }
+
+    async fn get_txs_batch_fee(
+        &self,
+        tx_types: Vec<TxFeeTypes>,
+        addresses: Vec<Address>,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> ResponseResult<BigUint> {
+        let token = token.into();
+        self.observe(
+            "get_txs_batch_fee",
+            self.inner.get_txs_batch_fee(tx_types, addresses, token),
+        )
+        .await
+    }
+
+    async fn ethop_info(&self, serial_id: u32) -> ResponseResult<EthOpInfo> {
+        self.observe("ethop_info", self.inner.ethop_info(serial_id))
+            .await
+    }
+
+    async fn get_eth_tx_for_withdrawal(
+        &self,
+        withdrawal_hash: TxHash,
+    ) -> ResponseResult<Option<String>> {
+        self.observe(
+            "get_eth_tx_for_withdrawal",
+            self.inner.get_eth_tx_for_withdrawal(withdrawal_hash),
+        )
+        .await
+    }
+
+    async fn contract_address(&self) -> ResponseResult<ContractAddress> {
+        self.observe("contract_address", self.inner.contract_address())
+            .await
+    }
+
+    async fn get_nft(&self, token_id: TokenId) -> ResponseResult<Option<NFTInfo>> {
+        self.observe("get_nft", self.inner.get_nft(token_id)).await
+    }
+
+    async fn get_nft_owner(&self, token_id: TokenId) -> ResponseResult<Option<AccountId>> {
+        self.observe("get_nft_owner", self.inner.get_nft_owner(token_id))
+            .await
+    }
+
+    async fn get_nft_id_by_tx_hash(&self, tx_hash: TxHash) -> ResponseResult<Option<TokenId>> {
+        self.observe(
+            "get_nft_id_by_tx_hash",
+            self.inner.get_nft_id_by_tx_hash(tx_hash),
+        )
+        .await
+    }
+
+    async fn send_tx(
+        &self,
+        tx: ZkSyncTx,
+        eth_signature: Option<PackedEthSignature>,
+    ) -> ResponseResult<TxHash> {
+        self.observe("send_tx", self.inner.send_tx(tx, eth_signature))
+            .await
+    }
+
+    async fn send_txs_batch(
+        &self,
+        txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+        eth_signature: Option<PackedEthSignature>,
+    ) -> ResponseResult<Vec<TxHash>> {
+        self.observe(
+            "send_txs_batch",
+            self.inner.send_txs_batch(txs_signed, eth_signature),
+        )
+        .await
+    }
+
+    async fn send_swap(
+        &self,
+        swap: Swap,
+        eth_signature: Option<PackedEthSignature>,
+        orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+    ) -> ResponseResult<TxHash> {
+        self.observe(
+            "send_swap",
+            self.inner
+                .send_swap(swap, eth_signature, orders_eth_signatures),
+        )
+        .await
+    }
+
+    fn network(&self) -> Network {
+        self.inner.network()
+    }
+}
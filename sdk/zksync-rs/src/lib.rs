@@ -1,19 +1,39 @@
 pub mod credentials;
 pub mod error;
 pub mod ethereum;
+pub mod failover_provider;
+pub mod history;
+pub mod instrumented_provider;
+pub mod mock_provider;
+pub mod network_config;
+pub mod offline;
 pub mod operations;
 pub mod provider;
+pub mod retry_provider;
 pub mod signer;
 pub mod tokens_cache;
 pub mod types;
 pub mod utils;
 pub mod wallet;
+pub mod ws_provider;
 
 pub use crate::{
-    credentials::WalletCredentials, ethereum::EthereumProvider, provider::RpcProvider,
+    credentials::WalletCredentials,
+    ethereum::EthereumProvider,
+    failover_provider::FailoverProvider,
+    history::HistoryProvider,
+    instrumented_provider::InstrumentedProvider,
+    network_config::NetworkConfig,
+    provider::RpcProvider,
+    retry_provider::{RetryConfig, RetryProvider},
     wallet::Wallet,
+    ws_provider::WsProvider,
 };
 pub use zksync_types::network::Network;
 
+#[cfg(feature = "ethers")]
+pub use zksync_eth_signer::EthersSigner;
+
 pub use web3;
+pub use zksync_api_types;
 pub use zksync_types;
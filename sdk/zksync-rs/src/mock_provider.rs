@@ -0,0 +1,312 @@
+//! Provider replaying the canned responses instead of communicating with the zkSync server,
+//! so the applications built on top of the SDK can be unit-tested without a live server.
+//!
+//! The responses are set per JSON RPC method, e.g. `account_info` or `tx_info`, in the same
+//! form the server returns them, and are deserialized just like the `RpcProvider` does it.
+//! Several responses set for the same method are replayed in order, and the last one is repeated
+//! for all the subsequent calls. Every call is recorded along with its JSON RPC parameters.
+//!
+//! The transactions submitted without the canned response are accepted, their hashes are returned.
+
+// Built-in imports
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+// External uses
+use async_trait::async_trait;
+use num::BigUint;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+// Workspace uses
+use zksync_types::{
+    network::Network,
+    tx::{PackedEthSignature, TxHash, ZkSyncTx},
+    AccountId, Address, Swap, TokenId, TokenLike, TxFeeTypes,
+};
+
+// Local uses
+use crate::{
+    error::{ClientError, ServerError, ServerErrorCode, TransportError},
+    provider::{JsonRpcRequest, Provider, ResponseResult},
+    types::*,
+};
+
+/// Call of the `MockProvider` method.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedCall {
+    /// Name of the corresponding JSON RPC method.
+    pub method: String,
+    pub params: Vec<Value>,
+}
+
+#[derive(Debug, Clone)]
+enum MockResponse {
+    Result(Value),
+    ServerError(ServerError),
+    TransportError(TransportError),
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    responses: HashMap<String, VecDeque<MockResponse>>,
+    calls: Vec<RecordedCall>,
+}
+
+/// `MockProvider` is a `Provider` replaying the canned responses, see the [module](index.html)
+/// documentation for details. The clones of the provider share the responses and the recorded calls.
+#[derive(Debug, Clone)]
+pub struct MockProvider {
+    network: Network,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockProvider {
+    pub fn new(network: Network) -> Self {
+        Self {
+            network,
+            state: Arc::default(),
+        }
+    }
+
+    /// Adds the result to be returned by the JSON RPC method.
+    pub fn respond(&self, method: impl Into<String>, result: impl Serialize) -> &Self {
+        let result = serde_json::to_value(result).expect("serialization fail");
+        self.push_response(method.into(), MockResponse::Result(result))
+    }
+
+    /// Adds the server error to be returned by the JSON RPC method, e.g. the
+    /// `ServerErrorCode::FeeTooLow` error for the `tx_submit` method.
+    pub fn respond_server_error(
+        &self,
+        method: impl Into<String>,
+        code: ServerErrorCode,
+        message: impl Into<String>,
+    ) -> &Self {
+        let error = ServerError {
+            code,
+            message: message.into(),
+            data: None,
+        };
+        self.push_response(method.into(), MockResponse::ServerError(error))
+    }
+
+    /// Adds the transport error to be returned by the JSON RPC method, e.g. to check
+    /// the behavior when the server is unavailable.
+    pub fn respond_transport_error(
+        &self,
+        method: impl Into<String>,
+        error: TransportError,
+    ) -> &Self {
+        self.push_response(method.into(), MockResponse::TransportError(error))
+    }
+
+    /// Makes the transactions executed after `polls` requests of their status: the `tx_info`
+    /// requests return the pending transaction first, then the one executed in the committed block,
+    /// and then in the verified one.
+    pub fn confirm_txs_after(&self, polls: usize, block_number: i64) -> &Self {
+        let executed = |verified| TransactionInfo {
+            executed: true,
+            success: Some(true),
+            fail_reason: None,
+            block: Some(BlockInfo {
+                block_number,
+                committed: true,
+                verified,
+            }),
+        };
+        let pending = TransactionInfo {
+            executed: false,
+            success: None,
+            fail_reason: None,
+            block: None,
+        };
+
+        for _ in 0..polls {
+            self.respond("tx_info", &pending);
+        }
+        self.respond("tx_info", executed(false))
+            .respond("tx_info", executed(true))
+    }
+
+    /// Returns all the calls made so far, in order.
+    pub fn calls(&self) -> Vec<RecordedCall> {
+        self.state.lock().unwrap().calls.clone()
+    }
+
+    /// Returns the calls of the JSON RPC method made so far, in order.
+    pub fn calls_of(&self, method: &str) -> Vec<RecordedCall> {
+        self.calls()
+            .into_iter()
+            .filter(|call| call.method == method)
+            .collect()
+    }
+
+    /// Forgets the recorded calls, the responses are kept.
+    pub fn clear_calls(&self) {
+        self.state.lock().unwrap().calls.clear();
+    }
+
+    fn push_response(&self, method: String, response: MockResponse) -> &Self {
+        self.state
+            .lock()
+            .unwrap()
+            .responses
+            .entry(method)
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    /// Records the call and returns the next response for its method, if any.
+    fn next_response(&self, msg: JsonRpcRequest) -> Option<MockResponse> {
+        let mut state = self.state.lock().unwrap();
+        let queue = state.responses.get_mut(&msg.method);
+        let response = queue.and_then(|queue| {
+            if queue.len() > 1 {
+                queue.pop_front()
+            } else {
+                queue.front().cloned()
+            }
+        });
+
+        state.calls.push(RecordedCall {
+            method: msg.method,
+            params: msg.params,
+        });
+        response
+    }
+
+    fn call<R: DeserializeOwned>(&self, msg: JsonRpcRequest) -> ResponseResult<R> {
+        let method = msg.method.clone();
+        match self.next_response(msg) {
+            Some(response) => parse_response(response),
+            None => Err(ServerError {
+                code: ServerErrorCode::MethodNotFound,
+                message: format!("No mock response for the '{}' method", method),
+                data: None,
+            }
+            .into()),
+        }
+    }
+
+    /// Same as `MockProvider::call`, but returns the `default` result if there is no response.
+    fn call_or<R: DeserializeOwned>(&self, msg: JsonRpcRequest, default: R) -> ResponseResult<R> {
+        match self.next_response(msg) {
+            Some(response) => parse_response(response),
+            None => Ok(default),
+        }
+    }
+}
+
+fn parse_response<R: DeserializeOwned>(response: MockResponse) -> ResponseResult<R> {
+    match response {
+        MockResponse::Result(result) => serde_json::from_value(result)
+            .map_err(|err| TransportError::MalformedResponse(err.to_string()).into()),
+        MockResponse::ServerError(err) => Err(err.into()),
+        MockResponse::TransportError(err) => Err(err.into()),
+    }
+}
+
+#[async_trait]
+impl Provider for MockProvider {
+    async fn account_info(&self, address: Address) -> ResponseResult<AccountInfo> {
+        self.call(JsonRpcRequest::account_info(address))
+    }
+
+    async fn tokens(&self) -> ResponseResult<Tokens> {
+        self.call(JsonRpcRequest::tokens())
+    }
+
+    async fn tx_info(&self, tx_hash: TxHash) -> ResponseResult<TransactionInfo> {
+        self.call(JsonRpcRequest::tx_info(tx_hash))
+    }
+
+    async fn get_tx_fee(
+        &self,
+        tx_type: TxFeeTypes,
+        address: Address,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> ResponseResult<Fee> {
+        self.call(JsonRpcRequest::get_tx_fee(tx_type, address, token.into()))
+    }
+
+    async fn get_txs_batch_fee(
+        &self,
+        tx_types: Vec<TxFeeTypes>,
+        addresses: Vec<Address>,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> ResponseResult<BigUint> {
+        let msg = JsonRpcRequest::get_txs_batch_fee_in_wei(tx_types, addresses, token.into());
+
+        let batch_fee: BatchFee = self.call(msg)?;
+        Ok(batch_fee.total_fee)
+    }
+
+    async fn ethop_info(&self, serial_id: u32) -> ResponseResult<EthOpInfo> {
+        self.call(JsonRpcRequest::ethop_info(serial_id))
+    }
+
+    async fn get_eth_tx_for_withdrawal(
+        &self,
+        withdrawal_hash: TxHash,
+    ) -> ResponseResult<Option<String>> {
+        self.call(JsonRpcRequest::eth_tx_for_withdrawal(withdrawal_hash))
+    }
+
+    async fn contract_address(&self) -> ResponseResult<ContractAddress> {
+        self.call(JsonRpcRequest::contract_address())
+    }
+
+    async fn get_nft(&self, token_id: TokenId) -> ResponseResult<Option<NFTInfo>> {
+        self.call(JsonRpcRequest::get_nft(token_id))
+    }
+
+    async fn get_nft_owner(&self, token_id: TokenId) -> ResponseResult<Option<AccountId>> {
+        self.call(JsonRpcRequest::get_nft_owner(token_id))
+    }
+
+    async fn get_nft_id_by_tx_hash(&self, tx_hash: TxHash) -> ResponseResult<Option<TokenId>> {
+        self.call(JsonRpcRequest::get_nft_id_by_tx_hash(tx_hash))
+    }
+
+    async fn send_tx(
+        &self,
+        tx: ZkSyncTx,
+        eth_signature: Option<PackedEthSignature>,
+    ) -> ResponseResult<TxHash> {
+        let tx_hash = tx.hash();
+        self.call_or(JsonRpcRequest::submit_tx(tx, eth_signature), tx_hash)
+    }
+
+    async fn send_txs_batch(
+        &self,
+        txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+        eth_signature: Option<PackedEthSignature>,
+    ) -> ResponseResult<Vec<TxHash>> {
+        let tx_hashes = txs_signed.iter().map(|(tx, _)| tx.hash()).collect();
+        self.call_or(
+            JsonRpcRequest::submit_tx_batch(txs_signed, eth_signature),
+            tx_hashes,
+        )
+    }
+
+    async fn send_swap(
+        &self,
+        swap: Swap,
+        eth_signature: Option<PackedEthSignature>,
+        orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+    ) -> ResponseResult<TxHash> {
+        let tx_hash = ZkSyncTx::from(swap.clone()).hash();
+        self.call_or(
+            JsonRpcRequest::submit_swap(swap, eth_signature, orders_eth_signatures),
+            tx_hash,
+        )
+    }
+
+    fn network(&self) -> Network {
+        self.network
+    }
+}
@@ -0,0 +1,135 @@
+//! Configuration of the zkSync network to connect to.
+//!
+//! Besides resolving the server address, the configuration pins the address of the zkSync
+//! contract on the Ethereum side. The contract address reported by the server is checked
+//! against the pinned one before the provider is used, so the funds are never sent to
+//! the contract of another network because of a misconfigured endpoint.
+
+// Built-in imports
+use std::str::FromStr;
+
+// Workspace uses
+use zksync_types::{network::Network, Address};
+
+// Local uses
+use crate::{
+    error::{ClientError, TransportError},
+    provider::{get_rpc_addr, Provider, RpcProvider},
+    types::ContractAddress,
+};
+
+/// Returns the known address of the zkSync contract for a provided network name.
+/// The address is not pinned for the networks which could be redeployed, e.g. `Localhost`.
+pub fn pinned_contract_address(network: Network) -> Option<Address> {
+    let address = match network {
+        Network::Mainnet => "aBEA9132b05A70803a4E85094fD0e1800777fBEF",
+        Network::Rinkeby => "82F67958A5474e40E1485742d648C0b0686b6e5D",
+        Network::Ropsten => "0bc1c9ab8e7d9ddcbfb2a3a6373c852ad05c1bf9",
+        _ => return None,
+    };
+
+    Some(Address::from_str(address).expect("Pinned contract address is correct"))
+}
+
+/// Parses the address of the zkSync contract reported by the server.
+pub(crate) fn main_contract_address(response: &ContractAddress) -> Result<Address, ClientError> {
+    let main_contract = response
+        .main_contract
+        .strip_prefix("0x")
+        .unwrap_or(&response.main_contract);
+
+    main_contract
+        .parse::<Address>()
+        .map_err(|err| TransportError::MalformedResponse(err.to_string()).into())
+}
+
+/// Checks that the server reports the expected address of the zkSync contract.
+/// Returns `ClientError::ContractAddressMismatch` otherwise.
+pub async fn verify_contract_address<P: Provider>(
+    provider: &P,
+    expected: Address,
+) -> Result<(), ClientError> {
+    let actual = main_contract_address(&provider.contract_address().await?)?;
+    if actual != expected {
+        return Err(ClientError::ContractAddressMismatch { expected, actual });
+    }
+
+    Ok(())
+}
+
+/// zkSync network to connect to, along with the address of its server.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NetworkConfig {
+    Mainnet,
+    Rinkeby,
+    Ropsten,
+    Localhost,
+    /// Network served via a custom address, e.g. a third-party node or a local deployment
+    /// with the known contract address.
+    Custom {
+        network: Network,
+        rpc_addr: String,
+        /// Expected address of the zkSync contract, the pinned one of the `network` is used if not set.
+        main_contract: Option<Address>,
+    },
+}
+
+impl NetworkConfig {
+    /// Returns the network type.
+    pub fn network(&self) -> Network {
+        match self {
+            Self::Mainnet => Network::Mainnet,
+            Self::Rinkeby => Network::Rinkeby,
+            Self::Ropsten => Network::Ropsten,
+            Self::Localhost => Network::Localhost,
+            Self::Custom { network, .. } => *network,
+        }
+    }
+
+    /// Returns the address of the zkSync JSON RPC server.
+    pub fn rpc_addr(&self) -> &str {
+        match self {
+            Self::Custom { rpc_addr, .. } => rpc_addr,
+            _ => get_rpc_addr(self.network()),
+        }
+    }
+
+    /// Returns the expected address of the zkSync contract, if it's known.
+    pub fn main_contract(&self) -> Option<Address> {
+        match self {
+            Self::Custom {
+                main_contract: Some(main_contract),
+                ..
+            } => Some(*main_contract),
+            _ => pinned_contract_address(self.network()),
+        }
+    }
+
+    /// Creates an `RpcProvider` for the network. If the address of the zkSync contract is known,
+    /// checks that the server reports the same one, and returns
+    /// `ClientError::ContractAddressMismatch` otherwise.
+    pub async fn connect(&self) -> Result<RpcProvider, ClientError> {
+        let provider = RpcProvider::from_addr_and_network(self.rpc_addr(), self.network());
+        if let Some(main_contract) = self.main_contract() {
+            verify_contract_address(&provider, main_contract).await?;
+        }
+
+        Ok(provider)
+    }
+}
+
+impl From<Network> for NetworkConfig {
+    fn from(network: Network) -> Self {
+        match network {
+            Network::Mainnet => Self::Mainnet,
+            Network::Rinkeby => Self::Rinkeby,
+            Network::Ropsten => Self::Ropsten,
+            Network::Localhost => Self::Localhost,
+            network => Self::Custom {
+                network,
+                rpc_addr: get_rpc_addr(network).into(),
+                main_contract: None,
+            },
+        }
+    }
+}
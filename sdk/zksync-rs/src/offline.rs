@@ -0,0 +1,161 @@
+//! Offline signing workflow.
+//!
+//! Building a transaction requires the network access (to get the nonce and the fee),
+//! while signing it requires the private keys. These steps can be performed on different
+//! machines:
+//!
+//! 1. The online machine builds the `UnsignedTx` via the `unsigned_tx` method of the
+//!    transfer, withdraw or change pubkey builder and passes its JSON to the signing machine.
+//! 2. The air-gapped machine holding the keys signs it with `UnsignedTx::sign`, getting
+//!    the `SignedTx`.
+//! 3. The online machine submits the received `SignedTx` via `SignedTx::send`.
+
+// External uses
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+
+// Workspace uses
+use zksync_eth_signer::{error::SignerError, EthereumSigner};
+use zksync_types::{
+    tx::{PackedEthSignature, TimeRange},
+    AccountId, Address, ChainId, Nonce, Token, ZkSyncTx,
+};
+use zksync_utils::BigUintSerdeAsRadix10Str;
+
+// Local uses
+use crate::{
+    error::ClientError, operations::SyncTransactionHandle, provider::Provider, signer::Signer,
+};
+
+/// Transfer with every field filled in except for the signatures.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsignedTransfer {
+    pub account_id: AccountId,
+    pub from: Address,
+    pub to: Address,
+    pub token: Token,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    pub nonce: Nonce,
+    pub time_range: TimeRange,
+}
+
+/// Withdraw with every field filled in except for the signatures.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsignedWithdraw {
+    pub account_id: AccountId,
+    pub from: Address,
+    pub to: Address,
+    pub token: Token,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    pub nonce: Nonce,
+    pub time_range: TimeRange,
+}
+
+/// Change pubkey transaction with every field filled in except for the signatures.
+/// The new public key hash is the one of the signer's zkSync private key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsignedChangePubKey {
+    pub account_id: AccountId,
+    pub account: Address,
+    pub fee_token: Token,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    pub nonce: Nonce,
+    pub time_range: TimeRange,
+    pub chain_id: ChainId,
+    /// Whether the new public key is authorized onchain rather than by the Ethereum signature.
+    pub onchain_auth: bool,
+}
+
+/// Transaction built by the online machine, to be signed offline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum UnsignedTx {
+    Transfer(UnsignedTransfer),
+    Withdraw(UnsignedWithdraw),
+    ChangePubKey(UnsignedChangePubKey),
+}
+
+/// Transaction signed offline, ready to be submitted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedTx {
+    pub tx: ZkSyncTx,
+    pub eth_signature: Option<PackedEthSignature>,
+}
+
+impl UnsignedTx {
+    /// Returns the account the transaction is sent from.
+    pub fn account(&self) -> (AccountId, Address) {
+        match self {
+            Self::Transfer(tx) => (tx.account_id, tx.from),
+            Self::Withdraw(tx) => (tx.account_id, tx.from),
+            Self::ChangePubKey(tx) => (tx.account_id, tx.account),
+        }
+    }
+
+    /// Signs the transaction. The signer must belong to the transaction account,
+    /// including the account id set via `Signer::set_account_id`.
+    pub async fn sign<S: EthereumSigner>(
+        self,
+        signer: &Signer<S>,
+    ) -> Result<SignedTx, SignerError> {
+        let (account_id, address) = self.account();
+        if signer.address != address || signer.get_account_id() != Some(account_id) {
+            return Err(SignerError::CustomError(
+                "Signer doesn't match the account of the transaction".to_string(),
+            ));
+        }
+
+        let (tx, eth_signature) = match self {
+            Self::Transfer(tx) => {
+                let (tx, eth_signature) = signer
+                    .sign_transfer(tx.token, tx.amount, tx.fee, tx.to, tx.nonce, tx.time_range)
+                    .await?;
+                (ZkSyncTx::Transfer(Box::new(tx)), eth_signature)
+            }
+            Self::Withdraw(tx) => {
+                let (tx, eth_signature) = signer
+                    .sign_withdraw(tx.token, tx.amount, tx.fee, tx.to, tx.nonce, tx.time_range)
+                    .await?;
+                (ZkSyncTx::Withdraw(Box::new(tx)), eth_signature)
+            }
+            Self::ChangePubKey(tx) => {
+                let tx = signer
+                    .sign_change_pubkey_tx(
+                        tx.nonce,
+                        tx.onchain_auth,
+                        tx.fee_token,
+                        tx.fee,
+                        tx.time_range,
+                        Some(tx.chain_id),
+                    )
+                    .await?;
+                (ZkSyncTx::from(tx), None)
+            }
+        };
+
+        Ok(SignedTx { tx, eth_signature })
+    }
+}
+
+impl SignedTx {
+    /// Sends the transaction, returning the handle for its awaiting.
+    pub async fn send<P: Provider>(
+        self,
+        provider: P,
+    ) -> Result<SyncTransactionHandle<P>, ClientError> {
+        let tx_hash = provider.send_tx(self.tx, self.eth_signature).await?;
+
+        Ok(SyncTransactionHandle::new(tx_hash, provider))
+    }
+}
@@ -0,0 +1,242 @@
+use num::BigUint;
+use zksync_eth_signer::EthereumSigner;
+use zksync_types::{
+    helpers::{closest_packable_token_amount, is_token_amount_packable},
+    Address, Nonce, Token, TokenLike, TxFeeTypes,
+};
+
+use crate::{
+    error::{ClientError, ServerErrorCode},
+    operations::SyncTransactionHandle,
+    provider::Provider,
+    wallet::Wallet,
+};
+
+/// The way the fee of the transfer is paid, selected by the `AutoFeeTransferBuilder`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeePayment {
+    /// The fee is paid in the transferred token by the transfer itself.
+    SameToken { fee: BigUint },
+    /// The fee is paid in another token by the transfer to the wallet itself,
+    /// sent in the batch with the transfer.
+    Batch { fee_token: Token, fee: BigUint },
+}
+
+/// Builder of the transfer which selects the token to pay the fee in by itself.
+///
+/// The fee tokens are tried in order: the transferred token first, then the other tokens
+/// of the account by their ids, unless the candidates are set via `fee_tokens`. The first
+/// token the server accepts fees in and whose committed balance covers the fee (along with
+/// the transferred amount, if it is the same token) is selected.
+#[derive(Debug)]
+pub struct AutoFeeTransferBuilder<'a, S: EthereumSigner, P: Provider> {
+    wallet: &'a Wallet<S, P>,
+    token: Option<Token>,
+    amount: Option<BigUint>,
+    to: Option<Address>,
+    fee_tokens: Option<Vec<Token>>,
+    nonce: Option<Nonce>,
+}
+
+impl<'a, S, P> AutoFeeTransferBuilder<'a, S, P>
+where
+    S: EthereumSigner,
+    P: Provider + Clone,
+{
+    /// Initializes a transfer with the automatic fee token selection building process.
+    pub fn new(wallet: &'a Wallet<S, P>) -> Self {
+        Self {
+            wallet,
+            token: None,
+            amount: None,
+            to: None,
+            fee_tokens: None,
+            nonce: None,
+        }
+    }
+
+    /// Selects the way to pay the fee without sending anything. Returns
+    /// `ClientError::InsufficientBalance` if the fee can't be paid in any of the tokens.
+    pub async fn fee_payment(&self) -> Result<FeePayment, ClientError> {
+        let token = self
+            .token
+            .as_ref()
+            .ok_or_else(|| ClientError::MissingRequiredField("token".into()))?;
+        let amount = self
+            .amount
+            .as_ref()
+            .ok_or_else(|| ClientError::MissingRequiredField("amount".into()))?;
+        let to = self
+            .to
+            .ok_or_else(|| ClientError::MissingRequiredField("to".into()))?;
+
+        let provider = &self.wallet.provider;
+        let address = self.wallet.address();
+        let balances = provider.account_info(address).await?.committed.balances;
+        let balance = |token: &Token| {
+            balances
+                .get(&token.symbol)
+                .map(|balance| balance.0.clone())
+                .unwrap_or_default()
+        };
+
+        if &balance(token) < amount {
+            return Err(ClientError::InsufficientBalance);
+        }
+
+        let candidates = match &self.fee_tokens {
+            Some(fee_tokens) => fee_tokens.clone(),
+            None => {
+                let mut held_tokens: Vec<_> = balances
+                    .keys()
+                    .filter_map(|symbol| self.wallet.tokens.resolve(symbol.as_str().into()))
+                    .filter(|held_token| held_token.id != token.id)
+                    .collect();
+                held_tokens.sort_by_key(|held_token| held_token.id);
+
+                std::iter::once(token.clone()).chain(held_tokens).collect()
+            }
+        };
+
+        for fee_token in candidates {
+            let is_same_token = fee_token.id == token.id;
+            let fee = if is_same_token {
+                provider
+                    .get_tx_fee(TxFeeTypes::Transfer, to, fee_token.id)
+                    .await
+                    .map(|fee| fee.total_fee)
+            } else {
+                // The fee is paid by the transfer to the wallet itself.
+                provider
+                    .get_txs_batch_fee(
+                        vec![TxFeeTypes::Transfer, TxFeeTypes::Transfer],
+                        vec![to, address],
+                        fee_token.id,
+                    )
+                    .await
+            };
+            let fee = match fee {
+                Ok(fee) => fee,
+                Err(ClientError::ServerError(err))
+                    if err.code == ServerErrorCode::InappropriateFeeToken =>
+                {
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            if is_same_token {
+                if balance(&fee_token) >= amount + &fee {
+                    return Ok(FeePayment::SameToken { fee });
+                }
+            } else if balance(&fee_token) >= fee {
+                return Ok(FeePayment::Batch { fee_token, fee });
+            }
+        }
+
+        Err(ClientError::InsufficientBalance)
+    }
+
+    /// Sends the transfer, batched with the fee payment if the fee is paid in another token.
+    /// Returns the handles for the sent transactions, the transfer goes first.
+    pub async fn send(self) -> Result<Vec<SyncTransactionHandle<P>>, ClientError> {
+        let fee_payment = self.fee_payment().await?;
+        // The required fields are checked by `fee_payment`.
+        let (token, amount, to) = (self.token.unwrap(), self.amount.unwrap(), self.to.unwrap());
+
+        match fee_payment {
+            FeePayment::SameToken { fee } => {
+                let mut builder = self
+                    .wallet
+                    .start_transfer()
+                    .token(token.id)?
+                    .amount_exact(amount)?
+                    .fee_exact(fee)?
+                    .to(to);
+                if let Some(nonce) = self.nonce {
+                    builder = builder.nonce(nonce);
+                }
+
+                Ok(vec![builder.send().await?])
+            }
+            FeePayment::Batch { fee_token, fee } => {
+                let mut builder = self
+                    .wallet
+                    .start_batch()
+                    .transfer_exact(token.id, to, amount)?
+                    .fee_token(fee_token.id)?
+                    .fee_exact(fee)?;
+                if let Some(nonce) = self.nonce {
+                    builder = builder.nonce(nonce);
+                }
+
+                builder.send().await
+            }
+        }
+    }
+
+    /// Sets the transaction token. Returns an error if token is not supported by zkSync.
+    pub fn token(mut self, token: impl Into<TokenLike>) -> Result<Self, ClientError> {
+        self.token = Some(self.resolve_token(token)?);
+        Ok(self)
+    }
+
+    /// Set the transfer amount. If the amount provided is not packable,
+    /// rounds it to the closest packable amount.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn amount(mut self, amount: impl Into<BigUint>) -> Self {
+        let amount = closest_packable_token_amount(&amount.into());
+        self.amount = Some(amount);
+
+        self
+    }
+
+    /// Set the transfer amount. If the provided amount is not packable,
+    /// returns an error.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn amount_exact(mut self, amount: impl Into<BigUint>) -> Result<Self, ClientError> {
+        let amount = amount.into();
+        if !is_token_amount_packable(&amount) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.amount = Some(amount);
+
+        Ok(self)
+    }
+
+    /// Sets the transaction recipient.
+    pub fn to(mut self, to: Address) -> Self {
+        self.to = Some(to);
+        self
+    }
+
+    /// Sets the tokens to try paying the fee in, in the order of preference.
+    /// Returns an error if any of the tokens is not supported by zkSync.
+    pub fn fee_tokens<T: Into<TokenLike>>(
+        mut self,
+        tokens: impl IntoIterator<Item = T>,
+    ) -> Result<Self, ClientError> {
+        let fee_tokens = tokens
+            .into_iter()
+            .map(|token| self.resolve_token(token))
+            .collect::<Result<_, _>>()?;
+        self.fee_tokens = Some(fee_tokens);
+
+        Ok(self)
+    }
+
+    /// Sets the nonce of the transfer, the fee payment gets the subsequent one.
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    fn resolve_token(&self, token: impl Into<TokenLike>) -> Result<Token, ClientError> {
+        self.wallet
+            .tokens
+            .resolve(token.into())
+            .ok_or(ClientError::UnknownToken)
+    }
+}
@@ -0,0 +1,352 @@
+use num::BigUint;
+use zksync_eth_signer::{error::SignerError, EthereumSigner};
+use zksync_types::{
+    helpers::{
+        closest_packable_fee_amount, closest_packable_token_amount, is_fee_amount_packable,
+        is_token_amount_packable,
+    },
+    tokens::ChangePubKeyFeeTypeArg,
+    tx::{ChangePubKeyType, PackedEthSignature, TimeRange, TransactionError},
+    Address, Nonce, Token, TokenLike, Transfer, TxFeeTypes, Withdraw, ZkSyncTx,
+};
+
+use crate::{
+    error::ClientError, operations::SyncTransactionHandle, provider::Provider, wallet::Wallet,
+};
+
+/// Transaction of the batch, not signed yet.
+#[derive(Debug)]
+enum BatchTx {
+    Transfer {
+        token: Token,
+        to: Address,
+        amount: BigUint,
+    },
+    Withdraw {
+        token: Token,
+        to: Address,
+        amount: BigUint,
+    },
+    ChangePubKey {
+        onchain_auth: bool,
+    },
+}
+
+impl BatchTx {
+    fn fee_type(&self) -> TxFeeTypes {
+        match self {
+            Self::Transfer { .. } => TxFeeTypes::Transfer,
+            Self::Withdraw { .. } => TxFeeTypes::Withdraw,
+            Self::ChangePubKey { onchain_auth } => {
+                let auth_type = if *onchain_auth {
+                    ChangePubKeyType::Onchain
+                } else {
+                    ChangePubKeyType::EIP712
+                };
+                TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(auth_type))
+            }
+        }
+    }
+}
+
+/// Builder of the batch of the account transactions (transfers, withdrawals and
+/// change pubkey), e.g. to set the signing key and move the funds at once.
+///
+/// The fee for the whole batch is paid in the fee token by an additional transfer to the wallet
+/// itself, appended to the end of the batch. The batch is signed with the Ethereum key once.
+#[derive(Debug)]
+pub struct BatchBuilder<'a, S: EthereumSigner, P: Provider> {
+    wallet: &'a Wallet<S, P>,
+    txs: Vec<BatchTx>,
+    fee_token: Option<Token>,
+    fee: Option<BigUint>,
+    nonce: Option<Nonce>,
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
+}
+
+impl<'a, S, P> BatchBuilder<'a, S, P>
+where
+    S: EthereumSigner,
+    P: Provider + Clone,
+{
+    /// Initializes a transactions batch building process.
+    pub fn new(wallet: &'a Wallet<S, P>) -> Self {
+        Self {
+            wallet,
+            txs: Vec::new(),
+            fee_token: None,
+            fee: None,
+            nonce: None,
+            valid_from: None,
+            valid_until: None,
+        }
+    }
+
+    /// Directly returns the signed transactions along with the Ethereum signature of the whole batch.
+    pub async fn txs(
+        self,
+    ) -> Result<
+        (
+            Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+            Option<PackedEthSignature>,
+        ),
+        ClientError,
+    > {
+        if self.txs.is_empty() {
+            return Err(ClientError::MissingRequiredField("transactions".into()));
+        }
+        let fee_token = self
+            .fee_token
+            .ok_or_else(|| ClientError::MissingRequiredField("fee_token".into()))?;
+        let address = self.wallet.address();
+        let time_range = TimeRange::new(
+            self.valid_from.unwrap_or(0),
+            self.valid_until.unwrap_or(u64::MAX),
+        );
+
+        let fee = match self.fee {
+            Some(fee) => fee,
+            None => {
+                let (mut fee_types, mut recipients): (Vec<_>, Vec<_>) = self
+                    .txs
+                    .iter()
+                    .map(|tx| {
+                        let recipient = match tx {
+                            BatchTx::Transfer { to, .. } | BatchTx::Withdraw { to, .. } => *to,
+                            BatchTx::ChangePubKey { .. } => address,
+                        };
+                        (tx.fee_type(), recipient)
+                    })
+                    .unzip();
+                // The fee is paid by the transfer to the wallet itself.
+                fee_types.push(TxFeeTypes::Transfer);
+                recipients.push(address);
+
+                self.wallet
+                    .provider
+                    .get_txs_batch_fee(fee_types, recipients, fee_token.id)
+                    .await?
+            }
+        };
+
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => {
+                let account_info = self.wallet.provider.account_info(address).await?;
+                account_info.committed.nonce
+            }
+        };
+
+        let signer = &self.wallet.signer;
+        let account_id = signer
+            .get_account_id()
+            .ok_or(ClientError::SigningError(SignerError::NoSigningKey))?;
+        let signing_failed = |err: TransactionError| {
+            ClientError::SigningError(SignerError::SigningFailed(err.to_string()))
+        };
+
+        let fee_transfer = BatchTx::Transfer {
+            token: fee_token.clone(),
+            to: address,
+            amount: BigUint::from(0u16),
+        };
+        let fee_transfer_idx = self.txs.len();
+        let mut signed_txs = Vec::with_capacity(self.txs.len() + 1);
+        let txs = self.txs.into_iter().chain(Some(fee_transfer));
+        for ((idx, tx), nonce) in txs.enumerate().zip(*nonce..) {
+            let nonce = Nonce(nonce);
+            let zero_fee = BigUint::from(0u16);
+            let signed_tx = match tx {
+                BatchTx::Transfer { token, to, amount } => {
+                    let tx_fee = if idx == fee_transfer_idx {
+                        fee.clone()
+                    } else {
+                        zero_fee
+                    };
+                    let transfer = Transfer::new_signed(
+                        account_id,
+                        address,
+                        to,
+                        token.id,
+                        amount,
+                        tx_fee,
+                        nonce,
+                        time_range,
+                        &signer.private_key,
+                    )
+                    .map_err(signing_failed)?;
+                    (ZkSyncTx::from(transfer), token)
+                }
+                BatchTx::Withdraw { token, to, amount } => {
+                    let withdraw = Withdraw::new_signed(
+                        account_id,
+                        address,
+                        to,
+                        token.id,
+                        amount,
+                        zero_fee,
+                        nonce,
+                        time_range,
+                        &signer.private_key,
+                    )
+                    .map_err(signing_failed)?;
+                    (ZkSyncTx::from(withdraw), token)
+                }
+                BatchTx::ChangePubKey { onchain_auth } => {
+                    let change_pubkey = signer
+                        .sign_change_pubkey_tx(
+                            nonce,
+                            onchain_auth,
+                            fee_token.clone(),
+                            zero_fee,
+                            time_range,
+                            Some(self.wallet.provider.network().chain_id()),
+                        )
+                        .await
+                        .map_err(ClientError::SigningError)?;
+                    (ZkSyncTx::from(change_pubkey), fee_token.clone())
+                }
+            };
+            signed_txs.push(signed_tx);
+        }
+
+        let batch_signature = signer
+            .sign_batch(signed_txs.clone())
+            .await
+            .map_err(ClientError::SigningError)?;
+        let txs = signed_txs.into_iter().map(|(tx, _)| (tx, None)).collect();
+
+        Ok((txs, batch_signature))
+    }
+
+    /// Sends the transaction batch, returning the handles for its transactions.
+    pub async fn send(self) -> Result<Vec<SyncTransactionHandle<P>>, ClientError> {
+        let provider = self.wallet.provider.clone();
+
+        let (txs, batch_signature) = self.txs().await?;
+        let tx_hashes = provider.send_txs_batch(txs, batch_signature).await?;
+
+        Ok(tx_hashes
+            .into_iter()
+            .map(|tx_hash| SyncTransactionHandle::new(tx_hash, provider.clone()))
+            .collect())
+    }
+
+    /// Adds the transfer to the batch. If the provided amount is not packable,
+    /// rounds it to the closest packable amount. Returns an error if token is not
+    /// supported by zkSync.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn transfer(
+        self,
+        token: impl Into<TokenLike>,
+        to: Address,
+        amount: impl Into<BigUint>,
+    ) -> Result<Self, ClientError> {
+        let amount = closest_packable_token_amount(&amount.into());
+        self.transfer_exact(token, to, amount)
+    }
+
+    /// Adds the transfer to the batch. If the provided amount is not packable,
+    /// returns an error.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn transfer_exact(
+        mut self,
+        token: impl Into<TokenLike>,
+        to: Address,
+        amount: impl Into<BigUint>,
+    ) -> Result<Self, ClientError> {
+        let amount = amount.into();
+        if !is_token_amount_packable(&amount) {
+            return Err(ClientError::NotPackableValue);
+        }
+        let token = self.resolve_token(token)?;
+        self.txs.push(BatchTx::Transfer { token, to, amount });
+
+        Ok(self)
+    }
+
+    /// Adds the withdrawal to the Ethereum address to the batch. Returns an error if token
+    /// is not supported by zkSync.
+    pub fn withdraw(
+        mut self,
+        token: impl Into<TokenLike>,
+        to: Address,
+        amount: impl Into<BigUint>,
+    ) -> Result<Self, ClientError> {
+        let token = self.resolve_token(token)?;
+        self.txs.push(BatchTx::Withdraw {
+            token,
+            to,
+            amount: amount.into(),
+        });
+
+        Ok(self)
+    }
+
+    /// Adds setting the signing key of the wallet to the batch. With `onchain_auth` set,
+    /// the key change must be authorized in the zkSync contract beforehand.
+    pub fn change_pubkey(mut self, onchain_auth: bool) -> Self {
+        self.txs.push(BatchTx::ChangePubKey { onchain_auth });
+        self
+    }
+
+    /// Sets the token the fee for the whole batch is paid in. Returns an error if token
+    /// is not supported by zkSync.
+    pub fn fee_token(mut self, token: impl Into<TokenLike>) -> Result<Self, ClientError> {
+        self.fee_token = Some(self.resolve_token(token)?);
+        Ok(self)
+    }
+
+    /// Set the fee amount for the whole batch. If the provided fee is not packable,
+    /// rounds it to the closest packable fee amount.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee(mut self, fee: impl Into<BigUint>) -> Self {
+        let fee = closest_packable_fee_amount(&fee.into());
+        self.fee = Some(fee);
+
+        self
+    }
+
+    /// Set the fee amount for the whole batch. If the provided fee is not packable,
+    /// returns an error.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee_exact(mut self, fee: impl Into<BigUint>) -> Result<Self, ClientError> {
+        let fee = fee.into();
+        if !is_fee_amount_packable(&fee) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.fee = Some(fee);
+
+        Ok(self)
+    }
+
+    /// Sets the unix format timestamp of the first moment when transaction execution is valid.
+    pub fn valid_from(mut self, valid_from: u64) -> Self {
+        self.valid_from = Some(valid_from);
+        self
+    }
+
+    /// Sets the unix format timestamp of the last moment when transaction execution is valid.
+    pub fn valid_until(mut self, valid_until: u64) -> Self {
+        self.valid_until = Some(valid_until);
+        self
+    }
+
+    /// Sets the nonce of the first transaction, the following transactions get the subsequent nonces.
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    fn resolve_token(&self, token: impl Into<TokenLike>) -> Result<Token, ClientError> {
+        self.wallet
+            .tokens
+            .resolve(token.into())
+            .ok_or(ClientError::UnknownToken)
+    }
+}
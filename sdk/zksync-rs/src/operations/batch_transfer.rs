@@ -0,0 +1,231 @@
+use num::BigUint;
+use zksync_eth_signer::EthereumSigner;
+use zksync_types::{
+    helpers::{
+        closest_packable_fee_amount, closest_packable_token_amount, is_fee_amount_packable,
+        is_token_amount_packable,
+    },
+    tx::PackedEthSignature,
+    Address, Nonce, Token, TokenLike, TxFeeTypes, ZkSyncTx,
+};
+
+use crate::{
+    error::ClientError, operations::SyncTransactionHandle, provider::Provider, wallet::Wallet,
+};
+use zksync_types::tx::TimeRange;
+
+/// Builder of the batch of transfers of the same token to several recipients, e.g. for payrolls
+/// and airdrops. The batch is signed with the Ethereum key once, and the fee for the whole batch
+/// is paid by its first transfer.
+///
+/// Note that the batch consists of the ordinary `Transfer` transactions, one per recipient, so it
+/// takes the same amount of chunks in the block as the transfers sent separately. The protocol has
+/// no operation with several outputs: adding one would change the circuit, its verification keys
+/// and the pubdata parsed by the contract, which requires a new contract version and setup.
+/// Batching still guarantees that either all the transfers are executed, or none of them.
+#[derive(Debug)]
+pub struct BatchTransferBuilder<'a, S: EthereumSigner, P: Provider> {
+    wallet: &'a Wallet<S, P>,
+    token: Option<Token>,
+    recipients: Vec<(Address, BigUint)>,
+    fee: Option<BigUint>,
+    nonce: Option<Nonce>,
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
+}
+
+impl<'a, S, P> BatchTransferBuilder<'a, S, P>
+where
+    S: EthereumSigner,
+    P: Provider + Clone,
+{
+    /// Initializes a batch transfer building process.
+    pub fn new(wallet: &'a Wallet<S, P>) -> Self {
+        Self {
+            wallet,
+            token: None,
+            recipients: Vec::new(),
+            fee: None,
+            nonce: None,
+            valid_from: None,
+            valid_until: None,
+        }
+    }
+
+    /// Directly returns the signed transfers along with the Ethereum signature of the whole batch.
+    pub async fn txs(
+        self,
+    ) -> Result<
+        (
+            Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+            Option<PackedEthSignature>,
+        ),
+        ClientError,
+    > {
+        let token = self
+            .token
+            .ok_or_else(|| ClientError::MissingRequiredField("token".into()))?;
+        if self.recipients.is_empty() {
+            return Err(ClientError::MissingRequiredField("recipients".into()));
+        }
+        let valid_from = self.valid_from.unwrap_or(0);
+        let valid_until = self.valid_until.unwrap_or(u64::MAX);
+
+        let fee = match self.fee {
+            Some(fee) => fee,
+            None => {
+                let recipients = self.recipients.iter().map(|(to, _)| *to).collect();
+                self.wallet
+                    .provider
+                    .get_txs_batch_fee(
+                        vec![TxFeeTypes::Transfer; self.recipients.len()],
+                        recipients,
+                        token.id,
+                    )
+                    .await?
+            }
+        };
+
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => {
+                let account_info = self
+                    .wallet
+                    .provider
+                    .account_info(self.wallet.address())
+                    .await?;
+                account_info.committed.nonce
+            }
+        };
+
+        let transfers = self
+            .recipients
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (to, amount))| {
+                let fee = if idx == 0 {
+                    fee.clone()
+                } else {
+                    BigUint::from(0u16)
+                };
+                (to, amount, fee)
+            })
+            .collect();
+        let (transfers, batch_signature) = self
+            .wallet
+            .signer
+            .sign_transfers_batch(
+                token,
+                transfers,
+                nonce,
+                TimeRange::new(valid_from, valid_until),
+            )
+            .await
+            .map_err(ClientError::SigningError)?;
+
+        let txs = transfers
+            .into_iter()
+            .map(|transfer| (ZkSyncTx::Transfer(Box::new(transfer)), None))
+            .collect();
+        Ok((txs, batch_signature))
+    }
+
+    /// Sends the transaction batch, returning the handles for its transactions.
+    pub async fn send(self) -> Result<Vec<SyncTransactionHandle<P>>, ClientError> {
+        let provider = self.wallet.provider.clone();
+
+        let (txs, batch_signature) = self.txs().await?;
+        let tx_hashes = provider.send_txs_batch(txs, batch_signature).await?;
+
+        Ok(tx_hashes
+            .into_iter()
+            .map(|tx_hash| SyncTransactionHandle::new(tx_hash, provider.clone()))
+            .collect())
+    }
+
+    /// Sets the transferred token. Returns an error if token is not supported by zkSync.
+    pub fn token(mut self, token: impl Into<TokenLike>) -> Result<Self, ClientError> {
+        let token_like = token.into();
+        let token = self
+            .wallet
+            .tokens
+            .resolve(token_like)
+            .ok_or(ClientError::UnknownToken)?;
+
+        self.token = Some(token);
+
+        Ok(self)
+    }
+
+    /// Adds the transfer to the recipient. If the provided amount is not packable,
+    /// rounds it to the closest packable amount.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn transfer(mut self, to: Address, amount: impl Into<BigUint>) -> Self {
+        let amount = closest_packable_token_amount(&amount.into());
+        self.recipients.push((to, amount));
+
+        self
+    }
+
+    /// Adds the transfer to the recipient. If the provided amount is not packable,
+    /// returns an error.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn transfer_exact(
+        mut self,
+        to: Address,
+        amount: impl Into<BigUint>,
+    ) -> Result<Self, ClientError> {
+        let amount = amount.into();
+        if !is_token_amount_packable(&amount) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.recipients.push((to, amount));
+
+        Ok(self)
+    }
+
+    /// Set the fee amount for the whole batch. If the provided fee is not packable,
+    /// rounds it to the closest packable fee amount.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee(mut self, fee: impl Into<BigUint>) -> Self {
+        let fee = closest_packable_fee_amount(&fee.into());
+        self.fee = Some(fee);
+
+        self
+    }
+
+    /// Set the fee amount for the whole batch. If the provided fee is not packable,
+    /// returns an error.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee_exact(mut self, fee: impl Into<BigUint>) -> Result<Self, ClientError> {
+        let fee = fee.into();
+        if !is_fee_amount_packable(&fee) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.fee = Some(fee);
+
+        Ok(self)
+    }
+
+    /// Sets the unix format timestamp of the first moment when transaction execution is valid.
+    pub fn valid_from(mut self, valid_from: u64) -> Self {
+        self.valid_from = Some(valid_from);
+        self
+    }
+
+    /// Sets the unix format timestamp of the last moment when transaction execution is valid.
+    pub fn valid_until(mut self, valid_until: u64) -> Self {
+        self.valid_until = Some(valid_until);
+        self
+    }
+
+    /// Sets the nonce of the first transfer, the following transfers get the subsequent nonces.
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+}
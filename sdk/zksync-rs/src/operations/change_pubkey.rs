@@ -1,5 +1,5 @@
 use num::BigUint;
-use zksync_eth_signer::EthereumSigner;
+use zksync_eth_signer::{error::SignerError, EthereumSigner};
 use zksync_types::{
     helpers::{closest_packable_fee_amount, is_fee_amount_packable},
     tokens::TxFeeTypes,
@@ -8,7 +8,11 @@ use zksync_types::{
 };
 
 use crate::{
-    error::ClientError, operations::SyncTransactionHandle, provider::Provider, wallet::Wallet,
+    error::ClientError,
+    offline::{UnsignedChangePubKey, UnsignedTx},
+    operations::SyncTransactionHandle,
+    provider::Provider,
+    wallet::Wallet,
 };
 use zksync_types::tokens::ChangePubKeyFeeTypeArg;
 
@@ -43,6 +47,24 @@ where
 
     /// Directly returns the signed change pubkey transaction for the subsequent usage.
     pub async fn tx(self) -> Result<ZkSyncTx, ClientError> {
+        let wallet = self.wallet;
+        let signed_tx = self
+            .unsigned_tx()
+            .await?
+            .sign(&wallet.signer)
+            .await
+            .map_err(ClientError::SigningError)?;
+
+        Ok(signed_tx.tx)
+    }
+
+    /// Returns the change pubkey transaction with the nonce and the fee filled in, but not signed.
+    /// It can be signed on another machine, see [offline](../offline/index.html) module.
+    pub async fn unsigned_tx(self) -> Result<UnsignedTx, ClientError> {
+        let account_id = self
+            .wallet
+            .account_id()
+            .ok_or(ClientError::SigningError(SignerError::NoSigningKey))?;
         let fee_token = self
             .fee_token
             .ok_or_else(|| ClientError::MissingRequiredField("fee_token".into()))?;
@@ -85,20 +107,16 @@ where
 
         let time_range = Default::default();
 
-        Ok(ZkSyncTx::from(
-            self.wallet
-                .signer
-                .sign_change_pubkey_tx(
-                    nonce,
-                    self.onchain_auth,
-                    fee_token,
-                    fee,
-                    time_range,
-                    Some(self.wallet.provider.network().chain_id()),
-                )
-                .await
-                .map_err(ClientError::SigningError)?,
-        ))
+        Ok(UnsignedTx::ChangePubKey(UnsignedChangePubKey {
+            account_id,
+            account: self.wallet.address(),
+            fee_token,
+            fee,
+            nonce,
+            time_range,
+            chain_id: self.wallet.provider.network().chain_id(),
+            onchain_auth: self.onchain_auth,
+        }))
     }
 
     /// Sends the transaction, returning the handle for its awaiting.
@@ -111,6 +129,14 @@ where
         Ok(SyncTransactionHandle::new(tx_hash, provider))
     }
 
+    /// Sets whether the new signing key is authorized onchain via `EthereumProvider::set_auth_pubkey_hash`
+    /// rather than by the Ethereum signature of the transaction. The authorization must be made for the
+    /// nonce of this transaction, see `Wallet::authorize_signing_key_onchain`.
+    pub fn onchain_auth(mut self, onchain_auth: bool) -> Self {
+        self.onchain_auth = onchain_auth;
+        self
+    }
+
     /// Sets the transaction fee token. Returns an error if token is not supported by zkSync.
     pub fn fee_token(mut self, token: impl Into<TokenLike>) -> Result<Self, ClientError> {
         let token_like = token.into();
@@ -11,12 +11,28 @@ use crate::{
 };
 
 pub use self::{
-    change_pubkey::ChangePubKeyBuilder, mint_nft::MintNFTBuilder, transfer::TransferBuilder,
-    transfer_nft::TransferNFTBuilder, withdraw::WithdrawBuilder, withdraw_nft::WithdrawNFTBuilder,
+    auto_fee_transfer::{AutoFeeTransferBuilder, FeePayment},
+    batch::BatchBuilder,
+    batch_transfer::BatchTransferBuilder,
+    change_pubkey::ChangePubKeyBuilder,
+    forced_exit::ForcedExitBuilder,
+    mint_nft::MintNFTBuilder,
+    order::{OrderBuilder, SignedOrder},
+    swap::{SignedSwap, SwapBuilder},
+    transfer::TransferBuilder,
+    transfer_nft::TransferNFTBuilder,
+    withdraw::WithdrawBuilder,
+    withdraw_nft::WithdrawNFTBuilder,
 };
 
+mod auto_fee_transfer;
+mod batch;
+mod batch_transfer;
 mod change_pubkey;
+mod forced_exit;
 mod mint_nft;
+mod order;
+mod swap;
 mod transfer;
 mod transfer_nft;
 mod withdraw;
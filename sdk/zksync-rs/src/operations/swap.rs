@@ -0,0 +1,191 @@
+use num::{BigUint, Zero};
+use zksync_eth_signer::EthereumSigner;
+use zksync_types::{
+    helpers::{closest_packable_fee_amount, is_fee_amount_packable, is_token_amount_packable},
+    tx::PackedEthSignature,
+    Nonce, Swap, Token, TokenLike, TxFeeTypes,
+};
+
+use crate::{
+    error::ClientError,
+    operations::{SignedOrder, SyncTransactionHandle},
+    provider::Provider,
+    wallet::Wallet,
+};
+
+/// Swap with all the Ethereum signatures required to submit it:
+/// the signature of the swap itself and the signatures of both orders.
+#[derive(Debug, Clone)]
+pub struct SignedSwap {
+    pub swap: Swap,
+    pub eth_signature: Option<PackedEthSignature>,
+    pub orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+}
+
+#[derive(Debug)]
+pub struct SwapBuilder<'a, S: EthereumSigner, P: Provider> {
+    wallet: &'a Wallet<S, P>,
+    orders: Option<(SignedOrder, SignedOrder)>,
+    amounts: Option<(BigUint, BigUint)>,
+    fee_token: Option<Token>,
+    fee: Option<BigUint>,
+    nonce: Option<Nonce>,
+}
+
+impl<'a, S, P> SwapBuilder<'a, S, P>
+where
+    S: EthereumSigner,
+    P: Provider + Clone,
+{
+    /// Initializes a swap transaction building process. The wallet is the swap submitter,
+    /// it pays the fee but doesn't have to be the owner of any of the orders.
+    pub fn new(wallet: &'a Wallet<S, P>) -> Self {
+        Self {
+            wallet,
+            orders: None,
+            amounts: None,
+            fee_token: None,
+            fee: None,
+            nonce: None,
+        }
+    }
+
+    /// Directly returns the signed swap transaction for the subsequent usage.
+    pub async fn tx(self) -> Result<SignedSwap, ClientError> {
+        let (first, second) = self
+            .orders
+            .ok_or_else(|| ClientError::MissingRequiredField("orders".into()))?;
+        let fee_token = self
+            .fee_token
+            .ok_or_else(|| ClientError::MissingRequiredField("fee_token".into()))?;
+
+        // The amounts can be omitted if both orders are not limit ones, i.e. are filled completely.
+        let amounts = match self.amounts {
+            Some(amounts) => amounts,
+            None if !first.order.amount.is_zero() && !second.order.amount.is_zero() => {
+                (first.order.amount.clone(), second.order.amount.clone())
+            }
+            None => return Err(ClientError::MissingRequiredField("amounts".into())),
+        };
+
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => {
+                let account_info = self
+                    .wallet
+                    .provider
+                    .account_info(self.wallet.address())
+                    .await?;
+                account_info.committed.nonce
+            }
+        };
+
+        let fee = match self.fee {
+            Some(fee) => fee,
+            None => {
+                let fee = self
+                    .wallet
+                    .provider
+                    .get_tx_fee(TxFeeTypes::Swap, self.wallet.address(), fee_token.id)
+                    .await?;
+                fee.total_fee
+            }
+        };
+
+        let (swap, eth_signature) = self
+            .wallet
+            .signer
+            .sign_swap((first.order, second.order), amounts, fee_token, fee, nonce)
+            .await
+            .map_err(ClientError::SigningError)?;
+
+        Ok(SignedSwap {
+            swap,
+            eth_signature,
+            orders_eth_signatures: (first.eth_signature, second.eth_signature),
+        })
+    }
+
+    /// Sends the transaction, returning the handle for its awaiting.
+    pub async fn send(self) -> Result<SyncTransactionHandle<P>, ClientError> {
+        let provider = self.wallet.provider.clone();
+
+        let signed_swap = self.tx().await?;
+        let tx_hash = provider
+            .send_swap(
+                signed_swap.swap,
+                signed_swap.eth_signature,
+                signed_swap.orders_eth_signatures,
+            )
+            .await?;
+
+        Ok(SyncTransactionHandle::new(tx_hash, provider))
+    }
+
+    /// Sets the matching orders to swap.
+    pub fn orders(mut self, first: SignedOrder, second: SignedOrder) -> Self {
+        self.orders = Some((first, second));
+        self
+    }
+
+    /// Sets the amounts of the tokens sold by the first and the second orders respectively.
+    /// Returns an error if any of the amounts is not packable.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn amounts(
+        mut self,
+        first: impl Into<BigUint>,
+        second: impl Into<BigUint>,
+    ) -> Result<Self, ClientError> {
+        let amounts = (first.into(), second.into());
+        if !is_token_amount_packable(&amounts.0) || !is_token_amount_packable(&amounts.1) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.amounts = Some(amounts);
+
+        Ok(self)
+    }
+
+    /// Sets the token the fee is paid in. Returns an error if token is not supported by zkSync.
+    pub fn fee_token(mut self, token: impl Into<TokenLike>) -> Result<Self, ClientError> {
+        let token = self
+            .wallet
+            .tokens
+            .resolve(token.into())
+            .ok_or(ClientError::UnknownToken)?;
+        self.fee_token = Some(token);
+
+        Ok(self)
+    }
+
+    /// Set the fee amount. If the amount provided is not packable,
+    /// rounds it to the closest packable fee amount.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee(mut self, fee: impl Into<BigUint>) -> Self {
+        let fee = closest_packable_fee_amount(&fee.into());
+        self.fee = Some(fee);
+
+        self
+    }
+
+    /// Set the fee amount. If the provided fee is not packable,
+    /// returns an error.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee_exact(mut self, fee: impl Into<BigUint>) -> Result<Self, ClientError> {
+        let fee = fee.into();
+        if !is_fee_amount_packable(&fee) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.fee = Some(fee);
+
+        Ok(self)
+    }
+
+    /// Sets the transaction nonce.
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+}
@@ -1,5 +1,5 @@
 use num::BigUint;
-use zksync_eth_signer::EthereumSigner;
+use zksync_eth_signer::{error::SignerError, EthereumSigner};
 use zksync_types::{
     helpers::{
         closest_packable_fee_amount, closest_packable_token_amount, is_fee_amount_packable,
@@ -10,7 +10,11 @@ use zksync_types::{
 };
 
 use crate::{
-    error::ClientError, operations::SyncTransactionHandle, provider::Provider, wallet::Wallet,
+    error::ClientError,
+    offline::{UnsignedTx, UnsignedWithdraw},
+    operations::SyncTransactionHandle,
+    provider::Provider,
+    wallet::Wallet,
 };
 
 #[derive(Debug)]
@@ -46,6 +50,24 @@ where
 
     /// Directly returns the signed withdraw transaction for the subsequent usage.
     pub async fn tx(self) -> Result<(ZkSyncTx, Option<PackedEthSignature>), ClientError> {
+        let wallet = self.wallet;
+        let signed_tx = self
+            .unsigned_tx()
+            .await?
+            .sign(&wallet.signer)
+            .await
+            .map_err(ClientError::SigningError)?;
+
+        Ok((signed_tx.tx, signed_tx.eth_signature))
+    }
+
+    /// Returns the withdraw transaction with the nonce and the fee filled in, but not signed.
+    /// It can be signed on another machine, see [offline](../offline/index.html) module.
+    pub async fn unsigned_tx(self) -> Result<UnsignedTx, ClientError> {
+        let account_id = self
+            .wallet
+            .account_id()
+            .ok_or(ClientError::SigningError(SignerError::NoSigningKey))?;
         let token = self
             .token
             .ok_or_else(|| ClientError::MissingRequiredField("token".into()))?;
@@ -83,19 +105,16 @@ where
         let valid_from = self.valid_from.unwrap_or(0);
         let valid_until = self.valid_until.unwrap_or(u64::MAX);
 
-        self.wallet
-            .signer
-            .sign_withdraw(
-                token,
-                amount,
-                fee,
-                to,
-                nonce,
-                TimeRange::new(valid_from, valid_until),
-            )
-            .await
-            .map(|(tx, sign)| (ZkSyncTx::Withdraw(Box::new(tx)), sign))
-            .map_err(ClientError::SigningError)
+        Ok(UnsignedTx::Withdraw(UnsignedWithdraw {
+            account_id,
+            from: self.wallet.address(),
+            to,
+            token,
+            amount,
+            fee,
+            nonce,
+            time_range: TimeRange::new(valid_from, valid_until),
+        }))
     }
 
     /// Sends the transaction, returning the handle for its awaiting.
@@ -6,19 +6,22 @@ use std::time::Duration;
 
 // External uses
 use async_trait::async_trait;
-use jsonrpc_core::{types::response::Output, ErrorCode};
+use jsonrpc_core::types::response::Output;
 use num::BigUint;
 
 // Workspace uses
 use zksync_types::{
     network::Network,
     tx::{PackedEthSignature, TxHash, ZkSyncTx},
-    Address, TokenLike, TxFeeTypes,
+    AccountId, Address, Swap, TokenId, TokenLike, TxFeeTypes,
 };
 
 // Local uses
-use self::messages::JsonRpcRequest;
-use crate::{error::ClientError, types::*};
+pub(crate) use self::messages::JsonRpcRequest;
+use crate::{
+    error::{ClientError, ServerErrorCode, TransportError},
+    types::*,
+};
 
 /// Returns a corresponding address for a provided network name.
 pub fn get_rpc_addr(network: Network) -> &'static str {
@@ -77,6 +80,15 @@ pub trait Provider {
     /// Requests and returns a smart contract address (for Ethereum network associated with network specified in `Provider`).
     async fn contract_address(&self) -> ResponseResult<ContractAddress>;
 
+    /// Requests and returns information about an NFT given its token ID.
+    async fn get_nft(&self, token_id: TokenId) -> ResponseResult<Option<NFTInfo>>;
+
+    /// Requests and returns the ID of the account currently owning the NFT.
+    async fn get_nft_owner(&self, token_id: TokenId) -> ResponseResult<Option<AccountId>>;
+
+    /// Requests and returns the token ID of the NFT minted by the `MintNFT` transaction.
+    async fn get_nft_id_by_tx_hash(&self, tx_hash: TxHash) -> ResponseResult<Option<TokenId>>;
+
     /// Submits a transaction to the zkSync network.
     /// Returns the hash of the created transaction.
     async fn send_tx(
@@ -93,6 +105,16 @@ pub trait Provider {
         eth_signature: Option<PackedEthSignature>,
     ) -> ResponseResult<Vec<TxHash>>;
 
+    /// Submits a swap to the zkSync network. Besides the Ethereum signature of the swap itself,
+    /// the Ethereum signatures of both orders are required.
+    /// Returns the hash of the created transaction.
+    async fn send_swap(
+        &self,
+        swap: Swap,
+        eth_signature: Option<PackedEthSignature>,
+        orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+    ) -> ResponseResult<TxHash>;
+
     /// Type of network this provider is allowing access to.
     fn network(&self) -> Network;
 }
@@ -104,8 +126,12 @@ pub struct RpcProvider {
     rpc_addr: String,
     client: reqwest::Client,
     network: Network,
+    retry_timeout: Duration,
 }
 
+/// Default upper bound of the delay between the retries of the failed `RpcProvider` requests.
+const DEFAULT_RETRY_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[async_trait]
 impl Provider for RpcProvider {
     async fn account_info(&self, address: Address) -> ResponseResult<AccountInfo> {
@@ -164,6 +190,21 @@ impl Provider for RpcProvider {
         self.send_and_deserialize(&msg).await
     }
 
+    async fn get_nft(&self, token_id: TokenId) -> ResponseResult<Option<NFTInfo>> {
+        let msg = JsonRpcRequest::get_nft(token_id);
+        self.send_and_deserialize(&msg).await
+    }
+
+    async fn get_nft_owner(&self, token_id: TokenId) -> ResponseResult<Option<AccountId>> {
+        let msg = JsonRpcRequest::get_nft_owner(token_id);
+        self.send_and_deserialize(&msg).await
+    }
+
+    async fn get_nft_id_by_tx_hash(&self, tx_hash: TxHash) -> ResponseResult<Option<TokenId>> {
+        let msg = JsonRpcRequest::get_nft_id_by_tx_hash(tx_hash);
+        self.send_and_deserialize(&msg).await
+    }
+
     async fn send_tx(
         &self,
         tx: ZkSyncTx,
@@ -182,6 +223,16 @@ impl Provider for RpcProvider {
         self.send_and_deserialize(&msg).await
     }
 
+    async fn send_swap(
+        &self,
+        swap: Swap,
+        eth_signature: Option<PackedEthSignature>,
+        orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+    ) -> ResponseResult<TxHash> {
+        let msg = JsonRpcRequest::submit_swap(swap, eth_signature, orders_eth_signatures);
+        self.send_and_deserialize(&msg).await
+    }
+
     fn network(&self) -> Network {
         self.network
     }
@@ -194,6 +245,7 @@ impl RpcProvider {
             rpc_addr: get_rpc_addr(network).into(),
             client: reqwest::Client::new(),
             network,
+            retry_timeout: DEFAULT_RETRY_TIMEOUT,
         }
     }
 
@@ -203,6 +255,7 @@ impl RpcProvider {
             rpc_addr: rpc_addr.into(),
             client: reqwest::Client::new(),
             network: Network::Unknown,
+            retry_timeout: DEFAULT_RETRY_TIMEOUT,
         }
     }
 
@@ -212,9 +265,17 @@ impl RpcProvider {
             rpc_addr: rpc_addr.into(),
             client: reqwest::Client::new(),
             network,
+            retry_timeout: DEFAULT_RETRY_TIMEOUT,
         }
     }
 
+    /// Sets the upper bound of the delay between the retries of the failed requests,
+    /// the delay is doubled after every retry. With zero timeout, the requests are not retried.
+    pub fn retry_timeout(mut self, timeout: Duration) -> Self {
+        self.retry_timeout = timeout;
+        self
+    }
+
     /// Submits a batch transaction to the zkSync network.
     /// Returns the hashes of the created transactions.
     pub async fn send_txs_batch(
@@ -249,26 +310,19 @@ impl RpcProvider {
     async fn post(&self, message: impl serde::Serialize) -> ResponseResult<serde_json::Value> {
         // Repeat requests with exponential backoff until an ok response is received to avoid
         // network and internal errors impact.
-        const MAX_DURATION: Duration = Duration::from_secs(30);
         let mut delay = Duration::from_millis(50);
         loop {
             let result = self.post_raw(&message).await;
 
-            /// Determines if the error code is recoverable or not.
-            fn is_recoverable(code: &ErrorCode) -> bool {
-                code == &ErrorCode::InternalError
-                // This is a communication error code, so we can make attempt to retry request.
-                || code == &ErrorCode::ServerError(300)
-            }
-
             let should_retry = match result.as_ref() {
-                Err(ClientError::NetworkError(..)) => true,
-                Err(ClientError::RpcError(fail)) => is_recoverable(&fail.error.code),
-                Ok(Output::Failure(fail)) => is_recoverable(&fail.error.code),
+                Err(err) => err.is_retryable(),
+                Ok(Output::Failure(fail)) => {
+                    ServerErrorCode::from(fail.error.code.clone()).is_retryable()
+                }
                 _ => false,
             };
 
-            if should_retry && delay < MAX_DURATION {
+            if should_retry && delay < self.retry_timeout {
                 delay *= 2;
                 tokio::time::sleep(delay).await;
                 continue;
@@ -276,7 +330,7 @@ impl RpcProvider {
 
             return match result? {
                 Output::Success(success) => Ok(success.result),
-                Output::Failure(failure) => Err(ClientError::RpcError(failure)),
+                Output::Failure(failure) => Err(failure.into()),
             };
         }
     }
@@ -293,18 +347,14 @@ impl RpcProvider {
             .json(&message)
             .send()
             .await
-            .map_err(|err| ClientError::NetworkError(err.to_string()))?;
+            .map_err(|err| TransportError::RequestFailed(err.to_string()))?;
         if res.status() != reqwest::StatusCode::OK {
-            let error = format!(
-                "Post query responded with a non-OK response: {}",
-                res.status()
-            );
-            return Err(ClientError::NetworkError(error));
+            return Err(TransportError::HttpStatus(res.status().as_u16()).into());
         }
         let reply: Output = res
             .json()
             .await
-            .map_err(|err| ClientError::MalformedResponse(err.to_string()))?;
+            .map_err(|err| TransportError::MalformedResponse(err.to_string()))?;
 
         Ok(reply)
     }
@@ -315,7 +365,7 @@ impl RpcProvider {
     {
         let ret = self.post(msg).await?;
         let result = serde_json::from_value(ret)
-            .map_err(|err| ClientError::MalformedResponse(err.to_string()))?;
+            .map_err(|err| TransportError::MalformedResponse(err.to_string()))?;
         Ok(result)
     }
 }
@@ -323,8 +373,8 @@ impl RpcProvider {
 mod messages {
     use serde::Serialize;
     use zksync_types::{
-        tx::{PackedEthSignature, TxEthSignature, TxHash, ZkSyncTx},
-        Address, TokenLike, TxFeeTypes,
+        tx::{PackedEthSignature, TxEthSignature, TxEthSignatureVariant, TxHash, ZkSyncTx},
+        ActionType, Address, Swap, TokenId, TokenLike, TxFeeTypes,
     };
 
     #[derive(Debug, Serialize)]
@@ -372,6 +422,24 @@ mod messages {
             Self::create("tx_submit", params)
         }
 
+        pub fn submit_swap(
+            swap: Swap,
+            eth_signature: Option<PackedEthSignature>,
+            orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+        ) -> Self {
+            let signatures = TxEthSignatureVariant::Triple(
+                eth_signature.map(TxEthSignature::EthereumSignature),
+                orders_eth_signatures
+                    .0
+                    .map(TxEthSignature::EthereumSignature),
+                orders_eth_signatures
+                    .1
+                    .map(TxEthSignature::EthereumSignature),
+            );
+            let params = json_values![ZkSyncTx::from(swap), signatures];
+            Self::create("tx_submit", params)
+        }
+
         pub fn submit_tx_batch(
             txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
             eth_signature: Option<PackedEthSignature>,
@@ -412,6 +480,18 @@ mod messages {
             Self::create("get_eth_tx_for_withdrawal", json_values![withdrawal_hash])
         }
 
+        pub fn get_nft(token_id: TokenId) -> Self {
+            Self::create("get_nft", json_values![token_id])
+        }
+
+        pub fn get_nft_owner(token_id: TokenId) -> Self {
+            Self::create("get_nft_owner", json_values![token_id])
+        }
+
+        pub fn get_nft_id_by_tx_hash(tx_hash: TxHash) -> Self {
+            Self::create("get_nft_id_by_tx_hash", json_values![tx_hash])
+        }
+
         pub fn get_tx_fee(tx_type: TxFeeTypes, address: Address, token_symbol: TokenLike) -> Self {
             let params = json_values![tx_type, address, token_symbol];
             Self::create("get_tx_fee", params)
@@ -425,5 +505,13 @@ mod messages {
             let params = json_values![tx_types, addresses, token_like];
             Self::create("get_txs_batch_fee_in_wei", params)
         }
+
+        pub fn tx_subscribe(tx_hash: TxHash, action_type: ActionType) -> Self {
+            Self::create("tx_subscribe", json_values![tx_hash, action_type])
+        }
+
+        pub fn account_subscribe(address: Address, action_type: ActionType) -> Self {
+            Self::create("account_subscribe", json_values![address, action_type])
+        }
     }
 }
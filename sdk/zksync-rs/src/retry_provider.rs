@@ -0,0 +1,287 @@
+//! Provider wrapper retrying the failed requests with an exponential backoff.
+//!
+//! The requests are split into two groups:
+//!
+//! - reads (account, token, fee and transaction info) are idempotent, so they are retried on
+//!   any transient error: network errors, timeouts and internal server errors;
+//! - submits (`send_tx` and `send_txs_batch`) are retried on transport errors, i.e. when
+//!   the request most likely haven't been processed by the server, and on the retryable server
+//!   errors, e.g. when the server is shutting down or the transactions acceptance is paused.
+//!   A timed out submission may still be accepted, so it's returned to the caller as is.
+
+// Built-in imports
+use std::{future::Future, time::Duration};
+
+// External uses
+use async_trait::async_trait;
+use num::BigUint;
+use rand::Rng;
+
+// Workspace uses
+use zksync_types::{
+    network::Network,
+    tx::{PackedEthSignature, TxHash, ZkSyncTx},
+    AccountId, Address, Swap, TokenId, TokenLike, TxFeeTypes,
+};
+
+// Local uses
+use crate::{
+    error::ClientError,
+    provider::{Provider, ResponseResult},
+    types::*,
+};
+
+/// Parameters of the `RetryProvider` retries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryConfig {
+    /// Amount of the retries after the first failed attempt.
+    pub max_retries: usize,
+    /// Delay before the first retry.
+    pub initial_delay: Duration,
+    /// Upper bound of the delay between the retries.
+    pub max_delay: Duration,
+    /// The delay is multiplied by this value after every retry.
+    pub backoff_multiplier: u32,
+    /// With this flag set, the actual delay is randomly chosen between the half
+    /// of the backoff delay and the full one, so the clients don't retry in lockstep.
+    pub jitter: bool,
+    /// Timeout of a single attempt. If not set, the attempts are not limited in time.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            backoff_multiplier: 2,
+            jitter: true,
+            timeout: Some(Duration::from_secs(30)),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Returns the delay before the retry with the provided (zero-based) number.
+    fn delay(&self, retry: usize) -> Duration {
+        let mut delay = self.initial_delay;
+        for _ in 0..retry {
+            delay = delay
+                .checked_mul(self.backoff_multiplier)
+                .unwrap_or(self.max_delay);
+            if delay >= self.max_delay {
+                break;
+            }
+        }
+        let delay = delay.min(self.max_delay);
+
+        if self.jitter {
+            let millis = delay.as_millis() as u64;
+            Duration::from_millis(rand::thread_rng().gen_range(millis / 2..=millis))
+        } else {
+            delay
+        }
+    }
+}
+
+/// Kind of the request, determines which errors are retried.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum RequestKind {
+    Read,
+    Submit,
+}
+
+impl RequestKind {
+    pub(crate) fn should_retry(self, err: &ClientError) -> bool {
+        match self {
+            Self::Read => err.is_retryable(),
+            Self::Submit => match err {
+                ClientError::TransportError(err) => err.is_retryable(),
+                ClientError::ServerError(err) => err.code.is_retryable(),
+                _ => false,
+            },
+        }
+    }
+}
+
+/// `RetryProvider` wraps another `Provider` and retries its failed requests
+/// according to the `RetryConfig`.
+///
+/// Note that `RpcProvider` already retries the failed HTTP requests on its own
+/// for up to 30 seconds, the wrapper allows to control this behavior explicitly.
+#[derive(Debug, Clone)]
+pub struct RetryProvider<P: Provider> {
+    inner: P,
+    config: RetryConfig,
+}
+
+impl<P: Provider> RetryProvider<P> {
+    /// Wraps the provider using the default `RetryConfig`.
+    pub fn new(inner: P) -> Self {
+        Self::with_config(inner, RetryConfig::default())
+    }
+
+    /// Wraps the provider using the provided `RetryConfig`.
+    pub fn with_config(inner: P, config: RetryConfig) -> Self {
+        Self { inner, config }
+    }
+
+    /// Returns the wrapped provider.
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Returns the retries configuration.
+    pub fn config(&self) -> &RetryConfig {
+        &self.config
+    }
+
+    async fn retry<F, Fut, R>(&self, request: RequestKind, mut call: F) -> ResponseResult<R>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ResponseResult<R>>,
+    {
+        let mut retry = 0;
+        loop {
+            let result = match self.config.timeout {
+                Some(timeout) => tokio::time::timeout(timeout, call())
+                    .await
+                    .unwrap_or(Err(ClientError::OperationTimeout)),
+                None => call().await,
+            };
+
+            match result {
+                Err(err) if retry < self.config.max_retries && request.should_retry(&err) => {
+                    tokio::time::sleep(self.config.delay(retry)).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync> Provider for RetryProvider<P> {
+    async fn account_info(&self, address: Address) -> ResponseResult<AccountInfo> {
+        self.retry(RequestKind::Read, || self.inner.account_info(address))
+            .await
+    }
+
+    async fn tokens(&self) -> ResponseResult<Tokens> {
+        self.retry(RequestKind::Read, || self.inner.tokens()).await
+    }
+
+    async fn tx_info(&self, tx_hash: TxHash) -> ResponseResult<TransactionInfo> {
+        self.retry(RequestKind::Read, || self.inner.tx_info(tx_hash))
+            .await
+    }
+
+    async fn get_tx_fee(
+        &self,
+        tx_type: TxFeeTypes,
+        address: Address,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> ResponseResult<Fee> {
+        let token = token.into();
+        self.retry(RequestKind::Read, || {
+            self.inner.get_tx_fee(tx_type, address, token.clone())
+        })
+        .await
+    }
+
+    async fn get_txs_batch_fee(
+        &self,
+        tx_types: Vec<TxFeeTypes>,
+        addresses: Vec<Address>,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> ResponseResult<BigUint> {
+        let token = token.into();
+        self.retry(RequestKind::Read, || {
+            self.inner
+                .get_txs_batch_fee(tx_types.clone(), addresses.clone(), token.clone())
+        })
+        .await
+    }
+
+    async fn ethop_info(&self, serial_id: u32) -> ResponseResult<EthOpInfo> {
+        self.retry(RequestKind::Read, || self.inner.ethop_info(serial_id))
+            .await
+    }
+
+    async fn get_eth_tx_for_withdrawal(
+        &self,
+        withdrawal_hash: TxHash,
+    ) -> ResponseResult<Option<String>> {
+        self.retry(RequestKind::Read, || {
+            self.inner.get_eth_tx_for_withdrawal(withdrawal_hash)
+        })
+        .await
+    }
+
+    async fn contract_address(&self) -> ResponseResult<ContractAddress> {
+        self.retry(RequestKind::Read, || self.inner.contract_address())
+            .await
+    }
+
+    async fn get_nft(&self, token_id: TokenId) -> ResponseResult<Option<NFTInfo>> {
+        self.retry(RequestKind::Read, || self.inner.get_nft(token_id))
+            .await
+    }
+
+    async fn get_nft_owner(&self, token_id: TokenId) -> ResponseResult<Option<AccountId>> {
+        self.retry(RequestKind::Read, || self.inner.get_nft_owner(token_id))
+            .await
+    }
+
+    async fn get_nft_id_by_tx_hash(&self, tx_hash: TxHash) -> ResponseResult<Option<TokenId>> {
+        self.retry(RequestKind::Read, || {
+            self.inner.get_nft_id_by_tx_hash(tx_hash)
+        })
+        .await
+    }
+
+    async fn send_tx(
+        &self,
+        tx: ZkSyncTx,
+        eth_signature: Option<PackedEthSignature>,
+    ) -> ResponseResult<TxHash> {
+        self.retry(RequestKind::Submit, || {
+            self.inner.send_tx(tx.clone(), eth_signature.clone())
+        })
+        .await
+    }
+
+    async fn send_txs_batch(
+        &self,
+        txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+        eth_signature: Option<PackedEthSignature>,
+    ) -> ResponseResult<Vec<TxHash>> {
+        self.retry(RequestKind::Submit, || {
+            self.inner
+                .send_txs_batch(txs_signed.clone(), eth_signature.clone())
+        })
+        .await
+    }
+
+    async fn send_swap(
+        &self,
+        swap: Swap,
+        eth_signature: Option<PackedEthSignature>,
+        orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+    ) -> ResponseResult<TxHash> {
+        self.retry(RequestKind::Submit, || {
+            self.inner.send_swap(
+                swap.clone(),
+                eth_signature.clone(),
+                orders_eth_signatures.clone(),
+            )
+        })
+        .await
+    }
+
+    fn network(&self) -> Network {
+        self.inner.network()
+    }
+}
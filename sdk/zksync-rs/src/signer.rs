@@ -6,13 +6,14 @@ use num::BigUint;
 use zksync_crypto::PrivateKey;
 use zksync_eth_signer::{error::SignerError, EthereumSigner};
 use zksync_types::{
+    chain_bound_ethereum_message,
     tx::{
         eip712_signature::Eip712Domain, ChangePubKey, ChangePubKeyECDSAData,
-        ChangePubKeyEIP712Data, ChangePubKeyEthAuthData, PackedEthSignature, TimeRange,
-        TxEthSignature,
+        ChangePubKeyEIP712Data, ChangePubKeyEthAuthData, EthBatchSignData, PackedEthSignature,
+        TimeRange, TxEthSignature,
     },
-    AccountId, Address, ChainId, ForcedExit, MintNFT, Nonce, PubKeyHash, Token, TokenId, Transfer,
-    Withdraw, WithdrawNFT, H256,
+    AccountId, Address, ChainId, ForcedExit, MintNFT, Nonce, Order, PubKeyHash, Swap, Token,
+    TokenId, Transfer, Withdraw, WithdrawNFT, ZkSyncTx, H256,
 };
 // Local imports
 use crate::WalletCredentials;
@@ -27,6 +28,7 @@ pub struct Signer<S: EthereumSigner> {
     pub(crate) private_key: PrivateKey,
     pub(crate) eth_signer: Option<S>,
     pub(crate) account_id: Option<AccountId>,
+    pub(crate) chain_id: Option<ChainId>,
 }
 
 impl<S: EthereumSigner> fmt::Debug for Signer<S> {
@@ -52,6 +54,7 @@ impl<S: EthereumSigner> Signer<S> {
             address,
             eth_signer,
             account_id: None,
+            chain_id: None,
         }
     }
 
@@ -76,6 +79,34 @@ impl<S: EthereumSigner> Signer<S> {
         self.account_id
     }
 
+    /// Sets the network the Ethereum messages of the transactions are bound to, so the
+    /// signatures can't be replayed on another network. The messages are not bound if the
+    /// chain id is not set, the server accepts them only during the migration window.
+    pub fn set_chain_id(&mut self, chain_id: Option<ChainId>) {
+        self.chain_id = chain_id;
+    }
+
+    pub fn get_chain_id(&self) -> Option<ChainId> {
+        self.chain_id
+    }
+
+    /// Signs the message of the transactions with the Ethereum key,
+    /// binding it to the network if the chain id is set.
+    async fn sign_eth_message(
+        &self,
+        eth_signer: &S,
+        message: &[u8],
+    ) -> Result<TxEthSignature, SignerError> {
+        match self.chain_id {
+            Some(chain_id) => {
+                eth_signer
+                    .sign_message(&chain_bound_ethereum_message(message, chain_id))
+                    .await
+            }
+            None => eth_signer.sign_message(message).await,
+        }
+    }
+
     #[deprecated]
     #[doc(hidden)]
     /// This method required only for backward compatibility with tests.
@@ -121,6 +152,9 @@ impl<S: EthereumSigner> Signer<S> {
             TxEthSignature::EIP1271Signature(..) => Err(SignerError::CustomError(
                 "Can't sign ChangePubKey message with EIP1271 signer".to_string(),
             )),
+            TxEthSignature::EIP712Signature(..) => Err(SignerError::CustomError(
+                "Can't sign ChangePubKey message with EIP712 signature".to_string(),
+            )),
         }?;
 
         change_pubkey.eth_auth_data = Some(ChangePubKeyEthAuthData::ECDSA(ChangePubKeyECDSAData {
@@ -221,7 +255,7 @@ impl<S: EthereumSigner> Signer<S> {
         let eth_signature = match &self.eth_signer {
             Some(signer) => {
                 let message = transfer.get_ethereum_sign_message(&token.symbol, token.decimals);
-                let signature = signer.sign_message(message.as_bytes()).await?;
+                let signature = self.sign_eth_message(signer, message.as_bytes()).await?;
 
                 if let TxEthSignature::EthereumSignature(packed_signature) = signature {
                     Some(packed_signature)
@@ -235,6 +269,73 @@ impl<S: EthereumSigner> Signer<S> {
         Ok((transfer, eth_signature))
     }
 
+    /// Signs the transfers of the same token to the several recipients, given as
+    /// `(recipient, amount, fee)`. Instead of signing every transfer with the Ethereum key,
+    /// the whole batch is signed once.
+    pub async fn sign_transfers_batch(
+        &self,
+        token: Token,
+        transfers: Vec<(Address, BigUint, BigUint)>,
+        nonce: Nonce,
+        time_range: TimeRange,
+    ) -> Result<(Vec<Transfer>, Option<PackedEthSignature>), SignerError> {
+        let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
+
+        let transfers = transfers
+            .into_iter()
+            .zip(*nonce..)
+            .map(|((to, amount, fee), nonce)| {
+                Transfer::new_signed(
+                    account_id,
+                    self.address,
+                    to,
+                    token.id,
+                    amount,
+                    fee,
+                    Nonce(nonce),
+                    time_range,
+                    &self.private_key,
+                )
+                .map_err(signing_failed_error)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let txs = transfers
+            .iter()
+            .map(|transfer| {
+                (
+                    ZkSyncTx::Transfer(Box::new(transfer.clone())),
+                    token.clone(),
+                )
+            })
+            .collect();
+        let eth_signature = self.sign_batch(txs).await?;
+
+        Ok((transfers, eth_signature))
+    }
+
+    /// Signs the batch message of the account transactions with the Ethereum key, given
+    /// the transactions along with their tokens. Returns `None` if there is no Ethereum signer.
+    pub async fn sign_batch(
+        &self,
+        txs: Vec<(ZkSyncTx, Token)>,
+    ) -> Result<Option<PackedEthSignature>, SignerError> {
+        let signer = match &self.eth_signer {
+            Some(signer) => signer,
+            None => return Ok(None),
+        };
+        let txs = txs
+            .into_iter()
+            .map(|(tx, token)| (tx, token, self.address))
+            .collect();
+        let message = EthBatchSignData::get_batch_sign_message(txs);
+
+        match self.sign_eth_message(signer, &message).await? {
+            TxEthSignature::EthereumSignature(packed_signature) => Ok(Some(packed_signature)),
+            _ => Err(SignerError::MissingEthSigner),
+        }
+    }
+
     pub async fn sign_withdraw(
         &self,
         token: Token,
@@ -262,7 +363,7 @@ impl<S: EthereumSigner> Signer<S> {
         let eth_signature = match &self.eth_signer {
             Some(signer) => {
                 let message = withdraw.get_ethereum_sign_message(&token.symbol, token.decimals);
-                let signature = signer.sign_message(message.as_bytes()).await?;
+                let signature = self.sign_eth_message(signer, message.as_bytes()).await?;
 
                 if let TxEthSignature::EthereumSignature(packed_signature) = signature {
                     Some(packed_signature)
@@ -300,7 +401,7 @@ impl<S: EthereumSigner> Signer<S> {
         let eth_signature = match &self.eth_signer {
             Some(signer) => {
                 let message = forced_exit.get_ethereum_sign_message(&token.symbol, token.decimals);
-                let signature = signer.sign_message(message.as_bytes()).await?;
+                let signature = self.sign_eth_message(signer, message.as_bytes()).await?;
 
                 if let TxEthSignature::EthereumSignature(packed_signature) = signature {
                     Some(packed_signature)
@@ -340,7 +441,7 @@ impl<S: EthereumSigner> Signer<S> {
             Some(signer) => {
                 let message =
                     mint_nft.get_ethereum_sign_message(&fee_token.symbol, fee_token.decimals);
-                let signature = signer.sign_message(message.as_bytes()).await?;
+                let signature = self.sign_eth_message(signer, message.as_bytes()).await?;
 
                 if let TxEthSignature::EthereumSignature(packed_signature) = signature {
                     Some(packed_signature)
@@ -382,7 +483,7 @@ impl<S: EthereumSigner> Signer<S> {
             Some(signer) => {
                 let message =
                     withdraw_nft.get_ethereum_sign_message(&fee_token.symbol, fee_token.decimals);
-                let signature = signer.sign_message(message.as_bytes()).await?;
+                let signature = self.sign_eth_message(signer, message.as_bytes()).await?;
 
                 if let TxEthSignature::EthereumSignature(packed_signature) = signature {
                     Some(packed_signature)
@@ -395,4 +496,93 @@ impl<S: EthereumSigner> Signer<S> {
 
         Ok((withdraw_nft, eth_signature))
     }
+
+    /// Signs the order to exchange `token_sell` for `token_buy` at the provided ratio.
+    /// With zero `amount`, the order is a limit one, i.e. it can be filled by several swaps.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn sign_order(
+        &self,
+        recipient: Address,
+        token_sell: Token,
+        token_buy: Token,
+        price: (BigUint, BigUint),
+        amount: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+    ) -> Result<(Order, Option<PackedEthSignature>), SignerError> {
+        let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
+
+        let order = Order::new_signed(
+            account_id,
+            recipient,
+            nonce,
+            token_sell.id,
+            token_buy.id,
+            price,
+            amount,
+            time_range,
+            &self.private_key,
+        )
+        .map_err(signing_failed_error)?;
+
+        let eth_signature = match &self.eth_signer {
+            Some(signer) => {
+                let message = order.get_ethereum_sign_message(
+                    &token_sell.symbol,
+                    &token_buy.symbol,
+                    token_sell.decimals,
+                );
+                let signature = self.sign_eth_message(signer, message.as_bytes()).await?;
+
+                if let TxEthSignature::EthereumSignature(packed_signature) = signature {
+                    Some(packed_signature)
+                } else {
+                    return Err(SignerError::MissingEthSigner);
+                }
+            }
+            _ => None,
+        };
+
+        Ok((order, eth_signature))
+    }
+
+    /// Signs the swap of the two orders, submitted by this account.
+    pub async fn sign_swap(
+        &self,
+        orders: (Order, Order),
+        amounts: (BigUint, BigUint),
+        fee_token: Token,
+        fee: BigUint,
+        nonce: Nonce,
+    ) -> Result<(Swap, Option<PackedEthSignature>), SignerError> {
+        let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
+
+        let swap = Swap::new_signed(
+            account_id,
+            self.address,
+            nonce,
+            orders,
+            amounts,
+            fee,
+            fee_token.id,
+            &self.private_key,
+        )
+        .map_err(signing_failed_error)?;
+
+        let eth_signature = match &self.eth_signer {
+            Some(signer) => {
+                let message = swap.get_ethereum_sign_message(&fee_token.symbol, fee_token.decimals);
+                let signature = self.sign_eth_message(signer, message.as_bytes()).await?;
+
+                if let TxEthSignature::EthereumSignature(packed_signature) = signature {
+                    Some(packed_signature)
+                } else {
+                    return Err(SignerError::MissingEthSigner);
+                }
+            }
+            _ => None,
+        };
+
+        Ok((swap, eth_signature))
+    }
 }
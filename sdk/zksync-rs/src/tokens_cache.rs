@@ -1,6 +1,12 @@
+use num::BigUint;
 use std::collections::HashMap;
 use zksync_types::{Token, TokenLike};
 
+use crate::{
+    error::ClientError,
+    utils::{format_units, parse_fee_amount, parse_token_amount, RoundedAmount},
+};
+
 #[derive(Debug, Clone)]
 pub struct TokensCache {
     tokens: HashMap<String, Token>,
@@ -26,4 +32,41 @@ impl TokensCache {
     pub fn is_eth(&self, token: TokenLike) -> bool {
         token.is_eth()
     }
+
+    /// Parses the decimal amount of the token, e.g. "1.5", into the closest packable
+    /// token amount in the smallest units, reporting the rounding.
+    pub fn parse_amount(
+        &self,
+        token: impl Into<TokenLike>,
+        value: &str,
+    ) -> Result<RoundedAmount, ClientError> {
+        let token = self
+            .resolve(token.into())
+            .ok_or(ClientError::UnknownToken)?;
+        parse_token_amount(value, token.decimals)
+    }
+
+    /// Same as `TokensCache::parse_amount`, but rounds the amount to the closest packable fee.
+    pub fn parse_fee(
+        &self,
+        token: impl Into<TokenLike>,
+        value: &str,
+    ) -> Result<RoundedAmount, ClientError> {
+        let token = self
+            .resolve(token.into())
+            .ok_or(ClientError::UnknownToken)?;
+        parse_fee_amount(value, token.decimals)
+    }
+
+    /// Formats the amount of the token in the smallest units as a decimal string.
+    pub fn format_amount(
+        &self,
+        token: impl Into<TokenLike>,
+        amount: &BigUint,
+    ) -> Result<String, ClientError> {
+        let token = self
+            .resolve(token.into())
+            .ok_or(ClientError::UnknownToken)?;
+        Ok(format_units(amount, token.decimals))
+    }
 }
@@ -17,6 +17,22 @@ pub struct NFT {
     pub content_hash: H256,
 }
 
+/// NFT info as returned by the server, including its owner-independent details.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NFTInfo {
+    pub id: TokenId,
+    pub content_hash: H256,
+    pub creator_id: AccountId,
+    pub creator_address: Address,
+    pub serial_id: u32,
+    /// Address of the NFT on the L1.
+    pub address: Address,
+    pub symbol: String,
+    pub current_factory: Address,
+    pub withdrawn_factory: Option<Address>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountState {
@@ -30,14 +46,15 @@ pub struct AccountState {
 #[serde(rename_all = "camelCase")]
 pub struct DepositingFunds {
     #[serde(with = "BigUintSerdeAsRadix10Str")]
-    amount: BigUint,
-    expected_accept_block: u64,
+    pub amount: BigUint,
+    pub expected_accept_block: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct DepositingAccountBalances {
-    balances: HashMap<String, DepositingFunds>,
+    /// Deposits detected by zkSync but not executed yet, by the token symbol.
+    pub balances: HashMap<String, DepositingFunds>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,11 +13,13 @@ use zksync_types::{AccountId, U256};
 use crate::{error::ClientError, provider::Provider, wallet::Wallet};
 
 // Public re-exports.
-pub use zksync_types::helpers::{
+pub use zksync_types::packing::{
     closest_greater_or_eq_packable_fee_amount, closest_greater_or_eq_packable_token_amount,
     closest_packable_fee_amount, closest_packable_token_amount, is_fee_amount_packable,
-    is_token_amount_packable, pack_fee_amount, pack_token_amount,
+    is_token_amount_packable, pack_fee_amount, pack_token_amount, unpack_fee_amount,
+    unpack_token_amount,
 };
+pub use zksync_utils::format_units;
 
 /// Generates a new `PrivateKey` from seed using a deterministic algorithm:
 /// seed is hashed via `sha256` hash (twice), and the output treated as a `PrivateKey`.
@@ -88,6 +90,87 @@ where
     wallet.account_id()
 }
 
+/// Parses the decimal amount of tokens into the amount in the smallest token units.
+/// Behaves just like ethers.utils.parseUnits, i.e. `parse_units("1.5", 18)` is `1.5 * 10^18`.
+///
+/// Returns `ClientError::IncorrectAmount` if the value is not a decimal number or has more
+/// decimal places than the token has.
+pub fn parse_units(value: &str, decimals: u8) -> Result<BigUint, ClientError> {
+    let (integer, fraction) = match value.find('.') {
+        Some(pos) => (&value[..pos], &value[pos + 1..]),
+        None => (value, ""),
+    };
+
+    if (integer.is_empty() && fraction.is_empty())
+        || !integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(ClientError::IncorrectAmount(format!(
+            "'{}' is not a decimal number",
+            value
+        )));
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals as usize {
+        return Err(ClientError::IncorrectAmount(format!(
+            "'{}' has more than {} decimal places",
+            value, decimals
+        )));
+    }
+
+    let digits = format!(
+        "{}{}{}",
+        integer,
+        fraction,
+        "0".repeat(decimals as usize - fraction.len())
+    );
+    Ok(digits.parse().expect("digits are checked above"))
+}
+
+/// Amount rounded to the closest packable one, which can be used in the transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundedAmount {
+    /// Packable amount, never greater than the requested one.
+    pub amount: BigUint,
+    /// Amount before the rounding.
+    pub requested: BigUint,
+}
+
+impl RoundedAmount {
+    /// Returns `true` if the amount differs from the requested one.
+    pub fn is_rounded(&self) -> bool {
+        self.amount != self.requested
+    }
+
+    /// Returns the part of the requested amount lost due to the rounding.
+    pub fn rounding_loss(&self) -> BigUint {
+        &self.requested - &self.amount
+    }
+}
+
+/// Parses the decimal amount of tokens, see `parse_units`, and rounds it to the closest
+/// packable token amount.
+pub fn parse_token_amount(value: &str, decimals: u8) -> Result<RoundedAmount, ClientError> {
+    let requested = parse_units(value, decimals)?;
+    Ok(RoundedAmount {
+        amount: closest_packable_token_amount(&requested),
+        requested,
+    })
+}
+
+/// Parses the decimal amount of tokens, see `parse_units`, and rounds it to the closest
+/// packable fee amount.
+pub fn parse_fee_amount(value: &str, decimals: u8) -> Result<RoundedAmount, ClientError> {
+    let requested = parse_units(value, decimals)?;
+    Ok(RoundedAmount {
+        amount: closest_packable_fee_amount(&requested),
+        requested,
+    })
+}
+
 /// Converts `U256` into the corresponding `BigUint` value.
 pub fn u256_to_biguint(value: U256) -> BigUint {
     let mut bytes = [0u8; 32];
@@ -133,6 +216,59 @@ mod tests {
         biguint_u256_conversion_roundrip(u256)
     }
 
+    #[test]
+    fn test_parse_units() {
+        // Test vector of (decimals, input, expected amount)
+        let vectors = vec![
+            (18, "1", "1000000000000000000"),
+            (18, "1.5", "1500000000000000000"),
+            (18, "0.000000000000000001", "1"),
+            (6, ".25", "250000"),
+            (6, "12.", "12000000"),
+            (6, "1.100000000", "1100000"),
+            (0, "42", "42"),
+        ];
+
+        for (decimals, input, expected) in vectors {
+            let expected: BigUint = expected.parse().unwrap();
+            let parsed = parse_units(input, decimals).unwrap();
+            assert_eq!(parsed, expected, "{} with {} decimals", input, decimals);
+            // Formatting restores the value, up to the trailing zeros.
+            assert_eq!(
+                parse_units(&format_units(&parsed, decimals), decimals).unwrap(),
+                parsed
+            );
+        }
+
+        for &(decimals, input) in &[(18, "."), (18, "-1"), (18, "1e18"), (6, "0.0000001")] {
+            assert!(matches!(
+                parse_units(input, decimals),
+                Err(ClientError::IncorrectAmount(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_token_amount_rounding() {
+        let amount = parse_token_amount("1.5", 18).unwrap();
+        assert!(!amount.is_rounded());
+        assert_eq!(
+            amount.amount,
+            BigUint::from(15u64) * BigUint::from(10u64).pow(17)
+        );
+
+        // 35 bits of mantissa are not enough for this amount.
+        let amount = parse_token_amount("123456789.123456789", 18).unwrap();
+        assert!(amount.is_rounded());
+        assert!(is_token_amount_packable(&amount.amount));
+        assert_eq!(&amount.amount + amount.rounding_loss(), amount.requested);
+
+        // Fee mantissa is even shorter.
+        let fee = parse_fee_amount("0.123456789", 18).unwrap();
+        assert!(fee.is_rounded());
+        assert!(is_fee_amount_packable(&fee.amount));
+    }
+
     #[test]
     fn test_private_key_from_seed_too_short() {
         let short_seed = &[42; 30];
@@ -1,6 +1,6 @@
 use num::BigUint;
 use zksync_eth_signer::EthereumSigner;
-use zksync_types::{AccountId, Address, TokenId, TokenLike};
+use zksync_types::{network::Network, AccountId, Address, Nonce, TokenId, TokenLike};
 
 use crate::{
     credentials::WalletCredentials,
@@ -30,6 +30,12 @@ where
 
         let mut signer = Signer::with_credentials(credentials);
         signer.set_account_id(account_info.id);
+        // The transactions are bound to the network, unless it's not a real one.
+        let chain_id = match provider.network() {
+            Network::Unknown | Network::Test => None,
+            network => Some(network.chain_id()),
+        };
+        signer.set_chain_id(chain_id);
 
         let tokens = TokensCache::new(provider.tokens().await?);
 
@@ -133,6 +139,22 @@ where
         TransferBuilder::new(self)
     }
 
+    /// Initializes `Transfer` transaction sending, with the fee token selected automatically
+    /// among the tokens of the account.
+    pub fn start_auto_fee_transfer(&self) -> AutoFeeTransferBuilder<'_, S, P> {
+        AutoFeeTransferBuilder::new(self)
+    }
+
+    /// Initializes sending of the `Transfer` transactions batch to several recipients.
+    pub fn start_batch_transfer(&self) -> BatchTransferBuilder<'_, S, P> {
+        BatchTransferBuilder::new(self)
+    }
+
+    /// Initializes sending of the transactions batch signed by the Ethereum key at once.
+    pub fn start_batch(&self) -> BatchBuilder<'_, S, P> {
+        BatchBuilder::new(self)
+    }
+
     /// Initializes `TransferNFT` transaction sending.
     pub fn start_transfer_nft(&self) -> TransferNFTBuilder<'_, S, P> {
         TransferNFTBuilder::new(self)
@@ -148,6 +170,27 @@ where
         WithdrawBuilder::new(self)
     }
 
+    /// Initializes `ForcedExit` transaction sending.
+    pub fn start_forced_exit(&self) -> ForcedExitBuilder<'_, S, P> {
+        ForcedExitBuilder::new(self)
+    }
+
+    /// Sends the `ForcedExit` transaction, withdrawing all the funds of the token from the target
+    /// account to its Ethereum address. The fee is paid by the wallet in the same token.
+    ///
+    /// Returns `ClientError::TargetSigningKeySet` if the target account has the signing key set.
+    pub async fn force_exit(
+        &self,
+        target: Address,
+        token: impl Into<TokenLike>,
+    ) -> Result<SyncTransactionHandle<P>, ClientError> {
+        self.start_forced_exit()
+            .target(target)
+            .token(token)?
+            .send()
+            .await
+    }
+
     /// Initializes `MintNFT` transaction sending.
     pub fn start_mint_nft(&self) -> MintNFTBuilder<'_, S, P> {
         MintNFTBuilder::new(self)
@@ -158,6 +201,61 @@ where
         WithdrawNFTBuilder::new(self)
     }
 
+    /// Initializes signing of the `Order` to be executed by a swap.
+    pub fn start_order(&self) -> OrderBuilder<'_, S, P> {
+        OrderBuilder::new(self)
+    }
+
+    /// Initializes `Swap` transaction sending, the wallet submits the swap and pays the fee.
+    pub fn start_swap(&self) -> SwapBuilder<'_, S, P> {
+        SwapBuilder::new(self)
+    }
+
+    /// Authorizes the signing key of the wallet onchain for the next `ChangePubKey` transaction
+    /// and waits for the Ethereum transaction to be mined. Does nothing if the key is already
+    /// authorized. Returns the nonce the key is authorized for.
+    ///
+    /// This is the way to set the signing key for the accounts whose Ethereum wallet can't produce
+    /// the message signature, the key is then set via `ChangePubKeyBuilder::onchain_auth`.
+    pub async fn authorize_signing_key_onchain(
+        &self,
+        ethereum: &EthereumProvider<S>,
+    ) -> Result<Nonce, ClientError> {
+        let nonce = self.account_info().await?.committed.nonce;
+        let pub_key_hash = &self.signer.pubkey_hash;
+
+        if !ethereum
+            .is_pubkey_hash_authorized(self.address(), pub_key_hash, nonce)
+            .await?
+        {
+            let tx_hash = ethereum.set_auth_pubkey_hash(pub_key_hash, nonce).await?;
+            let receipt = ethereum.wait_for_tx(tx_hash).await?;
+            if receipt.status != Some(1.into()) {
+                return Err(ClientError::EthereumTxFailed(tx_hash));
+            }
+        }
+
+        Ok(nonce)
+    }
+
+    /// Sets the signing key of the wallet with the onchain authorization: performs
+    /// `Wallet::authorize_signing_key_onchain` and then sends the `ChangePubKey` transaction
+    /// paying the fee in the given token.
+    pub async fn change_pubkey_onchain(
+        &self,
+        ethereum: &EthereumProvider<S>,
+        fee_token: impl Into<TokenLike>,
+    ) -> Result<SyncTransactionHandle<P>, ClientError> {
+        let nonce = self.authorize_signing_key_onchain(ethereum).await?;
+
+        self.start_change_pubkey()
+            .onchain_auth(true)
+            .fee_token(fee_token)?
+            .nonce(nonce)
+            .send()
+            .await
+    }
+
     /// Creates an `EthereumProvider` to interact with the Ethereum network.
     ///
     /// Returns an error if wallet was created without providing an Ethereum private key.
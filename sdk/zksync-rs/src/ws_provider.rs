@@ -0,0 +1,255 @@
+//! Provider interacting with the zkSync node via its WebSocket JSON RPC interface.
+//!
+//! Besides the regular `Provider` requests, the WebSocket connection allows to subscribe
+//! to the transaction status and account updates, instead of polling the server.
+
+// Built-in imports
+use std::fmt;
+
+// External uses
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use jsonrpc_core::ErrorCode;
+use jsonrpc_core_client::{transports::ws, RpcError, TypedClient};
+use num::BigUint;
+
+// Workspace uses
+use zksync_types::{
+    network::Network,
+    tx::{PackedEthSignature, TxHash, ZkSyncTx},
+    AccountId, ActionType, Address, Swap, TokenId, TokenLike, TxFeeTypes,
+};
+
+// Local uses
+use crate::{
+    error::{ClientError, ServerError, TransportError},
+    provider::{JsonRpcRequest, Provider, ResponseResult},
+    types::*,
+};
+
+/// Returns a corresponding WebSocket address for a provided network name.
+pub fn get_ws_addr(network: Network) -> &'static str {
+    match network {
+        Network::Mainnet => "wss://api.zksync.io/jsrpc-ws",
+        Network::Rinkeby => "wss://rinkeby-api.zksync.io/jsrpc-ws",
+        Network::Ropsten => "wss://ropsten-api.zksync.io/jsrpc-ws",
+        Network::Localhost => "ws://127.0.0.1:3031",
+        Network::Unknown => panic!("Attempt to create a provider from an unknown network"),
+        Network::Test => panic!("Attempt to create a provider from an test network"),
+        Network::Goerli => "wss://goerli-api.zksync.io/jsrpc-ws",
+    }
+}
+
+fn rpc_error(err: RpcError) -> ClientError {
+    match err {
+        // The client is built on top of another `jsonrpc-core` version, so the code is converted.
+        RpcError::JsonRpcError(err) => ServerError {
+            code: ErrorCode::from(err.code.code()).into(),
+            message: err.message,
+            data: err.data,
+        }
+        .into(),
+        RpcError::ParseError(_, err) => TransportError::MalformedResponse(err.to_string()).into(),
+        err => TransportError::RequestFailed(err.to_string()).into(),
+    }
+}
+
+/// `WsProvider` is capable of interacting with the zkSync node via its
+/// WebSocket JSON RPC interface.
+///
+/// Unlike `RpcProvider`, the requests are not retried: the connection is kept open,
+/// and it's up to the caller to reconnect once it's lost.
+#[derive(Clone)]
+pub struct WsProvider {
+    client: TypedClient,
+    network: Network,
+}
+
+impl fmt::Debug for WsProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WsProvider")
+            .field("network", &self.network)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Provider for WsProvider {
+    async fn account_info(&self, address: Address) -> ResponseResult<AccountInfo> {
+        self.request(JsonRpcRequest::account_info(address)).await
+    }
+
+    async fn tokens(&self) -> ResponseResult<Tokens> {
+        self.request(JsonRpcRequest::tokens()).await
+    }
+
+    async fn tx_info(&self, tx_hash: TxHash) -> ResponseResult<TransactionInfo> {
+        self.request(JsonRpcRequest::tx_info(tx_hash)).await
+    }
+
+    async fn get_tx_fee(
+        &self,
+        tx_type: TxFeeTypes,
+        address: Address,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> ResponseResult<Fee> {
+        let msg = JsonRpcRequest::get_tx_fee(tx_type, address, token.into());
+        self.request(msg).await
+    }
+
+    async fn get_txs_batch_fee(
+        &self,
+        tx_types: Vec<TxFeeTypes>,
+        addresses: Vec<Address>,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> ResponseResult<BigUint> {
+        let msg = JsonRpcRequest::get_txs_batch_fee_in_wei(tx_types, addresses, token.into());
+
+        let batch_fee: BatchFee = self.request(msg).await?;
+        Ok(batch_fee.total_fee)
+    }
+
+    async fn ethop_info(&self, serial_id: u32) -> ResponseResult<EthOpInfo> {
+        self.request(JsonRpcRequest::ethop_info(serial_id)).await
+    }
+
+    async fn get_eth_tx_for_withdrawal(
+        &self,
+        withdrawal_hash: TxHash,
+    ) -> ResponseResult<Option<String>> {
+        self.request(JsonRpcRequest::eth_tx_for_withdrawal(withdrawal_hash))
+            .await
+    }
+
+    async fn contract_address(&self) -> ResponseResult<ContractAddress> {
+        self.request(JsonRpcRequest::contract_address()).await
+    }
+
+    async fn get_nft(&self, token_id: TokenId) -> ResponseResult<Option<NFTInfo>> {
+        self.request(JsonRpcRequest::get_nft(token_id)).await
+    }
+
+    async fn get_nft_owner(&self, token_id: TokenId) -> ResponseResult<Option<AccountId>> {
+        self.request(JsonRpcRequest::get_nft_owner(token_id)).await
+    }
+
+    async fn get_nft_id_by_tx_hash(&self, tx_hash: TxHash) -> ResponseResult<Option<TokenId>> {
+        self.request(JsonRpcRequest::get_nft_id_by_tx_hash(tx_hash))
+            .await
+    }
+
+    async fn send_tx(
+        &self,
+        tx: ZkSyncTx,
+        eth_signature: Option<PackedEthSignature>,
+    ) -> ResponseResult<TxHash> {
+        self.request(JsonRpcRequest::submit_tx(tx, eth_signature))
+            .await
+    }
+
+    async fn send_txs_batch(
+        &self,
+        txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+        eth_signature: Option<PackedEthSignature>,
+    ) -> ResponseResult<Vec<TxHash>> {
+        self.request(JsonRpcRequest::submit_tx_batch(txs_signed, eth_signature))
+            .await
+    }
+
+    async fn send_swap(
+        &self,
+        swap: Swap,
+        eth_signature: Option<PackedEthSignature>,
+        orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+    ) -> ResponseResult<TxHash> {
+        self.request(JsonRpcRequest::submit_swap(
+            swap,
+            eth_signature,
+            orders_eth_signatures,
+        ))
+        .await
+    }
+
+    fn network(&self) -> Network {
+        self.network
+    }
+}
+
+impl WsProvider {
+    /// Connects to the desired zkSync network.
+    pub async fn new(network: Network) -> ResponseResult<Self> {
+        Self::from_addr_and_network(get_ws_addr(network), network).await
+    }
+
+    /// Connects to a custom address and the desired zkSync network.
+    pub async fn from_addr_and_network(
+        ws_addr: impl AsRef<str>,
+        network: Network,
+    ) -> ResponseResult<Self> {
+        let client = ws::try_connect::<TypedClient>(ws_addr.as_ref())
+            .map_err(rpc_error)?
+            .await
+            .map_err(rpc_error)?;
+
+        Ok(Self { client, network })
+    }
+
+    /// Subscribes to the status of the transaction. The stream yields the transaction
+    /// info once the transaction is committed (or verified, depending on `action_type`).
+    pub fn subscribe_tx(
+        &self,
+        tx_hash: TxHash,
+        action_type: ActionType,
+    ) -> ResponseResult<impl Stream<Item = ResponseResult<TransactionInfo>>> {
+        self.subscribe(
+            JsonRpcRequest::tx_subscribe(tx_hash, action_type),
+            "tx",
+            "tx_unsubscribe",
+            "TransactionInfo",
+        )
+    }
+
+    /// Subscribes to the state of the account. The stream yields the account state every time
+    /// a block changing it is committed (or verified, depending on `action_type`).
+    pub fn subscribe_account(
+        &self,
+        address: Address,
+        action_type: ActionType,
+    ) -> ResponseResult<impl Stream<Item = ResponseResult<AccountState>>> {
+        self.subscribe(
+            JsonRpcRequest::account_subscribe(address, action_type),
+            "account",
+            "account_unsubscribe",
+            "AccountState",
+        )
+    }
+
+    async fn request<R>(&self, msg: JsonRpcRequest) -> ResponseResult<R>
+    where
+        R: serde::de::DeserializeOwned + 'static,
+    {
+        self.client
+            .call_method(&msg.method, "response", msg.params)
+            .await
+            .map_err(rpc_error)
+    }
+
+    /// The subscription is cancelled once the stream is dropped.
+    fn subscribe<R>(
+        &self,
+        msg: JsonRpcRequest,
+        topic: &str,
+        unsubscribe: &str,
+        returns: &'static str,
+    ) -> ResponseResult<impl Stream<Item = ResponseResult<R>>>
+    where
+        R: serde::de::DeserializeOwned + 'static,
+    {
+        let stream = self
+            .client
+            .subscribe(&msg.method, msg.params, topic, unsubscribe, returns)
+            .map_err(rpc_error)?;
+
+        Ok(stream.map(|item| item.map_err(rpc_error)))
+    }
+}
@@ -513,22 +513,29 @@ mod wallet_tests {
     use super::*;
     use num::{BigUint, ToPrimitive};
     use zksync::{
-        error::ClientError,
+        error::{ClientError, ServerError, ServerErrorCode},
+        network_config::verify_contract_address,
+        offline::UnsignedTx,
+        operations::FeePayment,
         provider::Provider,
         signer::Signer,
         types::{
-            AccountInfo, AccountState, BlockStatus, ContractAddress, EthOpInfo, Fee, Tokens,
-            TransactionInfo,
+            AccountInfo, AccountState, BlockStatus, ContractAddress, EthOpInfo, Fee, NFTInfo,
+            Tokens, TransactionInfo,
         },
-        Network, Wallet, WalletCredentials,
+        Network, NetworkConfig, Wallet, WalletCredentials,
     };
     use zksync_eth_signer::PrivateKeySigner;
     use zksync_types::{
+        chain_bound_ethereum_message,
         tokens::get_genesis_token_list,
-        tx::{PackedEthSignature, TxHash},
-        Address, PubKeyHash, TokenId, TokenLike, TxFeeTypes, ZkSyncTx, H256,
+        tx::{ChangePubKeyEthAuthData, EthBatchSignData, PackedEthSignature, TxHash},
+        Address, OutputFeeType, PubKeyHash, Swap, TokenId, TokenLike, TxFeeTypes, ZkSyncTx, H256,
     };
 
+    /// Address of the account without the signing key set.
+    const KEYLESS_ACCOUNT: Address = Address::repeat_byte(0xfe);
+
     #[derive(Debug, Clone)]
     /// Provides some hardcoded values the `Provider` responsible to
     /// without communicating with the network
@@ -555,15 +562,23 @@ mod wallet_tests {
         /// Returns the example `AccountInfo` instance:
         ///  - assigns the '42' value to account_id;
         ///  - assigns the PubKeyHash to match the wallet's signer's PubKeyHash
-        ///  - adds single entry of "DAI" token to the committed balances;
+        ///  - adds entries of "DAI" and "TUSD" tokens to the committed balances;
         ///  - adds single entry of "USDC" token to the verified balances.
         async fn account_info(&self, address: Address) -> Result<AccountInfo, ClientError> {
             let mut committed_balances = HashMap::new();
             committed_balances.insert("DAI".into(), BigUint::from(12345_u32).into());
+            committed_balances.insert("TUSD".into(), BigUint::from(500_u32).into());
 
             let mut verified_balances = HashMap::new();
             verified_balances.insert("USDC".into(), BigUint::from(98765_u32).into());
 
+            // The accounts created by the deposits only don't have the signing key set.
+            let pub_key_hash = if address == KEYLESS_ACCOUNT {
+                PubKeyHash::default()
+            } else {
+                self.pub_key_hash().await
+            };
+
             Ok(AccountInfo {
                 address,
                 id: Some(AccountId(42)),
@@ -571,7 +586,7 @@ mod wallet_tests {
                 committed: AccountState {
                     balances: committed_balances,
                     nonce: Nonce(0),
-                    pub_key_hash: self.pub_key_hash().await,
+                    pub_key_hash,
                     ..Default::default()
                 },
                 verified: AccountState {
@@ -607,22 +622,32 @@ mod wallet_tests {
             unreachable!()
         }
 
+        /// Returns the fee of 10 per transaction, "DAI" is not accepted to pay fees in.
         async fn get_tx_fee(
             &self,
             _tx_type: TxFeeTypes,
             _address: Address,
-            _token: impl Into<TokenLike> + Send + 'async_trait,
+            token: impl Into<TokenLike> + Send + 'async_trait,
         ) -> Result<Fee, ClientError> {
-            unreachable!()
+            let total_fee = mock_fee(token.into(), 1)?;
+            Ok(Fee {
+                fee_type: OutputFeeType::Transfer,
+                gas_tx_amount: Default::default(),
+                gas_price_wei: Default::default(),
+                gas_fee: Default::default(),
+                zkp_fee: total_fee.clone(),
+                total_fee,
+            })
         }
 
+        /// Returns the fee of 10 per transaction, "DAI" is not accepted to pay fees in.
         async fn get_txs_batch_fee(
             &self,
-            _tx_types: Vec<TxFeeTypes>,
+            tx_types: Vec<TxFeeTypes>,
             _addresses: Vec<Address>,
-            _token: impl Into<TokenLike> + Send + 'async_trait,
+            token: impl Into<TokenLike> + Send + 'async_trait,
         ) -> Result<BigUint, ClientError> {
-            unreachable!()
+            mock_fee(token.into(), tx_types.len())
         }
 
         async fn ethop_info(&self, _serial_id: u32) -> Result<EthOpInfo, ClientError> {
@@ -646,6 +671,24 @@ mod wallet_tests {
             })
         }
 
+        async fn get_nft(&self, _token_id: TokenId) -> Result<Option<NFTInfo>, ClientError> {
+            unreachable!()
+        }
+
+        async fn get_nft_owner(
+            &self,
+            _token_id: TokenId,
+        ) -> Result<Option<AccountId>, ClientError> {
+            unreachable!()
+        }
+
+        async fn get_nft_id_by_tx_hash(
+            &self,
+            _tx_hash: TxHash,
+        ) -> Result<Option<TokenId>, ClientError> {
+            unreachable!()
+        }
+
         async fn send_tx(
             &self,
             _tx: ZkSyncTx,
@@ -662,11 +705,33 @@ mod wallet_tests {
             unreachable!()
         }
 
+        async fn send_swap(
+            &self,
+            _swap: Swap,
+            _eth_signature: Option<PackedEthSignature>,
+            _orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+        ) -> Result<TxHash, ClientError> {
+            unreachable!()
+        }
+
         fn network(&self) -> Network {
             self.network
         }
     }
 
+    fn mock_fee(token: TokenLike, txs_count: usize) -> Result<BigUint, ClientError> {
+        // "DAI" has the id 1 in the tokens returned by `MockProvider`.
+        if token == TokenLike::Id(TokenId(1)) {
+            return Err(ServerError {
+                code: ServerErrorCode::InappropriateFeeToken,
+                message: "Chosen token is not suitable for paying fees".to_string(),
+                data: None,
+            }
+            .into());
+        }
+        Ok(BigUint::from(10 * txs_count as u64))
+    }
+
     async fn get_test_wallet(
         private_key_raw: &[u8],
         network: Network,
@@ -686,6 +751,25 @@ mod wallet_tests {
         Wallet::new(provider, creds).await.unwrap()
     }
 
+    /// Checks that the zkSync keys derived from the same Ethereum key differ between
+    /// the networks, so the zkSync signatures can't be replayed on another network.
+    #[tokio::test]
+    async fn test_credentials_bound_to_network() {
+        let private_key = H256::from([7; 32]);
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let mut pubkey_hashes = Vec::new();
+        for network in [Network::Mainnet, Network::Rinkeby, Network::Localhost].iter() {
+            let eth_signer = PrivateKeySigner::new(private_key);
+            let creds = WalletCredentials::from_eth_signer(address, eth_signer, *network)
+                .await
+                .unwrap();
+            pubkey_hashes.push(*Signer::with_credentials(creds).pubkey_hash());
+        }
+        assert_ne!(pubkey_hashes[0], pubkey_hashes[1]);
+        assert_ne!(pubkey_hashes[0], pubkey_hashes[2]);
+        assert_ne!(pubkey_hashes[1], pubkey_hashes[2]);
+    }
+
     #[tokio::test]
     async fn test_wallet_address() {
         let wallet = get_test_wallet(&[5; 32], Network::Mainnet).await;
@@ -774,4 +858,1177 @@ mod wallet_tests {
         let expected_address: Vec<_> = (0..20).collect();
         assert_eq!(eth_provider.contract_address().as_bytes(), expected_address);
     }
+
+    #[tokio::test]
+    async fn test_verify_contract_address() {
+        let wallet = get_test_wallet(&[51; 32], Network::Mainnet).await;
+        let expected_address = Address::from_slice(&(0..20).collect::<Vec<_>>());
+        verify_contract_address(&wallet.provider, expected_address)
+            .await
+            .unwrap();
+
+        // The mock server doesn't use the mainnet contract.
+        let mainnet_contract = NetworkConfig::Mainnet.main_contract().unwrap();
+        assert_eq!(
+            verify_contract_address(&wallet.provider, mainnet_contract).await,
+            Err(ClientError::ContractAddressMismatch {
+                expected: mainnet_contract,
+                actual: expected_address,
+            })
+        );
+
+        // The contract is pinned for the custom address of the known network.
+        let custom_mainnet = NetworkConfig::Custom {
+            network: Network::Mainnet,
+            rpc_addr: "http://127.0.0.1:3030".into(),
+            main_contract: None,
+        };
+        assert_eq!(custom_mainnet.main_contract(), Some(mainnet_contract));
+        assert_eq!(custom_mainnet.rpc_addr(), "http://127.0.0.1:3030");
+        assert_eq!(NetworkConfig::Localhost.main_contract(), None);
+    }
+
+    #[tokio::test]
+    async fn test_wallet_forced_exit() {
+        let wallet = get_test_wallet(&[52; 32], Network::Mainnet).await;
+        let (tx, eth_signature) = wallet
+            .start_forced_exit()
+            .target(KEYLESS_ACCOUNT)
+            .token("USDC")
+            .unwrap()
+            .tx()
+            .await
+            .unwrap();
+        match tx {
+            ZkSyncTx::ForcedExit(forced_exit) => {
+                assert_eq!(forced_exit.initiator_account_id, AccountId(42));
+                assert_eq!(forced_exit.target, KEYLESS_ACCOUNT);
+                assert_eq!(forced_exit.token, TokenId(2));
+                assert_eq!(forced_exit.fee, BigUint::from(10_u32));
+            }
+            tx => panic!("ForcedExit expected, got {:?}", tx),
+        }
+        assert!(eth_signature.is_some());
+
+        // The wallet itself has the signing key set.
+        let err = wallet
+            .start_forced_exit()
+            .target(wallet.address())
+            .token("USDC")
+            .unwrap()
+            .tx()
+            .await
+            .unwrap_err();
+        assert_eq!(err, ClientError::TargetSigningKeySet);
+    }
+
+    #[tokio::test]
+    async fn test_wallet_batch_transfer() {
+        let wallet = get_test_wallet(&[60; 32], Network::Mainnet).await;
+        let recipients = [
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+        ];
+        let builder = recipients.iter().fold(
+            wallet.start_batch_transfer().token("DAI").unwrap(),
+            |builder, to| builder.transfer(*to, 100_u32),
+        );
+        let (txs, batch_signature) = builder.fee(10_u32).txs().await.unwrap();
+
+        assert_eq!(txs.len(), recipients.len());
+        let mut batch = Vec::new();
+        for (idx, ((tx, signature), to)) in txs.into_iter().zip(&recipients).enumerate() {
+            assert!(signature.is_none());
+            let transfer = match &tx {
+                ZkSyncTx::Transfer(transfer) => transfer,
+                _ => panic!("Unexpected transaction {:?}", tx),
+            };
+            assert_eq!(transfer.to, *to);
+            assert_eq!(transfer.nonce, Nonce(idx as u32));
+            let expected_fee = if idx == 0 { 10_u32 } else { 0 };
+            assert_eq!(transfer.fee, BigUint::from(expected_fee));
+
+            let token = wallet.tokens.resolve("DAI".into()).unwrap();
+            batch.push((tx, token, wallet.address()));
+        }
+
+        // The whole batch is signed with the single Ethereum signature.
+        // The message is bound to the network of the wallet.
+        let message = EthBatchSignData::get_batch_sign_message(batch);
+        let batch_signature = batch_signature.unwrap();
+        let signer = batch_signature
+            .signature_recover_signer_from_raw_message(&chain_bound_ethereum_message(
+                &message,
+                Network::Mainnet.chain_id(),
+            ))
+            .unwrap();
+        assert_eq!(signer, wallet.address());
+        for message in [
+            message.clone(),
+            chain_bound_ethereum_message(&message, Network::Rinkeby.chain_id()),
+        ]
+        .iter()
+        {
+            let signer = batch_signature
+                .signature_recover_signer_from_raw_message(message)
+                .unwrap();
+            assert_ne!(signer, wallet.address());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wallet_change_pubkey_onchain_auth() {
+        let wallet = get_test_wallet(&[64; 32], Network::Mainnet).await;
+
+        let tx = wallet
+            .start_change_pubkey()
+            .onchain_auth(true)
+            .fee_token("DAI")
+            .unwrap()
+            .fee(10_u32)
+            .nonce(Nonce(3))
+            .tx()
+            .await
+            .unwrap();
+        let change_pubkey = match &tx {
+            ZkSyncTx::ChangePubKey(change_pubkey) => change_pubkey,
+            _ => panic!("Unexpected transaction {:?}", tx),
+        };
+
+        // The key is authorized in the contract, the transaction has no Ethereum signature.
+        assert_eq!(change_pubkey.nonce, Nonce(3));
+        assert_eq!(change_pubkey.new_pk_hash, wallet.signer.pubkey_hash);
+        assert!(matches!(
+            change_pubkey.eth_auth_data,
+            Some(ChangePubKeyEthAuthData::Onchain)
+        ));
+        assert!(change_pubkey.verify_signature().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_wallet_auto_fee_transfer() {
+        let wallet = get_test_wallet(&[63; 32], Network::Mainnet).await;
+        let to = Address::repeat_byte(1);
+
+        // The fee is paid in the transferred token if it's enough.
+        let fee_payment = wallet
+            .start_auto_fee_transfer()
+            .token("TUSD")
+            .unwrap()
+            .amount(100_u32)
+            .to(to)
+            .fee_payment()
+            .await
+            .unwrap();
+        assert_eq!(
+            fee_payment,
+            FeePayment::SameToken {
+                fee: BigUint::from(10_u32)
+            }
+        );
+
+        // "DAI" is not accepted to pay fees in, so the fee is paid by the batched transfer.
+        let fee_payment = wallet
+            .start_auto_fee_transfer()
+            .token("DAI")
+            .unwrap()
+            .amount(12345_u32)
+            .to(to)
+            .fee_payment()
+            .await
+            .unwrap();
+        assert_eq!(
+            fee_payment,
+            FeePayment::Batch {
+                fee_token: wallet.tokens.resolve("TUSD".into()).unwrap(),
+                fee: BigUint::from(20_u32),
+            }
+        );
+
+        // The remaining "TUSD" balance doesn't cover the fee.
+        let result = wallet
+            .start_auto_fee_transfer()
+            .token("TUSD")
+            .unwrap()
+            .amount(495_u32)
+            .to(to)
+            .fee_payment()
+            .await;
+        assert_eq!(result.unwrap_err(), ClientError::InsufficientBalance);
+
+        // Only the provided fee tokens are tried.
+        let result = wallet
+            .start_auto_fee_transfer()
+            .token("DAI")
+            .unwrap()
+            .amount(100_u32)
+            .to(to)
+            .fee_tokens(vec!["DAI"])
+            .unwrap()
+            .fee_payment()
+            .await;
+        assert_eq!(result.unwrap_err(), ClientError::InsufficientBalance);
+    }
+
+    #[tokio::test]
+    async fn test_wallet_swap() {
+        let maker = get_test_wallet(&[60; 32], Network::Mainnet).await;
+        let taker = get_test_wallet(&[61; 32], Network::Mainnet).await;
+        let token_a = maker.tokens.resolve(TokenId(1).into()).unwrap();
+        let token_b = maker.tokens.resolve(TokenId(2).into()).unwrap();
+
+        let first = maker
+            .start_order()
+            .token_sell(token_a.id)
+            .unwrap()
+            .token_buy(token_b.id)
+            .unwrap()
+            .ratio(1_u32, 2_u32)
+            .amount(100_u32)
+            .sign()
+            .await
+            .unwrap();
+        let second = taker
+            .start_order()
+            .token_sell(token_b.id)
+            .unwrap()
+            .token_buy(token_a.id)
+            .unwrap()
+            .ratio(2_u32, 1_u32)
+            .amount(200_u32)
+            .sign()
+            .await
+            .unwrap();
+        assert_eq!(first.order.recipient_address, maker.address());
+        assert!(first.order.verify_signature().is_some());
+
+        let signed_swap = taker
+            .start_swap()
+            .orders(first.clone(), second)
+            .fee_token(token_a.id)
+            .unwrap()
+            .fee(10_u32)
+            .tx()
+            .await
+            .unwrap();
+        let swap = &signed_swap.swap;
+        assert_eq!(swap.submitter_address, taker.address());
+        assert_eq!(
+            swap.amounts,
+            (BigUint::from(100_u32), BigUint::from(200_u32))
+        );
+        assert!(swap.verify_signature().is_some());
+
+        // The orders are signed by the Ethereum keys of their owners.
+        let chain_id = Network::Mainnet.chain_id();
+        let message = first.order.get_ethereum_sign_message(
+            &token_a.symbol,
+            &token_b.symbol,
+            token_a.decimals,
+        );
+        let signer = signed_swap
+            .orders_eth_signatures
+            .0
+            .unwrap()
+            .signature_recover_signer_from_raw_message(&chain_bound_ethereum_message(
+                message.as_bytes(),
+                chain_id,
+            ))
+            .unwrap();
+        assert_eq!(signer, maker.address());
+
+        let message = swap.get_ethereum_sign_message(&token_a.symbol, token_a.decimals);
+        let signer = signed_swap
+            .eth_signature
+            .unwrap()
+            .signature_recover_signer_from_raw_message(&chain_bound_ethereum_message(
+                message.as_bytes(),
+                chain_id,
+            ))
+            .unwrap();
+        assert_eq!(signer, taker.address());
+    }
+
+    #[tokio::test]
+    async fn test_wallet_offline_signing() {
+        let private_key = H256::from([62; 32]);
+        let wallet = get_test_wallet(private_key.as_bytes(), Network::Mainnet).await;
+
+        let unsigned_tx = wallet
+            .start_transfer()
+            .token(TokenId(1))
+            .unwrap()
+            .to(Address::repeat_byte(1))
+            .amount(100_u32)
+            .fee(1_u32)
+            .unsigned_tx()
+            .await
+            .unwrap();
+        // The payload is handed to the signing machine as JSON.
+        let unsigned_tx: UnsignedTx =
+            serde_json::from_str(&serde_json::to_string(&unsigned_tx).unwrap()).unwrap();
+        match &unsigned_tx {
+            UnsignedTx::Transfer(transfer) => {
+                assert_eq!(transfer.account_id, AccountId(42));
+                assert_eq!(transfer.from, wallet.address());
+                assert_eq!(transfer.fee, BigUint::from(1_u32));
+            }
+            _ => panic!("Transfer expected"),
+        }
+
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let credentials = WalletCredentials::from_eth_signer(
+            address,
+            PrivateKeySigner::new(private_key),
+            Network::Mainnet,
+        )
+        .await
+        .unwrap();
+        let mut signer = Signer::with_credentials(credentials);
+
+        // The account id is not known to the signer yet.
+        assert!(unsigned_tx.clone().sign(&signer).await.is_err());
+        signer.set_account_id(Some(AccountId(42)));
+
+        let signed_tx = unsigned_tx.sign(&signer).await.unwrap();
+        let (online_tx, _) = wallet
+            .start_transfer()
+            .token(TokenId(1))
+            .unwrap()
+            .to(Address::repeat_byte(1))
+            .amount(100_u32)
+            .fee(1_u32)
+            .tx()
+            .await
+            .unwrap();
+        assert_eq!(signed_tx.tx.hash(), online_tx.hash());
+        assert!(signed_tx.eth_signature.is_some());
+    }
+}
+
+#[cfg(test)]
+mod retry_provider_tests {
+    use super::*;
+    use jsonrpc_core::ErrorCode;
+    use num::BigUint;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use std::time::Duration;
+    use zksync::{
+        error::{ClientError, RpcFailure, ServerErrorCode, TransportError},
+        mock_provider::MockProvider,
+        provider::Provider,
+        types::{AccountInfo, ContractAddress, EthOpInfo, Fee, NFTInfo, Tokens, TransactionInfo},
+        Network, RetryConfig, RetryProvider,
+    };
+    use zksync_types::{
+        tx::{PackedEthSignature, TxHash},
+        Address, Swap, TokenLike, TxFeeTypes, ZkSyncTx,
+    };
+
+    /// Fails the first `failures` requests with the provided error.
+    #[derive(Debug, Clone)]
+    struct FlakyProvider {
+        error: fn() -> ClientError,
+        failures: usize,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl FlakyProvider {
+        fn new(error: fn() -> ClientError, failures: usize) -> Self {
+            Self {
+                error,
+                failures,
+                calls: Arc::default(),
+            }
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+
+        fn call<T>(&self, value: T) -> Result<T, ClientError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call < self.failures {
+                Err((self.error)())
+            } else {
+                Ok(value)
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for FlakyProvider {
+        async fn account_info(&self, address: Address) -> Result<AccountInfo, ClientError> {
+            self.call(AccountInfo {
+                address,
+                id: None,
+                depositing: Default::default(),
+                committed: Default::default(),
+                verified: Default::default(),
+            })
+        }
+
+        async fn tokens(&self) -> Result<Tokens, ClientError> {
+            unreachable!()
+        }
+
+        async fn tx_info(&self, _tx_hash: TxHash) -> Result<TransactionInfo, ClientError> {
+            unreachable!()
+        }
+
+        async fn get_tx_fee(
+            &self,
+            _tx_type: TxFeeTypes,
+            _address: Address,
+            _token: impl Into<TokenLike> + Send + 'async_trait,
+        ) -> Result<Fee, ClientError> {
+            unreachable!()
+        }
+
+        async fn get_txs_batch_fee(
+            &self,
+            _tx_types: Vec<TxFeeTypes>,
+            _addresses: Vec<Address>,
+            _token: impl Into<TokenLike> + Send + 'async_trait,
+        ) -> Result<BigUint, ClientError> {
+            unreachable!()
+        }
+
+        async fn ethop_info(&self, _serial_id: u32) -> Result<EthOpInfo, ClientError> {
+            unreachable!()
+        }
+
+        async fn get_eth_tx_for_withdrawal(
+            &self,
+            _withdrawal_hash: TxHash,
+        ) -> Result<Option<String>, ClientError> {
+            unreachable!()
+        }
+
+        async fn contract_address(&self) -> Result<ContractAddress, ClientError> {
+            unreachable!()
+        }
+
+        async fn get_nft(&self, _token_id: TokenId) -> Result<Option<NFTInfo>, ClientError> {
+            unreachable!()
+        }
+
+        async fn get_nft_owner(
+            &self,
+            _token_id: TokenId,
+        ) -> Result<Option<AccountId>, ClientError> {
+            unreachable!()
+        }
+
+        async fn get_nft_id_by_tx_hash(
+            &self,
+            _tx_hash: TxHash,
+        ) -> Result<Option<TokenId>, ClientError> {
+            unreachable!()
+        }
+
+        async fn send_tx(
+            &self,
+            tx: ZkSyncTx,
+            _eth_signature: Option<PackedEthSignature>,
+        ) -> Result<TxHash, ClientError> {
+            self.call(tx.hash())
+        }
+
+        async fn send_txs_batch(
+            &self,
+            _txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+            _eth_signature: Option<PackedEthSignature>,
+        ) -> Result<Vec<TxHash>, ClientError> {
+            unreachable!()
+        }
+
+        async fn send_swap(
+            &self,
+            _swap: Swap,
+            _eth_signature: Option<PackedEthSignature>,
+            _orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+        ) -> Result<TxHash, ClientError> {
+            unreachable!()
+        }
+
+        fn network(&self) -> Network {
+            Network::Localhost
+        }
+    }
+
+    fn retry_config() -> RetryConfig {
+        RetryConfig {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            ..Default::default()
+        }
+    }
+
+    fn network_error() -> ClientError {
+        ClientError::TransportError(TransportError::RequestFailed("connection refused".into()))
+    }
+
+    fn timeout() -> ClientError {
+        ClientError::OperationTimeout
+    }
+
+    fn rpc_error() -> ClientError {
+        let failure = serde_json::from_value::<RpcFailure>(serde_json::json!({
+            "jsonrpc": "2.0",
+            "error": { "code": -32603, "message": "Internal error" },
+            "id": 1,
+        }))
+        .unwrap();
+        failure.into()
+    }
+
+    fn transfer() -> ZkSyncTx {
+        zksync_types::Transfer::new(
+            AccountId(1),
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            TokenId(0),
+            BigUint::from(100_u32),
+            BigUint::from(10_u32),
+            Nonce(0),
+            Default::default(),
+            None,
+        )
+        .into()
+    }
+
+    #[tokio::test]
+    async fn reads_are_retried() {
+        for &error in &[network_error as fn() -> ClientError, timeout, rpc_error] {
+            let inner = FlakyProvider::new(error, 2);
+            let provider = RetryProvider::with_config(inner.clone(), retry_config());
+            provider.account_info(Address::zero()).await.unwrap();
+            assert_eq!(inner.calls(), 3);
+        }
+
+        // The amount of retries is limited.
+        let inner = FlakyProvider::new(network_error, 10);
+        let provider = RetryProvider::with_config(inner.clone(), retry_config());
+        assert_eq!(
+            provider.account_info(Address::zero()).await.unwrap_err(),
+            network_error()
+        );
+        assert_eq!(inner.calls(), 4);
+    }
+
+    #[tokio::test]
+    async fn submits_are_not_retried_on_timeouts() {
+        let tx = transfer();
+
+        for &error in &[network_error as fn() -> ClientError, rpc_error] {
+            let inner = FlakyProvider::new(error, 2);
+            let provider = RetryProvider::with_config(inner.clone(), retry_config());
+            assert_eq!(provider.send_tx(tx.clone(), None).await.unwrap(), tx.hash());
+            assert_eq!(inner.calls(), 3);
+        }
+
+        let inner = FlakyProvider::new(timeout, 2);
+        let provider = RetryProvider::with_config(inner.clone(), retry_config());
+        assert_eq!(
+            provider.send_tx(tx.clone(), None).await.unwrap_err(),
+            timeout()
+        );
+        assert_eq!(inner.calls(), 1);
+    }
+
+    /// Checks that the submissions rejected by the server with the retryable error codes,
+    /// i.e. while it's shutting down (107) or the transactions acceptance is paused (108), are retried.
+    #[tokio::test]
+    async fn submits_are_retried_on_retryable_server_errors() {
+        let tx = transfer();
+
+        for code in [107, 108] {
+            let mock = MockProvider::new(Network::Localhost);
+            mock.respond_server_error(
+                "tx_submit",
+                ServerErrorCode::from(ErrorCode::ServerError(code)),
+                "Try again later",
+            )
+            .respond("tx_submit", tx.hash());
+            let provider = RetryProvider::with_config(mock.clone(), retry_config());
+            assert_eq!(provider.send_tx(tx.clone(), None).await.unwrap(), tx.hash());
+            assert_eq!(mock.calls_of("tx_submit").len(), 2, "code {}", code);
+        }
+
+        // The transactions rejected for their own reasons are not retried.
+        let mock = MockProvider::new(Network::Localhost);
+        mock.respond_server_error("tx_submit", ServerErrorCode::FeeTooLow, "Fee is too low")
+            .respond("tx_submit", tx.hash());
+        let provider = RetryProvider::with_config(mock.clone(), retry_config());
+        provider.send_tx(tx.clone(), None).await.unwrap_err();
+        assert_eq!(mock.calls_of("tx_submit").len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod error_tests {
+    use jsonrpc_core::ErrorCode;
+    use zksync::error::{
+        ClientError, ErrorKind, RpcFailure, ServerError, ServerErrorCode, TransportError,
+    };
+
+    fn server_error(code: i64) -> ClientError {
+        serde_json::from_value::<RpcFailure>(serde_json::json!({
+            "jsonrpc": "2.0",
+            "error": { "code": code, "message": "error" },
+            "id": 1,
+        }))
+        .unwrap()
+        .into()
+    }
+
+    #[test]
+    fn server_error_codes() {
+        assert_eq!(
+            ServerErrorCode::from(ErrorCode::ServerError(101)),
+            ServerErrorCode::NonceMismatch
+        );
+        assert_eq!(
+            ServerErrorCode::from(ErrorCode::ServerError(104)),
+            ServerErrorCode::FeeTooLow
+        );
+        assert_eq!(
+            ServerErrorCode::from(ErrorCode::ServerError(106)),
+            ServerErrorCode::TokenBlacklisted
+        );
+        assert_eq!(
+            ServerErrorCode::from(ErrorCode::ServerError(107)),
+            ServerErrorCode::ServerShuttingDown
+        );
+        assert_eq!(
+            ServerErrorCode::from(ErrorCode::ServerError(108)),
+            ServerErrorCode::TxAcceptancePaused
+        );
+        assert_eq!(
+            ServerErrorCode::from(ErrorCode::InvalidParams),
+            ServerErrorCode::InvalidParams
+        );
+        assert_eq!(
+            ServerErrorCode::from(ErrorCode::ServerError(999)),
+            ServerErrorCode::Unknown(999)
+        );
+
+        match server_error(202) {
+            ClientError::ServerError(ServerError { code, message, .. }) => {
+                assert_eq!(code, ServerErrorCode::IncorrectEthSignature);
+                assert_eq!(message, "error");
+            }
+            err => panic!("Server error expected, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn error_kinds() {
+        let cases = vec![
+            (
+                ClientError::from(TransportError::RequestFailed("refused".into())),
+                ErrorKind::Transport,
+                true,
+            ),
+            (
+                TransportError::HttpStatus(503).into(),
+                ErrorKind::Transport,
+                true,
+            ),
+            (
+                TransportError::HttpStatus(404).into(),
+                ErrorKind::Transport,
+                false,
+            ),
+            (
+                TransportError::MalformedResponse("eof".into()).into(),
+                ErrorKind::Transport,
+                false,
+            ),
+            (ClientError::OperationTimeout, ErrorKind::Transport, true),
+            (server_error(-32603), ErrorKind::Server, true),
+            (server_error(300), ErrorKind::Server, true),
+            (server_error(101), ErrorKind::Server, false),
+            (server_error(106), ErrorKind::Server, false),
+            (server_error(107), ErrorKind::Server, true),
+            (server_error(108), ErrorKind::Server, true),
+            (
+                ClientError::SigningError(zksync_eth_signer::error::SignerError::NoSigningKey),
+                ErrorKind::Signing,
+                false,
+            ),
+            (ClientError::NotPackableValue, ErrorKind::Validation, false),
+            (
+                ClientError::MissingRequiredField("token".into()),
+                ErrorKind::Validation,
+                false,
+            ),
+        ];
+
+        for (err, kind, retryable) in cases {
+            assert_eq!(err.kind(), kind, "{:?}", err);
+            assert_eq!(err.is_retryable(), retryable, "{:?}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod mock_provider_tests {
+    use super::*;
+    use num::BigUint;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use zksync::{
+        error::{ClientError, ServerErrorCode},
+        instrumented_provider::ProviderCall,
+        mock_provider::MockProvider,
+        provider::Provider,
+        types::{AccountInfo, AccountState},
+        InstrumentedProvider, Network, Wallet, WalletCredentials,
+    };
+    use zksync_eth_signer::PrivateKeySigner;
+    use zksync_types::{
+        chain_bound_ethereum_message,
+        tokens::ChangePubKeyFeeTypeArg,
+        tx::{ChangePubKeyType, EthBatchSignData, PackedEthSignature, TxHash},
+        Address, TxFeeTypes, ZkSyncTx, H256,
+    };
+
+    /// Sets the responses required for the wallet initialization to the `mock`,
+    /// and creates the wallet using the `provider`, which is either the `mock` or its wrapper.
+    async fn get_mock_wallet<P: Provider + Clone>(
+        mock: &MockProvider,
+        provider: P,
+    ) -> Wallet<PrivateKeySigner, P> {
+        let private_key = H256::repeat_byte(7);
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let eth_signer = PrivateKeySigner::new(private_key);
+        let creds = WalletCredentials::from_eth_signer(address, eth_signer, Network::Mainnet)
+            .await
+            .unwrap();
+
+        let mut tokens = HashMap::new();
+        tokens.insert(
+            "DAI".to_string(),
+            Token::new(TokenId(1), H160::random(), "DAI", 18, TokenKind::ERC20),
+        );
+        mock.respond("tokens", tokens).respond(
+            "account_info",
+            AccountInfo {
+                address,
+                id: Some(AccountId(7)),
+                depositing: Default::default(),
+                committed: AccountState {
+                    nonce: Nonce(3),
+                    ..Default::default()
+                },
+                verified: Default::default(),
+            },
+        );
+
+        Wallet::new(provider, creds).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_transfer() {
+        let provider = MockProvider::new(Network::Mainnet);
+        provider.confirm_txs_after(2, 100);
+        let wallet = get_mock_wallet(&provider, provider.clone()).await;
+
+        let mut handle = wallet
+            .start_transfer()
+            .token("DAI")
+            .unwrap()
+            .to(Address::repeat_byte(1))
+            .amount(1000_u32)
+            .fee(10_u32)
+            .send()
+            .await
+            .unwrap();
+        handle.polling_interval(Duration::from_millis(200)).unwrap();
+
+        let info = handle.wait_for_commit().await.unwrap();
+        assert_eq!(info.block.unwrap().block_number, 100);
+        assert!(handle.wait_for_verify().await.unwrap().is_verified());
+
+        let submitted = provider.calls_of("tx_submit");
+        assert_eq!(submitted.len(), 1);
+        assert_eq!(submitted[0].params[0]["type"], "Transfer");
+        assert_eq!(submitted[0].params[0]["nonce"], 3);
+        // Two pending statuses, then committed and verified ones.
+        assert_eq!(provider.calls_of("tx_info").len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_errors() {
+        let provider = MockProvider::new(Network::Mainnet);
+        provider.respond_server_error("tx_submit", ServerErrorCode::FeeTooLow, "Fee is too low");
+        let wallet = get_mock_wallet(&provider, provider.clone()).await;
+
+        let err = wallet
+            .start_transfer()
+            .token("DAI")
+            .unwrap()
+            .to(Address::repeat_byte(1))
+            .amount(1000_u32)
+            .fee(1_u32)
+            .send()
+            .await
+            .unwrap_err();
+        match err {
+            ClientError::ServerError(err) => assert_eq!(err.code, ServerErrorCode::FeeTooLow),
+            err => panic!("Server error expected, got {:?}", err),
+        }
+
+        // There is no response for the fee request.
+        let err = provider
+            .get_txs_batch_fee(vec![], vec![], "DAI")
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), zksync::error::ErrorKind::Server);
+
+        provider.respond(
+            "get_txs_batch_fee_in_wei",
+            serde_json::json!({ "totalFee": "25" }),
+        );
+        assert_eq!(
+            provider
+                .get_txs_batch_fee(vec![], vec![], "DAI")
+                .await
+                .unwrap(),
+            BigUint::from(25_u32)
+        );
+    }
+
+    /// Checks that the batch fee is requested for all the batch transactions along with the fee
+    /// transfer appended to them, and that the batch is signed in the order it's submitted in.
+    #[tokio::test]
+    async fn test_batch_builder() {
+        let provider = MockProvider::new(Network::Mainnet);
+        provider.respond(
+            "get_txs_batch_fee_in_wei",
+            serde_json::json!({ "totalFee": "25" }),
+        );
+        let wallet = get_mock_wallet(&provider, provider.clone()).await;
+        let address = wallet.address();
+        let recipient = Address::repeat_byte(1);
+
+        let (txs, batch_signature) = wallet
+            .start_batch()
+            .change_pubkey(false)
+            .transfer("DAI", recipient, 1000_u32)
+            .unwrap()
+            .withdraw("DAI", recipient, 500_u32)
+            .unwrap()
+            .fee_token("DAI")
+            .unwrap()
+            .txs()
+            .await
+            .unwrap();
+
+        let fee_requests = provider.calls_of("get_txs_batch_fee_in_wei");
+        assert_eq!(fee_requests.len(), 1);
+        let fee_types = vec![
+            TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+                ChangePubKeyType::EIP712,
+            )),
+            TxFeeTypes::Transfer,
+            TxFeeTypes::Withdraw,
+            TxFeeTypes::Transfer,
+        ];
+        assert_eq!(
+            fee_requests[0].params[0],
+            serde_json::to_value(fee_types).unwrap()
+        );
+        assert_eq!(
+            fee_requests[0].params[1],
+            serde_json::to_value(vec![address, recipient, recipient, address]).unwrap()
+        );
+
+        // The whole fee is paid by the last transfer to the wallet itself.
+        assert_eq!(txs.len(), 4);
+        let fees: Vec<_> = txs
+            .iter()
+            .map(|(tx, _)| match tx {
+                ZkSyncTx::ChangePubKey(tx) => tx.fee.clone(),
+                ZkSyncTx::Transfer(tx) => tx.fee.clone(),
+                ZkSyncTx::Withdraw(tx) => tx.fee.clone(),
+                tx => panic!("Unexpected transaction in the batch: {:?}", tx),
+            })
+            .collect();
+        let zero = BigUint::from(0_u32);
+        assert_eq!(fees, vec![zero.clone(), zero.clone(), zero, 25_u32.into()]);
+        match &txs[3].0 {
+            ZkSyncTx::Transfer(tx) => {
+                assert_eq!((tx.to, tx.amount.clone()), (address, 0_u32.into()))
+            }
+            tx => panic!("Expected the fee transfer, got {:?}", tx),
+        }
+        let nonces: Vec<_> = txs.iter().map(|(tx, _)| tx.nonce()).collect();
+        assert_eq!(nonces, vec![Nonce(3), Nonce(4), Nonce(5), Nonce(6)]);
+
+        // The batch signature covers the transactions in the submitted order.
+        let dai = wallet.tokens.resolve("DAI".into()).unwrap();
+        let batch_message = |txs: Vec<ZkSyncTx>| {
+            let txs = txs
+                .into_iter()
+                .map(|tx| (tx, dai.clone(), address))
+                .collect();
+            chain_bound_ethereum_message(
+                &EthBatchSignData::get_batch_sign_message(txs),
+                Network::Mainnet.chain_id(),
+            )
+        };
+        let mut batch: Vec<_> = txs.into_iter().map(|(tx, _)| tx).collect();
+        let batch_signature = batch_signature.expect("The batch is not signed");
+        let signer = |message: Vec<u8>| {
+            batch_signature
+                .signature_recover_signer_from_raw_message(&message)
+                .unwrap()
+        };
+        assert_eq!(signer(batch_message(batch.clone())), address);
+        batch.swap(1, 2);
+        assert_ne!(signer(batch_message(batch)), address);
+    }
+
+    #[tokio::test]
+    async fn test_batch_builder_explicit_fee() {
+        let provider = MockProvider::new(Network::Mainnet);
+        let wallet = get_mock_wallet(&provider, provider.clone()).await;
+        provider.clear_calls();
+
+        let (txs, _) = wallet
+            .start_batch()
+            .transfer("DAI", Address::repeat_byte(1), 1000_u32)
+            .unwrap()
+            .fee_token("DAI")
+            .unwrap()
+            .fee(10_u32)
+            .nonce(Nonce(10))
+            .txs()
+            .await
+            .unwrap();
+
+        // Neither the fee nor the nonce is requested from the server.
+        assert!(provider.calls().is_empty());
+        match &txs[..] {
+            [(ZkSyncTx::Transfer(transfer), _), (ZkSyncTx::Transfer(fee_transfer), _)] => {
+                assert_eq!(transfer.fee, BigUint::from(0_u32));
+                assert_eq!(fee_transfer.fee, BigUint::from(10_u32));
+                assert_eq!((transfer.nonce, fee_transfer.nonce), (Nonce(10), Nonce(11)));
+            }
+            txs => panic!("Expected two transfers, got {:?}", txs),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_instrumented_provider_hook() {
+        let inner = MockProvider::new(Network::Mainnet);
+        inner.respond_server_error("tx_info", ServerErrorCode::Other, "Internal error");
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let provider = InstrumentedProvider::new(inner.clone()).with_hook({
+            let calls = calls.clone();
+            move |call: &ProviderCall<'_>| {
+                calls.lock().unwrap().push((
+                    call.method,
+                    call.is_success(),
+                    call.error.map(ToString::to_string),
+                ));
+            }
+        });
+        let wallet = get_mock_wallet(&inner, provider).await;
+        assert_eq!(wallet.account_id(), Some(AccountId(7)));
+
+        let err = wallet
+            .provider
+            .tx_info(TxHash::default())
+            .await
+            .unwrap_err();
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].0, "account_info");
+        assert_eq!(calls[1].0, "tokens");
+        assert!(calls[0].1 && calls[1].1);
+        assert_eq!(calls[2], ("tx_info", false, Some(err.to_string())));
+    }
+}
+
+#[cfg(test)]
+mod failover_provider_tests {
+    use super::*;
+    use num::BigUint;
+    use std::time::Duration;
+    use zksync::{
+        error::{ClientError, ServerErrorCode, TransportError},
+        failover_provider::{Endpoint, FailoverConfig},
+        mock_provider::MockProvider,
+        provider::Provider,
+        types::{AccountInfo, ContractAddress},
+        FailoverProvider, Network,
+    };
+    use zksync_types::{Address, ZkSyncTx};
+
+    fn network_error() -> TransportError {
+        TransportError::RequestFailed("connection refused".into())
+    }
+
+    /// Mock of the endpoint, responding to the health checks if it's `alive`.
+    /// The `account_info` requests are answered if the response is set by the test.
+    fn mock_endpoint(alive: bool) -> MockProvider {
+        let mock = MockProvider::new(Network::Localhost);
+        if alive {
+            mock.respond(
+                "contract_address",
+                ContractAddress {
+                    main_contract: "0x01".into(),
+                    gov_contract: "0x02".into(),
+                },
+            );
+        } else {
+            mock.respond_transport_error("contract_address", network_error());
+        }
+        mock
+    }
+
+    fn respond_account_info(mock: &MockProvider) {
+        mock.respond(
+            "account_info",
+            AccountInfo {
+                address: Address::zero(),
+                id: None,
+                depositing: Default::default(),
+                committed: Default::default(),
+                verified: Default::default(),
+            },
+        );
+    }
+
+    /// Failover provider running the health checks explicitly, or once before the first request.
+    fn failover_provider(mocks: &[MockProvider]) -> FailoverProvider<MockProvider> {
+        let endpoints = mocks
+            .iter()
+            .enumerate()
+            .map(|(idx, mock)| (Endpoint::new(format!("mock_{}", idx)), mock.clone()));
+        let config = FailoverConfig {
+            health_check_interval: Duration::from_secs(3600),
+            ..Default::default()
+        };
+        FailoverProvider::from_providers(endpoints, Network::Localhost, config).unwrap()
+    }
+
+    fn transfer() -> ZkSyncTx {
+        zksync_types::Transfer::new(
+            AccountId(1),
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            TokenId(0),
+            BigUint::from(100_u32),
+            BigUint::from(10_u32),
+            Nonce(0),
+            Default::default(),
+            None,
+        )
+        .into()
+    }
+
+    #[tokio::test]
+    async fn requests_are_repeated_on_other_endpoints() {
+        let mocks = [mock_endpoint(true), mock_endpoint(true)];
+        mocks[0].respond_transport_error("account_info", network_error());
+        respond_account_info(&mocks[1]);
+        let provider = failover_provider(&mocks);
+
+        provider.account_info(Address::zero()).await.unwrap();
+        assert_eq!(provider.active_endpoint(), "mock_1");
+        assert_eq!(mocks[0].calls_of("account_info").len(), 1);
+        assert_eq!(mocks[1].calls_of("account_info").len(), 1);
+
+        // The failed endpoint is not requested until the next health check.
+        provider.account_info(Address::zero()).await.unwrap();
+        assert_eq!(mocks[0].calls_of("account_info").len(), 1);
+        assert_eq!(mocks[1].calls_of("account_info").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn unhealthy_endpoints_are_skipped() {
+        let mocks = [mock_endpoint(false), mock_endpoint(true)];
+        respond_account_info(&mocks[1]);
+        let provider = failover_provider(&mocks);
+
+        provider.account_info(Address::zero()).await.unwrap();
+        assert_eq!(provider.active_endpoint(), "mock_1");
+        assert!(mocks[0].calls_of("account_info").is_empty());
+    }
+
+    #[tokio::test]
+    async fn primary_endpoint_is_restored() {
+        // The primary endpoint fails the first health check only.
+        let mocks = [mock_endpoint(false), mock_endpoint(true)];
+        mocks[0].respond(
+            "contract_address",
+            ContractAddress {
+                main_contract: "0x01".into(),
+                gov_contract: "0x02".into(),
+            },
+        );
+        let provider = failover_provider(&mocks);
+
+        provider.health_check().await;
+        assert_eq!(provider.active_endpoint(), "mock_1");
+        provider.health_check().await;
+        assert_eq!(provider.active_endpoint(), "mock_0");
+    }
+
+    #[tokio::test]
+    async fn submits_are_repeated_on_transport_errors_only() {
+        let tx = transfer();
+
+        let mocks = [mock_endpoint(true), mock_endpoint(true)];
+        mocks[0].respond_transport_error("tx_submit", network_error());
+        let provider = failover_provider(&mocks);
+        assert_eq!(provider.send_tx(tx.clone(), None).await.unwrap(), tx.hash());
+        assert_eq!(mocks[1].calls_of("tx_submit").len(), 1);
+
+        // The transaction rejected by the server is not sent to the other endpoints.
+        let mocks = [mock_endpoint(true), mock_endpoint(true)];
+        mocks[0].respond_server_error("tx_submit", ServerErrorCode::FeeTooLow, "Fee is too low");
+        let provider = failover_provider(&mocks);
+        match provider.send_tx(tx, None).await.unwrap_err() {
+            ClientError::ServerError(err) => assert_eq!(err.code, ServerErrorCode::FeeTooLow),
+            err => panic!("Server error expected, got {:?}", err),
+        }
+        assert_eq!(provider.active_endpoint(), "mock_0");
+        assert!(mocks[1].calls_of("tx_submit").is_empty());
+    }
+
+    #[tokio::test]
+    async fn last_error_is_returned_if_all_endpoints_fail() {
+        let mocks = [mock_endpoint(true), mock_endpoint(true)];
+        for mock in &mocks {
+            mock.respond_transport_error("account_info", network_error());
+        }
+        let provider = failover_provider(&mocks);
+
+        let err = provider.account_info(Address::zero()).await.unwrap_err();
+        assert_eq!(err, ClientError::TransportError(network_error()));
+        for mock in &mocks {
+            assert_eq!(mock.calls_of("account_info").len(), 1);
+        }
+
+        let no_endpoints: Vec<(Endpoint, MockProvider)> = Vec::new();
+        let err = FailoverProvider::from_providers(
+            no_endpoints,
+            Network::Localhost,
+            FailoverConfig::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ClientError::IncorrectInput);
+    }
 }
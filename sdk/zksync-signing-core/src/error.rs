@@ -0,0 +1,32 @@
+use std::fmt;
+
+/// Error of the signing operations. Messages are the ones passed to JS by `zksync-crypto`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    SeedTooShort,
+    InvalidPrivateKey,
+    InvalidPublicKey,
+    InvalidSignature,
+    InvalidSignatureLength,
+    MessageTooLong,
+    NotPackable(u128),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SeedTooShort => write!(f, "Seed is too short"),
+            Self::InvalidPrivateKey => write!(f, "couldn't read private key repr"),
+            Self::InvalidPublicKey => write!(f, "couldn't read public key"),
+            Self::InvalidSignature => write!(f, "Failed to parse signature"),
+            Self::InvalidSignatureLength => write!(
+                f,
+                "Signature length is not 96 bytes. Make sure it contains both the public key and the signature itself."
+            ),
+            Self::MessageTooLong => write!(f, "Message is too long to be signed"),
+            Self::NotPackable(amount) => write!(f, "Amount {} is not packable", amount),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
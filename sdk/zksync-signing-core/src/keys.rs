@@ -0,0 +1,89 @@
+//! Derivation of the zkSync keys.
+
+use franklin_crypto::{
+    alt_babyjubjub::{fs::FsRepr, FixedGenerators},
+    bellman::pairing::ff::{PrimeField, PrimeFieldRepr},
+    eddsa::{PrivateKey, PublicKey},
+};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::Error,
+    hash,
+    params::{Engine, Fs, JUBJUB_PARAMS, PACKED_POINT_SIZE},
+};
+
+/// Derives the private key from the seed, e.g. from the Ethereum signature of the
+/// "Access zkSync account" message. The seed must be at least 32 bytes long.
+pub fn private_key_from_seed(seed: &[u8]) -> Result<Vec<u8>, Error> {
+    if seed.len() < 32 {
+        return Err(Error::SeedTooShort);
+    };
+
+    let sha256_bytes = |input: &[u8]| -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.input(input);
+        hasher.result().to_vec()
+    };
+
+    let mut effective_seed = sha256_bytes(seed);
+
+    loop {
+        let raw_priv_key = sha256_bytes(&effective_seed);
+        let mut fs_repr = FsRepr::default();
+        fs_repr
+            .read_be(&raw_priv_key[..])
+            .expect("failed to read raw_priv_key");
+        if Fs::from_repr(fs_repr).is_ok() {
+            return Ok(raw_priv_key);
+        } else {
+            effective_seed = raw_priv_key;
+        }
+    }
+}
+
+/// Reads the private key from its big-endian representation.
+pub fn read_private_key(private_key: &[u8]) -> Result<PrivateKey<Engine>, Error> {
+    let mut fs_repr = FsRepr::default();
+    fs_repr
+        .read_be(private_key)
+        .map_err(|_| Error::InvalidPrivateKey)?;
+    Fs::from_repr(fs_repr)
+        .map(PrivateKey::<Engine>)
+        .map_err(|_| Error::InvalidPrivateKey)
+}
+
+/// Reads the packed public key.
+pub fn read_public_key(pubkey: &[u8]) -> Result<PublicKey<Engine>, Error> {
+    JUBJUB_PARAMS
+        .with(|params| PublicKey::read(pubkey, params))
+        .map_err(|_| Error::InvalidPublicKey)
+}
+
+pub fn public_key_from_private(private_key: &PrivateKey<Engine>) -> PublicKey<Engine> {
+    JUBJUB_PARAMS.with(|params| {
+        PublicKey::from_private(private_key, FixedGenerators::SpendingKeyGenerator, params)
+    })
+}
+
+/// Returns the hash of the packed public key, i.e. 20 bytes of the `sync:` address.
+pub fn pub_key_hash(pubkey: &[u8]) -> Result<Vec<u8>, Error> {
+    Ok(hash::pub_key_hash(&read_public_key(pubkey)?))
+}
+
+pub fn private_key_to_pubkey_hash(private_key: &[u8]) -> Result<Vec<u8>, Error> {
+    let private_key = read_private_key(private_key)?;
+    Ok(hash::pub_key_hash(&public_key_from_private(&private_key)))
+}
+
+/// Returns the packed public key.
+pub fn private_key_to_pubkey(private_key: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut pubkey_buf = Vec::with_capacity(PACKED_POINT_SIZE);
+
+    let private_key = read_private_key(private_key)?;
+    public_key_from_private(&private_key)
+        .write(&mut pubkey_buf)
+        .expect("failed to write pubkey to buffer");
+
+    Ok(pubkey_buf)
+}
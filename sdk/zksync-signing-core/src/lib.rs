@@ -0,0 +1,32 @@
+//! Signing logic of zkSync transactions: the key derivation, the construction of the signed
+//! messages and the L2 (musig Schnorr) signatures.
+//!
+//! The crate doesn't perform any I/O and doesn't use threads or the OS randomness (the signatures
+//! are deterministic), so it can be compiled for `wasm32-unknown-unknown` and reused by the
+//! browser and mobile wallets; see `zksync-crypto` for the `wasm-bindgen` bindings.
+//! It can't be `no_std` though, since `franklin-crypto` depends on the standard library.
+//!
+//! Keys, messages and signatures are represented by the raw bytes in the same format as used
+//! by the zkSync server.
+
+pub use self::{
+    error::Error,
+    keys::{
+        private_key_from_seed, private_key_to_pubkey, private_key_to_pubkey_hash, pub_key_hash,
+    },
+    messages::{
+        format_units, pack_fee_amount, pack_token_amount, ChangePubKey, Transfer, Withdraw,
+    },
+    params::{Engine, Fr, Fs, JUBJUB_PARAMS, RESCUE_PARAMS},
+    signature::{sign_musig, verify_musig, Signature},
+};
+
+pub mod error;
+pub mod hash;
+pub mod keys;
+pub mod messages;
+pub mod params;
+pub mod signature;
+
+#[cfg(test)]
+mod tests;
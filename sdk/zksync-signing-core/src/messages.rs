@@ -0,0 +1,225 @@
+//! Construction of the messages signed by the zkSync and the Ethereum keys of the account.
+//! The encoding is the same as of the transactions in the `zksync_types` crate.
+
+use std::collections::VecDeque;
+
+use crate::{
+    error::Error,
+    params::{
+        AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH, CHANGE_PUBKEY_TX_TYPE,
+        CURRENT_TX_VERSION, FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, TRANSFER_TX_TYPE,
+        WITHDRAW_TX_TYPE,
+    },
+};
+
+/// Transfer of the tokens between zkSync accounts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer {
+    pub account_id: u32,
+    pub from: [u8; 20],
+    pub to: [u8; 20],
+    pub token: u32,
+    pub amount: u128,
+    pub fee: u128,
+    pub nonce: u32,
+    pub valid_from: u64,
+    pub valid_until: u64,
+}
+
+impl Transfer {
+    /// Encodes the transaction as the message to be signed by the zkSync key.
+    /// Returns an error if the amount or the fee is not packable.
+    pub fn get_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut out = tx_header(TRANSFER_TX_TYPE);
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(&self.from);
+        out.extend_from_slice(&self.to);
+        out.extend_from_slice(&self.token.to_be_bytes());
+        out.extend_from_slice(&pack_token_amount(self.amount)?);
+        out.extend_from_slice(&pack_fee_amount(self.fee)?);
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        out.extend_from_slice(&self.valid_from.to_be_bytes());
+        out.extend_from_slice(&self.valid_until.to_be_bytes());
+        Ok(out)
+    }
+
+    /// Returns the message to be signed by the Ethereum key of the account.
+    pub fn get_ethereum_sign_message(&self, token_symbol: &str, decimals: u8) -> String {
+        eth_sign_message(
+            "Transfer",
+            self.amount,
+            self.fee,
+            token_symbol,
+            decimals,
+            &self.to,
+            self.nonce,
+        )
+    }
+}
+
+/// Withdrawal of the tokens from zkSync to Ethereum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Withdraw {
+    pub account_id: u32,
+    pub from: [u8; 20],
+    pub to: [u8; 20],
+    pub token: u32,
+    pub amount: u128,
+    pub fee: u128,
+    pub nonce: u32,
+    pub valid_from: u64,
+    pub valid_until: u64,
+}
+
+impl Withdraw {
+    /// Encodes the transaction as the message to be signed by the zkSync key.
+    /// Returns an error if the fee is not packable, the amount is not packed.
+    pub fn get_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut out = tx_header(WITHDRAW_TX_TYPE);
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(&self.from);
+        out.extend_from_slice(&self.to);
+        out.extend_from_slice(&self.token.to_be_bytes());
+        out.extend_from_slice(&self.amount.to_be_bytes());
+        out.extend_from_slice(&pack_fee_amount(self.fee)?);
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        out.extend_from_slice(&self.valid_from.to_be_bytes());
+        out.extend_from_slice(&self.valid_until.to_be_bytes());
+        Ok(out)
+    }
+
+    /// Returns the message to be signed by the Ethereum key of the account.
+    pub fn get_ethereum_sign_message(&self, token_symbol: &str, decimals: u8) -> String {
+        eth_sign_message(
+            "Withdraw",
+            self.amount,
+            self.fee,
+            token_symbol,
+            decimals,
+            &self.to,
+            self.nonce,
+        )
+    }
+}
+
+/// Setting of the new zkSync public key of the account.
+/// The Ethereum authorization (EIP-712 or onchain) is not covered by this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangePubKey {
+    pub account_id: u32,
+    pub account: [u8; 20],
+    pub new_pk_hash: [u8; 20],
+    pub fee_token: u32,
+    pub fee: u128,
+    pub nonce: u32,
+    pub valid_from: u64,
+    pub valid_until: u64,
+}
+
+impl ChangePubKey {
+    /// Encodes the transaction as the message to be signed by the zkSync key.
+    /// Returns an error if the fee is not packable.
+    pub fn get_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut out = tx_header(CHANGE_PUBKEY_TX_TYPE);
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(&self.account);
+        out.extend_from_slice(&self.new_pk_hash);
+        out.extend_from_slice(&self.fee_token.to_be_bytes());
+        out.extend_from_slice(&pack_fee_amount(self.fee)?);
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        out.extend_from_slice(&self.valid_from.to_be_bytes());
+        out.extend_from_slice(&self.valid_until.to_be_bytes());
+        Ok(out)
+    }
+}
+
+/// Packs the token amount, returns an error if the amount is not packable.
+pub fn pack_token_amount(amount: u128) -> Result<Vec<u8>, Error> {
+    pack(amount, AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH)
+}
+
+/// Packs the fee amount, returns an error if the fee is not packable.
+pub fn pack_fee_amount(fee: u128) -> Result<Vec<u8>, Error> {
+    pack(fee, FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH)
+}
+
+/// Formats the amount in the smallest units as the decimal number of tokens,
+/// the same way as `ethers.utils.formatUnits` does.
+pub fn format_units(amount: u128, decimals: u8) -> String {
+    let mut chars: VecDeque<char> = amount.to_string().chars().collect();
+
+    while chars.len() < decimals as usize {
+        chars.push_front('0');
+    }
+    chars.insert(chars.len() - decimals as usize, '.');
+    if *chars.front().unwrap() == '.' {
+        chars.push_front('0');
+    }
+    while *chars.back().unwrap() == '0' {
+        chars.pop_back();
+    }
+    if *chars.back().unwrap() == '.' {
+        chars.push_back('0');
+    }
+    chars.iter().collect()
+}
+
+/// Packs the number as `mantissa * 10 ^ exponent`, encoded as the big-endian mantissa bits
+/// followed by the exponent bits.
+fn pack(amount: u128, exponent_len: usize, mantissa_len: usize) -> Result<Vec<u8>, Error> {
+    let max_mantissa = (1u128 << mantissa_len) - 1;
+    let max_exponent = (1u32 << exponent_len) - 1;
+
+    // Choose the minimal exponent, so the packing is the same as of `zksync_crypto`.
+    let mut exponent = 0;
+    let mut mantissa = amount;
+    while mantissa > max_mantissa {
+        if exponent == max_exponent || mantissa % 10 != 0 {
+            return Err(Error::NotPackable(amount));
+        }
+        mantissa /= 10;
+        exponent += 1;
+    }
+
+    let packed = (mantissa << exponent_len) | u128::from(exponent);
+    let len = (exponent_len + mantissa_len) / 8;
+    Ok(packed.to_be_bytes()[16 - len..].to_vec())
+}
+
+fn tx_header(tx_type: u8) -> Vec<u8> {
+    vec![255u8 - tx_type, CURRENT_TX_VERSION]
+}
+
+fn eth_sign_message(
+    transaction: &str,
+    amount: u128,
+    fee: u128,
+    token_symbol: &str,
+    decimals: u8,
+    to: &[u8; 20],
+    nonce: u32,
+) -> String {
+    let mut lines = Vec::new();
+    if amount != 0 {
+        lines.push(format!(
+            "{} {} {} to: 0x{}",
+            transaction,
+            format_units(amount, decimals),
+            token_symbol,
+            to_hex(to)
+        ));
+    }
+    if fee != 0 {
+        lines.push(format!(
+            "Fee: {} {}",
+            format_units(fee, decimals),
+            token_symbol
+        ));
+    }
+    lines.push(format!("Nonce: {}", nonce));
+    lines.join("\n")
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,26 @@
+pub use franklin_crypto::bellman::pairing::bn256::{Bn256 as Engine, Fr};
+use franklin_crypto::{
+    alt_babyjubjub::AltJubjubBn256, jubjub::JubjubEngine, rescue::bn256::Bn256RescueParams,
+};
+
+pub type Fs = <Engine as JubjubEngine>::Fs;
+
+thread_local! {
+    pub static JUBJUB_PARAMS: AltJubjubBn256 = AltJubjubBn256::new();
+    pub static RESCUE_PARAMS: Bn256RescueParams = Bn256RescueParams::new_checked_2_into_1();
+}
+
+pub const PACKED_POINT_SIZE: usize = 32;
+pub const PACKED_SIGNATURE_SIZE: usize = 64;
+
+/// Version of the transactions encoding, see `CURRENT_TX_VERSION` of `zksync_crypto`.
+pub const CURRENT_TX_VERSION: u8 = 1;
+
+pub const AMOUNT_EXPONENT_BIT_WIDTH: usize = 5;
+pub const AMOUNT_MANTISSA_BIT_WIDTH: usize = 35;
+pub const FEE_EXPONENT_BIT_WIDTH: usize = 5;
+pub const FEE_MANTISSA_BIT_WIDTH: usize = 11;
+
+pub const WITHDRAW_TX_TYPE: u8 = 3;
+pub const TRANSFER_TX_TYPE: u8 = 5;
+pub const CHANGE_PUBKEY_TX_TYPE: u8 = 7;
@@ -0,0 +1,110 @@
+//! Musig Schnorr signatures of the zkSync transactions.
+
+use franklin_crypto::{
+    alt_babyjubjub::{edwards, fs::FsRepr, FixedGenerators},
+    bellman::pairing::ff::{PrimeField, PrimeFieldRepr},
+    eddsa::{PublicKey, Seed, Signature as EddsaSignature},
+};
+
+use crate::{
+    error::Error,
+    hash::{rescue_hash_tx_msg, PAD_MSG_BEFORE_HASH_BITS_LEN},
+    keys::{public_key_from_private, read_private_key},
+    params::{Engine, Fs, JUBJUB_PARAMS, PACKED_POINT_SIZE, PACKED_SIGNATURE_SIZE, RESCUE_PARAMS},
+};
+
+pub type Signature = EddsaSignature<Engine>;
+
+/// Signs the message, e.g. the transaction bytes, with the zkSync private key.
+///
+/// It is impossible to restore signer for signature, that is why the public key of the signer
+/// is provided along with signature:
+/// [0..32] - packed public key of signer.
+/// [32..64] - packed r point of the signature.
+/// [64..96] - s point of the signature.
+pub fn sign_musig(private_key: &[u8], msg: &[u8]) -> Result<Vec<u8>, Error> {
+    if msg.len() * 8 > PAD_MSG_BEFORE_HASH_BITS_LEN {
+        return Err(Error::MessageTooLong);
+    }
+
+    let mut packed_full_signature = Vec::with_capacity(PACKED_POINT_SIZE + PACKED_SIGNATURE_SIZE);
+    let p_g = FixedGenerators::SpendingKeyGenerator;
+    let private_key = read_private_key(private_key)?;
+
+    public_key_from_private(&private_key)
+        .write(&mut packed_full_signature)
+        .expect("failed to write pubkey to packed_point");
+
+    let signature = JUBJUB_PARAMS.with(|jubjub_params| {
+        RESCUE_PARAMS.with(|rescue_params| {
+            let hashed_msg = rescue_hash_tx_msg(msg);
+            let seed = Seed::deterministic_seed(&private_key, &hashed_msg);
+            private_key.musig_rescue_sign(&hashed_msg, &seed, p_g, rescue_params, jubjub_params)
+        })
+    });
+
+    signature
+        .r
+        .write(&mut packed_full_signature)
+        .expect("failed to write signature");
+    signature
+        .s
+        .into_repr()
+        .write_le(&mut packed_full_signature)
+        .expect("failed to write signature repr");
+
+    assert_eq!(
+        packed_full_signature.len(),
+        PACKED_POINT_SIZE + PACKED_SIGNATURE_SIZE,
+        "incorrect signature size when signing"
+    );
+
+    Ok(packed_full_signature)
+}
+
+/// Verifies the signature produced by `sign_musig`.
+pub fn verify_musig(msg: &[u8], signature: &[u8]) -> Result<bool, Error> {
+    if signature.len() != PACKED_POINT_SIZE + PACKED_SIGNATURE_SIZE {
+        return Err(Error::InvalidSignatureLength);
+    }
+    if msg.len() * 8 > PAD_MSG_BEFORE_HASH_BITS_LEN {
+        return Err(Error::MessageTooLong);
+    }
+
+    let pubkey = &signature[..PACKED_POINT_SIZE];
+    let pubkey = JUBJUB_PARAMS
+        .with(|params| edwards::Point::read(pubkey, params).map(PublicKey))
+        .map_err(|_| Error::InvalidPublicKey)?;
+
+    let signature = deserialize_signature(&signature[PACKED_POINT_SIZE..])?;
+
+    let msg = rescue_hash_tx_msg(msg);
+    let value = JUBJUB_PARAMS.with(|jubjub_params| {
+        RESCUE_PARAMS.with(|rescue_params| {
+            pubkey.verify_musig_rescue(
+                &msg,
+                &signature,
+                FixedGenerators::SpendingKeyGenerator,
+                rescue_params,
+                jubjub_params,
+            )
+        })
+    });
+
+    Ok(value)
+}
+
+fn deserialize_signature(bytes: &[u8]) -> Result<Signature, Error> {
+    let (r_bar, s_bar) = bytes.split_at(PACKED_POINT_SIZE);
+
+    let r = JUBJUB_PARAMS
+        .with(|params| edwards::Point::read(r_bar, params))
+        .map_err(|_| Error::InvalidSignature)?;
+
+    let mut s_repr = FsRepr::default();
+    s_repr.read_le(s_bar).map_err(|_| Error::InvalidSignature)?;
+
+    let s = Fs::from_repr(s_repr).map_err(|_| Error::InvalidSignature)?;
+
+    Ok(Signature { r, s })
+}
@@ -0,0 +1,184 @@
+//! Compare the messages and the signatures to those of the `zksync_types` crate.
+
+use super::{
+    keys::read_private_key, pack_fee_amount, pack_token_amount, private_key_to_pubkey_hash,
+    sign_musig, verify_musig, ChangePubKey, Error, Transfer, Withdraw,
+};
+
+use crypto_lib::{priv_key_from_fs, Engine, Fs};
+use franklin_crypto::bellman::pairing::ff::{PrimeField, PrimeFieldRepr};
+use franklin_crypto::eddsa::PrivateKey;
+use num::BigUint;
+use rand::{Rng, SeedableRng, XorShiftRng};
+use zksync_types::{
+    tx::{TimeRange, TxSignature},
+    AccountId, Address, Nonce, PubKeyHash, TokenId,
+};
+
+fn gen_private_key_and_its_be_bytes() -> (PrivateKey<Engine>, Vec<u8>) {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+
+    let pk = priv_key_from_fs(rng.gen::<Fs>());
+    let mut serialized_key = Vec::new();
+    pk.0.into_repr()
+        .write_be(&mut serialized_key)
+        .expect("private key write");
+    (pk, serialized_key)
+}
+
+fn transfer() -> Transfer {
+    Transfer {
+        account_id: 12,
+        from: [1; 20],
+        to: [2; 20],
+        token: 3,
+        amount: 1_234_500_000,
+        fee: 1_000,
+        nonce: 4,
+        valid_from: 5,
+        valid_until: 6,
+    }
+}
+
+#[test]
+fn test_transfer_bytes() {
+    let tx = transfer();
+    let expected = zksync_types::Transfer::new(
+        AccountId(tx.account_id),
+        Address::from(tx.from),
+        Address::from(tx.to),
+        TokenId(tx.token),
+        BigUint::from(tx.amount),
+        BigUint::from(tx.fee),
+        Nonce(tx.nonce),
+        TimeRange::new(tx.valid_from, tx.valid_until),
+        None,
+    );
+
+    assert_eq!(tx.get_bytes().unwrap(), expected.get_bytes());
+    assert_eq!(
+        tx.get_ethereum_sign_message("ETH", 18),
+        expected.get_ethereum_sign_message("ETH", 18)
+    );
+}
+
+#[test]
+fn test_withdraw_bytes() {
+    let tx = Withdraw {
+        account_id: 12,
+        from: [1; 20],
+        to: [2; 20],
+        token: 3,
+        amount: 1_234_567_891,
+        fee: 0,
+        nonce: 4,
+        valid_from: 5,
+        valid_until: 6,
+    };
+    let expected = zksync_types::Withdraw::new(
+        AccountId(tx.account_id),
+        Address::from(tx.from),
+        Address::from(tx.to),
+        TokenId(tx.token),
+        BigUint::from(tx.amount),
+        BigUint::from(tx.fee),
+        Nonce(tx.nonce),
+        TimeRange::new(tx.valid_from, tx.valid_until),
+        None,
+    );
+
+    assert_eq!(tx.get_bytes().unwrap(), expected.get_bytes());
+    assert_eq!(
+        tx.get_ethereum_sign_message("USDC", 6),
+        expected.get_ethereum_sign_message("USDC", 6)
+    );
+}
+
+#[test]
+fn test_change_pub_key_bytes() {
+    let (pk, serialized_pk) = gen_private_key_and_its_be_bytes();
+    let mut new_pk_hash = [0; 20];
+    new_pk_hash.copy_from_slice(&private_key_to_pubkey_hash(&serialized_pk).unwrap());
+
+    let tx = ChangePubKey {
+        account_id: 12,
+        account: [1; 20],
+        new_pk_hash,
+        fee_token: 3,
+        fee: 2_000,
+        nonce: 4,
+        valid_from: 5,
+        valid_until: 6,
+    };
+    let expected = zksync_types::ChangePubKey::new(
+        AccountId(tx.account_id),
+        Address::from(tx.account),
+        PubKeyHash::from_privkey(&pk),
+        TokenId(tx.fee_token),
+        BigUint::from(tx.fee),
+        Nonce(tx.nonce),
+        TimeRange::new(tx.valid_from, tx.valid_until),
+        None,
+        None,
+        None,
+    );
+
+    assert_eq!(tx.get_bytes().unwrap(), expected.get_bytes());
+}
+
+#[test]
+fn test_packing() {
+    for &amount in &[0u128, 1, 34_359_738_367, 34_359_738_367_000, 10u128.pow(30)] {
+        assert_eq!(
+            pack_token_amount(amount).unwrap(),
+            zksync_types::helpers::pack_token_amount(&BigUint::from(amount))
+        );
+    }
+    for &fee in &[0u128, 1, 2047, 2_047_000, 10u128.pow(20)] {
+        assert_eq!(
+            pack_fee_amount(fee).unwrap(),
+            zksync_types::helpers::pack_fee_amount(&BigUint::from(fee))
+        );
+    }
+
+    assert_eq!(pack_fee_amount(2049), Err(Error::NotPackable(2049)));
+    assert_eq!(
+        pack_token_amount(34_359_738_368),
+        Err(Error::NotPackable(34_359_738_368))
+    );
+}
+
+#[test]
+fn test_private_key_read() {
+    let (zksync_types_pk, serialized_pk) = gen_private_key_and_its_be_bytes();
+
+    let pk = read_private_key(&serialized_pk).unwrap();
+    assert_eq!(pk.0, zksync_types_pk.0);
+}
+
+#[test]
+fn test_transfer_signature() {
+    let (pk, serialized_pk) = gen_private_key_and_its_be_bytes();
+    let msg = transfer().get_bytes().unwrap();
+
+    let signature = sign_musig(&serialized_pk, &msg).unwrap();
+    assert!(verify_musig(&msg, &signature).unwrap());
+
+    let signature =
+        TxSignature::deserialize_from_packed_bytes(&signature).expect("failed to unpack signature");
+    assert_eq!(
+        signature
+            .verify_musig(&msg)
+            .map(|pk| PubKeyHash::from_pubkey(&pk)),
+        Some(PubKeyHash::from_privkey(&pk))
+    );
+
+    // zkSync signature is the same as the one produced by `zksync_types`.
+    let expected = TxSignature::sign_musig(&pk, &msg);
+    assert_eq!(signature.signature.0.s, expected.signature.0.s);
+
+    assert_eq!(
+        sign_musig(&serialized_pk, &[0; 93]),
+        Err(Error::MessageTooLong)
+    );
+}
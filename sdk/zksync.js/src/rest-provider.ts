@@ -511,10 +511,11 @@ export class RestProvider extends SyncProvider {
                 direction: 'newer'
             });
             for (let token of tokenPage.list) {
-                tokens[token.symbol] = {
+                tokens[token.displaySymbol] = {
                     address: token.address,
                     id: token.id,
                     symbol: token.symbol,
+                    displaySymbol: token.displaySymbol,
                     decimals: token.decimals,
                     enabledForFees: token.enabledForFees
                 };
@@ -480,6 +480,8 @@ export interface TokenInfo {
     id: number;
     address: Address;
     symbol: string;
+    // Unique identifier of the token, differs from the symbol if it's shared with other tokens.
+    displaySymbol: string;
     decimals: number;
     enabledForFees: boolean;
 }